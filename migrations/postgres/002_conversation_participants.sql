@@ -0,0 +1,15 @@
+-- Add conversation participants for group chats
+
+-- The conversation creator stays in conversations.user_id; invited principals live here.
+-- Participants track their own read position since messages.is_read belongs to the creator.
+CREATE TABLE IF NOT EXISTS conversation_participants (
+    conversation_id VARCHAR(255) NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    user_id VARCHAR(255) NOT NULL,
+    role VARCHAR(20) NOT NULL DEFAULT 'participant' CHECK (role IN ('participant', 'viewer')),
+    invited_by VARCHAR(255) NOT NULL,
+    joined_at TIMESTAMP DEFAULT NOW(),
+    last_read_at TIMESTAMP,
+    PRIMARY KEY (conversation_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_conversation_participants_user ON conversation_participants(user_id);
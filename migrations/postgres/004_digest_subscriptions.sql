@@ -0,0 +1,9 @@
+-- Add opt-in digest subscriptions (periodic "what you missed" push)
+
+CREATE TABLE IF NOT EXISTS digest_subscriptions (
+    user_id VARCHAR(255) PRIMARY KEY,
+    frequency VARCHAR(20) NOT NULL CHECK (frequency IN ('daily', 'weekly')),
+    last_sent_at TIMESTAMP,
+    created_at TIMESTAMP DEFAULT NOW(),
+    updated_at TIMESTAMP DEFAULT NOW()
+);
@@ -0,0 +1,10 @@
+-- Add Telegram bot connections for influencers
+
+CREATE TABLE IF NOT EXISTS telegram_bots (
+    influencer_id VARCHAR(255) PRIMARY KEY REFERENCES ai_influencers(id) ON DELETE CASCADE,
+    bot_token TEXT NOT NULL,
+    bot_username VARCHAR(255) NOT NULL,
+    webhook_secret VARCHAR(255) NOT NULL,
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP DEFAULT NOW()
+);
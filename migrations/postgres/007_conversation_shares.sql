@@ -0,0 +1,13 @@
+-- Add public, immutable conversation snapshots
+
+CREATE TABLE IF NOT EXISTS conversation_shares (
+    token VARCHAR(64) PRIMARY KEY,
+    conversation_id VARCHAR(255) NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    created_by VARCHAR(255) NOT NULL,
+    snapshot JSONB NOT NULL,
+    created_at TIMESTAMP DEFAULT NOW(),
+    revoked_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_conversation_shares_conversation
+    ON conversation_shares(conversation_id);
@@ -0,0 +1,17 @@
+-- Record malware scan verdicts for media uploads
+
+CREATE TABLE IF NOT EXISTS upload_scans (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(255) NOT NULL,
+    file_name TEXT NOT NULL,
+    sha256 VARCHAR(64) NOT NULL,
+    size BIGINT NOT NULL,
+    scanner VARCHAR(32) NOT NULL,
+    verdict VARCHAR(16) NOT NULL CHECK (verdict IN ('clean', 'infected', 'error')),
+    signature TEXT,
+    storage_key TEXT,
+    created_at TIMESTAMP DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_upload_scans_user
+    ON upload_scans(user_id, created_at);
@@ -0,0 +1,8 @@
+-- Server-verified duration of uploaded audio, keyed by storage key
+
+CREATE TABLE IF NOT EXISTS audio_uploads (
+    storage_key TEXT PRIMARY KEY,
+    user_id VARCHAR(255) NOT NULL,
+    duration_seconds INTEGER NOT NULL,
+    created_at TIMESTAMP DEFAULT NOW()
+);
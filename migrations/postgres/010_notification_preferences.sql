@@ -0,0 +1,16 @@
+-- Per-user push notification preferences (quiet hours)
+
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    user_id VARCHAR(255) PRIMARY KEY,
+    quiet_hours_start VARCHAR(5) NOT NULL,
+    quiet_hours_end VARCHAR(5) NOT NULL,
+    timezone VARCHAR(64) NOT NULL,
+    quiet_hours_digest BOOLEAN NOT NULL DEFAULT TRUE,
+    deferred_since TIMESTAMP,
+    created_at TIMESTAMP DEFAULT NOW(),
+    updated_at TIMESTAMP DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_preferences_deferred
+    ON notification_preferences(deferred_since)
+    WHERE deferred_since IS NOT NULL;
@@ -0,0 +1,17 @@
+-- Taps on an influencer's suggested messages, for click-through stats and rotation
+
+CREATE TABLE IF NOT EXISTS suggestion_taps (
+    id VARCHAR(255) PRIMARY KEY,
+    influencer_id VARCHAR(255) NOT NULL REFERENCES ai_influencers(id) ON DELETE CASCADE,
+    user_id VARCHAR(255) NOT NULL,
+    conversation_id VARCHAR(255) NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    message_id VARCHAR(255) NOT NULL,
+    suggestion TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_suggestion_taps_influencer
+    ON suggestion_taps(influencer_id, created_at);
+
+CREATE INDEX IF NOT EXISTS idx_conversations_influencer_created
+    ON conversations(influencer_id, created_at);
@@ -0,0 +1,6 @@
+-- When each user was last connected over WebSocket, for presence shown to bots
+
+CREATE TABLE IF NOT EXISTS user_presence (
+    user_id VARCHAR(255) PRIMARY KEY,
+    last_seen_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,3 @@
+-- Profile impressions per influencer, a trending signal
+
+ALTER TABLE ai_influencers ADD COLUMN IF NOT EXISTS view_count BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,16 @@
+-- Throwaway copies of conversations for trying a draft system prompt. Kept apart
+-- from conversations/messages so sandbox turns never reach analytics or memories.
+
+CREATE TABLE IF NOT EXISTS sandbox_conversations (
+    id VARCHAR(255) PRIMARY KEY,
+    influencer_id VARCHAR(255) NOT NULL,
+    owner_id VARCHAR(255) NOT NULL,
+    source_conversation_id VARCHAR(255) NOT NULL,
+    system_instructions TEXT NOT NULL,
+    conversation JSONB NOT NULL,
+    messages JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sandbox_conversations_expires_at ON sandbox_conversations(expires_at);
@@ -0,0 +1,3 @@
+-- Unpublished system prompt an owner is iterating on via preview chat
+
+ALTER TABLE ai_influencers ADD COLUMN IF NOT EXISTS draft_system_instructions TEXT;
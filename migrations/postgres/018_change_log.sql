@@ -0,0 +1,48 @@
+-- Change feed for conversations and messages, read by delta sync and analytics.
+-- Filled by triggers, so every write lands in the same transaction as its entry.
+
+CREATE TABLE IF NOT EXISTS change_log (
+    seq BIGSERIAL PRIMARY KEY,
+    entity_type VARCHAR(20) NOT NULL CHECK (entity_type IN ('conversation', 'message')),
+    entity_id VARCHAR(255) NOT NULL,
+    op VARCHAR(10) NOT NULL CHECK (op IN ('insert', 'update', 'delete')),
+    conversation_id VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_change_log_created_at ON change_log(created_at);
+CREATE INDEX IF NOT EXISTS idx_change_log_conversation ON change_log(conversation_id, seq);
+
+CREATE OR REPLACE FUNCTION record_change()
+RETURNS TRIGGER AS $$
+DECLARE
+    row_data RECORD;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        row_data := OLD;
+    ELSE
+        row_data := NEW;
+    END IF;
+
+    IF TG_TABLE_NAME = 'conversations' THEN
+        INSERT INTO change_log (entity_type, entity_id, op, conversation_id)
+        VALUES ('conversation', row_data.id, LOWER(TG_OP), row_data.id);
+    ELSE
+        INSERT INTO change_log (entity_type, entity_id, op, conversation_id)
+        VALUES ('message', row_data.id, LOWER(TG_OP), row_data.conversation_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trigger_change_log_conversations ON conversations;
+CREATE TRIGGER trigger_change_log_conversations
+AFTER INSERT OR UPDATE OR DELETE ON conversations
+FOR EACH ROW
+EXECUTE FUNCTION record_change();
+
+DROP TRIGGER IF EXISTS trigger_change_log_messages ON messages;
+CREATE TRIGGER trigger_change_log_messages
+AFTER INSERT OR UPDATE OR DELETE ON messages
+FOR EACH ROW
+EXECUTE FUNCTION record_change();
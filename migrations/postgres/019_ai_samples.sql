@@ -0,0 +1,20 @@
+-- Sampled AI turns (prompt, history, reply) kept for debugging bad replies.
+-- Text is PII-scrubbed before it is written; rows expire after AI_SAMPLE_RETENTION_DAYS.
+
+CREATE TABLE IF NOT EXISTS ai_samples (
+    id VARCHAR(255) PRIMARY KEY,
+    influencer_id VARCHAR(255) NOT NULL,
+    conversation_id VARCHAR(255) NOT NULL,
+    provider VARCHAR(32) NOT NULL,
+    model VARCHAR(255) NOT NULL,
+    system_prompt TEXT NOT NULL,
+    history JSONB NOT NULL DEFAULT '[]',
+    user_message TEXT NOT NULL,
+    response TEXT,
+    error TEXT,
+    latency_ms BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ai_samples_influencer ON ai_samples(influencer_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_ai_samples_created_at ON ai_samples(created_at);
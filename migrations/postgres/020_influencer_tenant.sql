@@ -0,0 +1,6 @@
+-- Which app (yral, dolr, ...) an influencer belongs to. Listings, lookups and
+-- a user's conversations are scoped to the tenant of the request.
+
+ALTER TABLE ai_influencers ADD COLUMN IF NOT EXISTS tenant VARCHAR(64) NOT NULL DEFAULT 'yral';
+
+CREATE INDEX IF NOT EXISTS idx_ai_influencers_tenant ON ai_influencers(tenant, is_active);
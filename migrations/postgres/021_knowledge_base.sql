@@ -0,0 +1,28 @@
+-- Documents an influencer's owner uploads for the bot to draw on, split into chunks
+-- with their embeddings. Similarity is computed in Rust over the influencer's chunks.
+
+CREATE TABLE IF NOT EXISTS knowledge_documents (
+    id VARCHAR(255) PRIMARY KEY,
+    influencer_id VARCHAR(255) NOT NULL,
+    title VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    chunk_count INTEGER NOT NULL DEFAULT 0,
+    uploaded_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_knowledge_documents_influencer ON knowledge_documents(influencer_id, created_at);
+
+-- embedding holds little-endian f32 values
+CREATE TABLE IF NOT EXISTS knowledge_chunks (
+    id VARCHAR(255) PRIMARY KEY,
+    document_id VARCHAR(255) NOT NULL,
+    influencer_id VARCHAR(255) NOT NULL,
+    chunk_index INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    embedding BYTEA NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_knowledge_chunks_influencer ON knowledge_chunks(influencer_id);
+CREATE INDEX IF NOT EXISTS idx_knowledge_chunks_document ON knowledge_chunks(document_id);
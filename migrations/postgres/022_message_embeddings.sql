@@ -0,0 +1,12 @@
+-- Embeddings of message text for searching a user's chat history by meaning.
+-- Filled in by a background worker; similarity is computed in Rust.
+
+-- embedding holds little-endian f32 values
+CREATE TABLE IF NOT EXISTS message_embeddings (
+    message_id VARCHAR(255) PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+    conversation_id VARCHAR(255) NOT NULL,
+    embedding BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_embeddings_conversation ON message_embeddings(conversation_id);
@@ -0,0 +1,11 @@
+-- Names influencers were renamed from. An old name keeps resolving to its
+-- influencer and can't be taken by anyone else.
+
+CREATE TABLE IF NOT EXISTS name_history (
+    old_name VARCHAR(255) PRIMARY KEY,
+    influencer_id VARCHAR(255) NOT NULL,
+    changed_by VARCHAR(255) NOT NULL,
+    changed_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_name_history_influencer ON name_history(influencer_id);
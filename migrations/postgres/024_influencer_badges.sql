@@ -0,0 +1,7 @@
+-- Badges set by admins: verified (the bot's identity is confirmed) and official
+-- (run by the platform itself). Shown on influencer cards and usable as filters.
+
+ALTER TABLE ai_influencers ADD COLUMN IF NOT EXISTS is_verified BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE ai_influencers ADD COLUMN IF NOT EXISTS is_official BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_ai_influencers_verified ON ai_influencers(tenant, is_verified);
@@ -0,0 +1,17 @@
+-- Flood and spam penalties: one row each time a user was throttled or
+-- shadow-limited in a conversation, for admins to review.
+
+CREATE TABLE IF NOT EXISTS abuse_events (
+    id VARCHAR(255) PRIMARY KEY,
+    user_id VARCHAR(255) NOT NULL,
+    conversation_id VARCHAR(255) NOT NULL,
+    kind VARCHAR(32) NOT NULL,
+    action VARCHAR(32) NOT NULL,
+    detail TEXT,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_abuse_events_user ON abuse_events(user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_abuse_events_conversation ON abuse_events(conversation_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_abuse_events_created_at ON abuse_events(created_at);
@@ -0,0 +1,13 @@
+-- Replies held back while an influencer is away (schedule away mode "delay"),
+-- sent by a background task once due so they survive restarts.
+
+CREATE TABLE IF NOT EXISTS scheduled_replies (
+    message_id VARCHAR(255) PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+    conversation_id VARCHAR(255) NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    user_id VARCHAR(255) NOT NULL,
+    influencer_id VARCHAR(255) NOT NULL,
+    due_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_replies_due_at ON scheduled_replies(due_at);
@@ -0,0 +1,9 @@
+-- Per-user image generation usage for the daily quota and cooldown, shared by
+-- all instances and kept across restarts. `count` is for `day` (UTC) only.
+
+CREATE TABLE IF NOT EXISTS image_quota_usage (
+    user_id VARCHAR(255) PRIMARY KEY,
+    day DATE NOT NULL,
+    count INTEGER NOT NULL DEFAULT 0,
+    last_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,8 @@
+-- Messages the embedder couldn't embed even on their own, so it skips them
+-- instead of retrying the batch that holds them every pass.
+
+CREATE TABLE IF NOT EXISTS message_embedding_failures (
+    message_id VARCHAR(255) PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+    error TEXT NOT NULL,
+    failed_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
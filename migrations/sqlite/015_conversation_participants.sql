@@ -0,0 +1,16 @@
+-- Add conversation participants for group chats
+-- Version: 1.2.0
+
+-- The conversation creator stays in conversations.user_id; invited principals live here.
+-- Participants track their own read position since messages.is_read belongs to the creator.
+CREATE TABLE IF NOT EXISTS conversation_participants (
+    conversation_id TEXT NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL,
+    role TEXT NOT NULL DEFAULT 'participant' CHECK (role IN ('participant', 'viewer')),
+    invited_by TEXT NOT NULL,
+    joined_at TEXT DEFAULT (datetime('now')),
+    last_read_at TEXT,
+    PRIMARY KEY (conversation_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_conversation_participants_user ON conversation_participants(user_id);
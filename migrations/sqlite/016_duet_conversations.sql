@@ -0,0 +1,21 @@
+-- Add duet conversations (one user, two influencers taking turns)
+-- Version: 1.3.0
+
+-- 'direct' conversations keep the one-per-user-and-influencer rule; duets are exempt
+ALTER TABLE conversations ADD COLUMN kind TEXT NOT NULL DEFAULT 'direct';
+
+DROP INDEX IF EXISTS idx_unique_user_influencer;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_unique_user_influencer
+ON conversations(user_id, influencer_id)
+WHERE kind = 'direct';
+
+-- Bot-side participants. conversations.influencer_id holds the first speaker.
+CREATE TABLE IF NOT EXISTS conversation_influencers (
+    conversation_id TEXT NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    influencer_id TEXT NOT NULL REFERENCES ai_influencers(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (conversation_id, influencer_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_conversation_influencers_influencer
+ON conversation_influencers(influencer_id);
@@ -0,0 +1,31 @@
+-- Add outbound webhooks registered by influencer owners
+-- Version: 1.5.0
+
+CREATE TABLE IF NOT EXISTS influencer_webhooks (
+    id TEXT PRIMARY KEY,
+    influencer_id TEXT NOT NULL REFERENCES ai_influencers(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    events TEXT NOT NULL DEFAULT '[]',  -- JSON array of event names
+    is_active INTEGER NOT NULL DEFAULT 1,
+    created_by TEXT NOT NULL,
+    created_at TEXT DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_influencer_webhooks_influencer ON influencer_webhooks(influencer_id);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id TEXT PRIMARY KEY,
+    webhook_id TEXT NOT NULL REFERENCES influencer_webhooks(id) ON DELETE CASCADE,
+    event TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'succeeded', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    response_status INTEGER,
+    error TEXT,
+    created_at TEXT DEFAULT (datetime('now')),
+    completed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook_created
+ON webhook_deliveries(webhook_id, created_at DESC);
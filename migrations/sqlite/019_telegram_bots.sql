@@ -0,0 +1,11 @@
+-- Add Telegram bot connections for influencers
+-- Version: 1.6.0
+
+CREATE TABLE IF NOT EXISTS telegram_bots (
+    influencer_id TEXT PRIMARY KEY REFERENCES ai_influencers(id) ON DELETE CASCADE,
+    bot_token TEXT NOT NULL,
+    bot_username TEXT NOT NULL,
+    webhook_secret TEXT NOT NULL,
+    created_by TEXT NOT NULL,
+    created_at TEXT DEFAULT (datetime('now'))
+);
@@ -0,0 +1,14 @@
+-- Add public, immutable conversation snapshots
+-- Version: 1.7.0
+
+CREATE TABLE IF NOT EXISTS conversation_shares (
+    token TEXT PRIMARY KEY,
+    conversation_id TEXT NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    created_by TEXT NOT NULL,
+    snapshot TEXT NOT NULL,  -- JSON: anonymized messages and influencer card
+    created_at TEXT DEFAULT (datetime('now')),
+    revoked_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_conversation_shares_conversation
+ON conversation_shares(conversation_id);
@@ -0,0 +1,18 @@
+-- Record malware scan verdicts for media uploads
+-- Version: 1.8.0
+
+CREATE TABLE IF NOT EXISTS upload_scans (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    sha256 TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    scanner TEXT NOT NULL,
+    verdict TEXT NOT NULL CHECK (verdict IN ('clean', 'infected', 'error')),
+    signature TEXT,
+    storage_key TEXT,  -- Stored object, or its quarantine key when infected
+    created_at TEXT DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_upload_scans_user
+ON upload_scans(user_id, created_at);
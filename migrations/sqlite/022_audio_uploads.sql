@@ -0,0 +1,9 @@
+-- Server-verified duration of uploaded audio, keyed by storage key
+-- Version: 1.9.0
+
+CREATE TABLE IF NOT EXISTS audio_uploads (
+    storage_key TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    duration_seconds INTEGER NOT NULL,
+    created_at TEXT DEFAULT (datetime('now'))
+);
@@ -0,0 +1,17 @@
+-- Per-user push notification preferences (quiet hours)
+-- Version: 1.10.0
+
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    user_id TEXT PRIMARY KEY,
+    quiet_hours_start TEXT NOT NULL,  -- "HH:MM", local to timezone
+    quiet_hours_end TEXT NOT NULL,
+    timezone TEXT NOT NULL,           -- IANA name, e.g. "Asia/Kolkata"
+    quiet_hours_digest INTEGER NOT NULL DEFAULT 1,
+    deferred_since TEXT,              -- First push held back in the current window
+    created_at TEXT DEFAULT (datetime('now')),
+    updated_at TEXT DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_preferences_deferred
+ON notification_preferences(deferred_since)
+WHERE deferred_since IS NOT NULL;
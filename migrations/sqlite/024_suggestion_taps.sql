@@ -0,0 +1,18 @@
+-- Taps on an influencer's suggested messages, for click-through stats and rotation
+-- Version: 1.11.0
+
+CREATE TABLE IF NOT EXISTS suggestion_taps (
+    id TEXT PRIMARY KEY,
+    influencer_id TEXT NOT NULL REFERENCES ai_influencers(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL,
+    conversation_id TEXT NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    message_id TEXT NOT NULL,
+    suggestion TEXT NOT NULL,         -- Text as sent by the client
+    created_at TEXT DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_suggestion_taps_influencer
+ON suggestion_taps(influencer_id, created_at);
+
+CREATE INDEX IF NOT EXISTS idx_conversations_influencer_created
+ON conversations(influencer_id, created_at);
@@ -0,0 +1,7 @@
+-- When each user was last connected over WebSocket, for presence shown to bots
+-- Version: 1.12.0
+
+CREATE TABLE IF NOT EXISTS user_presence (
+    user_id TEXT PRIMARY KEY,
+    last_seen_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
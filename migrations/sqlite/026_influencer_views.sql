@@ -0,0 +1,4 @@
+-- Profile impressions per influencer, a trending signal
+-- Version: 1.13.0
+
+ALTER TABLE ai_influencers ADD COLUMN view_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,11 @@
+-- Revoked JWTs by jti; rows are purged once the token would have expired anyway
+-- Version: 1.14.0
+
+CREATE TABLE IF NOT EXISTS revoked_tokens (
+    jti TEXT PRIMARY KEY,
+    expires_at TEXT NOT NULL,
+    reason TEXT,
+    revoked_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_revoked_tokens_expires_at ON revoked_tokens(expires_at);
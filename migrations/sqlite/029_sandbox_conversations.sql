@@ -0,0 +1,17 @@
+-- Throwaway copies of conversations for trying a draft system prompt. Kept apart
+-- from conversations/messages so sandbox turns never reach analytics or memories.
+-- Version: 1.16.0
+
+CREATE TABLE IF NOT EXISTS sandbox_conversations (
+    id TEXT PRIMARY KEY,
+    influencer_id TEXT NOT NULL,
+    owner_id TEXT NOT NULL,
+    source_conversation_id TEXT NOT NULL,
+    system_instructions TEXT NOT NULL,
+    conversation TEXT NOT NULL,
+    messages TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    expires_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sandbox_conversations_expires_at ON sandbox_conversations(expires_at);
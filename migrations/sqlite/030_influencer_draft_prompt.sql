@@ -0,0 +1,4 @@
+-- Unpublished system prompt an owner is iterating on via preview chat
+-- Version: 1.17.0
+
+ALTER TABLE ai_influencers ADD COLUMN draft_system_instructions TEXT;
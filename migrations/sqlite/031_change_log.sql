@@ -0,0 +1,57 @@
+-- Change feed for conversations and messages, read by delta sync and analytics.
+-- Filled by triggers, so every write lands in the same transaction as its entry.
+-- Version: 1.18.0
+
+CREATE TABLE IF NOT EXISTS change_log (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('conversation', 'message')),
+    entity_id TEXT NOT NULL,
+    op TEXT NOT NULL CHECK (op IN ('insert', 'update', 'delete')),
+    conversation_id TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_change_log_created_at ON change_log(created_at);
+CREATE INDEX IF NOT EXISTS idx_change_log_conversation ON change_log(conversation_id, seq);
+
+CREATE TRIGGER IF NOT EXISTS trigger_change_log_conversation_insert
+AFTER INSERT ON conversations
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, op, conversation_id)
+    VALUES ('conversation', NEW.id, 'insert', NEW.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trigger_change_log_conversation_update
+AFTER UPDATE ON conversations
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, op, conversation_id)
+    VALUES ('conversation', NEW.id, 'update', NEW.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trigger_change_log_conversation_delete
+AFTER DELETE ON conversations
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, op, conversation_id)
+    VALUES ('conversation', OLD.id, 'delete', OLD.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trigger_change_log_message_insert
+AFTER INSERT ON messages
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, op, conversation_id)
+    VALUES ('message', NEW.id, 'insert', NEW.conversation_id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trigger_change_log_message_update
+AFTER UPDATE ON messages
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, op, conversation_id)
+    VALUES ('message', NEW.id, 'update', NEW.conversation_id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trigger_change_log_message_delete
+AFTER DELETE ON messages
+BEGIN
+    INSERT INTO change_log (entity_type, entity_id, op, conversation_id)
+    VALUES ('message', OLD.id, 'delete', OLD.conversation_id);
+END;
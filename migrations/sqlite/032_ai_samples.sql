@@ -0,0 +1,21 @@
+-- Sampled AI turns (prompt, history, reply) kept for debugging bad replies.
+-- Text is PII-scrubbed before it is written; rows expire after AI_SAMPLE_RETENTION_DAYS.
+-- Version: 1.19.0
+
+CREATE TABLE IF NOT EXISTS ai_samples (
+    id TEXT PRIMARY KEY,
+    influencer_id TEXT NOT NULL,
+    conversation_id TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    system_prompt TEXT NOT NULL,
+    history TEXT NOT NULL DEFAULT '[]',
+    user_message TEXT NOT NULL,
+    response TEXT,
+    error TEXT,
+    latency_ms INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_ai_samples_influencer ON ai_samples(influencer_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_ai_samples_created_at ON ai_samples(created_at);
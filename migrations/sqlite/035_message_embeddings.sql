@@ -0,0 +1,13 @@
+-- Embeddings of message text for searching a user's chat history by meaning.
+-- Filled in by a background worker; similarity is computed in Rust.
+-- Version: 1.22.0
+
+-- embedding holds little-endian f32 values
+CREATE TABLE IF NOT EXISTS message_embeddings (
+    message_id TEXT PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+    conversation_id TEXT NOT NULL,
+    embedding BLOB NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_embeddings_conversation ON message_embeddings(conversation_id);
@@ -0,0 +1,12 @@
+-- Names influencers were renamed from. An old name keeps resolving to its
+-- influencer and can't be taken by anyone else.
+-- Version: 1.23.0
+
+CREATE TABLE IF NOT EXISTS name_history (
+    old_name TEXT PRIMARY KEY,
+    influencer_id TEXT NOT NULL,
+    changed_by TEXT NOT NULL,
+    changed_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_name_history_influencer ON name_history(influencer_id);
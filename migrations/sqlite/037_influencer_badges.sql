@@ -0,0 +1,8 @@
+-- Badges set by admins: verified (the bot's identity is confirmed) and official
+-- (run by the platform itself). Shown on influencer cards and usable as filters.
+-- Version: 1.24.0
+
+ALTER TABLE ai_influencers ADD COLUMN is_verified INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE ai_influencers ADD COLUMN is_official INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_ai_influencers_verified ON ai_influencers(tenant, is_verified);
@@ -0,0 +1,18 @@
+-- Flood and spam penalties: one row each time a user was throttled or
+-- shadow-limited in a conversation, for admins to review.
+-- Version: 1.25.0
+
+CREATE TABLE IF NOT EXISTS abuse_events (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    conversation_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    action TEXT NOT NULL,
+    detail TEXT,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_abuse_events_user ON abuse_events(user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_abuse_events_conversation ON abuse_events(conversation_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_abuse_events_created_at ON abuse_events(created_at);
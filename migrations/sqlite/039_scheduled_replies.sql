@@ -0,0 +1,14 @@
+-- Replies held back while an influencer is away (schedule away mode "delay"),
+-- sent by a background task once due so they survive restarts.
+-- Version: 1.26.0
+
+CREATE TABLE IF NOT EXISTS scheduled_replies (
+    message_id TEXT PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+    conversation_id TEXT NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL,
+    influencer_id TEXT NOT NULL,
+    due_at TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_replies_due_at ON scheduled_replies(due_at);
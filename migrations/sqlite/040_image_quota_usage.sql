@@ -0,0 +1,10 @@
+-- Per-user image generation usage for the daily quota and cooldown, shared by
+-- all instances and kept across restarts. `count` is for `day` (UTC) only.
+-- Version: 1.27.0
+
+CREATE TABLE IF NOT EXISTS image_quota_usage (
+    user_id TEXT PRIMARY KEY,
+    day TEXT NOT NULL,
+    count INTEGER NOT NULL DEFAULT 0,
+    last_at TEXT NOT NULL
+);
@@ -0,0 +1,9 @@
+-- Messages the embedder couldn't embed even on their own, so it skips them
+-- instead of retrying the batch that holds them every pass.
+-- Version: 1.28.0
+
+CREATE TABLE IF NOT EXISTS message_embedding_failures (
+    message_id TEXT PRIMARY KEY REFERENCES messages(id) ON DELETE CASCADE,
+    error TEXT NOT NULL,
+    failed_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
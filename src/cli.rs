@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use utoipa::OpenApi;
+
+use crate::config::Settings;
+use crate::db::{self, Database};
+use crate::models::entities::AIInfluencer;
+use crate::routes::openapi::ApiDoc;
+
+/// Yral AI Chat backend. Starts the HTTP server unless a maintenance command is given.
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run the HTTP server (the default)
+    Serve,
+    /// Apply pending database migrations and exit
+    Migrate,
+    /// Checkpoint and truncate the SQLite WAL (staging builds only)
+    Checkpoint,
+    /// Insert influencers from a JSON array; ids that already exist are left untouched
+    Seed {
+        #[arg(long)]
+        file: PathBuf,
+    },
+    /// Write the OpenAPI document to stdout, or to `--output`
+    ExportOpenapi {
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Run a maintenance command to completion. `Serve` and `ExportOpenapi` are
+/// dispatched by `main`, the latter before settings are loaded.
+pub async fn run(command: Command, settings: &Settings) -> anyhow::Result<()> {
+    match command {
+        Command::Serve | Command::ExportOpenapi { .. } => {
+            unreachable!("dispatched by main")
+        }
+        Command::Migrate => migrate(settings).await,
+        Command::Checkpoint => checkpoint(settings).await,
+        Command::Seed { file } => seed(settings, file).await,
+    }
+}
+
+async fn migrate(settings: &Settings) -> anyhow::Result<()> {
+    let database = Database::connect(settings).await?;
+    let migrations_dir = db::migrations_dir();
+
+    #[cfg(feature = "staging")]
+    db::run_migrations(&database.pool, migrations_dir).await?;
+    #[cfg(not(feature = "staging"))]
+    db::run_pg_migrations(&database.pg_pool, migrations_dir).await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "staging")]
+async fn checkpoint(settings: &Settings) -> anyhow::Result<()> {
+    let database = Database::connect(settings).await?;
+    let (busy, log, checkpointed) = database.truncate_wal().await?;
+    if busy != 0 {
+        anyhow::bail!("WAL checkpoint could not complete: database is busy");
+    }
+    tracing::info!(
+        log_pages = log,
+        checkpointed_pages = checkpointed,
+        "WAL checkpoint completed"
+    );
+    Ok(())
+}
+
+#[cfg(not(feature = "staging"))]
+async fn checkpoint(_settings: &Settings) -> anyhow::Result<()> {
+    anyhow::bail!("checkpoint only applies to the SQLite (staging) build")
+}
+
+async fn seed(settings: &Settings, file: PathBuf) -> anyhow::Result<()> {
+    let contents = tokio::fs::read_to_string(&file)
+        .await
+        .with_context(|| format!("Failed to read {}", file.display()))?;
+    let influencers: Vec<AIInfluencer> = serde_json::from_str(&contents)
+        .with_context(|| format!("{} is not a JSON array of influencers", file.display()))?;
+
+    let database = Database::connect(settings).await?;
+    let repo = database.legacy_import_repo();
+    let mut inserted = 0;
+    for influencer in &influencers {
+        if repo.insert_influencer(influencer).await? {
+            inserted += 1;
+        }
+    }
+
+    tracing::info!(
+        file = %file.display(),
+        inserted,
+        skipped = influencers.len() - inserted,
+        "Seed finished"
+    );
+    Ok(())
+}
+
+pub fn export_openapi(output: Option<PathBuf>) -> anyhow::Result<()> {
+    let json = ApiDoc::openapi().to_pretty_json()?;
+    match output {
+        Some(path) => std::fs::write(&path, json)
+            .with_context(|| format!("Failed to write {}", path.display()))?,
+        None => println!("{json}"),
+    }
+    Ok(())
+}
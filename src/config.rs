@@ -1,4 +1,31 @@
+use std::collections::HashMap;
 use std::env;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+use crate::services::ai::{AiFixtureMode, ModelDefaults};
+use crate::services::prompt_guard::InjectionStrictness;
+use crate::services::storage::MediaUrlMode;
+use crate::services::upload_scan::UploadScanMode;
+
+/// Copy each listed field from `$fresh` into `$target` where they differ and push
+/// its name onto `$changed`; without `$changed`, just copy them.
+macro_rules! take_changed {
+    ($target:expr, $fresh:expr, $changed:expr; $($field:ident),* $(,)?) => {$(
+        if $target.$field != $fresh.$field {
+            $target.$field = $fresh.$field.clone();
+            $changed.push(stringify!($field));
+        }
+    )*};
+    ($target:expr, $fresh:expr; $($field:ident),* $(,)?) => {$(
+        $target.$field = $fresh.$field.clone();
+    )*};
+}
+
+/// The live settings, shared with the services that read them per request. A
+/// reload swaps in a new snapshot; see [`Settings::apply_reloadable`].
+pub type SharedSettings = Arc<ArcSwap<Settings>>;
 
 #[derive(Debug, Clone)]
 pub struct Settings {
@@ -32,6 +59,11 @@ pub struct Settings {
     pub gemini_max_tokens: u32,
     pub gemini_temperature: f32,
     pub gemini_timeout: u64,
+    pub gemini_max_concurrency: usize,
+
+    // AI fixtures (record/replay of provider responses)
+    pub ai_fixture_mode: AiFixtureMode,
+    pub ai_fixture_dir: String,
 
     // OpenRouter
     pub openrouter_api_key: String,
@@ -39,11 +71,25 @@ pub struct Settings {
     pub openrouter_max_tokens: u32,
     pub openrouter_temperature: f32,
     pub openrouter_timeout: u64,
+    pub openrouter_max_concurrency: usize,
+    pub ai_queue_timeout_ms: u64,
 
     // Media limits
     pub max_image_size_mb: u32,
     pub max_audio_size_mb: u32,
     pub max_audio_duration_seconds: u32,
+    pub normalize_uploaded_images: bool,
+    /// Lossy quality, 1-100, photos are re-encoded at when normalized
+    pub image_normalize_quality: u8,
+
+    // Upload scanning
+    pub upload_scan_mode: UploadScanMode,
+    pub clamd_address: String,
+    pub upload_scan_url: Option<String>,
+    pub upload_scan_api_key: Option<String>,
+
+    // Request body limits
+    pub max_json_body_kb: u32,
 
     // S3
     pub aws_access_key_id: String,
@@ -54,177 +100,953 @@ pub struct Settings {
     pub s3_public_url_base: String,
     pub s3_url_expires_seconds: u32,
 
+    // Media URLs
+    pub media_url_mode: MediaUrlMode,
+    pub media_cdn_base_url: Option<String>,
+    pub media_url_signing_secret: Option<String>,
+    pub media_signed_url_ttl_seconds: u64,
+
     // CORS
     pub cors_origins: String,
 
+    // Auth
+    /// Comma-separated JWT issuers accepted
+    pub jwt_issuers: String,
+    /// Comma-separated JWT audiences accepted; empty skips the audience check
+    pub jwt_audiences: String,
+    /// Clock skew tolerated on `exp` and `iat`, for devices with drifting clocks
+    pub jwt_leeway_seconds: u64,
+    /// How often revoked token ids are reloaded from the database
+    pub token_revocation_sync_interval_seconds: u64,
+
     // Rate limiting
     pub rate_limit_per_minute: u32,
     pub rate_limit_per_hour: u32,
+    /// AI replies one conversation may get per minute, against bot callers and
+    /// retry loops; 0 disables the cap
+    pub conversation_replies_per_minute: u32,
+
+    // Load shedding (0 disables a cap)
+    pub load_shed_max_concurrency: usize,
+    pub load_shed_ai_max_concurrency: usize,
+    pub load_shed_queue_timeout_ms: u64,
 
     // Logging
     pub log_level: String,
     pub log_format: String,
 
-    // Replicate (Image Generation)
+    // Replicate (Image and Video Generation)
     pub replicate_api_token: String,
     pub replicate_model: String,
+    pub replicate_video_model: String,
+    /// Render a starter video for new influencers after their prompt is generated
+    pub starter_video_enabled: bool,
+    /// Images a user may generate per UTC day; 0 is unlimited
+    pub image_gen_daily_limit: u32,
+    /// Minimum time between a user's image generations
+    pub image_gen_cooldown_seconds: u64,
+    /// Image predictions started per UTC day across all users; 0 is unlimited
+    pub replicate_daily_image_limit: u32,
+    /// Video predictions started per UTC day; 0 is unlimited
+    pub replicate_daily_video_limit: u32,
+
+    // Avatar moderation
+    /// Safety-check influencer avatars with the vision model before they go public
+    pub avatar_moderation_enabled: bool,
+    /// Avatars generated for one influencer before giving up on a safe one
+    pub avatar_moderation_max_attempts: u32,
 
     // Push Notifications (Metadata Server)
     pub metadata_url: String,
     pub metadata_auth_token: Option<String>,
+    /// Messages in one conversation within this window go out as a single push; 0 sends each
+    pub push_batch_window_ms: u64,
 
     // Sentry
     pub sentry_dsn: Option<String>,
     pub sentry_traces_sample_rate: f64,
     pub sentry_profiles_sample_rate: f64,
+    /// Client secret of the Sentry integration that posts to /webhooks/sentry
+    pub sentry_webhook_secret: Option<String>,
+    /// Repeat alerts for the same issue within this many seconds are dropped
+    pub sentry_alert_dedup_seconds: u64,
     // Notifications
     pub google_chat_webhook_url: Option<String>,
 
     // Admin
     pub admin_key_to_delete_influencer: Option<String>,
+
+    // Safety
+    pub prompt_injection_strictness: InjectionStrictness,
+    pub output_sanitize_enabled: bool,
+    pub assistant_max_chars: usize,
+    pub outbound_link_redirect_url: Option<String>,
+    /// Scrub personal data from user text and memories sent to AI providers
+    pub pii_redaction_enabled: bool,
+    /// Comma-separated kinds to scrub: email, phone, address
+    pub pii_redaction_kinds: String,
+
+    // Message length
+    /// Longest user message accepted, in characters
+    pub message_max_chars: usize,
+    /// Messages longer than this are condensed before reaching the model; 0 sends them whole
+    pub message_condense_threshold_chars: usize,
+    /// Size of the parts a long message is split into for condensing
+    pub message_condense_chunk_chars: usize,
+    /// What the AI is asked when a message is only media; `{media}` becomes e.g.
+    /// "photo" or "voice note" and `{count}` the number attached. Influencers can
+    /// override it
+    pub media_only_prompt: String,
+    /// User messages sent within this many seconds of each other get one combined
+    /// reply; 0 replies to every message
+    pub message_debounce_seconds: u64,
+
+    // Memories
+    /// Most memories kept per conversation; the least recently referenced go first
+    pub memory_max_count: usize,
+    /// Longer memory values are cut to this many characters
+    pub memory_max_value_chars: usize,
+    /// Above this many memories the model is asked to merge redundant ones; 0 disables
+    pub memory_consolidate_at: usize,
+
+    // Failed replies
+    pub failed_reply_retry_enabled: bool,
+    pub failed_reply_retry_interval_seconds: u64,
+    pub failed_reply_max_attempts: u32,
+    /// How often replies held back by an influencer's away schedule are checked
+    pub scheduled_reply_check_interval_seconds: u64,
+
+    // Digest
+    pub digest_enabled: bool,
+    pub digest_check_interval_seconds: u64,
+    pub quiet_hours_check_interval_seconds: u64,
+
+    // Suggested messages
+    pub suggestion_rotation_enabled: bool,
+    pub suggestion_rotation_interval_seconds: u64,
+    pub suggestion_rotation_window_days: i32,
+    pub suggestion_rotation_min_conversations: i64,
+    /// Suggested messages shown for influencers with tagged conversation starters;
+    /// others show all of theirs. 0 shows everything for every influencer
+    pub suggested_message_count: usize,
+
+    // Abuse detection
+    pub abuse_detection_enabled: bool,
+    pub abuse_window_seconds: u64,
+    pub abuse_repeat_threshold: usize,
+    pub abuse_max_chars_per_window: usize,
+    pub abuse_max_urls: usize,
+    pub abuse_throttle_seconds: u64,
+    pub abuse_shadow_limit_seconds: u64,
+
+    // Email gateway
+    pub email_inbound_secret: Option<String>,
+    pub email_domain: String,
+    pub sendgrid_api_key: Option<String>,
+
+    // Telegram bridge
+    pub telegram_webhook_base_url: Option<String>,
+
+    // Caching
+    pub influencer_cache_ttl_seconds: u64,
+    pub character_cache_ttl_seconds: u64,
+    /// How long a principal's bot/user account type is remembered
+    pub caller_type_cache_ttl_seconds: u64,
+    /// How long a failed account type lookup is remembered (as a user)
+    pub caller_type_negative_ttl_seconds: u64,
+    /// Treat every caller as a user without asking the canister (no IC access)
+    pub caller_type_force_user: bool,
+    /// How long a user's username and profile picture are served without refetching
+    pub profile_cache_ttl_seconds: u64,
+    /// How long past the TTL a profile is still served while it refreshes in the background
+    pub profile_cache_stale_seconds: u64,
+
+    // Sandbox
+    /// How long a prompt-testing sandbox conversation lives
+    pub sandbox_ttl_minutes: i64,
+
+    // WebSocket
+    /// Events buffered per connection; beyond this the oldest are dropped
+    pub ws_queue_capacity: usize,
+    /// Connections whose buffer stays full this long are disconnected
+    pub ws_slow_client_timeout_seconds: u64,
+    /// Minimum time between `last_seen_at` writes for a connected user
+    pub presence_write_interval_seconds: u64,
+
+    // Impressions
+    /// How often buffered profile impressions are written to `view_count`
+    pub impression_flush_interval_seconds: u64,
+
+    // Change log
+    /// Days `change_log` entries are kept for delta sync and analytics
+    pub change_log_retention_days: i64,
+    /// How often expired `change_log` entries are pruned
+    pub change_log_prune_interval_seconds: u64,
+
+    // AI samples
+    /// Record a share of AI turns (prompt, history, reply) for debugging
+    pub ai_sample_logging_enabled: bool,
+    /// Share of turns recorded, 0.0-1.0; influencers can override it
+    pub ai_sample_rate: f64,
+    /// Days samples are kept
+    pub ai_sample_retention_days: i64,
+
+    // Image captions
+    /// Write alt text for image messages with the vision model, for screen readers
+    pub image_captions_enabled: bool,
+
+    // AI tools
+    /// Offer the model server-side tools (time, weather, web search) when a
+    /// message asks for something they answer
+    pub ai_tools_enabled: bool,
+    /// Weather lookup returning plain text; `{location}` is replaced with the
+    /// place. Empty turns the weather tool off
+    pub weather_api_url: String,
+    /// Search API returning JSON results; `{query}` is replaced with the query.
+    /// Empty turns the search tool off
+    pub web_search_api_url: String,
+    /// Sent as a bearer token to the search API
+    pub web_search_api_key: Option<String>,
+
+    // Knowledge base
+    /// Add passages from the influencer's uploaded documents to the prompt
+    pub knowledge_base_enabled: bool,
+    /// Gemini embedding model. Stored vectors only match the model that made
+    /// them, so documents must be uploaded again after changing it
+    pub knowledge_embedding_model: String,
+    pub knowledge_max_document_mb: u32,
+    /// Documents one influencer may have
+    pub knowledge_max_documents: usize,
+    /// Chunks across all of one influencer's documents
+    pub knowledge_max_chunks: usize,
+    /// Target length of each embedded chunk
+    pub knowledge_chunk_chars: usize,
+    /// Passages added to the prompt per message
+    pub knowledge_top_k: usize,
+    /// Cosine similarity a passage needs to be used, 0.0-1.0
+    pub knowledge_min_score: f32,
+
+    // Message embeddings
+    /// Embed message text in the background for semantic search of chat history
+    pub message_embeddings_enabled: bool,
+    pub message_embedding_interval_seconds: u64,
+    /// Messages embedded per pass
+    pub message_embedding_batch_size: i64,
+    /// Most recent embedded messages of a user compared against a search
+    pub semantic_search_max_candidates: i64,
+    /// Cosine similarity a message needs to be returned, 0.0-1.0
+    pub semantic_search_min_score: f32,
+
+    // Duplicate influencers
+    /// Refuse to create an influencer that looks like one the caller already has,
+    /// unless the request passes `force`
+    pub duplicate_check_enabled: bool,
+    /// Name similarity at which an existing influencer counts as a duplicate, 0.0-1.0
+    pub duplicate_name_similarity: f32,
+    /// System prompt cosine similarity at which one counts as a duplicate, 0.0-1.0
+    pub duplicate_prompt_similarity: f32,
+
+    // Influencer names
+    /// Comma-separated handles no user may create or rename to, matched
+    /// case-insensitively, e.g. official and staff accounts
+    pub reserved_influencer_names: String,
+    /// List verified influencers first in the influencer and trending lists
+    pub boost_verified_influencers: bool,
+
+    // Tenants
+    /// Comma-separated apps served, e.g. `yral,dolr`; the first is the default
+    pub tenants: String,
+    /// Comma-separated `issuer=tenant` pairs mapping JWT issuers to tenants
+    pub tenant_issuers: String,
+
+    // Backup verification
+    /// Periodically restore the latest Litestream backup and check it
+    pub backup_verify_enabled: bool,
+    pub backup_verify_interval_hours: u64,
+    /// Litestream config naming the replica, as written by the entrypoint
+    pub litestream_config_path: String,
+
+    // Legacy import
+    pub legacy_import_max_mb: u32,
 }
 
 impl Settings {
     pub fn from_env() -> Self {
+        Self::from_lookup(|key| env::var(key))
+    }
+
+    pub fn from_lookup(var: impl Fn(&str) -> Result<String, env::VarError>) -> Self {
         Self {
-            app_name: env::var("APP_NAME").unwrap_or("Yral AI Chat API".into()),
-            app_version: env::var("APP_VERSION").unwrap_or("1.0.0".into()),
-            environment: env::var("ENVIRONMENT").unwrap_or("development".into()),
-            debug: env::var("DEBUG")
+            app_name: var("APP_NAME").unwrap_or("Yral AI Chat API".into()),
+            app_version: var("APP_VERSION").unwrap_or("1.0.0".into()),
+            environment: var("ENVIRONMENT").unwrap_or("development".into()),
+            debug: var("DEBUG")
                 .unwrap_or("false".into())
                 .parse()
                 .unwrap_or(false),
-            host: env::var("HOST").unwrap_or("0.0.0.0".into()),
-            port: env::var("PORT")
-                .unwrap_or("8000".into())
-                .parse()
-                .unwrap_or(8000),
+            host: var("HOST").unwrap_or("0.0.0.0".into()),
+            port: var("PORT").unwrap_or("8000".into()).parse().unwrap_or(8000),
 
-            database_path: env::var("DATABASE_PATH").unwrap_or("data/yral_chat.db".into()),
-            database_pool_size: env::var("DATABASE_POOL_SIZE")
+            database_path: var("DATABASE_PATH").unwrap_or("data/yral_chat.db".into()),
+            database_pool_size: var("DATABASE_POOL_SIZE")
                 .unwrap_or("10".into())
                 .parse()
                 .unwrap_or(10),
-            database_pool_timeout: env::var("DATABASE_POOL_TIMEOUT")
+            database_pool_timeout: var("DATABASE_POOL_TIMEOUT")
                 .unwrap_or("60".into())
                 .parse()
                 .unwrap_or(60),
 
-            pg_database_url: env::var("PG_DATABASE_URL").ok().filter(|s| !s.is_empty()),
-            pg_pool_size: env::var("PG_POOL_SIZE")
+            pg_database_url: var("PG_DATABASE_URL").ok().filter(|s| !s.is_empty()),
+            pg_pool_size: var("PG_POOL_SIZE")
                 .unwrap_or("5".into())
                 .parse()
                 .unwrap_or(5),
-            pg_pool_timeout: env::var("PG_POOL_TIMEOUT")
+            pg_pool_timeout: var("PG_POOL_TIMEOUT")
                 .unwrap_or("10".into())
                 .parse()
                 .unwrap_or(10),
-            pg_read_enabled: env::var("PG_READ_ENABLED")
+            pg_read_enabled: var("PG_READ_ENABLED")
                 .unwrap_or("false".into())
                 .parse()
                 .unwrap_or(false),
 
-            jwt_secret_key: env::var("JWT_SECRET_KEY").expect("JWT_SECRET_KEY is required"),
-            jwt_algorithm: env::var("JWT_ALGORITHM").unwrap_or("HS256".into()),
-            jwt_issuer: env::var("JWT_ISSUER").unwrap_or("yral_auth".into()),
+            jwt_secret_key: var("JWT_SECRET_KEY").expect("JWT_SECRET_KEY is required"),
+            jwt_algorithm: var("JWT_ALGORITHM").unwrap_or("HS256".into()),
+            jwt_issuer: var("JWT_ISSUER").unwrap_or("yral_auth".into()),
 
-            gemini_api_key: env::var("GEMINI_API_KEY").expect("GEMINI_API_KEY is required"),
-            gemini_model: env::var("GEMINI_MODEL").unwrap_or("gemini-2.5-flash".into()),
-            gemini_max_tokens: env::var("GEMINI_MAX_TOKENS")
+            gemini_api_key: var("GEMINI_API_KEY").expect("GEMINI_API_KEY is required"),
+            gemini_model: var("GEMINI_MODEL").unwrap_or("gemini-2.5-flash".into()),
+            gemini_max_tokens: var("GEMINI_MAX_TOKENS")
                 .unwrap_or("2048".into())
                 .parse()
                 .unwrap_or(2048),
-            gemini_temperature: env::var("GEMINI_TEMPERATURE")
+            gemini_temperature: var("GEMINI_TEMPERATURE")
                 .unwrap_or("0.7".into())
                 .parse()
                 .unwrap_or(0.7),
-            gemini_timeout: env::var("GEMINI_TIMEOUT")
+            gemini_timeout: var("GEMINI_TIMEOUT")
                 .unwrap_or("60".into())
                 .parse()
                 .unwrap_or(60),
+            gemini_max_concurrency: var("GEMINI_MAX_CONCURRENCY")
+                .unwrap_or("32".into())
+                .parse()
+                .unwrap_or(32),
+
+            ai_fixture_mode: var("AI_FIXTURE_MODE")
+                .unwrap_or("off".into())
+                .parse()
+                .unwrap_or(AiFixtureMode::Off),
+            ai_fixture_dir: var("AI_FIXTURE_DIR").unwrap_or("fixtures/ai".into()),
 
-            openrouter_api_key: env::var("OPENROUTER_API_KEY").unwrap_or_default(),
-            openrouter_model: env::var("OPENROUTER_MODEL")
-                .unwrap_or("google/gemini-2.5-flash".into()),
-            openrouter_max_tokens: env::var("OPENROUTER_MAX_TOKENS")
+            openrouter_api_key: var("OPENROUTER_API_KEY").unwrap_or_default(),
+            openrouter_model: var("OPENROUTER_MODEL").unwrap_or("google/gemini-2.5-flash".into()),
+            openrouter_max_tokens: var("OPENROUTER_MAX_TOKENS")
                 .unwrap_or("2048".into())
                 .parse()
                 .unwrap_or(2048),
-            openrouter_temperature: env::var("OPENROUTER_TEMPERATURE")
+            openrouter_temperature: var("OPENROUTER_TEMPERATURE")
                 .unwrap_or("0.7".into())
                 .parse()
                 .unwrap_or(0.7),
-            openrouter_timeout: env::var("OPENROUTER_TIMEOUT")
+            openrouter_timeout: var("OPENROUTER_TIMEOUT")
                 .unwrap_or("30".into())
                 .parse()
                 .unwrap_or(30),
+            openrouter_max_concurrency: var("OPENROUTER_MAX_CONCURRENCY")
+                .unwrap_or("16".into())
+                .parse()
+                .unwrap_or(16),
+            ai_queue_timeout_ms: var("AI_QUEUE_TIMEOUT_MS")
+                .unwrap_or("1000".into())
+                .parse()
+                .unwrap_or(1000),
 
-            max_image_size_mb: env::var("MAX_IMAGE_SIZE_MB")
+            max_image_size_mb: var("MAX_IMAGE_SIZE_MB")
                 .unwrap_or("10".into())
                 .parse()
                 .unwrap_or(10),
-            max_audio_size_mb: env::var("MAX_AUDIO_SIZE_MB")
+            max_audio_size_mb: var("MAX_AUDIO_SIZE_MB")
                 .unwrap_or("20".into())
                 .parse()
                 .unwrap_or(20),
-            max_audio_duration_seconds: env::var("MAX_AUDIO_DURATION_SECONDS")
+            max_audio_duration_seconds: var("MAX_AUDIO_DURATION_SECONDS")
                 .unwrap_or("300".into())
                 .parse()
                 .unwrap_or(300),
+            normalize_uploaded_images: var("NORMALIZE_UPLOADED_IMAGES")
+                .unwrap_or("true".into())
+                .parse()
+                .unwrap_or(true),
+            image_normalize_quality: var("IMAGE_NORMALIZE_QUALITY")
+                .unwrap_or("90".into())
+                .parse::<u8>()
+                .unwrap_or(90)
+                .clamp(1, 100),
+
+            upload_scan_mode: var("UPLOAD_SCAN_MODE")
+                .unwrap_or("off".into())
+                .parse()
+                .unwrap_or(UploadScanMode::Off),
+            clamd_address: var("CLAMD_ADDRESS").unwrap_or("127.0.0.1:3310".into()),
+            upload_scan_url: var("UPLOAD_SCAN_URL").ok().filter(|s| !s.is_empty()),
+            upload_scan_api_key: var("UPLOAD_SCAN_API_KEY").ok().filter(|s| !s.is_empty()),
+
+            max_json_body_kb: var("MAX_JSON_BODY_KB")
+                .unwrap_or("256".into())
+                .parse()
+                .unwrap_or(256),
 
-            aws_access_key_id: env::var("AWS_ACCESS_KEY_ID")
-                .expect("AWS_ACCESS_KEY_ID is required"),
-            aws_secret_access_key: env::var("AWS_SECRET_ACCESS_KEY")
+            aws_access_key_id: var("AWS_ACCESS_KEY_ID").expect("AWS_ACCESS_KEY_ID is required"),
+            aws_secret_access_key: var("AWS_SECRET_ACCESS_KEY")
                 .expect("AWS_SECRET_ACCESS_KEY is required"),
-            aws_s3_bucket: env::var("AWS_S3_BUCKET").expect("AWS_S3_BUCKET is required"),
-            aws_region: env::var("AWS_REGION").expect("AWS_REGION is required"),
-            s3_endpoint_url: env::var("S3_ENDPOINT_URL").expect("S3_ENDPOINT_URL is required"),
-            s3_public_url_base: env::var("S3_PUBLIC_URL_BASE")
-                .expect("S3_PUBLIC_URL_BASE is required"),
-            s3_url_expires_seconds: env::var("S3_URL_EXPIRES_SECONDS")
+            aws_s3_bucket: var("AWS_S3_BUCKET").expect("AWS_S3_BUCKET is required"),
+            aws_region: var("AWS_REGION").expect("AWS_REGION is required"),
+            s3_endpoint_url: var("S3_ENDPOINT_URL").expect("S3_ENDPOINT_URL is required"),
+            s3_public_url_base: var("S3_PUBLIC_URL_BASE").expect("S3_PUBLIC_URL_BASE is required"),
+            s3_url_expires_seconds: var("S3_URL_EXPIRES_SECONDS")
                 .unwrap_or("900".into())
                 .parse()
                 .unwrap_or(900),
 
-            cors_origins: env::var("CORS_ORIGINS").unwrap_or("*".into()),
+            media_url_mode: var("MEDIA_URL_MODE")
+                .unwrap_or("presigned".into())
+                .parse()
+                .unwrap_or(MediaUrlMode::Presigned),
+            media_cdn_base_url: var("MEDIA_CDN_BASE_URL").ok().filter(|s| !s.is_empty()),
+            media_url_signing_secret: var("MEDIA_URL_SIGNING_SECRET")
+                .ok()
+                .filter(|s| !s.is_empty()),
+            media_signed_url_ttl_seconds: var("MEDIA_SIGNED_URL_TTL_SECONDS")
+                .unwrap_or("604800".into())
+                .parse()
+                .unwrap_or(604800),
+
+            cors_origins: var("CORS_ORIGINS").unwrap_or("*".into()),
+
+            jwt_issuers: var("JWT_ISSUERS")
+                .unwrap_or("https://auth.yral.com,https://auth.dolr.ai".into()),
+            jwt_audiences: var("JWT_AUDIENCES").unwrap_or_default(),
+            jwt_leeway_seconds: var("JWT_LEEWAY_SECONDS")
+                .unwrap_or("60".into())
+                .parse()
+                .unwrap_or(60),
+            token_revocation_sync_interval_seconds: var("TOKEN_REVOCATION_SYNC_INTERVAL_SECONDS")
+                .unwrap_or("30".into())
+                .parse()
+                .unwrap_or(30),
 
-            rate_limit_per_minute: env::var("RATE_LIMIT_PER_MINUTE")
+            rate_limit_per_minute: var("RATE_LIMIT_PER_MINUTE")
                 .unwrap_or("300".into())
                 .parse()
                 .unwrap_or(300),
-            rate_limit_per_hour: env::var("RATE_LIMIT_PER_HOUR")
+            rate_limit_per_hour: var("RATE_LIMIT_PER_HOUR")
                 .unwrap_or("5000".into())
                 .parse()
                 .unwrap_or(5000),
+            conversation_replies_per_minute: var("CONVERSATION_REPLIES_PER_MINUTE")
+                .unwrap_or("20".into())
+                .parse()
+                .unwrap_or(20),
+            load_shed_max_concurrency: var("LOAD_SHED_MAX_CONCURRENCY")
+                .unwrap_or("512".into())
+                .parse()
+                .unwrap_or(512),
+            load_shed_ai_max_concurrency: var("LOAD_SHED_AI_MAX_CONCURRENCY")
+                .unwrap_or("64".into())
+                .parse()
+                .unwrap_or(64),
+            load_shed_queue_timeout_ms: var("LOAD_SHED_QUEUE_TIMEOUT_MS")
+                .unwrap_or("2000".into())
+                .parse()
+                .unwrap_or(2000),
 
-            log_level: env::var("LOG_LEVEL").unwrap_or("info".into()),
-            log_format: env::var("LOG_FORMAT").unwrap_or("json".into()),
+            log_level: var("LOG_LEVEL").unwrap_or("info".into()),
+            log_format: var("LOG_FORMAT").unwrap_or("json".into()),
 
-            replicate_api_token: env::var("REPLICATE_API_TOKEN").unwrap_or_default(),
-            replicate_model: env::var("REPLICATE_MODEL")
-                .unwrap_or("black-forest-labs/flux-dev".into()),
+            replicate_api_token: var("REPLICATE_API_TOKEN").unwrap_or_default(),
+            replicate_model: var("REPLICATE_MODEL").unwrap_or("black-forest-labs/flux-dev".into()),
+            replicate_video_model: var("REPLICATE_VIDEO_MODEL")
+                .unwrap_or("lightricks/ltx-video".into()),
+            starter_video_enabled: var("STARTER_VIDEO_ENABLED")
+                .unwrap_or("false".into())
+                .parse()
+                .unwrap_or(false),
+            image_gen_daily_limit: var("IMAGE_GEN_DAILY_LIMIT")
+                .unwrap_or("10".into())
+                .parse()
+                .unwrap_or(10),
+            image_gen_cooldown_seconds: var("IMAGE_GEN_COOLDOWN_SECONDS")
+                .unwrap_or("30".into())
+                .parse()
+                .unwrap_or(30),
+            replicate_daily_image_limit: var("REPLICATE_DAILY_IMAGE_LIMIT")
+                .unwrap_or("0".into())
+                .parse()
+                .unwrap_or(0),
+            replicate_daily_video_limit: var("REPLICATE_DAILY_VIDEO_LIMIT")
+                .unwrap_or("0".into())
+                .parse()
+                .unwrap_or(0),
+
+            // Avatar moderation
+            avatar_moderation_enabled: var("AVATAR_MODERATION_ENABLED")
+                .unwrap_or("false".into())
+                .parse()
+                .unwrap_or(false),
+            avatar_moderation_max_attempts: var("AVATAR_MODERATION_MAX_ATTEMPTS")
+                .unwrap_or("2".into())
+                .parse()
+                .unwrap_or(2),
 
-            metadata_url: env::var("METADATA_URL").unwrap_or("https://metadata.yral.com".into()),
-            metadata_auth_token: env::var("YRAL_METADATA_NOTIFICATION_API_KEY")
+            metadata_url: var("METADATA_URL").unwrap_or("https://metadata.yral.com".into()),
+            metadata_auth_token: var("YRAL_METADATA_NOTIFICATION_API_KEY")
                 .ok()
                 .filter(|s| !s.is_empty()),
+            push_batch_window_ms: var("PUSH_BATCH_WINDOW_MS")
+                .unwrap_or("3000".into())
+                .parse()
+                .unwrap_or(3000),
 
-            sentry_dsn: env::var("SENTRY_DSN").ok().filter(|s| !s.is_empty()),
-            sentry_traces_sample_rate: env::var("SENTRY_TRACES_SAMPLE_RATE")
+            sentry_dsn: var("SENTRY_DSN").ok().filter(|s| !s.is_empty()),
+            sentry_traces_sample_rate: var("SENTRY_TRACES_SAMPLE_RATE")
                 .unwrap_or("1.0".into())
                 .parse()
                 .unwrap_or(1.0),
-            sentry_profiles_sample_rate: env::var("SENTRY_PROFILES_SAMPLE_RATE")
+            sentry_profiles_sample_rate: var("SENTRY_PROFILES_SAMPLE_RATE")
                 .unwrap_or("1.0".into())
                 .parse()
                 .unwrap_or(1.0),
-            google_chat_webhook_url: env::var("GOOGLE_CHAT_WEBHOOK_URL")
+            sentry_webhook_secret: var("SENTRY_WEBHOOK_SECRET").ok().filter(|s| !s.is_empty()),
+            sentry_alert_dedup_seconds: var("SENTRY_ALERT_DEDUP_SECONDS")
+                .unwrap_or("600".into())
+                .parse()
+                .unwrap_or(600),
+            google_chat_webhook_url: var("GOOGLE_CHAT_WEBHOOK_URL")
+                .ok()
+                .filter(|s| !s.is_empty()),
+
+            admin_key_to_delete_influencer: var("ADMIN_KEY_TO_DELETE_INFLUENCER")
                 .ok()
                 .filter(|s| !s.is_empty()),
 
-            admin_key_to_delete_influencer: env::var("ADMIN_KEY_TO_DELETE_INFLUENCER")
+            prompt_injection_strictness: var("PROMPT_INJECTION_STRICTNESS")
+                .unwrap_or("neutralize".into())
+                .parse()
+                .unwrap_or(InjectionStrictness::Neutralize),
+            output_sanitize_enabled: var("OUTPUT_SANITIZE_ENABLED")
+                .unwrap_or("true".into())
+                .parse()
+                .unwrap_or(true),
+            assistant_max_chars: var("ASSISTANT_MAX_CHARS")
+                .unwrap_or("4000".into())
+                .parse()
+                .unwrap_or(4000),
+            outbound_link_redirect_url: var("OUTBOUND_LINK_REDIRECT_URL")
                 .ok()
                 .filter(|s| !s.is_empty()),
+            pii_redaction_enabled: var("PII_REDACTION_ENABLED")
+                .unwrap_or("false".into())
+                .parse()
+                .unwrap_or(false),
+            pii_redaction_kinds: var("PII_REDACTION_KINDS").unwrap_or("email,phone,address".into()),
+
+            message_max_chars: var("MESSAGE_MAX_CHARS")
+                .unwrap_or("16000".into())
+                .parse()
+                .unwrap_or(16000),
+            message_condense_threshold_chars: var("MESSAGE_CONDENSE_THRESHOLD_CHARS")
+                .unwrap_or("4000".into())
+                .parse()
+                .unwrap_or(4000),
+            message_condense_chunk_chars: var("MESSAGE_CONDENSE_CHUNK_CHARS")
+                .unwrap_or("3000".into())
+                .parse()
+                .unwrap_or(3000),
+            media_only_prompt: var("MEDIA_ONLY_PROMPT")
+                .unwrap_or("What do you think of this {media}?".into()),
+            message_debounce_seconds: var("MESSAGE_DEBOUNCE_SECONDS")
+                .unwrap_or("0".into())
+                .parse()
+                .unwrap_or(0),
+
+            memory_max_count: var("MEMORY_MAX_COUNT")
+                .unwrap_or("30".into())
+                .parse()
+                .unwrap_or(30),
+            memory_max_value_chars: var("MEMORY_MAX_VALUE_CHARS")
+                .unwrap_or("200".into())
+                .parse()
+                .unwrap_or(200),
+            memory_consolidate_at: var("MEMORY_CONSOLIDATE_AT")
+                .unwrap_or("20".into())
+                .parse()
+                .unwrap_or(20),
+
+            failed_reply_retry_enabled: var("FAILED_REPLY_RETRY_ENABLED")
+                .unwrap_or("true".into())
+                .parse()
+                .unwrap_or(true),
+            failed_reply_retry_interval_seconds: var("FAILED_REPLY_RETRY_INTERVAL_SECONDS")
+                .unwrap_or("60".into())
+                .parse()
+                .unwrap_or(60),
+            failed_reply_max_attempts: var("FAILED_REPLY_MAX_ATTEMPTS")
+                .unwrap_or("5".into())
+                .parse()
+                .unwrap_or(5),
+            scheduled_reply_check_interval_seconds: var("SCHEDULED_REPLY_CHECK_INTERVAL_SECONDS")
+                .unwrap_or("30".into())
+                .parse()
+                .unwrap_or(30),
+
+            digest_enabled: var("DIGEST_ENABLED")
+                .unwrap_or("false".into())
+                .parse()
+                .unwrap_or(false),
+            digest_check_interval_seconds: var("DIGEST_CHECK_INTERVAL_SECONDS")
+                .unwrap_or("3600".into())
+                .parse()
+                .unwrap_or(3600),
+            quiet_hours_check_interval_seconds: var("QUIET_HOURS_CHECK_INTERVAL_SECONDS")
+                .unwrap_or("60".into())
+                .parse()
+                .unwrap_or(60),
+
+            suggestion_rotation_enabled: var("SUGGESTION_ROTATION_ENABLED")
+                .unwrap_or("false".into())
+                .parse()
+                .unwrap_or(false),
+            suggestion_rotation_interval_seconds: var("SUGGESTION_ROTATION_INTERVAL_SECONDS")
+                .unwrap_or("86400".into())
+                .parse()
+                .unwrap_or(86400),
+            suggestion_rotation_window_days: var("SUGGESTION_ROTATION_WINDOW_DAYS")
+                .unwrap_or("14".into())
+                .parse::<i32>()
+                .unwrap_or(14)
+                .clamp(1, 365),
+            suggestion_rotation_min_conversations: var("SUGGESTION_ROTATION_MIN_CONVERSATIONS")
+                .unwrap_or("20".into())
+                .parse()
+                .unwrap_or(20),
+            suggested_message_count: var("SUGGESTED_MESSAGE_COUNT")
+                .unwrap_or("3".into())
+                .parse()
+                .unwrap_or(3),
+
+            abuse_detection_enabled: var("ABUSE_DETECTION_ENABLED")
+                .unwrap_or("true".into())
+                .parse()
+                .unwrap_or(true),
+            abuse_window_seconds: var("ABUSE_WINDOW_SECONDS")
+                .unwrap_or("600".into())
+                .parse::<u64>()
+                .unwrap_or(600)
+                .max(1),
+            abuse_repeat_threshold: var("ABUSE_REPEAT_THRESHOLD")
+                .unwrap_or("3".into())
+                .parse::<usize>()
+                .unwrap_or(3)
+                .max(2),
+            abuse_max_chars_per_window: var("ABUSE_MAX_CHARS_PER_WINDOW")
+                .unwrap_or("20000".into())
+                .parse()
+                .unwrap_or(20000),
+            abuse_max_urls: var("ABUSE_MAX_URLS")
+                .unwrap_or("3".into())
+                .parse::<usize>()
+                .unwrap_or(3)
+                .max(1),
+            abuse_throttle_seconds: var("ABUSE_THROTTLE_SECONDS")
+                .unwrap_or("300".into())
+                .parse()
+                .unwrap_or(300),
+            abuse_shadow_limit_seconds: var("ABUSE_SHADOW_LIMIT_SECONDS")
+                .unwrap_or("3600".into())
+                .parse()
+                .unwrap_or(3600),
+
+            email_inbound_secret: var("EMAIL_INBOUND_SECRET").ok().filter(|s| !s.is_empty()),
+            email_domain: var("EMAIL_DOMAIN").unwrap_or("chat.yral.com".into()),
+            sendgrid_api_key: var("SENDGRID_API_KEY").ok().filter(|s| !s.is_empty()),
+
+            telegram_webhook_base_url: var("TELEGRAM_WEBHOOK_BASE_URL")
+                .ok()
+                .filter(|s| !s.is_empty()),
+
+            influencer_cache_ttl_seconds: var("INFLUENCER_CACHE_TTL_SECONDS")
+                .unwrap_or("60".into())
+                .parse()
+                .unwrap_or(60),
+            character_cache_ttl_seconds: var("CHARACTER_CACHE_TTL_SECONDS")
+                .unwrap_or("600".into())
+                .parse()
+                .unwrap_or(600),
+            caller_type_cache_ttl_seconds: var("CALLER_TYPE_CACHE_TTL_SECONDS")
+                .unwrap_or("3600".into())
+                .parse()
+                .unwrap_or(3600),
+            caller_type_negative_ttl_seconds: var("CALLER_TYPE_NEGATIVE_TTL_SECONDS")
+                .unwrap_or("60".into())
+                .parse()
+                .unwrap_or(60),
+            caller_type_force_user: var("CALLER_TYPE_FORCE_USER")
+                .unwrap_or("false".into())
+                .parse()
+                .unwrap_or(false),
+            profile_cache_ttl_seconds: var("PROFILE_CACHE_TTL_SECONDS")
+                .unwrap_or("300".into())
+                .parse()
+                .unwrap_or(300),
+            profile_cache_stale_seconds: var("PROFILE_CACHE_STALE_SECONDS")
+                .unwrap_or("3600".into())
+                .parse()
+                .unwrap_or(3600),
+            sandbox_ttl_minutes: var("SANDBOX_TTL_MINUTES")
+                .unwrap_or("60".into())
+                .parse()
+                .unwrap_or(60),
+            ws_queue_capacity: var("WS_QUEUE_CAPACITY")
+                .unwrap_or("256".into())
+                .parse()
+                .unwrap_or(256),
+            ws_slow_client_timeout_seconds: var("WS_SLOW_CLIENT_TIMEOUT_SECONDS")
+                .unwrap_or("30".into())
+                .parse()
+                .unwrap_or(30),
+            presence_write_interval_seconds: var("PRESENCE_WRITE_INTERVAL_SECONDS")
+                .unwrap_or("60".into())
+                .parse()
+                .unwrap_or(60),
+            impression_flush_interval_seconds: var("IMPRESSION_FLUSH_INTERVAL_SECONDS")
+                .unwrap_or("30".into())
+                .parse()
+                .unwrap_or(30),
+            change_log_retention_days: var("CHANGE_LOG_RETENTION_DAYS")
+                .unwrap_or("14".into())
+                .parse()
+                .unwrap_or(14),
+            change_log_prune_interval_seconds: var("CHANGE_LOG_PRUNE_INTERVAL_SECONDS")
+                .unwrap_or("3600".into())
+                .parse()
+                .unwrap_or(3600),
+            ai_sample_logging_enabled: var("AI_SAMPLE_LOGGING_ENABLED")
+                .unwrap_or("false".into())
+                .parse()
+                .unwrap_or(false),
+            ai_sample_rate: var("AI_SAMPLE_RATE")
+                .unwrap_or("0.01".into())
+                .parse()
+                .unwrap_or(0.01),
+            ai_sample_retention_days: var("AI_SAMPLE_RETENTION_DAYS")
+                .unwrap_or("7".into())
+                .parse()
+                .unwrap_or(7),
+            image_captions_enabled: var("IMAGE_CAPTIONS_ENABLED")
+                .unwrap_or("true".into())
+                .parse()
+                .unwrap_or(true),
+            ai_tools_enabled: var("AI_TOOLS_ENABLED")
+                .unwrap_or("false".into())
+                .parse()
+                .unwrap_or(false),
+            weather_api_url: var("WEATHER_API_URL").unwrap_or(
+                "https://wttr.in/{location}?format=%l:+%C,+%t+(feels+like+%f),+humidity+%h,+wind+%w"
+                    .into(),
+            ),
+            web_search_api_url: var("WEB_SEARCH_API_URL").unwrap_or_default(),
+            web_search_api_key: var("WEB_SEARCH_API_KEY").ok().filter(|k| !k.is_empty()),
+            knowledge_base_enabled: var("KNOWLEDGE_BASE_ENABLED")
+                .unwrap_or("true".into())
+                .parse()
+                .unwrap_or(true),
+            knowledge_embedding_model: var("KNOWLEDGE_EMBEDDING_MODEL")
+                .unwrap_or("gemini-embedding-001".into()),
+            knowledge_max_document_mb: var("KNOWLEDGE_MAX_DOCUMENT_MB")
+                .unwrap_or("5".into())
+                .parse()
+                .unwrap_or(5),
+            knowledge_max_documents: var("KNOWLEDGE_MAX_DOCUMENTS")
+                .unwrap_or("20".into())
+                .parse()
+                .unwrap_or(20),
+            knowledge_max_chunks: var("KNOWLEDGE_MAX_CHUNKS")
+                .unwrap_or("2000".into())
+                .parse()
+                .unwrap_or(2000),
+            knowledge_chunk_chars: var("KNOWLEDGE_CHUNK_CHARS")
+                .unwrap_or("1200".into())
+                .parse()
+                .unwrap_or(1200),
+            knowledge_top_k: var("KNOWLEDGE_TOP_K")
+                .unwrap_or("4".into())
+                .parse()
+                .unwrap_or(4),
+            knowledge_min_score: var("KNOWLEDGE_MIN_SCORE")
+                .unwrap_or("0.55".into())
+                .parse()
+                .unwrap_or(0.55),
+            message_embeddings_enabled: var("MESSAGE_EMBEDDINGS_ENABLED")
+                .unwrap_or("false".into())
+                .parse()
+                .unwrap_or(false),
+            message_embedding_interval_seconds: var("MESSAGE_EMBEDDING_INTERVAL_SECONDS")
+                .unwrap_or("30".into())
+                .parse()
+                .unwrap_or(30),
+            message_embedding_batch_size: var("MESSAGE_EMBEDDING_BATCH_SIZE")
+                .unwrap_or("200".into())
+                .parse()
+                .unwrap_or(200),
+            semantic_search_max_candidates: var("SEMANTIC_SEARCH_MAX_CANDIDATES")
+                .unwrap_or("5000".into())
+                .parse()
+                .unwrap_or(5000),
+            semantic_search_min_score: var("SEMANTIC_SEARCH_MIN_SCORE")
+                .unwrap_or("0.5".into())
+                .parse()
+                .unwrap_or(0.5),
+            duplicate_check_enabled: var("DUPLICATE_CHECK_ENABLED")
+                .unwrap_or("true".into())
+                .parse()
+                .unwrap_or(true),
+            duplicate_name_similarity: var("DUPLICATE_NAME_SIMILARITY")
+                .unwrap_or("0.85".into())
+                .parse()
+                .unwrap_or(0.85),
+            duplicate_prompt_similarity: var("DUPLICATE_PROMPT_SIMILARITY")
+                .unwrap_or("0.9".into())
+                .parse()
+                .unwrap_or(0.9),
+            reserved_influencer_names: var("RESERVED_INFLUENCER_NAMES").unwrap_or(
+                "admin,administrator,yral,yralai,dolr,dolrai,official,support,help,system,moderator,staff,team,root,null,undefined"
+                    .into(),
+            ),
+            boost_verified_influencers: var("BOOST_VERIFIED_INFLUENCERS")
+                .unwrap_or("false".into())
+                .parse()
+                .unwrap_or(false),
+            tenants: var("TENANTS").unwrap_or("yral".into()),
+            tenant_issuers: var("TENANT_ISSUERS").unwrap_or_default(),
+            backup_verify_enabled: var("BACKUP_VERIFY_ENABLED")
+                .unwrap_or("false".into())
+                .parse()
+                .unwrap_or(false),
+            backup_verify_interval_hours: var("BACKUP_VERIFY_INTERVAL_HOURS")
+                .unwrap_or("24".into())
+                .parse()
+                .unwrap_or(24),
+            litestream_config_path: var("LITESTREAM_CONFIG_PATH")
+                .unwrap_or("/tmp/litestream.yml".into()),
+            legacy_import_max_mb: var("LEGACY_IMPORT_MAX_MB")
+                .unwrap_or("512".into())
+                .parse()
+                .unwrap_or(512),
+        }
+    }
+
+    /// Copy the settings that take effect without a restart from `fresh` and return
+    /// the names of those that changed. Everything else — listeners, pools, keys,
+    /// caches and background job schedules — keeps its startup value.
+    pub fn apply_reloadable(&mut self, fresh: &Settings) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        take_changed!(self, fresh, changed;
+            rate_limit_per_minute,
+            rate_limit_per_hour,
+            conversation_replies_per_minute,
+            gemini_model,
+            gemini_max_tokens,
+            gemini_temperature,
+            openrouter_model,
+            openrouter_max_tokens,
+            openrouter_temperature,
+            max_audio_duration_seconds,
+            normalize_uploaded_images,
+            image_normalize_quality,
+            starter_video_enabled,
+            prompt_injection_strictness,
+            output_sanitize_enabled,
+            assistant_max_chars,
+            outbound_link_redirect_url,
+            message_max_chars,
+            message_condense_threshold_chars,
+            message_condense_chunk_chars,
+            media_only_prompt,
+            message_debounce_seconds,
+            suggested_message_count,
+            abuse_detection_enabled,
+            abuse_window_seconds,
+            abuse_repeat_threshold,
+            abuse_max_chars_per_window,
+            abuse_max_urls,
+            abuse_throttle_seconds,
+            abuse_shadow_limit_seconds,
+            memory_max_count,
+            memory_max_value_chars,
+            memory_consolidate_at,
+            ai_sample_logging_enabled,
+            ai_sample_rate,
+            avatar_moderation_enabled,
+            image_captions_enabled,
+            ai_tools_enabled,
+            weather_api_url,
+            web_search_api_url,
+            knowledge_base_enabled,
+            knowledge_top_k,
+            knowledge_min_score,
+            message_embeddings_enabled,
+            message_embedding_batch_size,
+            semantic_search_max_candidates,
+            semantic_search_min_score,
+            duplicate_check_enabled,
+            duplicate_name_similarity,
+            duplicate_prompt_similarity,
+            reserved_influencer_names,
+            boost_verified_influencers,
+        );
+        changed
+    }
+
+    /// `self` with the settings a tenant can override taken from `{TENANT}_`-prefixed
+    /// variables where they are set, e.g. `DOLR_RATE_LIMIT_PER_MINUTE`. `var` is the
+    /// lookup `self` was loaded with, so unprefixed values agree with it.
+    pub fn for_tenant(
+        &self,
+        tenant: &str,
+        var: impl Fn(&str) -> Result<String, env::VarError>,
+    ) -> Self {
+        let prefix = format!("{}_", tenant.to_uppercase().replace('-', "_"));
+        let fresh = Self::from_lookup(|key| var(&format!("{prefix}{key}")).or_else(|_| var(key)));
+        let mut settings = self.clone();
+        take_changed!(settings, fresh;
+            rate_limit_per_minute,
+            rate_limit_per_hour,
+            conversation_replies_per_minute,
+            message_max_chars,
+            prompt_injection_strictness,
+            starter_video_enabled,
+            media_only_prompt,
+            message_debounce_seconds,
+            boost_verified_influencers,
+        );
+        settings
+    }
+
+    pub fn gemini_defaults(&self) -> ModelDefaults {
+        ModelDefaults {
+            model: self.gemini_model.clone(),
+            max_tokens: self.gemini_max_tokens,
+            temperature: self.gemini_temperature,
+        }
+    }
+
+    pub fn openrouter_defaults(&self) -> ModelDefaults {
+        ModelDefaults {
+            model: self.openrouter_model.clone(),
+            max_tokens: self.openrouter_max_tokens,
+            temperature: self.openrouter_temperature,
         }
     }
 
@@ -238,6 +1060,33 @@ impl Settings {
             .collect()
     }
 
+    pub fn jwt_issuers_list(&self) -> Vec<String> {
+        split_list(&self.jwt_issuers)
+    }
+
+    pub fn jwt_audiences_list(&self) -> Vec<String> {
+        split_list(&self.jwt_audiences)
+    }
+
+    pub fn pii_redaction_kinds_list(&self) -> Vec<String> {
+        split_list(&self.pii_redaction_kinds)
+    }
+
+    pub fn tenants_list(&self) -> Vec<String> {
+        split_list(&self.tenants)
+    }
+
+    /// `(issuer, tenant)` pairs; entries without an `=` are skipped.
+    pub fn tenant_issuers_list(&self) -> Vec<(String, String)> {
+        split_list(&self.tenant_issuers)
+            .iter()
+            .filter_map(|pair| {
+                let (issuer, tenant) = pair.split_once('=')?;
+                Some((issuer.trim().to_string(), tenant.trim().to_string()))
+            })
+            .collect()
+    }
+
     #[inline]
     pub fn max_image_size_bytes(&self) -> u64 {
         self.max_image_size_mb as u64 * 1024 * 1024
@@ -247,4 +1096,94 @@ impl Settings {
     pub fn max_audio_size_bytes(&self) -> u64 {
         self.max_audio_size_mb as u64 * 1024 * 1024
     }
+
+    #[inline]
+    pub fn max_json_body_bytes(&self) -> usize {
+        self.max_json_body_kb as usize * 1024
+    }
+
+    /// Largest accepted media file plus headroom for the multipart framing.
+    #[inline]
+    pub fn max_upload_body_bytes(&self) -> usize {
+        self.max_image_size_bytes().max(self.max_audio_size_bytes()) as usize + 1024 * 1024
+    }
+
+    #[inline]
+    pub fn legacy_import_max_bytes(&self) -> usize {
+        self.legacy_import_max_mb as usize * 1024 * 1024
+    }
+
+    /// Whether `name` is on the reserved handle list, ignoring case, separators
+    /// and digits standing in for letters (`Adm1n`, `y_r_a_l`).
+    pub fn is_reserved_influencer_name(&self, name: &str) -> bool {
+        let name = reserved_name_key(name);
+        !name.is_empty()
+            && self
+                .reserved_influencer_names
+                .split(',')
+                .any(|reserved| reserved_name_key(reserved) == name)
+    }
+
+    /// Largest accepted knowledge document plus headroom for the multipart framing.
+    #[inline]
+    pub fn knowledge_upload_body_bytes(&self) -> usize {
+        (self.knowledge_max_document_mb as usize + 1) * 1024 * 1024
+    }
+}
+
+/// Form of a name compared against the reserved list: lowercase letters only,
+/// with look-alike digits read as the letters they imitate.
+fn reserved_name_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            '0' => 'o',
+            '1' | 'l' => 'i',
+            '3' => 'e',
+            '4' => 'a',
+            '5' => 's',
+            '7' => 't',
+            '8' => 'b',
+            c => c,
+        })
+        .collect()
+}
+
+/// Comma-separated values with blanks dropped.
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Variables as a reload sees them: `.env` values over the environment the process
+/// started with. Values `.env` set at startup are in that environment too, so the
+/// file has to win for an edit to it to show.
+pub fn dotenv_lookup() -> impl Fn(&str) -> Result<String, env::VarError> {
+    let dotenv: HashMap<String, String> = dotenvy::dotenv_iter()
+        .map(|iter| iter.filter_map(Result::ok).collect())
+        .unwrap_or_default();
+    move |key| match dotenv.get(key) {
+        Some(value) => Ok(value.clone()),
+        None => env::var(key),
+    }
+}
+
+/// Swap the reloadable settings read through `var` into `shared`. Returns the
+/// names of the settings that changed.
+pub fn reload(
+    shared: &SharedSettings,
+    var: impl Fn(&str) -> Result<String, env::VarError>,
+) -> Vec<&'static str> {
+    let fresh = Settings::from_lookup(var);
+    let mut next = Settings::clone(&shared.load());
+    let changed = next.apply_reloadable(&fresh);
+    if !changed.is_empty() {
+        shared.store(Arc::new(next));
+    }
+    changed
 }
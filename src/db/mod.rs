@@ -1,3 +1,4 @@
+pub mod repos;
 pub mod repositories;
 
 use std::path::Path;
@@ -28,6 +29,11 @@ pub struct HealthCheckResult {
 
 // ── Staging: SQLite-only ──────────────────────────────────────────────────────
 
+#[cfg(feature = "staging")]
+const IN_MEMORY_PATH: &str = ":memory:";
+#[cfg(feature = "staging")]
+const IN_MEMORY_URL: &str = "sqlite::memory:";
+
 #[cfg(feature = "staging")]
 #[derive(Clone)]
 pub struct Database {
@@ -38,6 +44,9 @@ pub struct Database {
 #[cfg(feature = "staging")]
 impl Database {
     pub async fn connect(settings: &Settings) -> Result<Self, sqlx::Error> {
+        if settings.database_path == IN_MEMORY_PATH {
+            return Self::connect_in_memory(settings).await;
+        }
         let db_path = resolve_db_path(&settings.database_path);
 
         if let Some(parent) = Path::new(&db_path).parent() {
@@ -81,6 +90,31 @@ impl Database {
         Ok(Self { pool, db_path })
     }
 
+    /// Private in-memory database for hermetic test runs (`DATABASE_PATH=:memory:`).
+    /// sqlx gives every `sqlite::memory:` pool one shared-cache database, which only
+    /// lives while a connection is open, so connections are never retired.
+    async fn connect_in_memory(settings: &Settings) -> Result<Self, sqlx::Error> {
+        let connect_options = IN_MEMORY_URL
+            .parse::<SqliteConnectOptions>()?
+            .pragma("foreign_keys", "ON")
+            .disable_statement_logging();
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(settings.database_pool_size)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(connect_options)
+            .await?;
+
+        tracing::info!("Connected to in-memory SQLite database");
+
+        Ok(Self {
+            pool,
+            db_path: IN_MEMORY_PATH.to_string(),
+        })
+    }
+
     pub fn conv_repo(&self) -> repositories::ConversationRepository {
         repositories::ConversationRepository::new(self.pool.clone())
     }
@@ -93,6 +127,90 @@ impl Database {
         repositories::InfluencerRepository::new(self.pool.clone())
     }
 
+    pub fn part_repo(&self) -> repositories::ParticipantRepository {
+        repositories::ParticipantRepository::new(self.pool.clone())
+    }
+
+    pub fn digest_repo(&self) -> repositories::DigestRepository {
+        repositories::DigestRepository::new(self.pool.clone())
+    }
+
+    pub fn webhook_repo(&self) -> repositories::WebhookRepository {
+        repositories::WebhookRepository::new(self.pool.clone())
+    }
+
+    pub fn telegram_repo(&self) -> repositories::TelegramRepository {
+        repositories::TelegramRepository::new(self.pool.clone())
+    }
+
+    pub fn share_repo(&self) -> repositories::ShareRepository {
+        repositories::ShareRepository::new(self.pool.clone())
+    }
+
+    pub fn upload_scan_repo(&self) -> repositories::UploadScanRepository {
+        repositories::UploadScanRepository::new(self.pool.clone())
+    }
+
+    pub fn audio_upload_repo(&self) -> repositories::AudioUploadRepository {
+        repositories::AudioUploadRepository::new(self.pool.clone())
+    }
+
+    pub fn notification_prefs_repo(&self) -> repositories::NotificationPreferencesRepository {
+        repositories::NotificationPreferencesRepository::new(self.pool.clone())
+    }
+
+    pub fn suggestion_repo(&self) -> repositories::SuggestionRepository {
+        repositories::SuggestionRepository::new(self.pool.clone())
+    }
+
+    pub fn presence_repo(&self) -> repositories::PresenceRepository {
+        repositories::PresenceRepository::new(self.pool.clone())
+    }
+
+    pub fn revoked_token_repo(&self) -> repositories::RevokedTokenRepository {
+        repositories::RevokedTokenRepository::new(self.pool.clone())
+    }
+
+    pub fn audit_log_repo(&self) -> repositories::AuditLogRepository {
+        repositories::AuditLogRepository::new(self.pool.clone())
+    }
+
+    pub fn change_log_repo(&self) -> repositories::ChangeLogRepository {
+        repositories::ChangeLogRepository::new(self.pool.clone())
+    }
+
+    pub fn ai_sample_repo(&self) -> repositories::AiSampleRepository {
+        repositories::AiSampleRepository::new(self.pool.clone())
+    }
+
+    pub fn abuse_event_repo(&self) -> repositories::AbuseEventRepository {
+        repositories::AbuseEventRepository::new(self.pool.clone())
+    }
+
+    pub fn sandbox_repo(&self) -> repositories::SandboxRepository {
+        repositories::SandboxRepository::new(self.pool.clone())
+    }
+
+    pub fn scheduled_reply_repo(&self) -> repositories::ScheduledReplyRepository {
+        repositories::ScheduledReplyRepository::new(self.pool.clone())
+    }
+
+    pub fn image_quota_repo(&self) -> repositories::ImageQuotaRepository {
+        repositories::ImageQuotaRepository::new(self.pool.clone())
+    }
+
+    pub fn knowledge_repo(&self) -> repositories::KnowledgeRepository {
+        repositories::KnowledgeRepository::new(self.pool.clone())
+    }
+
+    pub fn message_embedding_repo(&self) -> repositories::MessageEmbeddingRepository {
+        repositories::MessageEmbeddingRepository::new(self.pool.clone())
+    }
+
+    pub fn legacy_import_repo(&self) -> repositories::LegacyImportRepository {
+        repositories::LegacyImportRepository::new(self.pool.clone())
+    }
+
     pub async fn run_checkpoint(&self) {
         match sqlx::query_as::<_, (i32, i32, i32)>("PRAGMA wal_checkpoint(PASSIVE)")
             .fetch_one(&self.pool)
@@ -110,6 +228,14 @@ impl Database {
         }
     }
 
+    /// Checkpoint and truncate the WAL. Waits out readers and blocks writers, so it is
+    /// for one-off maintenance; the periodic task stays PASSIVE.
+    pub async fn truncate_wal(&self) -> Result<(i32, i32, i32), sqlx::Error> {
+        sqlx::query_as::<_, (i32, i32, i32)>("PRAGMA wal_checkpoint(TRUNCATE)")
+            .fetch_one(&self.pool)
+            .await
+    }
+
     pub fn spawn_periodic_checkpoint(pool: SqlitePool, interval_secs: u64) {
         tokio::spawn(async move {
             let interval = std::time::Duration::from_secs(interval_secs);
@@ -215,6 +341,90 @@ impl Database {
         repositories::InfluencerRepository::new(self.pg_pool.clone())
     }
 
+    pub fn part_repo(&self) -> repositories::ParticipantRepository {
+        repositories::ParticipantRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn digest_repo(&self) -> repositories::DigestRepository {
+        repositories::DigestRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn webhook_repo(&self) -> repositories::WebhookRepository {
+        repositories::WebhookRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn telegram_repo(&self) -> repositories::TelegramRepository {
+        repositories::TelegramRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn share_repo(&self) -> repositories::ShareRepository {
+        repositories::ShareRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn upload_scan_repo(&self) -> repositories::UploadScanRepository {
+        repositories::UploadScanRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn audio_upload_repo(&self) -> repositories::AudioUploadRepository {
+        repositories::AudioUploadRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn notification_prefs_repo(&self) -> repositories::NotificationPreferencesRepository {
+        repositories::NotificationPreferencesRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn suggestion_repo(&self) -> repositories::SuggestionRepository {
+        repositories::SuggestionRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn presence_repo(&self) -> repositories::PresenceRepository {
+        repositories::PresenceRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn revoked_token_repo(&self) -> repositories::RevokedTokenRepository {
+        repositories::RevokedTokenRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn audit_log_repo(&self) -> repositories::AuditLogRepository {
+        repositories::AuditLogRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn change_log_repo(&self) -> repositories::ChangeLogRepository {
+        repositories::ChangeLogRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn ai_sample_repo(&self) -> repositories::AiSampleRepository {
+        repositories::AiSampleRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn abuse_event_repo(&self) -> repositories::AbuseEventRepository {
+        repositories::AbuseEventRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn sandbox_repo(&self) -> repositories::SandboxRepository {
+        repositories::SandboxRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn scheduled_reply_repo(&self) -> repositories::ScheduledReplyRepository {
+        repositories::ScheduledReplyRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn image_quota_repo(&self) -> repositories::ImageQuotaRepository {
+        repositories::ImageQuotaRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn knowledge_repo(&self) -> repositories::KnowledgeRepository {
+        repositories::KnowledgeRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn message_embedding_repo(&self) -> repositories::MessageEmbeddingRepository {
+        repositories::MessageEmbeddingRepository::new(self.pg_pool.clone())
+    }
+
+    pub fn legacy_import_repo(&self) -> repositories::LegacyImportRepository {
+        repositories::LegacyImportRepository::new(self.pg_pool.clone())
+    }
+
     pub async fn health_check(&self) -> HealthCheckResult {
         let start = Instant::now();
         match sqlx::query_scalar::<_, i32>("SELECT 1")
@@ -243,6 +453,20 @@ impl Database {
 
 // ── Migrations ────────────────────────────────────────────────────────────────
 
+/// Migrations shipped in the container image, falling back to the source tree.
+pub fn migrations_dir() -> &'static str {
+    #[cfg(feature = "staging")]
+    let (image_dir, local_dir) = ("/app/migrations/sqlite", "./migrations/sqlite");
+    #[cfg(not(feature = "staging"))]
+    let (image_dir, local_dir) = ("/app/migrations/postgres", "./migrations/postgres");
+
+    if Path::new(image_dir).exists() {
+        image_dir
+    } else {
+        local_dir
+    }
+}
+
 #[cfg(feature = "staging")]
 pub async fn run_migrations(pool: &SqlitePool, migrations_dir: &str) -> Result<(), sqlx::Error> {
     let path = Path::new(migrations_dir);
@@ -271,8 +495,9 @@ pub async fn run_pg_migrations(pool: &PgPool, migrations_dir: &str) -> Result<()
 
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
-#[cfg(feature = "staging")]
-fn resolve_db_path(db_path: &str) -> String {
+/// `db_path` made absolute: relative paths are under `/app` in the container and
+/// the working directory elsewhere.
+pub fn resolve_db_path(db_path: &str) -> String {
     let path = Path::new(db_path);
     if path.is_absolute() {
         return db_path.to_string();
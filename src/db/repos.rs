@@ -0,0 +1,61 @@
+use std::convert::Infallible;
+use std::sync::{Arc, OnceLock};
+
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+
+use super::Database;
+use super::repositories::{
+    ConversationRepository, InfluencerRepository, MessageRepository, ParticipantRepository,
+};
+use crate::AppState;
+
+/// The chat repositories for one request. Taken as an extractor, it holds a
+/// single handle on the pool and builds each repository the first time a
+/// handler asks for it, instead of every handler cloning the pool per repo.
+pub struct Repos {
+    db: Database,
+    conv: OnceLock<ConversationRepository>,
+    msg: OnceLock<MessageRepository>,
+    inf: OnceLock<InfluencerRepository>,
+    part: OnceLock<ParticipantRepository>,
+}
+
+impl Repos {
+    pub fn new(db: Database) -> Self {
+        Self {
+            db,
+            conv: OnceLock::new(),
+            msg: OnceLock::new(),
+            inf: OnceLock::new(),
+            part: OnceLock::new(),
+        }
+    }
+
+    pub fn conv(&self) -> &ConversationRepository {
+        self.conv.get_or_init(|| self.db.conv_repo())
+    }
+
+    pub fn msg(&self) -> &MessageRepository {
+        self.msg.get_or_init(|| self.db.msg_repo())
+    }
+
+    pub fn inf(&self) -> &InfluencerRepository {
+        self.inf.get_or_init(|| self.db.inf_repo())
+    }
+
+    pub fn part(&self) -> &ParticipantRepository {
+        self.part.get_or_init(|| self.db.part_repo())
+    }
+}
+
+impl FromRequestParts<Arc<AppState>> for Repos {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        _parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(Self::new(state.db.clone()))
+    }
+}
@@ -0,0 +1,244 @@
+#[cfg(not(feature = "staging"))]
+use chrono::NaiveDateTime;
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+
+use crate::models::entities::{AbuseEvent, AbuseKind};
+
+/// Optional filters for listing abuse events; unset fields match everything.
+#[derive(Debug, Default)]
+pub struct AbuseEventFilter {
+    pub user_id: Option<String>,
+    pub conversation_id: Option<String>,
+    pub kind: Option<AbuseKind>,
+}
+
+impl AbuseEventFilter {
+    fn kind(&self) -> Option<&str> {
+        self.kind.as_ref().map(AsRef::as_ref)
+    }
+}
+
+const SELECT_COLS: &str =
+    "id, user_id, conversation_id, kind, action, detail, expires_at, created_at";
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct AbuseEventRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct AbuseEventRow {
+    id: String,
+    user_id: String,
+    conversation_id: String,
+    kind: String,
+    action: String,
+    detail: Option<String>,
+    expires_at: String,
+    created_at: String,
+}
+
+#[cfg(feature = "staging")]
+impl TryFrom<AbuseEventRow> for AbuseEvent {
+    type Error = strum::ParseError;
+
+    fn try_from(row: AbuseEventRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            user_id: row.user_id,
+            conversation_id: row.conversation_id,
+            kind: row.kind.parse()?,
+            action: row.action.parse()?,
+            detail: row.detail,
+            expires_at: parse_dt(&row.expires_at),
+            created_at: parse_dt(&row.created_at),
+        })
+    }
+}
+
+#[cfg(feature = "staging")]
+const FILTER_SQL: &str = "(?1 IS NULL OR user_id = ?1)
+       AND (?2 IS NULL OR conversation_id = ?2)
+       AND (?3 IS NULL OR kind = ?3)";
+
+#[cfg(feature = "staging")]
+impl AbuseEventRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn create(&self, event: &AbuseEvent) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO abuse_events
+             (id, user_id, conversation_id, kind, action, detail, expires_at, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&event.id)
+        .bind(&event.user_id)
+        .bind(&event.conversation_id)
+        .bind(event.kind.as_ref())
+        .bind(event.action.as_ref())
+        .bind(&event.detail)
+        .bind(event.expires_at.format("%Y-%m-%d %H:%M:%S").to_string())
+        .bind(event.created_at.format("%Y-%m-%d %H:%M:%S").to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Matching events, newest first. Rows with a kind this build doesn't know are skipped.
+    pub async fn list(
+        &self,
+        filter: &AbuseEventFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AbuseEvent>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, AbuseEventRow>(&format!(
+            "SELECT {SELECT_COLS} FROM abuse_events WHERE {FILTER_SQL}
+             ORDER BY created_at DESC, id DESC LIMIT ?4 OFFSET ?5"
+        ))
+        .bind(&filter.user_id)
+        .bind(&filter.conversation_id)
+        .bind(filter.kind())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| AbuseEvent::try_from(row).ok())
+            .collect())
+    }
+
+    pub async fn count(&self, filter: &AbuseEventFilter) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM abuse_events WHERE {FILTER_SQL}"
+        ))
+        .bind(&filter.user_id)
+        .bind(&filter.conversation_id)
+        .bind(filter.kind())
+        .fetch_one(&self.pool)
+        .await
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct AbuseEventRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgAbuseEventRow {
+    id: String,
+    user_id: String,
+    conversation_id: String,
+    kind: String,
+    action: String,
+    detail: Option<String>,
+    expires_at: NaiveDateTime,
+    created_at: NaiveDateTime,
+}
+
+#[cfg(not(feature = "staging"))]
+impl TryFrom<PgAbuseEventRow> for AbuseEvent {
+    type Error = strum::ParseError;
+
+    fn try_from(row: PgAbuseEventRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            user_id: row.user_id,
+            conversation_id: row.conversation_id,
+            kind: row.kind.parse()?,
+            action: row.action.parse()?,
+            detail: row.detail,
+            expires_at: row.expires_at,
+            created_at: row.created_at,
+        })
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+const FILTER_SQL: &str = "($1::text IS NULL OR user_id = $1)
+       AND ($2::text IS NULL OR conversation_id = $2)
+       AND ($3::text IS NULL OR kind = $3)";
+
+#[cfg(not(feature = "staging"))]
+impl AbuseEventRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn create(&self, event: &AbuseEvent) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO abuse_events
+             (id, user_id, conversation_id, kind, action, detail, expires_at, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(&event.id)
+        .bind(&event.user_id)
+        .bind(&event.conversation_id)
+        .bind(event.kind.as_ref())
+        .bind(event.action.as_ref())
+        .bind(&event.detail)
+        .bind(event.expires_at)
+        .bind(event.created_at)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Matching events, newest first. Rows with a kind this build doesn't know are skipped.
+    pub async fn list(
+        &self,
+        filter: &AbuseEventFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AbuseEvent>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PgAbuseEventRow>(&format!(
+            "SELECT {SELECT_COLS} FROM abuse_events WHERE {FILTER_SQL}
+             ORDER BY created_at DESC, id DESC LIMIT $4 OFFSET $5"
+        ))
+        .bind(&filter.user_id)
+        .bind(&filter.conversation_id)
+        .bind(filter.kind())
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| AbuseEvent::try_from(row).ok())
+            .collect())
+    }
+
+    pub async fn count(&self, filter: &AbuseEventFilter) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM abuse_events WHERE {FILTER_SQL}"
+        ))
+        .bind(&filter.user_id)
+        .bind(&filter.conversation_id)
+        .bind(filter.kind())
+        .fetch_one(&self.pg_pool)
+        .await
+    }
+}
@@ -0,0 +1,225 @@
+use chrono::NaiveDateTime;
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+
+use crate::models::entities::AiSample;
+
+const SELECT_COLS: &str = "id, influencer_id, conversation_id, provider, model, system_prompt,
+     history, user_message, response, error, latency_ms, created_at";
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct AiSampleRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct AiSampleRow {
+    id: String,
+    influencer_id: String,
+    conversation_id: String,
+    provider: String,
+    model: String,
+    system_prompt: String,
+    history: String,
+    user_message: String,
+    response: Option<String>,
+    error: Option<String>,
+    latency_ms: i64,
+    created_at: String,
+}
+
+#[cfg(feature = "staging")]
+impl From<AiSampleRow> for AiSample {
+    fn from(row: AiSampleRow) -> Self {
+        Self {
+            id: row.id,
+            influencer_id: row.influencer_id,
+            conversation_id: row.conversation_id,
+            provider: row.provider,
+            model: row.model,
+            system_prompt: row.system_prompt,
+            history: serde_json::from_str(&row.history).unwrap_or_default(),
+            user_message: row.user_message,
+            response: row.response,
+            error: row.error,
+            latency_ms: row.latency_ms,
+            created_at: parse_dt(&row.created_at),
+        }
+    }
+}
+
+#[cfg(feature = "staging")]
+impl AiSampleRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn create(&self, sample: &AiSample) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO ai_samples
+             (id, influencer_id, conversation_id, provider, model, system_prompt, history,
+              user_message, response, error, latency_ms, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&sample.id)
+        .bind(&sample.influencer_id)
+        .bind(&sample.conversation_id)
+        .bind(&sample.provider)
+        .bind(&sample.model)
+        .bind(&sample.system_prompt)
+        .bind(sample.history.to_string())
+        .bind(&sample.user_message)
+        .bind(&sample.response)
+        .bind(&sample.error)
+        .bind(sample.latency_ms)
+        .bind(sample.created_at.format("%Y-%m-%d %H:%M:%S").to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Drop samples recorded before `cutoff`; returns how many were removed.
+    pub async fn prune_before(&self, cutoff: NaiveDateTime) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM ai_samples WHERE created_at < ?")
+            .bind(cutoff.format("%Y-%m-%d %H:%M:%S").to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// The influencer's latest samples, newest first.
+    pub async fn list_by_influencer(
+        &self,
+        influencer_id: &str,
+        limit: i64,
+    ) -> Result<Vec<AiSample>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {SELECT_COLS} FROM ai_samples
+             WHERE influencer_id = ? ORDER BY created_at DESC LIMIT ?"
+        );
+        let rows = sqlx::query_as::<_, AiSampleRow>(&sql)
+            .bind(influencer_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(AiSample::from).collect())
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct AiSampleRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgAiSampleRow {
+    id: String,
+    influencer_id: String,
+    conversation_id: String,
+    provider: String,
+    model: String,
+    system_prompt: String,
+    history: serde_json::Value,
+    user_message: String,
+    response: Option<String>,
+    error: Option<String>,
+    latency_ms: i64,
+    created_at: NaiveDateTime,
+}
+
+#[cfg(not(feature = "staging"))]
+impl From<PgAiSampleRow> for AiSample {
+    fn from(row: PgAiSampleRow) -> Self {
+        Self {
+            id: row.id,
+            influencer_id: row.influencer_id,
+            conversation_id: row.conversation_id,
+            provider: row.provider,
+            model: row.model,
+            system_prompt: row.system_prompt,
+            history: row.history,
+            user_message: row.user_message,
+            response: row.response,
+            error: row.error,
+            latency_ms: row.latency_ms,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+impl AiSampleRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn create(&self, sample: &AiSample) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO ai_samples
+             (id, influencer_id, conversation_id, provider, model, system_prompt, history,
+              user_message, response, error, latency_ms, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        )
+        .bind(&sample.id)
+        .bind(&sample.influencer_id)
+        .bind(&sample.conversation_id)
+        .bind(&sample.provider)
+        .bind(&sample.model)
+        .bind(&sample.system_prompt)
+        .bind(&sample.history)
+        .bind(&sample.user_message)
+        .bind(&sample.response)
+        .bind(&sample.error)
+        .bind(sample.latency_ms)
+        .bind(sample.created_at)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Drop samples recorded before `cutoff`; returns how many were removed.
+    pub async fn prune_before(&self, cutoff: NaiveDateTime) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM ai_samples WHERE created_at < $1")
+            .bind(cutoff)
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// The influencer's latest samples, newest first.
+    pub async fn list_by_influencer(
+        &self,
+        influencer_id: &str,
+        limit: i64,
+    ) -> Result<Vec<AiSample>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {SELECT_COLS} FROM ai_samples
+             WHERE influencer_id = $1 ORDER BY created_at DESC LIMIT $2"
+        );
+        sqlx::query_as::<_, PgAiSampleRow>(&sql)
+            .bind(influencer_id)
+            .bind(limit)
+            .fetch_all(&self.pg_pool)
+            .await
+            .map(|rows| rows.into_iter().map(AiSample::from).collect())
+    }
+}
@@ -0,0 +1,86 @@
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct AudioUploadRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+impl AudioUploadRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(
+        &self,
+        storage_key: &str,
+        user_id: &str,
+        duration_seconds: i32,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO audio_uploads (storage_key, user_id, duration_seconds)
+             VALUES (?, ?, ?)
+             ON CONFLICT (storage_key) DO UPDATE SET duration_seconds = excluded.duration_seconds",
+        )
+        .bind(storage_key)
+        .bind(user_id)
+        .bind(duration_seconds)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Duration measured at upload time, if the clip went through `media/upload`.
+    pub async fn verified_duration(&self, storage_key: &str) -> Result<Option<i32>, sqlx::Error> {
+        sqlx::query_scalar("SELECT duration_seconds FROM audio_uploads WHERE storage_key = ?")
+            .bind(storage_key)
+            .fetch_optional(&self.pool)
+            .await
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct AudioUploadRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+impl AudioUploadRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    pub async fn record(
+        &self,
+        storage_key: &str,
+        user_id: &str,
+        duration_seconds: i32,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO audio_uploads (storage_key, user_id, duration_seconds)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (storage_key) DO UPDATE SET duration_seconds = EXCLUDED.duration_seconds",
+        )
+        .bind(storage_key)
+        .bind(user_id)
+        .bind(duration_seconds)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Duration measured at upload time, if the clip went through `media/upload`.
+    pub async fn verified_duration(&self, storage_key: &str) -> Result<Option<i32>, sqlx::Error> {
+        sqlx::query_scalar("SELECT duration_seconds FROM audio_uploads WHERE storage_key = $1")
+            .bind(storage_key)
+            .fetch_optional(&self.pg_pool)
+            .await
+    }
+}
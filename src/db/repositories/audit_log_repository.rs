@@ -0,0 +1,277 @@
+use chrono::NaiveDateTime;
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+
+use crate::models::entities::{AuditAction, AuditEntry};
+
+/// Optional filters for listing the audit log; unset fields match everything.
+#[derive(Debug, Default)]
+pub struct AuditLogFilter {
+    pub actor: Option<String>,
+    pub action: Option<AuditAction>,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    /// Inclusive lower bound on `created_at`
+    pub since: Option<NaiveDateTime>,
+    /// Exclusive upper bound on `created_at`
+    pub until: Option<NaiveDateTime>,
+}
+
+impl AuditLogFilter {
+    fn action(&self) -> Option<&str> {
+        self.action.as_ref().map(AsRef::as_ref)
+    }
+}
+
+const SELECT_COLS: &str =
+    "id, actor, action, target_type, target_id, before_state, after_state, request_id, created_at";
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct AuditLogRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct AuditEntryRow {
+    id: String,
+    actor: String,
+    action: String,
+    target_type: String,
+    target_id: Option<String>,
+    before_state: Option<String>,
+    after_state: Option<String>,
+    request_id: Option<String>,
+    created_at: String,
+}
+
+#[cfg(feature = "staging")]
+impl TryFrom<AuditEntryRow> for AuditEntry {
+    type Error = strum::ParseError;
+
+    fn try_from(row: AuditEntryRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            actor: row.actor,
+            action: row.action.parse()?,
+            target_type: row.target_type,
+            target_id: row.target_id,
+            before: row.before_state.and_then(|s| serde_json::from_str(&s).ok()),
+            after: row.after_state.and_then(|s| serde_json::from_str(&s).ok()),
+            request_id: row.request_id,
+            created_at: parse_dt(&row.created_at),
+        })
+    }
+}
+
+#[cfg(feature = "staging")]
+const FILTER_SQL: &str = "(?1 IS NULL OR actor = ?1)
+       AND (?2 IS NULL OR action = ?2)
+       AND (?3 IS NULL OR target_type = ?3)
+       AND (?4 IS NULL OR target_id = ?4)
+       AND (?5 IS NULL OR created_at >= ?5)
+       AND (?6 IS NULL OR created_at < ?6)";
+
+#[cfg(feature = "staging")]
+fn format_dt(dt: Option<NaiveDateTime>) -> Option<String> {
+    dt.map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+#[cfg(feature = "staging")]
+impl AuditLogRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn create(&self, entry: &AuditEntry) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO audit_log
+             (id, actor, action, target_type, target_id, before_state, after_state, request_id, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&entry.id)
+        .bind(&entry.actor)
+        .bind(entry.action.as_ref())
+        .bind(&entry.target_type)
+        .bind(&entry.target_id)
+        .bind(entry.before.as_ref().map(|v| v.to_string()))
+        .bind(entry.after.as_ref().map(|v| v.to_string()))
+        .bind(&entry.request_id)
+        .bind(entry.created_at.format("%Y-%m-%d %H:%M:%S").to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Matching entries, newest first. Rows with an action this build doesn't know are skipped.
+    pub async fn list(
+        &self,
+        filter: &AuditLogFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AuditEntry>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, AuditEntryRow>(&format!(
+            "SELECT {SELECT_COLS} FROM audit_log WHERE {FILTER_SQL}
+             ORDER BY created_at DESC, id DESC LIMIT ?7 OFFSET ?8"
+        ))
+        .bind(&filter.actor)
+        .bind(filter.action())
+        .bind(&filter.target_type)
+        .bind(&filter.target_id)
+        .bind(format_dt(filter.since))
+        .bind(format_dt(filter.until))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| AuditEntry::try_from(row).ok())
+            .collect())
+    }
+
+    pub async fn count(&self, filter: &AuditLogFilter) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM audit_log WHERE {FILTER_SQL}"
+        ))
+        .bind(&filter.actor)
+        .bind(filter.action())
+        .bind(&filter.target_type)
+        .bind(&filter.target_id)
+        .bind(format_dt(filter.since))
+        .bind(format_dt(filter.until))
+        .fetch_one(&self.pool)
+        .await
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct AuditLogRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgAuditEntryRow {
+    id: String,
+    actor: String,
+    action: String,
+    target_type: String,
+    target_id: Option<String>,
+    before_state: Option<serde_json::Value>,
+    after_state: Option<serde_json::Value>,
+    request_id: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+#[cfg(not(feature = "staging"))]
+impl TryFrom<PgAuditEntryRow> for AuditEntry {
+    type Error = strum::ParseError;
+
+    fn try_from(row: PgAuditEntryRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            actor: row.actor,
+            action: row.action.parse()?,
+            target_type: row.target_type,
+            target_id: row.target_id,
+            before: row.before_state,
+            after: row.after_state,
+            request_id: row.request_id,
+            created_at: row.created_at,
+        })
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+const FILTER_SQL: &str = "($1::text IS NULL OR actor = $1)
+       AND ($2::text IS NULL OR action = $2)
+       AND ($3::text IS NULL OR target_type = $3)
+       AND ($4::text IS NULL OR target_id = $4)
+       AND ($5::timestamp IS NULL OR created_at >= $5)
+       AND ($6::timestamp IS NULL OR created_at < $6)";
+
+#[cfg(not(feature = "staging"))]
+impl AuditLogRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn create(&self, entry: &AuditEntry) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO audit_log
+             (id, actor, action, target_type, target_id, before_state, after_state, request_id, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(&entry.id)
+        .bind(&entry.actor)
+        .bind(entry.action.as_ref())
+        .bind(&entry.target_type)
+        .bind(&entry.target_id)
+        .bind(&entry.before)
+        .bind(&entry.after)
+        .bind(&entry.request_id)
+        .bind(entry.created_at)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Matching entries, newest first. Rows with an action this build doesn't know are skipped.
+    pub async fn list(
+        &self,
+        filter: &AuditLogFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AuditEntry>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PgAuditEntryRow>(&format!(
+            "SELECT {SELECT_COLS} FROM audit_log WHERE {FILTER_SQL}
+             ORDER BY created_at DESC, id DESC LIMIT $7 OFFSET $8"
+        ))
+        .bind(&filter.actor)
+        .bind(filter.action())
+        .bind(&filter.target_type)
+        .bind(&filter.target_id)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| AuditEntry::try_from(row).ok())
+            .collect())
+    }
+
+    pub async fn count(&self, filter: &AuditLogFilter) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM audit_log WHERE {FILTER_SQL}"
+        ))
+        .bind(&filter.actor)
+        .bind(filter.action())
+        .bind(&filter.target_type)
+        .bind(&filter.target_id)
+        .bind(filter.since)
+        .bind(filter.until)
+        .fetch_one(&self.pg_pool)
+        .await
+    }
+}
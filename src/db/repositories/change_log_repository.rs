@@ -0,0 +1,179 @@
+use chrono::NaiveDateTime;
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+
+use crate::models::entities::ChangeLogEntry;
+
+/// `change_log` is written only by the triggers on `conversations` and `messages`
+/// (migration `change_log`), so this repository just reads and prunes it.
+const SELECT_COLS: &str = "seq, entity_type, entity_id, op, conversation_id, created_at";
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct ChangeLogRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct ChangeLogRow {
+    seq: i64,
+    entity_type: String,
+    entity_id: String,
+    op: String,
+    conversation_id: String,
+    created_at: String,
+}
+
+#[cfg(feature = "staging")]
+impl TryFrom<ChangeLogRow> for ChangeLogEntry {
+    type Error = strum::ParseError;
+
+    fn try_from(row: ChangeLogRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            seq: row.seq,
+            entity_type: row.entity_type.parse()?,
+            entity_id: row.entity_id,
+            op: row.op.parse()?,
+            conversation_id: row.conversation_id,
+            created_at: parse_dt(&row.created_at),
+        })
+    }
+}
+
+#[cfg(feature = "staging")]
+impl ChangeLogRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    /// Drop entries recorded before `cutoff`; returns how many were removed.
+    pub async fn prune_before(&self, cutoff: NaiveDateTime) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM change_log WHERE created_at < ?")
+            .bind(cutoff.format("%Y-%m-%d %H:%M:%S").to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Entries after `after_seq`, oldest first. Rows this build can't parse are skipped.
+    pub async fn list_since(
+        &self,
+        after_seq: i64,
+        limit: i64,
+    ) -> Result<Vec<ChangeLogEntry>, sqlx::Error> {
+        let sql =
+            format!("SELECT {SELECT_COLS} FROM change_log WHERE seq > ? ORDER BY seq LIMIT ?");
+        let rows = sqlx::query_as::<_, ChangeLogRow>(&sql)
+            .bind(after_seq)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|r| ChangeLogEntry::try_from(r).ok())
+            .collect())
+    }
+
+    /// Highest recorded seq, or 0 when the log is empty.
+    pub async fn latest_seq(&self) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT COALESCE(MAX(seq), 0) FROM change_log")
+            .fetch_one(&self.pool)
+            .await
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct ChangeLogRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgChangeLogRow {
+    seq: i64,
+    entity_type: String,
+    entity_id: String,
+    op: String,
+    conversation_id: String,
+    created_at: NaiveDateTime,
+}
+
+#[cfg(not(feature = "staging"))]
+impl TryFrom<PgChangeLogRow> for ChangeLogEntry {
+    type Error = strum::ParseError;
+
+    fn try_from(row: PgChangeLogRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            seq: row.seq,
+            entity_type: row.entity_type.parse()?,
+            entity_id: row.entity_id,
+            op: row.op.parse()?,
+            conversation_id: row.conversation_id,
+            created_at: row.created_at,
+        })
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+impl ChangeLogRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    /// Drop entries recorded before `cutoff`; returns how many were removed.
+    pub async fn prune_before(&self, cutoff: NaiveDateTime) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM change_log WHERE created_at < $1")
+            .bind(cutoff)
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Entries after `after_seq`, oldest first. Rows this build can't parse are skipped.
+    ///
+    /// Sequence numbers are taken when a row is written but become visible when its
+    /// transaction commits, so a slow transaction can surface a lower seq after a
+    /// reader has moved past it. Readers that can't miss a change should re-read
+    /// a short overlap behind their cursor.
+    pub async fn list_since(
+        &self,
+        after_seq: i64,
+        limit: i64,
+    ) -> Result<Vec<ChangeLogEntry>, sqlx::Error> {
+        let sql =
+            format!("SELECT {SELECT_COLS} FROM change_log WHERE seq > $1 ORDER BY seq LIMIT $2");
+        let rows = sqlx::query_as::<_, PgChangeLogRow>(&sql)
+            .bind(after_seq)
+            .bind(limit)
+            .fetch_all(&self.pg_pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|r| ChangeLogEntry::try_from(r).ok())
+            .collect())
+    }
+
+    /// Highest recorded seq, or 0 when the log is empty.
+    pub async fn latest_seq(&self) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT COALESCE(MAX(seq), 0)::BIGINT FROM change_log")
+            .fetch_one(&self.pg_pool)
+            .await
+    }
+}
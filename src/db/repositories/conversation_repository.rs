@@ -5,13 +5,27 @@ use sqlx::SqlitePool;
 
 use uuid::Uuid;
 
+#[cfg(feature = "staging")]
+use super::message_repository::MessageRow;
+#[cfg(not(feature = "staging"))]
+use super::message_repository::PgMessageRow;
 #[cfg(feature = "staging")]
 use super::{parse_dt, parse_json};
 
 use crate::models::entities::{
-    AIInfluencer, Conversation, InfluencerStatus, LastMessageInfo, MessageRole,
+    AIInfluencer, Conversation, ConversationSort, InfluencerStatus, LastMessageInfo, Message,
+    MessageRole,
 };
 
+/// Narrows and orders a user's conversation list.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct UserConversationFilter<'a> {
+    pub influencer_id: Option<&'a str>,
+    /// Only conversations with replies the user hasn't read
+    pub unread_only: bool,
+    pub sort: ConversationSort,
+}
+
 // ── Staging: SQLite-only ──────────────────────────────────────────────────────
 
 #[cfg(feature = "staging")]
@@ -33,12 +47,154 @@ struct ConversationRow {
     display_name: String,
     avatar_url: Option<String>,
     suggested_messages: String,
+    inf_metadata: String,
+    inf_tenant: String,
+    inf_is_verified: i32,
+    inf_is_official: i32,
     #[sqlx(default)]
     message_count: Option<i64>,
     #[sqlx(default)]
     unread_count: Option<i64>,
 }
 
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct ConversationListRow {
+    #[sqlx(flatten)]
+    conversation: ConversationRow,
+    last_content: Option<String>,
+    last_role: Option<String>,
+    last_created_at: Option<String>,
+    last_status: Option<String>,
+    last_is_read: Option<i32>,
+}
+
+/// `list_by_user` pages the conversations first, then reads counts and the latest
+/// message for just that page with one window pass over `messages`. The filters and
+/// `LIMIT`/`OFFSET` go between the two halves; `count_by_user` reuses the `FROM`.
+/// The user id is `?1` throughout and the influencers' tenant `?2`.
+#[cfg(feature = "staging")]
+const LIST_BY_USER_COLUMNS: &str = "WITH page AS (
+     SELECT c.id, c.user_id, c.influencer_id, c.created_at, c.updated_at, c.metadata,
+            i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages,
+            i.metadata as inf_metadata, i.tenant as inf_tenant,
+            i.is_verified as inf_is_verified, i.is_official as inf_is_official";
+
+#[cfg(feature = "staging")]
+const LIST_BY_USER_FROM: &str = "FROM conversations c
+     JOIN ai_influencers i ON c.influencer_id = i.id
+     WHERE (c.user_id = ?1 OR c.id IN (SELECT conversation_id FROM conversation_participants WHERE user_id = ?1))
+     AND i.tenant = ?2 AND i.is_active != 'discontinued'
+     AND c.user_id NOT IN (SELECT id FROM ai_influencers)";
+
+/// Whether `c` has replies the user hasn't read. The creator's read state is on the
+/// messages; invited participants read up to their own `last_read_at`.
+#[cfg(feature = "staging")]
+const HAS_UNREAD_FOR_USER: &str = "EXISTS (
+     SELECT 1 FROM messages mu
+     WHERE mu.conversation_id = c.id AND mu.role = 'assistant'
+     AND CASE WHEN c.user_id = ?1 THEN mu.is_read = 0
+         ELSE mu.created_at > (SELECT COALESCE(pu.last_read_at, pu.joined_at)
+                               FROM conversation_participants pu
+                               WHERE pu.conversation_id = c.id AND pu.user_id = ?1)
+     END)";
+
+/// `WHERE` conditions for `filter` beyond the user match; the influencer id binds as `?3`.
+#[cfg(feature = "staging")]
+fn user_filter_sql(filter: &UserConversationFilter<'_>) -> String {
+    let mut sql = String::new();
+    if filter.influencer_id.is_some() {
+        sql.push_str(" AND c.influencer_id = ?3");
+    }
+    if filter.unread_only {
+        sql.push_str(&format!(" AND {HAS_UNREAD_FOR_USER}"));
+    }
+    sql
+}
+
+#[cfg(feature = "staging")]
+const LIST_BY_USER_TAIL: &str = ", ranked AS (
+     SELECT m.conversation_id, m.content, m.role, m.created_at, m.status, m.is_read,
+            ROW_NUMBER() OVER (PARTITION BY m.conversation_id ORDER BY m.created_at DESC) as rn,
+            COUNT(*) OVER (PARTITION BY m.conversation_id) as message_count,
+            SUM(CASE WHEN m.role = 'assistant' AND m.is_read = 0 THEN 1 ELSE 0 END)
+                OVER (PARTITION BY m.conversation_id) as unread_count
+     FROM messages m
+     WHERE m.conversation_id IN (SELECT id FROM page)
+ )
+ SELECT p.*, COALESCE(r.message_count, 0) as message_count, COALESCE(r.unread_count, 0) as unread_count,
+        r.content as last_content, r.role as last_role, r.created_at as last_created_at,
+        r.status as last_status, r.is_read as last_is_read
+ FROM page p
+ LEFT JOIN ranked r ON r.conversation_id = p.id AND r.rn = 1";
+
+/// Wraps a one-conversation `list_by_user` page as `conv` and joins its latest
+/// messages, so `resume` reads everything in one round trip. The message limit
+/// binds as ?3; message columns are prefixed so they don't clash with the conversation's.
+#[cfg(feature = "staging")]
+const RESUME_MESSAGES: &str = ", recent AS (
+     SELECT m.*, ROW_NUMBER() OVER (ORDER BY m.created_at DESC) as recent_rn
+     FROM messages m
+     WHERE m.conversation_id IN (SELECT id FROM conv)
+ )
+ SELECT conv.*, rm.id as msg_id, rm.role as msg_role, rm.content as msg_content,
+        rm.message_type as msg_message_type, rm.media_urls as msg_media_urls,
+        rm.audio_url as msg_audio_url, rm.audio_duration_seconds as msg_audio_duration_seconds,
+        rm.token_count as msg_token_count, rm.client_message_id as msg_client_message_id,
+        rm.created_at as msg_created_at, rm.metadata as msg_metadata,
+        rm.status as msg_status, rm.is_read as msg_is_read
+ FROM conv
+ LEFT JOIN recent rm ON rm.recent_rn <= ?3
+ ORDER BY rm.created_at ASC";
+
+/// A `conv` row repeated once per recent message; the message is absent when the
+/// conversation has none.
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct ResumeRow {
+    #[sqlx(flatten)]
+    conversation: ConversationListRow,
+    msg_id: Option<String>,
+    msg_role: Option<String>,
+    msg_content: Option<String>,
+    msg_message_type: Option<String>,
+    msg_media_urls: Option<String>,
+    msg_audio_url: Option<String>,
+    msg_audio_duration_seconds: Option<i32>,
+    msg_token_count: Option<i32>,
+    msg_client_message_id: Option<String>,
+    msg_created_at: Option<String>,
+    msg_metadata: Option<String>,
+    msg_status: Option<String>,
+    msg_is_read: Option<i32>,
+}
+
+#[cfg(feature = "staging")]
+impl ResumeRow {
+    fn split(self) -> (ConversationListRow, Option<MessageRow>) {
+        let message = match (self.msg_id, self.msg_role, self.msg_created_at) {
+            (Some(id), Some(role), Some(created_at)) => Some(MessageRow {
+                id,
+                conversation_id: self.conversation.conversation.id.clone(),
+                role,
+                content: self.msg_content,
+                message_type: self.msg_message_type.unwrap_or_default(),
+                media_urls: self.msg_media_urls.unwrap_or_default(),
+                audio_url: self.msg_audio_url,
+                audio_duration_seconds: self.msg_audio_duration_seconds,
+                token_count: self.msg_token_count,
+                client_message_id: self.msg_client_message_id,
+                created_at,
+                metadata: self.msg_metadata.unwrap_or_default(),
+                status: self.msg_status,
+                is_read: self.msg_is_read,
+            }),
+            _ => None,
+        };
+        (self.conversation, message)
+    }
+}
+
 #[cfg(feature = "staging")]
 #[derive(sqlx::FromRow)]
 struct LastMessageRow {
@@ -105,13 +261,17 @@ impl From<ConversationRow> for Conversation {
             suggested_messages,
             is_active: InfluencerStatus::Active,
             is_nsfw: false,
+            is_verified: row.inf_is_verified != 0,
+            is_official: row.inf_is_official != 0,
             parent_principal_id: None,
             source: None,
+            tenant: row.inf_tenant,
             created_at,
             updated_at,
-            metadata: serde_json::Value::Object(Default::default()),
+            metadata: parse_json(&row.inf_metadata),
             conversation_count: None,
             message_count: None,
+            view_count: 0,
         };
 
         Self {
@@ -143,6 +303,24 @@ impl From<LastMessageRow> for LastMessageInfo {
     }
 }
 
+#[cfg(feature = "staging")]
+impl From<ConversationListRow> for Conversation {
+    fn from(row: ConversationListRow) -> Self {
+        let mut conversation = Conversation::from(row.conversation);
+        if let (Some(role), Some(created_at)) = (row.last_role, row.last_created_at) {
+            conversation.last_message = Some(LastMessageInfo::from(LastMessageRow {
+                conversation_id: conversation.id.clone(),
+                content: row.last_content,
+                role,
+                created_at,
+                status: row.last_status,
+                is_read: row.last_is_read.unwrap_or(0),
+            }));
+        }
+        conversation
+    }
+}
+
 #[cfg(feature = "staging")]
 impl ConversationRepository {
     pub fn new(pool: SqlitePool) -> Self {
@@ -158,28 +336,96 @@ impl ConversationRepository {
     ) -> Result<Conversation, sqlx::Error> {
         let conversation_id = Uuid::new_v4().to_string();
 
-        sqlx::query("INSERT INTO conversations (id, user_id, influencer_id) VALUES (?, ?, ?)")
-            .bind(&conversation_id)
-            .bind(user_id)
-            .bind(influencer_id)
-            .execute(&self.pool)
+        sqlx::query!(
+            "INSERT INTO conversations (id, user_id, influencer_id) VALUES (?, ?, ?)",
+            conversation_id,
+            user_id,
+            influencer_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.get_by_id(&conversation_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Create a duet: one user talking to several influencers that take turns.
+    /// The first influencer is stored on the conversation row as usual.
+    pub async fn create_duet(
+        &self,
+        user_id: &str,
+        influencer_ids: &[String],
+        metadata: &serde_json::Value,
+    ) -> Result<Conversation, sqlx::Error> {
+        let conversation_id = Uuid::new_v4().to_string();
+        let metadata_json = serde_json::to_string(metadata).unwrap_or("{}".to_string());
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            "INSERT INTO conversations (id, user_id, influencer_id, kind, metadata)
+             VALUES (?, ?, ?, 'duet', ?)",
+            conversation_id,
+            user_id,
+            influencer_ids[0],
+            metadata_json
+        )
+        .execute(&mut *tx)
+        .await?;
+        for (position, influencer_id) in influencer_ids.iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                "INSERT INTO conversation_influencers (conversation_id, influencer_id, position)
+                 VALUES (?, ?, ?)",
+                conversation_id,
+                influencer_id,
+                position
+            )
+            .execute(&mut *tx)
             .await?;
+        }
+        tx.commit().await?;
 
         self.get_by_id(&conversation_id)
             .await?
             .ok_or(sqlx::Error::RowNotFound)
     }
 
-    pub async fn update_metadata(
+    /// Set one top-level metadata key, leaving the rest of the object untouched.
+    /// Bumps `updated_at` so clients syncing by it see the change.
+    pub async fn set_metadata_key(
         &self,
         conversation_id: &str,
-        metadata: &serde_json::Value,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), sqlx::Error> {
+        let value_json = serde_json::to_string(value).unwrap_or("null".to_string());
+        sqlx::query(
+            "UPDATE conversations
+             SET metadata = json_set(COALESCE(metadata, '{}'), '$.' || ?, json(?)),
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?",
+        )
+        .bind(key)
+        .bind(&value_json)
+        .bind(conversation_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_metadata_key(
+        &self,
+        conversation_id: &str,
+        key: &str,
     ) -> Result<(), sqlx::Error> {
-        let metadata_json = serde_json::to_string(metadata).unwrap_or("{}".to_string());
         sqlx::query(
-            "UPDATE conversations SET metadata = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            "UPDATE conversations
+             SET metadata = json_remove(COALESCE(metadata, '{}'), '$.' || ?),
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?",
         )
-        .bind(&metadata_json)
+        .bind(key)
         .bind(conversation_id)
         .execute(&self.pool)
         .await?;
@@ -187,8 +433,7 @@ impl ConversationRepository {
     }
 
     pub async fn delete(&self, conversation_id: &str) -> Result<(), sqlx::Error> {
-        sqlx::query("DELETE FROM conversations WHERE id = ?")
-            .bind(conversation_id)
+        sqlx::query!("DELETE FROM conversations WHERE id = ?", conversation_id)
             .execute(&self.pool)
             .await?;
         Ok(())
@@ -202,7 +447,9 @@ impl ConversationRepository {
     ) -> Result<Option<Conversation>, sqlx::Error> {
         let row = sqlx::query_as::<_, ConversationRow>(
             "SELECT c.id, c.user_id, c.influencer_id, c.created_at, c.updated_at, c.metadata,
-                    i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages
+                    i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages,
+                    i.metadata as inf_metadata, i.tenant as inf_tenant,
+                    i.is_verified as inf_is_verified, i.is_official as inf_is_official
              FROM conversations c
              JOIN ai_influencers i ON c.influencer_id = i.id
              WHERE c.id = ?",
@@ -220,10 +467,12 @@ impl ConversationRepository {
     ) -> Result<Option<Conversation>, sqlx::Error> {
         let row = sqlx::query_as::<_, ConversationRow>(
             "SELECT c.id, c.user_id, c.influencer_id, c.created_at, c.updated_at, c.metadata,
-                    i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages
+                    i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages,
+                    i.metadata as inf_metadata, i.tenant as inf_tenant,
+                    i.is_verified as inf_is_verified, i.is_official as inf_is_official
              FROM conversations c
              JOIN ai_influencers i ON c.influencer_id = i.id
-             WHERE c.user_id = ? AND c.influencer_id = ?",
+             WHERE c.user_id = ? AND c.influencer_id = ? AND c.kind = 'direct'",
         )
         .bind(user_id)
         .bind(influencer_id)
@@ -232,91 +481,100 @@ impl ConversationRepository {
         Ok(row.map(Conversation::from))
     }
 
+    /// Influencers taking part in a duet, in speaking order. Empty for direct conversations.
+    pub async fn list_influencer_ids(
+        &self,
+        conversation_id: &str,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            "SELECT influencer_id FROM conversation_influencers
+             WHERE conversation_id = ? ORDER BY position ASC",
+            conversation_id
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
     pub async fn list_by_user(
         &self,
         user_id: &str,
-        influencer_id: Option<&str>,
+        tenant: &str,
+        filter: UserConversationFilter<'_>,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<Conversation>, sqlx::Error> {
-        let mut conversations: Vec<Conversation> = if let Some(inf_id) = influencer_id {
-            sqlx::query_as::<_, ConversationRow>(
-                "SELECT c.id, c.user_id, c.influencer_id, c.created_at, c.updated_at, c.metadata,
-                        i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages,
-                        COUNT(m.id) as message_count,
-                        (SELECT COUNT(*) FROM messages m2 WHERE m2.conversation_id = c.id AND m2.is_read = 0 AND m2.role = 'assistant') as unread_count
-                 FROM conversations c
-                 JOIN ai_influencers i ON c.influencer_id = i.id
-                 LEFT JOIN messages m ON c.id = m.conversation_id
-                 WHERE c.user_id = ? AND c.influencer_id = ? AND i.is_active != 'discontinued'
-                 AND c.user_id NOT IN (SELECT id FROM ai_influencers)
-                 GROUP BY c.id, i.id ORDER BY c.updated_at DESC LIMIT ? OFFSET ?",
-            )
-            .bind(user_id)
-            .bind(inf_id)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(&self.pool)
-            .await?
-            .into_iter()
-            .map(Conversation::from)
-            .collect()
+        let (page_order, outer_order) = match filter.sort {
+            ConversationSort::Recent => ("c.updated_at DESC", "p.updated_at DESC"),
+            ConversationSort::UnreadFirst => (
+                "has_unread DESC, c.updated_at DESC",
+                "p.has_unread DESC, p.updated_at DESC",
+            ),
+        };
+        // Anonymous `?` would bind from the first argument again after the numbered ones
+        let page = if filter.influencer_id.is_some() {
+            "LIMIT ?4 OFFSET ?5"
         } else {
-            sqlx::query_as::<_, ConversationRow>(
-                "SELECT c.id, c.user_id, c.influencer_id, c.created_at, c.updated_at, c.metadata,
-                        i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages,
-                        COUNT(m.id) as message_count,
-                        (SELECT COUNT(*) FROM messages m2 WHERE m2.conversation_id = c.id AND m2.is_read = 0 AND m2.role = 'assistant') as unread_count
-                 FROM conversations c
-                 JOIN ai_influencers i ON c.influencer_id = i.id
-                 LEFT JOIN messages m ON c.id = m.conversation_id
-                 WHERE c.user_id = ? AND i.is_active != 'discontinued'
-                 AND c.user_id NOT IN (SELECT id FROM ai_influencers)
-                 GROUP BY c.id, i.id ORDER BY c.updated_at DESC LIMIT ? OFFSET ?",
-            )
-            .bind(user_id)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(&self.pool)
-            .await?
-            .into_iter()
-            .map(Conversation::from)
-            .collect()
+            "LIMIT ?3 OFFSET ?4"
         };
-
-        if !conversations.is_empty() {
-            let conv_ids: Vec<String> = conversations.iter().map(|c| c.id.clone()).collect();
-            let last_messages = self.get_last_messages_batch(&conv_ids).await?;
-            for conv in &mut conversations {
-                conv.last_message = last_messages.get(&conv.id).cloned();
-            }
+        let sql = format!(
+            "{LIST_BY_USER_COLUMNS}, {HAS_UNREAD_FOR_USER} as has_unread {LIST_BY_USER_FROM} {}
+             ORDER BY {page_order} {page}) {LIST_BY_USER_TAIL} ORDER BY {outer_order}",
+            user_filter_sql(&filter)
+        );
+        let mut query = sqlx::query_as::<_, ConversationListRow>(&sql)
+            .bind(user_id)
+            .bind(tenant);
+        if let Some(inf_id) = filter.influencer_id {
+            query = query.bind(inf_id);
         }
-        Ok(conversations)
+        let rows = query.bind(limit).bind(offset).fetch_all(&self.pool).await?;
+        Ok(rows.into_iter().map(Conversation::from).collect())
     }
 
     pub async fn count_by_user(
         &self,
         user_id: &str,
-        influencer_id: Option<&str>,
+        tenant: &str,
+        filter: UserConversationFilter<'_>,
     ) -> Result<i64, sqlx::Error> {
-        if let Some(inf_id) = influencer_id {
-            let count: (i64,) = sqlx::query_as(
-                "SELECT COUNT(*) FROM conversations c JOIN ai_influencers i ON c.influencer_id = i.id WHERE c.user_id = ? AND c.influencer_id = ? AND i.is_active != 'discontinued' AND c.user_id NOT IN (SELECT id FROM ai_influencers)",
-            )
-            .bind(user_id)
-            .bind(inf_id)
-            .fetch_one(&self.pool)
-            .await?;
-            Ok(count.0)
-        } else {
-            let count: (i64,) = sqlx::query_as(
-                "SELECT COUNT(*) FROM conversations c JOIN ai_influencers i ON c.influencer_id = i.id WHERE c.user_id = ? AND i.is_active != 'discontinued' AND c.user_id NOT IN (SELECT id FROM ai_influencers)",
-            )
+        let sql = format!(
+            "SELECT COUNT(*) {LIST_BY_USER_FROM} {}",
+            user_filter_sql(&filter)
+        );
+        let mut query = sqlx::query_scalar(&sql).bind(user_id).bind(tenant);
+        if let Some(inf_id) = filter.influencer_id {
+            query = query.bind(inf_id);
+        }
+        query.fetch_one(&self.pool).await
+    }
+
+    /// The user's most recently active conversation in `tenant` with its latest `message_limit`
+    /// messages, oldest first.
+    pub async fn resume(
+        &self,
+        user_id: &str,
+        tenant: &str,
+        message_limit: i64,
+    ) -> Result<Option<(Conversation, Vec<Message>)>, sqlx::Error> {
+        let sql = format!(
+            "WITH conv AS ({LIST_BY_USER_COLUMNS} {LIST_BY_USER_FROM}
+             ORDER BY c.updated_at DESC LIMIT 1) {LIST_BY_USER_TAIL}) {RESUME_MESSAGES}"
+        );
+        let rows = sqlx::query_as::<_, ResumeRow>(&sql)
             .bind(user_id)
-            .fetch_one(&self.pool)
+            .bind(tenant)
+            .bind(message_limit)
+            .fetch_all(&self.pool)
             .await?;
-            Ok(count.0)
+
+        let mut conversation = None;
+        let mut messages = Vec::with_capacity(rows.len());
+        for row in rows {
+            let (conv, message) = row.split();
+            messages.extend(message.map(Message::from));
+            conversation.get_or_insert_with(|| Conversation::from(conv));
         }
+        Ok(conversation.map(|c| (c, messages)))
     }
 
     pub async fn list_by_influencer(
@@ -353,12 +611,12 @@ impl ConversationRepository {
     }
 
     pub async fn count_by_influencer(&self, influencer_id: &str) -> Result<i64, sqlx::Error> {
-        let count: (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM conversations WHERE influencer_id = ?")
-                .bind(influencer_id)
-                .fetch_one(&self.pool)
-                .await?;
-        Ok(count.0)
+        sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM conversations WHERE influencer_id = ?",
+            influencer_id
+        )
+        .fetch_one(&self.pool)
+        .await
     }
 
     async fn get_last_messages_batch(
@@ -414,12 +672,147 @@ struct PgConversationRow {
     display_name: String,
     avatar_url: Option<String>,
     suggested_messages: serde_json::Value,
+    inf_metadata: serde_json::Value,
+    inf_tenant: String,
+    inf_is_verified: bool,
+    inf_is_official: bool,
     #[sqlx(default)]
     message_count: Option<i64>,
     #[sqlx(default)]
     unread_count: Option<i64>,
 }
 
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgConversationListRow {
+    #[sqlx(flatten)]
+    conversation: PgConversationRow,
+    last_content: Option<String>,
+    last_role: Option<String>,
+    last_created_at: Option<chrono::NaiveDateTime>,
+    last_status: Option<String>,
+    last_is_read: Option<bool>,
+}
+
+#[cfg(not(feature = "staging"))]
+const LIST_BY_USER_COLUMNS: &str = "WITH page AS (
+     SELECT c.id, c.user_id, c.influencer_id, c.created_at, c.updated_at, c.metadata,
+            i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages,
+            i.metadata as inf_metadata, i.tenant as inf_tenant,
+            i.is_verified as inf_is_verified, i.is_official as inf_is_official";
+
+#[cfg(not(feature = "staging"))]
+const LIST_BY_USER_FROM: &str = "FROM conversations c
+     JOIN ai_influencers i ON c.influencer_id = i.id
+     WHERE (c.user_id = $1 OR c.id IN (SELECT conversation_id FROM conversation_participants WHERE user_id = $1))
+     AND i.tenant = $2 AND i.is_active != 'discontinued'
+     AND c.user_id NOT IN (SELECT id FROM ai_influencers)";
+
+#[cfg(not(feature = "staging"))]
+const HAS_UNREAD_FOR_USER: &str = "EXISTS (
+     SELECT 1 FROM messages mu
+     WHERE mu.conversation_id = c.id AND mu.role = 'assistant'
+     AND CASE WHEN c.user_id = $1 THEN mu.is_read = FALSE
+         ELSE mu.created_at > (SELECT COALESCE(pu.last_read_at, pu.joined_at)
+                               FROM conversation_participants pu
+                               WHERE pu.conversation_id = c.id AND pu.user_id = $1)
+     END)";
+
+#[cfg(not(feature = "staging"))]
+fn user_filter_sql(filter: &UserConversationFilter<'_>) -> String {
+    let mut sql = String::new();
+    if filter.influencer_id.is_some() {
+        sql.push_str(" AND c.influencer_id = $3");
+    }
+    if filter.unread_only {
+        sql.push_str(&format!(" AND {HAS_UNREAD_FOR_USER}"));
+    }
+    sql
+}
+
+#[cfg(not(feature = "staging"))]
+const LIST_BY_USER_TAIL: &str = ", ranked AS (
+     SELECT m.conversation_id, m.content, m.role, m.created_at, m.status, m.is_read,
+            ROW_NUMBER() OVER (PARTITION BY m.conversation_id ORDER BY m.created_at DESC) as rn,
+            COUNT(*) OVER (PARTITION BY m.conversation_id) as message_count,
+            SUM(CASE WHEN m.role = 'assistant' AND m.is_read = FALSE THEN 1 ELSE 0 END)
+                OVER (PARTITION BY m.conversation_id) as unread_count
+     FROM messages m
+     WHERE m.conversation_id IN (SELECT id FROM page)
+ )
+ SELECT p.*, COALESCE(r.message_count, 0) as message_count, COALESCE(r.unread_count, 0) as unread_count,
+        r.content as last_content, r.role as last_role, r.created_at as last_created_at,
+        r.status as last_status, r.is_read as last_is_read
+ FROM page p
+ LEFT JOIN ranked r ON r.conversation_id = p.id AND r.rn = 1";
+
+/// Wraps a one-conversation `list_by_user` page as `conv` and joins its latest
+/// messages, so `resume` reads everything in one round trip. The message limit
+/// binds as $3; message columns are prefixed so they don't clash with the conversation's.
+#[cfg(not(feature = "staging"))]
+const RESUME_MESSAGES: &str = ", recent AS (
+     SELECT m.*, ROW_NUMBER() OVER (ORDER BY m.created_at DESC) as recent_rn
+     FROM messages m
+     WHERE m.conversation_id IN (SELECT id FROM conv)
+ )
+ SELECT conv.*, rm.id as msg_id, rm.role as msg_role, rm.content as msg_content,
+        rm.message_type as msg_message_type, rm.media_urls as msg_media_urls,
+        rm.audio_url as msg_audio_url, rm.audio_duration_seconds as msg_audio_duration_seconds,
+        rm.token_count as msg_token_count, rm.client_message_id as msg_client_message_id,
+        rm.created_at as msg_created_at, rm.metadata as msg_metadata,
+        rm.status as msg_status, rm.is_read as msg_is_read
+ FROM conv
+ LEFT JOIN recent rm ON rm.recent_rn <= $3
+ ORDER BY rm.created_at ASC";
+
+/// A `conv` row repeated once per recent message; the message is absent when the
+/// conversation has none.
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgResumeRow {
+    #[sqlx(flatten)]
+    conversation: PgConversationListRow,
+    msg_id: Option<String>,
+    msg_role: Option<String>,
+    msg_content: Option<String>,
+    msg_message_type: Option<String>,
+    msg_media_urls: Option<serde_json::Value>,
+    msg_audio_url: Option<String>,
+    msg_audio_duration_seconds: Option<i32>,
+    msg_token_count: Option<i32>,
+    msg_client_message_id: Option<String>,
+    msg_created_at: Option<chrono::NaiveDateTime>,
+    msg_metadata: Option<serde_json::Value>,
+    msg_status: Option<String>,
+    msg_is_read: Option<bool>,
+}
+
+#[cfg(not(feature = "staging"))]
+impl PgResumeRow {
+    fn split(self) -> (PgConversationListRow, Option<PgMessageRow>) {
+        let message = match (self.msg_id, self.msg_role, self.msg_created_at) {
+            (Some(id), Some(role), Some(created_at)) => Some(PgMessageRow {
+                id,
+                conversation_id: self.conversation.conversation.id.clone(),
+                role,
+                content: self.msg_content,
+                message_type: self.msg_message_type.unwrap_or_default(),
+                media_urls: self.msg_media_urls.unwrap_or_default(),
+                audio_url: self.msg_audio_url,
+                audio_duration_seconds: self.msg_audio_duration_seconds,
+                token_count: self.msg_token_count,
+                client_message_id: self.msg_client_message_id,
+                created_at,
+                metadata: self.msg_metadata.unwrap_or_default(),
+                status: self.msg_status,
+                is_read: self.msg_is_read,
+            }),
+            _ => None,
+        };
+        (self.conversation, message)
+    }
+}
+
 #[cfg(not(feature = "staging"))]
 #[derive(sqlx::FromRow)]
 struct PgLastMessageRow {
@@ -486,13 +879,17 @@ impl From<PgConversationRow> for Conversation {
             suggested_messages,
             is_active: InfluencerStatus::Active,
             is_nsfw: false,
+            is_verified: row.inf_is_verified,
+            is_official: row.inf_is_official,
             parent_principal_id: None,
             source: None,
+            tenant: row.inf_tenant,
             created_at,
             updated_at,
-            metadata: serde_json::Value::Object(Default::default()),
+            metadata: row.inf_metadata,
             conversation_count: None,
             message_count: None,
+            view_count: 0,
         };
 
         Self {
@@ -524,6 +921,24 @@ impl From<PgLastMessageRow> for LastMessageInfo {
     }
 }
 
+#[cfg(not(feature = "staging"))]
+impl From<PgConversationListRow> for Conversation {
+    fn from(row: PgConversationListRow) -> Self {
+        let mut conversation = Conversation::from(row.conversation);
+        if let (Some(role), Some(created_at)) = (row.last_role, row.last_created_at) {
+            conversation.last_message = Some(LastMessageInfo::from(PgLastMessageRow {
+                conversation_id: conversation.id.clone(),
+                content: row.last_content,
+                role,
+                created_at,
+                status: row.last_status,
+                is_read: row.last_is_read.unwrap_or(false),
+            }));
+        }
+        conversation
+    }
+}
+
 #[cfg(not(feature = "staging"))]
 impl ConversationRepository {
     pub fn new(pg_pool: PgPool) -> Self {
@@ -539,34 +954,100 @@ impl ConversationRepository {
     ) -> Result<Conversation, sqlx::Error> {
         let conversation_id = Uuid::new_v4().to_string();
 
-        sqlx::query("INSERT INTO conversations (id, user_id, influencer_id) VALUES ($1, $2, $3)")
-            .bind(&conversation_id)
-            .bind(user_id)
-            .bind(influencer_id)
-            .execute(&self.pg_pool)
+        sqlx::query!(
+            "INSERT INTO conversations (id, user_id, influencer_id) VALUES ($1, $2, $3)",
+            conversation_id,
+            user_id,
+            influencer_id
+        )
+        .execute(&self.pg_pool)
+        .await?;
+
+        self.get_by_id(&conversation_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    /// Create a duet: one user talking to several influencers that take turns.
+    /// The first influencer is stored on the conversation row as usual.
+    pub async fn create_duet(
+        &self,
+        user_id: &str,
+        influencer_ids: &[String],
+        metadata: &serde_json::Value,
+    ) -> Result<Conversation, sqlx::Error> {
+        let conversation_id = Uuid::new_v4().to_string();
+
+        let mut tx = self.pg_pool.begin().await?;
+        sqlx::query!(
+            "INSERT INTO conversations (id, user_id, influencer_id, kind, metadata)
+             VALUES ($1, $2, $3, 'duet', $4)",
+            conversation_id,
+            user_id,
+            influencer_ids[0],
+            metadata
+        )
+        .execute(&mut *tx)
+        .await?;
+        for (position, influencer_id) in influencer_ids.iter().enumerate() {
+            let position = position as i32;
+            sqlx::query!(
+                "INSERT INTO conversation_influencers (conversation_id, influencer_id, position)
+                 VALUES ($1, $2, $3)",
+                conversation_id,
+                influencer_id,
+                position
+            )
+            .execute(&mut *tx)
             .await?;
+        }
+        tx.commit().await?;
 
         self.get_by_id(&conversation_id)
             .await?
             .ok_or(sqlx::Error::RowNotFound)
     }
 
-    pub async fn update_metadata(
+    /// Set one top-level metadata key, leaving the rest of the object untouched.
+    /// Bumps `updated_at` so clients syncing by it see the change.
+    pub async fn set_metadata_key(
         &self,
         conversation_id: &str,
-        metadata: &serde_json::Value,
+        key: &str,
+        value: &serde_json::Value,
     ) -> Result<(), sqlx::Error> {
-        sqlx::query("UPDATE conversations SET metadata = $1, updated_at = NOW() WHERE id = $2")
-            .bind(metadata)
-            .bind(conversation_id)
-            .execute(&self.pg_pool)
-            .await?;
+        sqlx::query(
+            "UPDATE conversations
+             SET metadata = jsonb_set(COALESCE(metadata, '{}'::jsonb), ARRAY[$1], $2),
+                 updated_at = NOW()
+             WHERE id = $3",
+        )
+        .bind(key)
+        .bind(value)
+        .bind(conversation_id)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_metadata_key(
+        &self,
+        conversation_id: &str,
+        key: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE conversations SET metadata = COALESCE(metadata, '{}'::jsonb) - $1, updated_at = NOW()
+             WHERE id = $2",
+        )
+        .bind(key)
+        .bind(conversation_id)
+        .execute(&self.pg_pool)
+        .await?;
         Ok(())
     }
 
     pub async fn delete(&self, conversation_id: &str) -> Result<(), sqlx::Error> {
-        sqlx::query("DELETE FROM conversations WHERE id = $1")
-            .bind(conversation_id)
+        sqlx::query!("DELETE FROM conversations WHERE id = $1", conversation_id)
             .execute(&self.pg_pool)
             .await?;
         Ok(())
@@ -580,7 +1061,9 @@ impl ConversationRepository {
     ) -> Result<Option<Conversation>, sqlx::Error> {
         let row = sqlx::query_as::<_, PgConversationRow>(
             "SELECT c.id, c.user_id, c.influencer_id, c.created_at, c.updated_at, c.metadata,
-                    i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages
+                    i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages,
+                    i.metadata as inf_metadata, i.tenant as inf_tenant,
+                    i.is_verified as inf_is_verified, i.is_official as inf_is_official
              FROM conversations c
              JOIN ai_influencers i ON c.influencer_id = i.id
              WHERE c.id = $1",
@@ -598,10 +1081,12 @@ impl ConversationRepository {
     ) -> Result<Option<Conversation>, sqlx::Error> {
         let row = sqlx::query_as::<_, PgConversationRow>(
             "SELECT c.id, c.user_id, c.influencer_id, c.created_at, c.updated_at, c.metadata,
-                    i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages
+                    i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages,
+                    i.metadata as inf_metadata, i.tenant as inf_tenant,
+                    i.is_verified as inf_is_verified, i.is_official as inf_is_official
              FROM conversations c
              JOIN ai_influencers i ON c.influencer_id = i.id
-             WHERE c.user_id = $1 AND c.influencer_id = $2",
+             WHERE c.user_id = $1 AND c.influencer_id = $2 AND c.kind = 'direct'",
         )
         .bind(user_id)
         .bind(influencer_id)
@@ -610,91 +1095,103 @@ impl ConversationRepository {
         Ok(row.map(Conversation::from))
     }
 
+    /// Influencers taking part in a duet, in speaking order. Empty for direct conversations.
+    pub async fn list_influencer_ids(
+        &self,
+        conversation_id: &str,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar!(
+            "SELECT influencer_id FROM conversation_influencers
+             WHERE conversation_id = $1 ORDER BY position ASC",
+            conversation_id
+        )
+        .fetch_all(&self.pg_pool)
+        .await
+    }
+
     pub async fn list_by_user(
         &self,
         user_id: &str,
-        influencer_id: Option<&str>,
+        tenant: &str,
+        filter: UserConversationFilter<'_>,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<Conversation>, sqlx::Error> {
-        let mut conversations: Vec<Conversation> = if let Some(inf_id) = influencer_id {
-            sqlx::query_as::<_, PgConversationRow>(
-                "SELECT c.id, c.user_id, c.influencer_id, c.created_at, c.updated_at, c.metadata,
-                        i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages,
-                        COUNT(m.id) as message_count,
-                        (SELECT COUNT(*) FROM messages m2 WHERE m2.conversation_id = c.id AND m2.is_read = FALSE AND m2.role = 'assistant') as unread_count
-                 FROM conversations c
-                 JOIN ai_influencers i ON c.influencer_id = i.id
-                 LEFT JOIN messages m ON c.id = m.conversation_id
-                 WHERE c.user_id = $1 AND c.influencer_id = $2 AND i.is_active != 'discontinued'
-                 AND c.user_id NOT IN (SELECT id FROM ai_influencers)
-                 GROUP BY c.id, i.id ORDER BY c.updated_at DESC LIMIT $3 OFFSET $4",
-            )
-            .bind(user_id)
-            .bind(inf_id)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(&self.pg_pool)
-            .await?
-            .into_iter()
-            .map(Conversation::from)
-            .collect()
+        let (page_order, outer_order) = match filter.sort {
+            ConversationSort::Recent => ("c.updated_at DESC", "p.updated_at DESC"),
+            ConversationSort::UnreadFirst => (
+                "has_unread DESC, c.updated_at DESC",
+                "p.has_unread DESC, p.updated_at DESC",
+            ),
+        };
+        let page = if filter.influencer_id.is_some() {
+            "LIMIT $4 OFFSET $5"
         } else {
-            sqlx::query_as::<_, PgConversationRow>(
-                "SELECT c.id, c.user_id, c.influencer_id, c.created_at, c.updated_at, c.metadata,
-                        i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages,
-                        COUNT(m.id) as message_count,
-                        (SELECT COUNT(*) FROM messages m2 WHERE m2.conversation_id = c.id AND m2.is_read = FALSE AND m2.role = 'assistant') as unread_count
-                 FROM conversations c
-                 JOIN ai_influencers i ON c.influencer_id = i.id
-                 LEFT JOIN messages m ON c.id = m.conversation_id
-                 WHERE c.user_id = $1 AND i.is_active != 'discontinued'
-                 AND c.user_id NOT IN (SELECT id FROM ai_influencers)
-                 GROUP BY c.id, i.id ORDER BY c.updated_at DESC LIMIT $2 OFFSET $3",
-            )
+            "LIMIT $3 OFFSET $4"
+        };
+        let sql = format!(
+            "{LIST_BY_USER_COLUMNS}, {HAS_UNREAD_FOR_USER} as has_unread {LIST_BY_USER_FROM} {}
+             ORDER BY {page_order} {page}) {LIST_BY_USER_TAIL} ORDER BY {outer_order}",
+            user_filter_sql(&filter)
+        );
+        let mut query = sqlx::query_as::<_, PgConversationListRow>(&sql)
             .bind(user_id)
+            .bind(tenant);
+        if let Some(inf_id) = filter.influencer_id {
+            query = query.bind(inf_id);
+        }
+        let rows = query
             .bind(limit)
             .bind(offset)
             .fetch_all(&self.pg_pool)
-            .await?
-            .into_iter()
-            .map(Conversation::from)
-            .collect()
-        };
-
-        if !conversations.is_empty() {
-            let conv_ids: Vec<String> = conversations.iter().map(|c| c.id.clone()).collect();
-            let last_messages = self.get_last_messages_batch(&conv_ids).await?;
-            for conv in &mut conversations {
-                conv.last_message = last_messages.get(&conv.id).cloned();
-            }
-        }
-        Ok(conversations)
+            .await?;
+        Ok(rows.into_iter().map(Conversation::from).collect())
     }
 
     pub async fn count_by_user(
         &self,
         user_id: &str,
-        influencer_id: Option<&str>,
+        tenant: &str,
+        filter: UserConversationFilter<'_>,
     ) -> Result<i64, sqlx::Error> {
-        if let Some(inf_id) = influencer_id {
-            let count: (i64,) = sqlx::query_as(
-                "SELECT COUNT(*) FROM conversations c JOIN ai_influencers i ON c.influencer_id = i.id WHERE c.user_id = $1 AND c.influencer_id = $2 AND i.is_active != 'discontinued' AND c.user_id NOT IN (SELECT id FROM ai_influencers)",
-            )
-            .bind(user_id)
-            .bind(inf_id)
-            .fetch_one(&self.pg_pool)
-            .await?;
-            Ok(count.0)
-        } else {
-            let count: (i64,) = sqlx::query_as(
-                "SELECT COUNT(*) FROM conversations c JOIN ai_influencers i ON c.influencer_id = i.id WHERE c.user_id = $1 AND i.is_active != 'discontinued' AND c.user_id NOT IN (SELECT id FROM ai_influencers)",
-            )
+        let sql = format!(
+            "SELECT COUNT(*) {LIST_BY_USER_FROM} {}",
+            user_filter_sql(&filter)
+        );
+        let mut query = sqlx::query_scalar(&sql).bind(user_id).bind(tenant);
+        if let Some(inf_id) = filter.influencer_id {
+            query = query.bind(inf_id);
+        }
+        query.fetch_one(&self.pg_pool).await
+    }
+
+    /// The user's most recently active conversation in `tenant` with its latest `message_limit`
+    /// messages, oldest first.
+    pub async fn resume(
+        &self,
+        user_id: &str,
+        tenant: &str,
+        message_limit: i64,
+    ) -> Result<Option<(Conversation, Vec<Message>)>, sqlx::Error> {
+        let sql = format!(
+            "WITH conv AS ({LIST_BY_USER_COLUMNS} {LIST_BY_USER_FROM}
+             ORDER BY c.updated_at DESC LIMIT 1) {LIST_BY_USER_TAIL}) {RESUME_MESSAGES}"
+        );
+        let rows = sqlx::query_as::<_, PgResumeRow>(&sql)
             .bind(user_id)
-            .fetch_one(&self.pg_pool)
+            .bind(tenant)
+            .bind(message_limit)
+            .fetch_all(&self.pg_pool)
             .await?;
-            Ok(count.0)
+
+        let mut conversation = None;
+        let mut messages = Vec::with_capacity(rows.len());
+        for row in rows {
+            let (conv, message) = row.split();
+            messages.extend(message.map(Message::from));
+            conversation.get_or_insert_with(|| Conversation::from(conv));
         }
+        Ok(conversation.map(|c| (c, messages)))
     }
 
     pub async fn list_by_influencer(
@@ -731,12 +1228,12 @@ impl ConversationRepository {
     }
 
     pub async fn count_by_influencer(&self, influencer_id: &str) -> Result<i64, sqlx::Error> {
-        let count: (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM conversations WHERE influencer_id = $1")
-                .bind(influencer_id)
-                .fetch_one(&self.pg_pool)
-                .await?;
-        Ok(count.0)
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM conversations WHERE influencer_id = $1"#,
+            influencer_id
+        )
+        .fetch_one(&self.pg_pool)
+        .await
     }
 
     async fn get_last_messages_batch(
@@ -767,3 +1264,159 @@ impl ConversationRepository {
         Ok(result)
     }
 }
+
+/// `list_by_user` against the per-row subquery plus batch last-message lookup it
+/// replaced. Ignored by default; run with
+/// `cargo test --release --features staging list_by_user_benchmark -- --ignored --nocapture`.
+#[cfg(all(test, feature = "staging"))]
+mod bench {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::test_support::server::TestServer;
+
+    const CONVERSATIONS: usize = 500;
+    const MESSAGES_PER_CONVERSATION: usize = 40;
+    const RUNS: u32 = 20;
+
+    impl ConversationRepository {
+        async fn list_by_user_subquery(
+            &self,
+            user_id: &str,
+            tenant: &str,
+            limit: i64,
+            offset: i64,
+        ) -> Result<Vec<Conversation>, sqlx::Error> {
+            let mut conversations: Vec<Conversation> = sqlx::query_as::<_, ConversationRow>(
+                "SELECT c.id, c.user_id, c.influencer_id, c.created_at, c.updated_at, c.metadata,
+                        i.id as inf_id, i.name, i.display_name, i.avatar_url, i.suggested_messages,
+                        i.metadata as inf_metadata, i.tenant as inf_tenant,
+                        i.is_verified as inf_is_verified, i.is_official as inf_is_official,
+                        COUNT(m.id) as message_count,
+                        (SELECT COUNT(*) FROM messages m2 WHERE m2.conversation_id = c.id AND m2.is_read = 0 AND m2.role = 'assistant') as unread_count
+                 FROM conversations c
+                 JOIN ai_influencers i ON c.influencer_id = i.id
+                 LEFT JOIN messages m ON c.id = m.conversation_id
+                 WHERE c.user_id = ?1 AND i.tenant = ?2 AND i.is_active != 'discontinued'
+                 AND c.user_id NOT IN (SELECT id FROM ai_influencers)
+                 GROUP BY c.id, i.id ORDER BY c.updated_at DESC LIMIT ?3 OFFSET ?4",
+            )
+            .bind(user_id)
+            .bind(tenant)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(Conversation::from)
+            .collect();
+
+            let conv_ids: Vec<String> = conversations.iter().map(|c| c.id.clone()).collect();
+            let last_messages = self.get_last_messages_batch(&conv_ids).await?;
+            for conv in &mut conversations {
+                conv.last_message = last_messages.get(&conv.id).cloned();
+            }
+            Ok(conversations)
+        }
+    }
+
+    async fn seed(pool: &SqlitePool, user_id: &str) {
+        let mut tx = pool.begin().await.unwrap();
+        for c in 0..CONVERSATIONS {
+            let influencer_id = format!("bench-bot-{c}");
+            sqlx::query(
+                "INSERT INTO ai_influencers (id, name, display_name, system_instructions)
+                 VALUES (?1, ?1, ?1, 'Benchmark bot')",
+            )
+            .bind(&influencer_id)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+            let conversation_id = format!("bench-conv-{c}");
+            sqlx::query(
+                "INSERT INTO conversations (id, user_id, influencer_id, updated_at)
+                 VALUES (?1, ?2, ?3, datetime('now', ?4))",
+            )
+            .bind(&conversation_id)
+            .bind(user_id)
+            .bind(&influencer_id)
+            .bind(format!("-{c} minutes"))
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+            for m in 0..MESSAGES_PER_CONVERSATION {
+                sqlx::query(
+                    "INSERT INTO messages (id, conversation_id, role, content, message_type, is_read, created_at)
+                     VALUES (?1, ?2, ?3, 'benchmark message', 'text', ?4, datetime('now', ?5))",
+                )
+                .bind(format!("{conversation_id}-{m}"))
+                .bind(&conversation_id)
+                .bind(if m % 2 == 0 { "user" } else { "assistant" })
+                .bind(m + 4 < MESSAGES_PER_CONVERSATION)
+                .bind(format!("-{} seconds", MESSAGES_PER_CONVERSATION - m))
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+            }
+        }
+        tx.commit().await.unwrap();
+    }
+
+    async fn time<F, Fut>(mut run: F) -> Duration
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Vec<Conversation>>,
+    {
+        // Warm the statement cache and pages first
+        run().await;
+        let started = Instant::now();
+        for _ in 0..RUNS {
+            std::hint::black_box(run().await);
+        }
+        started.elapsed() / RUNS
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "benchmark"]
+    async fn list_by_user_benchmark() {
+        let server = TestServer::start().await;
+        let user_id = "bench-user";
+        seed(&server.state.db.pool, user_id).await;
+        let repo = server.state.db.conv_repo();
+        let filter = || UserConversationFilter {
+            influencer_id: None,
+            unread_only: false,
+            sort: ConversationSort::Recent,
+        };
+
+        for limit in [20, 100] {
+            let new = repo
+                .list_by_user(user_id, "yral", filter(), limit, 0)
+                .await
+                .unwrap();
+            let old = repo
+                .list_by_user_subquery(user_id, "yral", limit, 0)
+                .await
+                .unwrap();
+            let ids = |list: &[Conversation]| list.iter().map(|c| c.id.clone()).collect::<Vec<_>>();
+            assert_eq!(ids(&new), ids(&old));
+
+            let window = time(|| async {
+                repo.list_by_user(user_id, "yral", filter(), limit, 0)
+                    .await
+                    .unwrap()
+            })
+            .await;
+            let subquery = time(|| async {
+                repo.list_by_user_subquery(user_id, "yral", limit, 0)
+                    .await
+                    .unwrap()
+            })
+            .await;
+            println!(
+                "{CONVERSATIONS} conversations x {MESSAGES_PER_CONVERSATION} messages, page of {limit}: \
+                 window query {window:?}, subquery + batch {subquery:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,329 @@
+use chrono::NaiveDateTime;
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+
+use crate::models::entities::{DigestFrequency, DigestSubscription, UnreadActivity};
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct DigestRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct DigestSubscriptionRow {
+    user_id: String,
+    frequency: String,
+    last_sent_at: Option<String>,
+    created_at: String,
+}
+
+#[cfg(feature = "staging")]
+impl From<DigestSubscriptionRow> for DigestSubscription {
+    fn from(row: DigestSubscriptionRow) -> Self {
+        Self {
+            user_id: row.user_id,
+            frequency: row.frequency.parse().unwrap_or(DigestFrequency::Daily),
+            last_sent_at: row.last_sent_at.as_deref().map(parse_dt),
+            created_at: parse_dt(&row.created_at),
+        }
+    }
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct UnreadActivityRow {
+    conversation_id: String,
+    influencer_id: String,
+    influencer_name: String,
+    unread_count: i64,
+    latest_content: Option<String>,
+}
+
+#[cfg(feature = "staging")]
+impl From<UnreadActivityRow> for UnreadActivity {
+    fn from(row: UnreadActivityRow) -> Self {
+        Self {
+            conversation_id: row.conversation_id,
+            influencer_id: row.influencer_id,
+            influencer_name: row.influencer_name,
+            unread_count: row.unread_count,
+            latest_content: row.latest_content,
+        }
+    }
+}
+
+#[cfg(feature = "staging")]
+const SELECT_COLS: &str = "user_id, frequency, last_sent_at, created_at";
+
+#[cfg(feature = "staging")]
+impl DigestRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn upsert(
+        &self,
+        user_id: &str,
+        frequency: &DigestFrequency,
+    ) -> Result<DigestSubscription, sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO digest_subscriptions (user_id, frequency) VALUES (?, ?)
+             ON CONFLICT (user_id) DO UPDATE
+             SET frequency = excluded.frequency, updated_at = datetime('now')",
+        )
+        .bind(user_id)
+        .bind(frequency.as_ref())
+        .execute(&self.pool)
+        .await?;
+
+        self.get(user_id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn remove(&self, user_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM digest_subscriptions WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn mark_sent(&self, user_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE digest_subscriptions SET last_sent_at = datetime('now') WHERE user_id = ?",
+        )
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(&self, user_id: &str) -> Result<Option<DigestSubscription>, sqlx::Error> {
+        let row = sqlx::query_as::<_, DigestSubscriptionRow>(&format!(
+            "SELECT {SELECT_COLS} FROM digest_subscriptions WHERE user_id = ?"
+        ))
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(DigestSubscription::from))
+    }
+
+    /// Subscriptions whose period has elapsed since the last digest (or since opting in).
+    pub async fn list_due(&self, limit: i64) -> Result<Vec<DigestSubscription>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, DigestSubscriptionRow>(&format!(
+            "SELECT {SELECT_COLS} FROM digest_subscriptions
+             WHERE (frequency = 'daily'
+                    AND COALESCE(last_sent_at, created_at) <= datetime('now', '-1 day'))
+                OR (frequency = 'weekly'
+                    AND COALESCE(last_sent_at, created_at) <= datetime('now', '-7 days'))
+             ORDER BY COALESCE(last_sent_at, created_at) ASC
+             LIMIT ?"
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(DigestSubscription::from).collect())
+    }
+
+    /// Conversations owned by `user_id` with unread assistant replies received after
+    /// `since` (the last digest), most recent first.
+    pub async fn unread_activity(
+        &self,
+        user_id: &str,
+        since: Option<NaiveDateTime>,
+        limit: i64,
+    ) -> Result<Vec<UnreadActivity>, sqlx::Error> {
+        let since = since.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string());
+        let rows = sqlx::query_as::<_, UnreadActivityRow>(
+            "SELECT c.id AS conversation_id, c.influencer_id, i.display_name AS influencer_name,
+                    COUNT(m.id) AS unread_count,
+                    (SELECT lm.content FROM messages lm
+                     WHERE lm.conversation_id = c.id AND lm.role = 'assistant' AND lm.is_read = 0
+                     ORDER BY lm.created_at DESC LIMIT 1) AS latest_content
+             FROM conversations c
+             JOIN ai_influencers i ON i.id = c.influencer_id
+             JOIN messages m ON m.conversation_id = c.id AND m.role = 'assistant' AND m.is_read = 0
+                 AND (?2 IS NULL OR julianday(m.created_at) > julianday(?2))
+             WHERE c.user_id = ?1
+             GROUP BY c.id, c.influencer_id, i.display_name
+             ORDER BY MAX(m.created_at) DESC
+             LIMIT ?3",
+        )
+        .bind(user_id)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(UnreadActivity::from).collect())
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct DigestRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgDigestSubscriptionRow {
+    user_id: String,
+    frequency: String,
+    last_sent_at: Option<NaiveDateTime>,
+    created_at: NaiveDateTime,
+}
+
+#[cfg(not(feature = "staging"))]
+impl From<PgDigestSubscriptionRow> for DigestSubscription {
+    fn from(row: PgDigestSubscriptionRow) -> Self {
+        Self {
+            user_id: row.user_id,
+            frequency: row.frequency.parse().unwrap_or(DigestFrequency::Daily),
+            last_sent_at: row.last_sent_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgUnreadActivityRow {
+    conversation_id: String,
+    influencer_id: String,
+    influencer_name: String,
+    unread_count: i64,
+    latest_content: Option<String>,
+}
+
+#[cfg(not(feature = "staging"))]
+impl From<PgUnreadActivityRow> for UnreadActivity {
+    fn from(row: PgUnreadActivityRow) -> Self {
+        Self {
+            conversation_id: row.conversation_id,
+            influencer_id: row.influencer_id,
+            influencer_name: row.influencer_name,
+            unread_count: row.unread_count,
+            latest_content: row.latest_content,
+        }
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+const SELECT_COLS: &str = "user_id, frequency, last_sent_at, created_at";
+
+#[cfg(not(feature = "staging"))]
+impl DigestRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn upsert(
+        &self,
+        user_id: &str,
+        frequency: &DigestFrequency,
+    ) -> Result<DigestSubscription, sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO digest_subscriptions (user_id, frequency) VALUES ($1, $2)
+             ON CONFLICT (user_id) DO UPDATE
+             SET frequency = EXCLUDED.frequency, updated_at = NOW()",
+        )
+        .bind(user_id)
+        .bind(frequency.as_ref())
+        .execute(&self.pg_pool)
+        .await?;
+
+        self.get(user_id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn remove(&self, user_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM digest_subscriptions WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn mark_sent(&self, user_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE digest_subscriptions SET last_sent_at = NOW() WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(&self, user_id: &str) -> Result<Option<DigestSubscription>, sqlx::Error> {
+        let row = sqlx::query_as::<_, PgDigestSubscriptionRow>(&format!(
+            "SELECT {SELECT_COLS} FROM digest_subscriptions WHERE user_id = $1"
+        ))
+        .bind(user_id)
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Ok(row.map(DigestSubscription::from))
+    }
+
+    /// Subscriptions whose period has elapsed since the last digest (or since opting in).
+    pub async fn list_due(&self, limit: i64) -> Result<Vec<DigestSubscription>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PgDigestSubscriptionRow>(&format!(
+            "SELECT {SELECT_COLS} FROM digest_subscriptions
+             WHERE (frequency = 'daily'
+                    AND COALESCE(last_sent_at, created_at) <= NOW() - INTERVAL '1 day')
+                OR (frequency = 'weekly'
+                    AND COALESCE(last_sent_at, created_at) <= NOW() - INTERVAL '7 days')
+             ORDER BY COALESCE(last_sent_at, created_at) ASC
+             LIMIT $1"
+        ))
+        .bind(limit)
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows.into_iter().map(DigestSubscription::from).collect())
+    }
+
+    /// Conversations owned by `user_id` with unread assistant replies received after
+    /// `since` (the last digest), most recent first.
+    pub async fn unread_activity(
+        &self,
+        user_id: &str,
+        since: Option<NaiveDateTime>,
+        limit: i64,
+    ) -> Result<Vec<UnreadActivity>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PgUnreadActivityRow>(
+            "SELECT c.id AS conversation_id, c.influencer_id, i.display_name AS influencer_name,
+                    COUNT(m.id) AS unread_count,
+                    (SELECT lm.content FROM messages lm
+                     WHERE lm.conversation_id = c.id AND lm.role = 'assistant'
+                       AND lm.is_read = FALSE
+                     ORDER BY lm.created_at DESC LIMIT 1) AS latest_content
+             FROM conversations c
+             JOIN ai_influencers i ON i.id = c.influencer_id
+             JOIN messages m ON m.conversation_id = c.id AND m.role = 'assistant'
+                 AND m.is_read = FALSE
+                 AND ($2::timestamp IS NULL OR m.created_at > $2)
+             WHERE c.user_id = $1
+             GROUP BY c.id, c.influencer_id, i.display_name
+             ORDER BY MAX(m.created_at) DESC
+             LIMIT $3",
+        )
+        .bind(user_id)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows.into_iter().map(UnreadActivity::from).collect())
+    }
+}
@@ -0,0 +1,199 @@
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+
+use crate::models::entities::ImageQuotaUsage;
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct ImageQuotaRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct ImageQuotaUsageRow {
+    day: String,
+    count: i64,
+    last_at: String,
+}
+
+#[cfg(feature = "staging")]
+impl From<ImageQuotaUsageRow> for ImageQuotaUsage {
+    fn from(row: ImageQuotaUsageRow) -> Self {
+        Self {
+            day: NaiveDate::parse_from_str(&row.day, "%Y-%m-%d").unwrap_or_default(),
+            count: row.count.max(0) as u32,
+            last_at: parse_dt(&row.last_at),
+        }
+    }
+}
+
+#[cfg(feature = "staging")]
+impl ImageQuotaRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    /// Count one generation on `day` unless the user generated after
+    /// `cooldown_start` or already has `daily_limit` (0 = none) for the day.
+    /// Returns the new count, or `None` when refused.
+    pub async fn try_acquire(
+        &self,
+        user_id: &str,
+        day: NaiveDate,
+        now: NaiveDateTime,
+        cooldown_start: NaiveDateTime,
+        daily_limit: u32,
+    ) -> Result<Option<u32>, sqlx::Error> {
+        let count: Option<i64> = sqlx::query_scalar(
+            "INSERT INTO image_quota_usage (user_id, day, count, last_at)
+             VALUES (?1, ?2, 1, ?3)
+             ON CONFLICT (user_id) DO UPDATE SET
+                 count = CASE WHEN image_quota_usage.day = excluded.day
+                              THEN image_quota_usage.count + 1 ELSE 1 END,
+                 day = excluded.day,
+                 last_at = excluded.last_at
+             WHERE julianday(image_quota_usage.last_at) <= julianday(?4)
+               AND (image_quota_usage.day <> excluded.day
+                    OR ?5 = 0 OR image_quota_usage.count < ?5)
+             RETURNING count",
+        )
+        .bind(user_id)
+        .bind(day.format("%Y-%m-%d").to_string())
+        .bind(now.format("%Y-%m-%d %H:%M:%S").to_string())
+        .bind(cooldown_start.format("%Y-%m-%d %H:%M:%S").to_string())
+        .bind(i64::from(daily_limit))
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(count.map(|c| c.max(0) as u32))
+    }
+
+    /// Give back a generation counted on `day`.
+    pub async fn release(&self, user_id: &str, day: NaiveDate) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE image_quota_usage SET count = MAX(count - 1, 0)
+             WHERE user_id = ? AND day = ?",
+        )
+        .bind(user_id)
+        .bind(day.format("%Y-%m-%d").to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(&self, user_id: &str) -> Result<Option<ImageQuotaUsage>, sqlx::Error> {
+        let row = sqlx::query_as::<_, ImageQuotaUsageRow>(
+            "SELECT day, count, last_at FROM image_quota_usage WHERE user_id = ?",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(ImageQuotaUsage::from))
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct ImageQuotaRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgImageQuotaUsageRow {
+    day: NaiveDate,
+    count: i32,
+    last_at: NaiveDateTime,
+}
+
+#[cfg(not(feature = "staging"))]
+impl From<PgImageQuotaUsageRow> for ImageQuotaUsage {
+    fn from(row: PgImageQuotaUsageRow) -> Self {
+        Self {
+            day: row.day,
+            count: row.count.max(0) as u32,
+            last_at: row.last_at,
+        }
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+impl ImageQuotaRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    /// Count one generation on `day` unless the user generated after
+    /// `cooldown_start` or already has `daily_limit` (0 = none) for the day.
+    /// Returns the new count, or `None` when refused.
+    pub async fn try_acquire(
+        &self,
+        user_id: &str,
+        day: NaiveDate,
+        now: NaiveDateTime,
+        cooldown_start: NaiveDateTime,
+        daily_limit: u32,
+    ) -> Result<Option<u32>, sqlx::Error> {
+        let count: Option<i32> = sqlx::query_scalar(
+            "INSERT INTO image_quota_usage (user_id, day, count, last_at)
+             VALUES ($1, $2, 1, $3)
+             ON CONFLICT (user_id) DO UPDATE SET
+                 count = CASE WHEN image_quota_usage.day = EXCLUDED.day
+                              THEN image_quota_usage.count + 1 ELSE 1 END,
+                 day = EXCLUDED.day,
+                 last_at = EXCLUDED.last_at
+             WHERE image_quota_usage.last_at <= $4
+               AND (image_quota_usage.day <> EXCLUDED.day
+                    OR $5 = 0 OR image_quota_usage.count < $5)
+             RETURNING count",
+        )
+        .bind(user_id)
+        .bind(day)
+        .bind(now)
+        .bind(cooldown_start)
+        .bind(i64::from(daily_limit))
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Ok(count.map(|c| c.max(0) as u32))
+    }
+
+    /// Give back a generation counted on `day`.
+    pub async fn release(&self, user_id: &str, day: NaiveDate) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE image_quota_usage SET count = GREATEST(count - 1, 0)
+             WHERE user_id = $1 AND day = $2",
+        )
+        .bind(user_id)
+        .bind(day)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(&self, user_id: &str) -> Result<Option<ImageQuotaUsage>, sqlx::Error> {
+        let row = sqlx::query_as::<_, PgImageQuotaUsageRow>(
+            "SELECT day, count, last_at FROM image_quota_usage WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Ok(row.map(ImageQuotaUsage::from))
+    }
+}
@@ -6,7 +6,62 @@ use sqlx::SqlitePool;
 #[cfg(feature = "staging")]
 use super::{parse_dt, parse_json};
 
-use crate::models::entities::{AIInfluencer, InfluencerStatus};
+use crate::models::entities::{
+    AIInfluencer, InfluencerStatus, LeaderboardEntry, LeaderboardMetric,
+};
+
+/// Narrows and orders the public influencer lists.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct InfluencerListFilter {
+    /// Only verified (`true`) or only unverified (`false`) influencers
+    pub verified: Option<bool>,
+    /// List verified influencers ahead of the rest
+    pub boost_verified: bool,
+}
+
+impl InfluencerListFilter {
+    /// Extra `WHERE` condition, empty when unfiltered.
+    fn where_sql(&self) -> &'static str {
+        match self.verified {
+            Some(true) => " AND is_verified = TRUE",
+            Some(false) => " AND is_verified = FALSE",
+            None => "",
+        }
+    }
+
+    /// Leading `ORDER BY` term, empty unless boosting.
+    fn order_sql(&self) -> &'static str {
+        if self.boost_verified {
+            "is_verified DESC, "
+        } else {
+            ""
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct LeaderboardRow {
+    id: String,
+    name: String,
+    display_name: String,
+    avatar_url: Option<String>,
+    category: Option<String>,
+    score: i64,
+}
+
+impl LeaderboardRow {
+    fn into_entry(self, rank: i64) -> LeaderboardEntry {
+        LeaderboardEntry {
+            rank,
+            influencer_id: self.id,
+            name: self.name,
+            display_name: self.display_name,
+            avatar_url: self.avatar_url,
+            category: self.category,
+            score: self.score,
+        }
+    }
+}
 
 // ── Staging: SQLite-only ──────────────────────────────────────────────────────
 
@@ -30,8 +85,11 @@ struct InfluencerRow {
     suggested_messages: String,
     is_active: String,
     is_nsfw: i32,
+    is_verified: i32,
+    is_official: i32,
     parent_principal_id: Option<String>,
     source: Option<String>,
+    tenant: String,
     created_at: String,
     updated_at: String,
     metadata: String,
@@ -39,6 +97,7 @@ struct InfluencerRow {
     conversation_count: Option<i64>,
     #[sqlx(default)]
     message_count: Option<i64>,
+    view_count: i64,
 }
 
 #[cfg(feature = "staging")]
@@ -57,13 +116,17 @@ impl From<InfluencerRow> for AIInfluencer {
             suggested_messages: serde_json::from_str(&row.suggested_messages).unwrap_or_default(),
             is_active: row.is_active.parse().unwrap_or(InfluencerStatus::Active),
             is_nsfw: row.is_nsfw != 0,
+            is_verified: row.is_verified != 0,
+            is_official: row.is_official != 0,
             parent_principal_id: row.parent_principal_id,
             source: row.source,
+            tenant: row.tenant,
             created_at: parse_dt(&row.created_at),
             updated_at: parse_dt(&row.updated_at),
             metadata: parse_json(&row.metadata),
             conversation_count: row.conversation_count,
             message_count: row.message_count,
+            view_count: row.view_count,
         }
     }
 }
@@ -72,7 +135,8 @@ impl From<InfluencerRow> for AIInfluencer {
 const SELECT_COLS: &str =
     "id, name, display_name, avatar_url, description, category, system_instructions,
      personality_traits, initial_greeting, suggested_messages, is_active, is_nsfw,
-     parent_principal_id, source, created_at, updated_at, metadata";
+     is_verified, is_official, parent_principal_id, source, tenant, created_at, updated_at,
+     metadata, view_count";
 
 #[cfg(feature = "staging")]
 impl InfluencerRepository {
@@ -94,8 +158,8 @@ impl InfluencerRepository {
                 id, name, display_name, avatar_url, description, category,
                 system_instructions, personality_traits, initial_greeting,
                 suggested_messages, is_active, is_nsfw, parent_principal_id, source,
-                created_at, updated_at, metadata
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                tenant, created_at, updated_at, metadata
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(&influencer.id)
         .bind(&influencer.name)
@@ -111,6 +175,7 @@ impl InfluencerRepository {
         .bind(influencer.is_nsfw as i32)
         .bind(&influencer.parent_principal_id)
         .bind(&influencer.source)
+        .bind(&influencer.tenant)
         .bind(
             influencer
                 .created_at
@@ -145,6 +210,182 @@ impl InfluencerRepository {
         Ok(())
     }
 
+    /// Change the influencer's name, keeping the old one in `name_history` so it
+    /// still resolves. Taking back one of its own old names removes that entry.
+    pub async fn rename(
+        &self,
+        influencer_id: &str,
+        old_name: &str,
+        new_name: &str,
+        changed_by: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM name_history WHERE old_name = ? AND influencer_id = ?")
+            .bind(new_name)
+            .bind(influencer_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO name_history (old_name, influencer_id, changed_by) VALUES (?, ?, ?)",
+        )
+        .bind(old_name)
+        .bind(influencer_id)
+        .bind(changed_by)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "UPDATE ai_influencers SET name = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        )
+        .bind(new_name)
+        .bind(influencer_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    /// Save (or with `None`, discard) the unpublished draft prompt.
+    pub async fn set_draft_system_prompt(
+        &self,
+        influencer_id: &str,
+        draft: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE ai_influencers SET draft_system_instructions = ? WHERE id = ?")
+            .bind(draft)
+            .bind(influencer_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Make `instructions` (the guarded draft) live and clear the draft.
+    pub async fn publish_draft_system_prompt(
+        &self,
+        influencer_id: &str,
+        instructions: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE ai_influencers
+             SET system_instructions = ?, draft_system_instructions = NULL, updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?",
+        )
+        .bind(instructions)
+        .bind(influencer_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_draft_system_prompt(
+        &self,
+        influencer_id: &str,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let draft: Option<Option<String>> =
+            sqlx::query_scalar("SELECT draft_system_instructions FROM ai_influencers WHERE id = ?")
+                .bind(influencer_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(draft.flatten())
+    }
+
+    /// Fill in fields produced by post-creation enrichment; `None` keeps the current value.
+    pub async fn apply_enrichment(
+        &self,
+        influencer_id: &str,
+        avatar_url: Option<&str>,
+        initial_greeting: Option<&str>,
+        suggested_messages: Option<&[String]>,
+    ) -> Result<(), sqlx::Error> {
+        let suggested_messages =
+            suggested_messages.map(|m| serde_json::to_string(m).unwrap_or("[]".to_string()));
+        sqlx::query(
+            "UPDATE ai_influencers
+             SET avatar_url = COALESCE(?, avatar_url),
+                 initial_greeting = COALESCE(?, initial_greeting),
+                 suggested_messages = COALESCE(?, suggested_messages),
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?",
+        )
+        .bind(avatar_url)
+        .bind(initial_greeting)
+        .bind(suggested_messages)
+        .bind(influencer_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Add batched impression counts to each influencer's `view_count`.
+    pub async fn add_views(&self, counts: &[(String, i64)]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        for (influencer_id, views) in counts {
+            sqlx::query("UPDATE ai_influencers SET view_count = view_count + ? WHERE id = ?")
+                .bind(views)
+                .bind(influencer_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Set one top-level metadata key, leaving the rest of the object untouched.
+    pub async fn set_metadata_key(
+        &self,
+        influencer_id: &str,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), sqlx::Error> {
+        let value_json = serde_json::to_string(value).unwrap_or("null".to_string());
+        sqlx::query(
+            "UPDATE ai_influencers
+             SET metadata = json_set(COALESCE(metadata, '{}'), '$.' || ?, json(?))
+             WHERE id = ?",
+        )
+        .bind(key)
+        .bind(&value_json)
+        .bind(influencer_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_metadata_key(
+        &self,
+        influencer_id: &str,
+        key: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE ai_influencers
+             SET metadata = json_remove(COALESCE(metadata, '{}'), '$.' || ?)
+             WHERE id = ?",
+        )
+        .bind(key)
+        .bind(influencer_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Set the admin badges; `None` leaves a badge as it is.
+    pub async fn set_badges(
+        &self,
+        influencer_id: &str,
+        verified: Option<bool>,
+        official: Option<bool>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE ai_influencers
+             SET is_verified = COALESCE(?, is_verified), is_official = COALESCE(?, is_official),
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?",
+        )
+        .bind(verified)
+        .bind(official)
+        .bind(influencer_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn soft_delete(&self, influencer_id: &str) -> Result<(), sqlx::Error> {
         sqlx::query(
             "UPDATE ai_influencers SET is_active = 'discontinued', display_name = 'Deleted Bot', updated_at = CURRENT_TIMESTAMP WHERE id = ?",
@@ -179,14 +420,19 @@ impl InfluencerRepository {
 
     pub async fn list_all(
         &self,
+        tenant: &str,
+        filter: InfluencerListFilter,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<AIInfluencer>, sqlx::Error> {
         let rows = sqlx::query_as::<_, InfluencerRow>(&format!(
-            "SELECT {SELECT_COLS} FROM ai_influencers WHERE is_active != 'discontinued'
-             ORDER BY CASE is_active WHEN 'active' THEN 1 WHEN 'coming_soon' THEN 2 END, created_at DESC
-             LIMIT ? OFFSET ?"
+            "SELECT {SELECT_COLS} FROM ai_influencers WHERE tenant = ? AND is_active != 'discontinued'{}
+             ORDER BY CASE is_active WHEN 'active' THEN 1 WHEN 'coming_soon' THEN 2 END, {}created_at DESC
+             LIMIT ? OFFSET ?",
+            filter.where_sql(),
+            filter.order_sql()
         ))
+        .bind(tenant)
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pool)
@@ -207,9 +453,33 @@ impl InfluencerRepository {
         Ok(row.map(AIInfluencer::from))
     }
 
+    /// Influencers `owner` created in this tenant, newest first, up to `limit`.
+    pub async fn list_by_owner(
+        &self,
+        owner: &str,
+        tenant: &str,
+        limit: i64,
+    ) -> Result<Vec<AIInfluencer>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, InfluencerRow>(&format!(
+            "SELECT {SELECT_COLS} FROM ai_influencers
+             WHERE parent_principal_id = ? AND tenant = ? AND is_active != 'discontinued'
+             ORDER BY created_at DESC
+             LIMIT ?"
+        ))
+        .bind(owner)
+        .bind(tenant)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(AIInfluencer::from).collect())
+    }
+
+    /// The influencer named `name`, or that was named `name` before a rename.
     pub async fn get_by_name(&self, name: &str) -> Result<Option<AIInfluencer>, sqlx::Error> {
         let row = sqlx::query_as::<_, InfluencerRow>(&format!(
-            "SELECT {SELECT_COLS} FROM ai_influencers WHERE name = ?"
+            "SELECT {SELECT_COLS} FROM ai_influencers
+             WHERE name = ?1 OR id = (SELECT influencer_id FROM name_history WHERE old_name = ?1)
+             ORDER BY (name = ?1) DESC LIMIT 1"
         ))
         .bind(name)
         .fetch_optional(&self.pool)
@@ -217,15 +487,18 @@ impl InfluencerRepository {
         Ok(row.map(AIInfluencer::from))
     }
 
+    /// Lookup by ID or name; a former name resolves to the renamed influencer.
     pub async fn get_by_id_or_name(
         &self,
         id_or_name: &str,
     ) -> Result<Option<AIInfluencer>, sqlx::Error> {
         let row = sqlx::query_as::<_, InfluencerRow>(&format!(
-            "SELECT {SELECT_COLS} FROM ai_influencers WHERE id = ? OR name = ? LIMIT 1"
+            "SELECT {SELECT_COLS} FROM ai_influencers
+             WHERE id = ?1 OR name = ?1
+                OR id = (SELECT influencer_id FROM name_history WHERE old_name = ?1)
+             ORDER BY (id = ?1 OR name = ?1) DESC LIMIT 1"
         ))
         .bind(id_or_name)
-        .bind(id_or_name)
         .fetch_optional(&self.pool)
         .await?;
         Ok(row.map(AIInfluencer::from))
@@ -251,8 +524,9 @@ impl InfluencerRepository {
             "SELECT i.id, i.name, i.display_name, i.avatar_url, i.description,
                     i.category, i.system_instructions, i.personality_traits,
                     i.initial_greeting, i.suggested_messages,
-                    i.is_active, i.is_nsfw, i.parent_principal_id, i.source,
-                    i.created_at, i.updated_at, i.metadata,
+                    i.is_active, i.is_nsfw, i.is_verified, i.is_official,
+                    i.parent_principal_id, i.source, i.tenant,
+                    i.created_at, i.updated_at, i.metadata, i.view_count,
                     COUNT(c.id) as conversation_count
              FROM ai_influencers i
              LEFT JOIN conversations c ON i.id = c.influencer_id
@@ -266,20 +540,30 @@ impl InfluencerRepository {
 
     pub async fn list_trending(
         &self,
+        tenant: &str,
+        filter: InfluencerListFilter,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<AIInfluencer>, sqlx::Error> {
-        let rows = sqlx::query_as::<_, InfluencerRow>(
-            "SELECT i.id, i.name, i.display_name, i.avatar_url, i.description,
-                    i.category, i.system_instructions, i.personality_traits,
-                    i.initial_greeting, i.suggested_messages,
-                    i.is_active, i.is_nsfw, i.parent_principal_id, i.source,
-                    i.created_at, i.updated_at, i.metadata,
-                    (SELECT COUNT(c.id) FROM conversations c WHERE c.influencer_id = i.id) as conversation_count,
-                    (SELECT COUNT(m.id) FROM conversations c JOIN messages m ON c.id = m.conversation_id WHERE c.influencer_id = i.id AND m.role = 'user') as message_count
-             FROM ai_influencers i WHERE i.is_active = 'active'
-             ORDER BY message_count DESC, i.created_at DESC LIMIT ? OFFSET ?",
-        )
+        // Ten profile views weigh as much as one user message, so bots that are
+        // being promoted but haven't built up chats yet still surface
+        let rows = sqlx::query_as::<_, InfluencerRow>(&format!(
+            "SELECT * FROM (
+                SELECT i.id, i.name, i.display_name, i.avatar_url, i.description,
+                       i.category, i.system_instructions, i.personality_traits,
+                       i.initial_greeting, i.suggested_messages,
+                       i.is_active, i.is_nsfw, i.is_verified, i.is_official,
+                       i.parent_principal_id, i.source, i.tenant,
+                       i.created_at, i.updated_at, i.metadata, i.view_count,
+                       (SELECT COUNT(c.id) FROM conversations c WHERE c.influencer_id = i.id) as conversation_count,
+                       (SELECT COUNT(m.id) FROM conversations c JOIN messages m ON c.id = m.conversation_id WHERE c.influencer_id = i.id AND m.role = 'user') as message_count
+                FROM ai_influencers i WHERE i.tenant = ? AND i.is_active = 'active'{}
+             ) ranked
+             ORDER BY {}message_count + view_count / 10.0 DESC, created_at DESC LIMIT ? OFFSET ?",
+            filter.where_sql(),
+            filter.order_sql()
+        ))
+        .bind(tenant)
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pool)
@@ -287,21 +571,72 @@ impl InfluencerRepository {
         Ok(rows.into_iter().map(AIInfluencer::from).collect())
     }
 
-    pub async fn count_trending(&self) -> Result<i64, sqlx::Error> {
-        let count: (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM ai_influencers WHERE is_active = 'active'")
-                .fetch_one(&self.pool)
-                .await?;
+    pub async fn count_trending(
+        &self,
+        tenant: &str,
+        filter: InfluencerListFilter,
+    ) -> Result<i64, sqlx::Error> {
+        let count: (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*) FROM ai_influencers WHERE tenant = ? AND is_active = 'active'{}",
+            filter.where_sql()
+        ))
+        .bind(tenant)
+        .fetch_one(&self.pool)
+        .await?;
         Ok(count.0)
     }
 
-    pub async fn count_all(&self) -> Result<i64, sqlx::Error> {
-        let count: (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM ai_influencers WHERE is_active != 'discontinued'")
-                .fetch_one(&self.pool)
-                .await?;
+    pub async fn count_all(
+        &self,
+        tenant: &str,
+        filter: InfluencerListFilter,
+    ) -> Result<i64, sqlx::Error> {
+        let count: (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*) FROM ai_influencers WHERE tenant = ? AND is_active != 'discontinued'{}",
+            filter.where_sql()
+        ))
+        .bind(tenant)
+        .fetch_one(&self.pool)
+        .await?;
         Ok(count.0)
     }
+
+    /// The tenant's active influencers ranked by user activity since `since`, highest first.
+    pub async fn leaderboard(
+        &self,
+        tenant: &str,
+        since: chrono::NaiveDateTime,
+        metric: LeaderboardMetric,
+        limit: i64,
+    ) -> Result<Vec<LeaderboardEntry>, sqlx::Error> {
+        let score = match metric {
+            LeaderboardMetric::Messages => "COUNT(m.id)",
+            LeaderboardMetric::Users => "COUNT(DISTINCT c.user_id)",
+        };
+        let rows = sqlx::query_as::<_, LeaderboardRow>(&format!(
+            "SELECT i.id, i.name, i.display_name, i.avatar_url, i.category, s.score
+             FROM (
+                SELECT c.influencer_id, {score} AS score
+                FROM messages m JOIN conversations c ON c.id = m.conversation_id
+                WHERE m.role = 'user' AND m.created_at >= ?
+                GROUP BY c.influencer_id
+             ) s
+             JOIN ai_influencers i ON i.id = s.influencer_id
+             WHERE i.tenant = ? AND i.is_active = 'active'
+             ORDER BY s.score DESC, i.created_at DESC
+             LIMIT ?"
+        ))
+        .bind(since.format("%Y-%m-%d %H:%M:%S").to_string())
+        .bind(tenant)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .zip(1..)
+            .map(|(row, rank)| row.into_entry(rank))
+            .collect())
+    }
 }
 
 // ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
@@ -326,8 +661,11 @@ struct PgInfluencerRow {
     suggested_messages: serde_json::Value,
     is_active: String,
     is_nsfw: bool,
+    is_verified: bool,
+    is_official: bool,
     parent_principal_id: Option<String>,
     source: Option<String>,
+    tenant: String,
     created_at: chrono::NaiveDateTime,
     updated_at: chrono::NaiveDateTime,
     metadata: serde_json::Value,
@@ -335,6 +673,7 @@ struct PgInfluencerRow {
     conversation_count: Option<i64>,
     #[sqlx(default)]
     message_count: Option<i64>,
+    view_count: i64,
 }
 
 #[cfg(not(feature = "staging"))]
@@ -353,13 +692,17 @@ impl From<PgInfluencerRow> for AIInfluencer {
             suggested_messages: serde_json::from_value(row.suggested_messages).unwrap_or_default(),
             is_active: row.is_active.parse().unwrap_or(InfluencerStatus::Active),
             is_nsfw: row.is_nsfw,
+            is_verified: row.is_verified,
+            is_official: row.is_official,
             parent_principal_id: row.parent_principal_id,
             source: row.source,
+            tenant: row.tenant,
             created_at: row.created_at,
             updated_at: row.updated_at,
             metadata: row.metadata,
             conversation_count: row.conversation_count,
             message_count: row.message_count,
+            view_count: row.view_count,
         }
     }
 }
@@ -368,7 +711,8 @@ impl From<PgInfluencerRow> for AIInfluencer {
 const SELECT_COLS: &str =
     "id, name, display_name, avatar_url, description, category, system_instructions,
      personality_traits, initial_greeting, suggested_messages, is_active, is_nsfw,
-     parent_principal_id, source, created_at, updated_at, metadata";
+     is_verified, is_official, parent_principal_id, source, tenant, created_at, updated_at,
+     metadata, view_count";
 
 #[cfg(not(feature = "staging"))]
 impl InfluencerRepository {
@@ -384,8 +728,8 @@ impl InfluencerRepository {
                 id, name, display_name, avatar_url, description, category,
                 system_instructions, personality_traits, initial_greeting,
                 suggested_messages, is_active, is_nsfw, parent_principal_id, source,
-                created_at, updated_at, metadata
-            ) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14,$15,$16,$17)
+                tenant, created_at, updated_at, metadata
+            ) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14,$15,$16,$17,$18)
             ON CONFLICT (id) DO NOTHING",
         )
         .bind(&influencer.id)
@@ -402,6 +746,7 @@ impl InfluencerRepository {
         .bind(influencer.is_nsfw)
         .bind(&influencer.parent_principal_id)
         .bind(&influencer.source)
+        .bind(&influencer.tenant)
         .bind(influencer.created_at)
         .bind(influencer.updated_at)
         .bind(&influencer.metadata)
@@ -426,6 +771,180 @@ impl InfluencerRepository {
         Ok(())
     }
 
+    /// Change the influencer's name, keeping the old one in `name_history` so it
+    /// still resolves. Taking back one of its own old names removes that entry.
+    pub async fn rename(
+        &self,
+        influencer_id: &str,
+        old_name: &str,
+        new_name: &str,
+        changed_by: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pg_pool.begin().await?;
+        sqlx::query("DELETE FROM name_history WHERE old_name = $1 AND influencer_id = $2")
+            .bind(new_name)
+            .bind(influencer_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO name_history (old_name, influencer_id, changed_by) VALUES ($1, $2, $3)",
+        )
+        .bind(old_name)
+        .bind(influencer_id)
+        .bind(changed_by)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "UPDATE ai_influencers SET name = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2",
+        )
+        .bind(new_name)
+        .bind(influencer_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    }
+
+    /// Save (or with `None`, discard) the unpublished draft prompt.
+    pub async fn set_draft_system_prompt(
+        &self,
+        influencer_id: &str,
+        draft: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE ai_influencers SET draft_system_instructions = $1 WHERE id = $2")
+            .bind(draft)
+            .bind(influencer_id)
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Make `instructions` (the guarded draft) live and clear the draft.
+    pub async fn publish_draft_system_prompt(
+        &self,
+        influencer_id: &str,
+        instructions: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE ai_influencers
+             SET system_instructions = $1, draft_system_instructions = NULL, updated_at = NOW()
+             WHERE id = $2",
+        )
+        .bind(instructions)
+        .bind(influencer_id)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_draft_system_prompt(
+        &self,
+        influencer_id: &str,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let draft: Option<Option<String>> = sqlx::query_scalar(
+            "SELECT draft_system_instructions FROM ai_influencers WHERE id = $1",
+        )
+        .bind(influencer_id)
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Ok(draft.flatten())
+    }
+
+    /// Fill in fields produced by post-creation enrichment; `None` keeps the current value.
+    pub async fn apply_enrichment(
+        &self,
+        influencer_id: &str,
+        avatar_url: Option<&str>,
+        initial_greeting: Option<&str>,
+        suggested_messages: Option<&[String]>,
+    ) -> Result<(), sqlx::Error> {
+        let suggested_messages =
+            suggested_messages.map(|m| serde_json::to_value(m).unwrap_or_default());
+        sqlx::query(
+            "UPDATE ai_influencers
+             SET avatar_url = COALESCE($1, avatar_url),
+                 initial_greeting = COALESCE($2, initial_greeting),
+                 suggested_messages = COALESCE($3, suggested_messages),
+                 updated_at = NOW()
+             WHERE id = $4",
+        )
+        .bind(avatar_url)
+        .bind(initial_greeting)
+        .bind(suggested_messages)
+        .bind(influencer_id)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Add batched impression counts to each influencer's `view_count`.
+    pub async fn add_views(&self, counts: &[(String, i64)]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pg_pool.begin().await?;
+        for (influencer_id, views) in counts {
+            sqlx::query("UPDATE ai_influencers SET view_count = view_count + $1 WHERE id = $2")
+                .bind(views)
+                .bind(influencer_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Set one top-level metadata key, leaving the rest of the object untouched.
+    pub async fn set_metadata_key(
+        &self,
+        influencer_id: &str,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE ai_influencers
+             SET metadata = jsonb_set(COALESCE(metadata, '{}'::jsonb), ARRAY[$1], $2)
+             WHERE id = $3",
+        )
+        .bind(key)
+        .bind(value)
+        .bind(influencer_id)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_metadata_key(
+        &self,
+        influencer_id: &str,
+        key: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE ai_influencers SET metadata = COALESCE(metadata, '{}'::jsonb) - $1 WHERE id = $2",
+        )
+        .bind(key)
+        .bind(influencer_id)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Set the admin badges; `None` leaves a badge as it is.
+    pub async fn set_badges(
+        &self,
+        influencer_id: &str,
+        verified: Option<bool>,
+        official: Option<bool>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE ai_influencers
+             SET is_verified = COALESCE($1, is_verified), is_official = COALESCE($2, is_official),
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE id = $3",
+        )
+        .bind(verified)
+        .bind(official)
+        .bind(influencer_id)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn soft_delete(&self, influencer_id: &str) -> Result<(), sqlx::Error> {
         sqlx::query(
             "UPDATE ai_influencers SET is_active = 'discontinued', display_name = 'Deleted Bot', updated_at = NOW() WHERE id = $1",
@@ -460,14 +979,19 @@ impl InfluencerRepository {
 
     pub async fn list_all(
         &self,
+        tenant: &str,
+        filter: InfluencerListFilter,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<AIInfluencer>, sqlx::Error> {
         let rows = sqlx::query_as::<_, PgInfluencerRow>(&format!(
-            "SELECT {SELECT_COLS} FROM ai_influencers WHERE is_active != 'discontinued'
-             ORDER BY CASE is_active WHEN 'active' THEN 1 WHEN 'coming_soon' THEN 2 END, created_at DESC
-             LIMIT $1 OFFSET $2"
+            "SELECT {SELECT_COLS} FROM ai_influencers WHERE tenant = $1 AND is_active != 'discontinued'{}
+             ORDER BY CASE is_active WHEN 'active' THEN 1 WHEN 'coming_soon' THEN 2 END, {}created_at DESC
+             LIMIT $2 OFFSET $3",
+            filter.where_sql(),
+            filter.order_sql()
         ))
+        .bind(tenant)
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pg_pool)
@@ -488,9 +1012,33 @@ impl InfluencerRepository {
         Ok(row.map(AIInfluencer::from))
     }
 
+    /// Influencers `owner` created in this tenant, newest first, up to `limit`.
+    pub async fn list_by_owner(
+        &self,
+        owner: &str,
+        tenant: &str,
+        limit: i64,
+    ) -> Result<Vec<AIInfluencer>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PgInfluencerRow>(&format!(
+            "SELECT {SELECT_COLS} FROM ai_influencers
+             WHERE parent_principal_id = $1 AND tenant = $2 AND is_active != 'discontinued'
+             ORDER BY created_at DESC
+             LIMIT $3"
+        ))
+        .bind(owner)
+        .bind(tenant)
+        .bind(limit)
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows.into_iter().map(AIInfluencer::from).collect())
+    }
+
+    /// The influencer named `name`, or that was named `name` before a rename.
     pub async fn get_by_name(&self, name: &str) -> Result<Option<AIInfluencer>, sqlx::Error> {
         let row = sqlx::query_as::<_, PgInfluencerRow>(&format!(
-            "SELECT {SELECT_COLS} FROM ai_influencers WHERE name = $1"
+            "SELECT {SELECT_COLS} FROM ai_influencers
+             WHERE name = $1 OR id = (SELECT influencer_id FROM name_history WHERE old_name = $1)
+             ORDER BY (name = $1) DESC LIMIT 1"
         ))
         .bind(name)
         .fetch_optional(&self.pg_pool)
@@ -498,12 +1046,16 @@ impl InfluencerRepository {
         Ok(row.map(AIInfluencer::from))
     }
 
+    /// Lookup by ID or name; a former name resolves to the renamed influencer.
     pub async fn get_by_id_or_name(
         &self,
         id_or_name: &str,
     ) -> Result<Option<AIInfluencer>, sqlx::Error> {
         let row = sqlx::query_as::<_, PgInfluencerRow>(&format!(
-            "SELECT {SELECT_COLS} FROM ai_influencers WHERE id = $1 OR name = $1 LIMIT 1"
+            "SELECT {SELECT_COLS} FROM ai_influencers
+             WHERE id = $1 OR name = $1
+                OR id = (SELECT influencer_id FROM name_history WHERE old_name = $1)
+             ORDER BY (id = $1 OR name = $1) DESC LIMIT 1"
         ))
         .bind(id_or_name)
         .fetch_optional(&self.pg_pool)
@@ -531,8 +1083,9 @@ impl InfluencerRepository {
             "SELECT i.id, i.name, i.display_name, i.avatar_url, i.description,
                     i.category, i.system_instructions, i.personality_traits,
                     i.initial_greeting, i.suggested_messages,
-                    i.is_active, i.is_nsfw, i.parent_principal_id, i.source,
-                    i.created_at, i.updated_at, i.metadata,
+                    i.is_active, i.is_nsfw, i.is_verified, i.is_official,
+                    i.parent_principal_id, i.source, i.tenant,
+                    i.created_at, i.updated_at, i.metadata, i.view_count,
                     COUNT(c.id) as conversation_count
              FROM ai_influencers i
              LEFT JOIN conversations c ON i.id = c.influencer_id
@@ -546,20 +1099,30 @@ impl InfluencerRepository {
 
     pub async fn list_trending(
         &self,
+        tenant: &str,
+        filter: InfluencerListFilter,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<AIInfluencer>, sqlx::Error> {
-        let rows = sqlx::query_as::<_, PgInfluencerRow>(
-            "SELECT i.id, i.name, i.display_name, i.avatar_url, i.description,
-                    i.category, i.system_instructions, i.personality_traits,
-                    i.initial_greeting, i.suggested_messages,
-                    i.is_active, i.is_nsfw, i.parent_principal_id, i.source,
-                    i.created_at, i.updated_at, i.metadata,
-                    (SELECT COUNT(c.id) FROM conversations c WHERE c.influencer_id = i.id) as conversation_count,
-                    (SELECT COUNT(m.id) FROM conversations c JOIN messages m ON c.id = m.conversation_id WHERE c.influencer_id = i.id AND m.role = 'user') as message_count
-             FROM ai_influencers i WHERE i.is_active = 'active'
-             ORDER BY message_count DESC, i.created_at DESC LIMIT $1 OFFSET $2",
-        )
+        // Ten profile views weigh as much as one user message, so bots that are
+        // being promoted but haven't built up chats yet still surface
+        let rows = sqlx::query_as::<_, PgInfluencerRow>(&format!(
+            "SELECT * FROM (
+                SELECT i.id, i.name, i.display_name, i.avatar_url, i.description,
+                       i.category, i.system_instructions, i.personality_traits,
+                       i.initial_greeting, i.suggested_messages,
+                       i.is_active, i.is_nsfw, i.is_verified, i.is_official,
+                       i.parent_principal_id, i.source, i.tenant,
+                       i.created_at, i.updated_at, i.metadata, i.view_count,
+                       (SELECT COUNT(c.id) FROM conversations c WHERE c.influencer_id = i.id) as conversation_count,
+                       (SELECT COUNT(m.id) FROM conversations c JOIN messages m ON c.id = m.conversation_id WHERE c.influencer_id = i.id AND m.role = 'user') as message_count
+                FROM ai_influencers i WHERE i.tenant = $1 AND i.is_active = 'active'{}
+             ) ranked
+             ORDER BY {}message_count + view_count / 10.0 DESC, created_at DESC LIMIT $2 OFFSET $3",
+            filter.where_sql(),
+            filter.order_sql()
+        ))
+        .bind(tenant)
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pg_pool)
@@ -567,19 +1130,70 @@ impl InfluencerRepository {
         Ok(rows.into_iter().map(AIInfluencer::from).collect())
     }
 
-    pub async fn count_trending(&self) -> Result<i64, sqlx::Error> {
-        let count: (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM ai_influencers WHERE is_active = 'active'")
-                .fetch_one(&self.pg_pool)
-                .await?;
+    pub async fn count_trending(
+        &self,
+        tenant: &str,
+        filter: InfluencerListFilter,
+    ) -> Result<i64, sqlx::Error> {
+        let count: (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*) FROM ai_influencers WHERE tenant = $1 AND is_active = 'active'{}",
+            filter.where_sql()
+        ))
+        .bind(tenant)
+        .fetch_one(&self.pg_pool)
+        .await?;
         Ok(count.0)
     }
 
-    pub async fn count_all(&self) -> Result<i64, sqlx::Error> {
-        let count: (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM ai_influencers WHERE is_active != 'discontinued'")
-                .fetch_one(&self.pg_pool)
-                .await?;
+    pub async fn count_all(
+        &self,
+        tenant: &str,
+        filter: InfluencerListFilter,
+    ) -> Result<i64, sqlx::Error> {
+        let count: (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*) FROM ai_influencers WHERE tenant = $1 AND is_active != 'discontinued'{}",
+            filter.where_sql()
+        ))
+        .bind(tenant)
+        .fetch_one(&self.pg_pool)
+        .await?;
         Ok(count.0)
     }
+
+    /// The tenant's active influencers ranked by user activity since `since`, highest first.
+    pub async fn leaderboard(
+        &self,
+        tenant: &str,
+        since: chrono::NaiveDateTime,
+        metric: LeaderboardMetric,
+        limit: i64,
+    ) -> Result<Vec<LeaderboardEntry>, sqlx::Error> {
+        let score = match metric {
+            LeaderboardMetric::Messages => "COUNT(m.id)",
+            LeaderboardMetric::Users => "COUNT(DISTINCT c.user_id)",
+        };
+        let rows = sqlx::query_as::<_, LeaderboardRow>(&format!(
+            "SELECT i.id, i.name, i.display_name, i.avatar_url, i.category, s.score
+             FROM (
+                SELECT c.influencer_id, {score} AS score
+                FROM messages m JOIN conversations c ON c.id = m.conversation_id
+                WHERE m.role = 'user' AND m.created_at >= $1
+                GROUP BY c.influencer_id
+             ) s
+             JOIN ai_influencers i ON i.id = s.influencer_id
+             WHERE i.tenant = $2 AND i.is_active = 'active'
+             ORDER BY s.score DESC, i.created_at DESC
+             LIMIT $3"
+        ))
+        .bind(since)
+        .bind(tenant)
+        .bind(limit)
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .zip(1..)
+            .map(|(row, rank)| row.into_entry(rank))
+            .collect())
+    }
 }
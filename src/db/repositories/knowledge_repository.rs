@@ -0,0 +1,379 @@
+#[cfg(not(feature = "staging"))]
+use chrono::NaiveDateTime;
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+use super::{decode_embedding, encode_embedding};
+
+use crate::models::entities::{KnowledgeChunk, KnowledgeDocument};
+
+/// How much one influencer's knowledge base may hold.
+#[derive(Debug, Clone, Copy)]
+pub struct KnowledgeLimits {
+    pub max_documents: usize,
+    pub max_chunks: usize,
+}
+
+const SELECT_COLS: &str =
+    "id, influencer_id, title, content_type, size_bytes, chunk_count, uploaded_by, created_at";
+
+#[derive(sqlx::FromRow)]
+struct ChunkRow {
+    content: String,
+    embedding: Vec<u8>,
+}
+
+impl From<ChunkRow> for KnowledgeChunk {
+    fn from(row: ChunkRow) -> Self {
+        Self {
+            content: row.content,
+            embedding: decode_embedding(&row.embedding),
+        }
+    }
+}
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct KnowledgeRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct KnowledgeDocumentRow {
+    id: String,
+    influencer_id: String,
+    title: String,
+    content_type: String,
+    size_bytes: i64,
+    chunk_count: i32,
+    uploaded_by: String,
+    created_at: String,
+}
+
+#[cfg(feature = "staging")]
+impl From<KnowledgeDocumentRow> for KnowledgeDocument {
+    fn from(row: KnowledgeDocumentRow) -> Self {
+        Self {
+            id: row.id,
+            influencer_id: row.influencer_id,
+            title: row.title,
+            content_type: row.content_type,
+            size_bytes: row.size_bytes,
+            chunk_count: row.chunk_count,
+            uploaded_by: row.uploaded_by,
+            created_at: parse_dt(&row.created_at),
+        }
+    }
+}
+
+#[cfg(feature = "staging")]
+impl KnowledgeRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    /// Store a document and its chunks, each paired with its embedding, in one go.
+    /// Stores nothing and returns `false` when the influencer would go over
+    /// `limits`; the check and the insert are one statement, so concurrent uploads
+    /// can't both squeeze in.
+    pub async fn create(
+        &self,
+        document: &KnowledgeDocument,
+        chunks: &[(String, Vec<f32>)],
+        limits: KnowledgeLimits,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let inserted = sqlx::query(
+            "INSERT INTO knowledge_documents
+             (id, influencer_id, title, content_type, size_bytes, chunk_count, uploaded_by, created_at)
+             SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8
+             WHERE (SELECT COUNT(*) FROM knowledge_documents WHERE influencer_id = ?2) < ?9
+             AND (SELECT COALESCE(SUM(chunk_count), 0) FROM knowledge_documents
+                  WHERE influencer_id = ?2) + ?6 <= ?10",
+        )
+        .bind(&document.id)
+        .bind(&document.influencer_id)
+        .bind(&document.title)
+        .bind(&document.content_type)
+        .bind(document.size_bytes)
+        .bind(document.chunk_count)
+        .bind(&document.uploaded_by)
+        .bind(document.created_at.format("%Y-%m-%d %H:%M:%S").to_string())
+        .bind(limits.max_documents as i64)
+        .bind(limits.max_chunks as i64)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+        if !inserted {
+            return Ok(false);
+        }
+        for (index, (content, embedding)) in chunks.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO knowledge_chunks
+                 (id, document_id, influencer_id, chunk_index, content, embedding)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(&document.id)
+            .bind(&document.influencer_id)
+            .bind(index as i32)
+            .bind(content)
+            .bind(encode_embedding(embedding))
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn delete(&self, document_id: &str) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM knowledge_chunks WHERE document_id = ?")
+            .bind(document_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM knowledge_documents WHERE id = ?")
+            .bind(document_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+
+    /// Drop every document and chunk of an influencer that is being deleted.
+    pub async fn delete_by_influencer(&self, influencer_id: &str) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM knowledge_chunks WHERE influencer_id = ?")
+            .bind(influencer_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM knowledge_documents WHERE influencer_id = ?")
+            .bind(influencer_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(&self, document_id: &str) -> Result<Option<KnowledgeDocument>, sqlx::Error> {
+        let sql = format!("SELECT {SELECT_COLS} FROM knowledge_documents WHERE id = ?");
+        let row = sqlx::query_as::<_, KnowledgeDocumentRow>(&sql)
+            .bind(document_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.map(KnowledgeDocument::from))
+    }
+
+    /// The influencer's documents, newest first.
+    pub async fn list_by_influencer(
+        &self,
+        influencer_id: &str,
+    ) -> Result<Vec<KnowledgeDocument>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {SELECT_COLS} FROM knowledge_documents
+             WHERE influencer_id = ? ORDER BY created_at DESC"
+        );
+        let rows = sqlx::query_as::<_, KnowledgeDocumentRow>(&sql)
+            .bind(influencer_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(KnowledgeDocument::from).collect())
+    }
+
+    /// Every chunk of the influencer's documents, for similarity search.
+    pub async fn list_chunks(
+        &self,
+        influencer_id: &str,
+    ) -> Result<Vec<KnowledgeChunk>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ChunkRow>(
+            "SELECT content, embedding FROM knowledge_chunks WHERE influencer_id = ?",
+        )
+        .bind(influencer_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(KnowledgeChunk::from).collect())
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct KnowledgeRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgKnowledgeDocumentRow {
+    id: String,
+    influencer_id: String,
+    title: String,
+    content_type: String,
+    size_bytes: i64,
+    chunk_count: i32,
+    uploaded_by: String,
+    created_at: NaiveDateTime,
+}
+
+#[cfg(not(feature = "staging"))]
+impl From<PgKnowledgeDocumentRow> for KnowledgeDocument {
+    fn from(row: PgKnowledgeDocumentRow) -> Self {
+        Self {
+            id: row.id,
+            influencer_id: row.influencer_id,
+            title: row.title,
+            content_type: row.content_type,
+            size_bytes: row.size_bytes,
+            chunk_count: row.chunk_count,
+            uploaded_by: row.uploaded_by,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+impl KnowledgeRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    /// Store a document and its chunks, each paired with its embedding, in one go.
+    /// Stores nothing and returns `false` when the influencer would go over
+    /// `limits`; the check and the insert are one statement, so concurrent uploads
+    /// can't both squeeze in.
+    pub async fn create(
+        &self,
+        document: &KnowledgeDocument,
+        chunks: &[(String, Vec<f32>)],
+        limits: KnowledgeLimits,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pg_pool.begin().await?;
+        // Uploads for one influencer take turns, so each sees the others' documents
+        sqlx::query("SELECT id FROM ai_influencers WHERE id = $1 FOR UPDATE")
+            .bind(&document.influencer_id)
+            .execute(&mut *tx)
+            .await?;
+        let inserted = sqlx::query(
+            "INSERT INTO knowledge_documents
+             (id, influencer_id, title, content_type, size_bytes, chunk_count, uploaded_by, created_at)
+             SELECT $1, $2, $3, $4, $5, $6, $7, $8
+             WHERE (SELECT COUNT(*) FROM knowledge_documents WHERE influencer_id = $2) < $9
+             AND (SELECT COALESCE(SUM(chunk_count), 0) FROM knowledge_documents
+                  WHERE influencer_id = $2) + $6 <= $10",
+        )
+        .bind(&document.id)
+        .bind(&document.influencer_id)
+        .bind(&document.title)
+        .bind(&document.content_type)
+        .bind(document.size_bytes)
+        .bind(document.chunk_count)
+        .bind(&document.uploaded_by)
+        .bind(document.created_at)
+        .bind(limits.max_documents as i64)
+        .bind(limits.max_chunks as i64)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+        if !inserted {
+            return Ok(false);
+        }
+        for (index, (content, embedding)) in chunks.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO knowledge_chunks
+                 (id, document_id, influencer_id, chunk_index, content, embedding)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(&document.id)
+            .bind(&document.influencer_id)
+            .bind(index as i32)
+            .bind(content)
+            .bind(encode_embedding(embedding))
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn delete(&self, document_id: &str) -> Result<(), sqlx::Error> {
+        let mut tx = self.pg_pool.begin().await?;
+        sqlx::query("DELETE FROM knowledge_chunks WHERE document_id = $1")
+            .bind(document_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM knowledge_documents WHERE id = $1")
+            .bind(document_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+
+    /// Drop every document and chunk of an influencer that is being deleted.
+    pub async fn delete_by_influencer(&self, influencer_id: &str) -> Result<(), sqlx::Error> {
+        let mut tx = self.pg_pool.begin().await?;
+        sqlx::query("DELETE FROM knowledge_chunks WHERE influencer_id = $1")
+            .bind(influencer_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM knowledge_documents WHERE influencer_id = $1")
+            .bind(influencer_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(&self, document_id: &str) -> Result<Option<KnowledgeDocument>, sqlx::Error> {
+        let sql = format!("SELECT {SELECT_COLS} FROM knowledge_documents WHERE id = $1");
+        let row = sqlx::query_as::<_, PgKnowledgeDocumentRow>(&sql)
+            .bind(document_id)
+            .fetch_optional(&self.pg_pool)
+            .await?;
+        Ok(row.map(KnowledgeDocument::from))
+    }
+
+    /// The influencer's documents, newest first.
+    pub async fn list_by_influencer(
+        &self,
+        influencer_id: &str,
+    ) -> Result<Vec<KnowledgeDocument>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {SELECT_COLS} FROM knowledge_documents
+             WHERE influencer_id = $1 ORDER BY created_at DESC"
+        );
+        sqlx::query_as::<_, PgKnowledgeDocumentRow>(&sql)
+            .bind(influencer_id)
+            .fetch_all(&self.pg_pool)
+            .await
+            .map(|rows| rows.into_iter().map(KnowledgeDocument::from).collect())
+    }
+
+    /// Every chunk of the influencer's documents, for similarity search.
+    pub async fn list_chunks(
+        &self,
+        influencer_id: &str,
+    ) -> Result<Vec<KnowledgeChunk>, sqlx::Error> {
+        sqlx::query_as::<_, ChunkRow>(
+            "SELECT content, embedding FROM knowledge_chunks WHERE influencer_id = $1",
+        )
+        .bind(influencer_id)
+        .fetch_all(&self.pg_pool)
+        .await
+        .map(|rows| rows.into_iter().map(KnowledgeChunk::from).collect())
+    }
+}
@@ -0,0 +1,273 @@
+//! Inserts rows carried over from a legacy dump, keeping their ids and timestamps.
+//! Every insert is a no-op on conflict so an import can be re-run; the `bool`
+//! results say whether a row was actually written.
+
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+use crate::models::entities::{AIInfluencer, Conversation, Message};
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct LegacyImportRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+fn format_dt(dt: &chrono::NaiveDateTime) -> String {
+    dt.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+#[cfg(feature = "staging")]
+impl LegacyImportRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn insert_influencer(&self, inf: &AIInfluencer) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "INSERT INTO ai_influencers (
+                id, name, display_name, avatar_url, description, category,
+                system_instructions, personality_traits, initial_greeting, suggested_messages,
+                is_active, is_nsfw, parent_principal_id, source, created_at, updated_at, metadata
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT DO NOTHING",
+        )
+        .bind(&inf.id)
+        .bind(&inf.name)
+        .bind(&inf.display_name)
+        .bind(&inf.avatar_url)
+        .bind(&inf.description)
+        .bind(&inf.category)
+        .bind(&inf.system_instructions)
+        .bind(inf.personality_traits.to_string())
+        .bind(&inf.initial_greeting)
+        .bind(serde_json::to_string(&inf.suggested_messages).unwrap_or("[]".to_string()))
+        .bind(inf.is_active.as_ref())
+        .bind(inf.is_nsfw as i32)
+        .bind(&inf.parent_principal_id)
+        .bind(&inf.source)
+        .bind(format_dt(&inf.created_at))
+        .bind(format_dt(&inf.updated_at))
+        .bind(inf.metadata.to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn insert_conversation(&self, conv: &Conversation) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "INSERT INTO conversations (id, user_id, influencer_id, created_at, updated_at, metadata)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(&conv.id)
+        .bind(&conv.user_id)
+        .bind(&conv.influencer_id)
+        .bind(format_dt(&conv.created_at))
+        .bind(format_dt(&conv.updated_at))
+        .bind(conv.metadata.to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn insert_message(&self, msg: &Message) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "INSERT INTO messages (
+                id, conversation_id, role, content, message_type,
+                media_urls, audio_url, audio_duration_seconds, token_count,
+                client_message_id, created_at, metadata, status, is_read
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT DO NOTHING",
+        )
+        .bind(&msg.id)
+        .bind(&msg.conversation_id)
+        .bind(msg.role.as_ref())
+        .bind(&msg.content)
+        .bind(msg.message_type.as_ref())
+        .bind(serde_json::to_string(&msg.media_urls).unwrap_or("[]".to_string()))
+        .bind(&msg.audio_url)
+        .bind(msg.audio_duration_seconds)
+        .bind(msg.token_count)
+        .bind(&msg.client_message_id)
+        .bind(format_dt(&msg.created_at))
+        .bind(msg.metadata.to_string())
+        .bind(&msg.status)
+        .bind(msg.is_read as i32)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Put back the legacy `updated_at`, which the message insert trigger overwrites.
+    pub async fn restore_updated_at(
+        &self,
+        conversation_id: &str,
+        updated_at: &chrono::NaiveDateTime,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE conversations SET updated_at = ? WHERE id = ?")
+            .bind(format_dt(updated_at))
+            .bind(conversation_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn influencer_exists(&self, id: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM ai_influencers WHERE id = ?)")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    pub async fn conversation_exists(&self, id: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM conversations WHERE id = ?)")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    pub async fn message_exists(&self, id: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM messages WHERE id = ?)")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct LegacyImportRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+impl LegacyImportRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn insert_influencer(&self, inf: &AIInfluencer) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "INSERT INTO ai_influencers (
+                id, name, display_name, avatar_url, description, category,
+                system_instructions, personality_traits, initial_greeting, suggested_messages,
+                is_active, is_nsfw, parent_principal_id, source, created_at, updated_at, metadata
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+            ON CONFLICT DO NOTHING",
+        )
+        .bind(&inf.id)
+        .bind(&inf.name)
+        .bind(&inf.display_name)
+        .bind(&inf.avatar_url)
+        .bind(&inf.description)
+        .bind(&inf.category)
+        .bind(&inf.system_instructions)
+        .bind(&inf.personality_traits)
+        .bind(&inf.initial_greeting)
+        .bind(serde_json::to_value(&inf.suggested_messages).unwrap_or_default())
+        .bind(inf.is_active.as_ref())
+        .bind(inf.is_nsfw)
+        .bind(&inf.parent_principal_id)
+        .bind(&inf.source)
+        .bind(inf.created_at)
+        .bind(inf.updated_at)
+        .bind(&inf.metadata)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn insert_conversation(&self, conv: &Conversation) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "INSERT INTO conversations (id, user_id, influencer_id, created_at, updated_at, metadata)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(&conv.id)
+        .bind(&conv.user_id)
+        .bind(&conv.influencer_id)
+        .bind(conv.created_at)
+        .bind(conv.updated_at)
+        .bind(&conv.metadata)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn insert_message(&self, msg: &Message) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "INSERT INTO messages (
+                id, conversation_id, role, content, message_type,
+                media_urls, audio_url, audio_duration_seconds, token_count,
+                client_message_id, created_at, metadata, status, is_read
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            ON CONFLICT DO NOTHING",
+        )
+        .bind(&msg.id)
+        .bind(&msg.conversation_id)
+        .bind(msg.role.as_ref())
+        .bind(&msg.content)
+        .bind(msg.message_type.as_ref())
+        .bind(serde_json::to_value(&msg.media_urls).unwrap_or_default())
+        .bind(&msg.audio_url)
+        .bind(msg.audio_duration_seconds)
+        .bind(msg.token_count)
+        .bind(&msg.client_message_id)
+        .bind(msg.created_at)
+        .bind(&msg.metadata)
+        .bind(&msg.status)
+        .bind(msg.is_read)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Put back the legacy `updated_at`, which the message insert trigger overwrites.
+    pub async fn restore_updated_at(
+        &self,
+        conversation_id: &str,
+        updated_at: &chrono::NaiveDateTime,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE conversations SET updated_at = $1 WHERE id = $2")
+            .bind(updated_at)
+            .bind(conversation_id)
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn influencer_exists(&self, id: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM ai_influencers WHERE id = $1)")
+            .bind(id)
+            .fetch_one(&self.pg_pool)
+            .await
+    }
+
+    pub async fn conversation_exists(&self, id: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM conversations WHERE id = $1)")
+            .bind(id)
+            .fetch_one(&self.pg_pool)
+            .await
+    }
+
+    pub async fn message_exists(&self, id: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM messages WHERE id = $1)")
+            .bind(id)
+            .fetch_one(&self.pg_pool)
+            .await
+    }
+}
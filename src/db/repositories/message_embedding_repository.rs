@@ -0,0 +1,301 @@
+use chrono::NaiveDateTime;
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+use super::{decode_embedding, encode_embedding};
+
+use crate::models::entities::{EmbeddedMessage, MessageRole};
+
+/// A message whose text hasn't been embedded yet.
+#[derive(Debug, sqlx::FromRow)]
+pub struct PendingMessage {
+    pub id: String,
+    pub conversation_id: String,
+    pub content: String,
+}
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct MessageEmbeddingRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct EmbeddedMessageRow {
+    message_id: String,
+    conversation_id: String,
+    influencer_id: String,
+    role: String,
+    content: String,
+    created_at: String,
+    embedding: Vec<u8>,
+}
+
+#[cfg(feature = "staging")]
+impl From<EmbeddedMessageRow> for EmbeddedMessage {
+    fn from(row: EmbeddedMessageRow) -> Self {
+        Self {
+            message_id: row.message_id,
+            conversation_id: row.conversation_id,
+            influencer_id: row.influencer_id,
+            role: row.role.parse().unwrap_or(MessageRole::User),
+            content: row.content,
+            created_at: parse_dt(&row.created_at),
+            embedding: decode_embedding(&row.embedding),
+        }
+    }
+}
+
+#[cfg(feature = "staging")]
+impl MessageEmbeddingRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    /// Store `(message_id, conversation_id, embedding)` rows; messages embedded
+    /// in the meantime keep their existing row.
+    pub async fn store(&self, rows: &[(String, String, Vec<f32>)]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        for (message_id, conversation_id, embedding) in rows {
+            sqlx::query(
+                "INSERT OR IGNORE INTO message_embeddings (message_id, conversation_id, embedding)
+                 VALUES (?, ?, ?)",
+            )
+            .bind(message_id)
+            .bind(conversation_id)
+            .bind(encode_embedding(embedding))
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Mark messages that failed to embed so [`pending`](Self::pending) skips them.
+    pub async fn record_failures(
+        &self,
+        message_ids: &[String],
+        error: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        for message_id in message_ids {
+            sqlx::query(
+                "INSERT OR IGNORE INTO message_embedding_failures (message_id, error) VALUES (?, ?)",
+            )
+            .bind(message_id)
+            .bind(error)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Delivered messages with text and no embedding yet, newest first. With
+    /// `since`, only messages created from then on are looked at.
+    pub async fn pending(
+        &self,
+        since: Option<NaiveDateTime>,
+        limit: i64,
+    ) -> Result<Vec<PendingMessage>, sqlx::Error> {
+        let since_filter = if since.is_some() {
+            "AND m.created_at >= ?"
+        } else {
+            ""
+        };
+        let sql = format!(
+            "SELECT m.id, m.conversation_id, m.content
+             FROM messages m
+             LEFT JOIN message_embeddings e ON e.message_id = m.id
+             LEFT JOIN message_embedding_failures f ON f.message_id = m.id
+             WHERE e.message_id IS NULL AND f.message_id IS NULL {since_filter}
+             AND m.content IS NOT NULL AND TRIM(m.content) != ''
+             AND COALESCE(m.status, '') != 'failed'
+             ORDER BY m.created_at DESC
+             LIMIT ?"
+        );
+        let mut query = sqlx::query_as::<_, PendingMessage>(&sql);
+        if let Some(since) = since {
+            query = query.bind(since.format("%Y-%m-%d %H:%M:%S").to_string());
+        }
+        query.bind(limit).fetch_all(&self.pool).await
+    }
+
+    /// The user's embedded messages in this tenant's conversations, newest
+    /// first, up to `limit`. Includes conversations the user was invited to.
+    pub async fn list_for_user(
+        &self,
+        user_id: &str,
+        tenant: &str,
+        limit: i64,
+    ) -> Result<Vec<EmbeddedMessage>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, EmbeddedMessageRow>(
+            "SELECT m.id AS message_id, m.conversation_id, c.influencer_id, m.role,
+                    m.content, m.created_at, e.embedding
+             FROM message_embeddings e
+             JOIN messages m ON m.id = e.message_id
+             JOIN conversations c ON c.id = m.conversation_id
+             JOIN ai_influencers i ON i.id = c.influencer_id
+             WHERE (c.user_id = ?1 OR c.id IN (SELECT conversation_id FROM conversation_participants WHERE user_id = ?1))
+             AND i.tenant = ?2 AND m.content IS NOT NULL
+             ORDER BY m.created_at DESC
+             LIMIT ?3",
+        )
+        .bind(user_id)
+        .bind(tenant)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(EmbeddedMessage::from).collect())
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct MessageEmbeddingRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgEmbeddedMessageRow {
+    message_id: String,
+    conversation_id: String,
+    influencer_id: String,
+    role: String,
+    content: String,
+    created_at: NaiveDateTime,
+    embedding: Vec<u8>,
+}
+
+#[cfg(not(feature = "staging"))]
+impl From<PgEmbeddedMessageRow> for EmbeddedMessage {
+    fn from(row: PgEmbeddedMessageRow) -> Self {
+        Self {
+            message_id: row.message_id,
+            conversation_id: row.conversation_id,
+            influencer_id: row.influencer_id,
+            role: row.role.parse().unwrap_or(MessageRole::User),
+            content: row.content,
+            created_at: row.created_at,
+            embedding: decode_embedding(&row.embedding),
+        }
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+impl MessageEmbeddingRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    /// Store `(message_id, conversation_id, embedding)` rows; messages embedded
+    /// in the meantime keep their existing row.
+    pub async fn store(&self, rows: &[(String, String, Vec<f32>)]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pg_pool.begin().await?;
+        for (message_id, conversation_id, embedding) in rows {
+            sqlx::query(
+                "INSERT INTO message_embeddings (message_id, conversation_id, embedding)
+                 VALUES ($1, $2, $3)
+                 ON CONFLICT (message_id) DO NOTHING",
+            )
+            .bind(message_id)
+            .bind(conversation_id)
+            .bind(encode_embedding(embedding))
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Mark messages that failed to embed so [`pending`](Self::pending) skips them.
+    pub async fn record_failures(
+        &self,
+        message_ids: &[String],
+        error: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pg_pool.begin().await?;
+        for message_id in message_ids {
+            sqlx::query(
+                "INSERT INTO message_embedding_failures (message_id, error) VALUES ($1, $2)
+                 ON CONFLICT (message_id) DO NOTHING",
+            )
+            .bind(message_id)
+            .bind(error)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Delivered messages with text and no embedding yet, newest first. With
+    /// `since`, only messages created from then on are looked at.
+    pub async fn pending(
+        &self,
+        since: Option<NaiveDateTime>,
+        limit: i64,
+    ) -> Result<Vec<PendingMessage>, sqlx::Error> {
+        let (since_filter, limit_param) = if since.is_some() {
+            ("AND m.created_at >= $1", "$2")
+        } else {
+            ("", "$1")
+        };
+        let sql = format!(
+            "SELECT m.id, m.conversation_id, m.content
+             FROM messages m
+             LEFT JOIN message_embeddings e ON e.message_id = m.id
+             LEFT JOIN message_embedding_failures f ON f.message_id = m.id
+             WHERE e.message_id IS NULL AND f.message_id IS NULL {since_filter}
+             AND m.content IS NOT NULL AND TRIM(m.content) != ''
+             AND COALESCE(m.status, '') != 'failed'
+             ORDER BY m.created_at DESC
+             LIMIT {limit_param}"
+        );
+        let mut query = sqlx::query_as::<_, PendingMessage>(&sql);
+        if let Some(since) = since {
+            query = query.bind(since);
+        }
+        query.bind(limit).fetch_all(&self.pg_pool).await
+    }
+
+    /// The user's embedded messages in this tenant's conversations, newest
+    /// first, up to `limit`. Includes conversations the user was invited to.
+    pub async fn list_for_user(
+        &self,
+        user_id: &str,
+        tenant: &str,
+        limit: i64,
+    ) -> Result<Vec<EmbeddedMessage>, sqlx::Error> {
+        sqlx::query_as::<_, PgEmbeddedMessageRow>(
+            "SELECT m.id AS message_id, m.conversation_id, c.influencer_id, m.role,
+                    m.content, m.created_at, e.embedding
+             FROM message_embeddings e
+             JOIN messages m ON m.id = e.message_id
+             JOIN conversations c ON c.id = m.conversation_id
+             JOIN ai_influencers i ON i.id = c.influencer_id
+             WHERE (c.user_id = $1 OR c.id IN (SELECT conversation_id FROM conversation_participants WHERE user_id = $1))
+             AND i.tenant = $2 AND m.content IS NOT NULL
+             ORDER BY m.created_at DESC
+             LIMIT $3",
+        )
+        .bind(user_id)
+        .bind(tenant)
+        .bind(limit)
+        .fetch_all(&self.pg_pool)
+        .await
+        .map(|rows| rows.into_iter().map(EmbeddedMessage::from).collect())
+    }
+}
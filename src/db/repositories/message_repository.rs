@@ -10,7 +10,9 @@ use uuid::Uuid;
 #[cfg(feature = "staging")]
 use super::parse_dt;
 
-use crate::models::entities::{Message, MessageRole, MessageType};
+use crate::models::entities::{
+    ConversationStats, Message, MessageProjection, MessageRole, MessageType,
+};
 
 // ── Staging: SQLite-only ──────────────────────────────────────────────────────
 
@@ -21,21 +23,21 @@ pub struct MessageRepository {
 
 #[cfg(feature = "staging")]
 #[derive(sqlx::FromRow)]
-struct MessageRow {
-    id: String,
-    conversation_id: String,
-    role: String,
-    content: Option<String>,
-    message_type: String,
-    media_urls: String,
-    audio_url: Option<String>,
-    audio_duration_seconds: Option<i32>,
-    token_count: Option<i32>,
-    client_message_id: Option<String>,
-    created_at: String,
-    metadata: String,
-    status: Option<String>,
-    is_read: Option<i32>,
+pub(super) struct MessageRow {
+    pub(super) id: String,
+    pub(super) conversation_id: String,
+    pub(super) role: String,
+    pub(super) content: Option<String>,
+    pub(super) message_type: String,
+    pub(super) media_urls: String,
+    pub(super) audio_url: Option<String>,
+    pub(super) audio_duration_seconds: Option<i32>,
+    pub(super) token_count: Option<i32>,
+    pub(super) client_message_id: Option<String>,
+    pub(super) created_at: String,
+    pub(super) metadata: String,
+    pub(super) status: Option<String>,
+    pub(super) is_read: Option<i32>,
 }
 
 #[cfg(feature = "staging")]
@@ -61,11 +63,19 @@ impl From<MessageRow> for Message {
     }
 }
 
+/// Columns for the queries that have to be built at runtime. The checked `query_as!`
+/// reads list them inline, with overrides where SQLite can't infer the type.
 #[cfg(feature = "staging")]
 const SELECT_COLS: &str = "id, conversation_id, role, content, message_type, media_urls, audio_url,
      audio_duration_seconds, token_count, client_message_id, created_at, metadata,
      status, is_read";
 
+/// Same shape as `SELECT_COLS`, but media, audio and metadata aren't read.
+#[cfg(feature = "staging")]
+const SLIM_COLS: &str = "id, conversation_id, role, content, message_type, '[]' AS media_urls,
+     NULL AS audio_url, NULL AS audio_duration_seconds, NULL AS token_count,
+     NULL AS client_message_id, created_at, '{}' AS metadata, status, is_read";
+
 #[cfg(feature = "staging")]
 impl MessageRepository {
     pub fn new(pool: SqlitePool) -> Self {
@@ -90,32 +100,37 @@ impl MessageRepository {
         let message_id = Uuid::new_v4().to_string();
         let media_urls_json = serde_json::to_string(media_urls).unwrap_or("[]".to_string());
 
-        sqlx::query(
+        // The inbox is ordered by updated_at, so the bump lands with the insert
+        let mut tx = self.pool.begin().await?;
+        let role = role.as_ref();
+        let message_type = message_type.as_ref();
+        sqlx::query!(
             "INSERT INTO messages (
                 id, conversation_id, role, content, message_type,
                 media_urls, audio_url, audio_duration_seconds, token_count,
                 client_message_id, status, is_read
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'delivered', 0)",
+            message_id,
+            conversation_id,
+            role,
+            content,
+            message_type,
+            media_urls_json,
+            audio_url,
+            audio_duration_seconds,
+            token_count,
+            client_message_id,
         )
-        .bind(&message_id)
-        .bind(conversation_id)
-        .bind(role.as_ref())
-        .bind(content)
-        .bind(message_type.as_ref())
-        .bind(&media_urls_json)
-        .bind(audio_url)
-        .bind(audio_duration_seconds)
-        .bind(token_count)
-        .bind(client_message_id)
-        .bind("delivered")
-        .bind(0)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
 
-        sqlx::query("UPDATE conversations SET updated_at = CURRENT_TIMESTAMP WHERE id = ?")
-            .bind(conversation_id)
-            .execute(&self.pool)
-            .await?;
+        sqlx::query!(
+            "UPDATE conversations SET updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            conversation_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
 
         self.get_by_id(&message_id)
             .await?
@@ -123,28 +138,132 @@ impl MessageRepository {
     }
 
     pub async fn delete_by_conversation(&self, conversation_id: &str) -> Result<i64, sqlx::Error> {
-        let count: (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM messages WHERE conversation_id = ?")
-                .bind(conversation_id)
-                .fetch_one(&self.pool)
-                .await?;
-
-        if count.0 > 0 {
-            sqlx::query("DELETE FROM messages WHERE conversation_id = ?")
-                .bind(conversation_id)
-                .execute(&self.pool)
-                .await?;
+        let count = self.count_by_conversation(conversation_id).await?;
+
+        if count > 0 {
+            sqlx::query!(
+                "DELETE FROM messages WHERE conversation_id = ?",
+                conversation_id
+            )
+            .execute(&self.pool)
+            .await?;
         }
 
-        Ok(count.0)
+        Ok(count)
     }
 
-    pub async fn mark_as_read(&self, conversation_id: &str) -> Result<(), sqlx::Error> {
+    pub async fn update_metadata(
+        &self,
+        message_id: &str,
+        metadata: &serde_json::Value,
+    ) -> Result<(), sqlx::Error> {
+        let metadata_json = serde_json::to_string(metadata).unwrap_or("{}".to_string());
+        sqlx::query!(
+            "UPDATE messages SET metadata = ? WHERE id = ?",
+            metadata_json,
+            message_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Set one top-level metadata key, leaving the rest of the object untouched.
+    pub async fn set_metadata_key(
+        &self,
+        message_id: &str,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), sqlx::Error> {
+        let value_json = serde_json::to_string(value).unwrap_or("null".to_string());
+        sqlx::query(
+            "UPDATE messages
+             SET metadata = json_set(COALESCE(metadata, '{}'), '$.' || ?, json(?))
+             WHERE id = ?",
+        )
+        .bind(key)
+        .bind(&value_json)
+        .bind(message_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Swap the greeting in an influencer's conversations that hold nothing but
+    /// the greeting, i.e. the user hasn't replied yet.
+    pub async fn replace_unanswered_greetings(
+        &self,
+        influencer_id: &str,
+        greeting: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE messages SET content = ?
+             WHERE role = 'assistant' AND conversation_id IN (
+                 SELECT m.conversation_id FROM messages m
+                 JOIN conversations c ON c.id = m.conversation_id
+                 WHERE c.influencer_id = ?
+                 GROUP BY m.conversation_id
+                 HAVING COUNT(*) = 1
+             )",
+        )
+        .bind(greeting)
+        .bind(influencer_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn mark_failed(&self, message_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE messages SET status = 'failed' WHERE id = ?")
+            .bind(message_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Fill in a failed reply once generation succeeds. Returns `false` if the
+    /// message was no longer failed (another retry got there first).
+    pub async fn complete_failed(
+        &self,
+        message_id: &str,
+        content: &str,
+        token_count: i32,
+        metadata: &serde_json::Value,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            "UPDATE messages
+             SET content = ?, token_count = ?, metadata = ?, status = 'delivered', is_read = 0
+             WHERE id = ? AND status = 'failed'",
+        )
+        .bind(content)
+        .bind(token_count)
+        .bind(serde_json::to_string(metadata).unwrap_or("{}".to_string()))
+        .bind(message_id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        // The recovered reply is new to the user, so the conversation moves up the inbox
         sqlx::query(
-            "UPDATE messages SET is_read = 1, status = 'read'
+            "UPDATE conversations SET updated_at = CURRENT_TIMESTAMP
+             WHERE id = (SELECT conversation_id FROM messages WHERE id = ?)",
+        )
+        .bind(message_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn mark_as_read(&self, conversation_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE messages
+             SET is_read = 1, status = CASE WHEN status = 'failed' THEN status ELSE 'read' END
              WHERE conversation_id = ? AND is_read = 0 AND role = 'assistant'",
+            conversation_id
         )
-        .bind(conversation_id)
         .execute(&self.pool)
         .await?;
         Ok(())
@@ -153,10 +272,17 @@ impl MessageRepository {
     // ── Reads ─────────────────────────────────────────────────────────────────
 
     pub async fn get_by_id(&self, message_id: &str) -> Result<Option<Message>, sqlx::Error> {
-        let row = sqlx::query_as::<_, MessageRow>(&format!(
-            "SELECT {SELECT_COLS} FROM messages WHERE id = ?"
-        ))
-        .bind(message_id)
+        let row = sqlx::query_as!(
+            MessageRow,
+            r#"SELECT id AS "id!", conversation_id, role, content, message_type,
+                    media_urls AS "media_urls!", audio_url,
+                    audio_duration_seconds AS "audio_duration_seconds: i32",
+                    token_count AS "token_count: i32", client_message_id,
+                    created_at AS "created_at!", metadata AS "metadata!",
+                    status, is_read AS "is_read: i32"
+             FROM messages WHERE id = ?"#,
+            message_id
+        )
         .fetch_optional(&self.pool)
         .await?;
         Ok(row.map(Message::from))
@@ -167,11 +293,18 @@ impl MessageRepository {
         conversation_id: &str,
         client_message_id: &str,
     ) -> Result<Option<Message>, sqlx::Error> {
-        let row = sqlx::query_as::<_, MessageRow>(&format!(
-            "SELECT {SELECT_COLS} FROM messages WHERE conversation_id = ? AND client_message_id = ?"
-        ))
-        .bind(conversation_id)
-        .bind(client_message_id)
+        let row = sqlx::query_as!(
+            MessageRow,
+            r#"SELECT id AS "id!", conversation_id, role, content, message_type,
+                    media_urls AS "media_urls!", audio_url,
+                    audio_duration_seconds AS "audio_duration_seconds: i32",
+                    token_count AS "token_count: i32", client_message_id,
+                    created_at AS "created_at!", metadata AS "metadata!",
+                    status, is_read AS "is_read: i32"
+             FROM messages WHERE conversation_id = ? AND client_message_id = ?"#,
+            conversation_id,
+            client_message_id
+        )
         .fetch_optional(&self.pool)
         .await?;
         Ok(row.map(Message::from))
@@ -185,15 +318,23 @@ impl MessageRepository {
             Some(m) => m,
             None => return Ok(None),
         };
-        let row = sqlx::query_as::<_, MessageRow>(&format!(
-            "SELECT {SELECT_COLS} FROM messages
+        let created_at = msg.created_at.format("%Y-%m-%d %H:%M:%S").to_string();
+        let row = sqlx::query_as!(
+            MessageRow,
+            r#"SELECT id AS "id!", conversation_id, role, content, message_type,
+                    media_urls AS "media_urls!", audio_url,
+                    audio_duration_seconds AS "audio_duration_seconds: i32",
+                    token_count AS "token_count: i32", client_message_id,
+                    created_at AS "created_at!", metadata AS "metadata!",
+                    status, is_read AS "is_read: i32"
+             FROM messages
              WHERE conversation_id = ? AND role = 'assistant'
                AND created_at >= ? AND id != ?
-             ORDER BY created_at ASC LIMIT 1"
-        ))
-        .bind(&msg.conversation_id)
-        .bind(msg.created_at.format("%Y-%m-%d %H:%M:%S").to_string())
-        .bind(message_id)
+             ORDER BY created_at ASC LIMIT 1"#,
+            msg.conversation_id,
+            created_at,
+            message_id
+        )
         .fetch_optional(&self.pool)
         .await?;
         Ok(row.map(Message::from))
@@ -222,18 +363,60 @@ impl MessageRepository {
         Ok(rows.into_iter().map(Message::from).collect())
     }
 
+    /// Messages from `from_id` through `to_id` inclusive (open ends reach the
+    /// first and latest message), oldest first. Ordered by `(created_at, rowid)`:
+    /// `created_at` is stored to the second, so a message and its reply usually
+    /// share it and only the insertion order tells them apart.
+    pub async fn list_between(
+        &self,
+        conversation_id: &str,
+        from_id: Option<&str>,
+        to_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Message>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            MessageRow,
+            r#"SELECT id AS "id!", conversation_id, role, content, message_type,
+                    media_urls AS "media_urls!", audio_url,
+                    audio_duration_seconds AS "audio_duration_seconds: i32",
+                    token_count AS "token_count: i32", client_message_id,
+                    created_at AS "created_at!", metadata AS "metadata!",
+                    status, is_read AS "is_read: i32"
+             FROM messages
+             WHERE conversation_id = ?1
+             AND (?2 IS NULL OR (created_at, rowid) >= (SELECT created_at, rowid FROM messages WHERE id = ?2))
+             AND (?3 IS NULL OR (created_at, rowid) <= (SELECT created_at, rowid FROM messages WHERE id = ?3))
+             ORDER BY created_at ASC, rowid ASC
+             LIMIT ?4"#,
+            conversation_id,
+            from_id,
+            to_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Message::from).collect())
+    }
+
     pub async fn get_recent_for_context(
         &self,
         conversation_id: &str,
         limit: i64,
     ) -> Result<Vec<Message>, sqlx::Error> {
-        let rows = sqlx::query_as::<_, MessageRow>(&format!(
-            "SELECT {SELECT_COLS} FROM messages
+        let rows = sqlx::query_as!(
+            MessageRow,
+            r#"SELECT id AS "id!", conversation_id, role, content, message_type,
+                    media_urls AS "media_urls!", audio_url,
+                    audio_duration_seconds AS "audio_duration_seconds: i32",
+                    token_count AS "token_count: i32", client_message_id,
+                    created_at AS "created_at!", metadata AS "metadata!",
+                    status, is_read AS "is_read: i32"
+             FROM messages
              WHERE conversation_id = ?
-             ORDER BY created_at DESC LIMIT ?"
-        ))
-        .bind(conversation_id)
-        .bind(limit)
+             ORDER BY created_at DESC LIMIT ?"#,
+            conversation_id,
+            limit
+        )
         .fetch_all(&self.pool)
         .await?;
         let mut messages: Vec<Message> = rows.into_iter().map(Message::from).collect();
@@ -245,15 +428,20 @@ impl MessageRepository {
         &self,
         conversation_ids: &[String],
         limit_per_conv: i64,
+        projection: &MessageProjection,
     ) -> Result<HashMap<String, Vec<Message>>, sqlx::Error> {
-        if conversation_ids.is_empty() {
+        if conversation_ids.is_empty() || limit_per_conv <= 0 {
             return Ok(HashMap::new());
         }
+        let cols = match projection {
+            MessageProjection::Full => SELECT_COLS,
+            MessageProjection::Slim => SLIM_COLS,
+        };
 
         let placeholders: Vec<&str> = conversation_ids.iter().map(|_| "?").collect();
         let sql = format!(
             "WITH RankedMessages AS (
-                SELECT {SELECT_COLS},
+                SELECT {cols},
                        ROW_NUMBER() OVER (
                            PARTITION BY conversation_id ORDER BY created_at DESC
                        ) as rn
@@ -285,22 +473,121 @@ impl MessageRepository {
     }
 
     pub async fn count_by_conversation(&self, conversation_id: &str) -> Result<i64, sqlx::Error> {
-        let count: (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM messages WHERE conversation_id = ?")
-                .bind(conversation_id)
-                .fetch_one(&self.pool)
-                .await?;
-        Ok(count.0)
+        sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM messages WHERE conversation_id = ?",
+            conversation_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Counts, first/last timestamps and streaks in one pass over the conversation.
+    pub async fn conversation_stats(
+        &self,
+        conversation_id: &str,
+    ) -> Result<ConversationStats, sqlx::Error> {
+        let row: (i64, i64, i64, Option<String>, Option<String>, i64, i64) =
+            sqlx::query_as(
+                "WITH m AS (
+                     SELECT role, created_at, date(created_at) AS day
+                     FROM messages WHERE conversation_id = ?
+                 ),
+                 user_days AS (SELECT DISTINCT day FROM m WHERE role = 'user'),
+                 streaks AS (
+                     SELECT MAX(day) AS last_day, COUNT(*) AS length
+                     FROM (SELECT day, julianday(day) - ROW_NUMBER() OVER (ORDER BY day) AS grp
+                           FROM user_days)
+                     GROUP BY grp
+                 )
+                 SELECT COALESCE(SUM(CASE WHEN role = 'user' THEN 1 ELSE 0 END), 0),
+                        COALESCE(SUM(CASE WHEN role = 'assistant' THEN 1 ELSE 0 END), 0),
+                        COALESCE(SUM(CASE WHEN day = date('now') THEN 1 ELSE 0 END), 0),
+                        MIN(created_at), MAX(created_at),
+                        COALESCE((SELECT length FROM streaks WHERE last_day >= date('now', '-1 day')), 0),
+                        COALESCE((SELECT MAX(length) FROM streaks), 0)
+                 FROM m",
+            )
+            .bind(conversation_id)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(ConversationStats {
+            user_messages: row.0,
+            assistant_messages: row.1,
+            messages_today: row.2,
+            first_message_at: row.3.as_deref().map(parse_dt),
+            last_message_at: row.4.as_deref().map(parse_dt),
+            current_streak_days: row.5,
+            longest_streak_days: row.6,
+        })
     }
 
     pub async fn count_unread(&self, conversation_id: &str) -> Result<i64, sqlx::Error> {
-        let count: (i64,) = sqlx::query_as(
+        sqlx::query_scalar!(
             "SELECT COUNT(*) FROM messages WHERE conversation_id = ? AND is_read = 0 AND role = 'assistant'",
+            conversation_id
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// User messages the bot side (the bot or its operator) hasn't read.
+    pub async fn count_unread_by_bot(&self, conversation_id: &str) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM messages WHERE conversation_id = ? AND is_read = 0 AND role = 'user'",
         )
         .bind(conversation_id)
         .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Failed replies from the last `hours` hours with fewer than `max_attempts`
+    /// attempts, oldest first.
+    pub async fn list_failed_replies(
+        &self,
+        hours: i32,
+        max_attempts: u32,
+        limit: i64,
+    ) -> Result<Vec<Message>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, MessageRow>(&format!(
+            "SELECT {SELECT_COLS} FROM messages
+             WHERE role = 'assistant' AND status = 'failed'
+               AND created_at >= datetime('now', ?)
+               AND COALESCE(json_extract(metadata, '$.failed_generation.attempts'), 0) < ?
+             ORDER BY created_at ASC
+             LIMIT ?"
+        ))
+        .bind(format!("-{hours} hours"))
+        .bind(max_attempts)
+        .bind(limit)
+        .fetch_all(&self.pool)
         .await?;
-        Ok(count.0)
+        Ok(rows.into_iter().map(Message::from).collect())
+    }
+
+    /// Whether `storage_key` is attached to a message in a conversation `user_id`
+    /// created or participates in.
+    pub async fn user_can_access_media(
+        &self,
+        user_id: &str,
+        storage_key: &str,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT EXISTS (
+                SELECT 1 FROM messages m
+                JOIN conversations c ON c.id = m.conversation_id
+                WHERE (c.user_id = ? OR EXISTS (
+                        SELECT 1 FROM conversation_participants p
+                        WHERE p.conversation_id = c.id AND p.user_id = ?))
+                  AND (m.audio_url = ? OR EXISTS (
+                        SELECT 1 FROM json_each(m.media_urls) WHERE json_each.value = ?))
+            )",
+        )
+        .bind(user_id)
+        .bind(user_id)
+        .bind(storage_key)
+        .bind(storage_key)
+        .fetch_one(&self.pool)
+        .await
     }
 }
 
@@ -313,21 +600,21 @@ pub struct MessageRepository {
 
 #[cfg(not(feature = "staging"))]
 #[derive(sqlx::FromRow)]
-struct PgMessageRow {
-    id: String,
-    conversation_id: String,
-    role: String,
-    content: Option<String>,
-    message_type: String,
-    media_urls: serde_json::Value,
-    audio_url: Option<String>,
-    audio_duration_seconds: Option<i32>,
-    token_count: Option<i32>,
-    client_message_id: Option<String>,
-    created_at: chrono::NaiveDateTime,
-    metadata: serde_json::Value,
-    status: Option<String>,
-    is_read: Option<bool>,
+pub(super) struct PgMessageRow {
+    pub(super) id: String,
+    pub(super) conversation_id: String,
+    pub(super) role: String,
+    pub(super) content: Option<String>,
+    pub(super) message_type: String,
+    pub(super) media_urls: serde_json::Value,
+    pub(super) audio_url: Option<String>,
+    pub(super) audio_duration_seconds: Option<i32>,
+    pub(super) token_count: Option<i32>,
+    pub(super) client_message_id: Option<String>,
+    pub(super) created_at: chrono::NaiveDateTime,
+    pub(super) metadata: serde_json::Value,
+    pub(super) status: Option<String>,
+    pub(super) is_read: Option<bool>,
 }
 
 #[cfg(not(feature = "staging"))]
@@ -357,6 +644,13 @@ const SELECT_COLS: &str = "id, conversation_id, role, content, message_type, med
      audio_duration_seconds, token_count, client_message_id, created_at, metadata,
      status, is_read";
 
+/// Same shape as `SELECT_COLS`, but media, audio and metadata aren't read.
+#[cfg(not(feature = "staging"))]
+const SLIM_COLS: &str = "id, conversation_id, role, content, message_type,
+     '[]'::jsonb AS media_urls, NULL::text AS audio_url, NULL::int AS audio_duration_seconds,
+     NULL::int AS token_count, NULL::text AS client_message_id, created_at,
+     '{}'::jsonb AS metadata, status, is_read";
+
 #[cfg(not(feature = "staging"))]
 impl MessageRepository {
     pub fn new(pg_pool: PgPool) -> Self {
@@ -382,32 +676,37 @@ impl MessageRepository {
         let media_urls_json =
             serde_json::to_value(media_urls).unwrap_or(serde_json::Value::Array(vec![]));
 
-        sqlx::query(
+        // The inbox is ordered by updated_at, so the bump lands with the insert
+        let mut tx = self.pg_pool.begin().await?;
+        let role = role.as_ref();
+        let message_type = message_type.as_ref();
+        sqlx::query!(
             "INSERT INTO messages (
                 id, conversation_id, role, content, message_type,
                 media_urls, audio_url, audio_duration_seconds, token_count,
                 client_message_id, status, is_read
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, 'delivered', FALSE)",
+            message_id,
+            conversation_id,
+            role,
+            content,
+            message_type,
+            media_urls_json,
+            audio_url,
+            audio_duration_seconds,
+            token_count,
+            client_message_id,
         )
-        .bind(&message_id)
-        .bind(conversation_id)
-        .bind(role.as_ref())
-        .bind(content)
-        .bind(message_type.as_ref())
-        .bind(&media_urls_json)
-        .bind(audio_url)
-        .bind(audio_duration_seconds)
-        .bind(token_count)
-        .bind(client_message_id)
-        .bind("delivered")
-        .bind(false)
-        .execute(&self.pg_pool)
+        .execute(&mut *tx)
         .await?;
 
-        sqlx::query("UPDATE conversations SET updated_at = NOW() WHERE id = $1")
-            .bind(conversation_id)
-            .execute(&self.pg_pool)
-            .await?;
+        sqlx::query!(
+            "UPDATE conversations SET updated_at = NOW() WHERE id = $1",
+            conversation_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
 
         self.get_by_id(&message_id)
             .await?
@@ -415,28 +714,130 @@ impl MessageRepository {
     }
 
     pub async fn delete_by_conversation(&self, conversation_id: &str) -> Result<i64, sqlx::Error> {
-        let count: (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM messages WHERE conversation_id = $1")
-                .bind(conversation_id)
-                .fetch_one(&self.pg_pool)
-                .await?;
-
-        if count.0 > 0 {
-            sqlx::query("DELETE FROM messages WHERE conversation_id = $1")
-                .bind(conversation_id)
-                .execute(&self.pg_pool)
-                .await?;
+        let count = self.count_by_conversation(conversation_id).await?;
+
+        if count > 0 {
+            sqlx::query!(
+                "DELETE FROM messages WHERE conversation_id = $1",
+                conversation_id
+            )
+            .execute(&self.pg_pool)
+            .await?;
         }
 
-        Ok(count.0)
+        Ok(count)
     }
 
-    pub async fn mark_as_read(&self, conversation_id: &str) -> Result<(), sqlx::Error> {
+    pub async fn update_metadata(
+        &self,
+        message_id: &str,
+        metadata: &serde_json::Value,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE messages SET metadata = $1 WHERE id = $2",
+            metadata,
+            message_id
+        )
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Set one top-level metadata key, leaving the rest of the object untouched.
+    pub async fn set_metadata_key(
+        &self,
+        message_id: &str,
+        key: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), sqlx::Error> {
         sqlx::query(
-            "UPDATE messages SET is_read = TRUE, status = 'read'
+            "UPDATE messages
+             SET metadata = jsonb_set(COALESCE(metadata, '{}'::jsonb), ARRAY[$1], $2)
+             WHERE id = $3",
+        )
+        .bind(key)
+        .bind(value)
+        .bind(message_id)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Swap the greeting in an influencer's conversations that hold nothing but
+    /// the greeting, i.e. the user hasn't replied yet.
+    pub async fn replace_unanswered_greetings(
+        &self,
+        influencer_id: &str,
+        greeting: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE messages SET content = $1
+             WHERE role = 'assistant' AND conversation_id IN (
+                 SELECT m.conversation_id FROM messages m
+                 JOIN conversations c ON c.id = m.conversation_id
+                 WHERE c.influencer_id = $2
+                 GROUP BY m.conversation_id
+                 HAVING COUNT(*) = 1
+             )",
+        )
+        .bind(greeting)
+        .bind(influencer_id)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn mark_failed(&self, message_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE messages SET status = 'failed' WHERE id = $1")
+            .bind(message_id)
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Fill in a failed reply once generation succeeds. Returns `false` if the
+    /// message was no longer failed (another retry got there first).
+    pub async fn complete_failed(
+        &self,
+        message_id: &str,
+        content: &str,
+        token_count: i32,
+        metadata: &serde_json::Value,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pg_pool.begin().await?;
+        let result = sqlx::query(
+            "UPDATE messages
+             SET content = $1, token_count = $2, metadata = $3, status = 'delivered', is_read = FALSE
+             WHERE id = $4 AND status = 'failed'",
+        )
+        .bind(content)
+        .bind(token_count)
+        .bind(metadata)
+        .bind(message_id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+        // The recovered reply is new to the user, so the conversation moves up the inbox
+        sqlx::query(
+            "UPDATE conversations SET updated_at = NOW()
+             WHERE id = (SELECT conversation_id FROM messages WHERE id = $1)",
+        )
+        .bind(message_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn mark_as_read(&self, conversation_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE messages
+             SET is_read = TRUE, status = CASE WHEN status = 'failed' THEN status ELSE 'read' END
              WHERE conversation_id = $1 AND is_read = FALSE AND role = 'assistant'",
+            conversation_id
         )
-        .bind(conversation_id)
         .execute(&self.pg_pool)
         .await?;
         Ok(())
@@ -445,10 +846,15 @@ impl MessageRepository {
     // ── Reads ─────────────────────────────────────────────────────────────────
 
     pub async fn get_by_id(&self, message_id: &str) -> Result<Option<Message>, sqlx::Error> {
-        let row = sqlx::query_as::<_, PgMessageRow>(&format!(
-            "SELECT {SELECT_COLS} FROM messages WHERE id = $1"
-        ))
-        .bind(message_id)
+        let row = sqlx::query_as!(
+            PgMessageRow,
+            r#"SELECT id, conversation_id, role, content, message_type,
+                    media_urls AS "media_urls!", audio_url, audio_duration_seconds, token_count,
+                    client_message_id, created_at AS "created_at!", metadata AS "metadata!",
+                    status, is_read
+             FROM messages WHERE id = $1"#,
+            message_id
+        )
         .fetch_optional(&self.pg_pool)
         .await?;
         Ok(row.map(Message::from))
@@ -459,12 +865,16 @@ impl MessageRepository {
         conversation_id: &str,
         client_message_id: &str,
     ) -> Result<Option<Message>, sqlx::Error> {
-        let row = sqlx::query_as::<_, PgMessageRow>(&format!(
-            "SELECT {SELECT_COLS} FROM messages
-             WHERE conversation_id = $1 AND client_message_id = $2"
-        ))
-        .bind(conversation_id)
-        .bind(client_message_id)
+        let row = sqlx::query_as!(
+            PgMessageRow,
+            r#"SELECT id, conversation_id, role, content, message_type,
+                    media_urls AS "media_urls!", audio_url, audio_duration_seconds, token_count,
+                    client_message_id, created_at AS "created_at!", metadata AS "metadata!",
+                    status, is_read
+             FROM messages WHERE conversation_id = $1 AND client_message_id = $2"#,
+            conversation_id,
+            client_message_id
+        )
         .fetch_optional(&self.pg_pool)
         .await?;
         Ok(row.map(Message::from))
@@ -478,15 +888,20 @@ impl MessageRepository {
             Some(m) => m,
             None => return Ok(None),
         };
-        let row = sqlx::query_as::<_, PgMessageRow>(&format!(
-            "SELECT {SELECT_COLS} FROM messages
+        let row = sqlx::query_as!(
+            PgMessageRow,
+            r#"SELECT id, conversation_id, role, content, message_type,
+                    media_urls AS "media_urls!", audio_url, audio_duration_seconds, token_count,
+                    client_message_id, created_at AS "created_at!", metadata AS "metadata!",
+                    status, is_read
+             FROM messages
              WHERE conversation_id = $1 AND role = 'assistant'
                AND created_at >= $2 AND id != $3
-             ORDER BY created_at ASC LIMIT 1"
-        ))
-        .bind(&msg.conversation_id)
-        .bind(msg.created_at)
-        .bind(message_id)
+             ORDER BY created_at ASC LIMIT 1"#,
+            msg.conversation_id,
+            msg.created_at,
+            message_id
+        )
         .fetch_optional(&self.pg_pool)
         .await?;
         Ok(row.map(Message::from))
@@ -515,18 +930,55 @@ impl MessageRepository {
         Ok(rows.into_iter().map(Message::from).collect())
     }
 
+    /// Messages from `from_id` through `to_id` inclusive (open ends reach the
+    /// first and latest message), oldest first. Ordered by `(created_at, id)`
+    /// so messages written at the same instant still fall on one side of an end.
+    pub async fn list_between(
+        &self,
+        conversation_id: &str,
+        from_id: Option<&str>,
+        to_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Message>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            PgMessageRow,
+            r#"SELECT id, conversation_id, role, content, message_type,
+                    media_urls AS "media_urls!", audio_url, audio_duration_seconds, token_count,
+                    client_message_id, created_at AS "created_at!", metadata AS "metadata!",
+                    status, is_read
+             FROM messages
+             WHERE conversation_id = $1
+             AND ($2::text IS NULL OR (created_at, id) >= (SELECT created_at, id FROM messages WHERE id = $2))
+             AND ($3::text IS NULL OR (created_at, id) <= (SELECT created_at, id FROM messages WHERE id = $3))
+             ORDER BY created_at ASC, id ASC
+             LIMIT $4"#,
+            conversation_id,
+            from_id,
+            to_id,
+            limit
+        )
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows.into_iter().map(Message::from).collect())
+    }
+
     pub async fn get_recent_for_context(
         &self,
         conversation_id: &str,
         limit: i64,
     ) -> Result<Vec<Message>, sqlx::Error> {
-        let rows = sqlx::query_as::<_, PgMessageRow>(&format!(
-            "SELECT {SELECT_COLS} FROM messages
+        let rows = sqlx::query_as!(
+            PgMessageRow,
+            r#"SELECT id, conversation_id, role, content, message_type,
+                    media_urls AS "media_urls!", audio_url, audio_duration_seconds, token_count,
+                    client_message_id, created_at AS "created_at!", metadata AS "metadata!",
+                    status, is_read
+             FROM messages
              WHERE conversation_id = $1
-             ORDER BY created_at DESC LIMIT $2"
-        ))
-        .bind(conversation_id)
-        .bind(limit)
+             ORDER BY created_at DESC LIMIT $2"#,
+            conversation_id,
+            limit
+        )
         .fetch_all(&self.pg_pool)
         .await?;
         let mut messages: Vec<Message> = rows.into_iter().map(Message::from).collect();
@@ -538,14 +990,19 @@ impl MessageRepository {
         &self,
         conversation_ids: &[String],
         limit_per_conv: i64,
+        projection: &MessageProjection,
     ) -> Result<HashMap<String, Vec<Message>>, sqlx::Error> {
-        if conversation_ids.is_empty() {
+        if conversation_ids.is_empty() || limit_per_conv <= 0 {
             return Ok(HashMap::new());
         }
+        let cols = match projection {
+            MessageProjection::Full => SELECT_COLS,
+            MessageProjection::Slim => SLIM_COLS,
+        };
 
         let rows = sqlx::query_as::<_, PgMessageRow>(&format!(
             "WITH RankedMessages AS (
-                SELECT {SELECT_COLS},
+                SELECT {cols},
                        ROW_NUMBER() OVER (
                            PARTITION BY conversation_id ORDER BY created_at DESC
                        ) as rn
@@ -573,21 +1030,126 @@ impl MessageRepository {
     }
 
     pub async fn count_by_conversation(&self, conversation_id: &str) -> Result<i64, sqlx::Error> {
-        let count: (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM messages WHERE conversation_id = $1")
-                .bind(conversation_id)
-                .fetch_one(&self.pg_pool)
-                .await?;
-        Ok(count.0)
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM messages WHERE conversation_id = $1"#,
+            conversation_id
+        )
+        .fetch_one(&self.pg_pool)
+        .await
+    }
+
+    /// Counts, first/last timestamps and streaks in one pass over the conversation.
+    pub async fn conversation_stats(
+        &self,
+        conversation_id: &str,
+    ) -> Result<ConversationStats, sqlx::Error> {
+        let row: (
+            i64,
+            i64,
+            i64,
+            Option<chrono::NaiveDateTime>,
+            Option<chrono::NaiveDateTime>,
+            i64,
+            i64,
+        ) = sqlx::query_as(
+            "WITH m AS (
+                 SELECT role, created_at, created_at::date AS day
+                 FROM messages WHERE conversation_id = $1
+             ),
+             user_days AS (SELECT DISTINCT day FROM m WHERE role = 'user'),
+             streaks AS (
+                 SELECT MAX(day) AS last_day, COUNT(*) AS length
+                 FROM (SELECT day, day - (ROW_NUMBER() OVER (ORDER BY day))::int AS grp
+                       FROM user_days) d
+                 GROUP BY grp
+             ),
+             today AS (SELECT (NOW() AT TIME ZONE 'UTC')::date AS day)
+             SELECT COUNT(*) FILTER (WHERE role = 'user'),
+                    COUNT(*) FILTER (WHERE role = 'assistant'),
+                    COUNT(*) FILTER (WHERE m.day = (SELECT day FROM today)),
+                    MIN(created_at), MAX(created_at),
+                    COALESCE((SELECT length FROM streaks
+                              WHERE last_day >= (SELECT day FROM today) - 1), 0),
+                    COALESCE((SELECT MAX(length) FROM streaks), 0)
+             FROM m",
+        )
+        .bind(conversation_id)
+        .fetch_one(&self.pg_pool)
+        .await?;
+        Ok(ConversationStats {
+            user_messages: row.0,
+            assistant_messages: row.1,
+            messages_today: row.2,
+            first_message_at: row.3,
+            last_message_at: row.4,
+            current_streak_days: row.5,
+            longest_streak_days: row.6,
+        })
     }
 
     pub async fn count_unread(&self, conversation_id: &str) -> Result<i64, sqlx::Error> {
-        let count: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM messages WHERE conversation_id = $1 AND is_read = FALSE AND role = 'assistant'",
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM messages WHERE conversation_id = $1 AND is_read = FALSE AND role = 'assistant'"#,
+            conversation_id
+        )
+        .fetch_one(&self.pg_pool)
+        .await
+    }
+
+    /// User messages the bot side (the bot or its operator) hasn't read.
+    pub async fn count_unread_by_bot(&self, conversation_id: &str) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM messages WHERE conversation_id = $1 AND is_read = FALSE AND role = 'user'",
         )
         .bind(conversation_id)
         .fetch_one(&self.pg_pool)
+        .await
+    }
+
+    /// Failed replies from the last `hours` hours with fewer than `max_attempts`
+    /// attempts, oldest first.
+    pub async fn list_failed_replies(
+        &self,
+        hours: i32,
+        max_attempts: u32,
+        limit: i64,
+    ) -> Result<Vec<Message>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PgMessageRow>(&format!(
+            "SELECT {SELECT_COLS} FROM messages
+             WHERE role = 'assistant' AND status = 'failed'
+               AND created_at >= NOW() - make_interval(hours => $1)
+               AND COALESCE((metadata->'failed_generation'->>'attempts')::int, 0) < $2
+             ORDER BY created_at ASC
+             LIMIT $3"
+        ))
+        .bind(hours)
+        .bind(max_attempts as i32)
+        .bind(limit)
+        .fetch_all(&self.pg_pool)
         .await?;
-        Ok(count.0)
+        Ok(rows.into_iter().map(Message::from).collect())
+    }
+
+    /// Whether `storage_key` is attached to a message in a conversation `user_id`
+    /// created or participates in.
+    pub async fn user_can_access_media(
+        &self,
+        user_id: &str,
+        storage_key: &str,
+    ) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT EXISTS (
+                SELECT 1 FROM messages m
+                JOIN conversations c ON c.id = m.conversation_id
+                WHERE (c.user_id = $1 OR EXISTS (
+                        SELECT 1 FROM conversation_participants p
+                        WHERE p.conversation_id = c.id AND p.user_id = $1))
+                  AND (m.audio_url = $2 OR m.media_urls @> jsonb_build_array($2::text))
+            )",
+        )
+        .bind(user_id)
+        .bind(storage_key)
+        .fetch_one(&self.pg_pool)
+        .await
     }
 }
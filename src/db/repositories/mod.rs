@@ -1,10 +1,52 @@
+pub mod abuse_event_repository;
+pub mod ai_sample_repository;
+pub mod audio_upload_repository;
+pub mod audit_log_repository;
+pub mod change_log_repository;
 pub mod conversation_repository;
+pub mod digest_repository;
+pub mod image_quota_repository;
 pub mod influencer_repository;
+pub mod knowledge_repository;
+pub mod legacy_import_repository;
+pub mod message_embedding_repository;
 pub mod message_repository;
+pub mod notification_preferences_repository;
+pub mod participant_repository;
+pub mod presence_repository;
+pub mod revoked_token_repository;
+pub mod sandbox_repository;
+pub mod scheduled_reply_repository;
+pub mod share_repository;
+pub mod suggestion_repository;
+pub mod telegram_repository;
+pub mod upload_scan_repository;
+pub mod webhook_repository;
 
-pub use conversation_repository::ConversationRepository;
-pub use influencer_repository::InfluencerRepository;
+pub use abuse_event_repository::{AbuseEventFilter, AbuseEventRepository};
+pub use ai_sample_repository::AiSampleRepository;
+pub use audio_upload_repository::AudioUploadRepository;
+pub use audit_log_repository::{AuditLogFilter, AuditLogRepository};
+pub use change_log_repository::ChangeLogRepository;
+pub use conversation_repository::{ConversationRepository, UserConversationFilter};
+pub use digest_repository::DigestRepository;
+pub use image_quota_repository::ImageQuotaRepository;
+pub use influencer_repository::{InfluencerListFilter, InfluencerRepository};
+pub use knowledge_repository::{KnowledgeLimits, KnowledgeRepository};
+pub use legacy_import_repository::LegacyImportRepository;
+pub use message_embedding_repository::MessageEmbeddingRepository;
 pub use message_repository::MessageRepository;
+pub use notification_preferences_repository::NotificationPreferencesRepository;
+pub use participant_repository::ParticipantRepository;
+pub use presence_repository::PresenceRepository;
+pub use revoked_token_repository::RevokedTokenRepository;
+pub use sandbox_repository::SandboxRepository;
+pub use scheduled_reply_repository::ScheduledReplyRepository;
+pub use share_repository::ShareRepository;
+pub use suggestion_repository::SuggestionRepository;
+pub use telegram_repository::TelegramRepository;
+pub use upload_scan_repository::UploadScanRepository;
+pub use webhook_repository::WebhookRepository;
 
 /// Parse a SQLite datetime string into NaiveDateTime (staging only).
 #[cfg(feature = "staging")]
@@ -17,3 +59,15 @@ pub(crate) fn parse_dt(s: &str) -> chrono::NaiveDateTime {
 pub(crate) fn parse_json(s: &str) -> serde_json::Value {
     serde_json::from_str(s).unwrap_or(serde_json::Value::Object(Default::default()))
 }
+
+/// Embeddings are stored as little-endian f32 bytes.
+pub(crate) fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+pub(crate) fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
@@ -0,0 +1,285 @@
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+
+use crate::models::entities::NotificationPreferences;
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct NotificationPreferencesRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct NotificationPreferencesRow {
+    user_id: String,
+    quiet_hours_start: String,
+    quiet_hours_end: String,
+    timezone: String,
+    quiet_hours_digest: bool,
+    deferred_since: Option<String>,
+    updated_at: String,
+}
+
+#[cfg(feature = "staging")]
+impl From<NotificationPreferencesRow> for NotificationPreferences {
+    fn from(row: NotificationPreferencesRow) -> Self {
+        Self {
+            user_id: row.user_id,
+            quiet_hours_start: row.quiet_hours_start,
+            quiet_hours_end: row.quiet_hours_end,
+            timezone: row.timezone,
+            quiet_hours_digest: row.quiet_hours_digest,
+            deferred_since: row.deferred_since.as_deref().map(parse_dt),
+            updated_at: parse_dt(&row.updated_at),
+        }
+    }
+}
+
+#[cfg(feature = "staging")]
+const SELECT_COLS: &str = "user_id, quiet_hours_start, quiet_hours_end, timezone, \
+                           quiet_hours_digest, deferred_since, updated_at";
+
+#[cfg(feature = "staging")]
+impl NotificationPreferencesRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn upsert_quiet_hours(
+        &self,
+        user_id: &str,
+        start: &str,
+        end: &str,
+        timezone: &str,
+        digest: bool,
+    ) -> Result<NotificationPreferences, sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO notification_preferences
+                (user_id, quiet_hours_start, quiet_hours_end, timezone, quiet_hours_digest)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT (user_id) DO UPDATE
+             SET quiet_hours_start = excluded.quiet_hours_start,
+                 quiet_hours_end = excluded.quiet_hours_end,
+                 timezone = excluded.timezone,
+                 quiet_hours_digest = excluded.quiet_hours_digest,
+                 updated_at = datetime('now')",
+        )
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .bind(timezone)
+        .bind(digest)
+        .execute(&self.pool)
+        .await?;
+
+        self.get(user_id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn remove(&self, user_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM notification_preferences WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Note that a push was held back; keeps the time of the first one in the window.
+    pub async fn mark_deferred(&self, user_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE notification_preferences
+             SET deferred_since = COALESCE(deferred_since, datetime('now'))
+             WHERE user_id = ?",
+        )
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn clear_deferred(&self, user_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE notification_preferences SET deferred_since = NULL WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(&self, user_id: &str) -> Result<Option<NotificationPreferences>, sqlx::Error> {
+        let row = sqlx::query_as::<_, NotificationPreferencesRow>(&format!(
+            "SELECT {SELECT_COLS} FROM notification_preferences WHERE user_id = ?"
+        ))
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(NotificationPreferences::from))
+    }
+
+    /// Users with pushes held back by quiet hours, oldest first.
+    pub async fn list_deferred(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<NotificationPreferences>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, NotificationPreferencesRow>(&format!(
+            "SELECT {SELECT_COLS} FROM notification_preferences
+             WHERE deferred_since IS NOT NULL
+             ORDER BY deferred_since ASC
+             LIMIT ?"
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(NotificationPreferences::from)
+            .collect())
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct NotificationPreferencesRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgNotificationPreferencesRow {
+    user_id: String,
+    quiet_hours_start: String,
+    quiet_hours_end: String,
+    timezone: String,
+    quiet_hours_digest: bool,
+    deferred_since: Option<chrono::NaiveDateTime>,
+    updated_at: chrono::NaiveDateTime,
+}
+
+#[cfg(not(feature = "staging"))]
+impl From<PgNotificationPreferencesRow> for NotificationPreferences {
+    fn from(row: PgNotificationPreferencesRow) -> Self {
+        Self {
+            user_id: row.user_id,
+            quiet_hours_start: row.quiet_hours_start,
+            quiet_hours_end: row.quiet_hours_end,
+            timezone: row.timezone,
+            quiet_hours_digest: row.quiet_hours_digest,
+            deferred_since: row.deferred_since,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+const SELECT_COLS: &str = "user_id, quiet_hours_start, quiet_hours_end, timezone, \
+                           quiet_hours_digest, deferred_since, updated_at";
+
+#[cfg(not(feature = "staging"))]
+impl NotificationPreferencesRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn upsert_quiet_hours(
+        &self,
+        user_id: &str,
+        start: &str,
+        end: &str,
+        timezone: &str,
+        digest: bool,
+    ) -> Result<NotificationPreferences, sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO notification_preferences
+                (user_id, quiet_hours_start, quiet_hours_end, timezone, quiet_hours_digest)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (user_id) DO UPDATE
+             SET quiet_hours_start = EXCLUDED.quiet_hours_start,
+                 quiet_hours_end = EXCLUDED.quiet_hours_end,
+                 timezone = EXCLUDED.timezone,
+                 quiet_hours_digest = EXCLUDED.quiet_hours_digest,
+                 updated_at = NOW()",
+        )
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .bind(timezone)
+        .bind(digest)
+        .execute(&self.pg_pool)
+        .await?;
+
+        self.get(user_id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn remove(&self, user_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM notification_preferences WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Note that a push was held back; keeps the time of the first one in the window.
+    pub async fn mark_deferred(&self, user_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE notification_preferences
+             SET deferred_since = COALESCE(deferred_since, NOW())
+             WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn clear_deferred(&self, user_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE notification_preferences SET deferred_since = NULL WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(&self, user_id: &str) -> Result<Option<NotificationPreferences>, sqlx::Error> {
+        let row = sqlx::query_as::<_, PgNotificationPreferencesRow>(&format!(
+            "SELECT {SELECT_COLS} FROM notification_preferences WHERE user_id = $1"
+        ))
+        .bind(user_id)
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Ok(row.map(NotificationPreferences::from))
+    }
+
+    /// Users with pushes held back by quiet hours, oldest first.
+    pub async fn list_deferred(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<NotificationPreferences>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PgNotificationPreferencesRow>(&format!(
+            "SELECT {SELECT_COLS} FROM notification_preferences
+             WHERE deferred_since IS NOT NULL
+             ORDER BY deferred_since ASC
+             LIMIT $1"
+        ))
+        .bind(limit)
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(NotificationPreferences::from)
+            .collect())
+    }
+}
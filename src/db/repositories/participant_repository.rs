@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+
+use crate::models::entities::{ConversationParticipant, ParticipantRole};
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct ParticipantRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct ParticipantRow {
+    conversation_id: String,
+    user_id: String,
+    role: String,
+    invited_by: String,
+    joined_at: String,
+    last_read_at: Option<String>,
+}
+
+#[cfg(feature = "staging")]
+impl From<ParticipantRow> for ConversationParticipant {
+    fn from(row: ParticipantRow) -> Self {
+        Self {
+            conversation_id: row.conversation_id,
+            user_id: row.user_id,
+            role: row.role.parse().unwrap_or(ParticipantRole::Viewer),
+            invited_by: row.invited_by,
+            joined_at: parse_dt(&row.joined_at),
+            last_read_at: row.last_read_at.as_deref().map(parse_dt),
+        }
+    }
+}
+
+#[cfg(feature = "staging")]
+const SELECT_COLS: &str = "conversation_id, user_id, role, invited_by, joined_at, last_read_at";
+
+#[cfg(feature = "staging")]
+impl ParticipantRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    /// Add a participant, or change the role of an existing one.
+    pub async fn upsert(
+        &self,
+        conversation_id: &str,
+        user_id: &str,
+        role: &ParticipantRole,
+        invited_by: &str,
+    ) -> Result<ConversationParticipant, sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO conversation_participants (conversation_id, user_id, role, invited_by)
+             VALUES (?, ?, ?, ?)
+             ON CONFLICT (conversation_id, user_id) DO UPDATE SET role = excluded.role",
+        )
+        .bind(conversation_id)
+        .bind(user_id)
+        .bind(role.as_ref())
+        .bind(invited_by)
+        .execute(&self.pool)
+        .await?;
+
+        self.get(conversation_id, user_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn remove(&self, conversation_id: &str, user_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM conversation_participants WHERE conversation_id = ? AND user_id = ?",
+        )
+        .bind(conversation_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn mark_read(&self, conversation_id: &str, user_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE conversation_participants SET last_read_at = datetime('now')
+             WHERE conversation_id = ? AND user_id = ?",
+        )
+        .bind(conversation_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(
+        &self,
+        conversation_id: &str,
+        user_id: &str,
+    ) -> Result<Option<ConversationParticipant>, sqlx::Error> {
+        let row = sqlx::query_as::<_, ParticipantRow>(&format!(
+            "SELECT {SELECT_COLS} FROM conversation_participants
+             WHERE conversation_id = ? AND user_id = ?"
+        ))
+        .bind(conversation_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(ConversationParticipant::from))
+    }
+
+    pub async fn list_by_conversation(
+        &self,
+        conversation_id: &str,
+    ) -> Result<Vec<ConversationParticipant>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ParticipantRow>(&format!(
+            "SELECT {SELECT_COLS} FROM conversation_participants
+             WHERE conversation_id = ? ORDER BY joined_at ASC"
+        ))
+        .bind(conversation_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(ConversationParticipant::from)
+            .collect())
+    }
+
+    /// Assistant messages each conversation received since the participant last read it
+    /// (or joined, if they never have). Compared as julian days since stored
+    /// timestamps mix "T" and space separators and fractional seconds.
+    pub async fn unread_counts(
+        &self,
+        user_id: &str,
+        conversation_ids: &[String],
+    ) -> Result<HashMap<String, i64>, sqlx::Error> {
+        if conversation_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let placeholders: Vec<&str> = conversation_ids.iter().map(|_| "?").collect();
+        let sql = format!(
+            "SELECT p.conversation_id, COUNT(m.id)
+             FROM conversation_participants p
+             LEFT JOIN messages m ON m.conversation_id = p.conversation_id
+                 AND m.role = 'assistant'
+                 AND julianday(m.created_at) > julianday(COALESCE(p.last_read_at, p.joined_at))
+             WHERE p.user_id = ? AND p.conversation_id IN ({})
+             GROUP BY p.conversation_id",
+            placeholders.join(", ")
+        );
+        let mut query = sqlx::query_as::<_, (String, i64)>(&sql).bind(user_id);
+        for id in conversation_ids {
+            query = query.bind(id);
+        }
+        Ok(query.fetch_all(&self.pool).await?.into_iter().collect())
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct ParticipantRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgParticipantRow {
+    conversation_id: String,
+    user_id: String,
+    role: String,
+    invited_by: String,
+    joined_at: chrono::NaiveDateTime,
+    last_read_at: Option<chrono::NaiveDateTime>,
+}
+
+#[cfg(not(feature = "staging"))]
+impl From<PgParticipantRow> for ConversationParticipant {
+    fn from(row: PgParticipantRow) -> Self {
+        Self {
+            conversation_id: row.conversation_id,
+            user_id: row.user_id,
+            role: row.role.parse().unwrap_or(ParticipantRole::Viewer),
+            invited_by: row.invited_by,
+            joined_at: row.joined_at,
+            last_read_at: row.last_read_at,
+        }
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+const SELECT_COLS: &str = "conversation_id, user_id, role, invited_by, joined_at, last_read_at";
+
+#[cfg(not(feature = "staging"))]
+impl ParticipantRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    /// Add a participant, or change the role of an existing one.
+    pub async fn upsert(
+        &self,
+        conversation_id: &str,
+        user_id: &str,
+        role: &ParticipantRole,
+        invited_by: &str,
+    ) -> Result<ConversationParticipant, sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO conversation_participants (conversation_id, user_id, role, invited_by)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (conversation_id, user_id) DO UPDATE SET role = EXCLUDED.role",
+        )
+        .bind(conversation_id)
+        .bind(user_id)
+        .bind(role.as_ref())
+        .bind(invited_by)
+        .execute(&self.pg_pool)
+        .await?;
+
+        self.get(conversation_id, user_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn remove(&self, conversation_id: &str, user_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM conversation_participants WHERE conversation_id = $1 AND user_id = $2",
+        )
+        .bind(conversation_id)
+        .bind(user_id)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn mark_read(&self, conversation_id: &str, user_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE conversation_participants SET last_read_at = NOW()
+             WHERE conversation_id = $1 AND user_id = $2",
+        )
+        .bind(conversation_id)
+        .bind(user_id)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(
+        &self,
+        conversation_id: &str,
+        user_id: &str,
+    ) -> Result<Option<ConversationParticipant>, sqlx::Error> {
+        let row = sqlx::query_as::<_, PgParticipantRow>(&format!(
+            "SELECT {SELECT_COLS} FROM conversation_participants
+             WHERE conversation_id = $1 AND user_id = $2"
+        ))
+        .bind(conversation_id)
+        .bind(user_id)
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Ok(row.map(ConversationParticipant::from))
+    }
+
+    pub async fn list_by_conversation(
+        &self,
+        conversation_id: &str,
+    ) -> Result<Vec<ConversationParticipant>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PgParticipantRow>(&format!(
+            "SELECT {SELECT_COLS} FROM conversation_participants
+             WHERE conversation_id = $1 ORDER BY joined_at ASC"
+        ))
+        .bind(conversation_id)
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(ConversationParticipant::from)
+            .collect())
+    }
+
+    /// Assistant messages each conversation received since the participant last read it
+    /// (or joined, if they never have).
+    pub async fn unread_counts(
+        &self,
+        user_id: &str,
+        conversation_ids: &[String],
+    ) -> Result<HashMap<String, i64>, sqlx::Error> {
+        if conversation_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            "SELECT p.conversation_id, COUNT(m.id)
+             FROM conversation_participants p
+             LEFT JOIN messages m ON m.conversation_id = p.conversation_id
+                 AND m.role = 'assistant'
+                 AND m.created_at > COALESCE(p.last_read_at, p.joined_at)
+             WHERE p.user_id = $1 AND p.conversation_id = ANY($2)
+             GROUP BY p.conversation_id",
+        )
+        .bind(user_id)
+        .bind(conversation_ids.to_vec())
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows.into_iter().collect())
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct PresenceRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+impl PresenceRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn touch(&self, user_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO user_presence (user_id, last_seen_at) VALUES (?, datetime('now'))
+             ON CONFLICT (user_id) DO UPDATE SET last_seen_at = excluded.last_seen_at",
+        )
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn last_seen(&self, user_id: &str) -> Result<Option<NaiveDateTime>, sqlx::Error> {
+        let row: Option<String> =
+            sqlx::query_scalar("SELECT last_seen_at FROM user_presence WHERE user_id = ?")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(row.as_deref().map(parse_dt))
+    }
+
+    pub async fn last_seen_many(
+        &self,
+        user_ids: &[String],
+    ) -> Result<HashMap<String, NaiveDateTime>, sqlx::Error> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let placeholders: Vec<&str> = user_ids.iter().map(|_| "?").collect();
+        let sql = format!(
+            "SELECT user_id, last_seen_at FROM user_presence WHERE user_id IN ({})",
+            placeholders.join(", ")
+        );
+        let mut query = sqlx::query_as::<_, (String, String)>(&sql);
+        for id in user_ids {
+            query = query.bind(id);
+        }
+        Ok(query
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|(id, at)| (id, parse_dt(&at)))
+            .collect())
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct PresenceRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+impl PresenceRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn touch(&self, user_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO user_presence (user_id, last_seen_at) VALUES ($1, NOW())
+             ON CONFLICT (user_id) DO UPDATE SET last_seen_at = excluded.last_seen_at",
+        )
+        .bind(user_id)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn last_seen(&self, user_id: &str) -> Result<Option<NaiveDateTime>, sqlx::Error> {
+        sqlx::query_scalar("SELECT last_seen_at FROM user_presence WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pg_pool)
+            .await
+    }
+
+    pub async fn last_seen_many(
+        &self,
+        user_ids: &[String],
+    ) -> Result<HashMap<String, NaiveDateTime>, sqlx::Error> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows = sqlx::query_as::<_, (String, NaiveDateTime)>(
+            "SELECT user_id, last_seen_at FROM user_presence WHERE user_id = ANY($1)",
+        )
+        .bind(user_ids.to_vec())
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows.into_iter().collect())
+    }
+}
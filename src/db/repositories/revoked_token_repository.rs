@@ -0,0 +1,118 @@
+use chrono::NaiveDateTime;
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct RevokedTokenRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+impl RevokedTokenRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    /// Revoke `jti` until `expires_at`; revoking again updates the expiry and reason.
+    pub async fn revoke(
+        &self,
+        jti: &str,
+        expires_at: NaiveDateTime,
+        reason: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO revoked_tokens (jti, expires_at, reason) VALUES (?, ?, ?)
+             ON CONFLICT (jti) DO UPDATE SET expires_at = excluded.expires_at, reason = excluded.reason",
+        )
+        .bind(jti)
+        .bind(expires_at.format("%Y-%m-%d %H:%M:%S").to_string())
+        .bind(reason)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn purge_expired(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM revoked_tokens WHERE expires_at <= datetime('now')")
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Revocations whose token hasn't expired yet, as (jti, expires_at).
+    pub async fn list_active(&self) -> Result<Vec<(String, NaiveDateTime)>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT jti, expires_at FROM revoked_tokens WHERE expires_at > datetime('now')",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(jti, at)| (jti, parse_dt(&at)))
+            .collect())
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct RevokedTokenRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+impl RevokedTokenRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    /// Revoke `jti` until `expires_at`; revoking again updates the expiry and reason.
+    pub async fn revoke(
+        &self,
+        jti: &str,
+        expires_at: NaiveDateTime,
+        reason: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO revoked_tokens (jti, expires_at, reason) VALUES ($1, $2, $3)
+             ON CONFLICT (jti) DO UPDATE SET expires_at = excluded.expires_at, reason = excluded.reason",
+        )
+        .bind(jti)
+        .bind(expires_at)
+        .bind(reason)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn purge_expired(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM revoked_tokens WHERE expires_at <= NOW()")
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Revocations whose token hasn't expired yet, as (jti, expires_at).
+    pub async fn list_active(&self) -> Result<Vec<(String, NaiveDateTime)>, sqlx::Error> {
+        sqlx::query_as::<_, (String, NaiveDateTime)>(
+            "SELECT jti, expires_at FROM revoked_tokens WHERE expires_at > NOW()",
+        )
+        .fetch_all(&self.pg_pool)
+        .await
+    }
+}
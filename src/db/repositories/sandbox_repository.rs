@@ -0,0 +1,214 @@
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+
+use crate::models::entities::{Message, SandboxConversation};
+
+const SELECT_COLS: &str =
+    "id, influencer_id, owner_id, source_conversation_id, system_instructions,
+     conversation, messages, created_at, expires_at";
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct SandboxRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct SandboxRow {
+    id: String,
+    influencer_id: String,
+    owner_id: String,
+    source_conversation_id: String,
+    system_instructions: String,
+    conversation: String,
+    messages: String,
+    created_at: String,
+    expires_at: String,
+}
+
+#[cfg(feature = "staging")]
+impl TryFrom<SandboxRow> for SandboxConversation {
+    type Error = serde_json::Error;
+
+    fn try_from(row: SandboxRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            influencer_id: row.influencer_id,
+            owner_id: row.owner_id,
+            source_conversation_id: row.source_conversation_id,
+            system_instructions: row.system_instructions,
+            conversation: serde_json::from_str(&row.conversation)?,
+            messages: serde_json::from_str(&row.messages)?,
+            created_at: parse_dt(&row.created_at),
+            expires_at: parse_dt(&row.expires_at),
+        })
+    }
+}
+
+#[cfg(feature = "staging")]
+impl SandboxRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn create(&self, sandbox: &SandboxConversation) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO sandbox_conversations
+             (id, influencer_id, owner_id, source_conversation_id, system_instructions,
+              conversation, messages, created_at, expires_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&sandbox.id)
+        .bind(&sandbox.influencer_id)
+        .bind(&sandbox.owner_id)
+        .bind(&sandbox.source_conversation_id)
+        .bind(&sandbox.system_instructions)
+        .bind(serde_json::to_string(&sandbox.conversation).unwrap_or_default())
+        .bind(serde_json::to_string(&sandbox.messages).unwrap_or_else(|_| "[]".into()))
+        .bind(sandbox.created_at.format("%Y-%m-%d %H:%M:%S").to_string())
+        .bind(sandbox.expires_at.format("%Y-%m-%d %H:%M:%S").to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_messages(&self, id: &str, messages: &[Message]) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE sandbox_conversations SET messages = ? WHERE id = ?")
+            .bind(serde_json::to_string(messages).unwrap_or_else(|_| "[]".into()))
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_expired(&self) -> Result<u64, sqlx::Error> {
+        let result =
+            sqlx::query("DELETE FROM sandbox_conversations WHERE expires_at <= datetime('now')")
+                .execute(&self.pool)
+                .await?;
+        Ok(result.rows_affected())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// The sandbox unless it has expired (or can't be decoded).
+    pub async fn get(&self, id: &str) -> Result<Option<SandboxConversation>, sqlx::Error> {
+        let row = sqlx::query_as::<_, SandboxRow>(&format!(
+            "SELECT {SELECT_COLS} FROM sandbox_conversations
+             WHERE id = ? AND expires_at > datetime('now')"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.and_then(|row| SandboxConversation::try_from(row).ok()))
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct SandboxRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgSandboxRow {
+    id: String,
+    influencer_id: String,
+    owner_id: String,
+    source_conversation_id: String,
+    system_instructions: String,
+    conversation: serde_json::Value,
+    messages: serde_json::Value,
+    created_at: chrono::NaiveDateTime,
+    expires_at: chrono::NaiveDateTime,
+}
+
+#[cfg(not(feature = "staging"))]
+impl TryFrom<PgSandboxRow> for SandboxConversation {
+    type Error = serde_json::Error;
+
+    fn try_from(row: PgSandboxRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            influencer_id: row.influencer_id,
+            owner_id: row.owner_id,
+            source_conversation_id: row.source_conversation_id,
+            system_instructions: row.system_instructions,
+            conversation: serde_json::from_value(row.conversation)?,
+            messages: serde_json::from_value(row.messages)?,
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+        })
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+impl SandboxRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn create(&self, sandbox: &SandboxConversation) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO sandbox_conversations
+             (id, influencer_id, owner_id, source_conversation_id, system_instructions,
+              conversation, messages, created_at, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(&sandbox.id)
+        .bind(&sandbox.influencer_id)
+        .bind(&sandbox.owner_id)
+        .bind(&sandbox.source_conversation_id)
+        .bind(&sandbox.system_instructions)
+        .bind(serde_json::to_value(&sandbox.conversation).unwrap_or_default())
+        .bind(serde_json::to_value(&sandbox.messages).unwrap_or_default())
+        .bind(sandbox.created_at)
+        .bind(sandbox.expires_at)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_messages(&self, id: &str, messages: &[Message]) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE sandbox_conversations SET messages = $1 WHERE id = $2")
+            .bind(serde_json::to_value(messages).unwrap_or_default())
+            .bind(id)
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_expired(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM sandbox_conversations WHERE expires_at <= NOW()")
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// The sandbox unless it has expired (or can't be decoded).
+    pub async fn get(&self, id: &str) -> Result<Option<SandboxConversation>, sqlx::Error> {
+        let row = sqlx::query_as::<_, PgSandboxRow>(&format!(
+            "SELECT {SELECT_COLS} FROM sandbox_conversations
+             WHERE id = $1 AND expires_at > NOW()"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Ok(row.and_then(|row| SandboxConversation::try_from(row).ok()))
+    }
+}
@@ -0,0 +1,171 @@
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+
+use crate::models::entities::ScheduledReply;
+
+const SELECT_COLS: &str = "message_id, conversation_id, user_id, influencer_id, due_at";
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct ScheduledReplyRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct ScheduledReplyRow {
+    message_id: String,
+    conversation_id: String,
+    user_id: String,
+    influencer_id: String,
+    due_at: String,
+}
+
+#[cfg(feature = "staging")]
+impl From<ScheduledReplyRow> for ScheduledReply {
+    fn from(row: ScheduledReplyRow) -> Self {
+        Self {
+            message_id: row.message_id,
+            conversation_id: row.conversation_id,
+            user_id: row.user_id,
+            influencer_id: row.influencer_id,
+            due_at: parse_dt(&row.due_at),
+        }
+    }
+}
+
+#[cfg(feature = "staging")]
+impl ScheduledReplyRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn schedule(&self, reply: &ScheduledReply) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO scheduled_replies (message_id, conversation_id, user_id, influencer_id, due_at)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT (message_id) DO UPDATE SET due_at = excluded.due_at",
+        )
+        .bind(&reply.message_id)
+        .bind(&reply.conversation_id)
+        .bind(&reply.user_id)
+        .bind(&reply.influencer_id)
+        .bind(reply.due_at.format("%Y-%m-%d %H:%M:%S").to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Take a reply off the schedule. `false` when it was already taken, e.g. by
+    /// another instance, so each reply is sent once.
+    pub async fn claim(&self, message_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM scheduled_replies WHERE message_id = ?")
+            .bind(message_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Replies whose time has come, oldest first.
+    pub async fn list_due(&self, limit: i64) -> Result<Vec<ScheduledReply>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ScheduledReplyRow>(&format!(
+            "SELECT {SELECT_COLS} FROM scheduled_replies
+             WHERE due_at <= datetime('now')
+             ORDER BY due_at ASC LIMIT ?"
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(ScheduledReply::from).collect())
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct ScheduledReplyRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgScheduledReplyRow {
+    message_id: String,
+    conversation_id: String,
+    user_id: String,
+    influencer_id: String,
+    due_at: chrono::NaiveDateTime,
+}
+
+#[cfg(not(feature = "staging"))]
+impl From<PgScheduledReplyRow> for ScheduledReply {
+    fn from(row: PgScheduledReplyRow) -> Self {
+        Self {
+            message_id: row.message_id,
+            conversation_id: row.conversation_id,
+            user_id: row.user_id,
+            influencer_id: row.influencer_id,
+            due_at: row.due_at,
+        }
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+impl ScheduledReplyRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn schedule(&self, reply: &ScheduledReply) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO scheduled_replies (message_id, conversation_id, user_id, influencer_id, due_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (message_id) DO UPDATE SET due_at = EXCLUDED.due_at",
+        )
+        .bind(&reply.message_id)
+        .bind(&reply.conversation_id)
+        .bind(&reply.user_id)
+        .bind(&reply.influencer_id)
+        .bind(reply.due_at)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Take a reply off the schedule. `false` when it was already taken, e.g. by
+    /// another instance, so each reply is sent once.
+    pub async fn claim(&self, message_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM scheduled_replies WHERE message_id = $1")
+            .bind(message_id)
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Replies whose time has come, oldest first.
+    pub async fn list_due(&self, limit: i64) -> Result<Vec<ScheduledReply>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PgScheduledReplyRow>(&format!(
+            "SELECT {SELECT_COLS} FROM scheduled_replies
+             WHERE due_at <= NOW()
+             ORDER BY due_at ASC LIMIT $1"
+        ))
+        .bind(limit)
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows.into_iter().map(ScheduledReply::from).collect())
+    }
+}
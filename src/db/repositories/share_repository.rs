@@ -0,0 +1,185 @@
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "staging")]
+use super::{parse_dt, parse_json};
+
+use crate::models::entities::ConversationShare;
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct ShareRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct ShareRow {
+    token: String,
+    conversation_id: String,
+    created_by: String,
+    snapshot: String,
+    created_at: String,
+    revoked_at: Option<String>,
+}
+
+#[cfg(feature = "staging")]
+impl From<ShareRow> for ConversationShare {
+    fn from(row: ShareRow) -> Self {
+        Self {
+            token: row.token,
+            conversation_id: row.conversation_id,
+            created_by: row.created_by,
+            snapshot: parse_json(&row.snapshot),
+            created_at: parse_dt(&row.created_at),
+            revoked_at: row.revoked_at.as_deref().map(parse_dt),
+        }
+    }
+}
+
+#[cfg(feature = "staging")]
+const SELECT_COLS: &str = "token, conversation_id, created_by, snapshot, created_at, revoked_at";
+
+#[cfg(feature = "staging")]
+impl ShareRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn create(
+        &self,
+        token: &str,
+        conversation_id: &str,
+        created_by: &str,
+        snapshot: &serde_json::Value,
+    ) -> Result<ConversationShare, sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO conversation_shares (token, conversation_id, created_by, snapshot)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(token)
+        .bind(conversation_id)
+        .bind(created_by)
+        .bind(snapshot.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        self.get(token).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn revoke(&self, token: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE conversation_shares SET revoked_at = datetime('now')
+             WHERE token = ? AND revoked_at IS NULL",
+        )
+        .bind(token)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(&self, token: &str) -> Result<Option<ConversationShare>, sqlx::Error> {
+        let row = sqlx::query_as::<_, ShareRow>(&format!(
+            "SELECT {SELECT_COLS} FROM conversation_shares WHERE token = ?"
+        ))
+        .bind(token)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(ConversationShare::from))
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct ShareRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgShareRow {
+    token: String,
+    conversation_id: String,
+    created_by: String,
+    snapshot: serde_json::Value,
+    created_at: chrono::NaiveDateTime,
+    revoked_at: Option<chrono::NaiveDateTime>,
+}
+
+#[cfg(not(feature = "staging"))]
+impl From<PgShareRow> for ConversationShare {
+    fn from(row: PgShareRow) -> Self {
+        Self {
+            token: row.token,
+            conversation_id: row.conversation_id,
+            created_by: row.created_by,
+            snapshot: row.snapshot,
+            created_at: row.created_at,
+            revoked_at: row.revoked_at,
+        }
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+const SELECT_COLS: &str = "token, conversation_id, created_by, snapshot, created_at, revoked_at";
+
+#[cfg(not(feature = "staging"))]
+impl ShareRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn create(
+        &self,
+        token: &str,
+        conversation_id: &str,
+        created_by: &str,
+        snapshot: &serde_json::Value,
+    ) -> Result<ConversationShare, sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO conversation_shares (token, conversation_id, created_by, snapshot)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(token)
+        .bind(conversation_id)
+        .bind(created_by)
+        .bind(snapshot)
+        .execute(&self.pg_pool)
+        .await?;
+
+        self.get(token).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn revoke(&self, token: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE conversation_shares SET revoked_at = NOW()
+             WHERE token = $1 AND revoked_at IS NULL",
+        )
+        .bind(token)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(&self, token: &str) -> Result<Option<ConversationShare>, sqlx::Error> {
+        let row = sqlx::query_as::<_, PgShareRow>(&format!(
+            "SELECT {SELECT_COLS} FROM conversation_shares WHERE token = $1"
+        ))
+        .bind(token)
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Ok(row.map(ConversationShare::from))
+    }
+}
@@ -0,0 +1,215 @@
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct SuggestionRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+impl SuggestionRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn record_tap(
+        &self,
+        influencer_id: &str,
+        user_id: &str,
+        conversation_id: &str,
+        message_id: &str,
+        suggestion: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO suggestion_taps
+                (id, influencer_id, user_id, conversation_id, message_id, suggestion)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(influencer_id)
+        .bind(user_id)
+        .bind(conversation_id)
+        .bind(message_id)
+        .bind(suggestion)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Taps per suggestion text over the last `days` days.
+    pub async fn tap_counts(
+        &self,
+        influencer_id: &str,
+        days: i32,
+    ) -> Result<Vec<(String, i64)>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT suggestion, COUNT(*) FROM suggestion_taps
+             WHERE influencer_id = ? AND created_at >= datetime('now', ?)
+             GROUP BY suggestion",
+        )
+        .bind(influencer_id)
+        .bind(format!("-{days} days"))
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Conversations started with the influencer over the last `days` days, and
+    /// how many of those had a suggestion tapped.
+    pub async fn conversation_counts(
+        &self,
+        influencer_id: &str,
+        days: i32,
+    ) -> Result<(i64, i64), sqlx::Error> {
+        sqlx::query_as(
+            "SELECT COUNT(*),
+                    COUNT(*) FILTER (WHERE EXISTS (
+                        SELECT 1 FROM suggestion_taps t WHERE t.conversation_id = c.id))
+             FROM conversations c
+             WHERE c.influencer_id = ? AND c.created_at >= datetime('now', ?)",
+        )
+        .bind(influencer_id)
+        .bind(format!("-{days} days"))
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Active influencers with suggestions that started at least
+    /// `min_conversations` conversations over the last `days` days.
+    pub async fn rotation_candidates(
+        &self,
+        days: i32,
+        min_conversations: i64,
+        limit: i64,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT c.influencer_id FROM conversations c
+             JOIN ai_influencers i ON i.id = c.influencer_id
+             WHERE c.created_at >= datetime('now', ?)
+               AND i.is_active = 'active'
+               AND json_array_length(i.suggested_messages) > 0
+             GROUP BY c.influencer_id
+             HAVING COUNT(*) >= ?
+             ORDER BY COUNT(*) DESC
+             LIMIT ?",
+        )
+        .bind(format!("-{days} days"))
+        .bind(min_conversations)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct SuggestionRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+impl SuggestionRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn record_tap(
+        &self,
+        influencer_id: &str,
+        user_id: &str,
+        conversation_id: &str,
+        message_id: &str,
+        suggestion: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO suggestion_taps
+                (id, influencer_id, user_id, conversation_id, message_id, suggestion)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(influencer_id)
+        .bind(user_id)
+        .bind(conversation_id)
+        .bind(message_id)
+        .bind(suggestion)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    /// Taps per suggestion text over the last `days` days.
+    pub async fn tap_counts(
+        &self,
+        influencer_id: &str,
+        days: i32,
+    ) -> Result<Vec<(String, i64)>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT suggestion, COUNT(*) FROM suggestion_taps
+             WHERE influencer_id = $1 AND created_at >= NOW() - make_interval(days => $2)
+             GROUP BY suggestion",
+        )
+        .bind(influencer_id)
+        .bind(days)
+        .fetch_all(&self.pg_pool)
+        .await
+    }
+
+    /// Conversations started with the influencer over the last `days` days, and
+    /// how many of those had a suggestion tapped.
+    pub async fn conversation_counts(
+        &self,
+        influencer_id: &str,
+        days: i32,
+    ) -> Result<(i64, i64), sqlx::Error> {
+        sqlx::query_as(
+            "SELECT COUNT(*),
+                    COUNT(*) FILTER (WHERE EXISTS (
+                        SELECT 1 FROM suggestion_taps t WHERE t.conversation_id = c.id))
+             FROM conversations c
+             WHERE c.influencer_id = $1 AND c.created_at >= NOW() - make_interval(days => $2)",
+        )
+        .bind(influencer_id)
+        .bind(days)
+        .fetch_one(&self.pg_pool)
+        .await
+    }
+
+    /// Active influencers with suggestions that started at least
+    /// `min_conversations` conversations over the last `days` days.
+    pub async fn rotation_candidates(
+        &self,
+        days: i32,
+        min_conversations: i64,
+        limit: i64,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT c.influencer_id FROM conversations c
+             JOIN ai_influencers i ON i.id = c.influencer_id
+             WHERE c.created_at >= NOW() - make_interval(days => $1)
+               AND i.is_active = 'active'
+               AND jsonb_array_length(i.suggested_messages) > 0
+             GROUP BY c.influencer_id
+             HAVING COUNT(*) >= $2
+             ORDER BY COUNT(*) DESC
+             LIMIT $3",
+        )
+        .bind(days)
+        .bind(min_conversations)
+        .bind(limit)
+        .fetch_all(&self.pg_pool)
+        .await
+    }
+}
@@ -0,0 +1,201 @@
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "staging")]
+use super::parse_dt;
+
+use crate::models::entities::TelegramBot;
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct TelegramRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct TelegramBotRow {
+    influencer_id: String,
+    bot_token: String,
+    bot_username: String,
+    webhook_secret: String,
+    created_by: String,
+    created_at: String,
+}
+
+#[cfg(feature = "staging")]
+impl From<TelegramBotRow> for TelegramBot {
+    fn from(row: TelegramBotRow) -> Self {
+        Self {
+            influencer_id: row.influencer_id,
+            bot_token: row.bot_token,
+            bot_username: row.bot_username,
+            webhook_secret: row.webhook_secret,
+            created_by: row.created_by,
+            created_at: parse_dt(&row.created_at),
+        }
+    }
+}
+
+#[cfg(feature = "staging")]
+const SELECT_COLS: &str =
+    "influencer_id, bot_token, bot_username, webhook_secret, created_by, created_at";
+
+#[cfg(feature = "staging")]
+impl TelegramRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    /// Connect a bot, replacing any bot previously connected to the influencer.
+    pub async fn upsert(
+        &self,
+        influencer_id: &str,
+        bot_token: &str,
+        bot_username: &str,
+        webhook_secret: &str,
+        created_by: &str,
+    ) -> Result<TelegramBot, sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO telegram_bots
+                 (influencer_id, bot_token, bot_username, webhook_secret, created_by)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT (influencer_id) DO UPDATE
+             SET bot_token = excluded.bot_token, bot_username = excluded.bot_username,
+                 webhook_secret = excluded.webhook_secret, created_by = excluded.created_by,
+                 created_at = datetime('now')",
+        )
+        .bind(influencer_id)
+        .bind(bot_token)
+        .bind(bot_username)
+        .bind(webhook_secret)
+        .bind(created_by)
+        .execute(&self.pool)
+        .await?;
+
+        self.get(influencer_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn remove(&self, influencer_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM telegram_bots WHERE influencer_id = ?")
+            .bind(influencer_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(&self, influencer_id: &str) -> Result<Option<TelegramBot>, sqlx::Error> {
+        let row = sqlx::query_as::<_, TelegramBotRow>(&format!(
+            "SELECT {SELECT_COLS} FROM telegram_bots WHERE influencer_id = ?"
+        ))
+        .bind(influencer_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(TelegramBot::from))
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct TelegramRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgTelegramBotRow {
+    influencer_id: String,
+    bot_token: String,
+    bot_username: String,
+    webhook_secret: String,
+    created_by: String,
+    created_at: chrono::NaiveDateTime,
+}
+
+#[cfg(not(feature = "staging"))]
+impl From<PgTelegramBotRow> for TelegramBot {
+    fn from(row: PgTelegramBotRow) -> Self {
+        Self {
+            influencer_id: row.influencer_id,
+            bot_token: row.bot_token,
+            bot_username: row.bot_username,
+            webhook_secret: row.webhook_secret,
+            created_by: row.created_by,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+const SELECT_COLS: &str =
+    "influencer_id, bot_token, bot_username, webhook_secret, created_by, created_at";
+
+#[cfg(not(feature = "staging"))]
+impl TelegramRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    /// Connect a bot, replacing any bot previously connected to the influencer.
+    pub async fn upsert(
+        &self,
+        influencer_id: &str,
+        bot_token: &str,
+        bot_username: &str,
+        webhook_secret: &str,
+        created_by: &str,
+    ) -> Result<TelegramBot, sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO telegram_bots
+                 (influencer_id, bot_token, bot_username, webhook_secret, created_by)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (influencer_id) DO UPDATE
+             SET bot_token = EXCLUDED.bot_token, bot_username = EXCLUDED.bot_username,
+                 webhook_secret = EXCLUDED.webhook_secret, created_by = EXCLUDED.created_by,
+                 created_at = NOW()",
+        )
+        .bind(influencer_id)
+        .bind(bot_token)
+        .bind(bot_username)
+        .bind(webhook_secret)
+        .bind(created_by)
+        .execute(&self.pg_pool)
+        .await?;
+
+        self.get(influencer_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn remove(&self, influencer_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM telegram_bots WHERE influencer_id = $1")
+            .bind(influencer_id)
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(&self, influencer_id: &str) -> Result<Option<TelegramBot>, sqlx::Error> {
+        let row = sqlx::query_as::<_, PgTelegramBotRow>(&format!(
+            "SELECT {SELECT_COLS} FROM telegram_bots WHERE influencer_id = $1"
+        ))
+        .bind(influencer_id)
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Ok(row.map(TelegramBot::from))
+    }
+}
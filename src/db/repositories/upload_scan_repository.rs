@@ -0,0 +1,74 @@
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+use crate::models::entities::UploadScan;
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct UploadScanRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+impl UploadScanRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(&self, scan: &UploadScan) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO upload_scans
+                (id, user_id, file_name, sha256, size, scanner, verdict, signature, storage_key)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&scan.id)
+        .bind(&scan.user_id)
+        .bind(&scan.file_name)
+        .bind(&scan.sha256)
+        .bind(scan.size)
+        .bind(&scan.scanner)
+        .bind(scan.verdict.as_ref())
+        .bind(&scan.signature)
+        .bind(&scan.storage_key)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct UploadScanRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+impl UploadScanRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    pub async fn record(&self, scan: &UploadScan) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO upload_scans
+                (id, user_id, file_name, sha256, size, scanner, verdict, signature, storage_key)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(&scan.id)
+        .bind(&scan.user_id)
+        .bind(&scan.file_name)
+        .bind(&scan.sha256)
+        .bind(scan.size)
+        .bind(&scan.scanner)
+        .bind(scan.verdict.as_ref())
+        .bind(&scan.signature)
+        .bind(&scan.storage_key)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+}
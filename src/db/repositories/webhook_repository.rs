@@ -0,0 +1,488 @@
+#[cfg(not(feature = "staging"))]
+use sqlx::PgPool;
+#[cfg(feature = "staging")]
+use sqlx::SqlitePool;
+
+use uuid::Uuid;
+
+#[cfg(feature = "staging")]
+use super::{parse_dt, parse_json};
+
+use crate::models::entities::{Webhook, WebhookDelivery, WebhookDeliveryStatus, WebhookEvent};
+
+// ── Staging: SQLite-only ──────────────────────────────────────────────────────
+
+#[cfg(feature = "staging")]
+pub struct WebhookRepository {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct WebhookRow {
+    id: String,
+    influencer_id: String,
+    url: String,
+    secret: String,
+    events: String,
+    is_active: i32,
+    created_by: String,
+    created_at: String,
+}
+
+#[cfg(feature = "staging")]
+impl From<WebhookRow> for Webhook {
+    fn from(row: WebhookRow) -> Self {
+        Self {
+            id: row.id,
+            influencer_id: row.influencer_id,
+            url: row.url,
+            secret: row.secret,
+            events: serde_json::from_str(&row.events).unwrap_or_default(),
+            is_active: row.is_active != 0,
+            created_by: row.created_by,
+            created_at: parse_dt(&row.created_at),
+        }
+    }
+}
+
+#[cfg(feature = "staging")]
+#[derive(sqlx::FromRow)]
+struct WebhookDeliveryRow {
+    id: String,
+    webhook_id: String,
+    event: String,
+    payload: String,
+    status: String,
+    attempts: i32,
+    response_status: Option<i32>,
+    error: Option<String>,
+    created_at: String,
+    completed_at: Option<String>,
+}
+
+#[cfg(feature = "staging")]
+impl From<WebhookDeliveryRow> for WebhookDelivery {
+    fn from(row: WebhookDeliveryRow) -> Self {
+        Self {
+            id: row.id,
+            webhook_id: row.webhook_id,
+            event: row.event,
+            payload: parse_json(&row.payload),
+            status: row.status.parse().unwrap_or(WebhookDeliveryStatus::Pending),
+            attempts: row.attempts,
+            response_status: row.response_status,
+            error: row.error,
+            created_at: parse_dt(&row.created_at),
+            completed_at: row.completed_at.as_deref().map(parse_dt),
+        }
+    }
+}
+
+#[cfg(feature = "staging")]
+const SELECT_COLS: &str =
+    "id, influencer_id, url, secret, events, is_active, created_by, created_at";
+
+#[cfg(feature = "staging")]
+const DELIVERY_COLS: &str =
+    "id, webhook_id, event, payload, status, attempts, response_status, error,
+     created_at, completed_at";
+
+#[cfg(feature = "staging")]
+impl WebhookRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn create(
+        &self,
+        influencer_id: &str,
+        url: &str,
+        secret: &str,
+        events: &[WebhookEvent],
+        created_by: &str,
+    ) -> Result<Webhook, sqlx::Error> {
+        let webhook_id = Uuid::new_v4().to_string();
+        let events_json = serde_json::to_string(events).unwrap_or("[]".to_string());
+
+        sqlx::query(
+            "INSERT INTO influencer_webhooks (id, influencer_id, url, secret, events, created_by)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&webhook_id)
+        .bind(influencer_id)
+        .bind(url)
+        .bind(secret)
+        .bind(&events_json)
+        .bind(created_by)
+        .execute(&self.pool)
+        .await?;
+
+        self.get(&webhook_id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn delete(&self, webhook_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM influencer_webhooks WHERE id = ?")
+            .bind(webhook_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn create_delivery(
+        &self,
+        webhook_id: &str,
+        event: &WebhookEvent,
+        payload: &serde_json::Value,
+    ) -> Result<String, sqlx::Error> {
+        let delivery_id = Uuid::new_v4().to_string();
+        let payload_json = serde_json::to_string(payload).unwrap_or("{}".to_string());
+
+        sqlx::query(
+            "INSERT INTO webhook_deliveries (id, webhook_id, event, payload) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&delivery_id)
+        .bind(webhook_id)
+        .bind(event.as_ref())
+        .bind(&payload_json)
+        .execute(&self.pool)
+        .await?;
+        Ok(delivery_id)
+    }
+
+    /// Record the outcome of a delivery attempt. Non-pending statuses are final.
+    pub async fn update_delivery(
+        &self,
+        delivery_id: &str,
+        status: &WebhookDeliveryStatus,
+        attempts: i32,
+        response_status: Option<i32>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE webhook_deliveries
+             SET status = ?, attempts = ?, response_status = ?, error = ?,
+                 completed_at = CASE WHEN ? = 'pending' THEN NULL ELSE datetime('now') END
+             WHERE id = ?",
+        )
+        .bind(status.as_ref())
+        .bind(attempts)
+        .bind(response_status)
+        .bind(error)
+        .bind(status.as_ref())
+        .bind(delivery_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(&self, webhook_id: &str) -> Result<Option<Webhook>, sqlx::Error> {
+        let row = sqlx::query_as::<_, WebhookRow>(&format!(
+            "SELECT {SELECT_COLS} FROM influencer_webhooks WHERE id = ?"
+        ))
+        .bind(webhook_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(Webhook::from))
+    }
+
+    pub async fn list_by_influencer(
+        &self,
+        influencer_id: &str,
+    ) -> Result<Vec<Webhook>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, WebhookRow>(&format!(
+            "SELECT {SELECT_COLS} FROM influencer_webhooks
+             WHERE influencer_id = ? ORDER BY created_at ASC"
+        ))
+        .bind(influencer_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Webhook::from).collect())
+    }
+
+    /// Active webhooks of an influencer that subscribe to `event`.
+    pub async fn list_subscribed(
+        &self,
+        influencer_id: &str,
+        event: &WebhookEvent,
+    ) -> Result<Vec<Webhook>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, WebhookRow>(&format!(
+            "SELECT {SELECT_COLS} FROM influencer_webhooks
+             WHERE influencer_id = ? AND is_active = 1
+               AND EXISTS (SELECT 1 FROM json_each(events) WHERE value = ?)"
+        ))
+        .bind(influencer_id)
+        .bind(event.as_ref())
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(Webhook::from).collect())
+    }
+
+    pub async fn list_deliveries(
+        &self,
+        webhook_id: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, WebhookDeliveryRow>(&format!(
+            "SELECT {DELIVERY_COLS} FROM webhook_deliveries
+             WHERE webhook_id = ? ORDER BY created_at DESC LIMIT ? OFFSET ?"
+        ))
+        .bind(webhook_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(WebhookDelivery::from).collect())
+    }
+
+    pub async fn count_deliveries(&self, webhook_id: &str) -> Result<i64, sqlx::Error> {
+        let count: (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM webhook_deliveries WHERE webhook_id = ?")
+                .bind(webhook_id)
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(count.0)
+    }
+}
+
+// ── Non-staging: PostgreSQL-only ──────────────────────────────────────────────
+
+#[cfg(not(feature = "staging"))]
+pub struct WebhookRepository {
+    pg_pool: PgPool,
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgWebhookRow {
+    id: String,
+    influencer_id: String,
+    url: String,
+    secret: String,
+    events: serde_json::Value,
+    is_active: bool,
+    created_by: String,
+    created_at: chrono::NaiveDateTime,
+}
+
+#[cfg(not(feature = "staging"))]
+impl From<PgWebhookRow> for Webhook {
+    fn from(row: PgWebhookRow) -> Self {
+        Self {
+            id: row.id,
+            influencer_id: row.influencer_id,
+            url: row.url,
+            secret: row.secret,
+            events: serde_json::from_value(row.events).unwrap_or_default(),
+            is_active: row.is_active,
+            created_by: row.created_by,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+#[derive(sqlx::FromRow)]
+struct PgWebhookDeliveryRow {
+    id: String,
+    webhook_id: String,
+    event: String,
+    payload: serde_json::Value,
+    status: String,
+    attempts: i32,
+    response_status: Option<i32>,
+    error: Option<String>,
+    created_at: chrono::NaiveDateTime,
+    completed_at: Option<chrono::NaiveDateTime>,
+}
+
+#[cfg(not(feature = "staging"))]
+impl From<PgWebhookDeliveryRow> for WebhookDelivery {
+    fn from(row: PgWebhookDeliveryRow) -> Self {
+        Self {
+            id: row.id,
+            webhook_id: row.webhook_id,
+            event: row.event,
+            payload: row.payload,
+            status: row.status.parse().unwrap_or(WebhookDeliveryStatus::Pending),
+            attempts: row.attempts,
+            response_status: row.response_status,
+            error: row.error,
+            created_at: row.created_at,
+            completed_at: row.completed_at,
+        }
+    }
+}
+
+#[cfg(not(feature = "staging"))]
+const SELECT_COLS: &str =
+    "id, influencer_id, url, secret, events, is_active, created_by, created_at";
+
+#[cfg(not(feature = "staging"))]
+const DELIVERY_COLS: &str =
+    "id, webhook_id, event, payload, status, attempts, response_status, error,
+     created_at, completed_at";
+
+#[cfg(not(feature = "staging"))]
+impl WebhookRepository {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    // ── Writes ────────────────────────────────────────────────────────────────
+
+    pub async fn create(
+        &self,
+        influencer_id: &str,
+        url: &str,
+        secret: &str,
+        events: &[WebhookEvent],
+        created_by: &str,
+    ) -> Result<Webhook, sqlx::Error> {
+        let webhook_id = Uuid::new_v4().to_string();
+
+        sqlx::query(
+            "INSERT INTO influencer_webhooks (id, influencer_id, url, secret, events, created_by)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(&webhook_id)
+        .bind(influencer_id)
+        .bind(url)
+        .bind(secret)
+        .bind(serde_json::to_value(events).unwrap_or_default())
+        .bind(created_by)
+        .execute(&self.pg_pool)
+        .await?;
+
+        self.get(&webhook_id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    pub async fn delete(&self, webhook_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM influencer_webhooks WHERE id = $1")
+            .bind(webhook_id)
+            .execute(&self.pg_pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn create_delivery(
+        &self,
+        webhook_id: &str,
+        event: &WebhookEvent,
+        payload: &serde_json::Value,
+    ) -> Result<String, sqlx::Error> {
+        let delivery_id = Uuid::new_v4().to_string();
+
+        sqlx::query(
+            "INSERT INTO webhook_deliveries (id, webhook_id, event, payload)
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(&delivery_id)
+        .bind(webhook_id)
+        .bind(event.as_ref())
+        .bind(payload)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(delivery_id)
+    }
+
+    /// Record the outcome of a delivery attempt. Non-pending statuses are final.
+    pub async fn update_delivery(
+        &self,
+        delivery_id: &str,
+        status: &WebhookDeliveryStatus,
+        attempts: i32,
+        response_status: Option<i32>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE webhook_deliveries
+             SET status = $1, attempts = $2, response_status = $3, error = $4,
+                 completed_at = CASE WHEN $1::VARCHAR = 'pending' THEN NULL ELSE NOW() END
+             WHERE id = $5",
+        )
+        .bind(status.as_ref())
+        .bind(attempts)
+        .bind(response_status)
+        .bind(error)
+        .bind(delivery_id)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    // ── Reads ─────────────────────────────────────────────────────────────────
+
+    pub async fn get(&self, webhook_id: &str) -> Result<Option<Webhook>, sqlx::Error> {
+        let row = sqlx::query_as::<_, PgWebhookRow>(&format!(
+            "SELECT {SELECT_COLS} FROM influencer_webhooks WHERE id = $1"
+        ))
+        .bind(webhook_id)
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Ok(row.map(Webhook::from))
+    }
+
+    pub async fn list_by_influencer(
+        &self,
+        influencer_id: &str,
+    ) -> Result<Vec<Webhook>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PgWebhookRow>(&format!(
+            "SELECT {SELECT_COLS} FROM influencer_webhooks
+             WHERE influencer_id = $1 ORDER BY created_at ASC"
+        ))
+        .bind(influencer_id)
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows.into_iter().map(Webhook::from).collect())
+    }
+
+    /// Active webhooks of an influencer that subscribe to `event`.
+    pub async fn list_subscribed(
+        &self,
+        influencer_id: &str,
+        event: &WebhookEvent,
+    ) -> Result<Vec<Webhook>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PgWebhookRow>(&format!(
+            "SELECT {SELECT_COLS} FROM influencer_webhooks
+             WHERE influencer_id = $1 AND is_active = TRUE AND events ? $2"
+        ))
+        .bind(influencer_id)
+        .bind(event.as_ref())
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows.into_iter().map(Webhook::from).collect())
+    }
+
+    pub async fn list_deliveries(
+        &self,
+        webhook_id: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PgWebhookDeliveryRow>(&format!(
+            "SELECT {DELIVERY_COLS} FROM webhook_deliveries
+             WHERE webhook_id = $1 ORDER BY created_at DESC LIMIT $2 OFFSET $3"
+        ))
+        .bind(webhook_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(rows.into_iter().map(WebhookDelivery::from).collect())
+    }
+
+    pub async fn count_deliveries(&self, webhook_id: &str) -> Result<i64, sqlx::Error> {
+        let count: (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM webhook_deliveries WHERE webhook_id = $1")
+                .bind(webhook_id)
+                .fetch_one(&self.pg_pool)
+                .await?;
+        Ok(count.0)
+    }
+}
@@ -1,15 +1,51 @@
 use axum::{
     Json,
-    http::StatusCode,
+    extract::multipart::MultipartError,
+    http::{HeaderMap, StatusCode, header},
     response::{IntoResponse, Response},
 };
+use std::collections::BTreeMap;
+
 use serde::Serialize;
 use utoipa::ToSchema;
+use validator::{ValidationErrors, ValidationErrorsKind};
+
+/// Messages per offending request field, e.g. `{"content": ["content exceeds 4000 characters"]}`.
+/// Nested fields use dotted paths and list items an index (`items[0].name`).
+pub type FieldErrors = BTreeMap<String, Vec<String>>;
+
+/// An existing influencer of the caller's that a new one closely resembles.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DuplicateCandidate {
+    pub influencer_id: String,
+    pub name: String,
+    pub display_name: String,
+    /// How alike the names are, 0.0-1.0
+    pub name_similarity: f32,
+    /// Cosine similarity of the system prompts; absent when it couldn't be computed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_similarity: Option<f32>,
+}
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ErrorBody {
     error: &'static str,
     message: String,
+    /// Present on validation errors that can be tied to request fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<FieldErrors>,
+    /// Seconds to wait before retrying; mirrors the `Retry-After` header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after: Option<u64>,
+    /// Which request rate limit was hit: `per_minute` or `per_hour`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit_type: Option<&'static str>,
+    /// The limit that was hit, in requests per `limit_type`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    /// The caller's existing influencers a new one looks like, most similar first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duplicates: Option<Vec<DuplicateCandidate>>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -23,12 +59,28 @@ pub enum AppError {
     #[error("{0}")]
     Unauthorized(String),
     #[error("{0}")]
-    ValidationError(String),
+    ValidationError(String, Option<FieldErrors>),
     #[error("{0}")]
     Conflict(String),
     #[error("{0}")]
+    DuplicateInfluencer(String, Vec<DuplicateCandidate>),
+    #[error("{0}")]
+    PayloadTooLarge(String),
+    #[error("{0}")]
+    RangeNotSatisfiable(String),
+    #[error("{0}")]
+    RateLimited(String, u64, &'static str, u32),
+    #[error("{0}")]
+    ImageQuotaExceeded(String, u64, Box<HeaderMap>),
+    #[error("{0}")]
+    ConversationThrottled(String, u64),
+    #[error("{0}")]
     ServiceUnavailable(String),
     #[error("{0}")]
+    Overloaded(String, u64),
+    #[error("{0}")]
+    AiTimeout(String),
+    #[error("{0}")]
     Database(String),
     #[error("Internal server error")]
     Internal(#[from] anyhow::Error),
@@ -48,27 +100,92 @@ impl AppError {
         Self::Unauthorized(msg.into())
     }
     pub fn validation_error(msg: impl Into<String>) -> Self {
-        Self::ValidationError(msg.into())
+        Self::ValidationError(msg.into(), None)
+    }
+    /// Validation error pinned to a single request field.
+    pub fn field_error(field: &str, msg: impl Into<String>) -> Self {
+        let msg = msg.into();
+        let details = FieldErrors::from([(field.to_string(), vec![msg.clone()])]);
+        Self::ValidationError(msg, Some(details))
     }
     pub fn conflict(msg: impl Into<String>) -> Self {
         Self::Conflict(msg.into())
     }
+    pub fn duplicate_influencer(
+        msg: impl Into<String>,
+        duplicates: Vec<DuplicateCandidate>,
+    ) -> Self {
+        Self::DuplicateInfluencer(msg.into(), duplicates)
+    }
+    pub fn payload_too_large(msg: impl Into<String>) -> Self {
+        Self::PayloadTooLarge(msg.into())
+    }
+    pub fn range_not_satisfiable(msg: impl Into<String>) -> Self {
+        Self::RangeNotSatisfiable(msg.into())
+    }
+    pub fn rate_limited(
+        msg: impl Into<String>,
+        retry_after: u64,
+        limit_type: &'static str,
+        limit: u32,
+    ) -> Self {
+        Self::RateLimited(msg.into(), retry_after, limit_type, limit)
+    }
+    /// `headers` describe the quota and are added to the response.
+    pub fn image_quota_exceeded(
+        msg: impl Into<String>,
+        retry_after: u64,
+        headers: HeaderMap,
+    ) -> Self {
+        Self::ImageQuotaExceeded(msg.into(), retry_after, Box::new(headers))
+    }
+    pub fn conversation_throttled(msg: impl Into<String>, retry_after: u64) -> Self {
+        Self::ConversationThrottled(msg.into(), retry_after)
+    }
     pub fn service_unavailable(msg: impl Into<String>) -> Self {
         Self::ServiceUnavailable(msg.into())
     }
+    pub fn overloaded(msg: impl Into<String>, retry_after: u64) -> Self {
+        Self::Overloaded(msg.into(), retry_after)
+    }
+    pub fn ai_timeout(msg: impl Into<String>) -> Self {
+        Self::AiTimeout(msg.into())
+    }
     pub fn database(msg: impl Into<String>) -> Self {
         Self::Database(msg.into())
     }
 
+    /// Failure while reading a multipart body. Hitting the body limit stays a 413
+    /// rather than being folded into a generic bad request.
+    pub fn multipart(context: &str, err: MultipartError) -> Self {
+        if err.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            Self::PayloadTooLarge(format!("{context}: request body is too large"))
+        } else {
+            Self::BadRequest(format!("{context}: {err}"))
+        }
+    }
+
     fn status_and_code(&self) -> (StatusCode, &'static str) {
         match self {
             Self::NotFound(_) => (StatusCode::NOT_FOUND, "not_found"),
             Self::Forbidden(_) => (StatusCode::FORBIDDEN, "forbidden"),
             Self::BadRequest(_) => (StatusCode::BAD_REQUEST, "bad_request"),
-            Self::ValidationError(_) => (StatusCode::UNPROCESSABLE_ENTITY, "validation_error"),
+            Self::ValidationError(..) => (StatusCode::UNPROCESSABLE_ENTITY, "validation_error"),
             Self::Unauthorized(_) => (StatusCode::UNAUTHORIZED, "unauthorized"),
             Self::Conflict(_) => (StatusCode::CONFLICT, "conflict"),
+            Self::DuplicateInfluencer(..) => (StatusCode::CONFLICT, "duplicate_influencer"),
+            Self::PayloadTooLarge(_) => (StatusCode::PAYLOAD_TOO_LARGE, "payload_too_large"),
+            Self::RangeNotSatisfiable(_) => {
+                (StatusCode::RANGE_NOT_SATISFIABLE, "range_not_satisfiable")
+            }
+            Self::RateLimited(..) => (StatusCode::TOO_MANY_REQUESTS, "rate_limit_exceeded"),
+            Self::ImageQuotaExceeded(..) => (StatusCode::TOO_MANY_REQUESTS, "image_quota_exceeded"),
+            Self::ConversationThrottled(..) => {
+                (StatusCode::TOO_MANY_REQUESTS, "conversation_throttled")
+            }
             Self::ServiceUnavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, "service_unavailable"),
+            Self::Overloaded(..) => (StatusCode::SERVICE_UNAVAILABLE, "overloaded"),
+            Self::AiTimeout(_) => (StatusCode::GATEWAY_TIMEOUT, "ai_timeout"),
             Self::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "database_error"),
             Self::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, "internal_error"),
         }
@@ -78,12 +195,51 @@ impl AppError {
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, code) = self.status_and_code();
-        sentry::capture_error(&self);
+        // Throttled and shed requests are expected under load, not failures worth reporting
+        if !matches!(
+            self,
+            Self::RateLimited(..)
+                | Self::ImageQuotaExceeded(..)
+                | Self::ConversationThrottled(..)
+                | Self::Overloaded(..)
+        ) {
+            sentry::capture_error(&self);
+        }
+        let message = self.to_string();
+        let (limit_type, limit) = match self {
+            Self::RateLimited(_, _, limit_type, limit) => (Some(limit_type), Some(limit)),
+            _ => (None, None),
+        };
+        let (details, retry_after, duplicates, quota_headers) = match self {
+            Self::ValidationError(_, details) => (details, None, None, None),
+            Self::ImageQuotaExceeded(_, retry_after, headers) => {
+                (None, Some(retry_after), None, Some(headers))
+            }
+            Self::RateLimited(_, retry_after, ..)
+            | Self::ConversationThrottled(_, retry_after)
+            | Self::Overloaded(_, retry_after) => (None, Some(retry_after), None, None),
+            Self::DuplicateInfluencer(_, duplicates) => (None, None, Some(duplicates), None),
+            _ => (None, None, None, None),
+        };
         let body = ErrorBody {
             error: code,
-            message: self.to_string(),
+            message,
+            details,
+            retry_after,
+            limit_type,
+            limit,
+            duplicates,
         };
-        (status, Json(body)).into_response()
+        let mut response = (status, Json(body)).into_response();
+        if let Some(secs) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, header::HeaderValue::from(secs));
+        }
+        if let Some(headers) = quota_headers {
+            response.headers_mut().extend(*headers);
+        }
+        response
     }
 }
 
@@ -93,3 +249,35 @@ impl From<sqlx::Error> for AppError {
         Self::Database("Database error".to_string())
     }
 }
+
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        let mut details = FieldErrors::new();
+        collect_field_errors(&errors, "", &mut details);
+        Self::ValidationError(errors.to_string(), Some(details))
+    }
+}
+
+fn collect_field_errors(errors: &ValidationErrors, prefix: &str, out: &mut FieldErrors) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{prefix}.{field}")
+        };
+        match kind {
+            ValidationErrorsKind::Field(errs) => {
+                out.entry(path).or_default().extend(
+                    errs.iter()
+                        .map(|e| e.message.as_deref().unwrap_or(&e.code).to_string()),
+                );
+            }
+            ValidationErrorsKind::Struct(inner) => collect_field_errors(inner, &path, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, inner) in items {
+                    collect_field_errors(inner, &format!("{path}[{index}]"), out);
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+mod cli;
 mod config;
 mod db;
 mod error;
@@ -5,42 +6,113 @@ mod middleware;
 mod models;
 mod routes;
 mod services;
+#[cfg(test)]
+mod test_support;
 
 use std::sync::Arc;
 use std::time::Instant;
 
 use axum::Router;
+use axum::extract::DefaultBodyLimit;
 use axum::http::header;
+use clap::Parser;
 use tower_http::compression::CompressionLayer;
 use tower_http::cors::{Any, CorsLayer};
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
 use tower_http::trace::TraceLayer;
 
-use config::Settings;
+use arc_swap::ArcSwap;
+use cli::{Cli, Command};
+use config::{Settings, SharedSettings};
 use db::Database;
-use services::ai::AiClient;
+use services::abuse_guard::AbuseGuard;
+use services::ai::{AiApi, AiClient, AiFixtureMode};
+use services::backup_verify::BackupVerifier;
+use services::caller_type::CallerTypeCache;
+use services::character_generator::CharacterGeneratorService;
+use services::conversation_throttle::ConversationThrottle;
+use services::digest::DigestSummaries;
+use services::duplicate_influencers::PromptEmbeddings;
+use services::email::EmailService;
 use services::google_chat::GoogleChatService;
-use services::notification::PushNotificationService;
-use services::replicate::ReplicateClient;
-use services::storage::StorageService;
+use services::image_quota::ImageQuota;
+use services::impressions::ImpressionBuffer;
+use services::influencer_cache::InfluencerCache;
+use services::knowledge::KnowledgeIndex;
+use services::leaderboard_cache::LeaderboardCache;
+use services::memory::MemoryMetrics;
+use services::notification::{PushApi, PushNotificationService};
+use services::pii::PiiPolicy;
+use services::presence::PresenceTracker;
+use services::profile_cache::ProfileCache;
+use services::prompt_cache::PromptCache;
+use services::push_batch::PushBatcher;
+use services::replicate::{ImageGen, ReplicateClient};
+use services::revocation::RevocationList;
+use services::sentry_alerts::AlertDeduper;
+use services::storage::{Storage, StorageService};
+use services::telegram::TelegramService;
+use services::tenants::TenantRegistry;
+use services::turn_debounce::TurnDebouncer;
+use services::upload_scan::UploadScanner;
 use services::websocket::WsManager;
 
 pub struct AppState {
     pub db: Database,
-    pub settings: Settings,
+    /// Swapped on reload; services that read it per request pick changes up.
+    /// These are the default tenant's; see `tenants` for the others
+    pub settings: SharedSettings,
+    pub tenants: Arc<TenantRegistry>,
     pub start_time: Instant,
     pub http_client: reqwest::Client,
-    pub storage: StorageService,
-    pub gemini: AiClient,
-    pub openrouter: AiClient,
-    pub replicate: ReplicateClient,
-    pub push_notifications: PushNotificationService,
+    pub storage: Arc<dyn Storage>,
+    pub gemini: Arc<dyn AiApi>,
+    pub openrouter: Arc<dyn AiApi>,
+    pub replicate: Arc<dyn ImageGen>,
+    pub push_notifications: Arc<dyn PushApi>,
+    pub push_batcher: Arc<PushBatcher>,
     pub ws_manager: Arc<WsManager>,
     pub ic_agent: ic_agent::Agent,
     pub google_chat: GoogleChatService,
+    pub email: EmailService,
+    pub telegram: TelegramService,
+    pub upload_scanner: UploadScanner,
+    pub influencer_cache: InfluencerCache,
+    pub caller_types: CallerTypeCache,
+    pub user_profiles: Arc<ProfileCache>,
+    pub character_generator: CharacterGeneratorService,
+    pub load_shed: middleware::LoadShedLimits,
+    pub pii_policy: PiiPolicy,
+    pub sentry_alerts: AlertDeduper,
+    pub presence: PresenceTracker,
+    pub revoked_tokens: RevocationList,
+    pub impressions: ImpressionBuffer,
+    pub memory_metrics: MemoryMetrics,
+    pub image_quota: ImageQuota,
+    pub system_prompts: PromptCache,
+    pub leaderboards: LeaderboardCache,
+    pub backup_verifier: BackupVerifier,
+    pub turn_debouncer: TurnDebouncer,
+    pub conversation_throttle: ConversationThrottle,
+    pub abuse_guard: AbuseGuard,
+    pub knowledge_index: KnowledgeIndex,
+    pub digest_summaries: DigestSummaries,
+    pub prompt_embeddings: PromptEmbeddings,
 }
 
 #[tokio::main]
 async fn main() {
+    let cli = Cli::parse();
+
+    // Needs no configuration, so it also runs from a bare checkout or in CI
+    if let Some(Command::ExportOpenapi { output }) = cli.command {
+        if let Err(e) = cli::export_openapi(output) {
+            eprintln!("export-openapi failed: {e:#}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Load .env file
     dotenvy::dotenv().ok();
 
@@ -56,6 +128,18 @@ async fn main() {
         ..Default::default()
     });
 
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve(settings).await,
+        command => {
+            if let Err(e) = cli::run(command, &settings).await {
+                tracing::error!(error = %format_args!("{e:#}"), "Command failed");
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+async fn serve(settings: Settings) {
     tracing::info!(
         app = %settings.app_name,
         version = %settings.app_version,
@@ -70,12 +154,7 @@ async fn main() {
     // Run migrations
     #[cfg(feature = "staging")]
     {
-        let migrations_dir = if std::path::Path::new("/app/migrations/sqlite").exists() {
-            "/app/migrations/sqlite"
-        } else {
-            "./migrations/sqlite"
-        };
-        db::run_migrations(&database.pool, migrations_dir)
+        db::run_migrations(&database.pool, db::migrations_dir())
             .await
             .expect("Failed to run SQLite migrations");
 
@@ -90,6 +169,9 @@ async fn main() {
     let storage = StorageService::new(&settings, http_client.clone())
         .expect("Failed to initialize storage service");
 
+    let shared_settings: SharedSettings = Arc::new(ArcSwap::from_pointee(settings.clone()));
+    let ai_queue_timeout = std::time::Duration::from_millis(settings.ai_queue_timeout_ms);
+    let pii_policy = PiiPolicy::from_settings(&settings);
     let gemini = AiClient::gemini(
         http_client.clone(),
         &settings.gemini_api_key,
@@ -97,7 +179,12 @@ async fn main() {
         settings.gemini_max_tokens,
         settings.gemini_temperature,
         settings.gemini_timeout,
-    );
+    )
+    .with_fixtures(settings.ai_fixture_mode, &settings.ai_fixture_dir)
+    .with_concurrency_limit(settings.gemini_max_concurrency, ai_queue_timeout)
+    .with_pii_redaction(pii_policy.clone())
+    .with_embedding_model(&settings.knowledge_embedding_model)
+    .with_live_defaults(shared_settings.clone(), Settings::gemini_defaults);
 
     let openrouter = AiClient::openrouter(
         http_client.clone(),
@@ -106,21 +193,174 @@ async fn main() {
         settings.openrouter_max_tokens,
         settings.openrouter_temperature,
         settings.openrouter_timeout,
-    );
+    )
+    .with_fixtures(settings.ai_fixture_mode, &settings.ai_fixture_dir)
+    .with_concurrency_limit(settings.openrouter_max_concurrency, ai_queue_timeout)
+    .with_pii_redaction(pii_policy.clone())
+    .with_live_defaults(shared_settings.clone(), Settings::openrouter_defaults);
+
+    if settings.ai_fixture_mode != AiFixtureMode::Off {
+        tracing::warn!(
+            mode = %settings.ai_fixture_mode,
+            dir = %settings.ai_fixture_dir,
+            "AI fixture mode enabled"
+        );
+    }
 
     let replicate = ReplicateClient::new(
         http_client.clone(),
         &settings.replicate_api_token,
         &settings.replicate_model,
+        &settings.replicate_video_model,
+    )
+    .with_daily_limits(
+        settings.replicate_daily_image_limit,
+        settings.replicate_daily_video_limit,
     );
 
-    let push_notifications = PushNotificationService::new(
+    let push_notifications: Arc<dyn PushApi> = Arc::new(PushNotificationService::new(
         http_client.clone(),
         &settings.metadata_url,
         settings.metadata_auth_token.clone(),
+    ));
+
+    let state = build_state(
+        &settings,
+        shared_settings,
+        database,
+        http_client,
+        ExternalClients {
+            storage: Arc::new(storage),
+            gemini: Arc::new(gemini),
+            openrouter: Arc::new(openrouter),
+            replicate: Arc::new(replicate),
+            push_notifications,
+        },
+    );
+
+    // Start periodic WAL checkpoint (every 5 minutes) - staging only
+    #[cfg(feature = "staging")]
+    Database::spawn_periodic_checkpoint(state.db.pool.clone(), 300);
+
+    // Start digest push scheduler
+    if settings.digest_enabled {
+        services::digest::spawn_digest_scheduler(
+            state.clone(),
+            settings.digest_check_interval_seconds,
+        );
+    }
+
+    // Follow up on pushes held back by quiet hours
+    services::quiet_hours::spawn_quiet_hours_flusher(
+        state.clone(),
+        settings.quiet_hours_check_interval_seconds,
+    );
+
+    // Replace fallback replies once the AI provider recovers
+    if settings.failed_reply_retry_enabled {
+        routes::chat::spawn_failed_reply_retrier(
+            state.clone(),
+            settings.failed_reply_retry_interval_seconds,
+            settings.failed_reply_max_attempts,
+        );
+    }
+
+    // Send replies held back while influencers were away
+    routes::chat::spawn_scheduled_reply_sender(
+        state.clone(),
+        settings.scheduled_reply_check_interval_seconds.max(1),
+    );
+
+    // Replace suggested messages that never get tapped
+    if settings.suggestion_rotation_enabled {
+        services::suggestion_rotation::spawn_suggestion_rotation(
+            state.clone(),
+            settings.suggestion_rotation_interval_seconds,
+            settings.suggestion_rotation_window_days,
+            settings.suggestion_rotation_min_conversations,
+        );
+    }
+
+    // Load revoked tokens before serving, then keep them in sync
+    if let Err(e) = state.revoked_tokens.refresh(&state.db).await {
+        tracing::error!(error = %e, "Failed to load token revocation list");
+    }
+    services::revocation::spawn_revocation_sync(
+        state.clone(),
+        settings.token_revocation_sync_interval_seconds.max(1),
+    );
+
+    // Write buffered profile impressions to view_count
+    services::impressions::spawn_impression_flusher(
+        state.clone(),
+        settings.impression_flush_interval_seconds.max(1),
+    );
+
+    // Keep the change log to its retention window
+    services::change_log::spawn_change_log_pruner(
+        state.clone(),
+        settings.change_log_prune_interval_seconds.max(1),
+        settings.change_log_retention_days.max(1),
+    );
+
+    // Expire sampled AI turns; runs even when sampling is off, as a reload can turn it on
+    services::ai_samples::spawn_ai_sample_pruner(
+        state.clone(),
+        settings.ai_sample_retention_days.max(1),
+    );
+
+    // Restore the latest Litestream backup now and then to prove it's usable
+    if settings.backup_verify_enabled {
+        services::backup_verify::spawn_backup_verifier(
+            state.clone(),
+            std::time::Duration::from_secs(settings.backup_verify_interval_hours.max(1) * 3600),
+        );
+    }
+
+    // Embed new messages for semantic search; idles while the setting is off
+    services::message_embeddings::spawn_message_embedder(
+        state.clone(),
+        settings.message_embedding_interval_seconds.max(1),
     );
 
-    let ws_manager = Arc::new(WsManager::new());
+    // Pick up edits to the reloadable settings on SIGHUP
+    #[cfg(unix)]
+    spawn_reload_on_sighup(state.tenants.clone());
+
+    let app = build_router(state);
+
+    // Start server
+    let addr = format!("{}:{}", settings.host, settings.port);
+    tracing::info!(address = %addr, "Server listening");
+
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .expect("Failed to bind address");
+
+    axum::serve(listener, app).await.expect("Server error");
+}
+
+/// Clients for the external services; tests substitute fakes.
+pub struct ExternalClients {
+    pub storage: Arc<dyn Storage>,
+    pub gemini: Arc<dyn AiApi>,
+    pub openrouter: Arc<dyn AiApi>,
+    pub replicate: Arc<dyn ImageGen>,
+    pub push_notifications: Arc<dyn PushApi>,
+}
+
+/// Application state around `clients`, with the rest built from `settings`.
+fn build_state(
+    settings: &Settings,
+    shared_settings: SharedSettings,
+    database: Database,
+    http_client: reqwest::Client,
+    clients: ExternalClients,
+) -> Arc<AppState> {
+    let ws_manager = Arc::new(WsManager::new(
+        settings.ws_queue_capacity,
+        std::time::Duration::from_secs(settings.ws_slow_client_timeout_seconds),
+    ));
 
     // Build IC agent for canister calls
     let ic_agent = ic_agent::Agent::builder()
@@ -133,34 +373,104 @@ async fn main() {
         settings.google_chat_webhook_url.clone(),
     );
 
-    // Build app state
-    let state = Arc::new(AppState {
+    let email = EmailService::new(
+        http_client.clone(),
+        &settings.metadata_url,
+        settings.metadata_auth_token.clone(),
+        settings.sendgrid_api_key.clone(),
+        &settings.email_domain,
+    );
+
+    let telegram = TelegramService::new(
+        http_client.clone(),
+        settings.telegram_webhook_base_url.clone(),
+    );
+
+    let upload_scanner = UploadScanner::new(
+        http_client.clone(),
+        settings.upload_scan_mode,
+        &settings.clamd_address,
+        settings.upload_scan_url.clone(),
+        settings.upload_scan_api_key.clone(),
+    );
+
+    Arc::new(AppState {
         db: database,
-        settings: settings.clone(),
+        tenants: Arc::new(TenantRegistry::new(&shared_settings)),
+        settings: shared_settings,
         start_time: Instant::now(),
         http_client: http_client.clone(),
-        storage,
-        gemini,
-        openrouter,
-        replicate,
-        push_notifications,
+        storage: clients.storage,
+        gemini: clients.gemini,
+        openrouter: clients.openrouter,
+        replicate: clients.replicate,
+        push_notifications: clients.push_notifications.clone(),
+        push_batcher: PushBatcher::new(
+            clients.push_notifications,
+            std::time::Duration::from_millis(settings.push_batch_window_ms),
+        ),
         ws_manager,
         ic_agent,
         google_chat,
-    });
-
-    // Start periodic WAL checkpoint (every 5 minutes) - staging only
-    #[cfg(feature = "staging")]
-    Database::spawn_periodic_checkpoint(state.db.pool.clone(), 300);
+        email,
+        telegram,
+        upload_scanner,
+        influencer_cache: InfluencerCache::new(std::time::Duration::from_secs(
+            settings.influencer_cache_ttl_seconds,
+        )),
+        caller_types: CallerTypeCache::new(
+            std::time::Duration::from_secs(settings.caller_type_cache_ttl_seconds),
+            std::time::Duration::from_secs(settings.caller_type_negative_ttl_seconds),
+            settings.caller_type_force_user,
+        ),
+        user_profiles: Arc::new(ProfileCache::new(
+            std::time::Duration::from_secs(settings.profile_cache_ttl_seconds),
+            std::time::Duration::from_secs(settings.profile_cache_stale_seconds),
+        )),
+        character_generator: CharacterGeneratorService::new(std::time::Duration::from_secs(
+            settings.character_cache_ttl_seconds,
+        )),
+        load_shed: middleware::LoadShedLimits::from_settings(settings),
+        pii_policy: PiiPolicy::from_settings(settings),
+        sentry_alerts: AlertDeduper::new(std::time::Duration::from_secs(
+            settings.sentry_alert_dedup_seconds,
+        )),
+        presence: PresenceTracker::new(std::time::Duration::from_secs(
+            settings.presence_write_interval_seconds.max(1),
+        )),
+        revoked_tokens: RevocationList::default(),
+        impressions: ImpressionBuffer::default(),
+        memory_metrics: MemoryMetrics::default(),
+        system_prompts: PromptCache::default(),
+        leaderboards: LeaderboardCache::new(std::time::Duration::from_secs(300)),
+        backup_verifier: BackupVerifier::new(settings),
+        turn_debouncer: TurnDebouncer::default(),
+        conversation_throttle: ConversationThrottle::default(),
+        abuse_guard: AbuseGuard::default(),
+        knowledge_index: KnowledgeIndex::default(),
+        digest_summaries: DigestSummaries::default(),
+        prompt_embeddings: PromptEmbeddings::default(),
+        image_quota: ImageQuota::new(
+            settings.image_gen_daily_limit,
+            std::time::Duration::from_secs(settings.image_gen_cooldown_seconds),
+        ),
+    })
+}
 
-    // Build CORS layer
+/// The full application router with its middleware stack, ready to serve.
+fn build_router(state: Arc<AppState>) -> Router {
+    let settings = state.settings.load_full();
     let cors = build_cors(&settings);
+    let shed = state.load_shed.clone();
 
     // Build router
-    use axum::routing::{delete, get, patch, post};
-    use routes::{chat, chat_v2, health, influencers, media, websocket};
+    use axum::routing::{delete, get, patch, post, put};
+    use routes::{
+        admin, alerts, chat, chat_v2, digest, email, health, influencers, knowledge, media,
+        notifications, sandbox, share, telegram, users, webhooks, websocket,
+    };
 
-    let app = Router::new()
+    Router::new()
         // Health
         .route("/", get(health::root))
         .route("/health", get(health::health))
@@ -171,17 +481,21 @@ async fn main() {
             "/api/v1/influencers/trending",
             get(influencers::list_trending),
         )
+        .route(
+            "/api/v1/influencers/leaderboard",
+            get(influencers::leaderboard),
+        )
         .route(
             "/api/v1/influencers/generate-prompt",
-            post(influencers::generate_prompt),
+            post(influencers::generate_prompt).layer(shed.ai.clone()),
         )
         .route(
             "/api/v1/influencers/validate-and-generate-metadata",
-            post(influencers::validate_and_generate_metadata),
+            post(influencers::validate_and_generate_metadata).layer(shed.ai.clone()),
         )
         .route(
             "/api/v1/influencers/create",
-            post(influencers::create_influencer),
+            post(influencers::create_influencer).layer(shed.ai.clone()),
         )
         .route(
             "/api/v1/influencers/{influencer_id}",
@@ -195,26 +509,163 @@ async fn main() {
             "/api/v1/admin/influencers/{influencer_id}/unban",
             post(influencers::admin_unban_influencer),
         )
+        .route(
+            "/api/v1/admin/influencers/{influencer_id}/badges",
+            put(influencers::admin_set_badges),
+        )
+        .route(
+            "/api/v1/admin/import/legacy",
+            post(admin::import_legacy)
+                .layer(DefaultBodyLimit::max(settings.legacy_import_max_bytes())),
+        )
+        .route("/api/v1/admin/audit-log", get(admin::list_audit_log))
+        .route("/api/v1/admin/changes", get(admin::list_changes))
+        .route("/api/v1/admin/config/reload", post(admin::reload_config))
+        .route("/api/v1/admin/db/verify-backup", post(admin::verify_backup))
+        .route(
+            "/api/v1/admin/replicate/predictions",
+            get(admin::list_predictions),
+        )
+        .route("/api/v1/admin/abuse-events", get(admin::list_abuse_events))
+        .route(
+            "/api/v1/admin/influencers/{influencer_id}/ai-samples",
+            get(admin::list_ai_samples),
+        )
+        .route(
+            "/api/v1/admin/influencers/{influencer_id}/ai-sampling",
+            put(admin::update_ai_sampling),
+        )
+        .route("/api/v1/admin/revoked-tokens", post(admin::revoke_token))
+        .route(
+            "/api/v1/admin/caller-types/{principal}",
+            delete(admin::invalidate_caller_type),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/impression",
+            post(influencers::record_impression),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/generation-status",
+            get(influencers::get_generation_status),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/starter-video",
+            post(influencers::render_starter_video),
+        )
         .route(
             "/api/v1/influencers/{influencer_id}/system-prompt",
             patch(influencers::update_system_prompt),
         )
+        .route(
+            "/api/v1/influencers/{influencer_id}/name",
+            patch(influencers::rename_influencer),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/sandbox",
+            post(sandbox::create_sandbox),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/sandbox/{sandbox_id}/messages",
+            post(sandbox::send_sandbox_message).layer(shed.ai.clone()),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/preview-chat",
+            post(sandbox::preview_chat).layer(shed.ai.clone()),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/publish-draft",
+            post(influencers::publish_draft),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/regenerate-greeting",
+            post(influencers::regenerate_greeting).layer(shed.ai.clone()),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/schedule",
+            get(influencers::get_schedule)
+                .put(influencers::update_schedule)
+                .delete(influencers::delete_schedule),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/media-only-prompt",
+            put(influencers::update_media_only_prompt),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/conversation-starters",
+            get(influencers::get_conversation_starters)
+                .put(influencers::update_conversation_starters),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/suggestions/stats",
+            get(influencers::get_suggestion_stats),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/regenerate-video-prompt",
+            post(influencers::regenerate_video_prompt).layer(shed.ai.clone()),
+        )
         .route(
             "/api/v1/influencers/{influencer_id}/generate-video-prompt",
-            post(influencers::generate_video_prompt),
+            post(influencers::generate_video_prompt).layer(shed.ai.clone()),
+        )
+        // Email gateway
+        .route(
+            "/api/v1/email/inbound",
+            post(email::inbound_email).layer(shed.ai.clone()),
+        )
+        // Telegram bridge
+        .route(
+            "/api/v1/influencers/{influencer_id}/telegram",
+            put(telegram::connect_bot)
+                .get(telegram::get_bot)
+                .delete(telegram::disconnect_bot),
+        )
+        .route(
+            "/api/v1/telegram/webhook/{influencer_id}",
+            post(telegram::telegram_webhook).layer(shed.ai.clone()),
+        )
+        // Webhooks
+        .route(
+            "/api/v1/influencers/{influencer_id}/webhooks",
+            post(webhooks::create_webhook).get(webhooks::list_webhooks),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/webhooks/{webhook_id}",
+            delete(webhooks::delete_webhook),
         )
+        .route(
+            "/api/v1/influencers/{influencer_id}/webhooks/{webhook_id}/deliveries",
+            get(webhooks::list_deliveries),
+        )
+        // Knowledge base
+        .route(
+            "/api/v1/influencers/{influencer_id}/knowledge",
+            post(knowledge::upload_document)
+                .layer(DefaultBodyLimit::max(
+                    settings.knowledge_upload_body_bytes(),
+                ))
+                .get(knowledge::list_documents),
+        )
+        .route(
+            "/api/v1/influencers/{influencer_id}/knowledge/{document_id}",
+            delete(knowledge::delete_document),
+        )
+        // Alerts
+        .route("/api/v1/webhooks/sentry", post(alerts::sentry_webhook))
         // Chat V1
         .route(
             "/api/v1/chat/conversations",
             post(chat::create_conversation).get(chat::list_conversations),
         )
+        .route("/api/v1/chat/conversations/duet", post(chat::create_duet))
+        .route("/api/v1/chat/resume", get(chat::resume))
+        .route("/api/v1/chat/semantic-search", get(chat::semantic_search))
         .route(
             "/api/v1/chat/conversations/{conversation_id}/messages",
-            get(chat::list_messages).post(chat::send_message),
+            get(chat::list_messages).merge(post(chat::send_message).layer(shed.ai.clone())),
         )
         .route(
             "/api/v1/chat/conversations/{conversation_id}",
-            delete(chat::delete_conversation),
+            patch(chat::update_conversation).delete(chat::delete_conversation),
         )
         .route(
             "/api/v1/chat/conversations/{conversation_id}/read",
@@ -222,43 +673,141 @@ async fn main() {
         )
         .route(
             "/api/v1/chat/conversations/{conversation_id}/images",
-            post(chat::generate_image),
+            post(chat::generate_image).layer(shed.ai.clone()),
+        )
+        .route(
+            "/api/v1/chat/conversations/{conversation_id}/debug-context",
+            get(chat::debug_context),
+        )
+        .route(
+            "/api/v1/chat/conversations/{conversation_id}/response-style",
+            patch(chat::update_response_style),
+        )
+        .route(
+            "/api/v1/chat/conversations/{conversation_id}/language",
+            patch(chat::update_language),
+        )
+        .route(
+            "/api/v1/chat/conversations/{conversation_id}/share",
+            post(share::create_share),
+        )
+        .route(
+            "/api/v1/chat/conversations/{conversation_id}/share/{token}",
+            delete(share::revoke_share),
+        )
+        .route("/api/v1/share/{token}", get(share::get_share))
+        .route(
+            "/api/v1/chat/conversations/{conversation_id}/takeover",
+            post(chat::start_takeover).delete(chat::end_takeover),
+        )
+        .route(
+            "/api/v1/chat/conversations/{conversation_id}/mute",
+            post(chat::mute_conversation).delete(chat::unmute_conversation),
+        )
+        .route(
+            "/api/v1/chat/conversations/{conversation_id}/participants",
+            post(chat::invite_participant).get(chat::list_participants),
+        )
+        .route(
+            "/api/v1/chat/conversations/{conversation_id}/participants/{principal}",
+            delete(chat::remove_participant),
+        )
+        .route("/api/v1/chat/messages/{message_id}", get(chat::get_message))
+        .route(
+            "/api/v1/chat/messages/{message_id}/retry",
+            post(chat::retry_message).layer(shed.ai.clone()),
+        )
+        .route(
+            "/api/v1/chat/messages/{message_id}/translate",
+            post(chat::translate_message).layer(shed.ai.clone()),
         )
         // Chat V2
         .route(
             "/api/v2/chat/conversations",
             get(chat_v2::list_conversations_v2),
         )
+        .route(
+            "/api/v2/chat/conversations/{conversation_id}",
+            delete(chat_v2::delete_bot_conversation),
+        )
+        .route(
+            "/api/v2/chat/conversations/{conversation_id}/messages",
+            get(chat_v2::list_bot_messages)
+                .merge(post(chat_v2::send_bot_reply).layer(shed.ai.clone())),
+        )
+        // Digest
+        .route(
+            "/api/v1/digest/subscription",
+            get(digest::get_subscription)
+                .put(digest::subscribe)
+                .delete(digest::unsubscribe),
+        )
+        .route("/api/v1/digest/preview", get(digest::preview))
+        .route(
+            "/api/v1/notifications/quiet-hours",
+            get(notifications::get_quiet_hours)
+                .put(notifications::set_quiet_hours)
+                .delete(notifications::clear_quiet_hours),
+        )
+        .route(
+            "/api/v1/users/{principal}/presence",
+            get(users::get_presence),
+        )
         // WebSocket
         .route("/api/v1/chat/ws/inbox/{user_id}", get(websocket::ws_inbox))
         .route("/api/v1/chat/ws/docs", get(websocket::ws_docs))
         // Media
-        .route("/api/v1/media/upload", post(media::upload_media))
+        .route(
+            "/api/v1/media/upload",
+            post(media::upload_media)
+                .layer(DefaultBodyLimit::max(settings.max_upload_body_bytes())),
+        )
+        .route("/api/v1/media/{*storage_key}", get(media::get_media))
         // OpenAPI / Swagger UI
         .merge(routes::openapi::swagger_ui())
         // Set Sentry transaction name to route pattern after routing
         .route_layer(axum::middleware::from_fn(
             middleware::sentry_transaction_name,
         ))
-        .layer(middleware::RateLimitLayer::new(
-            settings.rate_limit_per_minute,
-            settings.rate_limit_per_hour,
+        // Routes that take uploads raise this with their own layer
+        .layer(DefaultBodyLimit::max(settings.max_json_body_bytes()))
+        .layer(axum::middleware::map_response(
+            middleware::payload_too_large_body,
+        ))
+        .layer(shed.default)
+        .layer(middleware::RateLimitLayer::new(state.tenants.clone()))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::resolve_tenant,
         ))
         .layer(CompressionLayer::new())
         .layer(TraceLayer::new_for_http())
         .layer(cors)
+        // Keep the client's x-request-id or mint one, and echo it back
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
         .layer(sentry_tower::SentryHttpLayer::with_transaction())
-        .with_state(state);
-
-    // Start server
-    let addr = format!("{}:{}", settings.host, settings.port);
-    tracing::info!(address = %addr, "Server listening");
-
-    let listener = tokio::net::TcpListener::bind(&addr)
-        .await
-        .expect("Failed to bind address");
+        .with_state(state)
+}
 
-    axum::serve(listener, app).await.expect("Server error");
+/// Reload settings whenever the process receives SIGHUP, like the admin reload endpoint.
+#[cfg(unix)]
+fn spawn_reload_on_sighup(tenants: Arc<TenantRegistry>) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    tokio::spawn(async move {
+        let mut hangups = match signal(SignalKind::hangup()) {
+            Ok(hangups) => hangups,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to listen for SIGHUP, settings reload is admin-only");
+                return;
+            }
+        };
+        while hangups.recv().await.is_some() {
+            let changed = tenants.reload();
+            tracing::info!(?changed, "Settings reloaded on SIGHUP");
+        }
+    });
 }
 
 fn init_tracing(settings: &Settings) {
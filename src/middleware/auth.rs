@@ -1,13 +1,16 @@
+use std::sync::Arc;
+
 use axum::{
     Json,
-    extract::FromRequestParts,
-    http::{StatusCode, header::AUTHORIZATION, request::Parts},
+    extract::{FromRef, FromRequestParts, OptionalFromRequestParts},
+    http::{HeaderMap, StatusCode, header::AUTHORIZATION, request::Parts},
     response::{IntoResponse, Response},
 };
-use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, errors::ErrorKind};
 use serde::{Deserialize, Serialize};
 
-const EXPECTED_ISSUERS: &[&str] = &["https://auth.yral.com", "https://auth.dolr.ai"];
+use crate::AppState;
+use crate::config::Settings;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JwtPayload {
@@ -24,43 +27,159 @@ pub struct AuthenticatedUser {
     pub user_id: String,
 }
 
-/// Rejection type for auth errors that serializes as `{"detail": "..."}` to match Python's FastAPI.
-pub struct AuthRejection(pub StatusCode, pub String);
+/// Why a bearer token was refused; sent to clients as `reason` on 401s.
+#[derive(Debug, Clone, Copy, PartialEq, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum AuthFailure {
+    Missing,
+    Malformed,
+    Expired,
+    /// `iat` lies further in the future than the leeway allows
+    NotYetValid,
+    IssuerMismatch,
+    AudienceMismatch,
+    /// The token's jti is on the revocation list
+    Revoked,
+}
+
+#[derive(Debug)]
+pub struct JwtError {
+    pub reason: AuthFailure,
+    pub message: String,
+}
+
+impl std::fmt::Display for JwtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl JwtError {
+    fn new(reason: AuthFailure, message: impl Into<String>) -> Self {
+        Self {
+            reason,
+            message: message.into(),
+        }
+    }
+}
+
+impl AuthFailure {
+    /// Error code for the response: `token_expired` tells clients to refresh rather than
+    /// sign the user out.
+    pub fn error_code(self) -> &'static str {
+        match self {
+            Self::Expired => "token_expired",
+            _ => "unauthorized",
+        }
+    }
+}
+
+/// Rejection type for auth errors that serializes as
+/// `{"detail": "...", "error": "...", "reason": "..."}`; `detail` matches Python's FastAPI.
+pub struct AuthRejection(pub StatusCode, pub AuthFailure, pub String);
 
 impl IntoResponse for AuthRejection {
     fn into_response(self) -> Response {
-        tracing::error!(status = %self.0, error = %self.1, "Auth rejected");
-        (self.0, Json(serde_json::json!({"detail": self.1}))).into_response()
+        let reason: &'static str = self.1.into();
+        tracing::error!(status = %self.0, reason, error = %self.2, "Auth rejected");
+        (
+            self.0,
+            Json(serde_json::json!({
+                "detail": self.2,
+                "error": self.1.error_code(),
+                "reason": reason,
+            })),
+        )
+            .into_response()
+    }
+}
+
+impl From<JwtError> for AuthRejection {
+    fn from(e: JwtError) -> Self {
+        Self(StatusCode::UNAUTHORIZED, e.reason, e.message)
     }
 }
 
-/// Decode and validate a JWT token. Returns the claims payload or an error message string.
-pub fn decode_jwt(token: &str) -> Result<JwtPayload, String> {
+/// Whether the request carries a valid `X-Admin-Key` header.
+/// Always false when no admin key is configured.
+pub fn has_admin_key(headers: &HeaderMap, settings: &Settings) -> bool {
+    let provided_key = headers
+        .get("X-Admin-Key")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+
+    settings
+        .admin_key_to_delete_influencer
+        .as_deref()
+        .is_some_and(|key| provided_key == key)
+}
+
+/// Decode and validate a JWT token against the configured issuers and audiences.
+/// Returns the claims payload or why the token was refused.
+pub fn decode_jwt(token: &str, settings: &Settings) -> Result<JwtPayload, JwtError> {
     let mut validation = Validation::new(Algorithm::RS256);
     validation.insecure_disable_signature_validation();
-    validation.set_issuer(EXPECTED_ISSUERS);
-    validation.set_required_spec_claims(&["exp", "sub", "iss"]);
-    validation.validate_aud = false;
+    validation.leeway = settings.jwt_leeway_seconds;
+    validation.set_issuer(&settings.jwt_issuers_list());
+    let audiences = settings.jwt_audiences_list();
+    if audiences.is_empty() {
+        validation.set_required_spec_claims(&["exp", "sub", "iss"]);
+        validation.validate_aud = false;
+    } else {
+        validation.set_required_spec_claims(&["exp", "sub", "iss", "aud"]);
+        validation.set_audience(&audiences);
+    }
 
     let token_data = decode::<JwtPayload>(token, &DecodingKey::from_secret(b""), &validation)
-        .map_err(|e| format!("Invalid token: {e}"))?;
+        .map_err(|e| {
+            let reason = match e.kind() {
+                ErrorKind::ExpiredSignature => AuthFailure::Expired,
+                ErrorKind::InvalidIssuer => AuthFailure::IssuerMismatch,
+                ErrorKind::InvalidAudience => AuthFailure::AudienceMismatch,
+                _ => AuthFailure::Malformed,
+            };
+            JwtError::new(reason, format!("Invalid token: {e}"))
+        })?;
 
     let payload = token_data.claims;
 
     if payload.sub.is_empty() {
-        return Err("Invalid token: missing sub".to_string());
+        return Err(JwtError::new(
+            AuthFailure::Malformed,
+            "Invalid token: missing sub",
+        ));
+    }
+
+    // jsonwebtoken leaves `iat` alone; a token minted on a clock running ahead is fine
+    // within the leeway
+    if let Some(iat) = payload.iat
+        && iat > jsonwebtoken::get_current_timestamp() + settings.jwt_leeway_seconds
+    {
+        return Err(JwtError::new(
+            AuthFailure::NotYetValid,
+            "Invalid token: issued in the future",
+        ));
     }
 
     Ok(payload)
 }
 
+/// Whether the token was revoked before its expiry.
+pub fn is_revoked(state: &AppState, claims: &JwtPayload) -> bool {
+    claims
+        .jti
+        .as_deref()
+        .is_some_and(|jti| state.revoked_tokens.is_revoked(jti))
+}
+
 impl<S> FromRequestParts<S> for AuthenticatedUser
 where
+    Arc<AppState>: FromRef<S>,
     S: Send + Sync,
 {
     type Rejection = AuthRejection;
 
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let auth_header = parts
             .headers
             .get(AUTHORIZATION)
@@ -68,6 +187,7 @@ where
             .ok_or_else(|| {
                 AuthRejection(
                     StatusCode::UNAUTHORIZED,
+                    AuthFailure::Missing,
                     "Missing authorization header".to_string(),
                 )
             })?;
@@ -78,15 +198,45 @@ where
             .ok_or_else(|| {
                 AuthRejection(
                     StatusCode::UNAUTHORIZED,
+                    AuthFailure::Malformed,
                     "Invalid authorization header format. Expected: Bearer <token>".to_string(),
                 )
             })?;
 
-        let claims =
-            decode_jwt(token).map_err(|msg| AuthRejection(StatusCode::UNAUTHORIZED, msg))?;
+        let state = Arc::<AppState>::from_ref(state);
+        let claims = decode_jwt(token, &state.settings.load())?;
+        if is_revoked(&state, &claims) {
+            return Err(AuthRejection(
+                StatusCode::UNAUTHORIZED,
+                AuthFailure::Revoked,
+                "Token has been revoked".to_string(),
+            ));
+        }
 
         Ok(Self {
             user_id: claims.sub,
         })
     }
 }
+
+/// `Option<AuthenticatedUser>` resolves to `None` when no Authorization header is sent,
+/// but still rejects a malformed or invalid token.
+impl<S> OptionalFromRequestParts<S> for AuthenticatedUser
+where
+    Arc<AppState>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AuthRejection;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        if !parts.headers.contains_key(AUTHORIZATION) {
+            return Ok(None);
+        }
+        <Self as FromRequestParts<S>>::from_request_parts(parts, state)
+            .await
+            .map(Some)
+    }
+}
@@ -0,0 +1,20 @@
+use axum::{
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+
+use crate::error::AppError;
+
+/// Rewrites axum's plain-text 413 (raised by extractors when a route's
+/// `DefaultBodyLimit` is exceeded) into the standard `AppError` JSON body.
+pub async fn payload_too_large_body(response: Response) -> Response {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/json"));
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE || is_json {
+        return response;
+    }
+    AppError::payload_too_large("Request body is too large").into_response()
+}
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::http::{Request, Response};
+use axum::response::IntoResponse;
+use tokio::sync::Semaphore;
+use tower::{Layer, Service};
+
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::models::responses::LoadShedStats;
+
+/// Seconds a shed client is told to wait before retrying.
+const RETRY_AFTER_SECS: u64 = 5;
+
+const EXCLUDED_PATHS: &[&str] = &["/", "/health", "/status"];
+
+struct LoadShedState {
+    class: &'static str,
+    limit: usize,
+    permits: Semaphore,
+    queue_timeout: Duration,
+    shed: AtomicU64,
+}
+
+/// Caps in-flight requests for one route class. A request waits up to
+/// `queue_timeout` for a slot and is then rejected with a 503 and `Retry-After`,
+/// instead of queueing behind a saturated database pool or AI provider.
+/// A `limit` of 0 disables the cap.
+#[derive(Clone)]
+pub struct LoadShedLayer {
+    state: Arc<LoadShedState>,
+}
+
+impl LoadShedLayer {
+    pub fn new(class: &'static str, limit: usize, queue_timeout: Duration) -> Self {
+        Self {
+            state: Arc::new(LoadShedState {
+                class,
+                limit,
+                permits: Semaphore::new(limit),
+                queue_timeout,
+                shed: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    pub fn stats(&self) -> LoadShedStats {
+        let state = &self.state;
+        LoadShedStats {
+            class: state.class,
+            limit: state.limit,
+            in_flight: state.limit - state.permits.available_permits(),
+            shed: state.shed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl<S> Layer<S> for LoadShedLayer {
+    type Service = LoadShedService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LoadShedService {
+            inner,
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// Tower Service for load shedding.
+#[derive(Clone)]
+pub struct LoadShedService<S> {
+    inner: S,
+    state: Arc<LoadShedState>,
+}
+
+impl<S> Service<Request<Body>> for LoadShedService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        if self.state.limit == 0 || EXCLUDED_PATHS.contains(&req.uri().path()) {
+            return Box::pin(async move { inner.call(req).await });
+        }
+
+        let state = self.state.clone();
+        Box::pin(async move {
+            let permit =
+                match tokio::time::timeout(state.queue_timeout, state.permits.acquire()).await {
+                    Ok(Ok(permit)) => permit,
+                    // Timed out, or the semaphore was closed (it never is)
+                    _ => {
+                        let shed = state.shed.fetch_add(1, Ordering::Relaxed) + 1;
+                        tracing::warn!(
+                            class = state.class,
+                            limit = state.limit,
+                            shed_total = shed,
+                            path = %req.uri().path(),
+                            "Shedding request: server saturated"
+                        );
+                        return Ok(AppError::overloaded(
+                            "Server is busy. Please retry shortly.",
+                            RETRY_AFTER_SECS,
+                        )
+                        .into_response());
+                    }
+                };
+
+            let response = inner.call(req).await;
+            drop(permit);
+            response
+        })
+    }
+}
+
+/// One load-shed layer per route class. AI-backed routes get their own, tighter
+/// cap on top of the server-wide one so a slow provider can't use up every slot.
+#[derive(Clone)]
+pub struct LoadShedLimits {
+    pub default: LoadShedLayer,
+    pub ai: LoadShedLayer,
+}
+
+impl LoadShedLimits {
+    pub fn from_settings(settings: &Settings) -> Self {
+        let queue_timeout = Duration::from_millis(settings.load_shed_queue_timeout_ms);
+        Self {
+            default: LoadShedLayer::new(
+                "default",
+                settings.load_shed_max_concurrency,
+                queue_timeout,
+            ),
+            ai: LoadShedLayer::new("ai", settings.load_shed_ai_max_concurrency, queue_timeout),
+        }
+    }
+
+    pub fn stats(&self) -> Vec<LoadShedStats> {
+        vec![self.default.stats(), self.ai.stats()]
+    }
+}
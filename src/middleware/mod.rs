@@ -1,7 +1,17 @@
 mod auth;
+mod body_limit;
+mod load_shed;
 mod rate_limit;
+mod request_id;
 mod sentry;
+mod tenant;
+mod validation;
 
-pub use auth::{AuthenticatedUser, decode_jwt};
+pub use auth::{AuthFailure, AuthenticatedUser, decode_jwt, has_admin_key, is_revoked};
+pub use body_limit::payload_too_large_body;
+pub use load_shed::LoadShedLimits;
 pub use rate_limit::RateLimitLayer;
+pub use request_id::RequestId;
 pub use sentry::sentry_transaction_name;
+pub use tenant::{Tenant, resolve_tenant};
+pub use validation::{ValidatedJson, ValidatedQuery};
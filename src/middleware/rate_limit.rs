@@ -3,11 +3,15 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 
 use axum::body::Body;
-use axum::http::{Request, Response, StatusCode};
+use axum::http::{Request, Response};
 use axum::response::IntoResponse;
 use dashmap::DashMap;
 use tower::{Layer, Service};
 
+use super::Tenant;
+use crate::error::AppError;
+use crate::services::tenants::TenantRegistry;
+
 /// Token bucket for rate limiting.
 struct TokenBucket {
     tokens: f64,
@@ -54,6 +58,16 @@ impl TokenBucket {
     fn remaining(&self) -> u64 {
         self.tokens.max(0.0) as u64
     }
+
+    /// Apply a changed limit; tokens already held above the new capacity are lost.
+    fn resize(&mut self, capacity: f64, refill_rate: f64) {
+        if self.capacity != capacity {
+            self.refill();
+            self.capacity = capacity;
+            self.refill_rate = refill_rate;
+            self.tokens = self.tokens.min(capacity);
+        }
+    }
 }
 
 struct Buckets {
@@ -61,32 +75,47 @@ struct Buckets {
     hour: TokenBucket,
 }
 
-/// Shared state for rate limiting.
+/// Shared state for rate limiting. A client has one set of buckets whichever tenant
+/// it addresses, so switching tenants doesn't reset its count; the limits are the
+/// request's tenant's as of the request, so a reload applies to existing clients too.
 #[derive(Clone)]
 struct RateLimitState {
     buckets: Arc<DashMap<String, Buckets>>,
-    per_minute: u32,
-    per_hour: u32,
+    tenants: Arc<TenantRegistry>,
     last_cleanup: Arc<AtomicU64>,
 }
 
 impl RateLimitState {
-    fn new(per_minute: u32, per_hour: u32) -> Self {
+    fn new(tenants: Arc<TenantRegistry>) -> Self {
         Self {
             buckets: Arc::new(DashMap::new()),
-            per_minute,
-            per_hour,
+            tenants,
             last_cleanup: Arc::new(AtomicU64::new(0)),
         }
     }
 
-    fn get_or_create(&self, key: &str) -> dashmap::mapref::one::RefMut<'_, String, Buckets> {
-        self.buckets
+    fn limits(&self, tenant: &str) -> (u32, u32) {
+        let settings = self.tenants.settings(tenant);
+        (settings.rate_limit_per_minute, settings.rate_limit_per_hour)
+    }
+
+    fn get_or_create(
+        &self,
+        key: &str,
+        per_minute: u32,
+        per_hour: u32,
+    ) -> dashmap::mapref::one::RefMut<'_, String, Buckets> {
+        let (minute_rate, hour_rate) = (per_minute as f64 / 60.0, per_hour as f64 / 3600.0);
+        let mut entry = self
+            .buckets
             .entry(key.to_string())
             .or_insert_with(|| Buckets {
-                minute: TokenBucket::new(self.per_minute as f64, self.per_minute as f64 / 60.0),
-                hour: TokenBucket::new(self.per_hour as f64, self.per_hour as f64 / 3600.0),
-            })
+                minute: TokenBucket::new(per_minute as f64, minute_rate),
+                hour: TokenBucket::new(per_hour as f64, hour_rate),
+            });
+        entry.minute.resize(per_minute as f64, minute_rate);
+        entry.hour.resize(per_hour as f64, hour_rate);
+        entry
     }
 
     fn cleanup(&self) {
@@ -114,9 +143,11 @@ pub struct RateLimitLayer {
 }
 
 impl RateLimitLayer {
-    pub fn new(per_minute: u32, per_hour: u32) -> Self {
+    /// Must run inside [`super::resolve_tenant`]; requests without a tenant are
+    /// counted against the default one.
+    pub fn new(tenants: Arc<TenantRegistry>) -> Self {
         Self {
-            state: RateLimitState::new(per_minute, per_hour),
+            state: RateLimitState::new(tenants),
         }
     }
 }
@@ -177,21 +208,23 @@ where
 
         let state = self.state.clone();
         let mut inner = self.inner.clone();
+        let tenant = req
+            .extensions()
+            .get::<Tenant>()
+            .map_or_else(|| state.tenants.default_tenant().clone(), |t| t.0.clone());
 
         Box::pin(async move {
             state.cleanup();
 
-            let mut entry = state.get_or_create(&identifier);
+            let (per_minute, per_hour) = state.limits(&tenant);
+            let mut entry = state.get_or_create(&identifier, per_minute, per_hour);
 
             // Check per-minute bucket
             if !entry.minute.consume() {
                 let retry_after = entry.minute.retry_after();
                 drop(entry);
-                return Ok(rate_limit_response(
-                    retry_after,
-                    "per_minute",
-                    state.per_minute,
-                ));
+                state.tenants.record_request(&tenant, true);
+                return Ok(rate_limit_response(retry_after, "per_minute", per_minute));
             }
 
             // Check per-hour bucket
@@ -200,14 +233,14 @@ where
                 // Refund minute token
                 entry.minute.tokens += 1.0;
                 drop(entry);
-                return Ok(rate_limit_response(retry_after, "per_hour", state.per_hour));
+                state.tenants.record_request(&tenant, true);
+                return Ok(rate_limit_response(retry_after, "per_hour", per_hour));
             }
 
             let minute_remaining = entry.minute.remaining();
             let hour_remaining = entry.hour.remaining();
-            let per_minute = state.per_minute;
-            let per_hour = state.per_hour;
             drop(entry);
+            state.tenants.record_request(&tenant, false);
 
             let mut response = inner.call(req).await?;
 
@@ -235,19 +268,13 @@ where
     }
 }
 
-fn rate_limit_response(retry_after: u64, limit_type: &str, limit: u32) -> Response<Body> {
-    let body = serde_json::json!({
-        "error": "rate_limit_exceeded",
-        "message": format!("Too many requests. Try again in {retry_after} seconds."),
-        "retry_after": retry_after,
-        "limit_type": limit_type,
-        "limit": limit,
-    });
-
-    let mut resp = (StatusCode::TOO_MANY_REQUESTS, axum::Json(body)).into_response();
-
-    resp.headers_mut()
-        .insert("Retry-After", retry_after.to_string().parse().unwrap());
-
-    resp
+fn rate_limit_response(retry_after: u64, limit_type: &'static str, limit: u32) -> Response<Body> {
+    let window = limit_type.replace('_', " ");
+    AppError::rate_limited(
+        format!("Too many requests ({limit} {window}). Try again in {retry_after} seconds."),
+        retry_after,
+        limit_type,
+        limit,
+    )
+    .into_response()
 }
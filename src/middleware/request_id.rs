@@ -0,0 +1,25 @@
+use std::convert::Infallible;
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+/// `x-request-id` of the current request: the client's own or the one
+/// `SetRequestIdLayer` generated. `None` only outside the router's layers.
+#[derive(Debug, Clone)]
+pub struct RequestId(pub Option<String>);
+
+impl<S> FromRequestParts<S> for RequestId
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(
+            parts
+                .headers
+                .get("x-request-id")
+                .and_then(|v| v.to_str().ok())
+                .map(String::from),
+        ))
+    }
+}
@@ -0,0 +1,91 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::{
+    extract::{FromRef, FromRequestParts, Request, State},
+    http::{HeaderMap, HeaderValue, header, request::Parts},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use super::{decode_jwt, is_revoked};
+use crate::AppState;
+use crate::error::AppError;
+
+/// The app (yral, dolr, ...) a request is for, set by [`resolve_tenant`].
+#[derive(Debug, Clone)]
+pub struct Tenant(pub Arc<str>);
+
+impl Tenant {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Work out the request's tenant and store it for handlers and the rate limiter:
+/// the issuer of a valid bearer token decides, then an `X-Tenant` header, then the
+/// default tenant. A token always wins so a client can't step outside its app.
+pub async fn resolve_tenant(
+    State(state): State<Arc<AppState>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let tenant = match tenant_for(&state, req.headers()) {
+        Ok(tenant) => tenant,
+        Err(e) => return e.into_response(),
+    };
+    sentry::configure_scope(|scope| scope.set_tag("tenant", &tenant));
+    req.extensions_mut().insert(Tenant(tenant));
+
+    let mut response = next.run(req).await;
+    // Listings are publicly cacheable and differ per tenant
+    if state.tenants.is_multi_tenant() {
+        response.headers_mut().append(
+            header::VARY,
+            HeaderValue::from_static("X-Tenant, Authorization"),
+        );
+    }
+    response
+}
+
+fn tenant_for(state: &AppState, headers: &HeaderMap) -> Result<Arc<str>, AppError> {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| {
+            v.strip_prefix("Bearer ")
+                .or_else(|| v.strip_prefix("bearer "))
+        });
+    // An invalid or revoked token is left for the auth extractor to reject
+    if let Some(token) = token
+        && let Ok(claims) = decode_jwt(token, &state.settings.load())
+        && !is_revoked(state, &claims)
+    {
+        return Ok(state.tenants.for_issuer(&claims.iss));
+    }
+
+    match headers.get("X-Tenant").and_then(|v| v.to_str().ok()) {
+        Some(name) => state
+            .tenants
+            .get(name.trim())
+            .ok_or_else(|| AppError::bad_request(format!("Unknown tenant '{name}'"))),
+        None => Ok(state.tenants.default_tenant().clone()),
+    }
+}
+
+/// The default tenant outside the router's layers.
+impl<S> FromRequestParts<S> for Tenant
+where
+    Arc<AppState>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(tenant) = parts.extensions.get::<Tenant>() {
+            return Ok(tenant.clone());
+        }
+        let state = Arc::<AppState>::from_ref(state);
+        Ok(Self(state.tenants.default_tenant().clone()))
+    }
+}
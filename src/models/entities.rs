@@ -1,4 +1,6 @@
-use chrono::NaiveDateTime;
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, Display, EnumString};
 use utoipa::ToSchema;
@@ -30,6 +32,16 @@ pub enum MessageType {
     Audio,
 }
 
+/// How the user composed a message.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageSource {
+    #[default]
+    Typed,
+    /// Tapped one of the influencer's suggested messages
+    Suggested,
+}
+
 #[derive(
     Debug,
     Clone,
@@ -64,6 +76,497 @@ pub enum InfluencerStatus {
     Discontinued,
 }
 
+#[derive(
+    Debug, Clone, Serialize, Deserialize, PartialEq, Display, EnumString, AsRefStr, ToSchema,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum ParticipantRole {
+    /// Can read and send messages.
+    #[serde(rename = "participant")]
+    Participant,
+    /// Read-only member.
+    #[serde(rename = "viewer")]
+    Viewer,
+}
+
+/// How a duet picks which influencer answers the next user message.
+#[derive(
+    Debug, Clone, Serialize, Deserialize, PartialEq, Display, EnumString, AsRefStr, ToSchema,
+)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum DuetMode {
+    /// Influencers alternate, starting with the first one.
+    #[serde(rename = "round_robin")]
+    RoundRobin,
+    /// The influencer named in the message answers; falls back to round-robin.
+    #[serde(rename = "addressed")]
+    Addressed,
+}
+
+/// How long the user wants replies to be in a conversation.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Display,
+    EnumString,
+    AsRefStr,
+    ToSchema,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum ResponseLength {
+    #[serde(rename = "short")]
+    Short,
+    #[default]
+    #[serde(rename = "normal")]
+    Normal,
+    #[serde(rename = "detailed")]
+    Detailed,
+}
+
+/// How adventurous the user wants replies to be in a conversation.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Display,
+    EnumString,
+    AsRefStr,
+    ToSchema,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Creativity {
+    #[serde(rename = "low")]
+    Low,
+    #[default]
+    #[serde(rename = "medium")]
+    Medium,
+    #[serde(rename = "high")]
+    High,
+}
+
+impl Creativity {
+    /// `(temperature, top_p)` to sample with, or `None` to keep the provider's defaults.
+    pub fn sampling(self) -> Option<(f32, f32)> {
+        match self {
+            Creativity::Low => Some((0.3, 0.8)),
+            Creativity::Medium => None,
+            Creativity::High => Some((1.1, 0.98)),
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Serialize, Deserialize, PartialEq, Display, EnumString, AsRefStr, ToSchema,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum DigestFrequency {
+    #[serde(rename = "daily")]
+    Daily,
+    #[serde(rename = "weekly")]
+    Weekly,
+}
+
+/// How much of each message a list endpoint loads: `slim` skips media, audio and metadata.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Display,
+    EnumString,
+    AsRefStr,
+    ToSchema,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum MessageProjection {
+    #[default]
+    #[serde(rename = "full")]
+    Full,
+    #[serde(rename = "slim")]
+    Slim,
+}
+
+/// Order of a user's conversation list.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Display,
+    EnumString,
+    AsRefStr,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum ConversationSort {
+    /// Most recently active first
+    #[default]
+    Recent,
+    /// Conversations with unread replies first, each group most recent first
+    UnreadFirst,
+}
+
+/// Period an influencer leaderboard covers, ending now.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    Display,
+    EnumString,
+    AsRefStr,
+    ToSchema,
+)]
+pub enum LeaderboardWindow {
+    #[default]
+    #[serde(rename = "7d")]
+    #[strum(serialize = "7d")]
+    SevenDays,
+    #[serde(rename = "30d")]
+    #[strum(serialize = "30d")]
+    ThirtyDays,
+}
+
+impl LeaderboardWindow {
+    pub fn days(self) -> i64 {
+        match self {
+            Self::SevenDays => 7,
+            Self::ThirtyDays => 30,
+        }
+    }
+}
+
+/// What an influencer leaderboard ranks by.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    Display,
+    EnumString,
+    AsRefStr,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum LeaderboardMetric {
+    /// Messages users sent to the influencer
+    #[default]
+    Messages,
+    /// Distinct users who messaged the influencer
+    Users,
+}
+
+/// What a Replicate prediction renders; each has its own daily budget.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, Display, AsRefStr, ToSchema)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PredictionKind {
+    Image,
+    Video,
+}
+
+/// One ranked influencer on a leaderboard.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LeaderboardEntry {
+    /// 1-based position
+    pub rank: i64,
+    pub influencer_id: String,
+    pub name: String,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    pub category: Option<String>,
+    /// Messages or distinct users in the window, per the requested metric
+    pub score: i64,
+}
+
+/// Events an influencer's webhooks can subscribe to.
+#[derive(
+    Debug, Clone, Serialize, Deserialize, PartialEq, Display, EnumString, AsRefStr, ToSchema,
+)]
+pub enum WebhookEvent {
+    #[serde(rename = "message.created")]
+    #[strum(serialize = "message.created")]
+    MessageCreated,
+    #[serde(rename = "conversation.created")]
+    #[strum(serialize = "conversation.created")]
+    ConversationCreated,
+}
+
+/// Outcome of scanning an uploaded file for malware.
+#[derive(
+    Debug, Clone, Serialize, Deserialize, PartialEq, Display, EnumString, AsRefStr, ToSchema,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum ScanVerdict {
+    #[serde(rename = "clean")]
+    Clean,
+    #[serde(rename = "infected")]
+    Infected,
+    /// The scanner could not be reached or gave an unreadable answer.
+    #[serde(rename = "error")]
+    Error,
+}
+
+#[derive(
+    Debug, Clone, Serialize, Deserialize, PartialEq, Display, EnumString, AsRefStr, ToSchema,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum WebhookDeliveryStatus {
+    #[serde(rename = "pending")]
+    Pending,
+    #[serde(rename = "succeeded")]
+    Succeeded,
+    #[serde(rename = "failed")]
+    Failed,
+}
+
+/// Progress of the enrichment job that runs after an influencer is created,
+/// overall and per step.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Display, EnumString, AsRefStr, ToSchema,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum GenerationStatus {
+    #[serde(rename = "pending")]
+    Pending,
+    #[serde(rename = "running")]
+    Running,
+    #[serde(rename = "completed")]
+    Completed,
+    #[serde(rename = "failed")]
+    Failed,
+}
+
+/// Stored under the influencer's `metadata.generation`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InfluencerGeneration {
+    pub status: GenerationStatus,
+    /// Keyed by step: `avatar_check`, `avatar`, `greeting`, `starter_video_prompt`, `starter_video`
+    pub steps: BTreeMap<String, GenerationStatus>,
+    /// Where earlier versions kept the prompt; it now lives in
+    /// `metadata.starter_video_prompt`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starter_video_prompt: Option<String>,
+}
+
+/// Outcome of an avatar safety check.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Display, AsRefStr, ToSchema,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum AvatarVerdict {
+    Safe,
+    Flagged,
+    /// The check itself failed; the avatar was kept
+    Unchecked,
+}
+
+/// One avatar safety check, appended to the influencer's `metadata.avatar_moderation`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AvatarModerationEntry {
+    pub url: String,
+    /// `generated` or `supplied` by the creator
+    pub source: String,
+    pub verdict: AvatarVerdict,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Generation attempt, from 1; 0 for a supplied avatar
+    pub attempt: u32,
+    pub checked_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleDay {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl From<chrono::Weekday> for ScheduleDay {
+    fn from(day: chrono::Weekday) -> Self {
+        match day {
+            chrono::Weekday::Mon => Self::Mon,
+            chrono::Weekday::Tue => Self::Tue,
+            chrono::Weekday::Wed => Self::Wed,
+            chrono::Weekday::Thu => Self::Thu,
+            chrono::Weekday::Fri => Self::Fri,
+            chrono::Weekday::Sat => Self::Sat,
+            chrono::Weekday::Sun => Self::Sun,
+        }
+    }
+}
+
+/// What a scheduled influencer does with messages that arrive while it is offline.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Display,
+    EnumString,
+    AsRefStr,
+    ToSchema,
+)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum AwayMode {
+    /// Answer straight away with the away message
+    #[default]
+    #[serde(rename = "auto_reply")]
+    AutoReply,
+    /// Hold the AI reply until the next online window opens
+    #[serde(rename = "delay")]
+    Delay,
+}
+
+/// One weekly online window, in the schedule's timezone. An `end` earlier than
+/// `start` runs past midnight into the next day.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AvailabilityWindow {
+    /// Days the window starts on
+    pub days: Vec<ScheduleDay>,
+    /// "HH:MM"
+    pub start: String,
+    /// "HH:MM"
+    pub end: String,
+}
+
+/// Stored under the influencer's `metadata.schedule`. Influencers without one are
+/// always online while active.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AvailabilitySchedule {
+    /// IANA timezone, e.g. "Asia/Kolkata"
+    pub timezone: String,
+    pub windows: Vec<AvailabilityWindow>,
+    #[serde(default)]
+    pub away_mode: AwayMode,
+    /// Sent in `auto_reply` mode; a generic message is used when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub away_message: Option<String>,
+}
+
+impl AvailabilitySchedule {
+    fn tz(&self) -> chrono_tz::Tz {
+        self.timezone.parse().unwrap_or(chrono_tz::UTC)
+    }
+
+    /// Windows as `(day, start, end)`, skipping malformed times.
+    fn slots(&self) -> impl Iterator<Item = (ScheduleDay, NaiveTime, NaiveTime)> + '_ {
+        self.windows.iter().flat_map(|w| {
+            let times = NaiveTime::parse_from_str(&w.start, "%H:%M")
+                .ok()
+                .zip(NaiveTime::parse_from_str(&w.end, "%H:%M").ok());
+            w.days
+                .iter()
+                .filter_map(move |&day| times.map(|(start, end)| (day, start, end)))
+        })
+    }
+
+    pub fn is_online_at(&self, now: DateTime<Utc>) -> bool {
+        let local = now.with_timezone(&self.tz());
+        let today = ScheduleDay::from(local.weekday());
+        let yesterday = ScheduleDay::from(local.weekday().pred());
+        let time = local.time();
+        self.slots().any(|(day, start, end)| {
+            if start < end {
+                day == today && time >= start && time < end
+            } else {
+                (day == today && time >= start) || (day == yesterday && time < end)
+            }
+        })
+    }
+
+    /// When the next window opens after `now`, looking a week ahead.
+    pub fn next_online_at(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let tz = self.tz();
+        let local = now.with_timezone(&tz).naive_local();
+        (0..=7)
+            .filter_map(|offset| local.date().checked_add_days(chrono::Days::new(offset)))
+            .flat_map(|date| {
+                self.slots()
+                    .filter(move |(day, ..)| *day == ScheduleDay::from(date.weekday()))
+                    .map(move |(_, start, _)| date.and_time(start))
+            })
+            .filter(|start| *start > local)
+            .filter_map(|start| tz.from_local_datetime(&start).earliest())
+            .map(|start| start.with_timezone(&Utc))
+            .min()
+    }
+}
+
+/// Part of the user's local day a conversation starter suits.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DayPart {
+    /// 05:00–11:59
+    Morning,
+    /// 12:00–16:59
+    Afternoon,
+    /// 17:00–21:59
+    Evening,
+    /// 22:00–04:59
+    Night,
+}
+
+impl DayPart {
+    pub fn from_hour(hour: u32) -> Self {
+        match hour {
+            5..=11 => Self::Morning,
+            12..=16 => Self::Afternoon,
+            17..=21 => Self::Evening,
+            _ => Self::Night,
+        }
+    }
+}
+
+/// A suggested message shown only at some times of day or to speakers of some
+/// languages. Stored under the influencer's `metadata.conversation_starters`; an
+/// empty tag list matches everyone.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConversationStarter {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub day_parts: Vec<DayPart>,
+    /// Primary language subtags, e.g. "en", "hi"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<String>,
+}
+
 // ── Entities ──
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,8 +583,17 @@ pub struct AIInfluencer {
     pub suggested_messages: Vec<String>,
     pub is_active: InfluencerStatus,
     pub is_nsfw: bool,
+    /// Identity confirmed by an admin
+    #[serde(default)]
+    pub is_verified: bool,
+    /// Run by the platform itself
+    #[serde(default)]
+    pub is_official: bool,
     pub parent_principal_id: Option<String>,
     pub source: Option<String>,
+    /// App the influencer belongs to; see `TenantRegistry`
+    #[serde(default = "default_tenant")]
+    pub tenant: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub metadata: serde_json::Value,
@@ -89,6 +601,82 @@ pub struct AIInfluencer {
     pub conversation_count: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_count: Option<i64>,
+    /// Profile impressions reported by clients
+    #[serde(default)]
+    pub view_count: i64,
+}
+
+/// Seed files from before tenants existed belong to the default tenant
+fn default_tenant() -> String {
+    crate::services::tenants::DEFAULT_TENANT.to_string()
+}
+
+impl AIInfluencer {
+    pub fn generation(&self) -> Option<InfluencerGeneration> {
+        serde_json::from_value(self.metadata.get("generation")?.clone()).ok()
+    }
+
+    /// Prompt for the influencer's starter video. Older influencers only have it
+    /// inside `metadata.generation`.
+    pub fn starter_video_prompt(&self) -> Option<String> {
+        self.metadata
+            .get("starter_video_prompt")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| self.generation()?.starter_video_prompt)
+    }
+
+    /// Storage key of the rendered starter video.
+    pub fn starter_video_key(&self) -> Option<&str> {
+        self.metadata.get("starter_video_key")?.as_str()
+    }
+
+    /// Avatar safety checks so far, oldest first.
+    pub fn avatar_moderation(&self) -> Vec<AvatarModerationEntry> {
+        self.metadata
+            .get("avatar_moderation")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Template replacing the deployment's `MEDIA_ONLY_PROMPT` for this influencer.
+    pub fn media_only_prompt(&self) -> Option<&str> {
+        self.metadata.get("media_only_prompt")?.as_str()
+    }
+
+    /// Avatar supplied at creation, held back until it passes the safety check.
+    pub fn pending_avatar_url(&self) -> Option<&str> {
+        self.metadata.get("pending_avatar_url")?.as_str()
+    }
+
+    /// When the background job last replaced unused suggested messages.
+    pub fn suggestions_rotated_at(&self) -> Option<NaiveDateTime> {
+        let raw = self.metadata.get("suggestions_rotated_at")?.as_str()?;
+        NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S").ok()
+    }
+
+    /// Tagged suggested messages, rotated in alongside `suggested_messages`.
+    pub fn conversation_starters(&self) -> Vec<ConversationStarter> {
+        self.metadata
+            .get("conversation_starters")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn schedule(&self) -> Option<AvailabilitySchedule> {
+        serde_json::from_value(self.metadata.get("schedule")?.clone()).ok()
+    }
+
+    /// Share of turns recorded as AI samples, overriding `AI_SAMPLE_RATE`.
+    pub fn ai_sample_rate(&self) -> Option<f64> {
+        self.metadata.get("ai_sample_rate")?.as_f64()
+    }
+
+    /// Active and, if it keeps a schedule, inside one of its online windows.
+    pub fn is_online(&self) -> bool {
+        self.is_active == InfluencerStatus::Active
+            && self.schedule().is_none_or(|s| s.is_online_at(Utc::now()))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -110,6 +698,78 @@ pub struct Conversation {
     pub recent_messages: Option<Vec<Message>>,
 }
 
+impl Conversation {
+    /// Principal of the operator who has taken over this chat, if AI replies are paused.
+    pub fn takeover_principal(&self) -> Option<&str> {
+        self.metadata.get("takeover")?.get("by")?.as_str()
+    }
+
+    /// When `user_id`'s mute of this conversation ends: `Some(None)` while muted
+    /// until unmuted, `None` if not muted (or the mute has lapsed).
+    pub fn muted_until(&self, user_id: &str, now: NaiveDateTime) -> Option<Option<NaiveDateTime>> {
+        match self.metadata.get("mutes")?.get(user_id)? {
+            serde_json::Value::Null => Some(None),
+            until => {
+                let until =
+                    NaiveDateTime::parse_from_str(until.as_str()?, "%Y-%m-%d %H:%M:%S").ok()?;
+                (until > now).then_some(Some(until))
+            }
+        }
+    }
+
+    pub fn response_style(&self) -> ResponseStyle {
+        self.metadata
+            .get("response_style")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// User-chosen creativity, stored under `metadata.creativity`.
+    pub fn creativity(&self) -> Creativity {
+        self.metadata
+            .get("creativity")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Family-safe conversation: strictest guardrails, no NSFW model and
+    /// screened replies regardless of the influencer.
+    pub fn safe_mode(&self) -> bool {
+        self.metadata
+            .get("safe_mode")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Language the user asked the AI to reply in, if any.
+    pub fn preferred_language(&self) -> Option<&str> {
+        self.metadata.get("preferred_language")?.as_str()
+    }
+
+    /// Turn-taking mode when this is a duet conversation.
+    pub fn duet_mode(&self) -> Option<DuetMode> {
+        self.metadata
+            .get("duet")?
+            .get("mode")?
+            .as_str()?
+            .parse()
+            .ok()
+    }
+}
+
+/// Activity summary of one conversation. Days are UTC; a streak counts consecutive
+/// days with at least one user message and is current if it reaches today or yesterday.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConversationStats {
+    pub user_messages: i64,
+    pub assistant_messages: i64,
+    pub messages_today: i64,
+    pub first_message_at: Option<NaiveDateTime>,
+    pub last_message_at: Option<NaiveDateTime>,
+    pub current_streak_days: i64,
+    pub longest_streak_days: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct LastMessageInfo {
     pub content: Option<String>,
@@ -137,3 +797,499 @@ pub struct Message {
     pub status: String,
     pub is_read: bool,
 }
+
+/// User-chosen reply style for a conversation, stored under `metadata.response_style`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResponseStyle {
+    #[serde(default)]
+    pub length: ResponseLength,
+    /// `None` leaves emoji use up to the influencer's persona.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<bool>,
+}
+
+impl ResponseStyle {
+    /// Extra system prompt lines, or `None` when everything is at its default.
+    pub fn prompt_addendum(&self) -> Option<String> {
+        let mut lines = Vec::new();
+        match self.length {
+            ResponseLength::Short => {
+                lines.push("Keep every reply short: one or two sentences at most.")
+            }
+            ResponseLength::Normal => {}
+            ResponseLength::Detailed => lines
+                .push("Give detailed, thorough replies; it is fine to write several paragraphs."),
+        }
+        match self.emoji {
+            Some(true) => lines.push("Use emoji freely to express yourself."),
+            Some(false) => lines.push("Do not use any emoji."),
+            None => {}
+        }
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    /// Output token budget for this style, given the provider's default.
+    pub fn max_tokens(&self, default: u32) -> u32 {
+        match self.length {
+            ResponseLength::Short => (default / 4).max(256).min(default),
+            ResponseLength::Normal => default,
+            ResponseLength::Detailed => default.saturating_mul(2),
+        }
+    }
+}
+
+impl Message {
+    /// Influencer that authored an assistant message in a duet.
+    pub fn speaker_id(&self) -> Option<&str> {
+        self.metadata.get("speaker")?.as_str()
+    }
+
+    /// Name another duet speaker's turn is attributed to in a prompt. Only set on
+    /// the history copy built for the prompt, never stored.
+    pub fn speaker_name(&self) -> Option<&str> {
+        self.metadata.get("speaker_name")?.as_str()
+    }
+
+    /// Previously cached translation of this message.
+    pub fn cached_translation(&self, language: &str) -> Option<&str> {
+        self.metadata.get("translations")?.get(language)?.as_str()
+    }
+
+    /// An assistant message holding the fallback text because generation failed.
+    pub fn is_failed(&self) -> bool {
+        self.status == MESSAGE_STATUS_FAILED
+    }
+
+    pub fn failed_generation(&self) -> Option<FailedGeneration> {
+        serde_json::from_value(self.metadata.get("failed_generation")?.clone()).ok()
+    }
+
+    /// Provider and model that generated an assistant message.
+    pub fn generated_by(&self) -> Option<GenerationInfo> {
+        serde_json::from_value(self.metadata.get("generated_by")?.clone()).ok()
+    }
+
+    /// Screen-reader description of the message's images.
+    pub fn alt_text(&self) -> Option<&str> {
+        self.metadata.get("alt_text")?.as_str()
+    }
+
+    /// Tools the model called while writing an assistant message.
+    pub fn tool_calls(&self) -> Vec<ToolInvocation> {
+        self.metadata
+            .get("tool_calls")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn has_images(&self) -> bool {
+        matches!(
+            self.message_type,
+            MessageType::Image | MessageType::Multimodal
+        ) && !self.media_urls.is_empty()
+    }
+}
+
+/// AI backend behind an assistant message, stored under `metadata.generated_by`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GenerationInfo {
+    /// `gemini` or `openrouter`
+    pub provider: String,
+    pub model: String,
+}
+
+/// A server-side tool call made while generating an assistant message, stored
+/// under `metadata.tool_calls`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ToolInvocation {
+    /// `current_time`, `get_weather` or `web_search`
+    pub name: String,
+    /// Arguments as the model passed them
+    pub arguments: serde_json::Value,
+    /// What the tool returned to the model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: i64,
+}
+
+pub const MESSAGE_STATUS_FAILED: &str = "failed";
+/// Status of the stand-in reply in a 202 send response; no message has it.
+pub const MESSAGE_STATUS_PENDING: &str = "pending";
+
+/// What a failed assistant message needs to be generated again, stored under its
+/// `metadata.failed_generation` until a retry succeeds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedGeneration {
+    /// The user message being answered
+    pub user_message_id: String,
+    /// Influencer that was to reply (differs from the conversation's in a duet)
+    pub speaker_id: String,
+    /// Input as sent to the model
+    pub input: String,
+    /// Storage keys attached to the user message
+    #[serde(default)]
+    pub media_urls: Vec<String>,
+    pub attempts: u32,
+    pub error: String,
+    pub last_attempt_at: NaiveDateTime,
+}
+
+/// A reply held back while the influencer is away, to be generated at `due_at`.
+#[derive(Debug, Clone)]
+pub struct ScheduledReply {
+    /// The user message being answered
+    pub message_id: String,
+    pub conversation_id: String,
+    pub user_id: String,
+    /// Influencer that was away
+    pub influencer_id: String,
+    pub due_at: NaiveDateTime,
+}
+
+/// A user's image generations on `day` (UTC), for the daily quota and cooldown.
+#[derive(Debug, Clone)]
+pub struct ImageQuotaUsage {
+    pub day: NaiveDate,
+    pub count: u32,
+    pub last_at: NaiveDateTime,
+}
+
+/// A principal invited into a conversation alongside its creator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationParticipant {
+    pub conversation_id: String,
+    pub user_id: String,
+    pub role: ParticipantRole,
+    pub invited_by: String,
+    pub joined_at: NaiveDateTime,
+    pub last_read_at: Option<NaiveDateTime>,
+}
+
+/// A user's opt-in to periodic unread-activity digests.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestSubscription {
+    pub user_id: String,
+    pub frequency: DigestFrequency,
+    pub last_sent_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+/// A user's push notification preferences. A row exists once quiet hours are set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    pub user_id: String,
+    /// "HH:MM" in `timezone`; the window may wrap past midnight
+    pub quiet_hours_start: String,
+    pub quiet_hours_end: String,
+    /// IANA timezone name, e.g. "Asia/Kolkata"
+    pub timezone: String,
+    /// Send one digest when quiet hours end if pushes were held back
+    pub quiet_hours_digest: bool,
+    pub deferred_since: Option<NaiveDateTime>,
+    pub updated_at: NaiveDateTime,
+}
+
+impl NotificationPreferences {
+    /// Whether `now` falls inside the quiet hours window.
+    pub fn in_quiet_hours(&self, now: DateTime<Utc>) -> bool {
+        let (Ok(start), Ok(end), Ok(tz)) = (
+            NaiveTime::parse_from_str(&self.quiet_hours_start, "%H:%M"),
+            NaiveTime::parse_from_str(&self.quiet_hours_end, "%H:%M"),
+            self.timezone.parse::<chrono_tz::Tz>(),
+        ) else {
+            return false;
+        };
+        let local = now.with_timezone(&tz).time();
+        if start <= end {
+            local >= start && local < end
+        } else {
+            local >= start || local < end
+        }
+    }
+}
+
+/// Unread assistant replies in one of a user's conversations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnreadActivity {
+    pub conversation_id: String,
+    pub influencer_id: String,
+    pub influencer_name: String,
+    pub unread_count: i64,
+    pub latest_content: Option<String>,
+}
+
+/// Outbound endpoint an influencer owner registered for event notifications.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: String,
+    pub influencer_id: String,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub is_active: bool,
+    pub created_by: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+/// Telegram bot an influencer owner connected; its chats are bridged into conversations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramBot {
+    pub influencer_id: String,
+    pub bot_token: String,
+    pub bot_username: String,
+    pub webhook_secret: String,
+    pub created_by: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// Public snapshot of part of a conversation, served by token without auth.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationShare {
+    pub token: String,
+    pub conversation_id: String,
+    pub created_by: String,
+    pub snapshot: serde_json::Value,
+    pub created_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+/// Audit record of one malware scan of a media upload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadScan {
+    pub id: String,
+    pub user_id: String,
+    pub file_name: String,
+    pub sha256: String,
+    pub size: i64,
+    pub scanner: String,
+    pub verdict: ScanVerdict,
+    pub signature: Option<String>,
+    pub storage_key: Option<String>,
+}
+
+/// Heuristic that caught a user flooding or spamming a conversation.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Display,
+    EnumString,
+    AsRefStr,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AbuseKind {
+    /// The same message sent over and over
+    RepeatedMessage,
+    /// More text than `ABUSE_MAX_CHARS_PER_WINDOW` within the window
+    ExcessiveLength,
+    /// A message carrying `ABUSE_MAX_URLS` links or more
+    UrlSpam,
+}
+
+/// What happens to the user's messages in the conversation while a penalty lasts.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Display,
+    EnumString,
+    AsRefStr,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AbuseAction {
+    /// Rejected with 429
+    Throttled,
+    /// Stored, but no AI reply is generated; the sender isn't told
+    ShadowLimited,
+}
+
+/// A flood or spam penalty given to a user in one conversation.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AbuseEvent {
+    pub id: String,
+    pub user_id: String,
+    pub conversation_id: String,
+    pub kind: AbuseKind,
+    pub action: AbuseAction,
+    /// What tripped the heuristic, e.g. "4 links"
+    pub detail: Option<String>,
+    /// When the penalty lifts
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+/// Sensitive mutations recorded in the audit log.
+#[derive(
+    Debug, Clone, Serialize, Deserialize, PartialEq, Display, EnumString, AsRefStr, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AuditAction {
+    InfluencerCreated,
+    InfluencerUpdated,
+    InfluencerDeleted,
+    SystemPromptUpdated,
+    InfluencerBanned,
+    InfluencerUnbanned,
+    InfluencerRenamed,
+    InfluencerBadgesUpdated,
+    LegacyImported,
+    CallerTypeInvalidated,
+    TokenRevoked,
+    AiSamplingUpdated,
+    SettingsReloaded,
+    ConversationShared,
+    ConversationShareRevoked,
+    AiSamplesExported,
+}
+
+/// One audit log row; `before`/`after` are snapshots of the target around the change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: String,
+    /// Principal of the user, or "admin" for X-Admin-Key requests
+    pub actor: String,
+    pub action: AuditAction,
+    pub target_type: String,
+    pub target_id: Option<String>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub request_id: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+/// One AI turn kept for debugging, with personal data scrubbed from every text field.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AiSample {
+    pub id: String,
+    pub influencer_id: String,
+    pub conversation_id: String,
+    pub provider: String,
+    pub model: String,
+    /// Full system prompt as sent, guardrails and memories included
+    pub system_prompt: String,
+    /// Earlier turns as `{"role", "content"}` objects, oldest first
+    pub history: serde_json::Value,
+    pub user_message: String,
+    /// Absent when generation failed
+    pub response: Option<String>,
+    pub error: Option<String>,
+    pub latency_ms: i64,
+    pub created_at: NaiveDateTime,
+}
+
+/// A document in an influencer's knowledge base. Only its extracted text is kept,
+/// as embedded chunks; the uploaded file is not stored.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct KnowledgeDocument {
+    pub id: String,
+    pub influencer_id: String,
+    /// Given at upload, or the file name
+    pub title: String,
+    /// `text/plain`, `text/markdown` or `application/pdf`
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub chunk_count: i32,
+    pub uploaded_by: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// A passage of a knowledge document with its embedding.
+#[derive(Debug, Clone)]
+pub struct KnowledgeChunk {
+    pub content: String,
+    pub embedding: Vec<f32>,
+}
+
+/// A message in one of the user's conversations with its embedding, for semantic search.
+#[derive(Debug, Clone)]
+pub struct EmbeddedMessage {
+    pub message_id: String,
+    pub conversation_id: String,
+    pub influencer_id: String,
+    pub role: MessageRole,
+    pub content: String,
+    pub created_at: NaiveDateTime,
+    pub embedding: Vec<f32>,
+}
+
+/// Table a `change_log` entry points at.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Display, EnumString, AsRefStr, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ChangeEntity {
+    Conversation,
+    Message,
+}
+
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Display, EnumString, AsRefStr, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ChangeOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// One row of the change feed. Only ids are recorded; readers fetch the current
+/// state, so a delete means the entity is gone rather than carrying its last value.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ChangeLogEntry {
+    pub seq: i64,
+    pub entity_type: ChangeEntity,
+    pub entity_id: String,
+    pub op: ChangeOp,
+    /// The conversation itself, or the one a message belongs to
+    pub conversation_id: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// Copy of a conversation bound to a draft system prompt so an owner can replay it.
+/// Stored outside `conversations`/`messages`; sends never touch memories, analytics
+/// or notifications.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxConversation {
+    pub id: String,
+    pub influencer_id: String,
+    pub owner_id: String,
+    pub source_conversation_id: String,
+    /// Draft prompt as written, without guardrails
+    pub system_instructions: String,
+    /// Snapshot of the source conversation (settings, memories) turns are built from
+    pub conversation: Conversation,
+    pub messages: Vec<Message>,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+}
@@ -1,13 +1,23 @@
 use std::sync::LazyLock;
 
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
-use super::entities::MessageType;
+use super::entities::{
+    AbuseKind, AuditAction, AvailabilityWindow, AwayMode, ConversationSort, ConversationStarter,
+    Creativity, DigestFrequency, DuetMode, LeaderboardMetric, LeaderboardWindow, MessageProjection,
+    MessageRole, MessageSource, MessageType, ParticipantRole, ResponseLength, WebhookEvent,
+};
 
 static NAME_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[a-zA-Z0-9]+$").unwrap());
+/// Language codes ("hi", "pt-BR") or plain names ("Hindi").
+static LANGUAGE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[\p{L}][\p{L} _-]{1,34}$").unwrap());
+/// 24-hour "HH:MM".
+static CLOCK_TIME_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([01][0-9]|2[0-3]):[0-5][0-9]$").unwrap());
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateConversationRequest {
@@ -15,11 +25,28 @@ pub struct CreateConversationRequest {
     pub influencer_id: String,
 }
 
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateDuetRequest {
+    /// The influencers that take turns replying, in speaking order
+    #[validate(length(min = 2, max = 2, message = "a duet needs exactly two influencers"))]
+    pub influencer_ids: Vec<String>,
+    /// "round_robin" (default) or "addressed"
+    #[serde(default = "default_duet_mode")]
+    #[schema(value_type = String, default = "round_robin")]
+    pub mode: DuetMode,
+}
+
+fn default_duet_mode() -> DuetMode {
+    DuetMode::RoundRobin
+}
+
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct SendMessageRequest {
     pub message_type: String,
 
-    #[validate(length(max = 4000, message = "content exceeds 4000 characters"))]
+    /// Up to `MESSAGE_MAX_CHARS` characters (16000 by default). Longer than
+    /// `MESSAGE_CONDENSE_THRESHOLD_CHARS` and the model sees a condensed version;
+    /// the message is stored as sent.
     #[schema(default = "")]
     pub content: Option<String>,
 
@@ -31,6 +58,17 @@ pub struct SendMessageRequest {
     pub audio_duration_seconds: Option<i32>,
 
     pub client_message_id: Option<String>,
+
+    /// "suggested" when the user tapped one of the influencer's suggested messages
+    #[serde(default)]
+    pub source: MessageSource,
+}
+
+/// Nothing a reader would see: whitespace and zero-width characters only.
+pub fn is_blank(text: &str) -> bool {
+    text.chars().all(|c| {
+        c.is_whitespace() || matches!(c, '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}')
+    })
 }
 
 impl SendMessageRequest {
@@ -38,36 +76,51 @@ impl SendMessageRequest {
         self.message_type.parse().ok()
     }
 
-    pub fn validate_content(&self) -> Result<(), String> {
-        let msg_type = self.parsed_message_type().ok_or("Invalid message type")?;
+    /// Checks that depend on `message_type` or configuration. Errors name the field to fix.
+    pub fn validate_content(&self, max_chars: usize) -> Result<(), (&'static str, String)> {
+        let msg_type = self
+            .parsed_message_type()
+            .ok_or(("message_type", "Invalid message type".to_string()))?;
         let content = self.content.as_deref().unwrap_or("").trim();
+        if content.chars().count() > max_chars {
+            return Err(("content", format!("content exceeds {max_chars} characters")));
+        }
         let media_urls = self.media_urls.as_deref().unwrap_or(&[]);
 
         match msg_type {
             MessageType::Text => {
-                if content.is_empty() {
-                    return Err("content is required for text messages".into());
+                if is_blank(content) {
+                    return Err(("content", "content is required for text messages".into()));
                 }
             }
             MessageType::Image => {
                 if media_urls.is_empty() {
-                    return Err("media_urls is required for image messages".into());
+                    return Err((
+                        "media_urls",
+                        "media_urls is required for image messages".into(),
+                    ));
                 }
                 if media_urls.len() > 10 {
-                    return Err("Too many media URLs (max 10)".into());
+                    return Err(("media_urls", "Too many media URLs (max 10)".into()));
                 }
             }
             MessageType::Multimodal => {
                 if media_urls.is_empty() {
-                    return Err("media_urls is required for multimodal messages".into());
+                    return Err((
+                        "media_urls",
+                        "media_urls is required for multimodal messages".into(),
+                    ));
                 }
                 if media_urls.len() > 10 {
-                    return Err("Too many media URLs (max 10)".into());
+                    return Err(("media_urls", "Too many media URLs (max 10)".into()));
                 }
             }
             MessageType::Audio => {
                 if self.audio_url.is_none() {
-                    return Err("audio_url is required for audio messages".into());
+                    return Err((
+                        "audio_url",
+                        "audio_url is required for audio messages".into(),
+                    ));
                 }
             }
         }
@@ -82,6 +135,9 @@ pub struct PaginationParams {
     pub limit: Option<i64>,
     #[param(default = 0)]
     pub offset: Option<i64>,
+    /// `false` skips counting the total; use `has_more` to page instead
+    #[param(default = true)]
+    pub include_total: Option<bool>,
 }
 
 impl PaginationParams {
@@ -91,6 +147,52 @@ impl PaginationParams {
     pub fn offset(&self) -> i64 {
         self.offset.unwrap_or(0).max(0)
     }
+    pub fn include_total(&self) -> bool {
+        self.include_total.unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct ListInfluencersParams {
+    #[param(default = 50)]
+    pub limit: Option<i64>,
+    #[param(default = 0)]
+    pub offset: Option<i64>,
+    /// `false` skips counting the total; use `has_more` to page instead
+    #[param(default = true)]
+    pub include_total: Option<bool>,
+    /// `true` returns only verified influencers, `false` only unverified ones
+    pub verified: Option<bool>,
+}
+
+impl ListInfluencersParams {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(50).clamp(1, 100)
+    }
+    pub fn offset(&self) -> i64 {
+        self.offset.unwrap_or(0).max(0)
+    }
+    pub fn include_total(&self) -> bool {
+        self.include_total.unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct LeaderboardParams {
+    /// `7d` or `30d`
+    #[param(default = "7d", value_type = Option<String>)]
+    pub window: Option<LeaderboardWindow>,
+    /// `messages` (sent by users) or `users` (distinct users)
+    #[param(default = "messages", value_type = Option<String>)]
+    pub metric: Option<LeaderboardMetric>,
+    #[param(default = 20)]
+    pub limit: Option<i64>,
+}
+
+impl LeaderboardParams {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(20).clamp(1, 100) as usize
+    }
 }
 
 #[derive(Debug, Deserialize, IntoParams, ToSchema)]
@@ -100,6 +202,21 @@ pub struct ListConversationsParams {
     #[param(default = 0)]
     pub offset: Option<i64>,
     pub influencer_id: Option<String>,
+    /// Recent messages embedded per conversation (0-10); 0 omits them
+    #[param(default = 10)]
+    pub recent_limit: Option<i64>,
+    /// `slim` drops media, audio and metadata from embedded messages and
+    /// suggested messages from the influencer card
+    #[param(default = "full", value_type = Option<String>)]
+    pub fields: Option<MessageProjection>,
+    /// `false` skips counting the total; use `has_more` to page instead
+    #[param(default = true)]
+    pub include_total: Option<bool>,
+    /// `true` returns only conversations with unread replies
+    pub has_unread: Option<bool>,
+    /// `recent` (most recently active first) or `unread_first`
+    #[param(default = "recent", value_type = Option<String>)]
+    pub sort: Option<ConversationSort>,
 }
 
 impl ListConversationsParams {
@@ -109,6 +226,43 @@ impl ListConversationsParams {
     pub fn offset(&self) -> i64 {
         self.offset.unwrap_or(0).max(0)
     }
+    pub fn recent_limit(&self) -> i64 {
+        self.recent_limit.unwrap_or(10).clamp(0, 10)
+    }
+    pub fn fields(&self) -> MessageProjection {
+        self.fields.unwrap_or_default()
+    }
+    pub fn include_total(&self) -> bool {
+        self.include_total.unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ResumeParams {
+    /// Latest messages of the conversation to include (1-50)
+    #[param(default = 20)]
+    pub message_limit: Option<i64>,
+}
+
+impl ResumeParams {
+    pub fn message_limit(&self) -> i64 {
+        self.message_limit.unwrap_or(20).clamp(1, 50)
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SemanticSearchParams {
+    /// What to look for, in the user's own words
+    pub q: String,
+    /// Messages and conversations returned (1-50)
+    #[param(default = 20)]
+    pub limit: Option<i64>,
+}
+
+impl SemanticSearchParams {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(20).clamp(1, 50) as usize
+    }
 }
 
 #[derive(Debug, Deserialize, IntoParams, ToSchema)]
@@ -120,6 +274,15 @@ pub struct ListConversationsV2Params {
     #[param(default = 0)]
     pub offset: Option<i64>,
     pub influencer_id: Option<String>,
+    /// `false` skips counting the total; use `has_more` to page instead
+    #[param(default = true)]
+    pub include_total: Option<bool>,
+    /// Recent messages embedded per conversation (0-10); 0 omits them
+    #[param(default = 0)]
+    pub include_recent: Option<i64>,
+    /// `slim` drops media, audio and metadata from embedded messages
+    #[param(default = "full", value_type = Option<String>)]
+    pub fields: Option<MessageProjection>,
 }
 
 impl ListConversationsV2Params {
@@ -129,6 +292,15 @@ impl ListConversationsV2Params {
     pub fn offset(&self) -> i64 {
         self.offset.unwrap_or(0).max(0)
     }
+    pub fn include_total(&self) -> bool {
+        self.include_total.unwrap_or(true)
+    }
+    pub fn include_recent(&self) -> i64 {
+        self.include_recent.unwrap_or(0).clamp(0, 10)
+    }
+    pub fn fields(&self) -> MessageProjection {
+        self.fields.unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Deserialize, IntoParams, ToSchema)]
@@ -139,6 +311,13 @@ pub struct ListMessagesParams {
     pub offset: Option<i64>,
     #[param(default = "desc")]
     pub order: Option<String>,
+    /// `true` adds per-role counts, first/last timestamps and streaks as `stats`
+    #[serde(default)]
+    pub include_stats: bool,
+    /// `true` adds each assistant message's provider and model as `generated_by`;
+    /// bot owner or admin (`X-Admin-Key`) only
+    #[serde(default)]
+    pub include_debug: bool,
 }
 
 impl ListMessagesParams {
@@ -160,6 +339,9 @@ impl ListMessagesParams {
 pub struct GeneratePromptRequest {
     #[validate(length(min = 1, max = 1000, message = "prompt must be 1-1000 characters"))]
     pub prompt: String,
+    /// Skip the cached result for an identical prompt and ask the model again
+    #[serde(default)]
+    pub bypass_cache: bool,
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
@@ -174,9 +356,13 @@ pub struct GenerateVideoPromptRequest {
     pub reference_image_url: Option<String>,
 }
 
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct ValidateMetadataRequest {
+    #[validate(length(min = 1, message = "system_instructions is required"))]
     pub system_instructions: String,
+    /// Skip the cached result for identical instructions and validate again
+    #[serde(default)]
+    pub bypass_cache: bool,
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
@@ -188,6 +374,7 @@ pub struct CreateInfluencerRequest {
     pub display_name: String,
     #[validate(length(max = 500, message = "description max 500 characters"))]
     pub description: Option<String>,
+    #[validate(length(min = 1, message = "system_instructions is required"))]
     pub system_instructions: String,
     pub initial_greeting: Option<String>,
     #[serde(default)]
@@ -203,23 +390,178 @@ pub struct CreateInfluencerRequest {
     #[serde(default)]
     #[allow(dead_code)]
     pub is_nsfw: bool,
+    /// Create even if it looks like one of the caller's existing influencers
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RenameInfluencerRequest {
+    #[validate(length(min = 3, max = 15, message = "name must be 3-15 characters"))]
+    #[validate(regex(path = *NAME_REGEX, message = "name must be alphanumeric"))]
+    pub name: String,
 }
 
 fn default_personality_traits() -> serde_json::Value {
     serde_json::json!({})
 }
 
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct GenerateImageRequest {
     #[serde(default)]
+    #[validate(length(max = 1000, message = "prompt max 1000 characters"))]
     pub prompt: Option<String>,
 }
 
-#[derive(Debug, Deserialize, ToSchema)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct InviteParticipantRequest {
+    #[validate(length(min = 1, max = 255, message = "principal is required"))]
+    pub principal: String,
+    /// "participant" (default) or "viewer"
+    #[serde(default = "default_participant_role")]
+    #[schema(value_type = String, default = "participant")]
+    pub role: ParticipantRole,
+}
+
+fn default_participant_role() -> ParticipantRole {
+    ParticipantRole::Participant
+}
+
+/// Fields left out keep their current value.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateResponseStyleRequest {
+    /// "short", "normal" or "detailed"
+    #[schema(value_type = Option<String>)]
+    pub length: Option<ResponseLength>,
+    pub emoji: Option<bool>,
+}
+
+/// Conversation settings; fields left out keep their current value.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateConversationRequest {
+    /// "low", "medium" or "high"; how far replies may stray from the expected
+    #[schema(value_type = Option<String>)]
+    pub creativity: Option<Creativity>,
+    /// Keep replies family-safe whatever the influencer's settings
+    pub safe_mode: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct MuteConversationRequest {
+    /// Minutes to mute for; omit to mute until unmuted
+    #[validate(range(min = 1, max = 525600))]
+    pub duration_minutes: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateLanguageRequest {
+    /// Language the AI should reply in; `null` goes back to matching the user's language
+    #[validate(regex(path = *LANGUAGE_REGEX, message = "invalid language"))]
+    pub preferred_language: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+pub struct TranslateMessageParams {
+    /// Target language, e.g. "hi" or "Spanish"
+    #[validate(regex(path = *LANGUAGE_REGEX, message = "invalid language"))]
+    pub lang: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateScheduleRequest {
+    /// IANA timezone the windows are in, e.g. "Asia/Kolkata"
+    pub timezone: String,
+    #[validate(length(min = 1, max = 50, message = "between 1 and 50 windows"))]
+    pub windows: Vec<AvailabilityWindow>,
+    /// "auto_reply" (default) or "delay"
+    #[serde(default)]
+    #[schema(value_type = String)]
+    pub away_mode: AwayMode,
+    #[validate(length(max = 500))]
+    pub away_message: Option<String>,
+}
+
+/// `null` or blank goes back to the deployment's default.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateMediaOnlyPromptRequest {
+    /// Sent to the AI in place of the missing text when a user sends only media;
+    /// `{media}` becomes e.g. "photo" or "voice note", `{count}` the number attached
+    #[validate(length(max = 500, message = "media_only_prompt is limited to 500 characters"))]
+    pub media_only_prompt: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateConversationStartersRequest {
+    /// Replaces the influencer's tagged starters; an empty list removes them
+    #[validate(length(max = 30, message = "At most 30 conversation starters"))]
+    pub starters: Vec<ConversationStarter>,
+}
+
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct SuggestionStatsParams {
+    /// Window the stats cover, 1-365 days
+    #[param(default = 30)]
+    pub days: Option<i32>,
+}
+
+impl SuggestionStatsParams {
+    pub fn days(&self) -> i32 {
+        self.days.unwrap_or(30).clamp(1, 365)
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateSandboxRequest {
+    /// Conversation with this influencer whose history is copied
+    #[validate(length(min = 1))]
+    pub conversation_id: String,
+    /// Draft prompt replies in the sandbox are generated with
+    #[validate(length(min = 1, message = "system_instructions is required"))]
+    pub system_instructions: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SandboxMessageRequest {
+    #[validate(length(min = 1, max = 4000, message = "content must be 1-4000 characters"))]
+    pub content: String,
+}
+
+/// One earlier turn of a preview chat; previews aren't stored, so the client resends them.
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct PreviewTurn {
+    #[schema(value_type = String)]
+    pub role: MessageRole,
+    #[validate(length(max = 4000, message = "content exceeds 4000 characters"))]
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct PreviewChatRequest {
+    /// Saved as the draft before replying; omit to use the draft already saved
+    #[validate(length(min = 1, message = "system_instructions must not be empty"))]
+    pub system_instructions: Option<String>,
+    #[validate(length(min = 1, max = 4000, message = "message must be 1-4000 characters"))]
+    pub message: String,
+    /// Earlier preview turns, oldest first
+    #[serde(default)]
+    #[validate(length(max = 20, message = "history is limited to 20 turns"), nested)]
+    pub history: Vec<PreviewTurn>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateSystemPromptRequest {
+    #[validate(length(min = 1, message = "system_instructions is required"))]
     pub system_instructions: String,
 }
 
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RegenerateGreetingRequest {
+    /// Keep existing conversations as they are. Otherwise conversations the user
+    /// hasn't replied in yet get the new greeting too
+    #[serde(default)]
+    pub new_conversations_only: bool,
+}
+
 /// Multipart form body for media upload
 #[derive(ToSchema)]
 #[allow(dead_code)]
@@ -231,3 +573,231 @@ pub struct UploadMediaBody {
     #[schema(rename = "type")]
     pub media_type: String,
 }
+
+/// Multipart form body for a knowledge base document
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct UploadKnowledgeDocumentBody {
+    /// The document: .txt, .md or .pdf
+    #[schema(format = Binary)]
+    pub file: String,
+    /// Shown in the document list; defaults to the file name
+    pub title: Option<String>,
+}
+
+/// Multipart form body for a legacy database import
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct LegacyImportBody {
+    /// SQLite database file from the Python backend
+    #[schema(format = Binary)]
+    pub file: String,
+    /// Set to "false" to keep media references as they are
+    pub rekey_media: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct DigestSubscriptionRequest {
+    /// "daily" or "weekly"
+    #[schema(value_type = String)]
+    pub frequency: DigestFrequency,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct QuietHoursRequest {
+    /// Start of the quiet window, "HH:MM" (24-hour) in `timezone`
+    #[validate(regex(path = *CLOCK_TIME_REGEX, message = "must be HH:MM"))]
+    pub start: String,
+    /// End of the quiet window; earlier than `start` wraps past midnight
+    #[validate(regex(path = *CLOCK_TIME_REGEX, message = "must be HH:MM"))]
+    pub end: String,
+    /// IANA timezone, e.g. "Asia/Kolkata"
+    pub timezone: String,
+    /// Send one digest of what was missed when quiet hours end (default true)
+    #[serde(default = "default_send_digest")]
+    pub send_digest: bool,
+}
+
+fn default_send_digest() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateWebhookRequest {
+    /// HTTPS endpoint that receives signed event payloads
+    #[validate(url(message = "url must be a valid URL"), length(max = 2048))]
+    pub url: String,
+    /// Events to deliver; defaults to all of them
+    #[serde(default = "default_webhook_events")]
+    #[validate(length(min = 1, message = "At least one event is required"))]
+    #[schema(value_type = Vec<String>)]
+    pub events: Vec<WebhookEvent>,
+}
+
+fn default_webhook_events() -> Vec<WebhookEvent> {
+    vec![
+        WebhookEvent::MessageCreated,
+        WebhookEvent::ConversationCreated,
+    ]
+}
+
+/// Normalized inbound email, as posted by the mail provider's parse webhook
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct InboundEmailRequest {
+    /// Sender, e.g. `"Jane <jane@example.com>"`
+    pub from: String,
+    /// Recipient; the local part names the influencer
+    pub to: String,
+    #[serde(default)]
+    pub subject: Option<String>,
+    /// Plain-text body
+    #[serde(default)]
+    pub text: Option<String>,
+    /// `Message-ID` header, used for threading and to drop provider retries
+    #[serde(default)]
+    pub message_id: Option<String>,
+    /// The provider's SPF verdict for the sender, e.g. `pass`
+    #[serde(default, alias = "SPF")]
+    pub spf: Option<String>,
+    /// The provider's DKIM results, e.g. `{@example.com : pass}`
+    #[serde(default)]
+    pub dkim: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ConnectTelegramRequest {
+    /// Token issued by @BotFather
+    #[validate(length(min = 1, max = 100, message = "bot_token is required"))]
+    pub bot_token: String,
+}
+
+/// Message range to snapshot; defaults to the most recent messages
+#[derive(Debug, Default, Deserialize, Validate, ToSchema)]
+pub struct CreateShareRequest {
+    /// First message to include (inclusive)
+    pub from_message_id: Option<String>,
+    /// Last message to include (inclusive)
+    pub to_message_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RevokeTokenRequest {
+    /// `jti` claim of the token to cut off
+    #[validate(length(min = 1, max = 255))]
+    pub jti: String,
+    /// The token's `exp` claim (Unix seconds); the revocation is forgotten once the
+    /// JWT leeway past this has elapsed
+    #[validate(range(min = 1))]
+    pub exp: i64,
+    #[validate(length(max = 500))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct AuditLogParams {
+    #[param(default = 50)]
+    pub limit: Option<i64>,
+    #[param(default = 0)]
+    pub offset: Option<i64>,
+    /// User principal, or "admin" for admin-key actions
+    pub actor: Option<String>,
+    /// e.g. "system_prompt_updated"
+    #[param(value_type = Option<String>)]
+    pub action: Option<AuditAction>,
+    /// e.g. "influencer"
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    /// Entries at or after this UTC time ("2026-01-31T00:00:00")
+    #[param(value_type = Option<String>)]
+    pub since: Option<chrono::NaiveDateTime>,
+    /// Entries before this UTC time
+    #[param(value_type = Option<String>)]
+    pub until: Option<chrono::NaiveDateTime>,
+    /// `false` skips counting the total; use `has_more` to page instead
+    #[param(default = true)]
+    pub include_total: Option<bool>,
+}
+
+impl AuditLogParams {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(50).clamp(1, 200)
+    }
+    pub fn offset(&self) -> i64 {
+        self.offset.unwrap_or(0).max(0)
+    }
+    pub fn include_total(&self) -> bool {
+        self.include_total.unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AbuseEventsParams {
+    #[param(default = 50)]
+    pub limit: Option<i64>,
+    #[param(default = 0)]
+    pub offset: Option<i64>,
+    pub user_id: Option<String>,
+    pub conversation_id: Option<String>,
+    /// e.g. "url_spam"
+    #[param(value_type = Option<String>)]
+    pub kind: Option<AbuseKind>,
+    /// `false` skips counting the total; use `has_more` to page instead
+    #[param(default = true)]
+    pub include_total: Option<bool>,
+}
+
+impl AbuseEventsParams {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(50).clamp(1, 200)
+    }
+    pub fn offset(&self) -> i64 {
+        self.offset.unwrap_or(0).max(0)
+    }
+    pub fn include_total(&self) -> bool {
+        self.include_total.unwrap_or(true)
+    }
+}
+
+/// Badges left out keep their current value.
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateBadgesRequest {
+    pub verified: Option<bool>,
+    pub official: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateAiSamplingRequest {
+    /// Share of the influencer's turns recorded, 0.0-1.0; null falls back to `AI_SAMPLE_RATE`
+    #[validate(range(min = 0.0, max = 1.0, message = "rate must be between 0 and 1"))]
+    pub rate: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AiSamplesParams {
+    #[param(default = 20)]
+    pub limit: Option<i64>,
+}
+
+impl AiSamplesParams {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(20).clamp(1, 100)
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ChangeLogParams {
+    /// Return entries after this seq; 0 starts from the oldest retained entry
+    #[param(default = 0)]
+    pub after_seq: Option<i64>,
+    #[param(default = 500)]
+    pub limit: Option<i64>,
+}
+
+impl ChangeLogParams {
+    pub fn after_seq(&self) -> i64 {
+        self.after_seq.unwrap_or(0).max(0)
+    }
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(500).clamp(1, 1000)
+    }
+}
@@ -1,8 +1,16 @@
+use std::collections::BTreeMap;
+
 use chrono::NaiveDateTime;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use super::entities::{InfluencerStatus, LastMessageInfo, MessageRole, MessageType};
+use super::entities::{
+    AbuseEvent, AiSample, AuditAction, AvailabilitySchedule, AvatarModerationEntry, ChangeLogEntry,
+    ConversationStarter, ConversationStats, Creativity, DigestFrequency, DuetMode, GenerationInfo,
+    GenerationStatus, InfluencerStatus, KnowledgeDocument, LastMessageInfo, LeaderboardEntry,
+    LeaderboardMetric, LeaderboardWindow, MessageRole, MessageType, ParticipantRole,
+    PredictionKind, ResponseLength, ToolInvocation, WebhookDeliveryStatus, WebhookEvent,
+};
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct InfluencerBasicInfo {
@@ -11,6 +19,8 @@ pub struct InfluencerBasicInfo {
     pub display_name: String,
     pub avatar_url: Option<String>,
     pub is_online: bool,
+    pub is_verified: bool,
+    pub is_official: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub suggested_messages: Option<Vec<String>>,
 }
@@ -22,6 +32,8 @@ pub struct InfluencerBasicInfoV2 {
     pub display_name: String,
     pub avatar_url: Option<String>,
     pub is_online: bool,
+    pub is_verified: bool,
+    pub is_official: bool,
 }
 
 #[derive(Debug, Clone, Serialize, ToSchema)]
@@ -29,9 +41,20 @@ pub struct UserBasicInfo {
     pub principal_id: String,
     pub username: Option<String>,
     pub profile_picture_url: Option<String>,
+    /// Has an open WebSocket connection right now
+    pub is_online: bool,
+    /// Last time the user was connected; null if never seen
+    pub last_seen_at: Option<NaiveDateTime>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
+pub struct PresenceResponse {
+    pub principal_id: String,
+    pub is_online: bool,
+    pub last_seen_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct MessageResponse {
     pub id: String,
     pub role: MessageRole,
@@ -42,9 +65,25 @@ pub struct MessageResponse {
     pub audio_duration_seconds: Option<i32>,
     pub token_count: Option<i32>,
     pub created_at: NaiveDateTime,
+    /// Description of the images for screen readers; written in the background,
+    /// so absent until the caption is ready
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_text: Option<String>,
+    /// Author of a user message in a group conversation, when not the creator.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender_id: Option<String>,
+    /// Influencer that wrote an assistant message in a duet conversation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speaker_id: Option<String>,
     #[schema(default = "delivered")]
     pub status: String,
     pub is_read: bool,
+    /// AI backend that wrote an assistant message; only with `include_debug`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generated_by: Option<GenerationInfo>,
+    /// Tools the AI looked things up with for this reply
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolInvocation>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -55,10 +94,21 @@ pub struct ConversationResponse {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub message_count: i64,
+    /// Replies the user hasn't read
+    pub unread_count: i64,
     pub last_message: Option<LastMessageInfo>,
     pub recent_messages: Option<Vec<MessageResponse>>,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DuetConversationResponse {
+    #[serde(flatten)]
+    pub conversation: ConversationResponse,
+    pub mode: DuetMode,
+    /// Every influencer in the duet, in speaking order
+    pub influencers: Vec<InfluencerBasicInfo>,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ConversationResponseV2 {
     pub id: String,
@@ -73,26 +123,62 @@ pub struct ConversationResponseV2 {
     pub message_count: i64,
     pub unread_count: i64,
     pub last_message: Option<LastMessageInfo>,
+    /// Present when the list was requested with `include_recent`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recent_messages: Option<Vec<MessageResponse>>,
+}
+
+/// A sandbox and its full history; `system_instructions` is the draft as written.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SandboxResponse {
+    pub id: String,
+    pub influencer_id: String,
+    pub source_conversation_id: String,
+    pub system_instructions: String,
+    pub messages: Vec<MessageResponse>,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct SendMessageResponse {
     pub user_message: MessageResponse,
+    /// With a 202 (an operator replies, or the reply comes later), a `pending`
+    /// stand-in with no content; the real reply arrives over the WebSocket.
     pub assistant_message: MessageResponse,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ListConversationsResponse {
     pub conversations: Vec<ConversationResponse>,
-    pub total: i64,
+    /// Omitted when the request set `include_total=false`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+    pub has_more: bool,
     pub limit: i64,
     pub offset: i64,
 }
 
+/// Everything the chat screen needs on app start.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResumeResponse {
+    /// The most recently active conversation, its latest messages in `recent_messages`
+    /// (oldest first); null when the user has no conversations
+    pub conversation: Option<ConversationResponse>,
+    /// The latest assistant reply when it failed; retry it through
+    /// `POST /api/v1/chat/messages/{message_id}/retry`
+    pub pending_reply: Option<MessageResponse>,
+    /// The latest message is the user's and its reply is still on the way
+    pub awaiting_reply: bool,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ListConversationsResponseV2 {
     pub conversations: Vec<ConversationResponseV2>,
-    pub total: i64,
+    /// Omitted when the request set `include_total=false`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+    pub has_more: bool,
     pub limit: i64,
     pub offset: i64,
 }
@@ -104,6 +190,23 @@ pub struct ListMessagesResponse {
     pub total: i64,
     pub limit: i64,
     pub offset: i64,
+    /// Present when requested with `include_stats=true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<ConversationStats>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListMessagesResponseV2 {
+    pub conversation_id: String,
+    /// The user the bot is talking to
+    pub user: UserBasicInfo,
+    pub messages: Vec<MessageResponse>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    /// Present when requested with `include_stats=true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<ConversationStats>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -115,20 +218,111 @@ pub struct InfluencerResponse {
     pub description: Option<String>,
     pub category: Option<String>,
     pub is_active: InfluencerStatus,
+    /// Identity confirmed by an admin
+    pub is_verified: bool,
+    /// Run by the platform itself
+    pub is_official: bool,
     pub parent_principal_id: Option<String>,
     pub source: Option<String>,
     pub system_prompt: Option<String>,
     pub created_at: NaiveDateTime,
     pub conversation_count: Option<i64>,
     pub message_count: Option<i64>,
+    pub view_count: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub starter_video_prompt: Option<String>,
+    /// Set while (or after) post-creation enrichment runs; poll
+    /// `/generation-status` for progress
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generation_status: Option<GenerationStatus>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ScheduleResponse {
+    pub influencer_id: String,
+    /// `null` when the influencer has no schedule and is online whenever active
+    pub schedule: Option<AvailabilitySchedule>,
+    pub is_online: bool,
+    /// Next window opening, when currently offline
+    pub next_online_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MediaOnlyPromptResponse {
+    pub influencer_id: String,
+    /// The influencer's own template; `null` when it uses the default
+    pub media_only_prompt: Option<String>,
+    pub default_prompt: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConversationStartersResponse {
+    pub influencer_id: String,
+    pub starters: Vec<ConversationStarter>,
+    /// Untagged suggestions, rotated in alongside the starters
+    pub suggested_messages: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SuggestionStat {
+    pub text: String,
+    pub taps: i64,
+    /// Taps per conversation started in the window
+    pub click_through_rate: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SuggestionStatsResponse {
+    pub influencer_id: String,
+    pub days: i32,
+    pub conversations_started: i64,
+    /// Conversations in which at least one suggestion was tapped
+    pub conversations_with_tap: i64,
+    pub click_through_rate: f64,
+    /// The influencer's current suggested messages
+    pub suggestions: Vec<SuggestionStat>,
+    /// Taps on text that is no longer suggested (rotated out or edited)
+    pub other_taps: i64,
+    pub last_rotated_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RegenerateGreetingResponse {
+    pub influencer_id: String,
+    pub initial_greeting: String,
+    pub suggested_messages: Vec<String>,
+    /// Existing conversations whose greeting was replaced
+    pub conversations_updated: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StarterVideoPromptResponse {
+    pub influencer_id: String,
+    pub starter_video_prompt: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GenerationStatusResponse {
+    pub influencer_id: String,
+    pub status: GenerationStatus,
+    pub steps: BTreeMap<String, GenerationStatus>,
+    pub avatar_url: Option<String>,
+    pub initial_greeting: Option<String>,
+    pub suggested_messages: Vec<String>,
+    pub starter_video_prompt: Option<String>,
+    pub starter_video_url: Option<String>,
+    /// Safety checks of the avatar, oldest first; empty when moderation is off
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub avatar_moderation: Vec<AvatarModerationEntry>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ListInfluencersResponse {
     pub influencers: Vec<InfluencerResponse>,
-    pub total: i64,
+    /// Omitted when the request set `include_total=false`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+    pub has_more: bool,
     pub limit: i64,
     pub offset: i64,
 }
@@ -142,15 +336,31 @@ pub struct TrendingInfluencerResponse {
     pub description: Option<String>,
     pub category: Option<String>,
     pub is_active: InfluencerStatus,
+    pub is_verified: bool,
+    pub is_official: bool,
     pub created_at: NaiveDateTime,
     pub conversation_count: i64,
     pub message_count: i64,
+    pub view_count: i64,
+}
+
+/// Influencers ranked by recent activity, for discovery.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LeaderboardResponse {
+    pub window: LeaderboardWindow,
+    pub metric: LeaderboardMetric,
+    pub entries: Vec<LeaderboardEntry>,
+    /// When the ranking was computed; it is cached for a few minutes
+    pub generated_at: NaiveDateTime,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ListTrendingInfluencersResponse {
     pub influencers: Vec<TrendingInfluencerResponse>,
-    pub total: i64,
+    /// Omitted when the request set `include_total=false`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+    pub has_more: bool,
     pub limit: i64,
     pub offset: i64,
 }
@@ -161,6 +371,13 @@ pub struct SystemPromptResponse {
 }
 
 #[derive(Debug, Serialize, ToSchema)]
+pub struct PreviewChatResponse {
+    pub reply: String,
+    /// The draft the reply was generated with, as saved
+    pub draft_system_instructions: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct GeneratedMetadataResponse {
     pub is_valid: bool,
     pub reason: Option<String>,
@@ -188,6 +405,95 @@ pub struct MarkConversationAsReadResponse {
     pub last_read_at: NaiveDateTime,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ParticipantResponse {
+    pub user_id: String,
+    pub role: ParticipantRole,
+    pub invited_by: String,
+    pub joined_at: NaiveDateTime,
+    pub last_read_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListParticipantsResponse {
+    pub conversation_id: String,
+    /// Principal that created the conversation.
+    pub owner_id: String,
+    pub participants: Vec<ParticipantResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RemoveParticipantResponse {
+    pub success: bool,
+    pub conversation_id: String,
+    pub user_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MuteResponse {
+    pub conversation_id: String,
+    pub muted: bool,
+    /// End of the mute; `null` while muted means until unmuted
+    pub muted_until: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TakeoverResponse {
+    pub conversation_id: String,
+    pub active: bool,
+    pub taken_over_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResponseStyleResponse {
+    pub conversation_id: String,
+    pub length: ResponseLength,
+    /// `null` when emoji use is left to the influencer
+    pub emoji: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConversationSettingsResponse {
+    pub conversation_id: String,
+    pub creativity: Creativity,
+    pub safe_mode: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LanguageResponse {
+    pub conversation_id: String,
+    pub preferred_language: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TranslateMessageResponse {
+    pub message_id: String,
+    pub language: String,
+    pub translated_content: String,
+    /// True when served from the translation cached on the message
+    pub cached: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ContextTokenEstimate {
+    pub system_instructions: i32,
+    pub history: i32,
+    pub total: i32,
+    pub max_output_tokens: u32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DebugContextResponse {
+    pub conversation_id: String,
+    pub influencer_id: String,
+    pub provider: String,
+    pub model: String,
+    pub system_instructions: String,
+    pub memories: std::collections::HashMap<String, String>,
+    pub history: Vec<MessageResponse>,
+    pub token_estimate: ContextTokenEstimate,
+}
+
 // ── Health / Status ──
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -218,9 +524,265 @@ pub struct StatusResponse {
     pub uptime_seconds: u64,
     pub database: DatabaseStats,
     pub statistics: SystemStatistics,
+    pub influencer_cache: CacheStats,
+    pub caller_type_cache: CacheStats,
+    pub profile_cache: ProfileCacheStats,
+    /// Guardrail-wrapped system prompts reused across turns
+    pub prompt_cache: CacheStats,
+    pub memories: MemoryStats,
+    pub load_shedding: Vec<LoadShedStats>,
+    pub websocket: WsStats,
+    pub tenants: Vec<TenantStats>,
+    /// Latest backup verification; absent until one has run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<BackupVerificationResponse>,
     pub timestamp: NaiveDateTime,
 }
 
+/// Per-table reconciliation of a legacy import: every `source` row ends up in
+/// exactly one of the other three buckets.
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ImportCounts {
+    pub source: u64,
+    pub imported: u64,
+    pub already_present: u64,
+    pub skipped: u64,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct MediaImportCounts {
+    pub rekeyed: u64,
+    pub unchanged: u64,
+    pub failed: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditEntryResponse {
+    pub id: String,
+    pub actor: String,
+    pub action: AuditAction,
+    pub target_type: String,
+    pub target_id: Option<String>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub request_id: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListAuditLogResponse {
+    pub entries: Vec<AuditEntryResponse>,
+    /// Omitted when the request set `include_total=false`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+    pub has_more: bool,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SettingsReloadResponse {
+    /// Settings whose value changed, as `tenant.setting` for tenants other than the
+    /// default; empty when the reload found nothing new
+    pub changed: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AiSamplingResponse {
+    pub influencer_id: String,
+    /// The influencer's own rate; null when it uses the default
+    pub rate: Option<f64>,
+    /// Rate applied to the influencer's turns
+    pub effective_rate: f64,
+    /// Whether sampling is switched on at all (`AI_SAMPLE_LOGGING_ENABLED`)
+    pub logging_enabled: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListAbuseEventsResponse {
+    /// Newest first
+    pub events: Vec<AbuseEvent>,
+    /// Omitted when the request set `include_total=false`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+    pub has_more: bool,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListAiSamplesResponse {
+    pub influencer_id: String,
+    /// Newest first
+    pub samples: Vec<AiSample>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListChangesResponse {
+    pub entries: Vec<ChangeLogEntry>,
+    /// Pass as `after_seq` for the next page
+    pub next_seq: i64,
+    pub has_more: bool,
+    /// Highest seq recorded so far
+    pub latest_seq: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevokedTokenResponse {
+    pub jti: String,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CallerTypeInvalidationResponse {
+    pub principal: String,
+    /// False when nothing was cached for the principal
+    pub invalidated: bool,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct LegacyImportResponse {
+    pub influencers: ImportCounts,
+    pub conversations: ImportCounts,
+    pub messages: ImportCounts,
+    pub media: MediaImportCounts,
+    /// Why rows were skipped or media failed to move (first 100 only)
+    pub issues: Vec<String>,
+}
+
+/// Outcome of restoring the latest Litestream backup and checking the copy.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BackupVerificationResponse {
+    pub ok: bool,
+    pub checked_at: NaiveDateTime,
+    pub duration_ms: i64,
+    /// What `PRAGMA integrity_check` reported, `ok` when the copy is sound
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<String>,
+    pub tables: Vec<BackupTableCount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BackupTableCount {
+    pub table: String,
+    pub restored: i64,
+    /// Rows in the live database; absent when it isn't the replicated one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub live: Option<i64>,
+}
+
+/// A Replicate prediction this instance is waiting on.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InFlightPrediction {
+    /// Replicate's id; absent until the create request has returned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub model: String,
+    pub kind: PredictionKind,
+    pub started_at: NaiveDateTime,
+    pub elapsed_seconds: u64,
+}
+
+/// Predictions started today (UTC) against the daily limit.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PredictionBudget {
+    pub kind: PredictionKind,
+    pub used: u32,
+    /// 0 when there is no limit
+    pub limit: u32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListPredictionsResponse {
+    /// Oldest first
+    pub predictions: Vec<InFlightPrediction>,
+    pub budget: Vec<PredictionBudget>,
+}
+
+/// Requests seen per tenant since startup.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TenantStats {
+    pub tenant: String,
+    pub requests: u64,
+    /// Requests the rate limiter turned away
+    pub rate_limited: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+}
+
+/// User profile cache; stale hits were served while a background refresh ran.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProfileCacheStats {
+    pub entries: usize,
+    pub hits: u64,
+    pub stale_hits: u64,
+    pub misses: u64,
+    /// Fetches where the metadata server or canister failed (results not cached)
+    pub fetch_failures: u64,
+    pub hit_rate: f64,
+}
+
+/// Conversation memories saved since startup and how large the saved sets were.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MemoryStats {
+    pub updates: u64,
+    /// Memories dropped for exceeding `MEMORY_MAX_COUNT`, least recently referenced first
+    pub pruned: u64,
+    /// Passes where the model merged redundant memories
+    pub consolidations: u64,
+    pub consolidation_failures: u64,
+    /// Mean characters (keys plus values) per saved memory set
+    pub mean_chars: f64,
+    /// Saved memory sets by number of memories
+    pub size_distribution: Vec<MemorySizeBucket>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MemorySizeBucket {
+    /// Memory count range, e.g. "6-10"
+    pub range: String,
+    pub updates: u64,
+}
+
+/// WebSocket delivery: events waiting on clients and what slow clients lost.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WsStats {
+    pub connections: usize,
+    pub queued: usize,
+    /// Events dropped from full queues by connections still open
+    pub dropped: u64,
+    /// Connections closed for staying behind too long
+    pub slow_disconnects: u64,
+    /// Connections whose queue is currently full
+    pub lagging: Vec<WsConnectionLag>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WsConnectionLag {
+    pub conn_id: u64,
+    pub queued: usize,
+    pub dropped: u64,
+    /// How long the queue has been full
+    pub lagging_seconds: u64,
+}
+
+/// Concurrency cap of one route class and how many requests it has turned away.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LoadShedStats {
+    pub class: &'static str,
+    pub limit: usize,
+    pub in_flight: usize,
+    pub shed: u64,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct DatabaseStats {
     pub connected: bool,
@@ -284,6 +846,19 @@ pub struct ConversationReadEvent {
     pub data: ConversationReadEventData,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConversationUpdatedEventData {
+    pub conversation_id: String,
+    pub updated_at: String,
+    pub last_message: MessageResponse,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConversationUpdatedEvent {
+    pub event: String,
+    pub data: ConversationUpdatedEventData,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct TypingStatusEventData {
     pub conversation_id: String,
@@ -301,5 +876,211 @@ pub struct TypingStatusEvent {
 pub struct WsDocsResponse {
     pub new_message: NewMessageEvent,
     pub conversation_read: ConversationReadEvent,
+    pub conversation_updated: ConversationUpdatedEvent,
     pub typing_status: TypingStatusEvent,
 }
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DigestSubscriptionResponse {
+    pub user_id: String,
+    pub subscribed: bool,
+    pub frequency: Option<DigestFrequency>,
+    pub last_sent_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuietHoursResponse {
+    pub user_id: String,
+    pub enabled: bool,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub timezone: Option<String>,
+    pub send_digest: bool,
+    /// Whether pushes are being held back right now
+    pub active_now: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DigestConversationItem {
+    pub conversation_id: String,
+    pub influencer_id: String,
+    pub influencer_name: String,
+    pub unread_count: i64,
+    pub latest_message: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DigestPreviewResponse {
+    pub has_activity: bool,
+    pub title: Option<String>,
+    pub body: Option<String>,
+    pub conversations: Vec<DigestConversationItem>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookResponse {
+    pub id: String,
+    pub influencer_id: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub is_active: bool,
+    pub created_at: NaiveDateTime,
+    /// Signing secret; only returned when the webhook is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListWebhooksResponse {
+    pub influencer_id: String,
+    pub webhooks: Vec<WebhookResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeleteWebhookResponse {
+    pub success: bool,
+    pub webhook_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListKnowledgeDocumentsResponse {
+    pub influencer_id: String,
+    pub documents: Vec<KnowledgeDocument>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeleteKnowledgeDocumentResponse {
+    pub success: bool,
+    pub document_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SemanticSearchMessageHit {
+    pub message_id: String,
+    pub conversation_id: String,
+    pub influencer_id: String,
+    pub role: MessageRole,
+    pub content: String,
+    pub created_at: NaiveDateTime,
+    /// Cosine similarity to the query, 0.0-1.0
+    pub score: f32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SemanticSearchConversationHit {
+    pub conversation_id: String,
+    pub influencer_id: String,
+    /// Score of the conversation's best matching message
+    pub score: f32,
+    /// Messages in the conversation that matched
+    pub matches: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SemanticSearchResponse {
+    pub query: String,
+    /// Best matching messages first
+    pub messages: Vec<SemanticSearchMessageHit>,
+    /// Conversations ranked by their best matching message
+    pub conversations: Vec<SemanticSearchConversationHit>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookDeliveryResponse {
+    pub id: String,
+    pub event: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub payload: serde_json::Value,
+    pub created_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListWebhookDeliveriesResponse {
+    pub webhook_id: String,
+    pub deliveries: Vec<WebhookDeliveryResponse>,
+    /// Omitted when the request set `include_total=false`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+    pub has_more: bool,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InboundEmailResponse {
+    pub conversation_id: String,
+    pub user_message_id: String,
+    pub assistant_message_id: Option<String>,
+    /// Whether the assistant reply was emailed back to the sender
+    pub replied: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TelegramBotResponse {
+    pub influencer_id: String,
+    pub bot_username: String,
+    /// `https://t.me/{bot_username}`
+    pub bot_url: String,
+    pub connected_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DisconnectTelegramResponse {
+    pub success: bool,
+    pub influencer_id: String,
+}
+
+/// Anonymized message inside a shared snapshot
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SharedMessage {
+    pub role: MessageRole,
+    pub content: Option<String>,
+    pub message_type: MessageType,
+    pub media_urls: Vec<String>,
+    pub audio_url: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SharedInfluencer {
+    pub name: String,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SharedConversationResponse {
+    pub token: String,
+    pub influencer: SharedInfluencer,
+    pub messages: Vec<SharedMessage>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShareResponse {
+    pub token: String,
+    pub conversation_id: String,
+    /// Public path serving the snapshot
+    pub share_path: String,
+    pub message_count: usize,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevokeShareResponse {
+    pub success: bool,
+    pub token: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MessagePermalinkResponse {
+    pub conversation_id: String,
+    /// Owner of the conversation
+    pub user_id: String,
+    pub influencer: InfluencerBasicInfo,
+    pub message: MessageResponse,
+}
@@ -0,0 +1,697 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Multipart, Path as PathParam, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+use super::pagination::{count_if, trim_page};
+use crate::AppState;
+use crate::db::repositories::{AbuseEventFilter, AuditLogFilter};
+use crate::error::{AppError, ErrorBody};
+use crate::middleware::{RequestId, ValidatedJson, has_admin_key};
+use crate::models::entities::Message;
+use crate::models::entities::{AuditAction, AuditEntry};
+use crate::models::requests::{
+    AbuseEventsParams, AiSamplesParams, AuditLogParams, ChangeLogParams, LegacyImportBody,
+    RevokeTokenRequest, UpdateAiSamplingRequest,
+};
+use crate::models::responses::{
+    AiSamplingResponse, AuditEntryResponse, BackupVerificationResponse,
+    CallerTypeInvalidationResponse, LegacyImportResponse, ListAbuseEventsResponse,
+    ListAiSamplesResponse, ListAuditLogResponse, ListChangesResponse, ListPredictionsResponse,
+    RevokedTokenResponse, SettingsReloadResponse,
+};
+use crate::services::ai_samples::AiSampler;
+use crate::services::audit::{self, ADMIN_ACTOR, AuditEvent};
+use crate::services::backup_verify;
+use crate::services::legacy_import::LegacyDump;
+
+/// Issues listed in the response; the counts cover the rest.
+const MAX_ISSUES: usize = 100;
+
+fn note(report: &mut LegacyImportResponse, issue: String) {
+    if report.issues.len() < MAX_ISSUES {
+        report.issues.push(issue);
+    }
+}
+
+/// Import a SQLite database from the legacy Python backend (admin only) — requires X-Admin-Key header
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/import/legacy",
+    request_body(content = LegacyImportBody, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, body = LegacyImportResponse, description = "Import finished; see counts for reconciliation"),
+        (status = 400, body = ErrorBody, description = "Upload is not a legacy database"),
+        (status = 401, body = ErrorBody, description = "Missing or invalid admin key")
+    ),
+    tag = "Admin"
+)]
+pub async fn import_legacy(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    request_id: RequestId,
+    multipart: Multipart,
+) -> Result<Json<LegacyImportResponse>, AppError> {
+    if !has_admin_key(&headers, &state.settings.load()) {
+        return Err(AppError::unauthorized("Invalid or missing admin key"));
+    }
+
+    // SQLite needs a real file to open, so the upload is spooled to disk first
+    let path = std::env::temp_dir().join(format!("legacy-import-{}.db", Uuid::new_v4()));
+    let result = match receive_upload(multipart, &path).await {
+        Ok(rekey_media) => run_import(&state, &path, rekey_media).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = tokio::fs::remove_file(&path).await {
+        tracing::warn!(error = %e, path = %path.display(), "Failed to remove legacy import file");
+    }
+    let report = result?;
+
+    tracing::info!(
+        influencers = report.influencers.imported,
+        conversations = report.conversations.imported,
+        messages = report.messages.imported,
+        skipped_messages = report.messages.skipped,
+        media_failed = report.media.failed,
+        "Legacy import finished"
+    );
+    audit::record(
+        &state.db,
+        ADMIN_ACTOR,
+        &request_id,
+        AuditEvent {
+            action: AuditAction::LegacyImported,
+            target_type: "database",
+            target_id: None,
+            before: None,
+            after: audit::snapshot(&report),
+        },
+    )
+    .await;
+
+    Ok(Json(report))
+}
+
+/// Write the `file` field to `path`. Returns whether media should be re-keyed.
+async fn receive_upload(mut multipart: Multipart, path: &Path) -> Result<bool, AppError> {
+    let mut received = false;
+    let mut rekey_media = true;
+
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::multipart("Invalid multipart data", e))?
+    {
+        match field.name().unwrap_or("") {
+            "file" => {
+                let mut file = tokio::fs::File::create(path)
+                    .await
+                    .map_err(anyhow::Error::from)?;
+                while let Some(chunk) = field
+                    .chunk()
+                    .await
+                    .map_err(|e| AppError::multipart("Failed to read file", e))?
+                {
+                    file.write_all(&chunk).await.map_err(anyhow::Error::from)?;
+                }
+                file.flush().await.map_err(anyhow::Error::from)?;
+                received = true;
+            }
+            "rekey_media" => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| AppError::multipart("Failed to read rekey_media", e))?;
+                rekey_media = value.trim() != "false";
+            }
+            _ => {}
+        }
+    }
+
+    if !received {
+        return Err(AppError::bad_request("Missing 'file' field in upload"));
+    }
+    Ok(rekey_media)
+}
+
+/// Copy influencers, then each conversation with its messages. Rows keep their ids,
+/// so running the same dump again only fills in what is missing.
+async fn run_import(
+    state: &AppState,
+    path: &Path,
+    rekey_media: bool,
+) -> Result<LegacyImportResponse, AppError> {
+    let dump = LegacyDump::open(path).await?;
+    let repo = state.db.legacy_import_repo();
+    let mut report = LegacyImportResponse::default();
+    let mut known_influencers = HashSet::new();
+
+    for inf in dump.influencers().await? {
+        report.influencers.source += 1;
+        if repo.insert_influencer(&inf).await? {
+            report.influencers.imported += 1;
+        } else if repo.influencer_exists(&inf.id).await? {
+            report.influencers.already_present += 1;
+        } else {
+            report.influencers.skipped += 1;
+            note(
+                &mut report,
+                format!(
+                    "Influencer {}: name '{}' is already taken",
+                    inf.id, inf.name
+                ),
+            );
+            continue;
+        }
+        known_influencers.insert(inf.id);
+    }
+
+    for conv in dump.conversations().await? {
+        report.conversations.source += 1;
+        let messages = dump.messages(&conv.id).await?;
+
+        let skip_reason = if !known_influencers.contains(&conv.influencer_id)
+            && !repo.influencer_exists(&conv.influencer_id).await?
+        {
+            Some(format!("unknown influencer {}", conv.influencer_id))
+        } else if repo.insert_conversation(&conv).await? {
+            report.conversations.imported += 1;
+            None
+        } else if repo.conversation_exists(&conv.id).await? {
+            report.conversations.already_present += 1;
+            None
+        } else {
+            Some("the user already has a conversation with this influencer".to_string())
+        };
+        if let Some(reason) = skip_reason {
+            report.conversations.skipped += 1;
+            report.messages.source += messages.len() as u64;
+            report.messages.skipped += messages.len() as u64;
+            note(&mut report, format!("Conversation {}: {reason}", conv.id));
+            continue;
+        }
+
+        for msg in messages {
+            report.messages.source += 1;
+            let mut msg = match msg {
+                Ok(msg) => msg,
+                Err(reason) => {
+                    report.messages.skipped += 1;
+                    note(&mut report, reason);
+                    continue;
+                }
+            };
+            let has_media = !msg.media_urls.is_empty() || msg.audio_url.is_some();
+            if rekey_media && has_media && !repo.message_exists(&msg.id).await? {
+                rekey(state, &mut msg, &conv.user_id, &mut report).await;
+            }
+            if repo.insert_message(&msg).await? {
+                report.messages.imported += 1;
+            } else {
+                report.messages.already_present += 1;
+            }
+        }
+        repo.restore_updated_at(&conv.id, &conv.updated_at).await?;
+    }
+
+    dump.close().await;
+    Ok(report)
+}
+
+/// Move a message's media under its owner's prefix. References that fail to move are
+/// kept as they were so the message still imports.
+async fn rekey(
+    state: &AppState,
+    msg: &mut Message,
+    user_id: &str,
+    report: &mut LegacyImportResponse,
+) {
+    let refs = msg.media_urls.iter_mut().chain(msg.audio_url.as_mut());
+    for url in refs {
+        match state.storage.import_object(url, user_id).await {
+            Ok(key) if key == *url => report.media.unchanged += 1,
+            Ok(key) => {
+                *url = key;
+                report.media.rekeyed += 1;
+            }
+            Err(e) => {
+                report.media.failed += 1;
+                note(
+                    report,
+                    format!("Message {}: media {url} not moved: {e}", msg.id),
+                );
+            }
+        }
+    }
+}
+
+/// Drop a principal's cached bot/user account type (admin only) — requires X-Admin-Key header
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/caller-types/{principal}",
+    params(("principal" = String, Path, description = "Caller principal")),
+    responses(
+        (status = 200, body = CallerTypeInvalidationResponse, description = "Cache entry dropped"),
+        (status = 401, body = ErrorBody, description = "Missing or invalid admin key")
+    ),
+    tag = "Admin"
+)]
+pub async fn invalidate_caller_type(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    request_id: RequestId,
+    PathParam(principal): PathParam<String>,
+) -> Result<Json<CallerTypeInvalidationResponse>, AppError> {
+    if !has_admin_key(&headers, &state.settings.load()) {
+        return Err(AppError::unauthorized("Invalid or missing admin key"));
+    }
+
+    let invalidated = state.caller_types.invalidate(&principal);
+    audit::record(
+        &state.db,
+        ADMIN_ACTOR,
+        &request_id,
+        AuditEvent::new(AuditAction::CallerTypeInvalidated, "principal", &principal),
+    )
+    .await;
+    Ok(Json(CallerTypeInvalidationResponse {
+        principal,
+        invalidated,
+    }))
+}
+
+/// Revoke a JWT by its jti before it expires (admin only) — requires X-Admin-Key header
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/revoked-tokens",
+    request_body = RevokeTokenRequest,
+    responses(
+        (status = 201, body = RevokedTokenResponse, description = "Token revoked"),
+        (status = 401, body = ErrorBody, description = "Missing or invalid admin key"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Admin"
+)]
+pub async fn revoke_token(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    request_id: RequestId,
+    ValidatedJson(req): ValidatedJson<RevokeTokenRequest>,
+) -> Result<(StatusCode, Json<RevokedTokenResponse>), AppError> {
+    if !has_admin_key(&headers, &state.settings.load()) {
+        return Err(AppError::unauthorized("Invalid or missing admin key"));
+    }
+
+    // Tokens are accepted for the leeway past their exp, so the revocation must outlast it
+    let leeway = chrono::Duration::seconds(state.settings.load().jwt_leeway_seconds as i64);
+    let expires_at = chrono::DateTime::from_timestamp(req.exp, 0)
+        .and_then(|exp| exp.checked_add_signed(leeway))
+        .ok_or_else(|| AppError::field_error("exp", "exp is out of range"))?
+        .naive_utc();
+
+    state
+        .db
+        .revoked_token_repo()
+        .revoke(&req.jti, expires_at, req.reason.as_deref())
+        .await?;
+    state.revoked_tokens.insert(&req.jti, expires_at);
+
+    tracing::info!(jti = %req.jti, expires_at = %expires_at, "Token revoked");
+    audit::record(
+        &state.db,
+        ADMIN_ACTOR,
+        &request_id,
+        AuditEvent {
+            after: Some(serde_json::json!({ "expires_at": expires_at, "reason": req.reason })),
+            ..AuditEvent::new(AuditAction::TokenRevoked, "token", &req.jti)
+        },
+    )
+    .await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(RevokedTokenResponse {
+            jti: req.jti,
+            expires_at,
+        }),
+    ))
+}
+
+fn audit_entry_to_response(entry: AuditEntry) -> AuditEntryResponse {
+    AuditEntryResponse {
+        id: entry.id,
+        actor: entry.actor,
+        action: entry.action,
+        target_type: entry.target_type,
+        target_id: entry.target_id,
+        before: entry.before,
+        after: entry.after,
+        request_id: entry.request_id,
+        created_at: entry.created_at,
+    }
+}
+
+/// Audit log of sensitive changes, newest first (admin only) — requires X-Admin-Key header
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit-log",
+    params(AuditLogParams),
+    responses(
+        (status = 200, body = ListAuditLogResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Missing or invalid admin key")
+    ),
+    tag = "Admin"
+)]
+pub async fn list_audit_log(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<AuditLogParams>,
+) -> Result<Json<ListAuditLogResponse>, AppError> {
+    if !has_admin_key(&headers, &state.settings.load()) {
+        return Err(AppError::unauthorized("Invalid or missing admin key"));
+    }
+
+    let limit = params.limit();
+    let offset = params.offset();
+    let include_total = params.include_total();
+    let filter = AuditLogFilter {
+        actor: params.actor,
+        action: params.action,
+        target_type: params.target_type,
+        target_id: params.target_id,
+        since: params.since,
+        until: params.until,
+    };
+    let repo = state.db.audit_log_repo();
+    let (mut entries, total) = tokio::try_join!(
+        repo.list(&filter, limit + 1, offset),
+        count_if(include_total, repo.count(&filter)),
+    )?;
+    let has_more = trim_page(&mut entries, limit);
+
+    Ok(Json(ListAuditLogResponse {
+        entries: entries.into_iter().map(audit_entry_to_response).collect(),
+        total,
+        has_more,
+        limit,
+        offset,
+    }))
+}
+
+/// Flood and spam penalties given to users, newest first (admin only) — requires X-Admin-Key header
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/abuse-events",
+    params(AbuseEventsParams),
+    responses(
+        (status = 200, body = ListAbuseEventsResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Missing or invalid admin key")
+    ),
+    tag = "Admin"
+)]
+pub async fn list_abuse_events(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<AbuseEventsParams>,
+) -> Result<Json<ListAbuseEventsResponse>, AppError> {
+    if !has_admin_key(&headers, &state.settings.load()) {
+        return Err(AppError::unauthorized("Invalid or missing admin key"));
+    }
+
+    let limit = params.limit();
+    let offset = params.offset();
+    let include_total = params.include_total();
+    let filter = AbuseEventFilter {
+        user_id: params.user_id,
+        conversation_id: params.conversation_id,
+        kind: params.kind,
+    };
+    let repo = state.db.abuse_event_repo();
+    let (mut events, total) = tokio::try_join!(
+        repo.list(&filter, limit + 1, offset),
+        count_if(include_total, repo.count(&filter)),
+    )?;
+    let has_more = trim_page(&mut events, limit);
+
+    Ok(Json(ListAbuseEventsResponse {
+        events,
+        total,
+        has_more,
+        limit,
+        offset,
+    }))
+}
+
+/// Conversation and message changes after a seq, oldest first (admin only) — requires X-Admin-Key header
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/changes",
+    params(ChangeLogParams),
+    responses(
+        (status = 200, body = ListChangesResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Missing or invalid admin key")
+    ),
+    tag = "Admin"
+)]
+pub async fn list_changes(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<ChangeLogParams>,
+) -> Result<Json<ListChangesResponse>, AppError> {
+    if !has_admin_key(&headers, &state.settings.load()) {
+        return Err(AppError::unauthorized("Invalid or missing admin key"));
+    }
+
+    let after_seq = params.after_seq();
+    let limit = params.limit();
+    let repo = state.db.change_log_repo();
+    let (mut entries, latest_seq) =
+        tokio::try_join!(repo.list_since(after_seq, limit + 1), repo.latest_seq())?;
+    let has_more = trim_page(&mut entries, limit);
+
+    Ok(Json(ListChangesResponse {
+        next_seq: entries.last().map_or(after_seq, |e| e.seq),
+        entries,
+        has_more,
+        latest_seq,
+    }))
+}
+
+/// Recent sampled AI turns of an influencer, newest first (admin only) — requires X-Admin-Key header
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/influencers/{influencer_id}/ai-samples",
+    params(
+        ("influencer_id" = String, Path, description = "Influencer ID"),
+        AiSamplesParams
+    ),
+    responses(
+        (status = 200, body = ListAiSamplesResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Missing or invalid admin key"),
+        (status = 404, body = ErrorBody, description = "Influencer not found")
+    ),
+    tag = "Admin"
+)]
+pub async fn list_ai_samples(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    request_id: RequestId,
+    PathParam(influencer_id): PathParam<String>,
+    Query(params): Query<AiSamplesParams>,
+) -> Result<Json<ListAiSamplesResponse>, AppError> {
+    if !has_admin_key(&headers, &state.settings.load()) {
+        return Err(AppError::unauthorized("Invalid or missing admin key"));
+    }
+
+    let influencer = state
+        .db
+        .inf_repo()
+        .get_by_id_or_name(&influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+    let samples = state
+        .db
+        .ai_sample_repo()
+        .list_by_influencer(&influencer.id, params.limit())
+        .await?;
+    audit::record(
+        &state.db,
+        ADMIN_ACTOR,
+        &request_id,
+        AuditEvent {
+            after: Some(serde_json::json!({ "sample_count": samples.len() })),
+            ..AuditEvent::new(AuditAction::AiSamplesExported, "influencer", &influencer.id)
+        },
+    )
+    .await;
+
+    Ok(Json(ListAiSamplesResponse {
+        influencer_id: influencer.id,
+        samples,
+    }))
+}
+
+/// Override the share of an influencer's AI turns that are sampled (admin only) — requires X-Admin-Key header
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/influencers/{influencer_id}/ai-sampling",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    request_body = UpdateAiSamplingRequest,
+    responses(
+        (status = 200, body = AiSamplingResponse, description = "Sampling updated"),
+        (status = 401, body = ErrorBody, description = "Missing or invalid admin key"),
+        (status = 404, body = ErrorBody, description = "Influencer not found"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Admin"
+)]
+pub async fn update_ai_sampling(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    request_id: RequestId,
+    PathParam(influencer_id): PathParam<String>,
+    ValidatedJson(req): ValidatedJson<UpdateAiSamplingRequest>,
+) -> Result<Json<AiSamplingResponse>, AppError> {
+    if !has_admin_key(&headers, &state.settings.load()) {
+        return Err(AppError::unauthorized("Invalid or missing admin key"));
+    }
+
+    let repo = state.db.inf_repo();
+    let mut influencer = repo
+        .get_by_id_or_name(&influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+    let before = influencer.ai_sample_rate();
+
+    match req.rate {
+        Some(rate) => {
+            repo.set_metadata_key(&influencer.id, "ai_sample_rate", &rate.into())
+                .await?;
+            influencer.metadata["ai_sample_rate"] = rate.into();
+        }
+        None => {
+            repo.remove_metadata_key(&influencer.id, "ai_sample_rate")
+                .await?;
+            if let Some(metadata) = influencer.metadata.as_object_mut() {
+                metadata.remove("ai_sample_rate");
+            }
+        }
+    }
+    state.influencer_cache.invalidate(&influencer.id);
+
+    audit::record(
+        &state.db,
+        ADMIN_ACTOR,
+        &request_id,
+        AuditEvent {
+            before: Some(serde_json::json!({ "ai_sample_rate": before })),
+            after: Some(serde_json::json!({ "ai_sample_rate": req.rate })),
+            ..AuditEvent::new(AuditAction::AiSamplingUpdated, "influencer", &influencer.id)
+        },
+    )
+    .await;
+
+    let sampler = AiSampler::from_settings(&state.settings.load());
+    Ok(Json(AiSamplingResponse {
+        rate: influencer.ai_sample_rate(),
+        effective_rate: sampler.rate_for(&influencer),
+        logging_enabled: sampler.is_enabled(),
+        influencer_id: influencer.id,
+    }))
+}
+
+/// Re-read `.env` and the environment and apply the settings that don't need a
+/// restart: rate limits, AI models, max tokens and temperatures, message and
+/// memory limits, and safety and sampling switches (admin only) — requires X-Admin-Key header
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/config/reload",
+    responses(
+        (status = 200, body = SettingsReloadResponse, description = "Settings reloaded"),
+        (status = 401, body = ErrorBody, description = "Missing or invalid admin key")
+    ),
+    tag = "Admin"
+)]
+pub async fn reload_config(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    request_id: RequestId,
+) -> Result<Json<SettingsReloadResponse>, AppError> {
+    if !has_admin_key(&headers, &state.settings.load()) {
+        return Err(AppError::unauthorized("Invalid or missing admin key"));
+    }
+
+    let changed = state.tenants.reload();
+    tracing::info!(?changed, "Settings reloaded");
+    if !changed.is_empty() {
+        audit::record(
+            &state.db,
+            ADMIN_ACTOR,
+            &request_id,
+            AuditEvent {
+                action: AuditAction::SettingsReloaded,
+                target_type: "settings",
+                target_id: None,
+                before: None,
+                after: Some(serde_json::json!({ "changed": changed })),
+            },
+        )
+        .await;
+    }
+
+    Ok(Json(SettingsReloadResponse { changed }))
+}
+
+/// Restore the latest Litestream backup and check it now (admin only) — requires X-Admin-Key header
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/db/verify-backup",
+    responses(
+        (status = 200, body = BackupVerificationResponse, description = "Verification finished; `ok` says whether it passed"),
+        (status = 401, body = ErrorBody, description = "Missing or invalid admin key"),
+        (status = 409, body = ErrorBody, description = "A verification is already running")
+    ),
+    tag = "Admin"
+)]
+pub async fn verify_backup(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<BackupVerificationResponse>, AppError> {
+    if !has_admin_key(&headers, &state.settings.load()) {
+        return Err(AppError::unauthorized("Invalid or missing admin key"));
+    }
+
+    let report = backup_verify::run(&state)
+        .await
+        .ok_or_else(|| AppError::conflict("A backup verification is already running"))?;
+    Ok(Json(report))
+}
+
+/// Replicate predictions this instance is waiting on, and today's budget use (admin only) — requires X-Admin-Key header
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/replicate/predictions",
+    responses(
+        (status = 200, body = ListPredictionsResponse, description = "In-flight predictions"),
+        (status = 401, body = ErrorBody, description = "Missing or invalid admin key")
+    ),
+    tag = "Admin"
+)]
+pub async fn list_predictions(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ListPredictionsResponse>, AppError> {
+    if !has_admin_key(&headers, &state.settings.load()) {
+        return Err(AppError::unauthorized("Invalid or missing admin key"));
+    }
+
+    Ok(Json(ListPredictionsResponse {
+        predictions: state.replicate.in_flight(),
+        budget: state.replicate.budget(),
+    }))
+}
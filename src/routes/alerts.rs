@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+
+use crate::AppState;
+use crate::error::{AppError, ErrorBody};
+use crate::services::sentry_alerts;
+
+/// Relay Sentry alerts to the team's Google Chat space. Authenticated by the
+/// integration's body signature; repeats for the same issue within the dedup
+/// window are acknowledged and dropped.
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/sentry",
+    params(
+        ("Sentry-Hook-Signature" = String, Header, description = "Hex HMAC-SHA256 of the body keyed by the integration's client secret"),
+        ("Sentry-Hook-Resource" = String, Header, description = "Payload kind, e.g. issue or event_alert")
+    ),
+    request_body = Object,
+    responses(
+        (status = 200, description = "Alert accepted"),
+        (status = 400, body = ErrorBody, description = "Malformed payload"),
+        (status = 401, body = ErrorBody, description = "Invalid signature"),
+        (status = 503, body = ErrorBody, description = "Sentry relay not configured")
+    ),
+    tag = "Alerts"
+)]
+pub async fn sentry_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, AppError> {
+    let settings = state.settings.load_full();
+    let Some(secret) = settings.sentry_webhook_secret.as_deref() else {
+        return Err(AppError::service_unavailable(
+            "Sentry relay is not configured",
+        ));
+    };
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+    };
+    if !sentry_alerts::verify_signature(secret, &body, header("Sentry-Hook-Signature")) {
+        return Err(AppError::unauthorized("Invalid signature"));
+    }
+
+    let payload: serde_json::Value = serde_json::from_slice(&body)
+        .map_err(|_| AppError::bad_request("Payload is not valid JSON"))?;
+    // Installation events, resolved issues and the like are acknowledged and ignored
+    let Some(alert) = sentry_alerts::parse(header("Sentry-Hook-Resource"), &payload) else {
+        return Ok(StatusCode::OK);
+    };
+    if !state.sentry_alerts.should_relay(&alert.issue_id) {
+        tracing::debug!(issue_id = %alert.issue_id, "Duplicate Sentry alert dropped");
+        return Ok(StatusCode::OK);
+    }
+
+    // Sentry gives up on slow webhooks, so the relay happens in the background
+    tokio::spawn(async move {
+        state.google_chat.notify_sentry_alert(&alert).await;
+    });
+
+    Ok(StatusCode::OK)
+}
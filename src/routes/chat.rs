@@ -1,24 +1,61 @@
 use std::collections::HashMap;
+use std::fmt::Write;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use axum::Json;
 use axum::extract::{Path, Query, State};
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
 
+use super::pagination::{count_if, trim_page};
 use crate::AppState;
-use crate::db::repositories::{InfluencerRepository, MessageRepository};
+use crate::config::Settings;
+use crate::db::repos::Repos;
+use crate::db::repositories::{MessageRepository, UserConversationFilter};
 use crate::error::{AppError, ErrorBody};
-use crate::middleware::AuthenticatedUser;
-use crate::models::entities::{AIInfluencer, InfluencerStatus, Message, MessageRole, MessageType};
+use crate::middleware::{AuthenticatedUser, Tenant, ValidatedJson, ValidatedQuery, has_admin_key};
+use crate::models::entities::{
+    AIInfluencer, AvailabilitySchedule, AwayMode, ConversationParticipant, DuetMode,
+    FailedGeneration, InfluencerStatus, MESSAGE_STATUS_FAILED, MESSAGE_STATUS_PENDING, Message,
+    MessageProjection, MessageRole, MessageSource, MessageType, ParticipantRole, ScheduledReply,
+    WebhookEvent,
+};
 use crate::models::requests::{
-    CreateConversationRequest, GenerateImageRequest, ListConversationsParams, ListMessagesParams,
-    SendMessageRequest,
+    CreateConversationRequest, CreateDuetRequest, GenerateImageRequest, InviteParticipantRequest,
+    ListConversationsParams, ListMessagesParams, MuteConversationRequest, ResumeParams,
+    SemanticSearchParams, SendMessageRequest, TranslateMessageParams, UpdateConversationRequest,
+    UpdateLanguageRequest, UpdateResponseStyleRequest, is_blank,
 };
 use crate::models::responses::{
-    ConversationResponse, DeleteConversationResponse, InfluencerBasicInfo,
-    ListConversationsResponse, ListMessagesResponse, MarkConversationAsReadResponse,
-    MessageResponse, SendMessageResponse,
+    ContextTokenEstimate, ConversationResponse, ConversationSettingsResponse, DebugContextResponse,
+    DeleteConversationResponse, DuetConversationResponse, InfluencerBasicInfo, LanguageResponse,
+    ListConversationsResponse, ListMessagesResponse, ListParticipantsResponse,
+    MarkConversationAsReadResponse, MessagePermalinkResponse, MessageResponse, MuteResponse,
+    ParticipantResponse, RemoveParticipantResponse, ResponseStyleResponse, ResumeResponse,
+    SemanticSearchConversationHit, SemanticSearchMessageHit, SemanticSearchResponse,
+    SendMessageResponse, TakeoverResponse, TranslateMessageResponse,
 };
+use crate::services::abuse_guard::{self, AbuseLimits, Verdict};
+use crate::services::ai::{AiApi, GenerationOptions, estimate_tokens};
+use crate::services::ai_samples::{AiSampler, SampledTurn};
+use crate::services::conversation_starters::{self, StarterContext};
+use crate::services::image_caption;
+use crate::services::knowledge;
+use crate::services::long_message;
+use crate::services::memory::{self, MemoryLimits};
+use crate::services::message_embeddings;
+use crate::services::moderation;
+use crate::services::notification_format;
+use crate::services::output_sanitizer::{self, OutputPolicy};
+use crate::services::prompt_guard::{self, InjectionStrictness};
+use crate::services::push_batch::PushItem;
+use crate::services::quiet_hours;
+use crate::services::tools::ToolBox;
+use crate::services::webhooks;
+
+/// Failed replies older than this are left as they are.
+const FAILED_REPLY_RETRY_WINDOW_HOURS: i32 = 24;
+const FAILED_REPLY_RETRY_BATCH: i64 = 50;
 
 const FALLBACK_ERROR_MESSAGE: &str =
     "I'm having trouble generating a response right now. Please try again.";
@@ -26,24 +63,67 @@ const FALLBACK_ERROR_MESSAGE: &str =
 /// Check if a user can access a conversation.
 /// Allowed if they are the user, the bot, or the bot's parent (owner).
 async fn can_access_conversation(
+    state: &AppState,
     user_id: &str,
     conv: &crate::models::entities::Conversation,
-    inf_repo: &InfluencerRepository,
 ) -> Result<bool, AppError> {
     if conv.user_id == user_id || conv.influencer_id == user_id {
         return Ok(true);
     }
     // Check if caller is the bot's owner
-    if let Some(parent) = inf_repo.get_parent_principal(&conv.influencer_id).await?
-        && parent == user_id
+    if let Some(influencer) = state
+        .influencer_cache
+        .get_by_id(&state.db.inf_repo(), &conv.influencer_id)
+        .await?
+        && influencer.parent_principal_id.as_deref() == Some(user_id)
     {
         return Ok(true);
     }
     Ok(false)
 }
 
+/// Resolve the caller's access to a conversation.
+/// `Ok(None)` for the creator, the bot or its owner; `Ok(Some(role))` for an invited
+/// group member; `Forbidden` for everyone else.
+async fn authorize_member(
+    state: &AppState,
+    user_id: &str,
+    conv: &crate::models::entities::Conversation,
+) -> Result<Option<ParticipantRole>, AppError> {
+    if can_access_conversation(state, user_id, conv).await? {
+        return Ok(None);
+    }
+    match state.db.part_repo().get(&conv.id, user_id).await? {
+        Some(participant) => Ok(Some(participant.role)),
+        None => Err(AppError::forbidden("Not your conversation")),
+    }
+}
+
+/// What the AI is asked when a message has no text: the influencer's
+/// `media_only_prompt`, else the deployment's, naming the media that was sent.
+fn media_only_input(
+    settings: &Settings,
+    influencer: &AIInfluencer,
+    message_type: &MessageType,
+    media_count: usize,
+) -> String {
+    let media = match (message_type, media_count) {
+        (MessageType::Audio, _) => "voice note",
+        (_, 0 | 1) => "photo",
+        _ => "photos",
+    };
+    influencer
+        .media_only_prompt()
+        .unwrap_or(&settings.media_only_prompt)
+        .replace("{media}", media)
+        .replace("{count}", &media_count.max(1).to_string())
+}
+
 impl From<Message> for MessageResponse {
     fn from(m: Message) -> Self {
+        let speaker_id = m.speaker_id().map(String::from);
+        let alt_text = m.alt_text().map(String::from);
+        let tool_calls = m.tool_calls();
         Self {
             id: m.id,
             role: m.role,
@@ -54,42 +134,95 @@ impl From<Message> for MessageResponse {
             audio_duration_seconds: m.audio_duration_seconds,
             token_count: m.token_count,
             created_at: m.created_at,
+            alt_text,
+            sender_id: m
+                .metadata
+                .get("sender")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            speaker_id,
             status: m.status,
             is_read: m.is_read,
+            generated_by: None,
+            tool_calls,
+        }
+    }
+}
+
+impl SendMessageResponse {
+    /// Response for a message stored without a reply yet. The stand-in reply keeps
+    /// `assistant_message` an object, as clients have always parsed it; it isn't
+    /// stored, so its id only ties it to the user message.
+    fn accepted(user_message: MessageResponse) -> Self {
+        let assistant_message = MessageResponse {
+            id: format!("pending-{}", user_message.id),
+            role: MessageRole::Assistant,
+            content: None,
+            message_type: MessageType::Text,
+            media_urls: Vec::new(),
+            audio_url: None,
+            audio_duration_seconds: None,
+            token_count: None,
+            created_at: user_message.created_at,
+            alt_text: None,
+            sender_id: None,
+            speaker_id: None,
+            status: MESSAGE_STATUS_PENDING.to_string(),
+            is_read: false,
+            generated_by: None,
+            tool_calls: Vec::new(),
+        };
+        Self {
+            user_message,
+            assistant_message,
         }
     }
 }
 
+fn participant_to_response(p: ConversationParticipant) -> ParticipantResponse {
+    ParticipantResponse {
+        user_id: p.user_id,
+        role: p.role,
+        invited_by: p.invited_by,
+        joined_at: p.joined_at,
+        last_read_at: p.last_read_at,
+    }
+}
+
+/// `starters` is who the suggested messages are picked for; `None` leaves them out.
 fn influencer_to_basic_info(
     influencer: &AIInfluencer,
-    include_suggested_messages: bool,
+    starters: Option<&StarterContext>,
 ) -> InfluencerBasicInfo {
     InfluencerBasicInfo {
         id: influencer.id.clone(),
         name: influencer.name.clone(),
         display_name: influencer.display_name.clone(),
         avatar_url: influencer.avatar_url.clone(),
-        is_online: influencer.is_active == InfluencerStatus::Active,
-        suggested_messages: include_suggested_messages
-            .then(|| influencer.suggested_messages.clone()),
+        is_online: influencer.is_online(),
+        is_verified: influencer.is_verified,
+        is_official: influencer.is_official,
+        suggested_messages: starters.map(|ctx| conversation_starters::pick(influencer, ctx)),
     }
 }
 
 fn conversation_to_response(
     conv: crate::models::entities::Conversation,
     recent_messages: Option<Vec<Message>>,
-    include_suggested_messages: bool,
+    starters: Option<&StarterContext>,
 ) -> ConversationResponse {
     let influencer_info = conv
         .influencer
         .as_ref()
-        .map(|i| influencer_to_basic_info(i, include_suggested_messages))
+        .map(|i| influencer_to_basic_info(i, starters))
         .unwrap_or_else(|| InfluencerBasicInfo {
             id: conv.influencer_id.clone(),
             name: String::new(),
             display_name: String::new(),
             avatar_url: None,
             is_online: false,
+            is_verified: false,
+            is_official: false,
             suggested_messages: None,
         });
 
@@ -100,6 +233,7 @@ fn conversation_to_response(
         created_at: conv.created_at,
         updated_at: conv.updated_at,
         message_count: conv.message_count.unwrap_or(0),
+        unread_count: conv.unread_count,
         last_message: conv.last_message,
         recent_messages: recent_messages
             .map(|msgs| msgs.into_iter().map(MessageResponse::from).collect()),
@@ -122,20 +256,25 @@ fn conversation_to_response(
 )]
 pub async fn create_conversation(
     State(state): State<Arc<AppState>>,
+    tenant: Tenant,
     user: AuthenticatedUser,
-    Json(body): Json<CreateConversationRequest>,
+    repos: Repos,
+    headers: HeaderMap,
+    ValidatedJson(body): ValidatedJson<CreateConversationRequest>,
 ) -> Result<(StatusCode, Json<ConversationResponse>), AppError> {
-    let conv_repo = state.db.conv_repo();
-    let inf_repo = state.db.inf_repo();
-    let msg_repo = state.db.msg_repo();
+    let conv_repo = repos.conv();
+    let inf_repo = repos.inf();
+    let msg_repo = repos.msg();
 
-    // Verify influencer exists
+    // Verify influencer exists in this tenant
     let influencer = inf_repo
         .get_by_id(&body.influencer_id)
         .await?
+        .filter(|i| i.tenant == tenant.as_str())
         .ok_or_else(|| {
             AppError::not_found(format!("Influencer '{}' not found", body.influencer_id))
         })?;
+    let starters = StarterContext::resolve(&state, &headers, &user.user_id).await;
 
     // Check for existing conversation
     if let Some(existing) = conv_repo
@@ -152,12 +291,22 @@ pub async fn create_conversation(
 
         return Ok((
             StatusCode::CREATED,
-            Json(conversation_to_response(conv, Some(messages), true)),
+            Json(conversation_to_response(
+                conv,
+                Some(messages),
+                Some(&starters),
+            )),
         ));
     }
 
     // Create new conversation
     let conv = conv_repo.create(&user.user_id, &body.influencer_id).await?;
+    webhooks::dispatch(
+        &state,
+        &conv.influencer_id,
+        WebhookEvent::ConversationCreated,
+        serde_json::json!({ "conversation_id": conv.id, "user_id": conv.user_id }),
+    );
 
     // Generate initial greeting if the influencer has one
     let initial_messages = match influencer.initial_greeting.as_deref() {
@@ -165,7 +314,7 @@ pub async fn create_conversation(
             .create(
                 &conv.id,
                 &MessageRole::Assistant,
-                Some(greeting),
+                Some(&sanitize_assistant_text(&state, greeting).0),
                 &MessageType::Text,
                 &[],
                 None,
@@ -184,7 +333,80 @@ pub async fn create_conversation(
 
     Ok((
         StatusCode::CREATED,
-        Json(conversation_to_response(conv, Some(initial_messages), true)),
+        Json(conversation_to_response(
+            conv,
+            Some(initial_messages),
+            Some(&starters),
+        )),
+    ))
+}
+
+/// Start a duet: one conversation where two influencers take turns replying
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/conversations/duet",
+    request_body = CreateDuetRequest,
+    responses(
+        (status = 201, body = DuetConversationResponse, description = "Duet created"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 404, body = ErrorBody, description = "Influencer not found"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn create_duet(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    headers: HeaderMap,
+    ValidatedJson(body): ValidatedJson<CreateDuetRequest>,
+) -> Result<(StatusCode, Json<DuetConversationResponse>), AppError> {
+    if body.influencer_ids[0] == body.influencer_ids[1] {
+        return Err(AppError::field_error(
+            "influencer_ids",
+            "A duet needs two different influencers",
+        ));
+    }
+
+    let inf_repo = state.db.inf_repo();
+    let mut cast = Vec::with_capacity(body.influencer_ids.len());
+    for influencer_id in &body.influencer_ids {
+        let influencer = inf_repo
+            .get_by_id(influencer_id)
+            .await?
+            .filter(|i| i.is_active != InfluencerStatus::Discontinued)
+            .ok_or_else(|| {
+                AppError::not_found(format!("Influencer '{influencer_id}' not found"))
+            })?;
+        cast.push(influencer);
+    }
+
+    let metadata = serde_json::json!({ "duet": { "mode": body.mode.as_ref() } });
+    let conv = state
+        .db
+        .conv_repo()
+        .create_duet(&user.user_id, &body.influencer_ids, &metadata)
+        .await?;
+    for influencer_id in &body.influencer_ids {
+        webhooks::dispatch(
+            &state,
+            influencer_id,
+            WebhookEvent::ConversationCreated,
+            serde_json::json!({ "conversation_id": conv.id, "user_id": conv.user_id }),
+        );
+    }
+    let starters = StarterContext::resolve(&state, &headers, &user.user_id).await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(DuetConversationResponse {
+            conversation: conversation_to_response(conv, Some(vec![]), Some(&starters)),
+            mode: body.mode,
+            influencers: cast
+                .iter()
+                .map(|i| influencer_to_basic_info(i, Some(&starters)))
+                .collect(),
+        }),
     ))
 }
 
@@ -203,45 +425,234 @@ pub async fn create_conversation(
 )]
 pub async fn list_conversations(
     State(state): State<Arc<AppState>>,
+    tenant: Tenant,
     user: AuthenticatedUser,
+    repos: Repos,
+    headers: HeaderMap,
     Query(params): Query<ListConversationsParams>,
 ) -> Result<Json<ListConversationsResponse>, AppError> {
-    let conv_repo = state.db.conv_repo();
-    let msg_repo = state.db.msg_repo();
+    let conv_repo = repos.conv();
+    let msg_repo = repos.msg();
 
     let limit = params.limit();
     let offset = params.offset();
-    let influencer_id = params.influencer_id.as_deref();
+    let filter = UserConversationFilter {
+        influencer_id: params.influencer_id.as_deref(),
+        unread_only: params.has_unread.unwrap_or(false),
+        sort: params.sort.unwrap_or_default(),
+    };
 
-    let (conversations, total) = tokio::try_join!(
-        conv_repo.list_by_user(&user.user_id, influencer_id, limit, offset),
-        conv_repo.count_by_user(&user.user_id, influencer_id),
+    let (mut conversations, total) = tokio::try_join!(
+        conv_repo.list_by_user(&user.user_id, tenant.as_str(), filter, limit + 1, offset),
+        count_if(
+            params.include_total(),
+            conv_repo.count_by_user(&user.user_id, tenant.as_str(), filter),
+        ),
     )?;
+    let has_more = trim_page(&mut conversations, limit);
+
+    // Group conversations the user was invited into track unread per participant
+    let group_ids: Vec<String> = conversations
+        .iter()
+        .filter(|c| c.user_id != user.user_id)
+        .map(|c| c.id.clone())
+        .collect();
+    let group_unread = repos
+        .part()
+        .unread_counts(&user.user_id, &group_ids)
+        .await?;
+    for conv in &mut conversations {
+        if let Some(&unread) = group_unread.get(&conv.id) {
+            conv.unread_count = unread;
+        }
+    }
 
-    // Batch fetch recent messages
+    // Batch fetch recent messages, trimmed to what the client asked for
+    let recent_limit = params.recent_limit();
+    let fields = params.fields();
     let conv_ids: Vec<String> = conversations.iter().map(|c| c.id.clone()).collect();
     let recent_messages_map = msg_repo
-        .get_recent_for_conversations_batch(&conv_ids, 10)
+        .get_recent_for_conversations_batch(&conv_ids, recent_limit, &fields)
         .await?;
+    // Only show suggested_messages if conversation has <= 1 message (empty or just greeting)
+    let wants_suggested = |conv: &crate::models::entities::Conversation| {
+        fields == MessageProjection::Full && conv.message_count.unwrap_or(0) <= 1
+    };
+    let starters = if conversations.iter().any(wants_suggested) {
+        Some(StarterContext::resolve(&state, &headers, &user.user_id).await)
+    } else {
+        None
+    };
 
-    let conversations = conversations
+    let mut conversations: Vec<ConversationResponse> = conversations
         .into_iter()
         .map(|conv| {
-            let messages = recent_messages_map.get(&conv.id).cloned();
-            // Only show suggested_messages if conversation has <= 1 message (empty or just greeting)
-            let include_suggested = conv.message_count.unwrap_or(0) <= 1;
-            conversation_to_response(conv, messages, include_suggested)
+            let messages = (recent_limit > 0)
+                .then(|| recent_messages_map.get(&conv.id).cloned())
+                .flatten();
+            let starters = starters.as_ref().filter(|_| wants_suggested(&conv));
+            conversation_to_response(conv, messages, starters)
         })
         .collect();
+    for conv in &mut conversations {
+        if let Some(messages) = conv.recent_messages.as_mut() {
+            presign_messages_urls(state.storage.as_ref(), messages).await;
+        }
+    }
 
     Ok(Json(ListConversationsResponse {
         conversations,
         total,
+        has_more,
         limit,
         offset,
     }))
 }
 
+/// Resume the user's most recently active conversation
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/resume",
+    params(ResumeParams),
+    responses(
+        (status = 200, body = ResumeResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn resume(
+    State(state): State<Arc<AppState>>,
+    tenant: Tenant,
+    user: AuthenticatedUser,
+    repos: Repos,
+    headers: HeaderMap,
+    Query(params): Query<ResumeParams>,
+) -> Result<Json<ResumeResponse>, AppError> {
+    let Some((mut conv, messages)) = repos
+        .conv()
+        .resume(&user.user_id, tenant.as_str(), params.message_limit())
+        .await?
+    else {
+        return Ok(Json(ResumeResponse {
+            conversation: None,
+            pending_reply: None,
+            awaiting_reply: false,
+        }));
+    };
+
+    // Invited participants track unread on their own
+    if conv.user_id != user.user_id
+        && let Some(&unread) = repos
+            .part()
+            .unread_counts(&user.user_id, std::slice::from_ref(&conv.id))
+            .await?
+            .get(&conv.id)
+    {
+        conv.unread_count = unread;
+    }
+
+    let latest = messages.last();
+    let failed_reply = latest.is_some_and(|m| m.role == MessageRole::Assistant && m.is_failed());
+    let awaiting_reply = latest.is_some_and(|m| m.role == MessageRole::User);
+    let starters = if conv.message_count.unwrap_or(0) <= 1 {
+        Some(StarterContext::resolve(&state, &headers, &user.user_id).await)
+    } else {
+        None
+    };
+
+    let mut conversation = conversation_to_response(conv, Some(messages), starters.as_ref());
+    let mut pending_reply = None;
+    if let Some(messages) = conversation.recent_messages.as_mut() {
+        presign_messages_urls(state.storage.as_ref(), messages).await;
+        pending_reply = messages.last().filter(|_| failed_reply).cloned();
+    }
+
+    Ok(Json(ResumeResponse {
+        conversation: Some(conversation),
+        pending_reply,
+        awaiting_reply,
+    }))
+}
+
+/// Longest accepted semantic search query.
+const MAX_SEARCH_QUERY_CHARS: usize = 500;
+
+/// Search the user's chat history by meaning
+///
+/// Ranks the user's messages by how close they are in meaning to `q`, so a search
+/// finds a conversation without repeating its exact words. Messages are indexed in
+/// the background and become searchable shortly after they're sent.
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/semantic-search",
+    params(SemanticSearchParams),
+    responses(
+        (status = 200, body = SemanticSearchResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 422, body = ErrorBody, description = "Missing or overlong query"),
+        (status = 503, body = ErrorBody, description = "Semantic search disabled or embedding unavailable")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn semantic_search(
+    State(state): State<Arc<AppState>>,
+    tenant: Tenant,
+    user: AuthenticatedUser,
+    Query(params): Query<SemanticSearchParams>,
+) -> Result<Json<SemanticSearchResponse>, AppError> {
+    if !state.settings.load().message_embeddings_enabled {
+        return Err(AppError::service_unavailable(
+            "Semantic search is not enabled",
+        ));
+    }
+    let query = params.q.trim();
+    if query.is_empty() {
+        return Err(AppError::field_error("q", "Search query is required"));
+    }
+    if query.chars().count() > MAX_SEARCH_QUERY_CHARS {
+        return Err(AppError::field_error(
+            "q",
+            format!("Search query is limited to {MAX_SEARCH_QUERY_CHARS} characters"),
+        ));
+    }
+
+    let (messages, conversations) = message_embeddings::search(
+        &state,
+        &user.user_id,
+        tenant.as_str(),
+        query,
+        params.limit(),
+    )
+    .await?;
+
+    Ok(Json(SemanticSearchResponse {
+        query: query.to_string(),
+        messages: messages
+            .into_iter()
+            .map(|m| SemanticSearchMessageHit {
+                message_id: m.message.message_id,
+                conversation_id: m.message.conversation_id,
+                influencer_id: m.message.influencer_id,
+                role: m.message.role,
+                content: m.message.content,
+                created_at: m.message.created_at,
+                score: m.score,
+            })
+            .collect(),
+        conversations: conversations
+            .into_iter()
+            .map(|c| SemanticSearchConversationHit {
+                conversation_id: c.conversation_id,
+                influencer_id: c.influencer_id,
+                score: c.score,
+                matches: c.matches,
+            })
+            .collect(),
+    }))
+}
+
 /// List messages in a conversation
 #[utoipa::path(
     get,
@@ -263,37 +674,68 @@ pub async fn list_conversations(
 pub async fn list_messages(
     State(state): State<Arc<AppState>>,
     user: AuthenticatedUser,
+    repos: Repos,
+    headers: HeaderMap,
     Path(conversation_id): Path<String>,
     Query(params): Query<ListMessagesParams>,
 ) -> Result<Json<ListMessagesResponse>, AppError> {
-    let conv_repo = state.db.conv_repo();
-    let msg_repo = state.db.msg_repo();
-    let inf_repo = state.db.inf_repo();
+    let conv_repo = repos.conv();
+    let msg_repo = repos.msg();
 
     let conv = conv_repo
         .get_by_id(&conversation_id)
         .await?
         .ok_or_else(|| AppError::not_found("Conversation not found"))?;
 
-    if !can_access_conversation(&user.user_id, &conv, &inf_repo).await? {
-        return Err(AppError::forbidden("Not your conversation"));
+    authorize_member(&state, &user.user_id, &conv).await?;
+
+    if params.include_debug && !has_admin_key(&headers, &state.settings.load()) {
+        let parent = repos
+            .inf()
+            .get_parent_principal(&conv.influencer_id)
+            .await?;
+        if parent.as_deref() != Some(user.user_id.as_str()) {
+            return Err(AppError::forbidden(
+                "Only the bot owner or an admin can include debug details",
+            ));
+        }
     }
 
     let limit = params.limit();
     let offset = params.offset();
     let order = params.order();
 
-    let (messages, total) = tokio::try_join!(
+    let (messages, total, stats) = tokio::try_join!(
         msg_repo.list_by_conversation(&conversation_id, limit, offset, order),
         msg_repo.count_by_conversation(&conversation_id),
+        async {
+            if params.include_stats {
+                msg_repo
+                    .conversation_stats(&conversation_id)
+                    .await
+                    .map(Some)
+            } else {
+                Ok(None)
+            }
+        },
     )?;
 
+    let mut messages: Vec<MessageResponse> = messages
+        .into_iter()
+        .map(|m| MessageResponse {
+            generated_by: m.generated_by().filter(|_| params.include_debug),
+            ..MessageResponse::from(m)
+        })
+        .collect();
+    presign_messages_urls(state.storage.as_ref(), &mut messages).await;
+
     Ok(Json(ListMessagesResponse {
         conversation_id,
-        messages: messages.into_iter().map(MessageResponse::from).collect(),
+        messages,
         total,
         limit,
         offset,
+        stats,
     }))
 }
 
@@ -305,31 +747,36 @@ pub async fn list_messages(
     request_body = SendMessageRequest,
     responses(
         (status = 200, body = SendMessageResponse, description = "Successful response"),
+        (status = 202, body = SendMessageResponse, description = "Stored; a human operator replies, or the reply is still being generated"),
         (status = 401, body = ErrorBody, description = "Unauthorized"),
         (status = 403, body = ErrorBody, description = "Forbidden"),
         (status = 404, body = ErrorBody, description = "Conversation not found"),
-        (status = 422, body = ErrorBody, description = "Validation error")
+        (status = 422, body = ErrorBody, description = "Validation error"),
+        (status = 429, body = ErrorBody, description = "Too many replies in this conversation (`conversation_throttled`)")
     ),
     tag = "Chat",
     security(("BearerAuth" = []))
 )]
 pub async fn send_message(
     State(state): State<Arc<AppState>>,
+    tenant: Tenant,
     user: AuthenticatedUser,
     Path(conversation_id): Path<String>,
-    Json(body): Json<SendMessageRequest>,
+    ValidatedJson(body): ValidatedJson<SendMessageRequest>,
 ) -> Result<(StatusCode, Json<SendMessageResponse>), AppError> {
     let conv_repo = state.db.conv_repo();
     let msg_repo = state.db.msg_repo();
     let inf_repo = state.db.inf_repo();
 
+    let tenant_settings = state.tenants.settings(tenant.as_str());
+
     // Validate
-    body.validate_content()
-        .map_err(AppError::validation_error)?;
+    body.validate_content(tenant_settings.message_max_chars)
+        .map_err(|(field, msg)| AppError::field_error(field, msg))?;
 
     let message_type = body
         .parsed_message_type()
-        .ok_or_else(|| AppError::validation_error("Invalid message type"))?;
+        .ok_or_else(|| AppError::field_error("message_type", "Invalid message type"))?;
 
     // Verify conversation
     let conv = conv_repo
@@ -337,8 +784,8 @@ pub async fn send_message(
         .await?
         .ok_or_else(|| AppError::not_found("Conversation not found"))?;
 
-    if !can_access_conversation(&user.user_id, &conv, &inf_repo).await? {
-        return Err(AppError::forbidden("Not your conversation"));
+    if authorize_member(&state, &user.user_id, &conv).await? == Some(ParticipantRole::Viewer) {
+        return Err(AppError::forbidden("Viewers cannot send messages"));
     }
 
     // Deduplication
@@ -346,19 +793,28 @@ pub async fn send_message(
         && let Some(existing) = msg_repo
             .get_by_client_id(&conversation_id, client_id)
             .await?
-        && let Some(reply) = msg_repo.get_assistant_reply(&existing.id).await?
     {
-        return Ok((
-            StatusCode::OK,
-            Json(SendMessageResponse {
-                user_message: MessageResponse::from(existing),
-                assistant_message: MessageResponse::from(reply),
-            }),
-        ));
+        // A resend after a dropped connection. If the reply is still being
+        // generated it arrives over WebSocket/push once stored
+        let user_resp = MessageResponse::from(existing);
+        return Ok(match msg_repo.get_assistant_reply(&user_resp.id).await? {
+            Some(reply) => (
+                StatusCode::OK,
+                Json(SendMessageResponse {
+                    user_message: user_resp,
+                    assistant_message: MessageResponse::from(reply),
+                }),
+            ),
+            None => (
+                StatusCode::ACCEPTED,
+                Json(SendMessageResponse::accepted(user_resp)),
+            ),
+        });
     }
 
-    let influencer = inf_repo
-        .get_by_id(&conv.influencer_id)
+    let influencer = state
+        .influencer_cache
+        .get_by_id(&inf_repo, &conv.influencer_id)
         .await?
         .ok_or_else(|| AppError::not_found("Influencer not found"))?;
 
@@ -369,15 +825,47 @@ pub async fn send_message(
         ));
     }
 
+    let shadow_limited = screen_for_abuse(
+        &state,
+        &user.user_id,
+        &conversation_id,
+        body.content.as_deref().unwrap_or_default(),
+    )
+    .await?;
+    // Outside the influencer's online hours the reply is an away message or held
+    let away_schedule = influencer.schedule().filter(|schedule| {
+        conv.duet_mode().is_none() && !schedule.is_online_at(chrono::Utc::now())
+    });
+    // Only turns the AI answers right away count against the reply cap. Checked
+    // before the message is stored so a looping caller doesn't grow the
+    // conversation either
+    if !shadow_limited && conv.takeover_principal().is_none() && away_schedule.is_none() {
+        throttle_replies(&state, &user.user_id, &conversation_id, &tenant_settings)?;
+    }
+
+    // Prefer the duration measured at upload over the client-reported one
+    let mut audio_duration_seconds = body.audio_duration_seconds;
+    if let Some(ref audio_key) = body.audio_url {
+        let key = state.storage.extract_key_from_url(audio_key);
+        if let Some(verified) = state.db.audio_upload_repo().verified_duration(&key).await? {
+            audio_duration_seconds = Some(verified);
+        }
+    }
+
     // Transcribe audio if needed
+    let max_audio_seconds = state.settings.load().max_audio_duration_seconds as i32;
     let transcribed_content = if message_type == MessageType::Audio {
         if let Some(ref audio_key) = body.audio_url {
-            let presigned = state.storage.generate_presigned_url(audio_key).await;
-            match state.gemini.transcribe_audio(&presigned).await {
-                Ok(text) => Some(format!("[Transcribed: {text}]")),
-                Err(e) => {
-                    tracing::error!(error = %e, "Audio transcription failed");
-                    Some("[Audio message - transcription unavailable]".to_string())
+            if audio_duration_seconds.is_some_and(|d| d > max_audio_seconds) {
+                Some("[Audio message - too long to transcribe]".to_string())
+            } else {
+                let presigned = state.storage.generate_presigned_url(audio_key).await;
+                match state.gemini.transcribe_audio(&presigned).await {
+                    Ok(text) => Some(format!("[Transcribed: {text}]")),
+                    Err(e) => {
+                        tracing::error!(error = %e, "Audio transcription failed");
+                        Some("[Audio message - transcription unavailable]".to_string())
+                    }
                 }
             }
         } else {
@@ -387,8 +875,33 @@ pub async fn send_message(
         body.content.clone()
     };
 
+    // Screen for prompt injection before anything is persisted
+    // Safe mode never passes a flagged message through unchanged
+    let strictness = match tenant_settings.prompt_injection_strictness {
+        InjectionStrictness::Off | InjectionStrictness::Detect if conv.safe_mode() => {
+            InjectionStrictness::Neutralize
+        }
+        strictness => strictness,
+    };
+    let injection_scan = prompt_guard::scan(
+        transcribed_content.as_deref().unwrap_or_default(),
+        strictness,
+    );
+    if injection_scan.is_flagged() {
+        tracing::warn!(
+            conversation_id = %conversation_id,
+            findings = ?injection_scan.findings,
+            "Possible prompt injection in user message"
+        );
+        if strictness == InjectionStrictness::Block {
+            return Err(AppError::validation_error(
+                "Message rejected: it attempts to override the assistant's instructions",
+            ));
+        }
+    }
+
     // Save user message
-    let user_message = msg_repo
+    let mut user_message = msg_repo
         .create(
             &conversation_id,
             &MessageRole::User,
@@ -396,183 +909,1880 @@ pub async fn send_message(
             &message_type,
             body.media_urls.as_deref().unwrap_or(&[]),
             body.audio_url.as_deref(),
-            body.audio_duration_seconds,
+            audio_duration_seconds,
             None,
             body.client_message_id.as_deref(),
         )
         .await?;
 
-    // Get conversation history (last 10 excluding current message)
-    let all_recent = msg_repo
-        .get_recent_for_context(&conversation_id, 11)
-        .await?;
-    let mut history: Vec<Message> = all_recent
-        .into_iter()
-        .filter(|m| m.id != user_message.id)
-        .collect();
-    let skip = history.len().saturating_sub(10);
-    history.drain(..skip);
-
-    // Presign S3 keys in history
-    let s3_keys: Vec<String> = history
-        .iter()
-        .flat_map(|m| {
-            m.media_urls
-                .iter()
-                .chain(m.audio_url.iter())
-                .filter(|u| !u.starts_with("http"))
-                .cloned()
-        })
-        .collect();
-    let url_map = if s3_keys.is_empty() {
-        HashMap::new()
-    } else {
-        state.storage.generate_presigned_urls_batch(&s3_keys).await
-    };
-    let presign = |key: &str| url_map.get(key).cloned().unwrap_or_else(|| key.to_string());
-    for msg in &mut history {
-        msg.media_urls = msg.media_urls.iter().map(|u| presign(u)).collect();
-        msg.audio_url = msg.audio_url.as_ref().map(|u| presign(u));
+    let mut user_metadata = serde_json::Map::new();
+    if injection_scan.is_flagged()
+        && let serde_json::Value::Object(findings) = injection_scan.to_metadata(strictness)
+    {
+        user_metadata.extend(findings);
     }
-
-    // Enhance system instructions with memories
-    let memories: HashMap<String, String> = conv
-        .metadata
-        .get("memories")
-        .and_then(|m| serde_json::from_value(m.clone()).ok())
-        .unwrap_or_default();
-
-    let mut enhanced_instructions = influencer.system_instructions.clone();
-    if !memories.is_empty() {
-        enhanced_instructions.push_str("\n\n**MEMORIES:**\n");
-        for (key, value) in &memories {
-            enhanced_instructions.push_str(&format!("- {key}: {value}\n"));
+    // In group chats, record which member wrote the message
+    if user.user_id != conv.user_id {
+        user_metadata.insert("sender".into(), user.user_id.clone().into());
+    }
+    if body.source == MessageSource::Suggested {
+        user_metadata.insert("source".into(), "suggested".into());
+    }
+    if shadow_limited {
+        user_metadata.insert("shadow_limited".into(), true.into());
+    }
+    if !user_metadata.is_empty() {
+        let metadata = serde_json::Value::Object(user_metadata);
+        match msg_repo.update_metadata(&user_message.id, &metadata).await {
+            Ok(()) => user_message.metadata = metadata,
+            Err(e) => tracing::error!(error = %e, "Failed to record user message metadata"),
         }
     }
 
-    // Presign current media URLs for AI
-    let media_urls_for_ai: Option<Vec<String>> =
-        if matches!(message_type, MessageType::Image | MessageType::Multimodal) {
-            if let Some(urls) = body.media_urls.as_ref() {
-                let batch = state.storage.generate_presigned_urls_batch(urls).await;
-                Some(
-                    urls.iter()
-                        .map(|u| batch.get(u).cloned().unwrap_or_else(|| u.clone()))
-                        .collect(),
-                )
-            } else {
-                None
-            }
-        } else {
-            None
-        };
-
-    // Select AI client and generate response
-    let ai_input = transcribed_content
-        .as_deref()
-        .or(body.content.as_deref())
-        .unwrap_or("What do you think?");
+    // A shadow-limited sender's message is kept but goes no further: no reply,
+    // caption, webhook or group fan-out
+    if shadow_limited {
+        let mut user_resp = MessageResponse::from(user_message);
+        presign_message_urls(state.storage.as_ref(), &mut user_resp).await;
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(SendMessageResponse::accepted(user_resp)),
+        ));
+    }
+    image_caption::spawn_caption(&state, &user_message);
+
+    // Feeds the owner's click-through stats and the suggestion rotation job
+    if body.source == MessageSource::Suggested
+        && let Some(text) = body.content.as_deref()
+        && let Err(e) = state
+            .db
+            .suggestion_repo()
+            .record_tap(
+                &conv.influencer_id,
+                &user.user_id,
+                &conversation_id,
+                &user_message.id,
+                text.trim(),
+            )
+            .await
+    {
+        tracing::warn!(error = %e, "Failed to record suggestion tap (non-fatal)");
+    }
 
-    // Broadcast typing indicator: START
-    state.ws_manager.broadcast_typing_status(
-        &user.user_id,
-        &conversation_id,
+    spawn_group_fanout(&state, &conv, &user.user_id, &influencer, &user_message);
+    webhooks::dispatch(
+        &state,
         &conv.influencer_id,
-        true,
+        WebhookEvent::MessageCreated,
+        serde_json::json!({
+            "conversation_id": conversation_id,
+            "user_id": user.user_id,
+            "message": MessageResponse::from(user_message.clone()),
+        }),
     );
 
-    // AI generation with fallback error handling
-    let ai_result = if influencer.is_nsfw && state.openrouter.is_configured() {
-        state
-            .openrouter
-            .generate_response(
-                ai_input,
-                &enhanced_instructions,
-                &history,
-                media_urls_for_ai.as_deref(),
-            )
-            .await
-    } else {
-        state
-            .gemini
-            .generate_response(
-                ai_input,
-                &enhanced_instructions,
-                &history,
-                media_urls_for_ai.as_deref(),
-            )
+    // A human operator has taken over: store the message, hand it to them, skip the AI
+    if let Some(operator) = conv.takeover_principal() {
+        // The operator's unread count is of the user's messages, not the replies
+        let unread_count = msg_repo
+            .count_unread_by_bot(&conversation_id)
             .await
-    };
+            .unwrap_or(0);
+        let influencer_json = serde_json::json!({
+            "id": influencer.id,
+            "display_name": influencer.display_name,
+            "avatar_url": influencer.avatar_url,
+            "is_online": influencer.is_online(),
+        });
+        let mut user_resp = MessageResponse::from(user_message);
+        presign_message_urls(state.storage.as_ref(), &mut user_resp).await;
+        state.ws_manager.broadcast_new_message(
+            operator,
+            &conversation_id,
+            &serde_json::to_value(&user_resp).unwrap_or_default(),
+            &influencer_json,
+            unread_count,
+        );
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(SendMessageResponse::accepted(user_resp)),
+        ));
+    }
 
-    // Broadcast typing indicator: STOP
-    state.ws_manager.broadcast_typing_status(
-        &user.user_id,
-        &conversation_id,
-        &conv.influencer_id,
-        false,
-    );
+    // Outside the influencer's online hours: send the away message or hold the reply
+    if let Some(schedule) = away_schedule {
+        return reply_while_away(
+            &state,
+            &user.user_id,
+            &conv,
+            &influencer,
+            schedule,
+            user_message,
+        )
+        .await;
+    }
 
-    let (response_text, token_count, is_fallback) = match ai_result {
-        Ok((text, tokens)) => (text, tokens, false),
-        Err(e) => {
-            tracing::error!(error = %e, "AI generation failed, using fallback");
-            (FALLBACK_ERROR_MESSAGE.to_string(), 0, true)
+    // In a duet, one of the influencers takes this turn
+    let duet_cast = load_duet_cast(&state, &conv).await?;
+    let influencer = if conv.duet_mode().is_some() {
+        if duet_cast.is_empty() {
+            return Err(AppError::forbidden(
+                "None of the bots in this conversation can receive messages.",
+            ));
         }
+        next_duet_speaker(&state, &conv, &duet_cast, transcribed_content.as_deref())
+            .await?
+            .clone()
+    } else {
+        influencer
     };
 
-    // Save assistant message
-    let assistant_message = msg_repo
-        .create(
-            &conversation_id,
-            &MessageRole::Assistant,
-            Some(&response_text),
+    // Quick successive messages share one reply: each request waits out the window
+    // and only the one whose message is still the burst's latest replies
+    let debounce = Duration::from_secs(tenant_settings.message_debounce_seconds);
+    let burst = (!debounce.is_zero()).then(|| {
+        state
+            .turn_debouncer
+            .join(&conversation_id, &user_message.id, debounce)
+    });
+
+    // Generation and storage run detached so a client that drops the connection
+    // mid-request still gets the reply: it is stored and pushed over WebSocket/push,
+    // and a resend with the same client_message_id returns it
+    tokio::spawn(async move {
+        let finish_burst = burst
+            .as_ref()
+            .map(|ticket| state.turn_debouncer.finish_on_drop(ticket));
+        if let Some(ticket) = &burst {
+            tokio::time::sleep(debounce).await;
+            if state.turn_debouncer.is_superseded(ticket) {
+                return Ok(superseded_response(&state, user_message).await);
+            }
+        }
+
+        let media_only;
+        let ai_input = match transcribed_content
+            .as_deref()
+            .or(body.content.as_deref())
+            .filter(|text| !is_blank(text))
+        {
+            Some(text) => text,
+            None => {
+                media_only = media_only_input(
+                    &state.tenants.settings(&influencer.tenant),
+                    &influencer,
+                    &message_type,
+                    body.media_urls.as_ref().map_or(0, Vec::len),
+                );
+                &media_only
+            }
+        };
+        let guarded_input = if injection_scan.is_flagged() {
+            injection_scan.prompt_text.as_str()
+        } else {
+            ai_input
+        };
+
+        let turn = build_turn_context(
+            &state,
+            &conv,
+            &influencer,
+            &duet_cast,
+            Some(&user_message.id),
+        )
+        .await;
+        let TurnContext {
+            system_instructions: mut enhanced_instructions,
+            history,
+            memories,
+            mut generation,
+        } = match turn {
+            Ok(turn) => turn,
+            // Earlier messages of the burst were told this request answers them;
+            // a failed reply lets the retrier do so. They are in its history.
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to prepare the reply, storing a failed one");
+                let failed = FailedGeneration {
+                    user_message_id: user_message.id.clone(),
+                    speaker_id: influencer.id.clone(),
+                    input: guarded_input.to_string(),
+                    media_urls: body.media_urls.clone().unwrap_or_default(),
+                    attempts: 1,
+                    error: e.to_string(),
+                    last_attempt_at: chrono::Utc::now().naive_utc(),
+                };
+                let assistant_message =
+                    store_failed_reply(&state, &conv, &duet_cast, failed).await?;
+                let mut user_resp = MessageResponse::from(user_message);
+                presign_message_urls(state.storage.as_ref(), &mut user_resp).await;
+                return Ok((
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(SendMessageResponse {
+                        user_message: user_resp,
+                        assistant_message: MessageResponse::from(assistant_message),
+                    }),
+                ));
+            }
+        };
+        // The burst's earlier messages are part of this turn, not its history
+        let burst_ids = burst
+            .as_ref()
+            .map(|ticket| state.turn_debouncer.message_ids(ticket))
+            .unwrap_or_default();
+        let (earlier, history): (Vec<Message>, Vec<Message>) = history
+            .into_iter()
+            .partition(|m| m.role == MessageRole::User && burst_ids.contains(&m.id));
+
+        // Presign current media URLs for AI
+        let mut media_urls_for_ai: Option<Vec<String>> =
+            if matches!(message_type, MessageType::Image | MessageType::Multimodal) {
+                if let Some(urls) = body.media_urls.as_ref() {
+                    let batch = state.storage.generate_presigned_urls_batch(urls).await;
+                    Some(
+                        urls.iter()
+                            .map(|u| batch.get(u).cloned().unwrap_or_else(|| u.clone()))
+                            .collect(),
+                    )
+                } else {
+                    None
+                }
+            } else {
+                None
+            };
+
+        let combined;
+        let (ai_input, guarded_input) = if earlier.is_empty() {
+            (ai_input, guarded_input)
+        } else {
+            // Their media is already presigned by the history
+            let earlier_media: Vec<String> = earlier
+                .iter()
+                .flat_map(|m| m.media_urls.iter().cloned())
+                .collect();
+            if !earlier_media.is_empty() {
+                media_urls_for_ai
+                    .get_or_insert_with(Vec::new)
+                    .splice(0..0, earlier_media);
+            }
+            let earlier_text: Vec<&str> = earlier
+                .iter()
+                .filter_map(|m| m.content.as_deref())
+                .filter(|text| !is_blank(text))
+                .collect();
+            let join = |current: &str| {
+                let mut parts = earlier_text.clone();
+                parts.push(current);
+                parts.join("\n")
+            };
+            combined = (join(ai_input), join(guarded_input));
+            (combined.0.as_str(), combined.1.as_str())
+        };
+
+        // Broadcast typing indicator: START
+        state.ws_manager.broadcast_typing_status(
+            &user.user_id,
+            &conversation_id,
+            &influencer.id,
+            true,
+        );
+
+        // Long pastes are condensed so they fit the model's context
+        let ai_client = select_ai_client(&state, &influencer, &conv);
+        let condensed = long_message::condense(
+            ai_client,
+            guarded_input,
+            state.settings.load().message_condense_threshold_chars,
+            state.settings.load().message_condense_chunk_chars,
+        )
+        .await;
+        let guarded_input = condensed.as_deref().unwrap_or(guarded_input);
+
+        // Passages from the influencer's documents that bear on this message
+        knowledge::augment_prompt(&state, &influencer.id, ai_input, &mut enhanced_instructions)
+            .await;
+
+        // Messages asking about the time, weather or news get tools to look them up
+        let influencer_settings = state.tenants.settings(&influencer.tenant);
+        let toolbox = ToolBox::new(&state.http_client, &influencer_settings);
+        generation.tools = toolbox.triggered_by(ai_input);
+
+        // AI generation with fallback error handling
+        let started = Instant::now();
+        let mut tool_calls = Vec::new();
+        let ai_result = ai_client
+            .generate_with_tools(
+                guarded_input,
+                &enhanced_instructions,
+                &history,
+                media_urls_for_ai.as_deref(),
+                &generation,
+                &toolbox,
+            )
+            .await
+            .map(|(text, tokens, calls)| {
+                tool_calls = calls;
+                (text, tokens)
+            });
+        sample_turn(
+            &state,
+            &influencer,
+            &conversation_id,
+            ai_client,
+            &enhanced_instructions,
+            &history,
+            guarded_input,
+            &ai_result,
+            started,
+        );
+
+        // Broadcast typing indicator: STOP
+        state.ws_manager.broadcast_typing_status(
+            &user.user_id,
+            &conversation_id,
+            &influencer.id,
+            false,
+        );
+
+        let (response_text, token_count, generation_error) = match ai_result {
+            Ok((text, tokens)) => (text, tokens, None),
+            // Including a provider at its concurrency cap: the user's message is
+            // stored, so it gets a failed reply the retrier can answer later
+            Err(e) => {
+                tracing::error!(error = %e, "AI generation failed, using fallback");
+                (FALLBACK_ERROR_MESSAGE.to_string(), 0, Some(e.to_string()))
+            }
+        };
+        let is_fallback = generation_error.is_some();
+
+        // Another message came in while generating; its request answers them all
+        if let Some(ticket) = &burst
+            && state.turn_debouncer.is_superseded(ticket)
+        {
+            return Ok(superseded_response(&state, user_message).await);
+        }
+
+        // Save assistant message
+        let (response_text, mut assistant_metadata) = sanitize_reply(&state, &conv, &response_text);
+        let mut assistant_message = msg_repo
+            .create(
+                &conversation_id,
+                &MessageRole::Assistant,
+                Some(&response_text),
+                &MessageType::Text,
+                &[],
+                None,
+                None,
+                Some(token_count),
+                None,
+            )
+            .await?;
+        if !duet_cast.is_empty() {
+            assistant_metadata.insert("speaker".into(), influencer.id.clone().into());
+        }
+        if !is_fallback {
+            record_generator(&mut assistant_metadata, ai_client);
+        }
+        if !tool_calls.is_empty() {
+            assistant_metadata.insert(
+                "tool_calls".into(),
+                serde_json::to_value(&tool_calls).unwrap_or_default(),
+            );
+        }
+        // Keep what the retry needs so the real answer can replace the fallback
+        if let Some(error) = generation_error {
+            let failed = FailedGeneration {
+                user_message_id: user_message.id.clone(),
+                speaker_id: influencer.id.clone(),
+                input: guarded_input.to_string(),
+                media_urls: body.media_urls.clone().unwrap_or_default(),
+                attempts: 1,
+                error,
+                last_attempt_at: chrono::Utc::now().naive_utc(),
+            };
+            assistant_metadata.insert(
+                "failed_generation".into(),
+                serde_json::to_value(&failed).unwrap_or_default(),
+            );
+            msg_repo.mark_failed(&assistant_message.id).await?;
+            assistant_message.status = MESSAGE_STATUS_FAILED.to_string();
+        }
+        if !assistant_metadata.is_empty() {
+            let metadata = serde_json::Value::Object(assistant_metadata);
+            match msg_repo
+                .update_metadata(&assistant_message.id, &metadata)
+                .await
+            {
+                Ok(()) => assistant_message.metadata = metadata,
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to record assistant message metadata")
+                }
+            }
+        }
+
+        drop(finish_burst);
+
+        // Background tasks: memory extraction + notifications. A fallback reply is
+        // neither remembered nor pushed; its retry does both once it succeeds.
+        if !is_fallback {
+            spawn_memory_extraction(
+                &state,
+                &conversation_id,
+                ai_input,
+                &response_text,
+                &memories,
+                uses_nsfw_model(&influencer, &conv),
+            );
+            spawn_notifications(
+                &state,
+                &user.user_id,
+                &conversation_id,
+                &influencer.id,
+                &influencer,
+                &response_text,
+                &assistant_message,
+            );
+        }
+
+        let status = if is_fallback {
+            StatusCode::SERVICE_UNAVAILABLE
+        } else {
+            StatusCode::OK
+        };
+
+        // Presign media URLs in response messages so clients get usable URLs
+        let mut user_resp = MessageResponse::from(user_message);
+        let mut asst_resp = MessageResponse::from(assistant_message);
+        presign_message_urls(state.storage.as_ref(), &mut user_resp).await;
+        presign_message_urls(state.storage.as_ref(), &mut asst_resp).await;
+
+        Ok::<_, AppError>((
+            status,
+            Json(SendMessageResponse {
+                user_message: user_resp,
+                assistant_message: asst_resp,
+            }),
+        ))
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!("Reply task failed: {e}"))?
+}
+
+/// Count an AI reply against the conversation's per-minute cap. Over it, the caller
+/// gets a 429 and a `conversation_throttled` notice on their sockets.
+fn throttle_replies(
+    state: &AppState,
+    user_id: &str,
+    conversation_id: &str,
+    settings: &Settings,
+) -> Result<(), AppError> {
+    let result = state
+        .conversation_throttle
+        .acquire(conversation_id, settings.conversation_replies_per_minute);
+    if let Err(AppError::ConversationThrottled(_, retry_after)) = &result {
+        tracing::warn!(
+            conversation_id,
+            user_id,
+            retry_after,
+            "Conversation throttled"
+        );
+        state
+            .ws_manager
+            .broadcast_conversation_throttled(user_id, conversation_id, *retry_after);
+    }
+    result
+}
+
+/// Flood and spam check for a message about to be stored. Returns whether the
+/// sender is shadow-limited in this conversation; a throttled sender gets a 429.
+pub(super) async fn screen_for_abuse(
+    state: &AppState,
+    user_id: &str,
+    conversation_id: &str,
+    text: &str,
+) -> Result<bool, AppError> {
+    let Some(limits) = AbuseLimits::from_settings(&state.settings.load()) else {
+        return Ok(false);
+    };
+    let (verdict, offence) = state
+        .abuse_guard
+        .check(conversation_id, user_id, text, &limits);
+    if let Some(offence) = &offence {
+        abuse_guard::record(state, user_id, conversation_id, offence).await;
+    }
+    match verdict {
+        Verdict::Allow => Ok(false),
+        Verdict::ShadowLimit => Ok(true),
+        Verdict::Throttle(retry_after) => {
+            state.ws_manager.broadcast_conversation_throttled(
+                user_id,
+                conversation_id,
+                retry_after,
+            );
+            Err(AppError::conversation_throttled(
+                "Too many repeated or oversized messages in this conversation; try again later",
+                retry_after,
+            ))
+        }
+    }
+}
+
+/// Response for a message whose reply was left to a later message in its burst;
+/// that reply arrives over WebSocket/push.
+/// Store a failed stand-in reply for a turn that couldn't be generated, for the
+/// retrier to answer later.
+async fn store_failed_reply(
+    state: &AppState,
+    conv: &crate::models::entities::Conversation,
+    duet_cast: &[AIInfluencer],
+    failed: FailedGeneration,
+) -> Result<Message, AppError> {
+    let msg_repo = state.db.msg_repo();
+    let (text, mut metadata) = sanitize_reply(state, conv, FALLBACK_ERROR_MESSAGE);
+    let mut message = msg_repo
+        .create(
+            &conv.id,
+            &MessageRole::Assistant,
+            Some(&text),
             &MessageType::Text,
             &[],
             None,
             None,
-            Some(token_count),
+            Some(0),
             None,
         )
         .await?;
+    if !duet_cast.is_empty() {
+        metadata.insert("speaker".into(), failed.speaker_id.clone().into());
+    }
+    metadata.insert(
+        "failed_generation".into(),
+        serde_json::to_value(&failed).unwrap_or_default(),
+    );
+    msg_repo.mark_failed(&message.id).await?;
+    message.status = MESSAGE_STATUS_FAILED.to_string();
+    let metadata = serde_json::Value::Object(metadata);
+    msg_repo.update_metadata(&message.id, &metadata).await?;
+    message.metadata = metadata;
+    Ok(message)
+}
+
+async fn superseded_response(
+    state: &AppState,
+    user_message: Message,
+) -> (StatusCode, Json<SendMessageResponse>) {
+    let mut user_resp = MessageResponse::from(user_message);
+    presign_message_urls(state.storage.as_ref(), &mut user_resp).await;
+    (
+        StatusCode::ACCEPTED,
+        Json(SendMessageResponse::accepted(user_resp)),
+    )
+}
+
+/// Generate the reply a failed assistant message stands in for and store it in
+/// place of the fallback text. A failed attempt is counted on the message.
+async fn retry_failed_reply(
+    state: &Arc<AppState>,
+    conv: &crate::models::entities::Conversation,
+    message: Message,
+) -> Result<Message, AppError> {
+    let mut failed = message
+        .failed_generation()
+        .ok_or_else(|| AppError::bad_request("This reply has nothing to retry"))?;
+    let msg_repo = state.db.msg_repo();
+    let user_message = msg_repo
+        .get_by_id(&failed.user_message_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("The message this reply answers was deleted"))?;
+    let influencer = state
+        .influencer_cache
+        .get_by_id(&state.db.inf_repo(), &failed.speaker_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+    if influencer.is_active == InfluencerStatus::Discontinued {
+        return Err(AppError::forbidden(
+            "This bot has been deleted and can no longer receive messages.",
+        ));
+    }
+
+    let duet_cast = load_duet_cast(state, conv).await?;
+    let TurnContext {
+        mut system_instructions,
+        mut history,
+        memories,
+        generation,
+    } = build_turn_context(state, conv, &influencer, &duet_cast, Some(&user_message.id)).await?;
+    // Answer as of the original turn, not what was said since
+    history.retain(|m| m.created_at <= user_message.created_at);
+    knowledge::augment_prompt(
+        state,
+        &influencer.id,
+        &failed.input,
+        &mut system_instructions,
+    )
+    .await;
+
+    let media_urls = if failed.media_urls.is_empty() {
+        None
+    } else {
+        let batch = state
+            .storage
+            .generate_presigned_urls_batch(&failed.media_urls)
+            .await;
+        Some(
+            failed
+                .media_urls
+                .iter()
+                .map(|u| batch.get(u).cloned().unwrap_or_else(|| u.clone()))
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    let ai_client = select_ai_client(state, &influencer, conv);
+    let started = Instant::now();
+    let result = ai_client
+        .generate_response_with(
+            &failed.input,
+            &system_instructions,
+            &history,
+            media_urls.as_deref(),
+            &generation,
+        )
+        .await;
+    sample_turn(
+        state,
+        &influencer,
+        &conv.id,
+        ai_client,
+        &system_instructions,
+        &history,
+        &failed.input,
+        &result,
+        started,
+    );
+    let (raw, token_count) = match result {
+        Ok(reply) => reply,
+        Err(e) => {
+            // Turned away before reaching the provider is not an attempt, but it
+            // still waits out the backoff
+            let overloaded = matches!(e, AppError::Overloaded(..));
+            if !overloaded {
+                failed.attempts += 1;
+            }
+            failed.error = e.to_string();
+            failed.last_attempt_at = chrono::Utc::now().naive_utc();
+            let mut metadata = message.metadata.clone();
+            metadata["failed_generation"] = serde_json::to_value(&failed).unwrap_or_default();
+            msg_repo.update_metadata(&message.id, &metadata).await?;
+            if overloaded {
+                return Err(e);
+            }
+            return Err(AppError::service_unavailable(
+                "The AI provider is still unavailable. Please retry shortly.",
+            ));
+        }
+    };
+
+    let (text, mut metadata) = sanitize_reply(state, conv, &raw);
+    if !duet_cast.is_empty() {
+        metadata.insert("speaker".into(), influencer.id.clone().into());
+    }
+    metadata.insert("recovered_after_attempts".into(), failed.attempts.into());
+    record_generator(&mut metadata, ai_client);
+    let completed = msg_repo
+        .complete_failed(
+            &message.id,
+            &text,
+            token_count,
+            &serde_json::Value::Object(metadata),
+        )
+        .await?;
+    let updated = msg_repo
+        .get_by_id(&message.id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Message not found"))?;
+    if !completed {
+        // Another retry stored its reply first
+        return Ok(updated);
+    }
 
-    // Background tasks: memory extraction + notifications
+    let sender = user_message
+        .metadata
+        .get("sender")
+        .and_then(|v| v.as_str())
+        .unwrap_or(&conv.user_id);
     spawn_memory_extraction(
-        &state,
-        &conversation_id,
-        ai_input,
-        &response_text,
+        state,
+        &conv.id,
+        &failed.input,
+        &text,
         &memories,
-        influencer.is_nsfw,
+        uses_nsfw_model(&influencer, conv),
     );
     spawn_notifications(
-        &state,
-        &user.user_id,
-        &conversation_id,
-        &conv.influencer_id,
+        state,
+        sender,
+        &conv.id,
+        &influencer.id,
+        &influencer,
+        &text,
+        &updated,
+    );
+    Ok(updated)
+}
+
+/// Periodically retry recent failed replies with exponential backoff per
+/// message, stopping a round when the provider is still down or at capacity.
+/// A reply that can't be retried for any other reason (its message or bot was
+/// deleted) is given up on so it doesn't hold up the rest.
+pub fn spawn_failed_reply_retrier(state: Arc<AppState>, interval_secs: u64, max_attempts: u32) {
+    tokio::spawn(async move {
+        let interval = std::time::Duration::from_secs(interval_secs);
+        loop {
+            tokio::time::sleep(interval).await;
+            let failed = match state
+                .db
+                .msg_repo()
+                .list_failed_replies(
+                    FAILED_REPLY_RETRY_WINDOW_HOURS,
+                    max_attempts,
+                    FAILED_REPLY_RETRY_BATCH,
+                )
+                .await
+            {
+                Ok(failed) => failed,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to load failed replies (non-fatal)");
+                    continue;
+                }
+            };
+
+            let now = chrono::Utc::now().naive_utc();
+            let mut recovered = 0;
+            for message in failed {
+                let Some(attempt) = message.failed_generation() else {
+                    continue;
+                };
+                let backoff = interval_secs.saturating_mul(1 << attempt.attempts.min(16));
+                let due = attempt.last_attempt_at + chrono::Duration::seconds(backoff as i64);
+                if attempt.attempts >= max_attempts || due > now {
+                    continue;
+                }
+                let conv = match state
+                    .db
+                    .conv_repo()
+                    .get_by_id(&message.conversation_id)
+                    .await
+                {
+                    Ok(Some(conv)) => conv,
+                    _ => continue,
+                };
+                let message_id = message.id.clone();
+                let mut metadata = message.metadata.clone();
+                match retry_failed_reply(&state, &conv, message).await {
+                    Ok(_) => recovered += 1,
+                    Err(
+                        e @ (AppError::Overloaded(..)
+                        | AppError::ServiceUnavailable(_)
+                        | AppError::AiTimeout(_)),
+                    ) => {
+                        tracing::info!(error = %e, message_id, "Failed reply retry unsuccessful");
+                        break;
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, message_id, "Giving up on failed reply");
+                        let exhausted = FailedGeneration {
+                            attempts: max_attempts,
+                            error: e.to_string(),
+                            last_attempt_at: now,
+                            ..attempt
+                        };
+                        metadata["failed_generation"] =
+                            serde_json::to_value(&exhausted).unwrap_or_default();
+                        if let Err(e) = state
+                            .db
+                            .msg_repo()
+                            .update_metadata(&message_id, &metadata)
+                            .await
+                        {
+                            tracing::warn!(error = %e, message_id, "Failed to mark reply exhausted");
+                        }
+                    }
+                }
+            }
+            if recovered > 0 {
+                tracing::info!(recovered, "Recovered failed replies");
+            }
+        }
+    });
+}
+
+/// Answer a message that arrived outside the influencer's online windows, per
+/// the schedule's away mode. In `delay` mode the reply is scheduled for when the
+/// next window opens and sent by [`spawn_scheduled_reply_sender`].
+async fn reply_while_away(
+    state: &Arc<AppState>,
+    user_id: &str,
+    conv: &crate::models::entities::Conversation,
+    influencer: &AIInfluencer,
+    schedule: AvailabilitySchedule,
+    user_message: Message,
+) -> Result<(StatusCode, Json<SendMessageResponse>), AppError> {
+    let mut user_resp = MessageResponse::from(user_message.clone());
+    presign_message_urls(state.storage.as_ref(), &mut user_resp).await;
+
+    match schedule.away_mode {
+        AwayMode::AutoReply => {
+            let text = schedule.away_message.unwrap_or_else(|| {
+                format!(
+                    "{} is away right now and will reply when back online.",
+                    influencer.display_name
+                )
+            });
+            let (text, mut metadata) = sanitize_reply(state, conv, &text);
+            let msg_repo = state.db.msg_repo();
+            let mut assistant_message = msg_repo
+                .create(
+                    &conv.id,
+                    &MessageRole::Assistant,
+                    Some(&text),
+                    &MessageType::Text,
+                    &[],
+                    None,
+                    None,
+                    Some(0),
+                    None,
+                )
+                .await?;
+            metadata.insert("away_reply".into(), true.into());
+            let metadata = serde_json::Value::Object(metadata);
+            match msg_repo
+                .update_metadata(&assistant_message.id, &metadata)
+                .await
+            {
+                Ok(()) => assistant_message.metadata = metadata,
+                Err(e) => tracing::error!(error = %e, "Failed to mark away reply"),
+            }
+            spawn_notifications(
+                state,
+                user_id,
+                &conv.id,
+                &influencer.id,
+                influencer,
+                &text,
+                &assistant_message,
+            );
+            Ok((
+                StatusCode::OK,
+                Json(SendMessageResponse {
+                    user_message: user_resp,
+                    assistant_message: MessageResponse::from(assistant_message),
+                }),
+            ))
+        }
+        AwayMode::Delay => {
+            let now = chrono::Utc::now();
+            let due_at = schedule.next_online_at(now).unwrap_or(now).naive_utc();
+            state
+                .db
+                .scheduled_reply_repo()
+                .schedule(&ScheduledReply {
+                    message_id: user_message.id,
+                    conversation_id: conv.id.clone(),
+                    user_id: user_id.to_string(),
+                    influencer_id: influencer.id.clone(),
+                    due_at,
+                })
+                .await?;
+            Ok((
+                StatusCode::ACCEPTED,
+                Json(SendMessageResponse::accepted(user_resp)),
+            ))
+        }
+    }
+}
+
+/// Scheduled replies sent per tick.
+const SCHEDULED_REPLY_BATCH: i64 = 50;
+
+/// Periodically send replies held back while their influencer was away. A reply
+/// the provider can't produce right now is put back a tick later; any other
+/// failure drops it, like a reply to a message that is no longer the latest.
+pub fn spawn_scheduled_reply_sender(state: Arc<AppState>, interval_secs: u64) {
+    tokio::spawn(async move {
+        let interval = std::time::Duration::from_secs(interval_secs);
+        loop {
+            tokio::time::sleep(interval).await;
+            let repo = state.db.scheduled_reply_repo();
+            let due = match repo.list_due(SCHEDULED_REPLY_BATCH).await {
+                Ok(due) => due,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to load scheduled replies (non-fatal)");
+                    continue;
+                }
+            };
+
+            for reply in due {
+                match repo.claim(&reply.message_id).await {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to claim scheduled reply");
+                        continue;
+                    }
+                }
+                match send_scheduled_reply(&state, &reply).await {
+                    Ok(()) => {}
+                    Err(
+                        e @ (AppError::Overloaded(..)
+                        | AppError::ServiceUnavailable(_)
+                        | AppError::AiTimeout(_)),
+                    ) => {
+                        tracing::info!(error = %e, message_id = %reply.message_id, "Scheduled reply postponed");
+                        let retry = ScheduledReply {
+                            due_at: chrono::Utc::now().naive_utc()
+                                + chrono::Duration::seconds(interval_secs as i64),
+                            ..reply
+                        };
+                        if let Err(e) = repo.schedule(&retry).await {
+                            tracing::error!(error = %e, "Failed to reschedule reply");
+                        }
+                        break;
+                    }
+                    Err(e) => {
+                        tracing::error!(
+                            error = %e,
+                            conversation_id = %reply.conversation_id,
+                            "Scheduled reply failed"
+                        );
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Generate a held reply. Only the latest message of the conversation is
+/// answered; earlier ones sent while the influencer was away are part of its history.
+async fn send_scheduled_reply(
+    state: &Arc<AppState>,
+    reply: &ScheduledReply,
+) -> Result<(), AppError> {
+    let conv_id = &reply.conversation_id;
+    let msg_repo = state.db.msg_repo();
+    let latest = msg_repo.get_recent_for_context(conv_id, 1).await?;
+    let user_message = match latest.into_iter().next() {
+        Some(last) if last.id == reply.message_id => last,
+        _ => return Ok(()),
+    };
+    let (conv, influencer) = match (
+        state.db.conv_repo().get_by_id(conv_id).await?,
+        state
+            .influencer_cache
+            .get_by_id(&state.db.inf_repo(), &reply.influencer_id)
+            .await?,
+    ) {
+        (Some(conv), Some(influencer))
+            if influencer.is_active != InfluencerStatus::Discontinued =>
+        {
+            (conv, influencer)
+        }
+        _ => return Ok(()),
+    };
+
+    let TurnContext {
+        mut system_instructions,
+        history,
+        memories,
+        generation,
+    } = build_turn_context(state, &conv, &influencer, &[], Some(&user_message.id)).await?;
+    let media_only;
+    let input = match user_message
+        .content
+        .as_deref()
+        .filter(|text| !is_blank(text))
+    {
+        Some(text) => text,
+        None => {
+            media_only = media_only_input(
+                &state.tenants.settings(&influencer.tenant),
+                &influencer,
+                &user_message.message_type,
+                user_message.media_urls.len(),
+            );
+            &media_only
+        }
+    };
+    knowledge::augment_prompt(state, &influencer.id, input, &mut system_instructions).await;
+    let ai_client = select_ai_client(state, &influencer, &conv);
+    let started = Instant::now();
+    let result = ai_client
+        .generate_response_with(input, &system_instructions, &history, None, &generation)
+        .await;
+    sample_turn(
+        state,
+        &influencer,
+        conv_id,
+        ai_client,
+        &system_instructions,
+        &history,
+        input,
+        &result,
+        started,
+    );
+    let (raw, tokens) = result?;
+    let (text, mut metadata) = sanitize_reply(state, &conv, &raw);
+    record_generator(&mut metadata, ai_client);
+    let mut assistant_message = msg_repo
+        .create(
+            conv_id,
+            &MessageRole::Assistant,
+            Some(&text),
+            &MessageType::Text,
+            &[],
+            None,
+            None,
+            Some(tokens),
+            None,
+        )
+        .await?;
+    if !metadata.is_empty() {
+        let metadata = serde_json::Value::Object(metadata);
+        msg_repo
+            .update_metadata(&assistant_message.id, &metadata)
+            .await?;
+        assistant_message.metadata = metadata;
+    }
+    spawn_memory_extraction(
+        state,
+        conv_id,
+        input,
+        &text,
+        &memories,
+        uses_nsfw_model(&influencer, &conv),
+    );
+    spawn_notifications(
+        state,
+        &reply.user_id,
+        conv_id,
+        &influencer.id,
         &influencer,
-        &response_text,
+        &text,
         &assistant_message,
     );
+    Ok(())
+}
+
+/// Inspect the exact context the AI would receive for the next turn (bot owner or admin only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/conversations/{conversation_id}/debug-context",
+    params(("conversation_id" = String, Path, description = "Conversation ID")),
+    responses(
+        (status = 200, body = DebugContextResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Conversation not found")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn debug_context(
+    State(state): State<Arc<AppState>>,
+    user: Option<AuthenticatedUser>,
+    repos: Repos,
+    headers: HeaderMap,
+    Path(conversation_id): Path<String>,
+) -> Result<Json<DebugContextResponse>, AppError> {
+    let conv_repo = repos.conv();
+    let inf_repo = repos.inf();
+
+    let is_admin = has_admin_key(&headers, &state.settings.load());
+    if !is_admin && user.is_none() {
+        return Err(AppError::unauthorized("Missing authorization header"));
+    }
+
+    let conv = conv_repo
+        .get_by_id(&conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Conversation not found"))?;
+
+    // Chat participants must not see the raw system prompt; only the bot's owner may.
+    if !is_admin {
+        let user_id = user
+            .as_ref()
+            .map(|u| u.user_id.as_str())
+            .unwrap_or_default();
+        let parent = inf_repo.get_parent_principal(&conv.influencer_id).await?;
+        if parent.as_deref() != Some(user_id) {
+            return Err(AppError::forbidden(
+                "Only the bot owner or an admin can inspect conversation context",
+            ));
+        }
+    }
+
+    let influencer = inf_repo
+        .get_by_id(&conv.influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+
+    let duet_cast = load_duet_cast(&state, &conv).await?;
+    let influencer = if duet_cast.is_empty() {
+        influencer
+    } else {
+        next_duet_speaker(&state, &conv, &duet_cast, None)
+            .await?
+            .clone()
+    };
+
+    let mut context = build_turn_context(&state, &conv, &influencer, &duet_cast, None).await?;
+    // Passages as retrieved for the latest user message
+    if let Some(query) = context
+        .history
+        .iter()
+        .rev()
+        .find(|m| m.role == MessageRole::User)
+        .and_then(|m| m.content.clone())
+    {
+        knowledge::augment_prompt(
+            &state,
+            &influencer.id,
+            &query,
+            &mut context.system_instructions,
+        )
+        .await;
+    }
+    let ai_client = select_ai_client(&state, &influencer, &conv);
+
+    let system_tokens = estimate_tokens(&context.system_instructions);
+    let history_tokens: i32 = context
+        .history
+        .iter()
+        .filter_map(|m| m.content.as_deref())
+        .map(estimate_tokens)
+        .sum();
+
+    Ok(Json(DebugContextResponse {
+        conversation_id,
+        influencer_id: influencer.id,
+        provider: ai_client.provider().to_string(),
+        model: ai_client.model().to_string(),
+        system_instructions: context.system_instructions,
+        memories: context.memories,
+        history: context
+            .history
+            .into_iter()
+            .map(MessageResponse::from)
+            .collect(),
+        token_estimate: ContextTokenEstimate {
+            system_instructions: system_tokens,
+            history: history_tokens,
+            total: system_tokens + history_tokens,
+            max_output_tokens: context
+                .generation
+                .max_tokens
+                .unwrap_or(ai_client.max_tokens()),
+        },
+    }))
+}
+
+/// Update conversation settings: how creative the AI's replies are and safe mode
+#[utoipa::path(
+    patch,
+    path = "/api/v1/chat/conversations/{conversation_id}",
+    params(("conversation_id" = String, Path, description = "Conversation ID")),
+    request_body = UpdateConversationRequest,
+    responses(
+        (status = 200, body = ConversationSettingsResponse, description = "Settings updated"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Not your conversation"),
+        (status = 404, body = ErrorBody, description = "Conversation not found"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn update_conversation(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(conversation_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<UpdateConversationRequest>,
+) -> Result<Json<ConversationSettingsResponse>, AppError> {
+    let conv_repo = state.db.conv_repo();
+    let conv = conv_repo
+        .get_by_id(&conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Conversation not found"))?;
+
+    if conv.user_id != user.user_id {
+        return Err(AppError::forbidden("Not your conversation"));
+    }
+
+    let mut creativity = conv.creativity();
+    if let Some(value) = body.creativity {
+        creativity = value;
+        conv_repo
+            .set_metadata_key(&conversation_id, "creativity", &creativity.as_ref().into())
+            .await?;
+    }
+    let mut safe_mode = conv.safe_mode();
+    if let Some(value) = body.safe_mode {
+        safe_mode = value;
+        conv_repo
+            .set_metadata_key(&conversation_id, "safe_mode", &safe_mode.into())
+            .await?;
+    }
+
+    Ok(Json(ConversationSettingsResponse {
+        conversation_id,
+        creativity,
+        safe_mode,
+    }))
+}
+
+/// Change how long and expressive the AI's replies are in a conversation
+#[utoipa::path(
+    patch,
+    path = "/api/v1/chat/conversations/{conversation_id}/response-style",
+    params(("conversation_id" = String, Path, description = "Conversation ID")),
+    request_body = UpdateResponseStyleRequest,
+    responses(
+        (status = 200, body = ResponseStyleResponse, description = "Response style updated"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Not your conversation"),
+        (status = 404, body = ErrorBody, description = "Conversation not found")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn update_response_style(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(conversation_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<UpdateResponseStyleRequest>,
+) -> Result<Json<ResponseStyleResponse>, AppError> {
+    let conv_repo = state.db.conv_repo();
+    let conv = conv_repo
+        .get_by_id(&conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Conversation not found"))?;
+
+    if conv.user_id != user.user_id {
+        return Err(AppError::forbidden("Not your conversation"));
+    }
+
+    let mut style = conv.response_style();
+    if let Some(length) = body.length {
+        style.length = length;
+    }
+    if body.emoji.is_some() {
+        style.emoji = body.emoji;
+    }
+
+    conv_repo
+        .set_metadata_key(
+            &conversation_id,
+            "response_style",
+            &serde_json::to_value(&style).unwrap_or_default(),
+        )
+        .await?;
+
+    Ok(Json(ResponseStyleResponse {
+        conversation_id,
+        length: style.length,
+        emoji: style.emoji,
+    }))
+}
+
+/// Set the language the AI replies in for a conversation
+#[utoipa::path(
+    patch,
+    path = "/api/v1/chat/conversations/{conversation_id}/language",
+    params(("conversation_id" = String, Path, description = "Conversation ID")),
+    request_body = UpdateLanguageRequest,
+    responses(
+        (status = 200, body = LanguageResponse, description = "Preferred language updated"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Not your conversation"),
+        (status = 404, body = ErrorBody, description = "Conversation not found"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn update_language(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(conversation_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<UpdateLanguageRequest>,
+) -> Result<Json<LanguageResponse>, AppError> {
+    let conv_repo = state.db.conv_repo();
+    let conv = conv_repo
+        .get_by_id(&conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Conversation not found"))?;
+
+    if conv.user_id != user.user_id {
+        return Err(AppError::forbidden("Not your conversation"));
+    }
+
+    let preferred_language = body
+        .preferred_language
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty());
+    match preferred_language.as_deref() {
+        Some(language) => {
+            conv_repo
+                .set_metadata_key(&conversation_id, "preferred_language", &language.into())
+                .await?
+        }
+        None => {
+            conv_repo
+                .remove_metadata_key(&conversation_id, "preferred_language")
+                .await?
+        }
+    }
+
+    Ok(Json(LanguageResponse {
+        conversation_id,
+        preferred_language,
+    }))
+}
+
+/// Get a single message with its conversation context (deep-link target)
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/messages/{message_id}",
+    params(("message_id" = String, Path, description = "Message ID")),
+    responses(
+        (status = 200, body = MessagePermalinkResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Message not found")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn get_message(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(message_id): Path<String>,
+) -> Result<Json<MessagePermalinkResponse>, AppError> {
+    let message = state
+        .db
+        .msg_repo()
+        .get_by_id(&message_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Message not found"))?;
+    let conv = state
+        .db
+        .conv_repo()
+        .get_by_id(&message.conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Message not found"))?;
+    authorize_member(&state, &user.user_id, &conv).await?;
+
+    // In a duet, show whoever actually spoke
+    let influencer = state
+        .db
+        .inf_repo()
+        .get_by_id(message.speaker_id().unwrap_or(&conv.influencer_id))
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+
+    let mut message = MessageResponse::from(message);
+    presign_message_urls(state.storage.as_ref(), &mut message).await;
+
+    Ok(Json(MessagePermalinkResponse {
+        conversation_id: conv.id,
+        user_id: conv.user_id,
+        influencer: influencer_to_basic_info(&influencer, None),
+        message,
+    }))
+}
+
+/// Generate again an assistant reply that failed and was stored as the fallback text
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/messages/{message_id}/retry",
+    params(("message_id" = String, Path, description = "Message ID")),
+    responses(
+        (status = 200, body = MessageResponse, description = "Reply generated, or already recovered"),
+        (status = 400, body = ErrorBody, description = "Not an assistant reply"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Message not found"),
+        (status = 429, body = ErrorBody, description = "Too many replies in this conversation (`conversation_throttled`)"),
+        (status = 503, body = ErrorBody, description = "AI provider still unavailable")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn retry_message(
+    State(state): State<Arc<AppState>>,
+    tenant: Tenant,
+    user: AuthenticatedUser,
+    Path(message_id): Path<String>,
+) -> Result<Json<MessageResponse>, AppError> {
+    let message = state
+        .db
+        .msg_repo()
+        .get_by_id(&message_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Message not found"))?;
+    let conv = state
+        .db
+        .conv_repo()
+        .get_by_id(&message.conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Message not found"))?;
+    if authorize_member(&state, &user.user_id, &conv).await? == Some(ParticipantRole::Viewer) {
+        return Err(AppError::forbidden("Viewers cannot retry messages"));
+    }
+    if message.role != MessageRole::Assistant {
+        return Err(AppError::bad_request(
+            "Only assistant replies can be retried",
+        ));
+    }
+
+    // Recovered in the meantime (e.g. by the background retry)
+    let message = if message.is_failed() {
+        throttle_replies(
+            &state,
+            &user.user_id,
+            &conv.id,
+            &state.tenants.settings(tenant.as_str()),
+        )?;
+        retry_failed_reply(&state, &conv, message).await?
+    } else {
+        message
+    };
+
+    let mut resp = MessageResponse::from(message);
+    presign_message_urls(state.storage.as_ref(), &mut resp).await;
+    Ok(Json(resp))
+}
+
+/// Translate a stored message, caching the result on the message
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/messages/{message_id}/translate",
+    params(
+        ("message_id" = String, Path, description = "Message ID"),
+        TranslateMessageParams
+    ),
+    responses(
+        (status = 200, body = TranslateMessageResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Message not found"),
+        (status = 422, body = ErrorBody, description = "Validation error"),
+        (status = 503, body = ErrorBody, description = "Translation unavailable")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn translate_message(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(message_id): Path<String>,
+    ValidatedQuery(params): ValidatedQuery<TranslateMessageParams>,
+) -> Result<Json<TranslateMessageResponse>, AppError> {
+    let language = params.lang.trim().to_lowercase();
+
+    let msg_repo = state.db.msg_repo();
+    let message = msg_repo
+        .get_by_id(&message_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Message not found"))?;
+    let conv = state
+        .db
+        .conv_repo()
+        .get_by_id(&message.conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Message not found"))?;
+    authorize_member(&state, &user.user_id, &conv).await?;
+
+    if let Some(cached) = message.cached_translation(&language) {
+        return Ok(Json(TranslateMessageResponse {
+            message_id,
+            language,
+            translated_content: cached.to_string(),
+            cached: true,
+        }));
+    }
+
+    let content = message
+        .content
+        .as_deref()
+        .filter(|c| !c.trim().is_empty())
+        .ok_or_else(|| AppError::validation_error("Message has no text to translate"))?;
+
+    let influencer = state
+        .db
+        .inf_repo()
+        .get_by_id(message.speaker_id().unwrap_or(&conv.influencer_id))
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+    let translated = select_ai_client(&state, &influencer, &conv)
+        .translate(content, &language)
+        .await?;
+
+    // Only the translations key is written, so a caption or other metadata stored
+    // while the model was translating isn't overwritten
+    let mut translations = message
+        .metadata
+        .get("translations")
+        .and_then(|t| t.as_object())
+        .cloned()
+        .unwrap_or_default();
+    translations.insert(language.clone(), translated.clone().into());
+    if let Err(e) = msg_repo
+        .set_metadata_key(
+            &message_id,
+            "translations",
+            &serde_json::Value::Object(translations),
+        )
+        .await
+    {
+        tracing::error!(error = %e, "Failed to cache message translation");
+    }
+
+    Ok(Json(TranslateMessageResponse {
+        message_id,
+        language,
+        translated_content: translated,
+        cached: false,
+    }))
+}
+
+/// Take over a conversation as the bot owner, pausing AI auto-replies
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/conversations/{conversation_id}/takeover",
+    params(("conversation_id" = String, Path, description = "Conversation ID")),
+    responses(
+        (status = 200, body = TakeoverResponse, description = "AI replies paused"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Not the bot owner"),
+        (status = 404, body = ErrorBody, description = "Conversation not found")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn start_takeover(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(conversation_id): Path<String>,
+) -> Result<Json<TakeoverResponse>, AppError> {
+    let conv = get_conversation_as_owner(&state, &user, &conversation_id).await?;
+    let started_at = chrono::Utc::now().naive_utc();
+
+    if conv.takeover_principal().is_none() {
+        state
+            .db
+            .conv_repo()
+            .set_metadata_key(
+                &conversation_id,
+                "takeover",
+                &serde_json::json!({
+                    "by": user.user_id,
+                    "started_at": started_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                }),
+            )
+            .await?;
+    }
+
+    Ok(Json(TakeoverResponse {
+        conversation_id,
+        active: true,
+        taken_over_by: Some(user.user_id),
+    }))
+}
+
+/// End a takeover and resume AI auto-replies
+#[utoipa::path(
+    delete,
+    path = "/api/v1/chat/conversations/{conversation_id}/takeover",
+    params(("conversation_id" = String, Path, description = "Conversation ID")),
+    responses(
+        (status = 200, body = TakeoverResponse, description = "AI replies resumed"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Not the bot owner"),
+        (status = 404, body = ErrorBody, description = "Conversation not found")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn end_takeover(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(conversation_id): Path<String>,
+) -> Result<Json<TakeoverResponse>, AppError> {
+    get_conversation_as_owner(&state, &user, &conversation_id).await?;
+
+    state
+        .db
+        .conv_repo()
+        .remove_metadata_key(&conversation_id, "takeover")
+        .await?;
+
+    Ok(Json(TakeoverResponse {
+        conversation_id,
+        active: false,
+        taken_over_by: None,
+    }))
+}
+
+/// Mute push notifications for a conversation; it still shows up in the inbox
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/conversations/{conversation_id}/mute",
+    params(("conversation_id" = String, Path, description = "Conversation ID")),
+    request_body = MuteConversationRequest,
+    responses(
+        (status = 200, body = MuteResponse, description = "Conversation muted"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Not your conversation"),
+        (status = 404, body = ErrorBody, description = "Conversation not found"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn mute_conversation(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(conversation_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<MuteConversationRequest>,
+) -> Result<Json<MuteResponse>, AppError> {
+    let muted_until = body
+        .duration_minutes
+        .map(|m| chrono::Utc::now().naive_utc() + chrono::Duration::minutes(m as i64));
+    set_mute(&state, &user, &conversation_id, Some(muted_until)).await?;
+
+    Ok(Json(MuteResponse {
+        conversation_id,
+        muted: true,
+        muted_until,
+    }))
+}
+
+/// Unmute push notifications for a conversation
+#[utoipa::path(
+    delete,
+    path = "/api/v1/chat/conversations/{conversation_id}/mute",
+    params(("conversation_id" = String, Path, description = "Conversation ID")),
+    responses(
+        (status = 200, body = MuteResponse, description = "Conversation unmuted"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Not your conversation"),
+        (status = 404, body = ErrorBody, description = "Conversation not found")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn unmute_conversation(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(conversation_id): Path<String>,
+) -> Result<Json<MuteResponse>, AppError> {
+    set_mute(&state, &user, &conversation_id, None).await?;
+
+    Ok(Json(MuteResponse {
+        conversation_id,
+        muted: false,
+        muted_until: None,
+    }))
+}
+
+/// Mutes are per member, kept in `metadata.mutes` as principal -> end time
+/// (`null` = until unmuted). `mute` of `None` unmutes.
+async fn set_mute(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    conversation_id: &str,
+    mute: Option<Option<chrono::NaiveDateTime>>,
+) -> Result<(), AppError> {
+    let conv_repo = state.db.conv_repo();
+    let conv = conv_repo
+        .get_by_id(conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Conversation not found"))?;
+    authorize_member(state, &user.user_id, &conv).await?;
+
+    // Lapsed mutes are dropped whenever the map is rewritten
+    let now = chrono::Utc::now().naive_utc();
+    let mut mutes = serde_json::Map::new();
+    if let Some(existing) = conv.metadata.get("mutes").and_then(|m| m.as_object()) {
+        for (principal, until) in existing {
+            if conv.muted_until(principal, now).is_some() {
+                mutes.insert(principal.clone(), until.clone());
+            }
+        }
+    }
+    match mute {
+        Some(until) => {
+            let until = until
+                .map(|u| u.format("%Y-%m-%d %H:%M:%S").to_string().into())
+                .unwrap_or(serde_json::Value::Null);
+            mutes.insert(user.user_id.clone(), until);
+        }
+        None => {
+            mutes.remove(&user.user_id);
+        }
+    }
+
+    conv_repo
+        .set_metadata_key(conversation_id, "mutes", &serde_json::Value::Object(mutes))
+        .await?;
+    Ok(())
+}
+
+/// Invite a principal into a conversation (conversation creator only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/conversations/{conversation_id}/participants",
+    params(("conversation_id" = String, Path, description = "Conversation ID")),
+    request_body = InviteParticipantRequest,
+    responses(
+        (status = 201, body = ParticipantResponse, description = "Participant added or role updated"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Not the conversation creator"),
+        (status = 404, body = ErrorBody, description = "Conversation not found"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn invite_participant(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(conversation_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<InviteParticipantRequest>,
+) -> Result<(StatusCode, Json<ParticipantResponse>), AppError> {
+    let conv = state
+        .db
+        .conv_repo()
+        .get_by_id(&conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Conversation not found"))?;
+
+    if conv.user_id != user.user_id {
+        return Err(AppError::forbidden(
+            "Only the conversation creator can invite participants",
+        ));
+    }
+    if body.principal == conv.user_id || body.principal == conv.influencer_id {
+        return Err(AppError::validation_error(
+            "principal is already part of this conversation",
+        ));
+    }
+
+    let participant = state
+        .db
+        .part_repo()
+        .upsert(&conversation_id, &body.principal, &body.role, &user.user_id)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(participant_to_response(participant)),
+    ))
+}
+
+/// List the members of a conversation
+#[utoipa::path(
+    get,
+    path = "/api/v1/chat/conversations/{conversation_id}/participants",
+    params(("conversation_id" = String, Path, description = "Conversation ID")),
+    responses(
+        (status = 200, body = ListParticipantsResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Conversation not found")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn list_participants(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(conversation_id): Path<String>,
+) -> Result<Json<ListParticipantsResponse>, AppError> {
+    let conv = state
+        .db
+        .conv_repo()
+        .get_by_id(&conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Conversation not found"))?;
 
-    let status = if is_fallback {
-        StatusCode::SERVICE_UNAVAILABLE
-    } else {
-        StatusCode::OK
-    };
+    authorize_member(&state, &user.user_id, &conv).await?;
 
-    // Presign media URLs in response messages so clients get usable URLs
-    let mut user_resp = MessageResponse::from(user_message);
-    let mut asst_resp = MessageResponse::from(assistant_message);
-    presign_message_urls(&state.storage, &mut user_resp).await;
-    presign_message_urls(&state.storage, &mut asst_resp).await;
+    let participants = state
+        .db
+        .part_repo()
+        .list_by_conversation(&conversation_id)
+        .await?;
 
-    Ok((
-        status,
-        Json(SendMessageResponse {
-            user_message: user_resp,
-            assistant_message: asst_resp,
-        }),
-    ))
+    Ok(Json(ListParticipantsResponse {
+        conversation_id,
+        owner_id: conv.user_id,
+        participants: participants
+            .into_iter()
+            .map(participant_to_response)
+            .collect(),
+    }))
+}
+
+/// Remove a participant (creator removes anyone, members can leave)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/chat/conversations/{conversation_id}/participants/{principal}",
+    params(
+        ("conversation_id" = String, Path, description = "Conversation ID"),
+        ("principal" = String, Path, description = "Participant principal")
+    ),
+    responses(
+        (status = 200, body = RemoveParticipantResponse, description = "Participant removed"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Conversation or participant not found")
+    ),
+    tag = "Chat",
+    security(("BearerAuth" = []))
+)]
+pub async fn remove_participant(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((conversation_id, principal)): Path<(String, String)>,
+) -> Result<Json<RemoveParticipantResponse>, AppError> {
+    let conv = state
+        .db
+        .conv_repo()
+        .get_by_id(&conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Conversation not found"))?;
+
+    if conv.user_id != user.user_id && principal != user.user_id {
+        return Err(AppError::forbidden(
+            "Only the conversation creator can remove other participants",
+        ));
+    }
+
+    if !state
+        .db
+        .part_repo()
+        .remove(&conversation_id, &principal)
+        .await?
+    {
+        return Err(AppError::not_found("Participant not found"));
+    }
+
+    Ok(Json(RemoveParticipantResponse {
+        success: true,
+        conversation_id,
+        user_id: principal,
+    }))
 }
 
 /// Mark all messages in a conversation as read
@@ -592,23 +2802,32 @@ pub async fn send_message(
 pub async fn mark_as_read(
     State(state): State<Arc<AppState>>,
     user: AuthenticatedUser,
+    repos: Repos,
     Path(conversation_id): Path<String>,
 ) -> Result<Json<MarkConversationAsReadResponse>, AppError> {
-    let conv_repo = state.db.conv_repo();
-    let msg_repo = state.db.msg_repo();
-    let inf_repo = state.db.inf_repo();
+    let conv_repo = repos.conv();
+    let msg_repo = repos.msg();
 
     let conv = conv_repo
         .get_by_id(&conversation_id)
         .await?
         .ok_or_else(|| AppError::not_found("Conversation not found"))?;
 
-    if !can_access_conversation(&user.user_id, &conv, &inf_repo).await? {
-        return Err(AppError::forbidden("Not your conversation"));
-    }
-
-    msg_repo.mark_as_read(&conversation_id).await?;
-    let unread_count = msg_repo.count_unread(&conversation_id).await?;
+    // Group members keep their own read position; message flags belong to the creator
+    let unread_count = if authorize_member(&state, &user.user_id, &conv)
+        .await?
+        .is_some()
+    {
+        state
+            .db
+            .part_repo()
+            .mark_read(&conversation_id, &user.user_id)
+            .await?;
+        0
+    } else {
+        msg_repo.mark_as_read(&conversation_id).await?;
+        msg_repo.count_unread(&conversation_id).await?
+    };
     let now = chrono::Utc::now().naive_utc();
 
     // WebSocket broadcast
@@ -637,6 +2856,7 @@ pub async fn mark_as_read(
         (status = 403, body = ErrorBody, description = "Forbidden"),
         (status = 404, body = ErrorBody, description = "Conversation not found"),
         (status = 422, body = ErrorBody, description = "Validation error"),
+        (status = 429, body = ErrorBody, description = "Image cooldown or daily quota (`image_quota_exceeded`); see `Retry-After` and `X-ImageQuota-*` headers"),
         (status = 503, body = ErrorBody, description = "Service unavailable")
     ),
     tag = "Chat",
@@ -646,8 +2866,8 @@ pub async fn generate_image(
     State(state): State<Arc<AppState>>,
     user: AuthenticatedUser,
     Path(conversation_id): Path<String>,
-    Json(body): Json<GenerateImageRequest>,
-) -> Result<(StatusCode, Json<MessageResponse>), AppError> {
+    ValidatedJson(body): ValidatedJson<GenerateImageRequest>,
+) -> Result<(StatusCode, HeaderMap, Json<MessageResponse>), AppError> {
     if !state.replicate.is_configured() {
         return Err(AppError::service_unavailable(
             "Image generation service not available",
@@ -656,15 +2876,14 @@ pub async fn generate_image(
 
     let conv_repo = state.db.conv_repo();
     let inf_repo = state.db.inf_repo();
-    let msg_repo = state.db.msg_repo();
 
     let conv = conv_repo
         .get_by_id(&conversation_id)
         .await?
         .ok_or_else(|| AppError::not_found("Conversation not found"))?;
 
-    if !can_access_conversation(&user.user_id, &conv, &inf_repo).await? {
-        return Err(AppError::forbidden("Not your conversation"));
+    if authorize_member(&state, &user.user_id, &conv).await? == Some(ParticipantRole::Viewer) {
+        return Err(AppError::forbidden("Viewers cannot generate images"));
     }
 
     let influencer = inf_repo
@@ -679,10 +2898,32 @@ pub async fn generate_image(
         ));
     }
 
+    let quota = state.image_quota.acquire(&state.db, &user.user_id).await?;
+    let message =
+        match create_image_message(&state, &user, &conversation_id, &influencer, body).await {
+            Ok(message) => message,
+            Err(e) => {
+                state.image_quota.release(&state.db, &user.user_id).await;
+                return Err(e);
+            }
+        };
+    Ok((StatusCode::CREATED, quota.headers(), Json(message)))
+}
+
+/// Generate the image, store it and save it as an assistant message.
+async fn create_image_message(
+    state: &Arc<AppState>,
+    user: &AuthenticatedUser,
+    conversation_id: &str,
+    influencer: &AIInfluencer,
+    body: GenerateImageRequest,
+) -> Result<MessageResponse, AppError> {
+    let msg_repo = state.db.msg_repo();
+
     // 1. Determine prompt
     let final_prompt = match body.prompt.as_deref().map(str::trim) {
         Some(p) if !p.is_empty() => p.to_string(),
-        _ => generate_image_prompt_from_context(&state, &msg_repo, &conversation_id).await?,
+        _ => generate_image_prompt_from_context(state, &msg_repo, conversation_id).await?,
     };
 
     tracing::info!(prompt = %final_prompt, "Generating image");
@@ -724,7 +2965,7 @@ pub async fn generate_image(
     // 4. Save as assistant message of type IMAGE
     let message = msg_repo
         .create(
-            &conversation_id,
+            conversation_id,
             &MessageRole::Assistant,
             Some(""),
             &MessageType::Image,
@@ -735,8 +2976,12 @@ pub async fn generate_image(
             None,
         )
         .await?;
+    image_caption::spawn_caption(state, &message);
+
+    let mut message = MessageResponse::from(message);
+    presign_message_urls(state.storage.as_ref(), &mut message).await;
 
-    Ok((StatusCode::CREATED, Json(MessageResponse::from(message))))
+    Ok(message)
 }
 
 /// Generate an image prompt from recent conversation context using Gemini.
@@ -790,18 +3035,18 @@ async fn generate_image_prompt_from_context(
 pub async fn delete_conversation(
     State(state): State<Arc<AppState>>,
     user: AuthenticatedUser,
+    repos: Repos,
     Path(conversation_id): Path<String>,
 ) -> Result<Json<DeleteConversationResponse>, AppError> {
-    let conv_repo = state.db.conv_repo();
-    let msg_repo = state.db.msg_repo();
-    let inf_repo = state.db.inf_repo();
+    let conv_repo = repos.conv();
+    let msg_repo = repos.msg();
 
     let conv = conv_repo
         .get_by_id(&conversation_id)
         .await?
         .ok_or_else(|| AppError::not_found("Conversation not found"))?;
 
-    if !can_access_conversation(&user.user_id, &conv, &inf_repo).await? {
+    if !can_access_conversation(&state, &user.user_id, &conv).await? {
         return Err(AppError::forbidden("Not your conversation"));
     }
 
@@ -818,15 +3063,420 @@ pub async fn delete_conversation(
 
 // ── Helpers ──
 
+/// Everything sent to the model for a turn apart from the incoming user message.
+pub(super) struct TurnContext {
+    pub(super) system_instructions: String,
+    pub(super) history: Vec<Message>,
+    pub(super) memories: HashMap<String, String>,
+    pub(super) generation: GenerationOptions,
+}
+
+/// Whether turns in `conv` go to the NSFW-capable model. Safe mode overrides
+/// the influencer's flag.
+fn uses_nsfw_model(
+    influencer: &AIInfluencer,
+    conv: &crate::models::entities::Conversation,
+) -> bool {
+    influencer.is_nsfw && !conv.safe_mode()
+}
+
+/// NSFW influencers go to OpenRouter when it is configured, unless the conversation
+/// is in safe mode; everything else uses Gemini.
+pub(super) fn select_ai_client<'a>(
+    state: &'a AppState,
+    influencer: &AIInfluencer,
+    conv: &crate::models::entities::Conversation,
+) -> &'a dyn AiApi {
+    if uses_nsfw_model(influencer, conv) && state.openrouter.is_configured() {
+        state.openrouter.as_ref()
+    } else {
+        state.gemini.as_ref()
+    }
+}
+
+/// Load the last 10 messages (skipping `exclude_message_id`) with media presigned,
+/// and enrich the influencer's system prompt with the conversation's memories.
+///
+/// In a duet, `duet_cast` lists every influencer in the conversation: the other
+/// speakers' turns are passed as assistant turns named after and labelled with
+/// their speaker, so `influencer` can tell them from its own replies.
+async fn build_turn_context(
+    state: &AppState,
+    conv: &crate::models::entities::Conversation,
+    influencer: &AIInfluencer,
+    duet_cast: &[AIInfluencer],
+    exclude_message_id: Option<&str>,
+) -> Result<TurnContext, AppError> {
+    let all_recent = state
+        .db
+        .msg_repo()
+        .get_recent_for_context(&conv.id, 11)
+        .await?;
+    let history = all_recent
+        .into_iter()
+        .filter(|m| Some(m.id.as_str()) != exclude_message_id)
+        .collect();
+    Ok(compose_turn_context(state, conv, influencer, duet_cast, history).await)
+}
+
+/// Room reserved for the per-turn sections appended to the cached base prompt.
+const DYNAMIC_PROMPT_CAPACITY: usize = 2048;
+
+/// [`build_turn_context`] for history the caller already has, oldest first.
+/// Failed replies are dropped and only the last 10 messages are kept.
+pub(super) async fn compose_turn_context(
+    state: &AppState,
+    conv: &crate::models::entities::Conversation,
+    influencer: &AIInfluencer,
+    duet_cast: &[AIInfluencer],
+    mut history: Vec<Message>,
+) -> TurnContext {
+    // Fallback text from failed generations never goes back to the model
+    history.retain(|m| !m.is_failed());
+    let skip = history.len().saturating_sub(10);
+    history.drain(..skip);
+
+    // A message neutralized when sent is stored as written; neutralize its prompt copy too
+    for msg in &mut history {
+        if msg.role == MessageRole::User
+            && prompt_guard::was_neutralized(&msg.metadata)
+            && let Some(content) = msg.content.as_deref()
+        {
+            msg.content = Some(prompt_guard::neutralize(content));
+        }
+    }
+
+    // Long messages were condensed when sent; don't let them crowd out the rest
+    let max_chars = state.settings.load().message_condense_threshold_chars;
+    if max_chars > 0 {
+        for msg in &mut history {
+            if let Some(clipped) = msg
+                .content
+                .as_deref()
+                .and_then(|c| long_message::clip_for_history(c, max_chars))
+            {
+                msg.content = Some(clipped);
+            }
+        }
+    }
+
+    // Presign S3 keys in history
+    let s3_keys: Vec<String> = history
+        .iter()
+        .flat_map(|m| {
+            m.media_urls
+                .iter()
+                .chain(m.audio_url.iter())
+                .filter(|u| !u.starts_with("http"))
+                .cloned()
+        })
+        .collect();
+    let url_map = if s3_keys.is_empty() {
+        HashMap::new()
+    } else {
+        state.storage.generate_presigned_urls_batch(&s3_keys).await
+    };
+    let presign = |key: &str| url_map.get(key).cloned().unwrap_or_else(|| key.to_string());
+    for msg in &mut history {
+        msg.media_urls = msg.media_urls.iter().map(|u| presign(u)).collect();
+        msg.audio_url = msg.audio_url.as_ref().map(|u| presign(u));
+    }
+
+    // Enhance system instructions with memories
+    let memories: HashMap<String, String> = conv
+        .metadata
+        .get("memories")
+        .and_then(|m| serde_json::from_value(m.clone()).ok())
+        .unwrap_or_default();
+
+    // Persona and guardrails come prebuilt; only the per-turn sections are appended
+    let base = state.system_prompts.guarded(influencer);
+    let mut system_instructions = String::with_capacity(base.len() + DYNAMIC_PROMPT_CAPACITY);
+    system_instructions.push_str(&base);
+    if !duet_cast.is_empty() {
+        relabel_duet_history(&mut history, influencer, duet_cast);
+        let others: Vec<&str> = duet_cast
+            .iter()
+            .filter(|i| i.id != influencer.id)
+            .map(|i| i.display_name.as_str())
+            .collect();
+        let _ = write!(
+            system_instructions,
+            "\n\n**GROUP CHAT:**\nYou are {} in a conversation with the user and {}. \
+             Lines from the other characters are prefixed with their name in brackets. \
+             Reply only as yourself and never write lines for anyone else.\n",
+            influencer.display_name,
+            others.join(", ")
+        );
+    }
+    if !memories.is_empty() {
+        system_instructions.push_str("\n\n**MEMORIES:**\n");
+        for (key, value) in &memories {
+            let value = state.pii_policy.redact(value);
+            let _ = writeln!(system_instructions, "- {key}: {value}");
+        }
+    }
+
+    // Only a preferred language needs an instruction; otherwise the persona's own language rules apply
+    if let Some(language) = conv.preferred_language() {
+        let _ = write!(
+            system_instructions,
+            "\n\n**LANGUAGE:**\nAlways reply in {language}, even if the user writes in another language.\n"
+        );
+    }
+
+    // User-chosen reply style goes last so it overrides the persona's defaults
+    let style = conv.response_style();
+    if let Some(addendum) = style.prompt_addendum() {
+        let _ = write!(system_instructions, "\n\n**RESPONSE STYLE:**\n{addendum}\n");
+    }
+    // Except for safe mode, which nothing may override
+    if conv.safe_mode() {
+        system_instructions.push('\n');
+        system_instructions.push_str(moderation::SAFE_MODE_PROMPT);
+    }
+    let sampling = conv.creativity().sampling();
+    let generation = GenerationOptions {
+        max_tokens: Some(style.max_tokens(select_ai_client(state, influencer, conv).max_tokens())),
+        temperature: sampling.map(|(temperature, _)| temperature),
+        top_p: sampling.map(|(_, top_p)| top_p),
+        ..Default::default()
+    };
+
+    TurnContext {
+        system_instructions,
+        history,
+        memories,
+        generation,
+    }
+}
+
+/// Influencers taking turns in a duet, in speaking order. Empty for direct conversations.
+/// Discontinued influencers drop out of the rotation.
+async fn load_duet_cast(
+    state: &AppState,
+    conv: &crate::models::entities::Conversation,
+) -> Result<Vec<AIInfluencer>, AppError> {
+    if conv.duet_mode().is_none() {
+        return Ok(vec![]);
+    }
+    let inf_repo = state.db.inf_repo();
+    let mut cast = Vec::new();
+    for influencer_id in state.db.conv_repo().list_influencer_ids(&conv.id).await? {
+        if let Some(influencer) = state
+            .influencer_cache
+            .get_by_id(&inf_repo, &influencer_id)
+            .await?
+            && influencer.is_active != InfluencerStatus::Discontinued
+        {
+            cast.push(influencer);
+        }
+    }
+    Ok(cast)
+}
+
+/// Choose which duet influencer answers next: the one named in `text` when the mode
+/// allows addressing, otherwise whoever follows the most recent speaker.
+async fn next_duet_speaker<'a>(
+    state: &AppState,
+    conv: &crate::models::entities::Conversation,
+    cast: &'a [AIInfluencer],
+    text: Option<&str>,
+) -> Result<&'a AIInfluencer, AppError> {
+    if conv.duet_mode() == Some(DuetMode::Addressed)
+        && let Some(text) = text
+    {
+        let text = text.to_lowercase();
+        let mut addressed = cast.iter().filter(|i| {
+            text.contains(&i.display_name.to_lowercase()) || text.contains(&i.name.to_lowercase())
+        });
+        if let (Some(influencer), None) = (addressed.next(), addressed.next()) {
+            return Ok(influencer);
+        }
+    }
+
+    let recent = state
+        .db
+        .msg_repo()
+        .list_by_conversation(&conv.id, 10, 0, "desc")
+        .await?;
+    let last_speaker = recent
+        .iter()
+        .filter(|m| m.role == MessageRole::Assistant)
+        .find_map(|m| m.speaker_id());
+    let next = last_speaker
+        .and_then(|id| cast.iter().position(|i| i.id == id))
+        .map(|pos| (pos + 1) % cast.len())
+        .unwrap_or(0);
+    Ok(&cast[next])
+}
+
+/// Tag other duet speakers' replies with their name. They stay assistant turns,
+/// attributed to that speaker, so the model never reads them as the user's.
+fn relabel_duet_history(history: &mut [Message], speaker: &AIInfluencer, cast: &[AIInfluencer]) {
+    for msg in history.iter_mut() {
+        if msg.role != MessageRole::Assistant {
+            continue;
+        }
+        let author = msg.speaker_id().unwrap_or(cast[0].id.as_str());
+        if author == speaker.id {
+            continue;
+        }
+        let name = cast
+            .iter()
+            .find(|i| i.id == author)
+            .map(|i| i.display_name.clone())
+            .unwrap_or_default();
+        msg.content = Some(format!(
+            "[{name}]: {}",
+            msg.content.as_deref().unwrap_or_default()
+        ));
+        if let Some(metadata) = msg.metadata.as_object_mut() {
+            metadata.insert("speaker_name".into(), name.into());
+        } else {
+            msg.metadata = serde_json::json!({ "speaker_name": name });
+        }
+    }
+}
+
+/// Load a conversation, requiring the caller to own its bot.
+async fn get_conversation_as_owner(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    conversation_id: &str,
+) -> Result<crate::models::entities::Conversation, AppError> {
+    let conv = state
+        .db
+        .conv_repo()
+        .get_by_id(conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Conversation not found"))?;
+
+    let parent = state
+        .db
+        .inf_repo()
+        .get_parent_principal(&conv.influencer_id)
+        .await?;
+    if parent.as_deref() != Some(user.user_id.as_str()) {
+        return Err(AppError::forbidden("Only the bot owner can do this"));
+    }
+
+    Ok(conv)
+}
+
+/// Run assistant text through the output sanitizer. Returns the text to store and
+/// the metadata to record with it, which keeps the raw text when anything changed.
+pub(super) fn sanitize_assistant_text(
+    state: &AppState,
+    raw: &str,
+) -> (String, serde_json::Map<String, serde_json::Value>) {
+    sanitize_with_policy(raw, &OutputPolicy::from_settings(&state.settings.load()))
+}
+
+/// [`sanitize_assistant_text`] for a reply in `conv`. In safe mode the sanitizer
+/// runs even when disabled globally, and a reply failing the output screen is
+/// replaced, keeping the original in metadata for review.
+pub(super) fn sanitize_reply(
+    state: &AppState,
+    conv: &crate::models::entities::Conversation,
+    raw: &str,
+) -> (String, serde_json::Map<String, serde_json::Value>) {
+    if !conv.safe_mode() {
+        return sanitize_assistant_text(state, raw);
+    }
+    let policy = OutputPolicy {
+        enabled: true,
+        ..OutputPolicy::from_settings(&state.settings.load())
+    };
+    let (text, mut metadata) = sanitize_with_policy(raw, &policy);
+    if !moderation::is_unsafe_output(&text) {
+        return (text, metadata);
+    }
+    metadata.insert(
+        "safe_mode_blocked".into(),
+        serde_json::json!({ "raw_content": raw }),
+    );
+    (moderation::SAFE_MODE_REPLY.to_string(), metadata)
+}
+
+/// Keep the turn as an AI sample when the influencer's sample rate picks it.
+/// Calls turned away before reaching the provider aren't turns.
+#[allow(clippy::too_many_arguments)]
+fn sample_turn(
+    state: &AppState,
+    influencer: &AIInfluencer,
+    conversation_id: &str,
+    ai: &dyn AiApi,
+    system_prompt: &str,
+    history: &[Message],
+    input: &str,
+    result: &Result<(String, i32), AppError>,
+    started: Instant,
+) {
+    let sampler = AiSampler::from_settings(&state.settings.load());
+    if matches!(result, Err(AppError::Overloaded(..))) || !sampler.pick(influencer) {
+        return;
+    }
+    sampler.record(
+        &state.db,
+        SampledTurn {
+            influencer_id: &influencer.id,
+            conversation_id,
+            provider: ai.provider(),
+            model: &ai.model(),
+            system_prompt,
+            history,
+            user_message: input,
+            reply: result
+                .as_ref()
+                .map(|(text, _)| text.as_str())
+                .map_err(ToString::to_string),
+            latency: started.elapsed(),
+        },
+    );
+}
+
+/// Note which backend wrote a reply, so quality complaints can be traced to it.
+pub(super) fn record_generator(
+    metadata: &mut serde_json::Map<String, serde_json::Value>,
+    ai: &dyn AiApi,
+) {
+    metadata.insert(
+        "generated_by".into(),
+        serde_json::json!({ "provider": ai.provider(), "model": ai.model() }),
+    );
+}
+
+fn sanitize_with_policy(
+    raw: &str,
+    policy: &OutputPolicy,
+) -> (String, serde_json::Map<String, serde_json::Value>) {
+    let sanitized = output_sanitizer::sanitize(raw, policy);
+    let mut metadata = serde_json::Map::new();
+    if sanitized.is_modified()
+        && let serde_json::Value::Object(audit) = sanitized.to_metadata(raw)
+    {
+        metadata.extend(audit);
+    }
+    (sanitized.text, metadata)
+}
+
 /// Presign S3 storage keys in a MessageResponse so clients receive usable URLs.
-async fn presign_message_urls(
-    storage: &crate::services::storage::StorageService,
+pub(super) async fn presign_message_urls(
+    storage: &dyn crate::services::storage::Storage,
     msg: &mut MessageResponse,
 ) {
-    let s3_keys: Vec<String> = msg
-        .media_urls
+    presign_messages_urls(storage, std::slice::from_mut(msg)).await;
+}
+
+/// [`presign_message_urls`] for many messages with a single batch of lookups.
+pub(super) async fn presign_messages_urls(
+    storage: &dyn crate::services::storage::Storage,
+    msgs: &mut [MessageResponse],
+) {
+    let s3_keys: Vec<String> = msgs
         .iter()
-        .chain(msg.audio_url.iter())
+        .flat_map(|m| m.media_urls.iter().chain(m.audio_url.iter()))
         .filter(|u| !u.starts_with("http"))
         .cloned()
         .collect();
@@ -838,8 +3488,10 @@ async fn presign_message_urls(
     let url_map = storage.generate_presigned_urls_batch(&s3_keys).await;
     let presign = |key: &str| url_map.get(key).cloned().unwrap_or_else(|| key.to_string());
 
-    msg.media_urls = msg.media_urls.iter().map(|u| presign(u)).collect();
-    msg.audio_url = msg.audio_url.as_ref().map(|u| presign(u));
+    for msg in msgs {
+        msg.media_urls = msg.media_urls.iter().map(|u| presign(u)).collect();
+        msg.audio_url = msg.audio_url.as_ref().map(|u| presign(u));
+    }
 }
 
 // ── Background task helpers ──
@@ -852,41 +3504,67 @@ fn spawn_memory_extraction(
     memories: &HashMap<String, String>,
     is_nsfw: bool,
 ) {
-    let db = state.db.clone();
+    let state = state.clone();
     let conv_id = conversation_id.to_string();
     let ai_input = user_input.to_string();
     let response = response_text.to_string();
     let memories = memories.clone();
-    let gemini = state.gemini.clone();
-    let openrouter = state.openrouter.clone();
 
     tokio::spawn(async move {
-        let result = if is_nsfw && openrouter.is_configured() {
-            openrouter
-                .extract_memories(&ai_input, &response, &memories)
-                .await
+        let ai = if is_nsfw && state.openrouter.is_configured() {
+            state.openrouter.as_ref()
         } else {
-            gemini
-                .extract_memories(&ai_input, &response, &memories)
-                .await
+            state.gemini.as_ref()
+        };
+        let updated = match ai.extract_memories(&ai_input, &response, &memories).await {
+            Ok(updated) => updated,
+            Err(e) => {
+                tracing::error!(error = %e, "Memory extraction failed");
+                return;
+            }
         };
 
-        match result {
-            Ok(updated) if updated != memories => {
-                let conv_repo = db.conv_repo();
-                let mut metadata = serde_json::json!({});
-                metadata["memories"] = serde_json::to_value(&updated).unwrap_or_default();
-                if let Err(e) = conv_repo.update_metadata(&conv_id, &metadata).await {
-                    tracing::error!(error = %e, "Failed to update conversation memories");
-                }
+        let conv_repo = state.db.conv_repo();
+        let refs = match conv_repo.get_by_id(&conv_id).await {
+            Ok(Some(conv)) => memory::load_refs(&conv.metadata),
+            _ => memory::MemoryRefs::new(),
+        };
+        let (updated, updated_refs) = memory::maintain(
+            ai,
+            &state.pii_policy,
+            &state.memory_metrics,
+            MemoryLimits::from_settings(&state.settings.load()),
+            &memories,
+            updated,
+            refs.clone(),
+            &format!("{ai_input}\n{response}"),
+        )
+        .await;
+
+        if updated != memories {
+            let memories_json = serde_json::to_value(&updated).unwrap_or_default();
+            if let Err(e) = conv_repo
+                .set_metadata_key(&conv_id, "memories", &memories_json)
+                .await
+            {
+                tracing::error!(error = %e, "Failed to update conversation memories");
+                return;
+            }
+            state.memory_metrics.record_save(&updated);
+        }
+        if updated_refs != refs {
+            let refs_json = serde_json::to_value(&updated_refs).unwrap_or_default();
+            if let Err(e) = conv_repo
+                .set_metadata_key(&conv_id, memory::REFS_KEY, &refs_json)
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to update memory reference times");
             }
-            Err(e) => tracing::error!(error = %e, "Memory extraction failed"),
-            _ => {}
         }
     });
 }
 
-fn spawn_notifications(
+pub(super) fn spawn_notifications(
     state: &Arc<AppState>,
     user_id: &str,
     conversation_id: &str,
@@ -895,7 +3573,7 @@ fn spawn_notifications(
     response_text: &str,
     assistant_message: &Message,
 ) {
-    let push = state.push_notifications.clone();
+    let push = state.push_batcher.clone();
     let ws = state.ws_manager.clone();
     let db = state.db.clone();
     let user_id = user_id.to_string();
@@ -903,7 +3581,14 @@ fn spawn_notifications(
     let influencer_id = influencer_id.to_string();
     let influencer_name = influencer.display_name.clone();
     let influencer_avatar = influencer.avatar_url.clone();
+    let influencer_online = influencer.is_online();
     let msg_content = response_text.to_string();
+    let message_id = assistant_message.id.clone();
+    let message_type = assistant_message.message_type.clone();
+    let updated_at = assistant_message
+        .created_at
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
     let msg_json =
         serde_json::to_value(MessageResponse::from(assistant_message.clone())).unwrap_or_default();
 
@@ -914,7 +3599,7 @@ fn spawn_notifications(
             "id": influencer_id,
             "display_name": influencer_name,
             "avatar_url": influencer_avatar,
-            "is_online": true,
+            "is_online": influencer_online,
         });
         ws.broadcast_new_message(
             &user_id,
@@ -924,18 +3609,118 @@ fn spawn_notifications(
             unread_count,
         );
 
-        let truncated = if msg_content.chars().count() > 100 {
-            let s: String = msg_content.chars().take(100).collect();
-            format!("{s}...")
-        } else {
-            msg_content
-        };
+        let part_repo = db.part_repo();
+        let members: Vec<String> = part_repo
+            .list_by_conversation(&conv_id)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|p| p.user_id)
+            .filter(|id| *id != user_id)
+            .collect();
+        for member in &members {
+            let unread = part_repo
+                .unread_counts(member, std::slice::from_ref(&conv_id))
+                .await
+                .ok()
+                .and_then(|counts| counts.get(&conv_id).copied())
+                .unwrap_or(0);
+            ws.broadcast_new_message(member, &conv_id, &msg_json, &influencer_json, unread);
+        }
+        for recipient in std::iter::once(&user_id).chain(&members) {
+            ws.broadcast_conversation_updated(recipient, &conv_id, &updated_at, &msg_json);
+        }
+
+        // message_id lets the client deep-link via GET /api/v1/chat/messages/{message_id}
         let data = serde_json::json!({
             "conversation_id": conv_id,
             "influencer_id": influencer_id,
+            "message_id": message_id,
             "type": "new_message",
         });
-        push.send_push_notification(&user_id, &influencer_name, &truncated, Some(&data))
-            .await;
+        // Muted members still get the WebSocket update above, just no push
+        let conv = db.conv_repo().get_by_id(&conv_id).await.ok().flatten();
+        let now = chrono::Utc::now().naive_utc();
+        let language = conv
+            .as_ref()
+            .and_then(|c| c.preferred_language())
+            .map(str::to_string);
+        let body =
+            notification_format::message_preview(&msg_content, &message_type, language.as_deref());
+        let is_muted = |principal: &str| {
+            conv.as_ref()
+                .is_some_and(|c| c.muted_until(principal, now).is_some())
+        };
+        for recipient in std::iter::once(&user_id).chain(&members) {
+            if is_muted(recipient) || quiet_hours::should_defer(&db, recipient).await {
+                continue;
+            }
+            push.enqueue(PushItem {
+                recipient: recipient.clone(),
+                conversation_id: conv_id.clone(),
+                message_id: message_id.clone(),
+                title: influencer_name.clone(),
+                body: body.clone(),
+                data: data.clone(),
+                language: language.clone(),
+            });
+        }
+    });
+}
+
+/// Relay a member's message to everyone else in a group conversation, and let
+/// every member's inbox (the sender's other devices included) re-sort.
+fn spawn_group_fanout(
+    state: &Arc<AppState>,
+    conv: &crate::models::entities::Conversation,
+    sender_id: &str,
+    influencer: &AIInfluencer,
+    message: &Message,
+) {
+    let ws = state.ws_manager.clone();
+    let db = state.db.clone();
+    let conv_id = conv.id.clone();
+    let creator_id = conv.user_id.clone();
+    let sender_id = sender_id.to_string();
+    let influencer_json = serde_json::json!({
+        "id": influencer.id,
+        "display_name": influencer.display_name,
+        "avatar_url": influencer.avatar_url,
+        "is_online": influencer.is_online(),
+    });
+    let msg_json = serde_json::to_value(MessageResponse::from(message.clone())).unwrap_or_default();
+    let updated_at = message.created_at.format("%Y-%m-%d %H:%M:%S").to_string();
+
+    tokio::spawn(async move {
+        let participants = db
+            .part_repo()
+            .list_by_conversation(&conv_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!(error = %e, "Failed to load group participants");
+                Vec::new()
+            });
+        let recipients =
+            std::iter::once(creator_id.clone()).chain(participants.into_iter().map(|p| p.user_id));
+        for recipient in recipients {
+            if recipient != sender_id {
+                // The creator reads through messages.is_read, invited members
+                // through their own read position
+                let unread = if recipient == creator_id {
+                    db.msg_repo().count_unread(&conv_id).await
+                } else {
+                    db.part_repo()
+                        .unread_counts(&recipient, std::slice::from_ref(&conv_id))
+                        .await
+                        .map(|counts| counts.get(&conv_id).copied().unwrap_or(0))
+                }
+                .unwrap_or_else(|e| {
+                    tracing::warn!(error = %e, "Failed to count unread messages for group fan-out");
+                    0
+                });
+                ws.broadcast_new_message(&recipient, &conv_id, &msg_json, &influencer_json, unread);
+            }
+            ws.broadcast_conversation_updated(&recipient, &conv_id, &updated_at, &msg_json);
+        }
     });
 }
@@ -2,190 +2,74 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::Json;
-use axum::extract::{Query, State};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
 
+use super::chat::{
+    presign_message_urls, presign_messages_urls, sanitize_reply, screen_for_abuse,
+    spawn_notifications,
+};
+use super::pagination::{count_if, trim_page};
 use crate::AppState;
-use crate::db::repositories::ConversationRepository;
+use crate::db::repositories::{
+    ConversationRepository, ParticipantRepository, UserConversationFilter,
+};
 use crate::error::{AppError, ErrorBody};
-use crate::middleware::AuthenticatedUser;
-use crate::models::entities::InfluencerStatus;
-use crate::models::requests::ListConversationsV2Params;
+use crate::middleware::{AuthenticatedUser, Tenant, ValidatedJson};
+use crate::models::entities::{Conversation, InfluencerStatus, MessageRole};
+use crate::models::requests::{ListConversationsV2Params, ListMessagesParams, SendMessageRequest};
 use crate::models::responses::{
-    ConversationResponseV2, InfluencerBasicInfoV2, ListConversationsResponseV2, UserBasicInfo,
+    ConversationResponseV2, DeleteConversationResponse, InfluencerBasicInfoV2,
+    ListConversationsResponseV2, ListMessagesResponseV2, MessageResponse, UserBasicInfo,
 };
+use crate::services::caller_type::CallerType;
+use crate::services::image_caption;
 
-/// Whether the authenticated caller is a regular user or a bot.
-enum CallerType {
-    User,
-    Bot,
-}
-
-/// Determine if the caller is a bot or user by querying the User Info Service canister.
-/// Falls back to `User` on any error (parsing, network, canister error).
-async fn resolve_caller_type(agent: &ic_agent::Agent, principal_str: &str) -> CallerType {
-    let principal = match candid::Principal::from_text(principal_str) {
-        Ok(p) => p,
-        Err(e) => {
-            tracing::debug!(error = %e, principal = %principal_str, "Failed to parse principal, defaulting to User");
-            return CallerType::User;
-        }
-    };
-
-    let canister_id = yral_canisters_client::ic::USER_INFO_SERVICE_ID;
-    let service = yral_canisters_client::user_info_service::UserInfoService(canister_id, agent);
-
-    match service.get_user_profile_details_v_7(principal).await {
-        Ok(yral_canisters_client::user_info_service::Result7::Ok(profile)) => {
-            match profile.account_type {
-                yral_canisters_client::user_info_service::UserAccountType::BotAccount {
-                    ..
-                } => CallerType::Bot,
-                yral_canisters_client::user_info_service::UserAccountType::MainAccount {
-                    ..
-                } => CallerType::User,
-            }
-        }
-        Ok(yral_canisters_client::user_info_service::Result7::Err(e)) => {
-            tracing::warn!(error = %e, "Canister returned error for caller type lookup, defaulting to User");
-            CallerType::User
-        }
-        Err(e) => {
-            tracing::warn!(error = %e, "IC agent error during caller type lookup, defaulting to User");
-            CallerType::User
-        }
-    }
-}
-
-/// Batch fetch user profiles: profile pictures from canister + usernames from metadata server.
+/// Batch fetch user profiles (usernames from the metadata server, profile pictures from the
+/// canister) through the shared profile cache, with presence.
 /// Returns a map of principal_id -> UserBasicInfo.
 async fn batch_fetch_user_profiles(
-    agent: &ic_agent::Agent,
-    http_client: &reqwest::Client,
-    metadata_url: &str,
+    state: &AppState,
     user_ids: &[String],
-) -> HashMap<String, UserBasicInfo> {
-    let mut profiles: HashMap<String, UserBasicInfo> = user_ids
+) -> Result<HashMap<String, UserBasicInfo>, AppError> {
+    let presence_repo = state.db.presence_repo();
+    let settings = state.settings.load_full();
+    let (mut cached, last_seen) = tokio::join!(
+        state.user_profiles.get_many(
+            &state.ic_agent,
+            &state.http_client,
+            &settings.metadata_url,
+            user_ids,
+        ),
+        presence_repo.last_seen_many(user_ids),
+    );
+    let last_seen = last_seen?;
+
+    Ok(user_ids
         .iter()
         .map(|uid| {
+            let profile = cached.remove(uid).unwrap_or_default();
             (
                 uid.clone(),
                 UserBasicInfo {
                     principal_id: uid.clone(),
-                    username: None,
-                    profile_picture_url: None,
+                    username: profile.username,
+                    profile_picture_url: profile.profile_picture_url,
+                    is_online: state.ws_manager.is_connected(uid),
+                    last_seen_at: last_seen.get(uid).copied(),
                 },
             )
         })
-        .collect();
-
-    if user_ids.is_empty() {
-        return profiles;
-    }
-
-    // Fetch usernames from metadata server and profile pics from canister in parallel
-    let (metadata_result, canister_result) = tokio::join!(
-        fetch_usernames_from_metadata(http_client, metadata_url, user_ids),
-        fetch_profile_pics_from_canister(agent, user_ids),
-    );
-
-    // Merge metadata (usernames)
-    for (pid, username) in metadata_result {
-        if let Some(info) = profiles.get_mut(&pid) {
-            info.username = Some(username);
-        }
-    }
-
-    // Merge canister data (profile pictures)
-    for (pid, pic_url) in canister_result {
-        if let Some(info) = profiles.get_mut(&pid) {
-            info.profile_picture_url = Some(pic_url);
-        }
-    }
-
-    profiles
-}
-
-/// Fetch usernames from the yral metadata server via POST /metadata-bulk.
-async fn fetch_usernames_from_metadata(
-    http_client: &reqwest::Client,
-    metadata_url: &str,
-    user_ids: &[String],
-) -> HashMap<String, String> {
-    let url = format!("{}/metadata-bulk", metadata_url.trim_end_matches('/'));
-
-    let body = serde_json::json!({ "users": user_ids });
-
-    let result: HashMap<String, String> = match http_client.post(&url).json(&body).send().await {
-        Ok(resp) => {
-            if !resp.status().is_success() {
-                tracing::warn!(status = %resp.status(), "Metadata server returned error for bulk fetch");
-                return HashMap::new();
-            }
-            match resp.json::<serde_json::Value>().await {
-                Ok(json) => {
-                    let mut usernames = HashMap::new();
-                    if let Some(ok_data) = json.get("Ok").and_then(|v| v.as_object()) {
-                        for (principal, meta) in ok_data {
-                            if let Some(name) = meta.get("user_name").and_then(|v| v.as_str())
-                                && !name.trim().is_empty()
-                            {
-                                usernames.insert(principal.clone(), name.to_string());
-                            }
-                        }
-                    }
-                    usernames
-                }
-                Err(e) => {
-                    tracing::warn!(error = %e, "Failed to parse metadata bulk response");
-                    HashMap::new()
-                }
-            }
-        }
-        Err(e) => {
-            tracing::warn!(error = %e, "Failed to fetch usernames from metadata server");
-            HashMap::new()
-        }
-    };
-
-    result
+        .collect())
 }
 
-/// Fetch profile pictures from the User Info Service canister.
-async fn fetch_profile_pics_from_canister(
-    agent: &ic_agent::Agent,
-    user_ids: &[String],
-) -> HashMap<String, String> {
-    let principals: Vec<candid::Principal> = user_ids
-        .iter()
-        .filter_map(|id| candid::Principal::from_text(id).ok())
-        .collect();
-
-    if principals.is_empty() {
-        return HashMap::new();
-    }
-
-    let canister_id = yral_canisters_client::ic::USER_INFO_SERVICE_ID;
-    let service = yral_canisters_client::user_info_service::UserInfoService(canister_id, agent);
-
-    match service.get_users_profile_details(principals).await {
-        Ok(yral_canisters_client::user_info_service::Result9::Ok(details)) => {
-            let mut pics = HashMap::new();
-            for detail in details {
-                if let Some(pic) = detail.profile_picture {
-                    pics.insert(detail.principal_id.to_text(), pic.url);
-                }
-            }
-            pics
-        }
-        Ok(yral_canisters_client::user_info_service::Result9::Err(e)) => {
-            tracing::warn!(error = %e, "Canister error fetching user profiles");
-            HashMap::new()
-        }
-        Err(e) => {
-            tracing::warn!(error = %e, "IC agent error fetching user profiles");
-            HashMap::new()
-        }
-    }
+/// Whether `principal` is the bot this conversation is with.
+async fn is_conversation_bot(state: &AppState, conv: &Conversation, principal: &str) -> bool {
+    conv.influencer_id == principal
+        && matches!(
+            state.caller_types.resolve(&state.ic_agent, principal).await,
+            CallerType::Bot
+        )
 }
 
 /// List user's conversations (V2 with enriched influencer info)
@@ -203,6 +87,7 @@ async fn fetch_profile_pics_from_canister(
 )]
 pub async fn list_conversations_v2(
     State(state): State<Arc<AppState>>,
+    tenant: Tenant,
     _user: AuthenticatedUser,
     Query(params): Query<ListConversationsV2Params>,
 ) -> Result<Json<ListConversationsResponseV2>, AppError> {
@@ -212,39 +97,93 @@ pub async fn list_conversations_v2(
     let principal = &params.principal;
 
     // Determine if the principal is a bot or user via canister lookup
-    let caller_type = resolve_caller_type(&state.ic_agent, principal).await;
+    let caller_type = state.caller_types.resolve(&state.ic_agent, principal).await;
 
-    match caller_type {
-        CallerType::User => list_for_user(conv_repo, principal, &params, limit, offset).await,
+    let Json(mut response) = match caller_type {
+        CallerType::User => {
+            list_for_user(
+                conv_repo,
+                state.db.part_repo(),
+                principal,
+                tenant.as_str(),
+                &params,
+                limit,
+                offset,
+            )
+            .await
+        }
         CallerType::Bot => {
             list_for_bot(
+                &state,
                 conv_repo,
-                &state.ic_agent,
-                &state.http_client,
-                &state.settings.metadata_url,
                 principal,
                 limit,
                 offset,
+                params.include_total(),
             )
             .await
         }
+    }?;
+
+    // Batch fetch recent messages, as V1 does, so clients skip a request per conversation
+    let recent_limit = params.include_recent();
+    if recent_limit > 0 {
+        let conv_ids: Vec<String> = response
+            .conversations
+            .iter()
+            .map(|c| c.id.clone())
+            .collect();
+        let mut recent = state
+            .db
+            .msg_repo()
+            .get_recent_for_conversations_batch(&conv_ids, recent_limit, &params.fields())
+            .await?;
+        for conv in &mut response.conversations {
+            let mut messages: Vec<MessageResponse> = recent
+                .remove(&conv.id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(MessageResponse::from)
+                .collect();
+            presign_messages_urls(state.storage.as_ref(), &mut messages).await;
+            conv.recent_messages = Some(messages);
+        }
     }
+
+    Ok(Json(response))
 }
 
 /// User is fetching conversations → return influencer info as the peer.
 async fn list_for_user(
     conv_repo: ConversationRepository,
+    part_repo: ParticipantRepository,
     user_id: &str,
+    tenant: &str,
     params: &ListConversationsV2Params,
     limit: i64,
     offset: i64,
 ) -> Result<Json<ListConversationsResponseV2>, AppError> {
-    let influencer_id = params.influencer_id.as_deref();
+    let filter = UserConversationFilter {
+        influencer_id: params.influencer_id.as_deref(),
+        ..Default::default()
+    };
 
-    let (conversations, total) = tokio::try_join!(
-        conv_repo.list_by_user(user_id, influencer_id, limit, offset),
-        conv_repo.count_by_user(user_id, influencer_id),
+    let (mut conversations, total) = tokio::try_join!(
+        conv_repo.list_by_user(user_id, tenant, filter, limit + 1, offset),
+        count_if(
+            params.include_total(),
+            conv_repo.count_by_user(user_id, tenant, filter),
+        ),
     )?;
+    let has_more = trim_page(&mut conversations, limit);
+
+    // Group conversations the user was invited into track unread per participant
+    let group_ids: Vec<String> = conversations
+        .iter()
+        .filter(|c| c.user_id != user_id)
+        .map(|c| c.id.clone())
+        .collect();
+    let group_unread = part_repo.unread_counts(user_id, &group_ids).await?;
 
     let conversations = conversations
         .into_iter()
@@ -257,7 +196,9 @@ async fn list_for_user(
                     name: i.name.clone(),
                     display_name: i.display_name.clone(),
                     avatar_url: i.avatar_url.clone(),
-                    is_online: i.is_active == InfluencerStatus::Active,
+                    is_online: i.is_online(),
+                    is_verified: i.is_verified,
+                    is_official: i.is_official,
                 })
                 .unwrap_or_else(|| InfluencerBasicInfoV2 {
                     id: conv.influencer_id.clone(),
@@ -265,8 +206,15 @@ async fn list_for_user(
                     display_name: String::new(),
                     avatar_url: None,
                     is_online: false,
+                    is_verified: false,
+                    is_official: false,
                 });
 
+            let unread_count = group_unread
+                .get(&conv.id)
+                .copied()
+                .unwrap_or(conv.unread_count);
+
             ConversationResponseV2 {
                 id: conv.id,
                 user_id: conv.user_id,
@@ -276,8 +224,9 @@ async fn list_for_user(
                 created_at: conv.created_at,
                 updated_at: conv.updated_at,
                 message_count: conv.message_count.unwrap_or(0),
-                unread_count: conv.unread_count,
+                unread_count,
                 last_message: conv.last_message,
+                recent_messages: None,
             }
         })
         .collect();
@@ -285,6 +234,7 @@ async fn list_for_user(
     Ok(Json(ListConversationsResponseV2 {
         conversations,
         total,
+        has_more,
         limit,
         offset,
     }))
@@ -293,18 +243,18 @@ async fn list_for_user(
 /// Bot is fetching conversations → return user info as the peer.
 /// The bot's principal (user_id from JWT) IS the influencer_id in the DB.
 async fn list_for_bot(
+    state: &AppState,
     conv_repo: ConversationRepository,
-    agent: &ic_agent::Agent,
-    http_client: &reqwest::Client,
-    metadata_url: &str,
     bot_principal: &str,
     limit: i64,
     offset: i64,
+    include_total: bool,
 ) -> Result<Json<ListConversationsResponseV2>, AppError> {
-    let (conversations, total) = tokio::try_join!(
-        conv_repo.list_by_influencer(bot_principal, limit, offset),
-        conv_repo.count_by_influencer(bot_principal),
+    let (mut conversations, total) = tokio::try_join!(
+        conv_repo.list_by_influencer(bot_principal, limit + 1, offset),
+        count_if(include_total, conv_repo.count_by_influencer(bot_principal)),
     )?;
+    let has_more = trim_page(&mut conversations, limit);
 
     // Collect unique user principals for batch profile fetch
     let unique_user_ids: Vec<String> = conversations
@@ -314,8 +264,7 @@ async fn list_for_bot(
         .into_iter()
         .collect();
 
-    let user_profiles =
-        batch_fetch_user_profiles(agent, http_client, metadata_url, &unique_user_ids).await;
+    let user_profiles = batch_fetch_user_profiles(state, &unique_user_ids).await?;
 
     let conversations = conversations
         .into_iter()
@@ -327,6 +276,8 @@ async fn list_for_bot(
                     principal_id: conv.user_id.clone(),
                     username: None,
                     profile_picture_url: None,
+                    is_online: false,
+                    last_seen_at: None,
                 });
 
             ConversationResponseV2 {
@@ -340,6 +291,7 @@ async fn list_for_bot(
                 message_count: conv.message_count.unwrap_or(0),
                 unread_count: conv.unread_count,
                 last_message: conv.last_message,
+                recent_messages: None,
             }
         })
         .collect();
@@ -347,7 +299,266 @@ async fn list_for_bot(
     Ok(Json(ListConversationsResponseV2 {
         conversations,
         total,
+        has_more,
+        limit,
+        offset,
+    }))
+}
+
+/// List messages in one of the bot's conversations (bot callers only), with the user's profile.
+#[utoipa::path(
+    get,
+    path = "/api/v2/chat/conversations/{conversation_id}/messages",
+    params(
+        ("conversation_id" = String, Path, description = "Conversation ID"),
+        ListMessagesParams
+    ),
+    responses(
+        (status = 200, body = ListMessagesResponseV2, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Caller is not this conversation's bot"),
+        (status = 404, body = ErrorBody, description = "Conversation not found")
+    ),
+    tag = "Chat V2",
+    security(("BearerAuth" = []))
+)]
+pub async fn list_bot_messages(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(conversation_id): Path<String>,
+    Query(params): Query<ListMessagesParams>,
+) -> Result<Json<ListMessagesResponseV2>, AppError> {
+    let msg_repo = state.db.msg_repo();
+
+    let conv = state
+        .db
+        .conv_repo()
+        .get_by_id(&conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Conversation not found"))?;
+    if !is_conversation_bot(&state, &conv, &user.user_id).await {
+        return Err(AppError::forbidden(
+            "Only the conversation's bot can read its messages",
+        ));
+    }
+
+    let limit = params.limit();
+    let offset = params.offset();
+    let (messages, total, stats) = tokio::try_join!(
+        msg_repo.list_by_conversation(&conversation_id, limit, offset, params.order()),
+        msg_repo.count_by_conversation(&conversation_id),
+        async {
+            if params.include_stats {
+                msg_repo
+                    .conversation_stats(&conversation_id)
+                    .await
+                    .map(Some)
+            } else {
+                Ok(None)
+            }
+        },
+    )?;
+    let mut profiles =
+        batch_fetch_user_profiles(&state, std::slice::from_ref(&conv.user_id)).await?;
+
+    let mut messages: Vec<MessageResponse> =
+        messages.into_iter().map(MessageResponse::from).collect();
+    presign_messages_urls(state.storage.as_ref(), &mut messages).await;
+
+    Ok(Json(ListMessagesResponseV2 {
+        user: profiles
+            .remove(&conv.user_id)
+            .unwrap_or_else(|| UserBasicInfo {
+                principal_id: conv.user_id.clone(),
+                username: None,
+                profile_picture_url: None,
+                is_online: false,
+                last_seen_at: None,
+            }),
+        conversation_id,
+        messages,
+        total,
         limit,
         offset,
+        stats,
+    }))
+}
+
+/// Post a reply into a conversation as the bot (bot callers, or the owner during a takeover).
+/// The message is stored with the assistant role and delivered to the user via WebSocket and push.
+#[utoipa::path(
+    post,
+    path = "/api/v2/chat/conversations/{conversation_id}/messages",
+    params(("conversation_id" = String, Path, description = "Conversation ID")),
+    request_body = SendMessageRequest,
+    responses(
+        (status = 201, body = MessageResponse, description = "Reply posted"),
+        (status = 200, body = MessageResponse, description = "Duplicate client_message_id, existing reply returned"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Caller is not this conversation's bot"),
+        (status = 404, body = ErrorBody, description = "Conversation not found"),
+        (status = 422, body = ErrorBody, description = "Validation error"),
+        (status = 429, body = ErrorBody, description = "Too many repeated or oversized messages in this conversation (`conversation_throttled`)")
+    ),
+    tag = "Chat V2",
+    security(("BearerAuth" = []))
+)]
+pub async fn send_bot_reply(
+    State(state): State<Arc<AppState>>,
+    tenant: Tenant,
+    user: AuthenticatedUser,
+    Path(conversation_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<SendMessageRequest>,
+) -> Result<(StatusCode, Json<MessageResponse>), AppError> {
+    let conv_repo = state.db.conv_repo();
+    let msg_repo = state.db.msg_repo();
+    let inf_repo = state.db.inf_repo();
+
+    body.validate_content(state.tenants.settings(tenant.as_str()).message_max_chars)
+        .map_err(|(field, msg)| AppError::field_error(field, msg))?;
+
+    let message_type = body
+        .parsed_message_type()
+        .ok_or_else(|| AppError::field_error("message_type", "Invalid message type"))?;
+
+    let conv = conv_repo
+        .get_by_id(&conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Conversation not found"))?;
+
+    // The bot itself may reply, and so may an owner who has taken over the conversation
+    let is_operator = conv.takeover_principal() == Some(user.user_id.as_str());
+    if !is_operator && !is_conversation_bot(&state, &conv, &user.user_id).await {
+        return Err(AppError::forbidden(
+            "Only the conversation's bot can post replies",
+        ));
+    }
+
+    if let Some(ref client_id) = body.client_message_id
+        && let Some(existing) = msg_repo
+            .get_by_client_id(&conversation_id, client_id)
+            .await?
+    {
+        let mut resp = MessageResponse::from(existing);
+        presign_message_urls(state.storage.as_ref(), &mut resp).await;
+        return Ok((StatusCode::OK, Json(resp)));
+    }
+
+    let influencer = inf_repo
+        .get_by_id(&conv.influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+
+    if influencer.is_active == InfluencerStatus::Discontinued {
+        return Err(AppError::forbidden(
+            "This bot has been deleted and can no longer send messages.",
+        ));
+    }
+
+    let shadow_limited = screen_for_abuse(
+        &state,
+        &user.user_id,
+        &conversation_id,
+        body.content.as_deref().unwrap_or_default(),
+    )
+    .await?;
+
+    let (content, mut metadata) = match body.content.as_deref() {
+        Some(raw) => {
+            let (text, metadata) = sanitize_reply(&state, &conv, raw);
+            (Some(text), metadata)
+        }
+        None => (None, serde_json::Map::new()),
+    };
+    let mut message = msg_repo
+        .create(
+            &conversation_id,
+            &MessageRole::Assistant,
+            content.as_deref(),
+            &message_type,
+            body.media_urls.as_deref().unwrap_or(&[]),
+            body.audio_url.as_deref(),
+            body.audio_duration_seconds,
+            None,
+            body.client_message_id.as_deref(),
+        )
+        .await?;
+
+    metadata.insert("author_principal".into(), user.user_id.clone().into());
+    if shadow_limited {
+        metadata.insert("shadow_limited".into(), true.into());
+    }
+    let metadata = serde_json::Value::Object(metadata);
+    match msg_repo.update_metadata(&message.id, &metadata).await {
+        Ok(()) => message.metadata = metadata,
+        Err(e) => tracing::error!(error = %e, "Failed to record bot reply author"),
+    }
+
+    // A shadow-limited sender's reply is kept but nobody is told about it
+    if shadow_limited {
+        let mut resp = MessageResponse::from(message);
+        presign_message_urls(state.storage.as_ref(), &mut resp).await;
+        return Ok((StatusCode::CREATED, Json(resp)));
+    }
+    image_caption::spawn_caption(&state, &message);
+
+    spawn_notifications(
+        &state,
+        &conv.user_id,
+        &conversation_id,
+        &conv.influencer_id,
+        &influencer,
+        content.as_deref().unwrap_or_default(),
+        &message,
+    );
+
+    let mut resp = MessageResponse::from(message);
+    presign_message_urls(state.storage.as_ref(), &mut resp).await;
+
+    Ok((StatusCode::CREATED, Json(resp)))
+}
+
+/// Delete one of the bot's conversations and all its messages (bot callers only).
+/// Same cascade as the user-side delete.
+#[utoipa::path(
+    delete,
+    path = "/api/v2/chat/conversations/{conversation_id}",
+    params(("conversation_id" = String, Path, description = "Conversation ID")),
+    responses(
+        (status = 200, body = DeleteConversationResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Caller is not this conversation's bot"),
+        (status = 404, body = ErrorBody, description = "Conversation not found")
+    ),
+    tag = "Chat V2",
+    security(("BearerAuth" = []))
+)]
+pub async fn delete_bot_conversation(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(conversation_id): Path<String>,
+) -> Result<Json<DeleteConversationResponse>, AppError> {
+    let conv_repo = state.db.conv_repo();
+    let msg_repo = state.db.msg_repo();
+
+    let conv = conv_repo
+        .get_by_id(&conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Conversation not found"))?;
+
+    if !is_conversation_bot(&state, &conv, &user.user_id).await {
+        return Err(AppError::forbidden(
+            "Only the conversation's bot can delete it",
+        ));
+    }
+
+    let deleted_messages = msg_repo.delete_by_conversation(&conversation_id).await?;
+    conv_repo.delete(&conversation_id).await?;
+
+    Ok(Json(DeleteConversationResponse {
+        success: true,
+        message: "Conversation deleted successfully".to_string(),
+        deleted_conversation_id: conversation_id,
+        deleted_messages_count: deleted_messages,
     }))
 }
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::State;
+
+use crate::AppState;
+use crate::error::{AppError, ErrorBody};
+use crate::middleware::{AuthenticatedUser, ValidatedJson};
+use crate::models::entities::DigestSubscription;
+use crate::models::requests::DigestSubscriptionRequest;
+use crate::models::responses::{
+    DigestConversationItem, DigestPreviewResponse, DigestSubscriptionResponse,
+};
+use crate::services::digest::build_digest;
+
+fn subscription_to_response(
+    user_id: String,
+    subscription: Option<DigestSubscription>,
+) -> DigestSubscriptionResponse {
+    DigestSubscriptionResponse {
+        user_id,
+        subscribed: subscription.is_some(),
+        frequency: subscription.as_ref().map(|s| s.frequency.clone()),
+        last_sent_at: subscription.and_then(|s| s.last_sent_at),
+    }
+}
+
+/// Get the caller's digest subscription
+#[utoipa::path(
+    get,
+    path = "/api/v1/digest/subscription",
+    responses(
+        (status = 200, body = DigestSubscriptionResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized")
+    ),
+    tag = "Digest",
+    security(("BearerAuth" = []))
+)]
+pub async fn get_subscription(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<DigestSubscriptionResponse>, AppError> {
+    let subscription = state.db.digest_repo().get(&user.user_id).await?;
+    Ok(Json(subscription_to_response(user.user_id, subscription)))
+}
+
+/// Opt in to (or change the frequency of) the unread-activity digest push
+#[utoipa::path(
+    put,
+    path = "/api/v1/digest/subscription",
+    request_body = DigestSubscriptionRequest,
+    responses(
+        (status = 200, body = DigestSubscriptionResponse, description = "Subscribed"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Digest",
+    security(("BearerAuth" = []))
+)]
+pub async fn subscribe(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    ValidatedJson(body): ValidatedJson<DigestSubscriptionRequest>,
+) -> Result<Json<DigestSubscriptionResponse>, AppError> {
+    let subscription = state
+        .db
+        .digest_repo()
+        .upsert(&user.user_id, &body.frequency)
+        .await?;
+    Ok(Json(subscription_to_response(
+        user.user_id,
+        Some(subscription),
+    )))
+}
+
+/// Opt out of the digest push
+#[utoipa::path(
+    delete,
+    path = "/api/v1/digest/subscription",
+    responses(
+        (status = 200, body = DigestSubscriptionResponse, description = "Unsubscribed"),
+        (status = 401, body = ErrorBody, description = "Unauthorized")
+    ),
+    tag = "Digest",
+    security(("BearerAuth" = []))
+)]
+pub async fn unsubscribe(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<DigestSubscriptionResponse>, AppError> {
+    state.db.digest_repo().remove(&user.user_id).await?;
+    Ok(Json(subscription_to_response(user.user_id, None)))
+}
+
+/// Preview the digest the caller would receive right now: unread replies since their last digest
+#[utoipa::path(
+    get,
+    path = "/api/v1/digest/preview",
+    responses(
+        (status = 200, body = DigestPreviewResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized")
+    ),
+    tag = "Digest",
+    security(("BearerAuth" = []))
+)]
+pub async fn preview(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<DigestPreviewResponse>, AppError> {
+    let since = state
+        .db
+        .digest_repo()
+        .get(&user.user_id)
+        .await?
+        .and_then(|s| s.last_sent_at);
+    let Some(digest) = build_digest(&state, &user.user_id, since).await? else {
+        return Ok(Json(DigestPreviewResponse {
+            has_activity: false,
+            title: None,
+            body: None,
+            conversations: vec![],
+        }));
+    };
+
+    Ok(Json(DigestPreviewResponse {
+        has_activity: true,
+        title: Some(digest.title),
+        body: Some(digest.body),
+        conversations: digest
+            .activity
+            .into_iter()
+            .map(|a| DigestConversationItem {
+                conversation_id: a.conversation_id,
+                influencer_id: a.influencer_id,
+                influencer_name: a.influencer_name,
+                unread_count: a.unread_count,
+                latest_message: a.latest_content,
+            })
+            .collect(),
+    }))
+}
@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+
+use crate::AppState;
+use crate::db::repos::Repos;
+use crate::error::{AppError, ErrorBody};
+use crate::middleware::{AuthenticatedUser, Tenant, ValidatedJson};
+use crate::models::entities::{InfluencerStatus, MessageSource};
+use crate::models::requests::{CreateConversationRequest, InboundEmailRequest, SendMessageRequest};
+use crate::models::responses::InboundEmailResponse;
+use crate::services::email::{
+    OutgoingEmail, parse_address, secret_matches, sender_authenticated, strip_quoted_reply,
+};
+
+/// Inbound email gateway: the provider's parse webhook posts here. The sender must
+/// pass the provider's SPF or DKIM check and is mapped to a principal, the body is
+/// sent as a chat message to the influencer named by the recipient's local part,
+/// and the reply goes back by email.
+#[utoipa::path(
+    post,
+    path = "/api/v1/email/inbound",
+    request_body = InboundEmailRequest,
+    params(("X-Email-Webhook-Secret" = String, Header, description = "Shared secret configured with the mail provider")),
+    responses(
+        (status = 200, body = InboundEmailResponse, description = "Email delivered to the conversation"),
+        (status = 401, body = ErrorBody, description = "Invalid webhook secret"),
+        (status = 403, body = ErrorBody, description = "Sender failed SPF and DKIM or has no linked account"),
+        (status = 404, body = ErrorBody, description = "Influencer not found"),
+        (status = 422, body = ErrorBody, description = "Validation error"),
+        (status = 503, body = ErrorBody, description = "Email gateway not configured")
+    ),
+    tag = "Email"
+)]
+pub async fn inbound_email(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    ValidatedJson(body): ValidatedJson<InboundEmailRequest>,
+) -> Result<Json<InboundEmailResponse>, AppError> {
+    let settings = state.settings.load_full();
+    let Some(secret) = settings.email_inbound_secret.as_deref() else {
+        return Err(AppError::service_unavailable(
+            "Email gateway is not configured",
+        ));
+    };
+    let provided = headers
+        .get("X-Email-Webhook-Secret")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if !secret_matches(provided, secret) {
+        return Err(AppError::unauthorized("Invalid webhook secret"));
+    }
+
+    let sender = parse_address(&body.from)
+        .ok_or_else(|| AppError::field_error("from", "Invalid sender address"))?;
+    // The From header is trivially forged; only act on mail the provider authenticated
+    if !sender_authenticated(&sender, body.spf.as_deref(), body.dkim.as_deref()) {
+        tracing::warn!(sender = %sender, spf = ?body.spf, dkim = ?body.dkim, "Unauthenticated inbound email");
+        return Err(AppError::forbidden(
+            "Sender could not be verified (SPF and DKIM failed)",
+        ));
+    }
+    let recipient = parse_address(&body.to)
+        .ok_or_else(|| AppError::field_error("to", "Invalid recipient address"))?;
+    let influencer_name = recipient.split('@').next().unwrap_or_default();
+
+    let influencer = state
+        .db
+        .inf_repo()
+        .get_by_id_or_name(influencer_name)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+    if influencer.is_active == InfluencerStatus::Discontinued {
+        return Err(AppError::forbidden(
+            "This bot has been deleted and can no longer receive messages.",
+        ));
+    }
+    // The message is handled in the bot's tenant, whichever app the sender uses
+    let tenant = Tenant(influencer.tenant.as_str().into());
+
+    let principal = state
+        .email
+        .resolve_principal(&sender)
+        .await?
+        .ok_or_else(|| AppError::forbidden("No account is linked to this email address"))?;
+
+    let content: String = strip_quoted_reply(body.text.as_deref().unwrap_or_default())
+        .chars()
+        .take(state.tenants.settings(tenant.as_str()).message_max_chars)
+        .collect();
+    if content.is_empty() {
+        return Err(AppError::field_error("text", "Email has no message text"));
+    }
+
+    // Reuse the regular chat pipeline so emails behave exactly like app messages
+    let user = AuthenticatedUser {
+        user_id: principal.clone(),
+    };
+    let (_, Json(conversation)) = super::chat::create_conversation(
+        State(state.clone()),
+        tenant.clone(),
+        user.clone(),
+        Repos::new(state.db.clone()),
+        HeaderMap::new(),
+        ValidatedJson(CreateConversationRequest {
+            influencer_id: influencer.id.clone(),
+        }),
+    )
+    .await?;
+
+    let (status, Json(sent)) = super::chat::send_message(
+        State(state.clone()),
+        tenant,
+        user,
+        Path(conversation.id.clone()),
+        ValidatedJson(SendMessageRequest {
+            message_type: "text".into(),
+            content: Some(content),
+            media_urls: None,
+            audio_url: None,
+            audio_duration_seconds: None,
+            client_message_id: body.message_id.clone(),
+            source: MessageSource::Typed,
+        }),
+    )
+    .await?;
+
+    let replied_now = status == StatusCode::OK;
+    let reply = sent
+        .assistant_message
+        .content
+        .as_deref()
+        .filter(|_| replied_now);
+    let replied = match reply {
+        Some(text) => {
+            let subject = body.subject.as_deref().unwrap_or_default().trim();
+            let subject = if subject.to_lowercase().starts_with("re:") {
+                subject.to_string()
+            } else if subject.is_empty() {
+                format!("Re: Chat with {}", influencer.display_name)
+            } else {
+                format!("Re: {subject}")
+            };
+            state
+                .email
+                .send(&OutgoingEmail {
+                    to: &sender,
+                    from_local: &influencer.name,
+                    from_name: &influencer.display_name,
+                    subject: &subject,
+                    text,
+                    in_reply_to: body.message_id.as_deref(),
+                })
+                .await
+        }
+        None => false,
+    };
+
+    tracing::info!(
+        conversation_id = %conversation.id,
+        influencer_id = %influencer.id,
+        replied,
+        "Inbound email processed"
+    );
+
+    Ok(Json(InboundEmailResponse {
+        conversation_id: conversation.id,
+        user_message_id: sent.user_message.id,
+        assistant_message_id: replied_now.then_some(sent.assistant_message.id),
+        replied,
+    }))
+}
@@ -27,9 +27,9 @@ pub async fn health(State(state): State<Arc<AppState>>) -> Json<HealthResponse>
             latency_ms: db_health.latency_ms,
             error: db_health.error,
             #[cfg(feature = "staging")]
-            pool_size: Some(state.settings.database_pool_size),
+            pool_size: Some(state.settings.load().database_pool_size),
             #[cfg(not(feature = "staging"))]
-            pool_size: Some(state.settings.pg_pool_size),
+            pool_size: Some(state.settings.load().pg_pool_size),
             pool_free: None,
         },
     );
@@ -53,12 +53,19 @@ pub async fn health(State(state): State<Arc<AppState>>) -> Json<HealthResponse>
             pool_free: None,
         },
     );
+    // Down only once a backup verification has failed; like PostgreSQL, it does
+    // NOT affect overall status
+    let backup = state.backup_verifier.last();
     services.insert(
         "litestream".to_string(),
         ServiceHealth {
-            status: "up".to_string(),
+            status: match &backup {
+                Some(b) if !b.ok => "down",
+                _ => "up",
+            }
+            .to_string(),
             latency_ms: None,
-            error: None,
+            error: backup.and_then(|b| b.error),
             pool_size: None,
             pool_free: None,
         },
@@ -72,7 +79,7 @@ pub async fn health(State(state): State<Arc<AppState>>) -> Json<HealthResponse>
                 status: pg_health.status,
                 latency_ms: pg_health.latency_ms,
                 error: pg_health.error,
-                pool_size: Some(state.settings.pg_pool_size),
+                pool_size: Some(state.settings.load().pg_pool_size),
                 pool_free: None,
             },
         );
@@ -121,15 +128,16 @@ pub async fn status(State(state): State<Arc<AppState>>) -> Json<StatusResponse>
             .await
             .unwrap_or(0);
 
+    let settings = state.settings.load();
     #[cfg(feature = "staging")]
-    let pool_size = state.settings.database_pool_size;
+    let pool_size = settings.database_pool_size;
     #[cfg(not(feature = "staging"))]
-    let pool_size = state.settings.pg_pool_size;
+    let pool_size = settings.pg_pool_size;
 
     Json(StatusResponse {
-        service: state.settings.app_name.clone(),
-        version: state.settings.app_version.clone(),
-        environment: state.settings.environment.clone(),
+        service: settings.app_name.clone(),
+        version: settings.app_version.clone(),
+        environment: settings.environment.clone(),
         uptime_seconds: uptime,
         database: DatabaseStats {
             connected: true,
@@ -141,6 +149,15 @@ pub async fn status(State(state): State<Arc<AppState>>) -> Json<StatusResponse>
             total_messages,
             active_influencers,
         },
+        influencer_cache: state.influencer_cache.stats(),
+        caller_type_cache: state.caller_types.stats(),
+        profile_cache: state.user_profiles.stats(),
+        prompt_cache: state.system_prompts.stats(),
+        memories: state.memory_metrics.stats(),
+        load_shedding: state.load_shed.stats(),
+        websocket: state.ws_manager.stats(),
+        tenants: state.tenants.stats(),
+        backup: state.backup_verifier.last(),
         timestamp: Utc::now().naive_utc(),
     })
 }
@@ -152,9 +169,10 @@ pub async fn status(State(state): State<Arc<AppState>>) -> Json<StatusResponse>
     tag = "Health"
 )]
 pub async fn root(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
+    let settings = state.settings.load();
     Json(serde_json::json!({
-        "service": state.settings.app_name,
-        "version": state.settings.app_version,
+        "service": settings.app_name,
+        "version": settings.app_version,
         "status": "running",
         "docs": "/explore/",
         "health": "/health",
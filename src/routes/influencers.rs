@@ -1,24 +1,43 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use axum::Json;
 use axum::extract::{Path, Query, State};
-use axum::http::{HeaderMap, header};
-use validator::Validate;
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
 
+use super::chat::sanitize_assistant_text;
+use super::pagination::{count_if, trim_page};
 use crate::AppState;
+use crate::db::repositories::{InfluencerListFilter, InfluencerRepository};
 use crate::error::{AppError, ErrorBody};
-use crate::middleware::AuthenticatedUser;
-use crate::models::entities::{AIInfluencer, InfluencerStatus};
+use crate::middleware::{AuthenticatedUser, RequestId, Tenant, ValidatedJson, has_admin_key};
+use crate::models::entities::{
+    AIInfluencer, AuditAction, AvailabilitySchedule, ConversationStarter, GenerationStatus,
+    InfluencerStatus,
+};
 use crate::models::requests::{
-    CreateInfluencerRequest, GeneratePromptRequest, GenerateVideoPromptRequest, PaginationParams,
-    UpdateSystemPromptRequest, ValidateMetadataRequest,
+    CreateInfluencerRequest, GeneratePromptRequest, GenerateVideoPromptRequest, LeaderboardParams,
+    ListInfluencersParams, RegenerateGreetingRequest, RenameInfluencerRequest,
+    SuggestionStatsParams, UpdateBadgesRequest, UpdateConversationStartersRequest,
+    UpdateMediaOnlyPromptRequest, UpdateScheduleRequest, UpdateSystemPromptRequest,
+    ValidateMetadataRequest,
 };
 use crate::models::responses::{
-    GeneratedMetadataResponse, InfluencerResponse, ListInfluencersResponse,
-    ListTrendingInfluencersResponse, SystemPromptResponse, TrendingInfluencerResponse,
-    VideoPromptResponse,
+    ConversationStartersResponse, GeneratedMetadataResponse, GenerationStatusResponse,
+    InfluencerResponse, LeaderboardResponse, ListInfluencersResponse,
+    ListTrendingInfluencersResponse, MediaOnlyPromptResponse, RegenerateGreetingResponse,
+    ScheduleResponse, StarterVideoPromptResponse, SuggestionStat, SuggestionStatsResponse,
+    SystemPromptResponse, TrendingInfluencerResponse, VideoPromptResponse,
 };
+use crate::services::audit::{self, ADMIN_ACTOR, AuditEvent};
 use crate::services::character_generator::CharacterGeneratorService;
+use crate::services::conversation_starters;
+use crate::services::duplicate_influencers;
+use crate::services::influencer_enrichment::{
+    self, STEP_AVATAR, STEP_AVATAR_CHECK, STEP_GREETING, STEP_STARTER_VIDEO,
+    STEP_STARTER_VIDEO_PROMPT,
+};
 use crate::services::moderation;
 
 /// Fetch profile picture from User Info Service canister for main user accounts
@@ -84,6 +103,7 @@ async fn fetch_username_from_metadata(
 
 impl From<AIInfluencer> for InfluencerResponse {
     fn from(i: AIInfluencer) -> Self {
+        let generation_status = i.generation().map(|g| g.status);
         Self {
             id: i.id,
             name: i.name,
@@ -92,24 +112,42 @@ impl From<AIInfluencer> for InfluencerResponse {
             description: i.description,
             category: i.category,
             is_active: i.is_active,
+            is_verified: i.is_verified,
+            is_official: i.is_official,
             parent_principal_id: i.parent_principal_id,
             source: i.source,
             system_prompt: Some(moderation::strip_guardrails(&i.system_instructions)),
             created_at: i.created_at,
             conversation_count: i.conversation_count,
             message_count: i.message_count,
+            view_count: i.view_count,
             starter_video_prompt: None,
+            generation_status,
         }
     }
 }
 
 type CachedJson<T> = ([(header::HeaderName, &'static str); 1], Json<T>);
 
+fn list_filter(
+    state: &AppState,
+    tenant: &Tenant,
+    params: &ListInfluencersParams,
+) -> InfluencerListFilter {
+    InfluencerListFilter {
+        verified: params.verified,
+        boost_verified: state
+            .tenants
+            .settings(tenant.as_str())
+            .boost_verified_influencers,
+    }
+}
+
 /// List all influencers
 #[utoipa::path(
     get,
     path = "/api/v1/influencers",
-    params(PaginationParams),
+    params(ListInfluencersParams),
     responses(
         (status = 200, body = ListInfluencersResponse, description = "Successful response"),
         (status = 422, body = ErrorBody, description = "Validation error")
@@ -118,14 +156,23 @@ type CachedJson<T> = ([(header::HeaderName, &'static str); 1], Json<T>);
 )]
 pub async fn list_influencers(
     State(state): State<Arc<AppState>>,
-    Query(params): Query<PaginationParams>,
+    tenant: Tenant,
+    Query(params): Query<ListInfluencersParams>,
 ) -> Result<CachedJson<ListInfluencersResponse>, AppError> {
     let repo = state.db.inf_repo();
 
-    let limit = params.limit(50, 100);
+    let limit = params.limit();
     let offset = params.offset();
+    let filter = list_filter(&state, &tenant, &params);
 
-    let (influencers, total) = tokio::try_join!(repo.list_all(limit, offset), repo.count_all(),)?;
+    let (mut influencers, total) = tokio::try_join!(
+        repo.list_all(tenant.as_str(), filter, limit + 1, offset),
+        count_if(
+            params.include_total(),
+            repo.count_all(tenant.as_str(), filter)
+        ),
+    )?;
+    let has_more = trim_page(&mut influencers, limit);
 
     Ok((
         [(header::CACHE_CONTROL, "public, max-age=300")],
@@ -135,6 +182,7 @@ pub async fn list_influencers(
                 .map(InfluencerResponse::from)
                 .collect(),
             total,
+            has_more,
             limit,
             offset,
         }),
@@ -145,7 +193,7 @@ pub async fn list_influencers(
 #[utoipa::path(
     get,
     path = "/api/v1/influencers/trending",
-    params(PaginationParams),
+    params(ListInfluencersParams),
     responses(
         (status = 200, body = ListTrendingInfluencersResponse, description = "Successful response"),
         (status = 422, body = ErrorBody, description = "Validation error")
@@ -154,15 +202,23 @@ pub async fn list_influencers(
 )]
 pub async fn list_trending(
     State(state): State<Arc<AppState>>,
-    Query(params): Query<PaginationParams>,
+    tenant: Tenant,
+    Query(params): Query<ListInfluencersParams>,
 ) -> Result<CachedJson<ListTrendingInfluencersResponse>, AppError> {
     let repo = state.db.inf_repo();
 
-    let limit = params.limit(50, 100);
+    let limit = params.limit();
     let offset = params.offset();
+    let filter = list_filter(&state, &tenant, &params);
 
-    let (influencers, total) =
-        tokio::try_join!(repo.list_trending(limit, offset), repo.count_trending(),)?;
+    let (mut influencers, total) = tokio::try_join!(
+        repo.list_trending(tenant.as_str(), filter, limit + 1, offset),
+        count_if(
+            params.include_total(),
+            repo.count_trending(tenant.as_str(), filter)
+        ),
+    )?;
+    let has_more = trim_page(&mut influencers, limit);
 
     let influencers = influencers
         .into_iter()
@@ -174,9 +230,12 @@ pub async fn list_trending(
             description: i.description,
             category: i.category,
             is_active: i.is_active,
+            is_verified: i.is_verified,
+            is_official: i.is_official,
             created_at: i.created_at,
             conversation_count: i.conversation_count.unwrap_or(0),
             message_count: i.message_count.unwrap_or(0),
+            view_count: i.view_count,
         })
         .collect();
 
@@ -185,38 +244,136 @@ pub async fn list_trending(
         Json(ListTrendingInfluencersResponse {
             influencers,
             total,
+            has_more,
             limit,
             offset,
         }),
     ))
 }
 
-/// Get an influencer by ID
+/// Influencers ranked by user activity over the last 7 or 30 days
+#[utoipa::path(
+    get,
+    path = "/api/v1/influencers/leaderboard",
+    params(LeaderboardParams),
+    responses(
+        (status = 200, body = LeaderboardResponse, description = "Successful response"),
+        (status = 400, body = ErrorBody, description = "Unknown window or metric")
+    ),
+    tag = "Influencers"
+)]
+pub async fn leaderboard(
+    State(state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Query(params): Query<LeaderboardParams>,
+) -> Result<CachedJson<LeaderboardResponse>, AppError> {
+    let window = params.window.unwrap_or_default();
+    let metric = params.metric.unwrap_or_default();
+    let (ranking, generated_at) = state
+        .leaderboards
+        .get(&state.db.inf_repo(), tenant.as_str(), window, metric)
+        .await?;
+
+    Ok((
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(LeaderboardResponse {
+            window,
+            metric,
+            entries: ranking.iter().take(params.limit()).cloned().collect(),
+            generated_at,
+        }),
+    ))
+}
+
+/// Get an influencer by ID. Signed in as the owner, the starter video prompt is included
+///
+/// A name, current or from before a rename, redirects to the influencer's ID.
 #[utoipa::path(
     get,
     path = "/api/v1/influencers/{influencer_id}",
     params(("influencer_id" = String, Path, description = "Influencer ID")),
     responses(
         (status = 200, body = InfluencerResponse),
+        (status = 301, description = "Looked up by name; `Location` has the influencer's URL"),
         (status = 404, body = ErrorBody)
     ),
-    tag = "Influencers"
+    tag = "Influencers",
+    security((), ("BearerAuth" = []))
 )]
 pub async fn get_influencer(
     State(state): State<Arc<AppState>>,
+    tenant: Tenant,
+    user: Option<AuthenticatedUser>,
     Path(influencer_id): Path<String>,
-) -> Result<CachedJson<InfluencerResponse>, AppError> {
+) -> Result<Response, AppError> {
     let repo = state.db.inf_repo();
 
-    let influencer = repo
+    let Some(influencer) = repo
         .get_with_conversation_count(&influencer_id)
         .await?
-        .ok_or_else(|| AppError::not_found(format!("Influencer '{influencer_id}' not found")))?;
+        .filter(|i| i.tenant == tenant.as_str())
+    else {
+        let renamed = repo
+            .get_by_name(&influencer_id)
+            .await?
+            .filter(|i| i.tenant == tenant.as_str())
+            .ok_or_else(|| {
+                AppError::not_found(format!("Influencer '{influencer_id}' not found"))
+            })?;
+        let location = format!("/api/v1/influencers/{}", renamed.id);
+        return Ok((
+            StatusCode::MOVED_PERMANENTLY,
+            [
+                (header::LOCATION, location),
+                (header::CACHE_CONTROL, "public, max-age=300".to_string()),
+            ],
+        )
+            .into_response());
+    };
+
+    // The owner also sees the starter video prompt, so their copy must not be
+    // served from a shared cache
+    let is_owner =
+        user.is_some_and(|u| influencer.parent_principal_id.as_deref() == Some(&u.user_id));
+    if is_owner {
+        let starter_video_prompt = influencer.starter_video_prompt();
+        let mut response = InfluencerResponse::from(influencer);
+        response.starter_video_prompt = starter_video_prompt;
+        return Ok((
+            [(header::CACHE_CONTROL, "private, no-cache")],
+            Json(response),
+        )
+            .into_response());
+    }
 
     Ok((
         [(header::CACHE_CONTROL, "public, max-age=300")],
         Json(InfluencerResponse::from(influencer)),
-    ))
+    )
+        .into_response())
+}
+
+/// Record a profile impression. Counts are buffered and written in batches, so
+/// `view_count` catches up within a flush interval. A user counts once per
+/// profile an hour; repeats are accepted and ignored
+#[utoipa::path(
+    post,
+    path = "/api/v1/influencers/{influencer_id}/impression",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    responses(
+        (status = 202, description = "Impression recorded"),
+        (status = 401, body = ErrorBody, description = "Unauthorized")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn record_impression(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+) -> StatusCode {
+    state.impressions.record(&user.user_id, &influencer_id);
+    StatusCode::ACCEPTED
 }
 
 /// Generate a system prompt from a user description
@@ -235,11 +392,12 @@ pub async fn get_influencer(
 pub async fn generate_prompt(
     State(state): State<Arc<AppState>>,
     _user: AuthenticatedUser,
-    Json(body): Json<GeneratePromptRequest>,
+    ValidatedJson(body): ValidatedJson<GeneratePromptRequest>,
 ) -> Result<Json<SystemPromptResponse>, AppError> {
-    let instructions =
-        CharacterGeneratorService::generate_system_instructions(&state.gemini, &body.prompt)
-            .await?;
+    let instructions = state
+        .character_generator
+        .generate_system_instructions(state.gemini.as_ref(), &body.prompt, body.bypass_cache)
+        .await?;
 
     Ok(Json(SystemPromptResponse {
         system_instructions: instructions,
@@ -254,7 +412,8 @@ pub async fn generate_prompt(
     responses(
         (status = 200, body = GeneratedMetadataResponse, description = "Successful response"),
         (status = 401, body = ErrorBody, description = "Unauthorized"),
-        (status = 422, body = ErrorBody, description = "Validation error")
+        (status = 422, body = ErrorBody, description = "Validation error"),
+        (status = 503, body = ErrorBody, description = "AI unavailable or kept returning malformed metadata")
     ),
     tag = "Influencers",
     security(("BearerAuth" = []))
@@ -262,14 +421,17 @@ pub async fn generate_prompt(
 pub async fn validate_and_generate_metadata(
     State(state): State<Arc<AppState>>,
     _user: AuthenticatedUser,
-    Json(body): Json<ValidateMetadataRequest>,
+    ValidatedJson(body): ValidatedJson<ValidateMetadataRequest>,
 ) -> Result<Json<GeneratedMetadataResponse>, AppError> {
-    let result = CharacterGeneratorService::validate_and_generate_metadata(
-        &state.gemini,
-        &state.replicate,
-        &body.system_instructions,
-    )
-    .await?;
+    let result = state
+        .character_generator
+        .validate_and_generate_metadata(
+            state.gemini.as_ref(),
+            state.replicate.as_ref(),
+            &body.system_instructions,
+            body.bypass_cache,
+        )
+        .await?;
 
     Ok(Json(result))
 }
@@ -282,7 +444,7 @@ pub async fn validate_and_generate_metadata(
     responses(
         (status = 200, body = InfluencerResponse, description = "Successful response"),
         (status = 401, body = ErrorBody, description = "Unauthorized"),
-        (status = 409, body = ErrorBody, description = "Conflict"),
+        (status = 409, body = ErrorBody, description = "Name taken, or the caller already has a similar influencer (listed in `duplicates`; pass `force` to create anyway)"),
         (status = 422, body = ErrorBody, description = "Validation error")
     ),
     tag = "Influencers",
@@ -290,16 +452,22 @@ pub async fn validate_and_generate_metadata(
 )]
 pub async fn create_influencer(
     State(state): State<Arc<AppState>>,
+    tenant: Tenant,
     user: AuthenticatedUser,
-    Json(body): Json<CreateInfluencerRequest>,
+    request_id: RequestId,
+    ValidatedJson(body): ValidatedJson<CreateInfluencerRequest>,
 ) -> Result<Json<InfluencerResponse>, AppError> {
-    // Validate request body
-    body.validate()
-        .map_err(|e| AppError::validation_error(format!("{e}")))?;
-
     let repo = state.db.inf_repo();
 
-    // Check name uniqueness
+    if state
+        .settings
+        .load()
+        .is_reserved_influencer_name(&body.name)
+    {
+        return Err(AppError::field_error("name", "This name is reserved"));
+    }
+
+    // Check name uniqueness, former names included
     if let Some(_existing) = repo.get_by_name(&body.name).await? {
         return Err(AppError::conflict(format!(
             "Influencer name '{}' already exists",
@@ -307,39 +475,60 @@ pub async fn create_influencer(
         )));
     }
 
+    // Catch a bot the caller already made, e.g. from a double submit or a retried form
+    let duplicates = duplicate_influencers::find_duplicates(
+        &state,
+        &user.user_id,
+        tenant.as_str(),
+        &body.name,
+        &body.display_name,
+        &body.system_instructions,
+    )
+    .await?;
+    if !duplicates.is_empty() && !body.force {
+        return Err(AppError::duplicate_influencer(
+            "This looks like an influencer you already have. Pass force=true to create it anyway",
+            duplicates,
+        ));
+    }
+
     // Append moderation guardrails
     let system_instructions = moderation::with_guardrails(&body.system_instructions);
 
-    // Generate greeting/suggestions if either is missing (matches Python behavior)
-    let mut initial_greeting = body.initial_greeting.clone();
-    let mut suggested_messages = body.suggested_messages.clone();
-
-    if initial_greeting.is_none() || suggested_messages.is_empty() {
-        match CharacterGeneratorService::generate_initial_greeting(
-            &state.gemini,
-            &body.display_name,
-            &body.system_instructions,
-        )
-        .await
-        {
-            Ok((gen_greeting, gen_suggestions)) => {
-                if initial_greeting.is_none() {
-                    initial_greeting = Some(gen_greeting);
-                }
-                if suggested_messages.is_empty() {
-                    suggested_messages = gen_suggestions;
-                }
-            }
-            Err(e) => {
-                tracing::error!(error = %e, "Failed to generate greeting");
-                if initial_greeting.is_none() {
-                    initial_greeting = Some(format!(
-                        "Hey! I'm {}! How can I help you today?",
-                        body.display_name
-                    ));
-                }
-            }
-        }
+    // Avatar, greeting and starter video prompt are generated in the background;
+    // clients poll /generation-status until it completes
+    let tenant_settings = state.tenants.settings(tenant.as_str());
+    let mut steps = Vec::new();
+    let mut metadata = serde_json::Map::new();
+    let mut avatar_url = body.avatar_url;
+    if avatar_url.is_none() && state.replicate.is_configured() {
+        steps.push(STEP_AVATAR);
+    }
+    // A supplied avatar stays out of the public list until it passes the check
+    if tenant_settings.avatar_moderation_enabled
+        && let Some(url) = avatar_url.take()
+    {
+        steps.push(STEP_AVATAR_CHECK);
+        metadata.insert("pending_avatar_url".into(), url.into());
+    }
+    if body.initial_greeting.is_none() || body.suggested_messages.is_empty() {
+        steps.push(STEP_GREETING);
+    }
+    steps.push(STEP_STARTER_VIDEO_PROMPT);
+    if tenant_settings.starter_video_enabled && state.replicate.is_configured() {
+        steps.push(STEP_STARTER_VIDEO);
+    }
+    let generation = influencer_enrichment::pending(&steps);
+    metadata.insert(
+        "generation".into(),
+        serde_json::to_value(&generation).unwrap_or_default(),
+    );
+    // Link a forced duplicate to the influencers it resembles
+    if !duplicates.is_empty() {
+        metadata.insert(
+            "duplicate_of".into(),
+            duplicates.iter().map(|d| d.influencer_id.clone()).collect(),
+        );
     }
 
     // Always use the authenticated user's ID (security: prevent override)
@@ -350,45 +539,168 @@ pub async fn create_influencer(
         id: body.bot_principal_id.clone(),
         name: body.name,
         display_name: body.display_name.clone(),
-        avatar_url: body.avatar_url,
+        avatar_url,
         description: body.description,
         category: body.category,
         system_instructions,
         personality_traits: body.personality_traits,
-        initial_greeting,
-        suggested_messages,
+        initial_greeting: body.initial_greeting,
+        suggested_messages: body.suggested_messages,
         is_active: InfluencerStatus::Active,
         is_nsfw: false, // enforced
+        is_verified: false,
+        is_official: false,
         parent_principal_id: Some(parent_principal_id),
         source: Some("user-created-influencer".to_string()),
+        tenant: tenant.0.to_string(),
         created_at: now,
         updated_at: now,
-        metadata: serde_json::json!({}),
+        metadata: metadata.into(),
         conversation_count: None,
         message_count: None,
+        view_count: 0,
     };
 
-    repo.create(&influencer).await?;
-
-    // Generate starter video prompt in parallel (best-effort)
-    let starter_video_prompt = match CharacterGeneratorService::generate_starter_video_prompt(
-        &state.gemini,
-        &body.display_name,
-        &body.system_instructions,
+    repo.create(&influencer)
+        .await
+        .map_err(|e| name_conflict(e, &influencer.name))?;
+    audit::record(
+        &state.db,
+        &user.user_id,
+        &request_id,
+        AuditEvent {
+            after: audit::snapshot(&influencer),
+            ..AuditEvent::new(AuditAction::InfluencerCreated, "influencer", &influencer.id)
+        },
     )
-    .await
-    {
-        Ok(prompt) => Some(prompt),
-        Err(e) => {
-            tracing::error!(error = %e, "Failed to generate starter video prompt");
-            None
-        }
+    .await;
+    influencer_enrichment::spawn_enrichment(state.clone(), influencer.clone());
+
+    Ok(Json(InfluencerResponse::from(influencer)))
+}
+
+/// Progress of the avatar/greeting/video-prompt generation started by create
+#[utoipa::path(
+    get,
+    path = "/api/v1/influencers/{influencer_id}/generation-status",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    responses(
+        (status = 200, body = GenerationStatusResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Not found")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn get_generation_status(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+) -> Result<Json<GenerationStatusResponse>, AppError> {
+    let influencer = state
+        .db
+        .inf_repo()
+        .get_by_id(&influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+
+    if influencer.parent_principal_id.as_deref() != Some(&user.user_id) {
+        return Err(AppError::forbidden(
+            "Only the bot owner can view generation status",
+        ));
+    }
+
+    Ok(Json(generation_status(&state, influencer).await))
+}
+
+async fn generation_status(state: &AppState, influencer: AIInfluencer) -> GenerationStatusResponse {
+    // Influencers created before background generation have nothing pending
+    let generation = influencer.generation();
+    let starter_video_prompt = influencer.starter_video_prompt();
+    let avatar_moderation = influencer.avatar_moderation();
+    let starter_video_url = match influencer.starter_video_key() {
+        Some(key) => Some(state.storage.generate_presigned_url(key).await),
+        None => None,
     };
+    GenerationStatusResponse {
+        influencer_id: influencer.id,
+        status: generation
+            .as_ref()
+            .map_or(GenerationStatus::Completed, |g| g.status),
+        steps: generation
+            .as_ref()
+            .map(|g| g.steps.clone())
+            .unwrap_or_default(),
+        avatar_url: influencer.avatar_url,
+        initial_greeting: influencer.initial_greeting,
+        suggested_messages: influencer.suggested_messages,
+        starter_video_prompt,
+        starter_video_url,
+        avatar_moderation,
+    }
+}
 
-    let mut resp = InfluencerResponse::from(influencer);
-    resp.starter_video_prompt = starter_video_prompt;
+/// Render the starter video in the background (owner only); poll
+/// `/generation-status` for progress
+#[utoipa::path(
+    post,
+    path = "/api/v1/influencers/{influencer_id}/starter-video",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    responses(
+        (status = 202, body = GenerationStatusResponse, description = "Rendering started"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Not found"),
+        (status = 409, body = ErrorBody, description = "Generation already in progress"),
+        (status = 503, body = ErrorBody, description = "Video generation is not configured")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn render_starter_video(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+) -> Result<(StatusCode, Json<GenerationStatusResponse>), AppError> {
+    let repo = state.db.inf_repo();
+    let mut influencer =
+        get_influencer_as_owner(&repo, &user, &influencer_id, "render the starter video").await?;
+
+    if !state.replicate.is_configured() {
+        return Err(AppError::service_unavailable(
+            "Video generation is not configured",
+        ));
+    }
+    if influencer.generation().is_some_and(|g| {
+        matches!(
+            g.status,
+            GenerationStatus::Pending | GenerationStatus::Running
+        )
+    }) {
+        return Err(AppError::conflict("Generation is already in progress"));
+    }
+
+    let mut steps = Vec::new();
+    if influencer.starter_video_prompt().is_none() {
+        steps.push(STEP_STARTER_VIDEO_PROMPT);
+    }
+    steps.push(STEP_STARTER_VIDEO);
+    let generation =
+        serde_json::to_value(influencer_enrichment::pending(&steps)).unwrap_or_default();
+    repo.set_metadata_key(&influencer.id, "generation", &generation)
+        .await?;
+    state.influencer_cache.invalidate(&influencer.id);
+
+    if let Some(metadata) = influencer.metadata.as_object_mut() {
+        metadata.insert("generation".into(), generation);
+    }
+    influencer_enrichment::spawn_enrichment(state.clone(), influencer.clone());
 
-    Ok(Json(resp))
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(generation_status(&state, influencer).await),
+    ))
 }
 
 /// Update an influencer's system prompt
@@ -410,8 +722,9 @@ pub async fn create_influencer(
 pub async fn update_system_prompt(
     State(state): State<Arc<AppState>>,
     user: AuthenticatedUser,
+    request_id: RequestId,
     Path(influencer_id): Path<String>,
-    Json(body): Json<UpdateSystemPromptRequest>,
+    ValidatedJson(body): ValidatedJson<UpdateSystemPromptRequest>,
 ) -> Result<Json<InfluencerResponse>, AppError> {
     let repo = state.db.inf_repo();
 
@@ -430,15 +743,759 @@ pub async fn update_system_prompt(
     let instructions = moderation::with_guardrails(&body.system_instructions);
     repo.update_system_prompt(&influencer_id, &instructions)
         .await?;
+    state.influencer_cache.invalidate(&influencer_id);
+
+    let updated = repo
+        .get_by_id(&influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+
+    audit::record(
+        &state.db,
+        &user.user_id,
+        &request_id,
+        AuditEvent {
+            before: Some(
+                serde_json::json!({ "system_instructions": influencer.system_instructions }),
+            ),
+            after: Some(serde_json::json!({ "system_instructions": updated.system_instructions })),
+            ..AuditEvent::new(
+                AuditAction::SystemPromptUpdated,
+                "influencer",
+                &influencer_id,
+            )
+        },
+    )
+    .await;
+
+    Ok(Json(InfluencerResponse::from(updated)))
+}
+
+/// 409 for a write that lost a race for `name` to the unique constraint.
+fn name_conflict(err: sqlx::Error, name: &str) -> AppError {
+    if err
+        .as_database_error()
+        .is_some_and(|e| e.is_unique_violation())
+    {
+        return AppError::conflict(format!("Influencer name '{name}' already exists"));
+    }
+    err.into()
+}
+
+/// Rename an influencer (owner only)
+///
+/// The old name keeps resolving to the influencer and stays reserved for it, so
+/// links and mentions using it still work and it can be taken back later.
+#[utoipa::path(
+    patch,
+    path = "/api/v1/influencers/{influencer_id}/name",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    request_body = RenameInfluencerRequest,
+    responses(
+        (status = 200, body = InfluencerResponse, description = "Influencer renamed"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Not found"),
+        (status = 409, body = ErrorBody, description = "Name taken, now or before a rename"),
+        (status = 422, body = ErrorBody, description = "Invalid or reserved name")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn rename_influencer(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    request_id: RequestId,
+    Path(influencer_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<RenameInfluencerRequest>,
+) -> Result<Json<InfluencerResponse>, AppError> {
+    let repo = state.db.inf_repo();
+    let influencer = get_influencer_as_owner(&repo, &user, &influencer_id, "rename it").await?;
+    if body.name == influencer.name {
+        return Ok(Json(InfluencerResponse::from(influencer)));
+    }
+    if state
+        .settings
+        .load()
+        .is_reserved_influencer_name(&body.name)
+    {
+        return Err(AppError::field_error("name", "This name is reserved"));
+    }
+    if repo
+        .get_by_name(&body.name)
+        .await?
+        .is_some_and(|holder| holder.id != influencer.id)
+    {
+        return Err(AppError::conflict(format!(
+            "Influencer name '{}' already exists",
+            body.name
+        )));
+    }
+
+    // A concurrent rename or create can take the name after the check above
+    repo.rename(&influencer.id, &influencer.name, &body.name, &user.user_id)
+        .await
+        .map_err(|e| name_conflict(e, &body.name))?;
+    state.influencer_cache.invalidate(&influencer.id);
+
+    let updated = repo
+        .get_by_id(&influencer.id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+    audit::record(
+        &state.db,
+        &user.user_id,
+        &request_id,
+        AuditEvent {
+            before: Some(serde_json::json!({ "name": influencer.name })),
+            after: Some(serde_json::json!({ "name": updated.name })),
+            ..AuditEvent::new(AuditAction::InfluencerRenamed, "influencer", &influencer.id)
+        },
+    )
+    .await;
+
+    Ok(Json(InfluencerResponse::from(updated)))
+}
+
+/// Replace the live system prompt with the saved draft (owner only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/influencers/{influencer_id}/publish-draft",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    responses(
+        (status = 200, body = InfluencerResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Not found"),
+        (status = 409, body = ErrorBody, description = "No draft system prompt saved")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn publish_draft(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    request_id: RequestId,
+    Path(influencer_id): Path<String>,
+) -> Result<Json<InfluencerResponse>, AppError> {
+    let repo = state.db.inf_repo();
+    let influencer =
+        get_influencer_as_owner(&repo, &user, &influencer_id, "publish a draft prompt").await?;
+
+    let draft = repo
+        .get_draft_system_prompt(&influencer_id)
+        .await?
+        .ok_or_else(|| AppError::conflict("No draft system prompt saved"))?;
+    let instructions = moderation::with_guardrails(&draft);
+    repo.publish_draft_system_prompt(&influencer_id, &instructions)
+        .await?;
+    state.influencer_cache.invalidate(&influencer_id);
 
     let updated = repo
         .get_by_id(&influencer_id)
         .await?
         .ok_or_else(|| AppError::not_found("Influencer not found"))?;
 
+    audit::record(
+        &state.db,
+        &user.user_id,
+        &request_id,
+        AuditEvent {
+            before: Some(
+                serde_json::json!({ "system_instructions": influencer.system_instructions }),
+            ),
+            after: Some(serde_json::json!({ "system_instructions": updated.system_instructions })),
+            ..AuditEvent::new(
+                AuditAction::SystemPromptUpdated,
+                "influencer",
+                &influencer_id,
+            )
+        },
+    )
+    .await;
+
     Ok(Json(InfluencerResponse::from(updated)))
 }
 
+/// Generate a fresh greeting and suggested messages (owner only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/influencers/{influencer_id}/regenerate-greeting",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    request_body = RegenerateGreetingRequest,
+    responses(
+        (status = 200, body = RegenerateGreetingResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Not found"),
+        (status = 503, body = ErrorBody, description = "AI unavailable")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn regenerate_greeting(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<RegenerateGreetingRequest>,
+) -> Result<Json<RegenerateGreetingResponse>, AppError> {
+    let repo = state.db.inf_repo();
+    let influencer =
+        get_influencer_as_owner(&repo, &user, &influencer_id, "regenerate the greeting").await?;
+
+    let instructions = moderation::strip_guardrails(&influencer.system_instructions);
+    let (greeting, suggested_messages) = CharacterGeneratorService::generate_initial_greeting(
+        state.gemini.as_ref(),
+        &influencer.display_name,
+        &instructions,
+    )
+    .await?;
+
+    repo.apply_enrichment(
+        &influencer.id,
+        None,
+        Some(&greeting),
+        Some(&suggested_messages),
+    )
+    .await?;
+    state.influencer_cache.invalidate(&influencer.id);
+
+    // Conversations store their own copy of the greeting, so new ones pick up the
+    // change on their own
+    let conversations_updated = if body.new_conversations_only {
+        0
+    } else {
+        let (text, _) = sanitize_assistant_text(&state, &greeting);
+        state
+            .db
+            .msg_repo()
+            .replace_unanswered_greetings(&influencer.id, &text)
+            .await?
+    };
+
+    Ok(Json(RegenerateGreetingResponse {
+        influencer_id: influencer.id,
+        initial_greeting: greeting,
+        suggested_messages,
+        conversations_updated,
+    }))
+}
+
+fn schedule_to_response(influencer: &AIInfluencer) -> ScheduleResponse {
+    let schedule = influencer.schedule();
+    let is_online = influencer.is_online();
+    let next_online_at = schedule
+        .as_ref()
+        .filter(|_| !is_online && influencer.is_active == InfluencerStatus::Active)
+        .and_then(|s| s.next_online_at(chrono::Utc::now()))
+        .map(|t| t.naive_utc());
+    ScheduleResponse {
+        influencer_id: influencer.id.clone(),
+        schedule,
+        is_online,
+        next_online_at,
+    }
+}
+
+/// Get an influencer's availability schedule and whether it is online now
+#[utoipa::path(
+    get,
+    path = "/api/v1/influencers/{influencer_id}/schedule",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    responses(
+        (status = 200, body = ScheduleResponse, description = "Successful response"),
+        (status = 404, body = ErrorBody, description = "Not found")
+    ),
+    tag = "Influencers"
+)]
+pub async fn get_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(influencer_id): Path<String>,
+) -> Result<Json<ScheduleResponse>, AppError> {
+    let influencer = state
+        .influencer_cache
+        .get_by_id(&state.db.inf_repo(), &influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+    Ok(Json(schedule_to_response(&influencer)))
+}
+
+/// Set the weekly online hours of an influencer (owner only)
+#[utoipa::path(
+    put,
+    path = "/api/v1/influencers/{influencer_id}/schedule",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    request_body = UpdateScheduleRequest,
+    responses(
+        (status = 200, body = ScheduleResponse, description = "Schedule saved"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Not found"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn update_schedule(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    request_id: RequestId,
+    Path(influencer_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<UpdateScheduleRequest>,
+) -> Result<Json<ScheduleResponse>, AppError> {
+    let repo = state.db.inf_repo();
+    let influencer =
+        get_influencer_as_owner(&repo, &user, &influencer_id, "change the schedule").await?;
+
+    if body.timezone.parse::<chrono_tz::Tz>().is_err() {
+        return Err(AppError::field_error(
+            "timezone",
+            "Unknown timezone; use an IANA name like \"Asia/Kolkata\"",
+        ));
+    }
+    for (i, window) in body.windows.iter().enumerate() {
+        let start = chrono::NaiveTime::parse_from_str(&window.start, "%H:%M");
+        let end = chrono::NaiveTime::parse_from_str(&window.end, "%H:%M");
+        let field = format!("windows[{i}]");
+        match (start, end) {
+            (Ok(start), Ok(end)) if start != end => {}
+            (Ok(_), Ok(_)) => {
+                return Err(AppError::field_error(&field, "start and end must differ"));
+            }
+            _ => return Err(AppError::field_error(&field, "times must be HH:MM")),
+        }
+        if window.days.is_empty() {
+            return Err(AppError::field_error(
+                &field,
+                "at least one day is required",
+            ));
+        }
+    }
+
+    let schedule = AvailabilitySchedule {
+        timezone: body.timezone,
+        windows: body.windows,
+        away_mode: body.away_mode,
+        away_message: body
+            .away_message
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty()),
+    };
+    let value = serde_json::to_value(&schedule).unwrap_or_default();
+    repo.set_metadata_key(&influencer.id, "schedule", &value)
+        .await?;
+    state.influencer_cache.invalidate(&influencer.id);
+
+    let updated = repo
+        .get_by_id(&influencer.id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+    audit::record(
+        &state.db,
+        &user.user_id,
+        &request_id,
+        AuditEvent {
+            before: Some(serde_json::json!({ "schedule": influencer.metadata.get("schedule") })),
+            after: Some(serde_json::json!({ "schedule": value })),
+            ..AuditEvent::new(AuditAction::InfluencerUpdated, "influencer", &influencer.id)
+        },
+    )
+    .await;
+    Ok(Json(schedule_to_response(&updated)))
+}
+
+/// Remove an influencer's schedule so it is online whenever active (owner only)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/influencers/{influencer_id}/schedule",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    responses(
+        (status = 200, body = ScheduleResponse, description = "Schedule removed"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Not found")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn delete_schedule(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    request_id: RequestId,
+    Path(influencer_id): Path<String>,
+) -> Result<Json<ScheduleResponse>, AppError> {
+    let repo = state.db.inf_repo();
+    let mut influencer =
+        get_influencer_as_owner(&repo, &user, &influencer_id, "change the schedule").await?;
+
+    repo.remove_metadata_key(&influencer.id, "schedule").await?;
+    state.influencer_cache.invalidate(&influencer.id);
+
+    let removed = influencer
+        .metadata
+        .as_object_mut()
+        .and_then(|metadata| metadata.remove("schedule"));
+    audit::record(
+        &state.db,
+        &user.user_id,
+        &request_id,
+        AuditEvent {
+            before: Some(serde_json::json!({ "schedule": removed })),
+            after: Some(serde_json::json!({ "schedule": null })),
+            ..AuditEvent::new(AuditAction::InfluencerUpdated, "influencer", &influencer.id)
+        },
+    )
+    .await;
+    Ok(Json(schedule_to_response(&influencer)))
+}
+
+/// Set what the influencer is asked when a user sends only media (owner only)
+#[utoipa::path(
+    put,
+    path = "/api/v1/influencers/{influencer_id}/media-only-prompt",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    request_body = UpdateMediaOnlyPromptRequest,
+    responses(
+        (status = 200, body = MediaOnlyPromptResponse, description = "Prompt saved"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Not found"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn update_media_only_prompt(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    request_id: RequestId,
+    Path(influencer_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<UpdateMediaOnlyPromptRequest>,
+) -> Result<Json<MediaOnlyPromptResponse>, AppError> {
+    let repo = state.db.inf_repo();
+    let influencer =
+        get_influencer_as_owner(&repo, &user, &influencer_id, "change the media-only prompt")
+            .await?;
+
+    let prompt = body
+        .media_only_prompt
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+    match &prompt {
+        Some(prompt) => {
+            repo.set_metadata_key(&influencer.id, "media_only_prompt", &prompt.as_str().into())
+                .await?
+        }
+        None => {
+            repo.remove_metadata_key(&influencer.id, "media_only_prompt")
+                .await?
+        }
+    }
+    state.influencer_cache.invalidate(&influencer.id);
+
+    audit::record(
+        &state.db,
+        &user.user_id,
+        &request_id,
+        AuditEvent {
+            before: Some(
+                serde_json::json!({ "media_only_prompt": influencer.media_only_prompt() }),
+            ),
+            after: Some(serde_json::json!({ "media_only_prompt": prompt })),
+            ..AuditEvent::new(AuditAction::InfluencerUpdated, "influencer", &influencer.id)
+        },
+    )
+    .await;
+    Ok(Json(MediaOnlyPromptResponse {
+        influencer_id: influencer.id,
+        media_only_prompt: prompt,
+        default_prompt: state
+            .tenants
+            .settings(&influencer.tenant)
+            .media_only_prompt
+            .clone(),
+    }))
+}
+
+/// Longest conversation starter text.
+const MAX_STARTER_CHARS: usize = 200;
+
+fn starters_to_response(influencer: &AIInfluencer) -> ConversationStartersResponse {
+    ConversationStartersResponse {
+        influencer_id: influencer.id.clone(),
+        starters: influencer.conversation_starters(),
+        suggested_messages: influencer.suggested_messages.clone(),
+    }
+}
+
+/// Get an influencer's suggested messages, with the time-of-day and language tags of its starters
+#[utoipa::path(
+    get,
+    path = "/api/v1/influencers/{influencer_id}/conversation-starters",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    responses(
+        (status = 200, body = ConversationStartersResponse, description = "Successful response"),
+        (status = 404, body = ErrorBody, description = "Not found")
+    ),
+    tag = "Influencers"
+)]
+pub async fn get_conversation_starters(
+    State(state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Path(influencer_id): Path<String>,
+) -> Result<Json<ConversationStartersResponse>, AppError> {
+    let influencer = state
+        .influencer_cache
+        .get_by_id(&state.db.inf_repo(), &influencer_id)
+        .await?
+        .filter(|i| i.tenant == tenant.as_str())
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+    Ok(Json(starters_to_response(&influencer)))
+}
+
+/// Replace an influencer's tagged conversation starters (owner only)
+///
+/// Users are shown a rotating few of the starters matching their language and
+/// local time of day, together with the untagged suggested messages.
+#[utoipa::path(
+    put,
+    path = "/api/v1/influencers/{influencer_id}/conversation-starters",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    request_body = UpdateConversationStartersRequest,
+    responses(
+        (status = 200, body = ConversationStartersResponse, description = "Starters saved"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Not found"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn update_conversation_starters(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    request_id: RequestId,
+    Path(influencer_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<UpdateConversationStartersRequest>,
+) -> Result<Json<ConversationStartersResponse>, AppError> {
+    let repo = state.db.inf_repo();
+    let influencer = get_influencer_as_owner(
+        &repo,
+        &user,
+        &influencer_id,
+        "change the conversation starters",
+    )
+    .await?;
+
+    let mut starters = Vec::with_capacity(body.starters.len());
+    for (i, starter) in body.starters.into_iter().enumerate() {
+        let field = format!("starters[{i}]");
+        let text = starter.text.trim().to_string();
+        if text.is_empty() || text.chars().count() > MAX_STARTER_CHARS {
+            return Err(AppError::field_error(
+                &field,
+                format!("text must be 1 to {MAX_STARTER_CHARS} characters"),
+            ));
+        }
+        let mut languages = Vec::with_capacity(starter.languages.len());
+        for language in &starter.languages {
+            let tag = conversation_starters::language_tag(language).ok_or_else(|| {
+                AppError::field_error(
+                    &field,
+                    format!(
+                        "\"{language}\" is not a language code; use 2 or 3 letters like \"en\" or \"hi\", optionally with a region"
+                    ),
+                )
+            })?;
+            if !languages.contains(&tag) {
+                languages.push(tag);
+            }
+        }
+        let mut day_parts = starter.day_parts;
+        day_parts.sort_unstable();
+        day_parts.dedup();
+        starters.push(ConversationStarter {
+            text,
+            day_parts,
+            languages,
+        });
+    }
+
+    let value = serde_json::to_value(&starters).unwrap_or_default();
+    if starters.is_empty() {
+        repo.remove_metadata_key(&influencer.id, "conversation_starters")
+            .await?;
+    } else {
+        repo.set_metadata_key(&influencer.id, "conversation_starters", &value)
+            .await?;
+    }
+    state.influencer_cache.invalidate(&influencer.id);
+
+    audit::record(
+        &state.db,
+        &user.user_id,
+        &request_id,
+        AuditEvent {
+            before: Some(serde_json::json!({
+                "conversation_starters": influencer.metadata.get("conversation_starters")
+            })),
+            after: Some(serde_json::json!({ "conversation_starters": value })),
+            ..AuditEvent::new(AuditAction::InfluencerUpdated, "influencer", &influencer.id)
+        },
+    )
+    .await;
+    Ok(Json(ConversationStartersResponse {
+        influencer_id: influencer.id,
+        starters,
+        suggested_messages: influencer.suggested_messages,
+    }))
+}
+
+/// Click-through of an influencer's suggested messages (owner only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/influencers/{influencer_id}/suggestions/stats",
+    params(
+        ("influencer_id" = String, Path, description = "Influencer ID"),
+        SuggestionStatsParams
+    ),
+    responses(
+        (status = 200, body = SuggestionStatsResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Not found")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn get_suggestion_stats(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+    Query(params): Query<SuggestionStatsParams>,
+) -> Result<Json<SuggestionStatsResponse>, AppError> {
+    let influencer = get_influencer_as_owner(
+        &state.db.inf_repo(),
+        &user,
+        &influencer_id,
+        "view suggestion stats",
+    )
+    .await?;
+
+    let days = params.days();
+    let repo = state.db.suggestion_repo();
+    let (conversations_started, conversations_with_tap) =
+        repo.conversation_counts(&influencer.id, days).await?;
+    let mut taps: HashMap<String, i64> = repo
+        .tap_counts(&influencer.id, days)
+        .await?
+        .into_iter()
+        .collect();
+
+    let rate = |n: i64| {
+        if conversations_started > 0 {
+            n as f64 / conversations_started as f64
+        } else {
+            0.0
+        }
+    };
+    let starters = influencer.conversation_starters();
+    let suggestions = influencer
+        .suggested_messages
+        .iter()
+        .chain(starters.iter().map(|s| &s.text))
+        .map(|text| {
+            let taps = taps.remove(text.trim()).unwrap_or(0);
+            SuggestionStat {
+                text: text.clone(),
+                taps,
+                click_through_rate: rate(taps),
+            }
+        })
+        .collect();
+
+    Ok(Json(SuggestionStatsResponse {
+        influencer_id: influencer.id.clone(),
+        days,
+        conversations_started,
+        conversations_with_tap,
+        click_through_rate: rate(conversations_with_tap),
+        suggestions,
+        other_taps: taps.values().sum(),
+        last_rotated_at: influencer.suggestions_rotated_at(),
+    }))
+}
+
+/// Generate a new starter video prompt and keep it on the influencer (owner only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/influencers/{influencer_id}/regenerate-video-prompt",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    responses(
+        (status = 200, body = StarterVideoPromptResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Not found"),
+        (status = 503, body = ErrorBody, description = "AI unavailable")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn regenerate_video_prompt(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+) -> Result<Json<StarterVideoPromptResponse>, AppError> {
+    let repo = state.db.inf_repo();
+    let influencer = get_influencer_as_owner(
+        &repo,
+        &user,
+        &influencer_id,
+        "regenerate the starter video prompt",
+    )
+    .await?;
+
+    let instructions = moderation::strip_guardrails(&influencer.system_instructions);
+    let prompt = CharacterGeneratorService::generate_starter_video_prompt(
+        state.gemini.as_ref(),
+        &influencer.display_name,
+        &instructions,
+    )
+    .await?;
+
+    repo.set_metadata_key(
+        &influencer.id,
+        "starter_video_prompt",
+        &serde_json::json!(prompt),
+    )
+    .await?;
+    state.influencer_cache.invalidate(&influencer.id);
+
+    Ok(Json(StarterVideoPromptResponse {
+        influencer_id: influencer.id,
+        starter_video_prompt: prompt,
+    }))
+}
+
+pub(super) async fn get_influencer_as_owner(
+    repo: &InfluencerRepository,
+    user: &AuthenticatedUser,
+    influencer_id: &str,
+    action: &str,
+) -> Result<AIInfluencer, AppError> {
+    let influencer = repo
+        .get_by_id(influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+    if influencer.parent_principal_id.as_deref() != Some(&user.user_id) {
+        return Err(AppError::forbidden(format!(
+            "Only the bot owner can {action}"
+        )));
+    }
+    Ok(influencer)
+}
+
 /// Generate a video prompt for subsequent bot videos
 /// This endpoint creates an LTX-optimized video prompt with full context from the bot's system instructions
 #[utoipa::path(
@@ -459,12 +1516,8 @@ pub async fn generate_video_prompt(
     State(state): State<Arc<AppState>>,
     _user: AuthenticatedUser,
     Path(influencer_id): Path<String>,
-    Json(body): Json<GenerateVideoPromptRequest>,
+    ValidatedJson(body): ValidatedJson<GenerateVideoPromptRequest>,
 ) -> Result<Json<VideoPromptResponse>, AppError> {
-    // Validate request body
-    body.validate()
-        .map_err(|e| AppError::validation_error(format!("{e}")))?;
-
     let repo = state.db.inf_repo();
 
     // Try to get the influencer - if not found, try to fetch profile from canister for main accounts
@@ -473,7 +1526,7 @@ pub async fn generate_video_prompt(
             // Bot context available - generate prompt with bot's system instructions
             let system_instructions = moderation::strip_guardrails(&influencer.system_instructions);
             CharacterGeneratorService::generate_subsequent_video_prompt(
-                &state.gemini,
+                state.gemini.as_ref(),
                 &influencer.display_name,
                 &system_instructions,
                 &body.scene_description,
@@ -484,11 +1537,12 @@ pub async fn generate_video_prompt(
         }
         None => {
             // Not a bot - fetch profile info for main user account
+            let settings = state.settings.load_full();
             let (avatar_url, username) = tokio::join!(
                 fetch_user_profile_pic(&state.ic_agent, &influencer_id),
                 fetch_username_from_metadata(
                     &state.http_client,
-                    &state.settings.metadata_url,
+                    &settings.metadata_url,
                     &influencer_id
                 )
             );
@@ -500,7 +1554,7 @@ pub async fn generate_video_prompt(
 
             // Generate base prompt with avatar (if found) since main accounts don't have system instructions
             CharacterGeneratorService::generate_base_video_prompt(
-                &state.gemini,
+                state.gemini.as_ref(),
                 &display_name,
                 &body.scene_description,
                 avatar_url.as_deref(),
@@ -533,6 +1587,7 @@ pub async fn generate_video_prompt(
 pub async fn delete_influencer(
     State(state): State<Arc<AppState>>,
     user: AuthenticatedUser,
+    request_id: RequestId,
     Path(influencer_id): Path<String>,
 ) -> Result<Json<InfluencerResponse>, AppError> {
     let repo = state.db.inf_repo();
@@ -550,12 +1605,31 @@ pub async fn delete_influencer(
     }
 
     repo.soft_delete(&influencer_id).await?;
+    state.influencer_cache.invalidate(&influencer_id);
+    state
+        .db
+        .knowledge_repo()
+        .delete_by_influencer(&influencer_id)
+        .await?;
+    state.knowledge_index.invalidate(&influencer_id);
 
     let updated = repo
         .get_by_id(&influencer_id)
         .await?
         .ok_or_else(|| AppError::not_found("Influencer not found"))?;
 
+    audit::record(
+        &state.db,
+        &user.user_id,
+        &request_id,
+        AuditEvent {
+            before: audit::snapshot(&influencer),
+            after: audit::snapshot(&updated),
+            ..AuditEvent::new(AuditAction::InfluencerDeleted, "influencer", &influencer_id)
+        },
+    )
+    .await;
+
     Ok(Json(InfluencerResponse::from(updated)))
 }
 
@@ -574,20 +1648,10 @@ pub async fn delete_influencer(
 pub async fn admin_ban_influencer(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
+    request_id: RequestId,
     Path(influencer_id): Path<String>,
 ) -> Result<Json<InfluencerResponse>, AppError> {
-    let provided_key = headers
-        .get("X-Admin-Key")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("");
-
-    let valid = state
-        .settings
-        .admin_key_to_delete_influencer
-        .as_deref()
-        .is_some_and(|key| provided_key == key);
-
-    if !valid {
+    if !has_admin_key(&headers, &state.settings.load()) {
         return Err(AppError::unauthorized("Invalid or missing admin key"));
     }
 
@@ -605,6 +1669,20 @@ pub async fn admin_ban_influencer(
             .await;
         return Err(e.into());
     }
+    state.influencer_cache.invalidate(&influencer.id);
+
+    let after = repo.get_by_id(&influencer.id).await?;
+    audit::record(
+        &state.db,
+        ADMIN_ACTOR,
+        &request_id,
+        AuditEvent {
+            before: audit::snapshot(&influencer),
+            after: after.as_ref().and_then(audit::snapshot),
+            ..AuditEvent::new(AuditAction::InfluencerBanned, "influencer", &influencer.id)
+        },
+    )
+    .await;
 
     state
         .google_chat
@@ -629,20 +1707,10 @@ pub async fn admin_ban_influencer(
 pub async fn admin_unban_influencer(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
+    request_id: RequestId,
     Path(influencer_id): Path<String>,
 ) -> Result<Json<InfluencerResponse>, AppError> {
-    let provided_key = headers
-        .get("X-Admin-Key")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("");
-
-    let valid = state
-        .settings
-        .admin_key_to_delete_influencer
-        .as_deref()
-        .is_some_and(|key| provided_key == key);
-
-    if !valid {
+    if !has_admin_key(&headers, &state.settings.load()) {
         return Err(AppError::unauthorized("Invalid or missing admin key"));
     }
 
@@ -660,6 +1728,24 @@ pub async fn admin_unban_influencer(
             .await;
         return Err(e.into());
     }
+    state.influencer_cache.invalidate(&influencer.id);
+
+    let after = repo.get_by_id(&influencer.id).await?;
+    audit::record(
+        &state.db,
+        ADMIN_ACTOR,
+        &request_id,
+        AuditEvent {
+            before: audit::snapshot(&influencer),
+            after: after.as_ref().and_then(audit::snapshot),
+            ..AuditEvent::new(
+                AuditAction::InfluencerUnbanned,
+                "influencer",
+                &influencer.id,
+            )
+        },
+    )
+    .await;
 
     state
         .google_chat
@@ -668,3 +1754,67 @@ pub async fn admin_unban_influencer(
 
     Ok(Json(InfluencerResponse::from(influencer)))
 }
+
+/// Set or clear the verified and official badges (admin only) — requires X-Admin-Key header
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/influencers/{influencer_id}/badges",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    request_body = UpdateBadgesRequest,
+    responses(
+        (status = 200, body = InfluencerResponse, description = "Badges updated"),
+        (status = 401, body = ErrorBody, description = "Missing or invalid admin key"),
+        (status = 404, body = ErrorBody, description = "Not found"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Admin"
+)]
+pub async fn admin_set_badges(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    request_id: RequestId,
+    Path(influencer_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<UpdateBadgesRequest>,
+) -> Result<Json<InfluencerResponse>, AppError> {
+    if !has_admin_key(&headers, &state.settings.load()) {
+        return Err(AppError::unauthorized("Invalid or missing admin key"));
+    }
+
+    let repo = state.db.inf_repo();
+    let influencer = repo
+        .get_by_id_or_name(&influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+
+    repo.set_badges(&influencer.id, body.verified, body.official)
+        .await?;
+    state.influencer_cache.invalidate(&influencer.id);
+
+    let updated = repo
+        .get_by_id(&influencer.id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+    audit::record(
+        &state.db,
+        ADMIN_ACTOR,
+        &request_id,
+        AuditEvent {
+            before: Some(serde_json::json!({
+                "is_verified": influencer.is_verified,
+                "is_official": influencer.is_official,
+            })),
+            after: Some(serde_json::json!({
+                "is_verified": updated.is_verified,
+                "is_official": updated.is_official,
+            })),
+            ..AuditEvent::new(
+                AuditAction::InfluencerBadgesUpdated,
+                "influencer",
+                &influencer.id,
+            )
+        },
+    )
+    .await;
+
+    Ok(Json(InfluencerResponse::from(updated)))
+}
@@ -0,0 +1,234 @@
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Multipart, Path, State};
+use axum::http::StatusCode;
+
+use super::influencers::get_influencer_as_owner;
+use crate::AppState;
+use crate::error::{AppError, ErrorBody};
+use crate::middleware::AuthenticatedUser;
+use crate::models::entities::KnowledgeDocument;
+use crate::models::requests::UploadKnowledgeDocumentBody;
+use crate::models::responses::{DeleteKnowledgeDocumentResponse, ListKnowledgeDocumentsResponse};
+use crate::services::knowledge;
+use crate::services::storage::file_extension;
+
+/// Longest stored document title.
+const MAX_TITLE_CHARS: usize = 200;
+
+/// Content type a knowledge document is read as, from the upload's declared type
+/// or its extension. `None` for anything that isn't plain text, Markdown or PDF.
+fn document_type(file_name: &str, content_type: Option<&str>) -> Option<&'static str> {
+    match content_type.map(|ct| ct.split(';').next().unwrap_or("").trim()) {
+        Some("application/pdf") => return Some("application/pdf"),
+        Some("text/markdown") => return Some("text/markdown"),
+        Some("text/plain") => return Some("text/plain"),
+        _ => {}
+    }
+    match file_extension(file_name).to_lowercase().as_str() {
+        ".pdf" => Some("application/pdf"),
+        ".md" | ".markdown" => Some("text/markdown"),
+        ".txt" => Some("text/plain"),
+        _ => None,
+    }
+}
+
+/// Add a document to an influencer's knowledge base (owner only)
+///
+/// Text, Markdown and PDF files are accepted. The text is split into chunks and
+/// embedded; passages relevant to a user's message are then added to the prompt.
+#[utoipa::path(
+    post,
+    path = "/api/v1/influencers/{influencer_id}/knowledge",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    request_body(content = UploadKnowledgeDocumentBody, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, body = KnowledgeDocument, description = "Document added"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Influencer not found"),
+        (status = 413, body = ErrorBody, description = "Document too large"),
+        (status = 422, body = ErrorBody, description = "Unsupported file type, no text, or the knowledge base is full"),
+        (status = 503, body = ErrorBody, description = "Text extraction or embedding unavailable")
+    ),
+    tag = "Knowledge",
+    security(("BearerAuth" = []))
+)]
+pub async fn upload_document(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<KnowledgeDocument>), AppError> {
+    let repo = state.db.inf_repo();
+    get_influencer_as_owner(&repo, &user, &influencer_id, "manage its knowledge base").await?;
+
+    let mut file_bytes: Option<Vec<u8>> = None;
+    let mut file_name: Option<String> = None;
+    let mut content_type: Option<String> = None;
+    let mut title: Option<String> = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::multipart("Invalid multipart data", e))?
+    {
+        match field.name().unwrap_or("") {
+            "file" => {
+                file_name = field.file_name().map(|s| s.to_string());
+                content_type = field.content_type().map(|s| s.to_string());
+                file_bytes = Some(
+                    field
+                        .bytes()
+                        .await
+                        .map_err(|e| AppError::multipart("Failed to read file", e))?
+                        .to_vec(),
+                );
+            }
+            "title" => {
+                title = Some(
+                    field
+                        .text()
+                        .await
+                        .map_err(|e| AppError::multipart("Failed to read title", e))?,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let file_bytes =
+        file_bytes.ok_or_else(|| AppError::bad_request("Missing 'file' field in upload"))?;
+    let file_name = file_name.unwrap_or("document".to_string());
+    let settings = state.settings.load();
+    let max_mb = settings.knowledge_max_document_mb;
+    if file_bytes.len() > max_mb as usize * 1024 * 1024 {
+        return Err(AppError::payload_too_large(format!(
+            "Document too large. Max: {max_mb}MB"
+        )));
+    }
+    let doc_type = document_type(&file_name, content_type.as_deref()).ok_or_else(|| {
+        AppError::field_error("file", "Unsupported file type. Use .txt, .md or .pdf")
+    })?;
+
+    // Saves extracting and embedding a document that can't be stored; the insert
+    // enforces the limits
+    let max_documents = settings.knowledge_max_documents;
+    if state
+        .db
+        .knowledge_repo()
+        .list_by_influencer(&influencer_id)
+        .await?
+        .len()
+        >= max_documents
+    {
+        return Err(AppError::field_error(
+            "file",
+            format!("Knowledge base is full ({max_documents} documents). Delete one first"),
+        ));
+    }
+
+    let text = if doc_type == "application/pdf" {
+        state.gemini.extract_text(&file_bytes, doc_type).await?
+    } else {
+        String::from_utf8(file_bytes.clone())
+            .map_err(|_| AppError::field_error("file", "Text documents must be UTF-8"))?
+    };
+
+    let title = title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or(&file_name);
+    let title: String = title.chars().take(MAX_TITLE_CHARS).collect();
+    let document = knowledge::new_document(
+        &influencer_id,
+        &title,
+        doc_type,
+        file_bytes.len(),
+        &user.user_id,
+    );
+    let document = knowledge::ingest(&state, document, &text).await?;
+    tracing::info!(
+        influencer_id,
+        document_id = %document.id,
+        chunks = document.chunk_count,
+        "Knowledge document added"
+    );
+
+    Ok((StatusCode::CREATED, Json(document)))
+}
+
+/// List the documents in an influencer's knowledge base, newest first (owner only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/influencers/{influencer_id}/knowledge",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    responses(
+        (status = 200, body = ListKnowledgeDocumentsResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Influencer not found")
+    ),
+    tag = "Knowledge",
+    security(("BearerAuth" = []))
+)]
+pub async fn list_documents(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+) -> Result<Json<ListKnowledgeDocumentsResponse>, AppError> {
+    let repo = state.db.inf_repo();
+    get_influencer_as_owner(&repo, &user, &influencer_id, "manage its knowledge base").await?;
+
+    let documents = state
+        .db
+        .knowledge_repo()
+        .list_by_influencer(&influencer_id)
+        .await?;
+
+    Ok(Json(ListKnowledgeDocumentsResponse {
+        influencer_id,
+        documents,
+    }))
+}
+
+/// Remove a document and its passages from an influencer's knowledge base (owner only)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/influencers/{influencer_id}/knowledge/{document_id}",
+    params(
+        ("influencer_id" = String, Path, description = "Influencer ID"),
+        ("document_id" = String, Path, description = "Document ID")
+    ),
+    responses(
+        (status = 200, body = DeleteKnowledgeDocumentResponse, description = "Document deleted"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Document not found")
+    ),
+    tag = "Knowledge",
+    security(("BearerAuth" = []))
+)]
+pub async fn delete_document(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((influencer_id, document_id)): Path<(String, String)>,
+) -> Result<Json<DeleteKnowledgeDocumentResponse>, AppError> {
+    let repo = state.db.inf_repo();
+    get_influencer_as_owner(&repo, &user, &influencer_id, "manage its knowledge base").await?;
+
+    let knowledge_repo = state.db.knowledge_repo();
+    knowledge_repo
+        .get(&document_id)
+        .await?
+        .filter(|d| d.influencer_id == influencer_id)
+        .ok_or_else(|| AppError::not_found("Document not found"))?;
+    knowledge_repo.delete(&document_id).await?;
+    state.knowledge_index.invalidate(&influencer_id);
+
+    Ok(Json(DeleteKnowledgeDocumentResponse {
+        success: true,
+        document_id,
+    }))
+}
@@ -1,15 +1,23 @@
 use std::sync::Arc;
 
 use axum::Json;
-use axum::extract::{Multipart, State};
+use axum::body::Body;
+use axum::extract::{Multipart, Path, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
 use chrono::Utc;
+use sha2::{Digest, Sha256};
 
 use crate::AppState;
 use crate::error::{AppError, ErrorBody};
 use crate::middleware::AuthenticatedUser;
+use crate::models::entities::{ScanVerdict, UploadScan};
 use crate::models::requests::UploadMediaBody;
 use crate::models::responses::MediaUploadResponse;
+use crate::services::audio_duration;
+use crate::services::image_normalize;
 use crate::services::storage::{file_extension, mime_from_extension};
+use crate::services::upload_scan::ScanResult;
 
 /// Upload a media file (image or audio) via multipart form
 #[utoipa::path(
@@ -19,7 +27,8 @@ use crate::services::storage::{file_extension, mime_from_extension};
     responses(
         (status = 200, body = MediaUploadResponse, description = "Upload successful"),
         (status = 401, body = ErrorBody, description = "Unauthorized"),
-        (status = 422, body = ErrorBody, description = "Validation error")
+        (status = 422, body = ErrorBody, description = "Validation error, audio longer than the allowed duration or of unreadable length, or the file was flagged by the malware scanner"),
+        (status = 503, body = ErrorBody, description = "Malware scanner unavailable")
     ),
     tag = "Media",
     security(("BearerAuth" = []))
@@ -37,7 +46,7 @@ pub async fn upload_media(
     while let Some(field) = multipart
         .next_field()
         .await
-        .map_err(|e| AppError::bad_request(format!("Invalid multipart data: {e}")))?
+        .map_err(|e| AppError::multipart("Invalid multipart data", e))?
     {
         let name = field.name().unwrap_or("").to_string();
         match name.as_str() {
@@ -48,7 +57,7 @@ pub async fn upload_media(
                     field
                         .bytes()
                         .await
-                        .map_err(|e| AppError::bad_request(format!("Failed to read file: {e}")))?
+                        .map_err(|e| AppError::multipart("Failed to read file", e))?
                         .to_vec(),
                 );
             }
@@ -57,7 +66,7 @@ pub async fn upload_media(
                     field
                         .text()
                         .await
-                        .map_err(|e| AppError::bad_request(format!("Failed to read type: {e}")))?,
+                        .map_err(|e| AppError::multipart("Failed to read type", e))?,
                 );
             }
             _ => {}
@@ -71,7 +80,8 @@ pub async fn upload_media(
     let file_name = file_name.unwrap_or("upload".to_string());
 
     if media_type != "image" && media_type != "audio" {
-        return Err(AppError::validation_error(
+        return Err(AppError::field_error(
+            "type",
             "Invalid type. Must be 'image' or 'audio'",
         ));
     }
@@ -86,15 +96,141 @@ pub async fn upload_media(
         state.storage.validate_audio(&file_name, size)?;
     }
 
+    // Measure audio ourselves; the client-reported duration is not trusted
+    let mut duration_seconds = None;
+    if media_type == "audio" {
+        match audio_duration::probe_duration(&file_bytes, &ext) {
+            Some(seconds) => {
+                let max = state.settings.load().max_audio_duration_seconds;
+                if seconds > max as f64 {
+                    return Err(AppError::field_error(
+                        "file",
+                        format!("Audio too long. Max: {max} seconds"),
+                    ));
+                }
+                duration_seconds = Some(seconds.ceil() as i32);
+            }
+            // Without a duration the length limit can't be enforced
+            None => {
+                tracing::warn!(user_id = %user.user_id, file_name = %file_name, "Could not read audio duration");
+                return Err(AppError::field_error(
+                    "file",
+                    "Could not read the audio's length. Upload a valid mp3, m4a, wav or ogg file",
+                ));
+            }
+        }
+    }
+
     // Determine content type
     let ct = content_type.unwrap_or_else(|| mime_from_extension(&ext).to_string());
 
+    // Scan before storing; flagged files are kept under quarantine/ and never served
+    let mut scan = None;
+    if state.upload_scanner.is_enabled() {
+        let mut record = UploadScan {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user.user_id.clone(),
+            file_name: file_name.clone(),
+            sha256: hex::encode(Sha256::digest(&file_bytes)),
+            size: size as i64,
+            scanner: state.upload_scanner.scanner_name().to_string(),
+            verdict: ScanVerdict::Error,
+            signature: None,
+            storage_key: None,
+        };
+        match state.upload_scanner.scan(&file_bytes).await {
+            Ok(ScanResult::Clean) => {
+                record.verdict = ScanVerdict::Clean;
+                scan = Some(record);
+            }
+            Ok(ScanResult::Infected(signature)) => {
+                tracing::warn!(
+                    user_id = %user.user_id,
+                    sha256 = %record.sha256,
+                    signature = %signature,
+                    "Upload flagged by malware scanner"
+                );
+                let quarantine_prefix = format!("quarantine/{}", user.user_id);
+                match state
+                    .storage
+                    .upload(&quarantine_prefix, file_bytes, &ext, &ct)
+                    .await
+                {
+                    Ok((key, _)) => record.storage_key = Some(key),
+                    Err(e) => tracing::error!(error = %e, "Failed to quarantine flagged upload"),
+                }
+                record.verdict = ScanVerdict::Infected;
+                record.signature = Some(signature);
+                record_scan(&state, &record).await;
+                return Err(AppError::field_error(
+                    "file",
+                    "File was flagged by the malware scanner",
+                ));
+            }
+            Err(e) => {
+                record_scan(&state, &record).await;
+                return Err(e);
+            }
+        }
+    }
+
+    // Auto-orient photos and drop their EXIF (GPS etc.) before anything is stored
+    let mut file_bytes = file_bytes;
+    let mut size = size;
+    let mut ext = ext;
+    let mut ct = ct;
+    let settings = state.settings.load();
+    if media_type == "image" && settings.normalize_uploaded_images {
+        let original = file_bytes.clone();
+        let normalize_ext = ext.clone();
+        let quality = settings.image_normalize_quality;
+        let normalized = tokio::task::spawn_blocking(move || {
+            image_normalize::normalize(&original, &normalize_ext, quality)
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("Image normalization task failed: {e}"))?;
+        match normalized {
+            Ok(Some(normalized)) => {
+                // Re-encoding can grow a file past the limit it was checked against
+                state
+                    .storage
+                    .validate_image(normalized.ext, normalized.bytes.len() as u64)?;
+                if normalized.ext != ext {
+                    ext = normalized.ext.to_string();
+                    ct = mime_from_extension(&ext).to_string();
+                }
+                size = normalized.bytes.len() as u64;
+                file_bytes = normalized.bytes;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tracing::info!(error = %e, user_id = %user.user_id, "Rejected undecodable image upload");
+                return Err(AppError::field_error("file", "Could not decode image"));
+            }
+        }
+    }
+
     // Upload to S3
     let (storage_key, _) = state
         .storage
         .upload(&user.user_id, file_bytes, &ext, &ct)
         .await?;
 
+    if let Some(mut record) = scan {
+        record.storage_key = Some(storage_key.clone());
+        record_scan(&state, &record).await;
+    }
+
+    if let Some(duration) = duration_seconds
+        && let Err(e) = state
+            .db
+            .audio_upload_repo()
+            .record(&storage_key, &user.user_id, duration)
+            .await
+    {
+        tracing::warn!(error = %e, storage_key = %storage_key, "Failed to record audio duration (non-fatal)");
+    }
+
     // Generate presigned URL for immediate access
     let presigned_url = state.storage.generate_presigned_url(&storage_key).await;
 
@@ -104,7 +240,90 @@ pub async fn upload_media(
         media_type,
         size,
         mime_type: ct,
-        duration_seconds: None,
+        duration_seconds,
         uploaded_at: Utc::now().naive_utc(),
     }))
 }
+
+async fn record_scan(state: &AppState, scan: &UploadScan) {
+    if let Err(e) = state.db.upload_scan_repo().record(scan).await {
+        tracing::warn!(error = %e, scan_id = %scan.id, "Failed to record upload scan (non-fatal)");
+    }
+}
+
+/// Stream a stored media file. Honors `Range` so audio can be scrubbed without
+/// downloading the whole clip, and never expires like presigned URLs do.
+#[utoipa::path(
+    get,
+    path = "/api/v1/media/{storage_key}",
+    params(
+        ("storage_key" = String, Path, description = "Storage key, e.g. `{user_id}/{file}`"),
+        ("Range" = Option<String>, Header, description = "Byte range, e.g. `bytes=0-1023`")
+    ),
+    responses(
+        (status = 200, description = "File contents"),
+        (status = 206, description = "Requested byte range"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "File is not attached to any of your conversations"),
+        (status = 404, body = ErrorBody, description = "File not found"),
+        (status = 416, body = ErrorBody, description = "Range not satisfiable")
+    ),
+    tag = "Media",
+    security(("BearerAuth" = []))
+)]
+pub async fn get_media(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(storage_key): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    // Uploads live under the uploader's id until they are attached to a message
+    let own_upload = storage_key
+        .strip_prefix(user.user_id.as_str())
+        .is_some_and(|rest| rest.starts_with('/'));
+    if !own_upload
+        && !state
+            .db
+            .msg_repo()
+            .user_can_access_media(&user.user_id, &storage_key)
+            .await?
+    {
+        return Err(AppError::forbidden(
+            "File is not attached to any of your conversations",
+        ));
+    }
+
+    let range = headers.get(header::RANGE).and_then(|v| v.to_str().ok());
+    let object = state.storage.get_object(&storage_key, range).await?;
+
+    let content_type = object
+        .content_type
+        .unwrap_or_else(|| mime_from_extension(&file_extension(&storage_key)).to_string());
+    let status = if object.content_range.is_some() {
+        StatusCode::PARTIAL_CONTENT
+    } else {
+        StatusCode::OK
+    };
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(header::ACCEPT_RANGES, "bytes".parse().unwrap());
+    response_headers.insert(
+        header::CACHE_CONTROL,
+        "private, max-age=3600".parse().unwrap(),
+    );
+    if let Ok(v) = content_type.parse() {
+        response_headers.insert(header::CONTENT_TYPE, v);
+    }
+    if let Some(len) = object.content_length {
+        response_headers.insert(header::CONTENT_LENGTH, len.into());
+    }
+    if let Some(v) = object.content_range.and_then(|r| r.parse().ok()) {
+        response_headers.insert(header::CONTENT_RANGE, v);
+    }
+
+    let stream = futures::stream::unfold(object.body, |mut body| async move {
+        body.next().await.map(|chunk| (chunk, body))
+    });
+
+    Ok((status, response_headers, Body::from_stream(stream)).into_response())
+}
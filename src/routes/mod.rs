@@ -1,7 +1,19 @@
+pub mod admin;
+pub mod alerts;
 pub mod chat;
 pub mod chat_v2;
+pub mod digest;
+pub mod email;
 pub mod health;
 pub mod influencers;
+pub mod knowledge;
 pub mod media;
+pub mod notifications;
 pub mod openapi;
+pub mod pagination;
+pub mod sandbox;
+pub mod share;
+pub mod telegram;
+pub mod users;
+pub mod webhooks;
 pub mod websocket;
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::State;
+
+use crate::AppState;
+use crate::error::{AppError, ErrorBody};
+use crate::middleware::{AuthenticatedUser, ValidatedJson};
+use crate::models::entities::NotificationPreferences;
+use crate::models::requests::QuietHoursRequest;
+use crate::models::responses::QuietHoursResponse;
+
+fn quiet_hours_to_response(
+    user_id: String,
+    prefs: Option<NotificationPreferences>,
+) -> QuietHoursResponse {
+    let active_now = prefs
+        .as_ref()
+        .is_some_and(|p| p.in_quiet_hours(chrono::Utc::now()));
+    QuietHoursResponse {
+        user_id,
+        enabled: prefs.is_some(),
+        send_digest: prefs.as_ref().is_some_and(|p| p.quiet_hours_digest),
+        start: prefs.as_ref().map(|p| p.quiet_hours_start.clone()),
+        end: prefs.as_ref().map(|p| p.quiet_hours_end.clone()),
+        timezone: prefs.map(|p| p.timezone),
+        active_now,
+    }
+}
+
+/// Get the caller's push notification quiet hours
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/quiet-hours",
+    responses(
+        (status = 200, body = QuietHoursResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized")
+    ),
+    tag = "Notifications",
+    security(("BearerAuth" = []))
+)]
+pub async fn get_quiet_hours(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<QuietHoursResponse>, AppError> {
+    let prefs = state
+        .db
+        .notification_prefs_repo()
+        .get(&user.user_id)
+        .await?;
+    Ok(Json(quiet_hours_to_response(user.user_id, prefs)))
+}
+
+/// Set quiet hours; pushes in the window are held back until it ends
+#[utoipa::path(
+    put,
+    path = "/api/v1/notifications/quiet-hours",
+    request_body = QuietHoursRequest,
+    responses(
+        (status = 200, body = QuietHoursResponse, description = "Quiet hours saved"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Notifications",
+    security(("BearerAuth" = []))
+)]
+pub async fn set_quiet_hours(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    ValidatedJson(body): ValidatedJson<QuietHoursRequest>,
+) -> Result<Json<QuietHoursResponse>, AppError> {
+    if body.timezone.parse::<chrono_tz::Tz>().is_err() {
+        return Err(AppError::field_error(
+            "timezone",
+            "Unknown timezone; use an IANA name like \"Asia/Kolkata\"",
+        ));
+    }
+    if body.start == body.end {
+        return Err(AppError::field_error(
+            "end",
+            "Quiet hours must not start and end at the same time",
+        ));
+    }
+
+    let prefs = state
+        .db
+        .notification_prefs_repo()
+        .upsert_quiet_hours(
+            &user.user_id,
+            &body.start,
+            &body.end,
+            &body.timezone,
+            body.send_digest,
+        )
+        .await?;
+    Ok(Json(quiet_hours_to_response(user.user_id, Some(prefs))))
+}
+
+/// Turn quiet hours off
+#[utoipa::path(
+    delete,
+    path = "/api/v1/notifications/quiet-hours",
+    responses(
+        (status = 200, body = QuietHoursResponse, description = "Quiet hours removed"),
+        (status = 401, body = ErrorBody, description = "Unauthorized")
+    ),
+    tag = "Notifications",
+    security(("BearerAuth" = []))
+)]
+pub async fn clear_quiet_hours(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> Result<Json<QuietHoursResponse>, AppError> {
+    state
+        .db
+        .notification_prefs_repo()
+        .remove(&user.user_id)
+        .await?;
+    Ok(Json(quiet_hours_to_response(user.user_id, None)))
+}
@@ -16,24 +16,95 @@ use utoipa_swagger_ui::SwaggerUi;
         // Influencers
         super::influencers::list_influencers,
         super::influencers::list_trending,
+        super::influencers::leaderboard,
         super::influencers::get_influencer,
+        super::influencers::record_impression,
         super::influencers::generate_prompt,
         super::influencers::validate_and_generate_metadata,
         super::influencers::create_influencer,
+        super::influencers::get_generation_status,
+        super::influencers::render_starter_video,
         super::influencers::update_system_prompt,
+        super::influencers::rename_influencer,
+        super::influencers::regenerate_greeting,
+        super::influencers::regenerate_video_prompt,
+        super::influencers::get_schedule,
+        super::influencers::update_schedule,
+        super::influencers::delete_schedule,
+        super::influencers::update_media_only_prompt,
+        super::influencers::get_conversation_starters,
+        super::influencers::update_conversation_starters,
+        super::influencers::get_suggestion_stats,
         super::influencers::delete_influencer,
+        super::sandbox::create_sandbox,
+        super::sandbox::send_sandbox_message,
+        super::sandbox::preview_chat,
+        super::influencers::publish_draft,
+        // Email gateway
+        super::email::inbound_email,
+        // Share
+        super::share::create_share,
+        super::share::get_share,
+        super::share::revoke_share,
+        // Telegram bridge
+        super::telegram::connect_bot,
+        super::telegram::get_bot,
+        super::telegram::disconnect_bot,
+        super::telegram::telegram_webhook,
+        // Webhooks
+        super::webhooks::create_webhook,
+        super::webhooks::list_webhooks,
+        super::webhooks::delete_webhook,
+        super::webhooks::list_deliveries,
+        // Knowledge
+        super::knowledge::upload_document,
+        super::knowledge::list_documents,
+        super::knowledge::delete_document,
+        // Alerts
+        super::alerts::sentry_webhook,
         // Chat V1
         super::chat::create_conversation,
+        super::chat::create_duet,
         super::chat::list_conversations,
+        super::chat::resume,
+        super::chat::semantic_search,
         super::chat::list_messages,
         super::chat::send_message,
+        super::chat::debug_context,
+        super::chat::update_conversation,
+        super::chat::update_response_style,
+        super::chat::update_language,
+        super::chat::get_message,
+        super::chat::retry_message,
+        super::chat::translate_message,
+        super::chat::start_takeover,
+        super::chat::end_takeover,
+        super::chat::mute_conversation,
+        super::chat::unmute_conversation,
+        super::chat::invite_participant,
+        super::chat::list_participants,
+        super::chat::remove_participant,
         super::chat::mark_as_read,
         super::chat::generate_image,
         super::chat::delete_conversation,
         // Chat V2
         super::chat_v2::list_conversations_v2,
+        super::chat_v2::list_bot_messages,
+        super::chat_v2::send_bot_reply,
+        super::chat_v2::delete_bot_conversation,
+        // Digest
+        super::digest::get_subscription,
+        super::digest::subscribe,
+        super::digest::unsubscribe,
+        super::digest::preview,
+        // Notifications
+        super::notifications::get_quiet_hours,
+        super::notifications::set_quiet_hours,
+        super::notifications::clear_quiet_hours,
+        super::users::get_presence,
         // Media
         super::media::upload_media,
+        super::media::get_media,
         // WebSocket
         super::websocket::ws_inbox,
         super::websocket::ws_docs,
@@ -41,60 +112,179 @@ use utoipa_swagger_ui::SwaggerUi;
     components(schemas(
         // Requests
         crate::models::requests::CreateConversationRequest,
+        crate::models::requests::CreateDuetRequest,
+        crate::models::requests::UpdateConversationRequest,
+        crate::models::requests::UpdateResponseStyleRequest,
+        crate::models::requests::UpdateLanguageRequest,
+        crate::models::requests::MuteConversationRequest,
         crate::models::requests::SendMessageRequest,
         crate::models::requests::GeneratePromptRequest,
         crate::models::requests::ValidateMetadataRequest,
         crate::models::requests::CreateInfluencerRequest,
         crate::models::requests::GenerateImageRequest,
         crate::models::requests::UpdateSystemPromptRequest,
+        crate::models::requests::RenameInfluencerRequest,
+        crate::models::requests::CreateSandboxRequest,
+        crate::models::requests::SandboxMessageRequest,
+        crate::models::requests::PreviewChatRequest,
+        crate::models::requests::PreviewTurn,
+        crate::models::requests::RegenerateGreetingRequest,
+        crate::models::requests::UpdateScheduleRequest,
+        crate::models::requests::UpdateMediaOnlyPromptRequest,
+        crate::models::requests::UpdateConversationStartersRequest,
+        crate::models::requests::InviteParticipantRequest,
         crate::models::requests::UploadMediaBody,
+        crate::models::requests::DigestSubscriptionRequest,
+        crate::models::requests::QuietHoursRequest,
+        crate::models::requests::CreateWebhookRequest,
+        crate::models::requests::UploadKnowledgeDocumentBody,
+        crate::models::requests::InboundEmailRequest,
+        crate::models::requests::ConnectTelegramRequest,
+        crate::models::requests::CreateShareRequest,
         // Responses
         crate::models::responses::InfluencerBasicInfo,
         crate::models::responses::InfluencerBasicInfoV2,
         crate::models::responses::MessageResponse,
         crate::models::responses::ConversationResponse,
+        crate::models::responses::DuetConversationResponse,
+        crate::models::responses::ConversationSettingsResponse,
+        crate::models::responses::ResponseStyleResponse,
+        crate::models::responses::LanguageResponse,
+        crate::models::responses::TranslateMessageResponse,
+        crate::models::responses::MessagePermalinkResponse,
         crate::models::responses::ConversationResponseV2,
         crate::models::responses::UserBasicInfo,
         crate::models::responses::SendMessageResponse,
+        crate::models::responses::SandboxResponse,
+        crate::models::responses::PreviewChatResponse,
         crate::models::responses::ListConversationsResponse,
+        crate::models::responses::ResumeResponse,
+        crate::models::responses::SemanticSearchResponse,
+        crate::models::responses::SemanticSearchMessageHit,
+        crate::models::responses::SemanticSearchConversationHit,
         crate::models::responses::ListConversationsResponseV2,
+        crate::models::responses::ListMessagesResponseV2,
         crate::models::responses::ListMessagesResponse,
         crate::models::responses::InfluencerResponse,
         crate::models::responses::ListInfluencersResponse,
         crate::models::responses::TrendingInfluencerResponse,
         crate::models::responses::ListTrendingInfluencersResponse,
+        crate::models::responses::LeaderboardResponse,
         crate::models::responses::SystemPromptResponse,
         crate::models::responses::GeneratedMetadataResponse,
+        crate::models::responses::GenerationStatusResponse,
+        crate::models::responses::RegenerateGreetingResponse,
+        crate::models::responses::StarterVideoPromptResponse,
+        crate::models::responses::ScheduleResponse,
+        crate::models::responses::MediaOnlyPromptResponse,
+        crate::models::responses::ConversationStartersResponse,
+        crate::models::responses::SuggestionStatsResponse,
+        crate::models::responses::SuggestionStat,
         crate::models::responses::MarkConversationAsReadResponse,
+        crate::models::responses::ParticipantResponse,
+        crate::models::responses::ListParticipantsResponse,
+        crate::models::responses::RemoveParticipantResponse,
+        crate::models::responses::TakeoverResponse,
+        crate::models::responses::MuteResponse,
+        crate::models::responses::ContextTokenEstimate,
+        crate::models::responses::DebugContextResponse,
         crate::models::responses::ServiceHealth,
         crate::models::responses::HealthResponse,
         crate::models::responses::StatusResponse,
         crate::models::responses::DatabaseStats,
         crate::models::responses::SystemStatistics,
+        crate::models::responses::CacheStats,
+        crate::models::responses::ProfileCacheStats,
+        crate::models::responses::MemoryStats,
+        crate::models::responses::MemorySizeBucket,
+        crate::models::responses::LoadShedStats,
+        crate::models::responses::WsStats,
+        crate::models::responses::TenantStats,
+        crate::models::responses::BackupVerificationResponse,
+        crate::models::responses::BackupTableCount,
+        crate::models::responses::WsConnectionLag,
         crate::models::responses::MediaUploadResponse,
         crate::models::responses::DeleteConversationResponse,
+        crate::models::responses::DigestSubscriptionResponse,
+        crate::models::responses::DigestConversationItem,
+        crate::models::responses::DigestPreviewResponse,
+        crate::models::responses::QuietHoursResponse,
+        crate::models::responses::PresenceResponse,
+        crate::models::responses::WebhookResponse,
+        crate::models::responses::ListWebhooksResponse,
+        crate::models::responses::DeleteWebhookResponse,
+        crate::models::responses::WebhookDeliveryResponse,
+        crate::models::responses::ListWebhookDeliveriesResponse,
+        crate::models::responses::ListKnowledgeDocumentsResponse,
+        crate::models::responses::DeleteKnowledgeDocumentResponse,
+        crate::models::responses::InboundEmailResponse,
+        crate::models::responses::TelegramBotResponse,
+        crate::models::responses::DisconnectTelegramResponse,
+        crate::models::responses::SharedMessage,
+        crate::models::responses::SharedInfluencer,
+        crate::models::responses::SharedConversationResponse,
+        crate::models::responses::ShareResponse,
+        crate::models::responses::RevokeShareResponse,
         // WebSocket event schemas
         crate::models::responses::NewMessageEvent,
         crate::models::responses::NewMessageEventData,
         crate::models::responses::ConversationReadEvent,
         crate::models::responses::ConversationReadEventData,
+        crate::models::responses::ConversationUpdatedEvent,
+        crate::models::responses::ConversationUpdatedEventData,
         crate::models::responses::TypingStatusEvent,
         crate::models::responses::TypingStatusEventData,
         crate::models::responses::WsDocsResponse,
         // Entities (enums + shared types)
         crate::models::entities::MessageType,
+        crate::models::entities::MessageSource,
         crate::models::entities::MessageRole,
         crate::models::entities::InfluencerStatus,
+        crate::models::entities::GenerationStatus,
+        crate::models::entities::AvatarModerationEntry,
+        crate::models::entities::AvatarVerdict,
+        crate::models::entities::AvailabilitySchedule,
+        crate::models::entities::ConversationStarter,
+        crate::models::entities::DayPart,
+        crate::models::entities::AvailabilityWindow,
+        crate::models::entities::ScheduleDay,
+        crate::models::entities::AwayMode,
+        crate::models::entities::ParticipantRole,
+        crate::models::entities::DuetMode,
+        crate::models::entities::ResponseLength,
+        crate::models::entities::Creativity,
+        crate::models::entities::DigestFrequency,
+        crate::models::entities::MessageProjection,
+        crate::models::entities::ConversationSort,
+        crate::models::entities::LeaderboardWindow,
+        crate::models::entities::LeaderboardMetric,
+        crate::models::entities::LeaderboardEntry,
+        crate::models::entities::WebhookEvent,
+        crate::models::entities::WebhookDeliveryStatus,
         crate::models::entities::LastMessageInfo,
+        crate::models::entities::ConversationStats,
+        crate::models::entities::GenerationInfo,
+        crate::models::entities::ToolInvocation,
+        crate::models::entities::KnowledgeDocument,
         // Error
         crate::error::ErrorBody,
+        crate::error::DuplicateCandidate,
     )),
     modifiers(&SecurityAddon),
     tags(
         (name = "Health", description = "Health and status endpoints"),
         (name = "Influencers", description = "AI influencer management"),
+        (name = "Email", description = "Inbound email-to-chat gateway"),
+        (name = "Share", description = "Public conversation snapshots"),
+        (name = "Telegram", description = "Telegram bot bridge for influencers"),
+        (name = "Webhooks", description = "Outbound event webhooks for influencer owners"),
+        (name = "Knowledge", description = "Reference documents influencers answer from"),
+        (name = "Alerts", description = "Operational alert relays"),
         (name = "Chat", description = "Chat conversations and messages (V1)"),
         (name = "Chat V2", description = "Chat conversations (V2)"),
+        (name = "Digest", description = "Unread-activity digest notifications"),
+        (name = "Notifications", description = "Push notification preferences"),
+        (name = "Users", description = "User presence"),
         (name = "Media", description = "Media upload"),
         (name = "WebSocket", description = "Real-time WebSocket endpoints"),
     )
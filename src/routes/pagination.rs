@@ -0,0 +1,26 @@
+//! Shared pieces of paginated list endpoints. Lists fetch one row past the
+//! page to report `has_more`, and only run the COUNT when the caller asked for
+//! `total`.
+
+use std::future::Future;
+
+/// Drop the extra row of a `limit + 1` fetch; returns whether it was there.
+pub fn trim_page<T>(rows: &mut Vec<T>, limit: i64) -> bool {
+    let limit = limit as usize;
+    let has_more = rows.len() > limit;
+    rows.truncate(limit);
+    has_more
+}
+
+/// Await `count` only when totals were requested. The future is lazy, so
+/// skipping it never touches the database.
+pub async fn count_if<F>(include_total: bool, count: F) -> Result<Option<i64>, sqlx::Error>
+where
+    F: Future<Output = Result<i64, sqlx::Error>>,
+{
+    if include_total {
+        count.await.map(Some)
+    } else {
+        Ok(None)
+    }
+}
@@ -0,0 +1,345 @@
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use super::chat::{
+    TurnContext, compose_turn_context, presign_messages_urls, record_generator, sanitize_reply,
+    select_ai_client,
+};
+use super::influencers::get_influencer_as_owner;
+use crate::AppState;
+use crate::error::{AppError, ErrorBody};
+use crate::middleware::{AuthenticatedUser, ValidatedJson};
+use crate::models::entities::{
+    Conversation, Message, MessageRole, MessageType, SandboxConversation,
+};
+use crate::models::requests::{CreateSandboxRequest, PreviewChatRequest, SandboxMessageRequest};
+use crate::models::responses::{
+    MessageResponse, PreviewChatResponse, SandboxResponse, SendMessageResponse,
+};
+use crate::services::moderation;
+
+/// Messages copied from the source conversation, most recent first.
+const CLONED_MESSAGES: i64 = 100;
+/// A sandbox stops accepting messages at this size; start a new one instead.
+const MAX_SANDBOX_MESSAGES: usize = 200;
+
+async fn sandbox_to_response(state: &AppState, sandbox: SandboxConversation) -> SandboxResponse {
+    let mut messages: Vec<MessageResponse> = sandbox
+        .messages
+        .into_iter()
+        .map(MessageResponse::from)
+        .collect();
+    presign_messages_urls(state.storage.as_ref(), &mut messages).await;
+    SandboxResponse {
+        id: sandbox.id,
+        influencer_id: sandbox.influencer_id,
+        source_conversation_id: sandbox.source_conversation_id,
+        system_instructions: sandbox.system_instructions,
+        messages,
+        created_at: sandbox.created_at,
+        expires_at: sandbox.expires_at,
+    }
+}
+
+fn sandbox_message(
+    sandbox_id: &str,
+    role: MessageRole,
+    content: String,
+    token_count: Option<i32>,
+    metadata: serde_json::Value,
+) -> Message {
+    Message {
+        id: Uuid::new_v4().to_string(),
+        conversation_id: sandbox_id.to_string(),
+        role,
+        content: Some(content),
+        message_type: MessageType::Text,
+        media_urls: Vec::new(),
+        audio_url: None,
+        audio_duration_seconds: None,
+        token_count,
+        client_message_id: None,
+        created_at: chrono::Utc::now().naive_utc(),
+        metadata,
+        status: "delivered".to_string(),
+        is_read: true,
+    }
+}
+
+/// Copy a conversation into a sandbox bound to a draft system prompt (owner only).
+/// Replies there use the draft; nothing reaches memories, analytics or notifications.
+#[utoipa::path(
+    post,
+    path = "/api/v1/influencers/{influencer_id}/sandbox",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    request_body = CreateSandboxRequest,
+    responses(
+        (status = 201, body = SandboxResponse, description = "Sandbox created"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Influencer or conversation not found"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn create_sandbox(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<CreateSandboxRequest>,
+) -> Result<(StatusCode, Json<SandboxResponse>), AppError> {
+    let influencer = get_influencer_as_owner(
+        &state.db.inf_repo(),
+        &user,
+        &influencer_id,
+        "create a sandbox",
+    )
+    .await?;
+
+    let mut conversation = state
+        .db
+        .conv_repo()
+        .get_by_id(&body.conversation_id)
+        .await?
+        .filter(|c| c.influencer_id == influencer.id)
+        .ok_or_else(|| AppError::not_found("Conversation not found"))?;
+    let source = state
+        .db
+        .msg_repo()
+        .get_recent_for_context(&conversation.id, CLONED_MESSAGES)
+        .await?;
+
+    let repo = state.db.sandbox_repo();
+    if let Err(e) = repo.delete_expired().await {
+        tracing::warn!(error = %e, "Failed to purge expired sandboxes (non-fatal)");
+    }
+
+    let id = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().naive_utc();
+    conversation.id = id.clone();
+    conversation.influencer = None;
+    conversation.recent_messages = None;
+    let messages = source
+        .into_iter()
+        .filter(|m| !m.is_failed())
+        .map(|mut m| {
+            m.conversation_id = id.clone();
+            m
+        })
+        .collect();
+    let sandbox = SandboxConversation {
+        id,
+        influencer_id: influencer.id,
+        owner_id: user.user_id,
+        source_conversation_id: body.conversation_id,
+        system_instructions: body.system_instructions,
+        conversation,
+        messages,
+        created_at: now,
+        expires_at: now
+            + chrono::Duration::minutes(state.settings.load().sandbox_ttl_minutes.max(1)),
+    };
+    repo.create(&sandbox).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(sandbox_to_response(&state, sandbox).await),
+    ))
+}
+
+/// Send a message in a sandbox and get the draft prompt's reply (owner only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/influencers/{influencer_id}/sandbox/{sandbox_id}/messages",
+    params(
+        ("influencer_id" = String, Path, description = "Influencer ID"),
+        ("sandbox_id" = String, Path, description = "Sandbox ID")
+    ),
+    request_body = SandboxMessageRequest,
+    responses(
+        (status = 200, body = SendMessageResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Sandbox not found or expired"),
+        (status = 409, body = ErrorBody, description = "Sandbox is full"),
+        (status = 503, body = ErrorBody, description = "AI unavailable")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn send_sandbox_message(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((influencer_id, sandbox_id)): Path<(String, String)>,
+    ValidatedJson(body): ValidatedJson<SandboxMessageRequest>,
+) -> Result<Json<SendMessageResponse>, AppError> {
+    let repo = state.db.sandbox_repo();
+    let mut sandbox = repo
+        .get(&sandbox_id)
+        .await?
+        .filter(|s| s.influencer_id == influencer_id)
+        .ok_or_else(|| AppError::not_found("Sandbox not found"))?;
+    if sandbox.owner_id != user.user_id {
+        return Err(AppError::forbidden(
+            "Only the bot owner can use this sandbox",
+        ));
+    }
+    if sandbox.messages.len() + 2 > MAX_SANDBOX_MESSAGES {
+        return Err(AppError::conflict(
+            "Sandbox is full; create a new one to keep testing",
+        ));
+    }
+
+    let mut influencer = state
+        .influencer_cache
+        .get_by_id(&state.db.inf_repo(), &influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+    influencer.system_instructions = moderation::with_guardrails(&sandbox.system_instructions);
+
+    let conv = &sandbox.conversation;
+    let TurnContext {
+        system_instructions,
+        history,
+        generation,
+        ..
+    } = compose_turn_context(&state, conv, &influencer, &[], sandbox.messages.clone()).await;
+    let ai_client = select_ai_client(&state, &influencer, conv);
+    let (raw, token_count) = ai_client
+        .generate_response_with(
+            &body.content,
+            &system_instructions,
+            &history,
+            None,
+            &generation,
+        )
+        .await?;
+    let (reply, mut metadata) = sanitize_reply(&state, conv, &raw);
+    record_generator(&mut metadata, ai_client);
+
+    let user_message = sandbox_message(
+        &sandbox.id,
+        MessageRole::User,
+        body.content,
+        None,
+        serde_json::json!({}),
+    );
+    let assistant_message = sandbox_message(
+        &sandbox.id,
+        MessageRole::Assistant,
+        reply,
+        Some(token_count),
+        serde_json::Value::Object(metadata),
+    );
+    sandbox.messages.push(user_message.clone());
+    sandbox.messages.push(assistant_message.clone());
+    repo.update_messages(&sandbox.id, &sandbox.messages).await?;
+
+    Ok(Json(SendMessageResponse {
+        user_message: MessageResponse::from(user_message),
+        // The sandbox is owner-only, so the backend is always shown
+        assistant_message: MessageResponse {
+            generated_by: assistant_message.generated_by(),
+            ..MessageResponse::from(assistant_message)
+        },
+    }))
+}
+
+/// Chat with the influencer's draft system prompt without storing anything (owner only).
+/// Passing `system_instructions` saves them as the draft first.
+#[utoipa::path(
+    post,
+    path = "/api/v1/influencers/{influencer_id}/preview-chat",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    request_body = PreviewChatRequest,
+    responses(
+        (status = 200, body = PreviewChatResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Influencer not found"),
+        (status = 409, body = ErrorBody, description = "No draft system prompt saved"),
+        (status = 422, body = ErrorBody, description = "Validation error"),
+        (status = 503, body = ErrorBody, description = "AI unavailable")
+    ),
+    tag = "Influencers",
+    security(("BearerAuth" = []))
+)]
+pub async fn preview_chat(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<PreviewChatRequest>,
+) -> Result<Json<PreviewChatResponse>, AppError> {
+    let repo = state.db.inf_repo();
+    let mut influencer =
+        get_influencer_as_owner(&repo, &user, &influencer_id, "preview a draft prompt").await?;
+
+    let draft = match body.system_instructions {
+        Some(instructions) => {
+            repo.set_draft_system_prompt(&influencer_id, Some(&instructions))
+                .await?;
+            instructions
+        }
+        None => repo
+            .get_draft_system_prompt(&influencer_id)
+            .await?
+            .ok_or_else(|| AppError::conflict("No draft system prompt saved"))?,
+    };
+    influencer.system_instructions = moderation::with_guardrails(&draft);
+
+    let now = chrono::Utc::now().naive_utc();
+    let preview_id = Uuid::new_v4().to_string();
+    let conv = Conversation {
+        id: preview_id.clone(),
+        user_id: user.user_id.clone(),
+        influencer_id: influencer.id.clone(),
+        created_at: now,
+        updated_at: now,
+        metadata: serde_json::json!({}),
+        influencer: None,
+        message_count: None,
+        unread_count: 0,
+        last_message: None,
+        recent_messages: None,
+    };
+    let history = body
+        .history
+        .into_iter()
+        .map(|turn| {
+            sandbox_message(
+                &preview_id,
+                turn.role,
+                turn.content,
+                None,
+                serde_json::json!({}),
+            )
+        })
+        .collect();
+
+    let TurnContext {
+        system_instructions,
+        history,
+        generation,
+        ..
+    } = compose_turn_context(&state, &conv, &influencer, &[], history).await;
+    let (raw, _) = select_ai_client(&state, &influencer, &conv)
+        .generate_response_with(
+            &body.message,
+            &system_instructions,
+            &history,
+            None,
+            &generation,
+        )
+        .await?;
+    let (reply, _) = sanitize_reply(&state, &conv, &raw);
+
+    Ok(Json(PreviewChatResponse {
+        reply,
+        draft_system_instructions: draft,
+    }))
+}
@@ -0,0 +1,313 @@
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::error::{AppError, ErrorBody};
+use crate::middleware::{AuthenticatedUser, RequestId, ValidatedJson};
+use crate::models::entities::{AuditAction, Message};
+use crate::models::requests::CreateShareRequest;
+use crate::models::responses::{
+    RevokeShareResponse, ShareResponse, SharedConversationResponse, SharedInfluencer, SharedMessage,
+};
+use crate::services::audit::{self, AuditEvent};
+
+/// Messages shared when no range is given.
+const DEFAULT_SHARE_MESSAGES: i64 = 50;
+/// Upper bound on the size of any snapshot.
+const MAX_SHARE_MESSAGES: i64 = 200;
+
+/// What gets frozen into `conversation_shares.snapshot`.
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    influencer: SharedInfluencer,
+    messages: Vec<SharedMessage>,
+}
+
+/// Load a message by id, making sure it belongs to `conversation_id`.
+async fn get_range_message(
+    state: &AppState,
+    conversation_id: &str,
+    message_id: &str,
+) -> Result<Message, AppError> {
+    state
+        .db
+        .msg_repo()
+        .get_by_id(message_id)
+        .await?
+        .filter(|m| m.conversation_id == conversation_id)
+        .ok_or_else(|| AppError::not_found(format!("Message '{message_id}' not found")))
+}
+
+/// Where a share's media copies live.
+fn share_media_prefix(token: &str) -> String {
+    format!("shared/{token}")
+}
+
+/// Copy media to public keys so the snapshot doesn't depend on presigned URLs.
+/// Media that can't be copied is left out rather than exposed privately.
+async fn rehost(state: &AppState, url: &str, prefix: &str) -> Option<String> {
+    match state.storage.copy_to_public(url, prefix).await {
+        Ok(public_url) => Some(public_url),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to re-host shared media, omitting it");
+            None
+        }
+    }
+}
+
+/// Share a snapshot of a conversation (conversation owner only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/chat/conversations/{conversation_id}/share",
+    params(("conversation_id" = String, Path, description = "Conversation ID")),
+    request_body = CreateShareRequest,
+    responses(
+        (status = 201, body = ShareResponse, description = "Snapshot created"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Conversation or message not found"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Share",
+    security(("BearerAuth" = []))
+)]
+pub async fn create_share(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    request_id: RequestId,
+    Path(conversation_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<CreateShareRequest>,
+) -> Result<(StatusCode, Json<ShareResponse>), AppError> {
+    let conv = state
+        .db
+        .conv_repo()
+        .get_by_id(&conversation_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Conversation not found"))?;
+    if conv.user_id != user.user_id {
+        return Err(AppError::forbidden(
+            "Only the conversation owner can share it",
+        ));
+    }
+
+    let msg_repo = state.db.msg_repo();
+    let messages = if body.from_message_id.is_none() && body.to_message_id.is_none() {
+        let mut recent = msg_repo
+            .list_by_conversation(&conversation_id, DEFAULT_SHARE_MESSAGES, 0, "desc")
+            .await?;
+        recent.reverse();
+        recent
+    } else {
+        let from = match body.from_message_id.as_deref() {
+            Some(id) => Some(get_range_message(&state, &conversation_id, id).await?),
+            None => None,
+        };
+        let to = match body.to_message_id.as_deref() {
+            Some(id) => Some(get_range_message(&state, &conversation_id, id).await?),
+            None => None,
+        };
+        if let (Some(from), Some(to)) = (&from, &to)
+            && from.created_at > to.created_at
+        {
+            return Err(AppError::field_error(
+                "from_message_id",
+                "from_message_id must not be after to_message_id",
+            ));
+        }
+        // Bounded by the endpoint messages themselves, not their timestamps: others
+        // written in the same second must not slip into a public snapshot
+        msg_repo
+            .list_between(
+                &conversation_id,
+                body.from_message_id.as_deref(),
+                body.to_message_id.as_deref(),
+                MAX_SHARE_MESSAGES,
+            )
+            .await?
+    };
+    if messages.is_empty() {
+        return Err(AppError::validation_error(
+            "No messages in the selected range",
+        ));
+    }
+
+    let influencer = state
+        .db
+        .inf_repo()
+        .get_by_id(&conv.influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+
+    let token = Uuid::new_v4().simple().to_string();
+    let media_prefix = share_media_prefix(&token);
+    let mut shared = Vec::with_capacity(messages.len());
+    // Only role, content and media survive: user ids, senders and metadata are dropped
+    for m in messages {
+        let mut media_urls = Vec::with_capacity(m.media_urls.len());
+        for url in &m.media_urls {
+            media_urls.extend(rehost(&state, url, &media_prefix).await);
+        }
+        let audio_url = match m.audio_url.as_deref() {
+            Some(url) => rehost(&state, url, &media_prefix).await,
+            None => None,
+        };
+        shared.push(SharedMessage {
+            role: m.role,
+            content: m.content,
+            message_type: m.message_type,
+            media_urls,
+            audio_url,
+            created_at: m.created_at,
+        });
+    }
+    let message_count = shared.len();
+
+    let snapshot = Snapshot {
+        influencer: SharedInfluencer {
+            name: influencer.name,
+            display_name: influencer.display_name,
+            avatar_url: influencer.avatar_url,
+        },
+        messages: shared,
+    };
+    let snapshot = serde_json::to_value(&snapshot).map_err(anyhow::Error::from)?;
+
+    let share = state
+        .db
+        .share_repo()
+        .create(&token, &conversation_id, &user.user_id, &snapshot)
+        .await?;
+    audit::record(
+        &state.db,
+        &user.user_id,
+        &request_id,
+        AuditEvent {
+            after: Some(serde_json::json!({
+                "token": share.token,
+                "message_count": message_count,
+            })),
+            ..AuditEvent::new(
+                AuditAction::ConversationShared,
+                "conversation",
+                &conversation_id,
+            )
+        },
+    )
+    .await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ShareResponse {
+            share_path: format!("/api/v1/share/{}", share.token),
+            token: share.token,
+            conversation_id,
+            message_count,
+            created_at: share.created_at,
+        }),
+    ))
+}
+
+/// View a shared conversation snapshot (no auth)
+#[utoipa::path(
+    get,
+    path = "/api/v1/share/{token}",
+    params(("token" = String, Path, description = "Share token")),
+    responses(
+        (status = 200, body = SharedConversationResponse, description = "Successful response"),
+        (status = 404, body = ErrorBody, description = "Share not found or revoked")
+    ),
+    tag = "Share"
+)]
+pub async fn get_share(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> Result<Json<SharedConversationResponse>, AppError> {
+    let share = state
+        .db
+        .share_repo()
+        .get(&token)
+        .await?
+        .filter(|s| s.revoked_at.is_none())
+        .ok_or_else(|| AppError::not_found("Share not found"))?;
+
+    let snapshot: Snapshot = serde_json::from_value(share.snapshot)
+        .map_err(|e| anyhow::anyhow!("Corrupt share snapshot: {e}"))?;
+
+    Ok(Json(SharedConversationResponse {
+        token: share.token,
+        influencer: snapshot.influencer,
+        messages: snapshot.messages,
+        created_at: share.created_at,
+    }))
+}
+
+/// Revoke a shared snapshot and delete its public media copies (its creator only)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/chat/conversations/{conversation_id}/share/{token}",
+    params(
+        ("conversation_id" = String, Path, description = "Conversation ID"),
+        ("token" = String, Path, description = "Share token")
+    ),
+    responses(
+        (status = 200, body = RevokeShareResponse, description = "Share revoked"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Share not found"),
+        (status = 503, body = ErrorBody, description = "Shared media could not be deleted; retry")
+    ),
+    tag = "Share",
+    security(("BearerAuth" = []))
+)]
+pub async fn revoke_share(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    request_id: RequestId,
+    Path((conversation_id, token)): Path<(String, String)>,
+) -> Result<Json<RevokeShareResponse>, AppError> {
+    let repo = state.db.share_repo();
+    let share = repo
+        .get(&token)
+        .await?
+        .filter(|s| s.conversation_id == conversation_id && s.revoked_at.is_none())
+        .ok_or_else(|| AppError::not_found("Share not found"))?;
+    if share.created_by != user.user_id {
+        return Err(AppError::forbidden(
+            "Only the user who shared this can revoke it",
+        ));
+    }
+
+    // The snapshot's media was copied to world-readable keys; take those down
+    // first so a failure leaves the share in place to retry
+    let deleted = state
+        .storage
+        .delete_prefix(&format!("{}/", share_media_prefix(&token)))
+        .await?;
+    repo.revoke(&token).await?;
+    tracing::info!(token = %token, deleted, "Share revoked");
+    audit::record(
+        &state.db,
+        &user.user_id,
+        &request_id,
+        AuditEvent {
+            before: Some(serde_json::json!({ "token": token })),
+            after: Some(serde_json::json!({ "deleted_media": deleted })),
+            ..AuditEvent::new(
+                AuditAction::ConversationShareRevoked,
+                "conversation",
+                &conversation_id,
+            )
+        },
+    )
+    .await;
+
+    Ok(Json(RevokeShareResponse {
+        success: true,
+        token,
+    }))
+}
@@ -0,0 +1,313 @@
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::db::repos::Repos;
+use crate::error::{AppError, ErrorBody};
+use crate::middleware::{AuthenticatedUser, Tenant, ValidatedJson};
+use crate::models::entities::{InfluencerStatus, MessageSource, TelegramBot};
+use crate::models::requests::{
+    ConnectTelegramRequest, CreateConversationRequest, SendMessageRequest,
+};
+use crate::models::responses::{DisconnectTelegramResponse, TelegramBotResponse};
+use crate::services::telegram::Update;
+
+const UNAVAILABLE_REPLY: &str = "Sorry, I couldn't process that message.";
+
+fn bot_to_response(bot: TelegramBot) -> TelegramBotResponse {
+    TelegramBotResponse {
+        influencer_id: bot.influencer_id,
+        bot_url: format!("https://t.me/{}", bot.bot_username),
+        bot_username: bot.bot_username,
+        connected_at: bot.created_at,
+    }
+}
+
+/// Only the influencer's owner may connect or disconnect a Telegram bot.
+async fn require_owner(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    influencer_id: &str,
+) -> Result<(), AppError> {
+    let parent = state
+        .db
+        .inf_repo()
+        .get_by_id(influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?
+        .parent_principal_id;
+    if parent.as_deref() != Some(&user.user_id) {
+        return Err(AppError::forbidden(
+            "Only the bot owner can manage its Telegram bridge",
+        ));
+    }
+    Ok(())
+}
+
+/// Connect a Telegram bot to an influencer (owner only)
+#[utoipa::path(
+    put,
+    path = "/api/v1/influencers/{influencer_id}/telegram",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    request_body = ConnectTelegramRequest,
+    responses(
+        (status = 200, body = TelegramBotResponse, description = "Bot connected"),
+        (status = 400, body = ErrorBody, description = "Telegram rejected the token"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Influencer not found"),
+        (status = 503, body = ErrorBody, description = "Telegram bridge not configured")
+    ),
+    tag = "Telegram",
+    security(("BearerAuth" = []))
+)]
+pub async fn connect_bot(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<ConnectTelegramRequest>,
+) -> Result<Json<TelegramBotResponse>, AppError> {
+    let webhook_url = state
+        .telegram
+        .webhook_url(&influencer_id)
+        .ok_or_else(|| AppError::service_unavailable("Telegram bridge is not configured"))?;
+
+    require_owner(&state, &user, &influencer_id).await?;
+
+    let bot_token = body.bot_token.trim();
+    let bot_username = state.telegram.get_me(bot_token).await?;
+    let webhook_secret = Uuid::new_v4().simple().to_string();
+    state
+        .telegram
+        .set_webhook(bot_token, &webhook_url, &webhook_secret)
+        .await?;
+
+    let bot = state
+        .db
+        .telegram_repo()
+        .upsert(
+            &influencer_id,
+            bot_token,
+            &bot_username,
+            &webhook_secret,
+            &user.user_id,
+        )
+        .await?;
+
+    tracing::info!(influencer_id = %influencer_id, bot_username = %bot.bot_username, "Telegram bot connected");
+
+    Ok(Json(bot_to_response(bot)))
+}
+
+/// Get the Telegram bot connected to an influencer (owner only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/influencers/{influencer_id}/telegram",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    responses(
+        (status = 200, body = TelegramBotResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "No Telegram bot connected")
+    ),
+    tag = "Telegram",
+    security(("BearerAuth" = []))
+)]
+pub async fn get_bot(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+) -> Result<Json<TelegramBotResponse>, AppError> {
+    require_owner(&state, &user, &influencer_id).await?;
+
+    let bot = state
+        .db
+        .telegram_repo()
+        .get(&influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("No Telegram bot connected"))?;
+
+    Ok(Json(bot_to_response(bot)))
+}
+
+/// Disconnect an influencer's Telegram bot (owner only)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/influencers/{influencer_id}/telegram",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    responses(
+        (status = 200, body = DisconnectTelegramResponse, description = "Bot disconnected"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "No Telegram bot connected")
+    ),
+    tag = "Telegram",
+    security(("BearerAuth" = []))
+)]
+pub async fn disconnect_bot(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+) -> Result<Json<DisconnectTelegramResponse>, AppError> {
+    require_owner(&state, &user, &influencer_id).await?;
+
+    let repo = state.db.telegram_repo();
+    let bot = repo
+        .get(&influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("No Telegram bot connected"))?;
+
+    // A revoked token can't be unhooked anymore; drop the connection regardless
+    if let Err(e) = state.telegram.delete_webhook(&bot.bot_token).await {
+        tracing::warn!(error = %e, influencer_id = %influencer_id, "Failed to delete Telegram webhook");
+    }
+    repo.remove(&influencer_id).await?;
+
+    Ok(Json(DisconnectTelegramResponse {
+        success: true,
+        influencer_id,
+    }))
+}
+
+/// Receive updates from Telegram. Authenticated by the secret token set on the webhook;
+/// messages are answered in the background so Telegram isn't kept waiting on the AI.
+#[utoipa::path(
+    post,
+    path = "/api/v1/telegram/webhook/{influencer_id}",
+    params(
+        ("influencer_id" = String, Path, description = "Influencer ID"),
+        ("X-Telegram-Bot-Api-Secret-Token" = String, Header, description = "Webhook secret set by the bridge")
+    ),
+    request_body = Object,
+    responses(
+        (status = 200, description = "Update accepted"),
+        (status = 401, body = ErrorBody, description = "Invalid secret token"),
+        (status = 404, body = ErrorBody, description = "No Telegram bot connected")
+    ),
+    tag = "Telegram"
+)]
+pub async fn telegram_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(influencer_id): Path<String>,
+    Json(body): Json<serde_json::Value>,
+) -> Result<StatusCode, AppError> {
+    let bot = state
+        .db
+        .telegram_repo()
+        .get(&influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("No Telegram bot connected"))?;
+
+    let provided = headers
+        .get("X-Telegram-Bot-Api-Secret-Token")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if provided != bot.webhook_secret {
+        return Err(AppError::unauthorized("Invalid secret token"));
+    }
+
+    // Anything we don't handle is acknowledged so Telegram doesn't redeliver it
+    let Ok(update) = serde_json::from_value::<Update>(body) else {
+        return Ok(StatusCode::OK);
+    };
+    let Some(message) = update.message else {
+        return Ok(StatusCode::OK);
+    };
+    let (Some(sender), Some(text)) = (message.from, message.text) else {
+        return Ok(StatusCode::OK);
+    };
+    if sender.is_bot || message.chat.kind != "private" {
+        return Ok(StatusCode::OK);
+    }
+
+    tokio::spawn(async move {
+        let reply = bridge_message(&state, &bot, update.update_id, sender.id, &text).await;
+        let reply = reply.unwrap_or_else(|e| {
+            tracing::warn!(error = %e, influencer_id = %bot.influencer_id, "Telegram message failed");
+            Some(UNAVAILABLE_REPLY.to_string())
+        });
+        if let Some(reply) = reply {
+            state
+                .telegram
+                .send_message(&bot.bot_token, message.chat.id, &reply)
+                .await;
+        }
+    });
+
+    Ok(StatusCode::OK)
+}
+
+/// Run one Telegram message through the regular chat pipeline, in the conversation
+/// keyed by the Telegram user. Returns the text to send back, if any.
+async fn bridge_message(
+    state: &Arc<AppState>,
+    bot: &TelegramBot,
+    update_id: i64,
+    telegram_user_id: i64,
+    text: &str,
+) -> Result<Option<String>, AppError> {
+    let influencer = state
+        .db
+        .inf_repo()
+        .get_by_id(&bot.influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?;
+    if influencer.is_active == InfluencerStatus::Discontinued {
+        return Ok(Some(
+            "This bot has been deleted and can no longer receive messages.".into(),
+        ));
+    }
+    let tenant = Tenant(influencer.tenant.as_str().into());
+
+    let user = AuthenticatedUser {
+        user_id: format!("telegram:{telegram_user_id}"),
+    };
+    let (_, Json(conversation)) = super::chat::create_conversation(
+        State(state.clone()),
+        tenant.clone(),
+        user.clone(),
+        Repos::new(state.db.clone()),
+        HeaderMap::new(),
+        ValidatedJson(CreateConversationRequest {
+            influencer_id: influencer.id.clone(),
+        }),
+    )
+    .await?;
+
+    // Telegram opens every chat with /start: answer with the greeting, not the AI
+    if text.trim().starts_with("/start") {
+        return Ok(influencer.initial_greeting.filter(|g| !g.is_empty()));
+    }
+
+    let content: String = text
+        .chars()
+        .take(state.tenants.settings(tenant.as_str()).message_max_chars)
+        .collect();
+    let (status, Json(sent)) = super::chat::send_message(
+        State(state.clone()),
+        tenant,
+        user,
+        Path(conversation.id),
+        ValidatedJson(SendMessageRequest {
+            message_type: "text".into(),
+            content: Some(content),
+            media_urls: None,
+            audio_url: None,
+            audio_duration_seconds: None,
+            client_message_id: Some(format!("telegram:{update_id}")),
+            source: MessageSource::Typed,
+        }),
+    )
+    .await?;
+
+    // A 202 carries only a pending stand-in; the reply isn't written yet
+    Ok(sent
+        .assistant_message
+        .content
+        .filter(|_| status == StatusCode::OK))
+}
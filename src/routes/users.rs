@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Path, State};
+
+use crate::AppState;
+use crate::error::{AppError, ErrorBody};
+use crate::middleware::AuthenticatedUser;
+use crate::models::responses::PresenceResponse;
+
+/// Whether a user is connected and when they were last seen. Visible to the
+/// user themselves and to bots they have a conversation with.
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{principal}/presence",
+    params(("principal" = String, Path, description = "User principal")),
+    responses(
+        (status = 200, body = PresenceResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "No conversation with this user")
+    ),
+    tag = "Users",
+    security(("BearerAuth" = []))
+)]
+pub async fn get_presence(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(principal): Path<String>,
+) -> Result<Json<PresenceResponse>, AppError> {
+    if user.user_id != principal
+        && state
+            .db
+            .conv_repo()
+            .get_existing(&principal, &user.user_id)
+            .await?
+            .is_none()
+    {
+        return Err(AppError::forbidden(
+            "Presence is only visible to bots the user chats with",
+        ));
+    }
+
+    let last_seen_at = state.db.presence_repo().last_seen(&principal).await?;
+    Ok(Json(PresenceResponse {
+        is_online: state.ws_manager.is_connected(&principal),
+        principal_id: principal,
+        last_seen_at,
+    }))
+}
@@ -0,0 +1,242 @@
+use std::sync::Arc;
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use super::pagination::{count_if, trim_page};
+use crate::AppState;
+use crate::error::{AppError, ErrorBody};
+use crate::middleware::{AuthenticatedUser, ValidatedJson};
+use crate::models::entities::{Webhook, WebhookDelivery};
+use crate::models::requests::{CreateWebhookRequest, PaginationParams};
+use crate::models::responses::{
+    DeleteWebhookResponse, ListWebhookDeliveriesResponse, ListWebhooksResponse,
+    WebhookDeliveryResponse, WebhookResponse,
+};
+use crate::services::webhooks::check_target;
+
+fn webhook_to_response(webhook: Webhook, include_secret: bool) -> WebhookResponse {
+    WebhookResponse {
+        id: webhook.id,
+        influencer_id: webhook.influencer_id,
+        url: webhook.url,
+        events: webhook.events,
+        is_active: webhook.is_active,
+        created_at: webhook.created_at,
+        secret: include_secret.then_some(webhook.secret),
+    }
+}
+
+fn delivery_to_response(delivery: WebhookDelivery) -> WebhookDeliveryResponse {
+    WebhookDeliveryResponse {
+        id: delivery.id,
+        event: delivery.event,
+        status: delivery.status,
+        attempts: delivery.attempts,
+        response_status: delivery.response_status,
+        error: delivery.error,
+        payload: delivery.payload,
+        created_at: delivery.created_at,
+        completed_at: delivery.completed_at,
+    }
+}
+
+/// Only the influencer's owner may manage its webhooks.
+async fn require_owner(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    influencer_id: &str,
+) -> Result<(), AppError> {
+    let parent = state
+        .db
+        .inf_repo()
+        .get_by_id(influencer_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Influencer not found"))?
+        .parent_principal_id;
+    if parent.as_deref() != Some(&user.user_id) {
+        return Err(AppError::forbidden(
+            "Only the bot owner can manage webhooks",
+        ));
+    }
+    Ok(())
+}
+
+/// Load a webhook, making sure it belongs to `influencer_id`.
+async fn get_webhook(
+    state: &AppState,
+    influencer_id: &str,
+    webhook_id: &str,
+) -> Result<Webhook, AppError> {
+    state
+        .db
+        .webhook_repo()
+        .get(webhook_id)
+        .await?
+        .filter(|w| w.influencer_id == influencer_id)
+        .ok_or_else(|| AppError::not_found("Webhook not found"))
+}
+
+/// Register a webhook for an influencer (owner only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/influencers/{influencer_id}/webhooks",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 201, body = WebhookResponse, description = "Webhook registered; the secret is only shown once"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Influencer not found"),
+        (status = 422, body = ErrorBody, description = "Validation error")
+    ),
+    tag = "Webhooks",
+    security(("BearerAuth" = []))
+)]
+pub async fn create_webhook(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+    ValidatedJson(body): ValidatedJson<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<WebhookResponse>), AppError> {
+    require_owner(&state, &user, &influencer_id).await?;
+
+    if let Err(message) = check_target(&body.url).await {
+        return Err(AppError::field_error("url", message));
+    }
+
+    let secret = format!("whsec_{}", Uuid::new_v4().simple());
+    let webhook = state
+        .db
+        .webhook_repo()
+        .create(
+            &influencer_id,
+            &body.url,
+            &secret,
+            &body.events,
+            &user.user_id,
+        )
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(webhook_to_response(webhook, true)),
+    ))
+}
+
+/// List an influencer's webhooks (owner only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/influencers/{influencer_id}/webhooks",
+    params(("influencer_id" = String, Path, description = "Influencer ID")),
+    responses(
+        (status = 200, body = ListWebhooksResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Influencer not found")
+    ),
+    tag = "Webhooks",
+    security(("BearerAuth" = []))
+)]
+pub async fn list_webhooks(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(influencer_id): Path<String>,
+) -> Result<Json<ListWebhooksResponse>, AppError> {
+    require_owner(&state, &user, &influencer_id).await?;
+
+    let webhooks = state
+        .db
+        .webhook_repo()
+        .list_by_influencer(&influencer_id)
+        .await?;
+
+    Ok(Json(ListWebhooksResponse {
+        influencer_id,
+        webhooks: webhooks
+            .into_iter()
+            .map(|w| webhook_to_response(w, false))
+            .collect(),
+    }))
+}
+
+/// Delete a webhook (owner only)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/influencers/{influencer_id}/webhooks/{webhook_id}",
+    params(
+        ("influencer_id" = String, Path, description = "Influencer ID"),
+        ("webhook_id" = String, Path, description = "Webhook ID")
+    ),
+    responses(
+        (status = 200, body = DeleteWebhookResponse, description = "Webhook deleted"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Webhook not found")
+    ),
+    tag = "Webhooks",
+    security(("BearerAuth" = []))
+)]
+pub async fn delete_webhook(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((influencer_id, webhook_id)): Path<(String, String)>,
+) -> Result<Json<DeleteWebhookResponse>, AppError> {
+    require_owner(&state, &user, &influencer_id).await?;
+    get_webhook(&state, &influencer_id, &webhook_id).await?;
+
+    state.db.webhook_repo().delete(&webhook_id).await?;
+
+    Ok(Json(DeleteWebhookResponse {
+        success: true,
+        webhook_id,
+    }))
+}
+
+/// Delivery log for a webhook, newest first (owner only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/influencers/{influencer_id}/webhooks/{webhook_id}/deliveries",
+    params(
+        ("influencer_id" = String, Path, description = "Influencer ID"),
+        ("webhook_id" = String, Path, description = "Webhook ID"),
+        PaginationParams
+    ),
+    responses(
+        (status = 200, body = ListWebhookDeliveriesResponse, description = "Successful response"),
+        (status = 401, body = ErrorBody, description = "Unauthorized"),
+        (status = 403, body = ErrorBody, description = "Forbidden"),
+        (status = 404, body = ErrorBody, description = "Webhook not found")
+    ),
+    tag = "Webhooks",
+    security(("BearerAuth" = []))
+)]
+pub async fn list_deliveries(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((influencer_id, webhook_id)): Path<(String, String)>,
+    Query(params): Query<PaginationParams>,
+) -> Result<Json<ListWebhookDeliveriesResponse>, AppError> {
+    require_owner(&state, &user, &influencer_id).await?;
+    get_webhook(&state, &influencer_id, &webhook_id).await?;
+
+    let limit = params.limit(50, 100);
+    let offset = params.offset();
+    let repo = state.db.webhook_repo();
+    let (mut deliveries, total) = tokio::try_join!(
+        repo.list_deliveries(&webhook_id, limit + 1, offset),
+        count_if(params.include_total(), repo.count_deliveries(&webhook_id)),
+    )?;
+    let has_more = trim_page(&mut deliveries, limit);
+
+    Ok(Json(ListWebhookDeliveriesResponse {
+        webhook_id,
+        deliveries: deliveries.into_iter().map(delivery_to_response).collect(),
+        total,
+        has_more,
+        limit,
+        offset,
+    }))
+}
@@ -10,7 +10,7 @@ use axum::response::IntoResponse;
 use crate::models::responses::WsDocsResponse;
 
 use crate::AppState;
-use crate::middleware;
+use crate::middleware::{self, AuthFailure};
 
 #[utoipa::path(
     get,
@@ -43,20 +43,37 @@ pub async fn ws_inbox(
         }
     };
 
-    let claims = match middleware::decode_jwt(&token) {
+    let claims = match middleware::decode_jwt(&token, &state.settings.load()) {
         Ok(c) => c,
-        Err(_) => {
-            return ws.on_upgrade(|mut socket| async move {
+        Err(e) => {
+            // Same close code either way; the reason lets clients refresh instead of signing out
+            let reason = if e.reason == AuthFailure::Expired {
+                "Token expired"
+            } else {
+                "Invalid or expired token"
+            };
+            return ws.on_upgrade(move |mut socket| async move {
                 let _ = socket
                     .send(Message::Close(Some(CloseFrame {
                         code: 4001,
-                        reason: "Invalid or expired token".into(),
+                        reason: reason.into(),
                     })))
                     .await;
             });
         }
     };
 
+    if middleware::is_revoked(&state, &claims) {
+        return ws.on_upgrade(|mut socket| async move {
+            let _ = socket
+                .send(Message::Close(Some(CloseFrame {
+                    code: 4001,
+                    reason: "Token has been revoked".into(),
+                })))
+                .await;
+        });
+    }
+
     // Verify path user_id matches JWT subject
     if claims.sub != user_id {
         return ws.on_upgrade(|mut socket| async move {
@@ -77,8 +94,14 @@ async fn handle_socket(state: Arc<AppState>, user_id: String, mut socket: WebSoc
 
     tracing::info!(user_id = %user_id, conn_id = conn_id, "WebSocket connected");
 
+    // Keeps last_seen_at fresh while connected; the first tick records the connect
+    let mut presence_tick = tokio::time::interval(state.presence.interval());
+
     loop {
         tokio::select! {
+            _ = presence_tick.tick() => {
+                state.presence.record(&state.db, &user_id, false).await;
+            }
             // Forward events from WsManager to the WebSocket client
             msg = rx.recv() => {
                 match msg {
@@ -87,7 +110,17 @@ async fn handle_socket(state: Arc<AppState>, user_id: String, mut socket: WebSoc
                             break;
                         }
                     }
-                    None => break, // channel closed
+                    None => {
+                        if rx.was_evicted() {
+                            let _ = socket
+                                .send(Message::Close(Some(CloseFrame {
+                                    code: 4008,
+                                    reason: "Client too slow".into(),
+                                })))
+                                .await;
+                        }
+                        break; // channel closed
+                    }
                 }
             }
             // Handle incoming messages from the client (or detect disconnect)
@@ -107,6 +140,10 @@ async fn handle_socket(state: Arc<AppState>, user_id: String, mut socket: WebSoc
     }
 
     state.ws_manager.disconnect(&user_id, conn_id);
+    state.presence.record(&state.db, &user_id, true).await;
+    if !state.ws_manager.is_connected(&user_id) {
+        state.presence.forget(&user_id);
+    }
     tracing::info!(user_id = %user_id, conn_id = conn_id, "WebSocket disconnected");
 }
 
@@ -141,6 +178,14 @@ pub async fn ws_docs() -> Json<serde_json::Value> {
                 "read_at": "ISO timestamp"
             }
         },
+        "conversation_updated": {
+            "event": "conversation_updated",
+            "data": {
+                "conversation_id": "string",
+                "updated_at": "ISO timestamp",
+                "last_message": "MessageResponse object"
+            }
+        },
         "typing_status": {
             "event": "typing_status",
             "data": {
@@ -148,6 +193,13 @@ pub async fn ws_docs() -> Json<serde_json::Value> {
                 "influencer_id": "string",
                 "is_typing": true
             }
+        },
+        "conversation_throttled": {
+            "event": "conversation_throttled",
+            "data": {
+                "conversation_id": "string",
+                "retry_after": "seconds until replies resume"
+            }
         }
     }))
 }
@@ -0,0 +1,287 @@
+use std::collections::VecDeque;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use regex::Regex;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::config::Settings;
+use crate::models::entities::{AbuseAction, AbuseEvent, AbuseKind};
+
+/// Above this many tracked senders, idle ones are dropped.
+const MAX_TRACKED_SENDERS: usize = 10_000;
+
+/// Messages shorter than this ("ok", "haha", "yes") are said again and again in
+/// normal chat, so they never count as repeats.
+const MIN_REPEAT_CHARS: usize = 12;
+
+static URL_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(https?://|www\.)\S+").unwrap());
+
+struct Sent {
+    at: Instant,
+    fingerprint: u64,
+    chars: usize,
+}
+
+struct Penalty {
+    action: AbuseAction,
+    until: Instant,
+}
+
+/// Heuristics applied to one message, from the reloadable settings.
+pub struct AbuseLimits {
+    window: Duration,
+    repeat_threshold: usize,
+    max_chars: usize,
+    max_urls: usize,
+    throttle: Duration,
+    shadow_limit: Duration,
+}
+
+impl AbuseLimits {
+    /// `None` when abuse detection is off.
+    pub fn from_settings(settings: &Settings) -> Option<Self> {
+        settings.abuse_detection_enabled.then(|| Self {
+            window: Duration::from_secs(settings.abuse_window_seconds),
+            repeat_threshold: settings.abuse_repeat_threshold,
+            max_chars: settings.abuse_max_chars_per_window,
+            max_urls: settings.abuse_max_urls,
+            throttle: Duration::from_secs(settings.abuse_throttle_seconds),
+            shadow_limit: Duration::from_secs(settings.abuse_shadow_limit_seconds),
+        })
+    }
+}
+
+/// A penalty given for the message just checked.
+pub struct Offence {
+    pub kind: AbuseKind,
+    pub action: AbuseAction,
+    pub detail: String,
+    pub duration: Duration,
+}
+
+/// How to treat a message.
+#[derive(Debug)]
+pub enum Verdict {
+    Allow,
+    /// Reject it; the penalty lifts in this many seconds
+    Throttle(u64),
+    /// Store it without an AI reply
+    ShadowLimit,
+}
+
+/// Per-sender, per-conversation flood and spam detection. Scripted abuse burns
+/// AI spend, so a sender who sends the same message several times in a row, floods a conversation with text
+/// or posts link spam is throttled (repeats, volume) or shadow-limited (links)
+/// in that conversation for a while. Kept in memory, like the reply throttle.
+#[derive(Default)]
+pub struct AbuseGuard {
+    recent: DashMap<String, VecDeque<Sent>>,
+    penalties: DashMap<String, Penalty>,
+}
+
+impl AbuseGuard {
+    /// Check a message `user_id` sends to `conversation_id` and remember it.
+    /// Returns the verdict, plus the offence when this message started a penalty.
+    pub fn check(
+        &self,
+        conversation_id: &str,
+        user_id: &str,
+        text: &str,
+        limits: &AbuseLimits,
+    ) -> (Verdict, Option<Offence>) {
+        let key = format!("{conversation_id}:{user_id}");
+        let now = Instant::now();
+        if let Some(verdict) = self.active_penalty(&key, now) {
+            return (verdict, None);
+        }
+        let text = text.trim();
+        if text.is_empty() {
+            return (Verdict::Allow, None);
+        }
+
+        if self.recent.len() >= MAX_TRACKED_SENDERS {
+            self.recent.retain(|_, sent| {
+                sent.back()
+                    .is_some_and(|s| now.duration_since(s.at) < limits.window)
+            });
+        }
+        let fingerprint = fingerprint(text);
+        let chars = text.chars().count();
+        let (repeats, chars) = {
+            let mut sent = self.recent.entry(key.clone()).or_default();
+            while sent
+                .front()
+                .is_some_and(|s| now.duration_since(s.at) >= limits.window)
+            {
+                sent.pop_front();
+            }
+            sent.push_back(Sent {
+                at: now,
+                fingerprint,
+                chars,
+            });
+            // Only an unbroken run of the same message counts; asking the same
+            // thing again later in a conversation is normal
+            let repeats = if chars < MIN_REPEAT_CHARS {
+                0
+            } else {
+                sent.iter()
+                    .rev()
+                    .take_while(|s| s.fingerprint == fingerprint)
+                    .count()
+            };
+            (repeats, sent.iter().map(|s| s.chars).sum::<usize>())
+        };
+
+        let urls = URL_REGEX.find_iter(text).count();
+        let offence = if urls >= limits.max_urls {
+            Offence {
+                kind: AbuseKind::UrlSpam,
+                action: AbuseAction::ShadowLimited,
+                detail: format!("{urls} links in one message"),
+                duration: limits.shadow_limit,
+            }
+        } else if repeats >= limits.repeat_threshold {
+            Offence {
+                kind: AbuseKind::RepeatedMessage,
+                action: AbuseAction::Throttled,
+                detail: format!("Same message {repeats} times"),
+                duration: limits.throttle,
+            }
+        } else if limits.max_chars > 0 && chars > limits.max_chars {
+            Offence {
+                kind: AbuseKind::ExcessiveLength,
+                action: AbuseAction::Throttled,
+                detail: format!("{chars} characters"),
+                duration: limits.throttle,
+            }
+        } else {
+            return (Verdict::Allow, None);
+        };
+        if offence.duration.is_zero() {
+            return (Verdict::Allow, None);
+        }
+
+        // The count starts over once the penalty is served
+        self.recent.remove(&key);
+        self.penalties.retain(|_, p| p.until > now);
+        self.penalties.insert(
+            key,
+            Penalty {
+                action: offence.action,
+                until: now + offence.duration,
+            },
+        );
+        let verdict = match offence.action {
+            AbuseAction::Throttled => Verdict::Throttle(offence.duration.as_secs().max(1)),
+            AbuseAction::ShadowLimited => Verdict::ShadowLimit,
+        };
+        (verdict, Some(offence))
+    }
+
+    fn active_penalty(&self, key: &str, now: Instant) -> Option<Verdict> {
+        let penalty = self.penalties.get(key)?;
+        if penalty.until <= now {
+            drop(penalty);
+            self.penalties.remove(key);
+            return None;
+        }
+        Some(match penalty.action {
+            AbuseAction::Throttled => {
+                Verdict::Throttle(penalty.until.duration_since(now).as_secs().max(1))
+            }
+            AbuseAction::ShadowLimited => Verdict::ShadowLimit,
+        })
+    }
+}
+
+/// Case- and whitespace-insensitive, so trivial variations still count as repeats.
+fn fingerprint(text: &str) -> u64 {
+    let normalized = text
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut hasher = DefaultHasher::new();
+    normalized.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Log a new penalty and keep it in `abuse_events` for admins. Best effort.
+pub async fn record(state: &AppState, user_id: &str, conversation_id: &str, offence: &Offence) {
+    tracing::warn!(
+        conversation_id,
+        user_id,
+        kind = %offence.kind,
+        action = %offence.action,
+        detail = %offence.detail,
+        "Abuse detected"
+    );
+    let now = chrono::Utc::now().naive_utc();
+    let event = AbuseEvent {
+        id: Uuid::new_v4().to_string(),
+        user_id: user_id.to_string(),
+        conversation_id: conversation_id.to_string(),
+        kind: offence.kind,
+        action: offence.action,
+        detail: Some(offence.detail.clone()),
+        expires_at: now + chrono::TimeDelta::from_std(offence.duration).unwrap_or_default(),
+        created_at: now,
+    };
+    if let Err(e) = state.db.abuse_event_repo().create(&event).await {
+        tracing::warn!(error = %e, "Failed to record abuse event (non-fatal)");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> AbuseLimits {
+        AbuseLimits {
+            window: Duration::from_secs(60),
+            repeat_threshold: 3,
+            max_chars: 0,
+            max_urls: 3,
+            throttle: Duration::from_secs(30),
+            shadow_limit: Duration::from_secs(30),
+        }
+    }
+
+    fn send(guard: &AbuseGuard, text: &str) -> Verdict {
+        guard.check("conv-1", "user-1", text, &limits()).0
+    }
+
+    #[test]
+    fn short_replies_are_never_repeats() {
+        let guard = AbuseGuard::default();
+        for _ in 0..10 {
+            assert!(matches!(send(&guard, "ok"), Verdict::Allow));
+        }
+    }
+
+    #[test]
+    fn only_an_unbroken_run_is_throttled() {
+        let guard = AbuseGuard::default();
+        let question = "what do you think about that?";
+        for _ in 0..4 {
+            assert!(matches!(send(&guard, question), Verdict::Allow));
+            assert!(matches!(
+                send(&guard, "tell me more please"),
+                Verdict::Allow
+            ));
+        }
+
+        assert!(matches!(send(&guard, question), Verdict::Allow));
+        assert!(matches!(send(&guard, question), Verdict::Allow));
+        assert!(matches!(
+            send(&guard, "What do you   think about THAT?"),
+            Verdict::Throttle(30)
+        ));
+    }
+}
@@ -1,32 +1,117 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 use async_openai::Client;
 use async_openai::config::OpenAIConfig;
 use async_openai::types::chat::{
+    ChatCompletionMessageToolCall, ChatCompletionMessageToolCalls,
     ChatCompletionRequestAssistantMessage, ChatCompletionRequestMessage,
     ChatCompletionRequestMessageContentPartImage, ChatCompletionRequestMessageContentPartText,
-    ChatCompletionRequestSystemMessage, ChatCompletionRequestUserMessage,
-    ChatCompletionRequestUserMessageContent, ChatCompletionRequestUserMessageContentPart,
-    CreateChatCompletionRequestArgs, ImageUrl,
+    ChatCompletionRequestSystemMessage, ChatCompletionRequestToolMessage,
+    ChatCompletionRequestUserMessage, ChatCompletionRequestUserMessageContent,
+    ChatCompletionRequestUserMessageContentPart, ChatCompletionStreamOptions, ChatCompletionTools,
+    CreateChatCompletionRequest, CreateChatCompletionRequestArgs, CreateChatCompletionResponse,
+    ImageUrl, ResponseFormat, ResponseFormatJsonSchema,
 };
+use async_trait::async_trait;
 use base64::Engine;
+use futures::StreamExt;
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use strum::{AsRefStr, Display, EnumString};
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tokio::time::Instant;
 
+use crate::config::{Settings, SharedSettings};
 use crate::error::AppError;
-use crate::models::entities::{Message, MessageRole};
+use crate::models::entities::{Message, MessageRole, ToolInvocation};
+use crate::services::pii::PiiPolicy;
+use crate::services::tools::{ToolBox, ToolKind};
+
+/// Per-request overrides of the client's generation defaults.
+#[derive(Debug, Clone, Default)]
+pub struct GenerationOptions {
+    pub max_tokens: Option<u32>,
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    /// Ask for a reply that is JSON matching this schema (the provider's
+    /// structured-output mode) instead of free text.
+    pub json_schema: Option<ResponseFormatJsonSchema>,
+    /// Tools offered through [`AiApi::generate_with_tools`]
+    pub tools: Vec<ToolKind>,
+}
+
+/// Model, output cap and temperature used when a request doesn't override them.
+#[derive(Debug, Clone)]
+pub struct ModelDefaults {
+    pub model: String,
+    pub max_tokens: u32,
+    pub temperature: f32,
+}
+
+/// Reads a provider's [`ModelDefaults`] out of the settings.
+type PickDefaults = fn(&Settings) -> ModelDefaults;
+
+/// Whether chat completions are recorded to, or replayed from, fixture files.
+#[derive(Debug, Clone, Copy, PartialEq, Display, EnumString, AsRefStr)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum AiFixtureMode {
+    /// Always call the provider.
+    Off,
+    /// Call the provider and write each response to the fixture directory.
+    Record,
+    /// Serve responses from the fixture directory without calling the provider.
+    Replay,
+}
+
+/// What an embedding is for; Gemini embeds documents and the queries run against
+/// them slightly differently.
+#[derive(Debug, Clone, Copy, PartialEq, AsRefStr)]
+pub enum EmbeddingTask {
+    #[strum(serialize = "RETRIEVAL_DOCUMENT")]
+    Document,
+    #[strum(serialize = "RETRIEVAL_QUERY")]
+    Query,
+}
+
+const DEFAULT_EMBEDDING_MODEL: &str = "gemini-embedding-001";
+
+/// Texts per `batchEmbedContents` call, the API's limit.
+const EMBED_BATCH_SIZE: usize = 100;
+
+/// Length embeddings are truncated to; stored vectors must all share it.
+const EMBEDDING_DIMENSIONS: usize = 768;
+
+/// Rounds of tool calls before the model must answer in text.
+const MAX_TOOL_ROUNDS: usize = 3;
+
+/// Seconds a caller turned away by the concurrency cap is told to wait.
+const BUSY_RETRY_AFTER_SECS: u64 = 2;
 
 #[derive(Clone)]
 pub struct AiClient {
     client: Client<OpenAIConfig>,
-    model: String,
-    max_tokens: u32,
-    temperature: f32,
+    defaults: ModelDefaults,
+    // When set, `defaults` is replaced by what these settings hold at call time
+    live_defaults: Option<(SharedSettings, PickDefaults)>,
     configured: bool,
     provider: &'static str,
     // For Gemini transcription (native API, not OpenAI-compatible)
     gemini_api_key: Option<String>,
-    gemini_model: Option<String>,
+    embedding_model: String,
     raw_http: reqwest::Client,
+    fixture_mode: AiFixtureMode,
+    fixture_dir: PathBuf,
+    // Caps concurrent upstream calls; shared by clones
+    permits: Option<Arc<Semaphore>>,
+    queue_timeout: Duration,
+    // Limit on each upstream call once it has a slot; zero waits indefinitely
+    timeout: Duration,
+    // Scrubs user text and memories from outgoing requests
+    pii: PiiPolicy,
 }
 
 impl AiClient {
@@ -36,7 +121,7 @@ impl AiClient {
         model: &str,
         max_tokens: u32,
         temperature: f32,
-        _timeout: u64,
+        timeout_secs: u64,
     ) -> Self {
         let config = OpenAIConfig::new()
             .with_api_key(api_key)
@@ -46,14 +131,23 @@ impl AiClient {
 
         Self {
             client,
-            model: model.to_string(),
-            max_tokens,
-            temperature,
+            defaults: ModelDefaults {
+                model: model.to_string(),
+                max_tokens,
+                temperature,
+            },
+            live_defaults: None,
             configured: !api_key.is_empty(),
             provider: "gemini",
             gemini_api_key: Some(api_key.to_string()),
-            gemini_model: Some(model.to_string()),
+            embedding_model: DEFAULT_EMBEDDING_MODEL.to_string(),
             raw_http: http,
+            fixture_mode: AiFixtureMode::Off,
+            fixture_dir: PathBuf::new(),
+            permits: None,
+            queue_timeout: Duration::ZERO,
+            timeout: Duration::from_secs(timeout_secs),
+            pii: PiiPolicy::default(),
         }
     }
 
@@ -63,7 +157,7 @@ impl AiClient {
         model: &str,
         max_tokens: u32,
         temperature: f32,
-        _timeout: u64,
+        timeout_secs: u64,
     ) -> Self {
         let config = OpenAIConfig::new()
             .with_api_key(api_key)
@@ -81,28 +175,285 @@ impl AiClient {
 
         Self {
             client,
-            model: model.to_string(),
-            max_tokens,
-            temperature,
+            defaults: ModelDefaults {
+                model: model.to_string(),
+                max_tokens,
+                temperature,
+            },
+            live_defaults: None,
             configured: !api_key.is_empty(),
             provider: "openrouter",
             gemini_api_key: None,
-            gemini_model: None,
+            embedding_model: String::new(),
             raw_http: http,
+            fixture_mode: AiFixtureMode::Off,
+            fixture_dir: PathBuf::new(),
+            permits: None,
+            queue_timeout: Duration::ZERO,
+            timeout: Duration::from_secs(timeout_secs),
+            pii: PiiPolicy::default(),
+        }
+    }
+
+    /// Record chat completions to `dir`, or replay them from it, keyed by a hash
+    /// of the provider and the full request. Audio transcription always goes live.
+    pub fn with_fixtures(mut self, mode: AiFixtureMode, dir: &str) -> Self {
+        self.fixture_mode = mode;
+        self.fixture_dir = PathBuf::from(dir);
+        self
+    }
+
+    /// Allow at most `max_concurrent` upstream calls at once. A call that can't get
+    /// a slot within `queue_timeout` fails with a busy error instead of piling onto
+    /// the provider's rate limit. 0 leaves the client uncapped.
+    pub fn with_concurrency_limit(
+        mut self,
+        max_concurrent: usize,
+        queue_timeout: Duration,
+    ) -> Self {
+        self.permits = (max_concurrent > 0).then(|| Arc::new(Semaphore::new(max_concurrent)));
+        self.queue_timeout = queue_timeout;
+        self
+    }
+
+    /// Redact personal data from user and assistant turns and memories before
+    /// they are sent. Callers keep the originals.
+    pub fn with_pii_redaction(mut self, policy: PiiPolicy) -> Self {
+        self.pii = policy;
+        self
+    }
+
+    /// Gemini model used by [`AiApi::embed`]. Vectors from different models can't
+    /// be compared, so changing it means re-embedding stored documents.
+    pub fn with_embedding_model(mut self, model: &str) -> Self {
+        self.embedding_model = model.to_string();
+        self
+    }
+
+    /// Read model, max tokens and temperature from `settings` on every call, so a
+    /// settings reload changes them without rebuilding the client.
+    pub fn with_live_defaults(mut self, settings: SharedSettings, pick: PickDefaults) -> Self {
+        self.live_defaults = Some((settings, pick));
+        self
+    }
+
+    fn defaults(&self) -> Cow<'_, ModelDefaults> {
+        match &self.live_defaults {
+            Some((settings, pick)) => Cow::Owned(pick(&settings.load())),
+            None => Cow::Borrowed(&self.defaults),
+        }
+    }
+
+    async fn acquire_slot(&self) -> Result<Option<SemaphorePermit<'_>>, AppError> {
+        let Some(permits) = &self.permits else {
+            return Ok(None);
+        };
+        match tokio::time::timeout(self.queue_timeout, permits.acquire()).await {
+            Ok(Ok(permit)) => Ok(Some(permit)),
+            _ => {
+                tracing::warn!(provider = self.provider, "AI provider at concurrency cap");
+                Err(AppError::overloaded(
+                    format!(
+                        "AI provider {} is busy. Please retry shortly.",
+                        self.provider
+                    ),
+                    BUSY_RETRY_AFTER_SECS,
+                ))
+            }
         }
     }
 
-    pub fn is_configured(&self) -> bool {
-        self.configured
+    async fn complete(
+        &self,
+        request: CreateChatCompletionRequest,
+    ) -> Result<CreateChatCompletionResponse, AppError> {
+        if self.fixture_mode == AiFixtureMode::Off {
+            let _slot = self.acquire_slot().await?;
+            return self
+                .within_timeout(self.client.chat().create(request))
+                .await?
+                .map_err(|e| AppError::service_unavailable(format!("AI API error: {e}")));
+        }
+
+        let key = serde_json::to_vec(&request).map_err(anyhow::Error::from)?;
+        let hash = hex::encode(
+            Sha256::new()
+                .chain_update(self.provider)
+                .chain_update(&key)
+                .finalize(),
+        );
+        let path = self
+            .fixture_dir
+            .join(format!("{}-{hash}.json", self.provider));
+
+        if self.fixture_mode == AiFixtureMode::Replay {
+            let contents = tokio::fs::read(&path).await.map_err(|e| {
+                tracing::warn!(path = %path.display(), error = %e, "Missing AI fixture");
+                AppError::service_unavailable(format!("No AI fixture for request {hash}"))
+            })?;
+            return serde_json::from_slice(&contents).map_err(|e| {
+                AppError::service_unavailable(format!("Corrupt AI fixture {hash}: {e}"))
+            });
+        }
+
+        let _slot = self.acquire_slot().await?;
+        let response = self
+            .within_timeout(self.client.chat().create(request))
+            .await?
+            .map_err(|e| AppError::service_unavailable(format!("AI API error: {e}")))?;
+        let write = async {
+            tokio::fs::create_dir_all(&self.fixture_dir).await?;
+            tokio::fs::write(&path, serde_json::to_vec_pretty(&response)?).await
+        };
+        if let Err(e) = write.await {
+            tracing::warn!(path = %path.display(), error = %e, "Failed to record AI fixture");
+        }
+        Ok(response)
+    }
+
+    async fn within_timeout<F: Future>(&self, call: F) -> Result<F::Output, AppError> {
+        if self.timeout.is_zero() {
+            return Ok(call.await);
+        }
+        tokio::time::timeout(self.timeout, call)
+            .await
+            .map_err(|_| self.timeout_error())
+    }
+
+    fn timeout_error(&self) -> AppError {
+        tracing::warn!(
+            provider = self.provider,
+            timeout_secs = self.timeout.as_secs(),
+            "AI call timed out"
+        );
+        AppError::ai_timeout(format!(
+            "AI provider {} did not respond within {}s",
+            self.provider,
+            self.timeout.as_secs()
+        ))
+    }
+
+    /// Stream a free-text completion so that hitting the timeout keeps what was
+    /// generated so far; only a timeout before the first token is an error.
+    /// Returns the text and the provider's token count when it reported one.
+    async fn complete_streaming(
+        &self,
+        mut request: CreateChatCompletionRequest,
+    ) -> Result<(String, Option<i32>), AppError> {
+        request.stream_options = Some(ChatCompletionStreamOptions {
+            include_usage: Some(true),
+            include_obfuscation: None,
+        });
+        let _slot = self.acquire_slot().await?;
+        let deadline = (!self.timeout.is_zero()).then(|| Instant::now() + self.timeout);
+
+        let mut stream = before_deadline(deadline, self.client.chat().create_stream(request))
+            .await
+            .ok_or_else(|| self.timeout_error())?
+            .map_err(|e| AppError::service_unavailable(format!("AI API error: {e}")))?;
+        let mut text = String::new();
+        let mut tokens = None;
+        loop {
+            let Some(chunk) = before_deadline(deadline, stream.next()).await else {
+                if text.is_empty() {
+                    return Err(self.timeout_error());
+                }
+                tracing::warn!(
+                    provider = self.provider,
+                    chars = text.chars().count(),
+                    "AI call timed out mid-reply, keeping partial text"
+                );
+                return Ok((text, None));
+            };
+            let Some(chunk) = chunk else { break };
+            let chunk =
+                chunk.map_err(|e| AppError::service_unavailable(format!("AI API error: {e}")))?;
+            if let Some(delta) = chunk
+                .choices
+                .first()
+                .and_then(|c| c.delta.content.as_deref())
+            {
+                text.push_str(delta);
+            }
+            if let Some(usage) = chunk.usage {
+                tokens = Some(usage.total_tokens as i32);
+            }
+        }
+        Ok((text, tokens))
+    }
+
+    /// One `generateContent` call on Gemini's native API (not OpenAI-compatible)
+    /// with `data` inlined after `prompt`; returns the reply's text. `what` names
+    /// the task in errors.
+    async fn gemini_native_generate(
+        &self,
+        prompt: &str,
+        mime_type: &str,
+        data: &[u8],
+        max_output_tokens: u32,
+        what: &str,
+    ) -> Result<String, AppError> {
+        let api_key = self.gemini_api_key.as_deref().ok_or_else(|| {
+            AppError::service_unavailable(format!("Gemini {what} requires Gemini client"))
+        })?;
+        let model = self.model();
+        let b64 = base64::engine::general_purpose::STANDARD.encode(data);
+        let request_body = serde_json::json!({
+            "contents": [{
+                "parts": [
+                    {"text": prompt},
+                    {"inlineData": {"mimeType": mime_type, "data": b64}}
+                ]
+            }],
+            "generationConfig": {"temperature": 0.1, "maxOutputTokens": max_output_tokens}
+        });
+
+        let url = format!(
+            "https://generativelanguage.googleapis.com/v1beta/models/{model}:generateContent"
+        );
+
+        let response = self
+            .raw_http
+            .post(&url)
+            .header("x-goog-api-key", api_key)
+            .timeout(std::time::Duration::from_secs(60))
+            .json(&request_body)
+            .send()
+            .await
+            .map_err(|e| AppError::service_unavailable(format!("Gemini {what} error: {e}")))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            tracing::error!(status = %status, body = %body, what, "Gemini native API error");
+            return Err(AppError::service_unavailable(format!(
+                "Gemini {what} failed"
+            )));
+        }
+
+        let gemini_resp: GeminiNativeResponse = response.json().await.map_err(|e| {
+            AppError::service_unavailable(format!("Failed to parse {what} response: {e}"))
+        })?;
+
+        gemini_resp
+            .candidates
+            .as_ref()
+            .and_then(|c| c.first())
+            .and_then(|c| c.content.parts.as_ref())
+            .and_then(|parts| parts.iter().find_map(|p| p.text.clone()))
+            .map(|t| t.trim().to_string())
+            .ok_or_else(|| AppError::service_unavailable(format!("Empty {what} response")))
     }
 
-    pub async fn generate_response(
+    /// System prompt, history and the current user turn as chat messages, with
+    /// personal data redacted.
+    fn chat_messages(
         &self,
         user_message: &str,
         system_instructions: &str,
         conversation_history: &[Message],
         media_urls: Option<&[String]>,
-    ) -> Result<(String, i32), AppError> {
+    ) -> Vec<ChatCompletionRequestMessage> {
         let mut messages: Vec<ChatCompletionRequestMessage> = Vec::new();
 
         // System message
@@ -117,8 +468,8 @@ impl AiClient {
         for msg in conversation_history {
             match msg.role {
                 MessageRole::User => {
-                    let content =
-                        build_user_content(msg.content.as_deref().unwrap_or(""), &msg.media_urls);
+                    let text = self.pii.redact(msg.content.as_deref().unwrap_or(""));
+                    let content = build_user_content(&text, &msg.media_urls);
                     messages.push(ChatCompletionRequestMessage::User(
                         ChatCompletionRequestUserMessage {
                             content,
@@ -129,8 +480,14 @@ impl AiClient {
                 MessageRole::Assistant => {
                     messages.push(ChatCompletionRequestMessage::Assistant(
                         ChatCompletionRequestAssistantMessage {
-                            content: msg.content.clone().map(Into::into),
-                            name: None,
+                            content: msg
+                                .content
+                                .as_deref()
+                                .map(|c| self.pii.redact(c).into_owned().into()),
+                            name: msg
+                                .speaker_name()
+                                .filter(|n| !n.is_empty())
+                                .map(participant_name),
                             ..Default::default()
                         },
                     ));
@@ -139,7 +496,8 @@ impl AiClient {
         }
 
         // Current user message
-        let current_content = build_user_content(user_message, media_urls.unwrap_or(&[]));
+        let current_content =
+            build_user_content(&self.pii.redact(user_message), media_urls.unwrap_or(&[]));
         messages.push(ChatCompletionRequestMessage::User(
             ChatCompletionRequestUserMessage {
                 content: current_content,
@@ -147,11 +505,256 @@ impl AiClient {
             },
         ));
 
-        let request = CreateChatCompletionRequestArgs::default()
-            .model(&self.model)
+        messages
+    }
+
+    fn request_args(
+        &self,
+        messages: Vec<ChatCompletionRequestMessage>,
+        options: &GenerationOptions,
+    ) -> CreateChatCompletionRequestArgs {
+        let defaults = self.defaults();
+        let mut args = CreateChatCompletionRequestArgs::default();
+        args.model(&defaults.model)
             .messages(messages)
-            .temperature(self.temperature)
-            .max_tokens(self.max_tokens)
+            .temperature(options.temperature.unwrap_or(defaults.temperature))
+            .max_tokens(options.max_tokens.unwrap_or(defaults.max_tokens));
+        if let Some(top_p) = options.top_p {
+            args.top_p(top_p);
+        }
+        if let Some(json_schema) = &options.json_schema {
+            args.response_format(ResponseFormat::JsonSchema {
+                json_schema: json_schema.clone(),
+            });
+        }
+        args
+    }
+
+    /// Plain completions in a loop: each round runs the tools the model called and
+    /// hands back their output, until it answers in text. The last round offers no
+    /// tools so it has to.
+    async fn complete_with_tools(
+        &self,
+        mut messages: Vec<ChatCompletionRequestMessage>,
+        options: &GenerationOptions,
+        toolbox: &ToolBox<'_>,
+    ) -> Result<(String, i32, Vec<ToolInvocation>), AppError> {
+        let tools: Vec<ChatCompletionTools> =
+            options.tools.iter().map(|kind| kind.definition()).collect();
+        let mut invocations = Vec::new();
+        let mut tokens = 0;
+        let mut round = 0;
+        loop {
+            let last_round = round == MAX_TOOL_ROUNDS;
+            round += 1;
+            let mut args = self.request_args(messages.clone(), options);
+            if !last_round {
+                args.tools(tools.clone());
+            }
+            let request = args.build().map_err(|e| {
+                AppError::service_unavailable(format!("Failed to build request: {e}"))
+            })?;
+            let response = self.complete(request).await?;
+            tokens += response.usage.as_ref().map_or(0, |u| u.total_tokens as i32);
+            let message = response
+                .choices
+                .into_iter()
+                .next()
+                .ok_or_else(|| AppError::service_unavailable("Empty response from AI"))?
+                .message;
+            let calls: Vec<ChatCompletionMessageToolCall> = message
+                .tool_calls
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|call| match call {
+                    ChatCompletionMessageToolCalls::Function(call) => Some(call),
+                    ChatCompletionMessageToolCalls::Custom(_) => None,
+                })
+                .collect();
+            if calls.is_empty() || last_round {
+                let text = message.content.unwrap_or_default();
+                if tokens == 0 {
+                    tokens = estimate_tokens(&text);
+                }
+                return Ok((text, tokens, invocations));
+            }
+
+            messages.push(ChatCompletionRequestMessage::Assistant(
+                ChatCompletionRequestAssistantMessage {
+                    content: message.content.map(Into::into),
+                    tool_calls: Some(
+                        calls
+                            .iter()
+                            .cloned()
+                            .map(ChatCompletionMessageToolCalls::Function)
+                            .collect(),
+                    ),
+                    ..Default::default()
+                },
+            ));
+            for call in calls {
+                let started = Instant::now();
+                let result = toolbox
+                    .call(&call.function.name, &call.function.arguments)
+                    .await;
+                tracing::info!(
+                    provider = self.provider,
+                    tool = call.function.name,
+                    ok = result.is_ok(),
+                    "AI tool call"
+                );
+                let output = match &result {
+                    Ok(output) => output.clone(),
+                    Err(error) => format!("Error: {error}"),
+                };
+                messages.push(ChatCompletionRequestMessage::Tool(
+                    ChatCompletionRequestToolMessage {
+                        content: output.into(),
+                        tool_call_id: call.id,
+                    },
+                ));
+                let (result, error) = match result {
+                    Ok(output) => (Some(output), None),
+                    Err(error) => (None, Some(error)),
+                };
+                invocations.push(ToolInvocation {
+                    name: call.function.name,
+                    arguments: serde_json::from_str(&call.function.arguments)
+                        .unwrap_or(serde_json::Value::String(call.function.arguments)),
+                    result,
+                    error,
+                    duration_ms: started.elapsed().as_millis() as i64,
+                });
+            }
+        }
+    }
+}
+
+/// `None` when `deadline` passes first.
+async fn before_deadline<F: Future>(deadline: Option<Instant>, call: F) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, call).await.ok(),
+        None => Some(call.await),
+    }
+}
+
+/// Chat-completion provider behind the handlers. [`AiClient`] implements it for
+/// Gemini and OpenRouter; tests can substitute a fake.
+#[async_trait]
+pub trait AiApi: Send + Sync {
+    fn is_configured(&self) -> bool;
+    fn provider(&self) -> &'static str;
+    fn model(&self) -> String;
+    fn max_tokens(&self) -> u32;
+
+    async fn generate_response(
+        &self,
+        user_message: &str,
+        system_instructions: &str,
+        conversation_history: &[Message],
+        media_urls: Option<&[String]>,
+    ) -> Result<(String, i32), AppError> {
+        self.generate_response_with(
+            user_message,
+            system_instructions,
+            conversation_history,
+            media_urls,
+            &GenerationOptions::default(),
+        )
+        .await
+    }
+
+    async fn generate_response_with(
+        &self,
+        user_message: &str,
+        system_instructions: &str,
+        conversation_history: &[Message],
+        media_urls: Option<&[String]>,
+        options: &GenerationOptions,
+    ) -> Result<(String, i32), AppError>;
+
+    /// [`generate_response_with`](Self::generate_response_with), letting the model
+    /// call the tools in `options.tools` through `toolbox` first. Also returns the
+    /// calls it made.
+    async fn generate_with_tools(
+        &self,
+        user_message: &str,
+        system_instructions: &str,
+        conversation_history: &[Message],
+        media_urls: Option<&[String]>,
+        options: &GenerationOptions,
+        _toolbox: &ToolBox<'_>,
+    ) -> Result<(String, i32, Vec<ToolInvocation>), AppError> {
+        let (text, tokens) = self
+            .generate_response_with(
+                user_message,
+                system_instructions,
+                conversation_history,
+                media_urls,
+                options,
+            )
+            .await?;
+        Ok((text, tokens, Vec::new()))
+    }
+
+    /// Transcribe audio using Gemini's native API (not OpenAI-compatible).
+    /// Only works on AiClient instances created with `AiClient::gemini()`.
+    async fn transcribe_audio(&self, audio_url: &str) -> Result<String, AppError>;
+
+    /// Read the text out of a document such as a PDF with Gemini's native API.
+    /// Only works on AiClient instances created with `AiClient::gemini()`.
+    async fn extract_text(&self, data: &[u8], mime_type: &str) -> Result<String, AppError>;
+
+    /// Embedding vector for each of `texts`, in order. Only works on AiClient
+    /// instances created with `AiClient::gemini()`.
+    async fn embed(&self, texts: &[String], task: EmbeddingTask)
+    -> Result<Vec<Vec<f32>>, AppError>;
+
+    async fn extract_memories(
+        &self,
+        user_message: &str,
+        assistant_response: &str,
+        existing_memories: &HashMap<String, String>,
+    ) -> Result<HashMap<String, String>, AppError>;
+
+    /// Translate a chat message into `target_language`, keeping tone and emoji.
+    async fn translate(&self, text: &str, target_language: &str) -> Result<String, AppError>;
+}
+
+#[async_trait]
+impl AiApi for AiClient {
+    fn is_configured(&self) -> bool {
+        self.configured || self.fixture_mode == AiFixtureMode::Replay
+    }
+
+    fn provider(&self) -> &'static str {
+        self.provider
+    }
+
+    fn model(&self) -> String {
+        self.defaults().model.clone()
+    }
+
+    fn max_tokens(&self) -> u32 {
+        self.defaults().max_tokens
+    }
+
+    async fn generate_response_with(
+        &self,
+        user_message: &str,
+        system_instructions: &str,
+        conversation_history: &[Message],
+        media_urls: Option<&[String]>,
+        options: &GenerationOptions,
+    ) -> Result<(String, i32), AppError> {
+        let messages = self.chat_messages(
+            user_message,
+            system_instructions,
+            conversation_history,
+            media_urls,
+        );
+        let request = self
+            .request_args(messages, options)
             .build()
             .map_err(|e| AppError::service_unavailable(format!("Failed to build request: {e}")))?;
 
@@ -160,41 +763,78 @@ impl AiClient {
             .as_ref()
             .map(|p| p.start_child("ai.generate", self.provider));
 
-        let response = self
-            .client
-            .chat()
-            .create(request)
-            .await
-            .map_err(|e| AppError::service_unavailable(format!("AI API error: {e}")));
+        // Partial JSON is useless, so structured replies and fixtures use a plain call
+        let completion = if self.fixture_mode == AiFixtureMode::Off && options.json_schema.is_none()
+        {
+            self.complete_streaming(request).await
+        } else {
+            self.complete(request).await.and_then(|response| {
+                let choice = response
+                    .choices
+                    .first()
+                    .ok_or_else(|| AppError::service_unavailable("Empty response from AI"))?;
+                Ok((
+                    choice.message.content.clone().unwrap_or_default(),
+                    response.usage.map(|u| u.total_tokens as i32),
+                ))
+            })
+        };
 
         if let Some(span) = sentry_span {
             span.finish();
         }
-        let response = response?;
-
-        let choice = response
-            .choices
-            .first()
-            .ok_or_else(|| AppError::service_unavailable("Empty response from AI"))?;
+        let (text, tokens) = completion?;
+        let token_count = tokens.unwrap_or_else(|| estimate_tokens(&text));
 
-        let text = choice.message.content.clone().unwrap_or_default();
+        Ok((text, token_count))
+    }
 
-        let token_count = response
-            .usage
-            .map(|u| u.total_tokens as i32)
-            .unwrap_or_else(|| estimate_tokens(&text));
+    async fn generate_with_tools(
+        &self,
+        user_message: &str,
+        system_instructions: &str,
+        conversation_history: &[Message],
+        media_urls: Option<&[String]>,
+        options: &GenerationOptions,
+        toolbox: &ToolBox<'_>,
+    ) -> Result<(String, i32, Vec<ToolInvocation>), AppError> {
+        if options.tools.is_empty() {
+            let (text, tokens) = self
+                .generate_response_with(
+                    user_message,
+                    system_instructions,
+                    conversation_history,
+                    media_urls,
+                    options,
+                )
+                .await?;
+            return Ok((text, tokens, Vec::new()));
+        }
+        let messages = self.chat_messages(
+            user_message,
+            system_instructions,
+            conversation_history,
+            media_urls,
+        );
 
-        Ok((text, token_count))
+        let parent = sentry::configure_scope(|s| s.get_span());
+        let sentry_span = parent
+            .as_ref()
+            .map(|p| p.start_child("ai.generate", self.provider));
+        let result = self.complete_with_tools(messages, options, toolbox).await;
+        if let Some(span) = sentry_span {
+            span.finish();
+        }
+        result
     }
 
-    /// Transcribe audio using Gemini's native API (not OpenAI-compatible).
-    /// Only works on AiClient instances created with `AiClient::gemini()`.
-    pub async fn transcribe_audio(&self, audio_url: &str) -> Result<String, AppError> {
-        let api_key = self
-            .gemini_api_key
-            .as_deref()
-            .ok_or_else(|| AppError::service_unavailable("Transcription requires Gemini client"))?;
-        let model = self.gemini_model.as_deref().unwrap_or("gemini-2.5-flash");
+    async fn transcribe_audio(&self, audio_url: &str) -> Result<String, AppError> {
+        if self.gemini_api_key.is_none() {
+            return Err(AppError::service_unavailable(
+                "Transcription requires Gemini client",
+            ));
+        }
+        let _slot = self.acquire_slot().await?;
 
         // Download audio
         let resp = self
@@ -217,66 +857,104 @@ impl AiClient {
             .await
             .map_err(|e| AppError::service_unavailable(format!("Failed to read audio: {e}")))?;
 
-        let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
+        self.gemini_native_generate(
+            "Please transcribe this audio file accurately. Only return the transcription text without any additional commentary.",
+            &content_type,
+            &bytes,
+            4096,
+            "transcription",
+        )
+        .await
+    }
 
-        // Call native Gemini API for transcription
-        let request_body = serde_json::json!({
-            "contents": [{
-                "parts": [
-                    {"text": "Please transcribe this audio file accurately. Only return the transcription text without any additional commentary."},
-                    {"inlineData": {"mimeType": content_type, "data": b64}}
-                ]
-            }],
-            "generationConfig": {"temperature": 0.1, "maxOutputTokens": 4096}
-        });
+    async fn extract_text(&self, data: &[u8], mime_type: &str) -> Result<String, AppError> {
+        let _slot = self.acquire_slot().await?;
+        self.gemini_native_generate(
+            "Extract all readable text from this document, in reading order. Keep headings and \
+             list structure as plain text. Only return the text without any additional commentary.",
+            mime_type,
+            data,
+            65536,
+            "text extraction",
+        )
+        .await
+    }
 
+    async fn embed(
+        &self,
+        texts: &[String],
+        task: EmbeddingTask,
+    ) -> Result<Vec<Vec<f32>>, AppError> {
+        let api_key = self
+            .gemini_api_key
+            .as_deref()
+            .ok_or_else(|| AppError::service_unavailable("Embeddings require Gemini client"))?;
+        let model = &self.embedding_model;
         let url = format!(
-            "https://generativelanguage.googleapis.com/v1beta/models/{model}:generateContent"
+            "https://generativelanguage.googleapis.com/v1beta/models/{model}:batchEmbedContents"
         );
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(EMBED_BATCH_SIZE) {
+            let requests: Vec<serde_json::Value> = batch
+                .iter()
+                .map(|text| {
+                    serde_json::json!({
+                        "model": format!("models/{model}"),
+                        "content": {"parts": [{"text": text}]},
+                        "taskType": task.as_ref(),
+                        "outputDimensionality": EMBEDDING_DIMENSIONS
+                    })
+                })
+                .collect();
+            let _slot = self.acquire_slot().await?;
+            let response = self
+                .raw_http
+                .post(&url)
+                .header("x-goog-api-key", api_key)
+                .timeout(std::time::Duration::from_secs(60))
+                .json(&serde_json::json!({ "requests": requests }))
+                .send()
+                .await
+                .map_err(|e| {
+                    AppError::service_unavailable(format!("Gemini embedding error: {e}"))
+                })?;
 
-        let response = self
-            .raw_http
-            .post(&url)
-            .header("x-goog-api-key", api_key)
-            .timeout(std::time::Duration::from_secs(60))
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| {
-                AppError::service_unavailable(format!("Gemini transcription error: {e}"))
-            })?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                tracing::error!(status = %status, body = %body, "Gemini embedding error");
+                return Err(AppError::service_unavailable("Embedding failed"));
+            }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            tracing::error!(status = %status, body = %body, "Gemini transcription error");
-            return Err(AppError::service_unavailable("Audio transcription failed"));
+            let parsed: GeminiEmbedResponse = response.json().await.map_err(|e| {
+                AppError::service_unavailable(format!("Failed to parse embedding response: {e}"))
+            })?;
+            if parsed.embeddings.len() != batch.len() {
+                return Err(AppError::service_unavailable(
+                    "Embedding response is missing vectors",
+                ));
+            }
+            embeddings.extend(parsed.embeddings.into_iter().map(|e| e.values));
         }
-
-        let gemini_resp: GeminiNativeResponse = response.json().await.map_err(|e| {
-            AppError::service_unavailable(format!("Failed to parse transcription response: {e}"))
-        })?;
-
-        gemini_resp
-            .candidates
-            .as_ref()
-            .and_then(|c| c.first())
-            .and_then(|c| c.content.parts.as_ref())
-            .and_then(|parts| parts.iter().find_map(|p| p.text.clone()))
-            .map(|t| t.trim().to_string())
-            .ok_or_else(|| AppError::service_unavailable("Empty transcription response"))
+        Ok(embeddings)
     }
 
-    pub async fn extract_memories(
+    async fn extract_memories(
         &self,
         user_message: &str,
         assistant_response: &str,
         existing_memories: &HashMap<String, String>,
     ) -> Result<HashMap<String, String>, AppError> {
-        let memories_text = if existing_memories.is_empty() {
+        let user_message = self.pii.redact(user_message);
+        let assistant_response = self.pii.redact(assistant_response);
+        let sent_memories: HashMap<&str, Cow<'_, str>> = existing_memories
+            .iter()
+            .map(|(k, v)| (k.as_str(), self.pii.redact(v)))
+            .collect();
+        let memories_text = if sent_memories.is_empty() {
             "(none)".to_string()
         } else {
-            existing_memories
+            sent_memories
                 .iter()
                 .map(|(k, v)| format!("- {k}: {v}"))
                 .collect::<Vec<_>>()
@@ -306,7 +984,7 @@ Format: {{"key1": "value1", "key2": "value2"}}"#
         );
 
         let request = CreateChatCompletionRequestArgs::default()
-            .model(&self.model)
+            .model(self.model())
             .messages(vec![ChatCompletionRequestMessage::User(
                 ChatCompletionRequestUserMessage {
                     content: ChatCompletionRequestUserMessageContent::Text(prompt),
@@ -318,7 +996,7 @@ Format: {{"key1": "value1", "key2": "value2"}}"#
             .build()
             .map_err(|e| AppError::service_unavailable(format!("Failed to build request: {e}")))?;
 
-        let response = match self.client.chat().create(request).await {
+        let response = match self.complete(request).await {
             Ok(r) => r,
             Err(e) => {
                 tracing::error!(error = %e, "Memory extraction API error");
@@ -332,10 +1010,73 @@ Format: {{"key1": "value1", "key2": "value2"}}"#
             .and_then(|c| c.message.content.clone())
             .unwrap_or_default();
 
-        parse_memory_json(&text, existing_memories)
+        let mut updated = parse_memory_json(&text, existing_memories)?;
+        // A memory the model repeated back redacted keeps the original held locally
+        for (key, value) in &mut updated {
+            if let Some(original) = existing_memories.get(key)
+                && sent_memories
+                    .get(key.as_str())
+                    .is_some_and(|sent| sent == value)
+            {
+                value.clone_from(original);
+            }
+        }
+        Ok(updated)
+    }
+
+    async fn translate(&self, text: &str, target_language: &str) -> Result<String, AppError> {
+        let prompt = format!(
+            r#"Translate the following chat message into {target_language}.
+Keep the tone, slang level, emoji and formatting. Do not add explanations, quotes or notes.
+If the message is already in {target_language}, return it unchanged.
+
+Message:
+{text}"#
+        );
+
+        let defaults = self.defaults();
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&defaults.model)
+            .messages(vec![ChatCompletionRequestMessage::User(
+                ChatCompletionRequestUserMessage {
+                    content: ChatCompletionRequestUserMessageContent::Text(prompt),
+                    name: None,
+                },
+            )])
+            .temperature(0.2f32)
+            .max_tokens(defaults.max_tokens)
+            .build()
+            .map_err(|e| AppError::service_unavailable(format!("Failed to build request: {e}")))?;
+
+        let response = self.complete(request).await?;
+
+        let translated = response
+            .choices
+            .first()
+            .and_then(|c| c.message.content.clone())
+            .unwrap_or_default();
+        if translated.trim().is_empty() {
+            return Err(AppError::service_unavailable("Empty response from AI"));
+        }
+        Ok(translated.trim().to_string())
     }
 }
 
+/// Chat APIs only accept `[A-Za-z0-9_-]` in a message's `name`, up to 64 characters.
+fn participant_name(display_name: &str) -> String {
+    display_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(64)
+        .collect()
+}
+
 fn build_user_content(
     text: &str,
     media_urls: &[String],
@@ -391,11 +1132,12 @@ fn parse_memory_json(
     Ok(merged)
 }
 
-fn estimate_tokens(text: &str) -> i32 {
+/// Rough token count (~4 bytes per token) for when the provider reports no usage.
+pub fn estimate_tokens(text: &str) -> i32 {
     (text.len() as f64 / 4.0).ceil() as i32
 }
 
-// Minimal types for Gemini native API (transcription only)
+// Minimal types for Gemini native API (generateContent and embeddings)
 #[derive(Deserialize)]
 struct GeminiNativeResponse {
     candidates: Option<Vec<GeminiCandidate>>,
@@ -415,3 +1157,14 @@ struct GeminiContent {
 struct GeminiPart {
     text: Option<String>,
 }
+
+#[derive(Deserialize)]
+struct GeminiEmbedResponse {
+    #[serde(default)]
+    embeddings: Vec<GeminiEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct GeminiEmbedding {
+    values: Vec<f32>,
+}
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::config::Settings;
+use crate::db::Database;
+use crate::models::entities::{AIInfluencer, AiSample, Message};
+use crate::services::pii::PiiPolicy;
+
+/// Picks a share of AI turns and stores them, PII-scrubbed, in `ai_samples` so bad
+/// replies can be debugged against the prompt that produced them.
+pub struct AiSampler {
+    enabled: bool,
+    default_rate: f64,
+    scrubber: PiiPolicy,
+}
+
+/// A finished turn as sent to the provider.
+pub struct SampledTurn<'a> {
+    pub influencer_id: &'a str,
+    pub conversation_id: &'a str,
+    pub provider: &'a str,
+    pub model: &'a str,
+    pub system_prompt: &'a str,
+    pub history: &'a [Message],
+    pub user_message: &'a str,
+    pub reply: Result<&'a str, String>,
+    pub latency: Duration,
+}
+
+impl AiSampler {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            enabled: settings.ai_sample_logging_enabled,
+            default_rate: settings.ai_sample_rate,
+            scrubber: PiiPolicy::all(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Share of the influencer's turns that are recorded.
+    pub fn rate_for(&self, influencer: &AIInfluencer) -> f64 {
+        influencer
+            .ai_sample_rate()
+            .unwrap_or(self.default_rate)
+            .clamp(0.0, 1.0)
+    }
+
+    /// Whether to record the current turn.
+    pub fn pick(&self, influencer: &AIInfluencer) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let roll = Uuid::new_v4().as_u128() as f64 / u128::MAX as f64;
+        roll < self.rate_for(influencer)
+    }
+
+    /// Scrub the turn and write it in the background; failures are only logged.
+    pub fn record(&self, db: &Database, turn: SampledTurn<'_>) {
+        let scrub = |text: &str| self.scrubber.redact(text).into_owned();
+        let history: Vec<serde_json::Value> = turn
+            .history
+            .iter()
+            .map(|m| {
+                serde_json::json!({
+                    "role": m.role,
+                    "content": m.content.as_deref().map(scrub),
+                })
+            })
+            .collect();
+        let (response, error) = match turn.reply {
+            Ok(text) => (Some(scrub(text)), None),
+            Err(e) => (None, Some(scrub(&e))),
+        };
+        let sample = AiSample {
+            id: Uuid::new_v4().to_string(),
+            influencer_id: turn.influencer_id.to_string(),
+            conversation_id: turn.conversation_id.to_string(),
+            provider: turn.provider.to_string(),
+            model: turn.model.to_string(),
+            system_prompt: scrub(turn.system_prompt),
+            history: history.into(),
+            user_message: scrub(turn.user_message),
+            response,
+            error,
+            latency_ms: turn.latency.as_millis() as i64,
+            created_at: chrono::Utc::now().naive_utc(),
+        };
+
+        let repo = db.ai_sample_repo();
+        tokio::spawn(async move {
+            if let Err(e) = repo.create(&sample).await {
+                tracing::warn!(error = %e, influencer_id = %sample.influencer_id, "Failed to store AI sample");
+            }
+        });
+    }
+}
+
+/// Hourly, drop samples older than `retention_days`.
+pub fn spawn_ai_sample_pruner(state: Arc<AppState>, retention_days: i64) {
+    tokio::spawn(async move {
+        let interval = Duration::from_secs(3600);
+        loop {
+            tokio::time::sleep(interval).await;
+            let cutoff = chrono::Utc::now().naive_utc() - chrono::Duration::days(retention_days);
+            match state.db.ai_sample_repo().prune_before(cutoff).await {
+                Ok(0) => {}
+                Ok(pruned) => tracing::info!(pruned, "Pruned AI samples"),
+                Err(e) => tracing::warn!(error = %e, "Failed to prune AI samples"),
+            }
+        }
+    });
+}
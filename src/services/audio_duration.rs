@@ -0,0 +1,199 @@
+//! Duration of uploaded audio, read from container headers so clients can't
+//! under-report it. Covers the formats uploads accept: mp3, m4a, wav and ogg.
+
+/// Seconds of audio in `bytes`, or `None` if the container can't be read.
+/// `ext` is the upload's extension including the dot (`.mp3`).
+pub fn probe_duration(bytes: &[u8], ext: &str) -> Option<f64> {
+    let seconds = match ext {
+        ".wav" => wav_duration(bytes),
+        ".m4a" => mp4_duration(bytes),
+        ".ogg" => ogg_duration(bytes),
+        ".mp3" => mp3_duration(bytes),
+        _ => None,
+    }?;
+    (seconds.is_finite() && seconds >= 0.0).then_some(seconds)
+}
+
+fn u16_le(b: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(b.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_le(b: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(b.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn u32_be(b: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(b.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn u64_be(b: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(b.get(at..at + 8)?.try_into().ok()?))
+}
+
+fn i64_le(b: &[u8], at: usize) -> Option<i64> {
+    Some(i64::from_le_bytes(b.get(at..at + 8)?.try_into().ok()?))
+}
+
+// ── WAV: data chunk size / byte rate from the fmt chunk ──
+
+fn wav_duration(b: &[u8]) -> Option<f64> {
+    if b.get(0..4)? != b"RIFF" || b.get(8..12)? != b"WAVE" {
+        return None;
+    }
+    let mut pos = 12;
+    let mut byte_rate = None;
+    while pos + 8 <= b.len() {
+        let id = &b[pos..pos + 4];
+        let size = u32_le(b, pos + 4)? as usize;
+        match id {
+            b"fmt " => byte_rate = u32_le(b, pos + 16),
+            b"data" => {
+                let byte_rate = byte_rate.filter(|r| *r > 0)?;
+                // Streamed WAVs may leave the size at 0 or 0xFFFFFFFF; fall back to what we have
+                let data = if size == 0 || pos + 8 + size > b.len() {
+                    b.len() - pos - 8
+                } else {
+                    size
+                };
+                return Some(data as f64 / byte_rate as f64);
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even size
+        pos += 8 + size + (size & 1);
+    }
+    None
+}
+
+// ── MP4/M4A: timescale and duration from moov/mvhd ──
+
+fn mp4_duration(b: &[u8]) -> Option<f64> {
+    let moov = find_box(b, b"moov")?;
+    let mvhd = find_box(moov, b"mvhd")?;
+    let (timescale, duration) = match *mvhd.first()? {
+        0 => (u32_be(mvhd, 12)?, u32_be(mvhd, 16)? as u64),
+        1 => (u32_be(mvhd, 20)?, u64_be(mvhd, 24)?),
+        _ => return None,
+    };
+    (timescale > 0).then(|| duration as f64 / timescale as f64)
+}
+
+/// Body of the first box of type `kind` directly inside `b`.
+fn find_box<'a>(b: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut pos = 0;
+    while pos + 8 <= b.len() {
+        let mut size = u32_be(b, pos)? as usize;
+        let mut header = 8;
+        if size == 1 {
+            size = usize::try_from(u64_be(b, pos + 8)?).ok()?;
+            header = 16;
+        } else if size == 0 {
+            size = b.len() - pos;
+        }
+        if size < header || pos + size > b.len() {
+            return None;
+        }
+        if &b[pos + 4..pos + 8] == kind {
+            return Some(&b[pos + header..pos + size]);
+        }
+        pos += size;
+    }
+    None
+}
+
+// ── Ogg (Vorbis/Opus): last page's granule position / sample rate ──
+
+fn ogg_duration(b: &[u8]) -> Option<f64> {
+    if b.get(0..4)? != b"OggS" {
+        return None;
+    }
+    // First page holds the codec identification packet
+    let segments = *b.get(26)? as usize;
+    let packet = b.get(27 + segments..)?;
+    let (rate, pre_skip) = if packet.starts_with(b"\x01vorbis") {
+        (u32_le(packet, 12)? as f64, 0)
+    } else if packet.starts_with(b"OpusHead") {
+        // Opus granules always count 48 kHz samples
+        (48_000.0, u16_le(packet, 10)? as i64)
+    } else {
+        return None;
+    };
+
+    let last_page = b.windows(4).rposition(|w| w == b"OggS")?;
+    let granule = i64_le(b, last_page + 6)?;
+    (rate > 0.0 && granule >= 0).then(|| (granule - pre_skip).max(0) as f64 / rate)
+}
+
+// ── MP3: Xing/Info or VBRI frame count, else a constant-bitrate estimate ──
+
+const MP3_BITRATES_V1_L3: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+const MP3_BITRATES_V2_L3: [u32; 15] =
+    [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+const MP3_SAMPLE_RATES: [u32; 3] = [44_100, 48_000, 32_000];
+
+fn mp3_duration(b: &[u8]) -> Option<f64> {
+    // Skip an ID3v2 tag; its size is syncsafe (7 bits per byte)
+    let mut pos = 0;
+    if b.get(0..3)? == b"ID3" {
+        let size = b
+            .get(6..10)?
+            .iter()
+            .fold(0usize, |acc, &x| (acc << 7) | (x & 0x7f) as usize);
+        pos = 10 + size;
+    }
+    // Find the first frame sync
+    while pos + 4 <= b.len() && !(b[pos] == 0xff && b[pos + 1] & 0xe0 == 0xe0) {
+        pos += 1;
+    }
+    let header = u32_be(b, pos)?;
+
+    // 3 = MPEG 1, 2 = MPEG 2, 0 = MPEG 2.5
+    let version = (header >> 19) & 0b11;
+    let layer = (header >> 17) & 0b11;
+    let bitrate_index = ((header >> 12) & 0b1111) as usize;
+    let rate_index = ((header >> 10) & 0b11) as usize;
+    let mono = (header >> 6) & 0b11 == 0b11;
+    if version == 1 || layer != 0b01 || bitrate_index == 0 || bitrate_index == 15 || rate_index == 3
+    {
+        return None;
+    }
+    let mpeg1 = version == 3;
+    let sample_rate = MP3_SAMPLE_RATES[rate_index]
+        >> if mpeg1 {
+            0
+        } else if version == 2 {
+            1
+        } else {
+            2
+        };
+    let samples_per_frame: f64 = if mpeg1 { 1152.0 } else { 576.0 };
+
+    // Xing/Info sits after the side information
+    let side_info = match (mpeg1, mono) {
+        (true, false) => 32,
+        (true, true) | (false, false) => 17,
+        (false, true) => 9,
+    };
+    let xing = pos + 4 + side_info;
+    if let Some(tag) = b.get(xing..xing + 4)
+        && (tag == b"Xing" || tag == b"Info")
+        && u32_be(b, xing + 4)? & 0x1 != 0
+    {
+        let frames = u32_be(b, xing + 8)?;
+        return Some(frames as f64 * samples_per_frame / sample_rate as f64);
+    }
+    let vbri = pos + 4 + 32;
+    if b.get(vbri..vbri + 4) == Some(b"VBRI") {
+        let frames = u32_be(b, vbri + 14)?;
+        return Some(frames as f64 * samples_per_frame / sample_rate as f64);
+    }
+
+    let kbps = if mpeg1 {
+        MP3_BITRATES_V1_L3[bitrate_index]
+    } else {
+        MP3_BITRATES_V2_L3[bitrate_index]
+    };
+    Some((b.len() - pos) as f64 * 8.0 / (kbps as f64 * 1000.0))
+}
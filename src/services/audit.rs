@@ -0,0 +1,59 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::middleware::RequestId;
+use crate::models::entities::{AuditAction, AuditEntry};
+
+/// Actor recorded for requests authorized by `X-Admin-Key`.
+pub const ADMIN_ACTOR: &str = "admin";
+
+/// A change to record; `before`/`after` come from [`snapshot`].
+pub struct AuditEvent {
+    pub action: AuditAction,
+    pub target_type: &'static str,
+    pub target_id: Option<String>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+impl AuditEvent {
+    pub fn new(action: AuditAction, target_type: &'static str, target_id: &str) -> Self {
+        Self {
+            action,
+            target_type,
+            target_id: Some(target_id.to_string()),
+            before: None,
+            after: None,
+        }
+    }
+}
+
+pub fn snapshot<T: Serialize>(value: &T) -> Option<serde_json::Value> {
+    serde_json::to_value(value).ok()
+}
+
+/// Write an audit entry. Failures are logged rather than returned: the change
+/// itself already happened and the caller shouldn't see an error for it.
+pub async fn record(db: &Database, actor: &str, request_id: &RequestId, event: AuditEvent) {
+    let entry = AuditEntry {
+        id: Uuid::new_v4().to_string(),
+        actor: actor.to_string(),
+        action: event.action,
+        target_type: event.target_type.to_string(),
+        target_id: event.target_id,
+        before: event.before,
+        after: event.after,
+        request_id: request_id.0.clone(),
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+    if let Err(e) = db.audit_log_repo().create(&entry).await {
+        tracing::error!(
+            error = %e,
+            actor = %entry.actor,
+            action = %entry.action,
+            target_id = ?entry.target_id,
+            "Failed to write audit log entry"
+        );
+    }
+}
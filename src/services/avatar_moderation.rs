@@ -0,0 +1,170 @@
+use async_openai::types::chat::ResponseFormatJsonSchema;
+use serde::Deserialize;
+
+use crate::AppState;
+use crate::error::AppError;
+use crate::models::entities::{AIInfluencer, AvatarModerationEntry, AvatarVerdict};
+use crate::services::ai::GenerationOptions;
+use crate::services::character_generator::parse_json_from_response;
+
+const CHECK_SYSTEM: &str = "You review profile pictures for a public chat app that \
+anyone, including teenagers, can browse. Judge only what is visible in the image.";
+
+const CHECK_PROMPT: &str = "Is this avatar safe to show publicly? Flag it if it shows \
+nudity or sexualised content, graphic violence, gore, weapons pointed at the viewer, \
+hate symbols, drugs, self-harm, or real-looking minors in any suggestive context. \
+Answer with JSON: {\"safe\": true|false, \"reason\": \"short reason when not safe\"}.";
+
+/// Appended to the avatar prompt once an attempt has been flagged.
+const SAFE_STYLE: &str = "modest clothing, friendly expression, family-friendly, \
+no nudity, no violence, no weapons, no text or logos";
+
+#[derive(Deserialize)]
+struct CheckResult {
+    safe: bool,
+    reason: Option<String>,
+}
+
+/// Structured-output schema for [`CHECK_PROMPT`], mirroring [`CheckResult`].
+fn check_schema() -> ResponseFormatJsonSchema {
+    ResponseFormatJsonSchema {
+        description: Some("Avatar safety verdict".into()),
+        name: "avatar_safety".into(),
+        schema: Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "safe": {"type": "boolean"},
+                "reason": {"type": "string", "nullable": true}
+            },
+            "required": ["safe"]
+        })),
+        strict: None,
+    }
+}
+
+/// Ask the vision model whether the image at `url` (a storage key or URL) is fit
+/// for the public influencer list.
+async fn check(state: &AppState, url: &str) -> Result<CheckResult, AppError> {
+    let image = state.storage.generate_presigned_url(url).await;
+    let options = GenerationOptions {
+        temperature: Some(0.0),
+        json_schema: Some(check_schema()),
+        ..Default::default()
+    };
+    let (text, _) = state
+        .gemini
+        .generate_response_with(CHECK_PROMPT, CHECK_SYSTEM, &[], Some(&[image]), &options)
+        .await?;
+    parse_json_from_response(&text)
+        .ok_or_else(|| AppError::service_unavailable("Avatar check returned malformed JSON"))
+}
+
+/// Avatar prompt for generation `attempt`, from 1. Retries add [`SAFE_STYLE`] and
+/// leave out the creator's description, the usual source of a flagged image.
+pub fn avatar_prompt(influencer: &AIInfluencer, attempt: u32) -> String {
+    if attempt > 1 {
+        return format!(
+            "Professional avatar portrait, high quality, {}, {SAFE_STYLE}",
+            influencer.display_name
+        );
+    }
+    format!(
+        "Professional avatar portrait, high quality, {}{}",
+        influencer.display_name,
+        influencer
+            .description
+            .as_deref()
+            .map(|d| format!(", {d}"))
+            .unwrap_or_default()
+    )
+}
+
+/// Checks an influencer's avatars and records each verdict in
+/// `metadata.avatar_moderation`. A check that errors keeps the avatar as
+/// `unchecked` rather than leaving the influencer without one.
+pub struct AvatarModerator<'a> {
+    state: &'a AppState,
+    influencer: &'a AIInfluencer,
+    history: Vec<AvatarModerationEntry>,
+}
+
+impl<'a> AvatarModerator<'a> {
+    pub fn new(state: &'a AppState, influencer: &'a AIInfluencer) -> Self {
+        Self {
+            state,
+            influencer,
+            history: influencer.avatar_moderation(),
+        }
+    }
+
+    /// Generate an avatar, regenerating with a safer prompt while the check flags
+    /// it, up to `max_attempts` images. `None` when none came out safe.
+    pub async fn generate(&mut self, max_attempts: u32) -> Option<String> {
+        for attempt in 1..=max_attempts.max(1) {
+            let prompt = avatar_prompt(self.influencer, attempt);
+            let url = match self.state.replicate.generate_image(&prompt, "1:1").await {
+                Ok(Some(url)) => url,
+                Ok(None) => return None,
+                Err(e) => {
+                    tracing::error!(error = %e, influencer_id = %self.influencer.id, "Avatar generation failed");
+                    return None;
+                }
+            };
+            if self.review(&url, "generated", attempt).await {
+                return Some(url);
+            }
+        }
+        tracing::warn!(influencer_id = %self.influencer.id, max_attempts, "No safe avatar after all attempts");
+        None
+    }
+
+    /// Check the avatar the creator supplied. A flagged one is replaced by a
+    /// generated avatar when Replicate is configured.
+    pub async fn supplied(&mut self, url: &str, max_attempts: u32) -> Option<String> {
+        if self.review(url, "supplied", 0).await {
+            return Some(url.to_string());
+        }
+        if !self.state.replicate.is_configured() {
+            return None;
+        }
+        self.generate(max_attempts).await
+    }
+
+    /// Check `url` and record the verdict; whether the avatar may be used.
+    async fn review(&mut self, url: &str, source: &str, attempt: u32) -> bool {
+        let (verdict, reason) = match check(self.state, url).await {
+            Ok(result) if result.safe => (AvatarVerdict::Safe, None),
+            Ok(result) => (AvatarVerdict::Flagged, result.reason),
+            Err(e) => {
+                tracing::warn!(error = %e, influencer_id = %self.influencer.id, "Avatar check failed, keeping the avatar");
+                (AvatarVerdict::Unchecked, Some(e.to_string()))
+            }
+        };
+        if verdict == AvatarVerdict::Flagged {
+            tracing::info!(influencer_id = %self.influencer.id, source, attempt, reason = reason.as_deref().unwrap_or(""), "Avatar flagged");
+        }
+        self.history.push(AvatarModerationEntry {
+            url: url.to_string(),
+            source: source.to_string(),
+            verdict,
+            reason,
+            attempt,
+            checked_at: chrono::Utc::now().naive_utc(),
+        });
+        self.save().await;
+        verdict != AvatarVerdict::Flagged
+    }
+
+    async fn save(&self) {
+        let value = serde_json::to_value(&self.history).unwrap_or_default();
+        if let Err(e) = self
+            .state
+            .db
+            .inf_repo()
+            .set_metadata_key(&self.influencer.id, "avatar_moderation", &value)
+            .await
+        {
+            tracing::warn!(error = %e, influencer_id = %self.influencer.id, "Failed to record avatar moderation (non-fatal)");
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{ConnectOptions, Connection, SqliteConnection};
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::config::Settings;
+use crate::db::{self, Database};
+use crate::models::responses::{BackupTableCount, BackupVerificationResponse};
+
+/// Tables counted in the restored copy and compared with the live database.
+const CHECKED_TABLES: &[&str] = &["ai_influencers", "conversations", "messages"];
+
+/// Replication runs every second, so a restore may only trail the live database
+/// by the writes of the last moments.
+const MIN_ROW_RATIO: f64 = 0.99;
+
+/// A restore still running after this is treated as failed.
+const RESTORE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// `PRAGMA integrity_check` lines kept in the report.
+const MAX_INTEGRITY_LINES: usize = 5;
+
+/// Restores the latest Litestream backup into a temp file and checks it: SQLite's
+/// integrity check, then row counts against the live database. Restoring is the
+/// only way to know the replica is usable, so this runs the real `litestream restore`.
+pub struct BackupVerifier {
+    litestream_config: String,
+    /// The database as named in the Litestream config
+    db_path: String,
+    last: RwLock<Option<BackupVerificationResponse>>,
+    /// Held during a run so two never restore at once
+    running: Mutex<()>,
+}
+
+impl BackupVerifier {
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            litestream_config: settings.litestream_config_path.clone(),
+            db_path: db::resolve_db_path(&settings.database_path),
+            last: RwLock::new(None),
+            running: Mutex::new(()),
+        }
+    }
+
+    /// The latest verification, if one has run since startup.
+    pub fn last(&self) -> Option<BackupVerificationResponse> {
+        self.last.read().unwrap().clone()
+    }
+
+    /// Restore and check the latest backup. `None` when a verification is already running.
+    pub async fn verify(&self, db: &Database) -> Option<BackupVerificationResponse> {
+        let _running = self.running.try_lock().ok()?;
+        let started = Instant::now();
+        let mut report = BackupVerificationResponse {
+            ok: false,
+            checked_at: chrono::Utc::now().naive_utc(),
+            duration_ms: 0,
+            integrity: None,
+            tables: Vec::new(),
+            error: None,
+        };
+
+        let restore_path =
+            std::env::temp_dir().join(format!("backup-verify-{}.db", Uuid::new_v4()));
+        if let Err(e) = self.check(db, &restore_path, &mut report).await {
+            report.error = Some(e);
+        }
+        remove_restored(&restore_path).await;
+
+        report.ok = report.error.is_none();
+        report.duration_ms = started.elapsed().as_millis() as i64;
+        *self.last.write().unwrap() = Some(report.clone());
+        Some(report)
+    }
+
+    async fn check(
+        &self,
+        db: &Database,
+        restore_path: &Path,
+        report: &mut BackupVerificationResponse,
+    ) -> Result<(), String> {
+        self.restore(restore_path).await?;
+
+        let mut conn = SqliteConnectOptions::new()
+            .filename(restore_path)
+            .read_only(true)
+            .connect()
+            .await
+            .map_err(|e| format!("Failed to open restored database: {e}"))?;
+        let result = check_restored(&mut conn, db, report).await;
+        conn.close().await.ok();
+        result
+    }
+
+    async fn restore(&self, restore_path: &Path) -> Result<(), String> {
+        let restore = Command::new("litestream")
+            .arg("restore")
+            .arg("-config")
+            .arg(&self.litestream_config)
+            .arg("-o")
+            .arg(restore_path)
+            .arg(&self.db_path)
+            .kill_on_drop(true)
+            .output();
+        let output = tokio::time::timeout(RESTORE_TIMEOUT, restore)
+            .await
+            .map_err(|_| format!("Restore timed out after {}s", RESTORE_TIMEOUT.as_secs()))?
+            .map_err(|e| format!("Failed to run litestream: {e}"))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!(
+                "litestream restore exited with {}: {}",
+                output.status,
+                stderr.trim()
+            ));
+        }
+        Ok(())
+    }
+}
+
+async fn check_restored(
+    conn: &mut SqliteConnection,
+    db: &Database,
+    report: &mut BackupVerificationResponse,
+) -> Result<(), String> {
+    let integrity: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Integrity check failed to run: {e}"))?;
+    let sound = integrity == ["ok"];
+    report.integrity = Some(
+        integrity
+            .into_iter()
+            .take(MAX_INTEGRITY_LINES)
+            .collect::<Vec<_>>()
+            .join("; "),
+    );
+    if !sound {
+        return Err("Restored database failed the integrity check".into());
+    }
+
+    let mut problems = Vec::new();
+    for &table in CHECKED_TABLES {
+        let restored: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to count {table} in restored database: {e}"))?;
+        let live = live_count(db, table)
+            .await
+            .map_err(|e| format!("Failed to count live {table}: {e}"))?;
+        if let Some(live) = live
+            && (restored as f64) < live as f64 * MIN_ROW_RATIO
+        {
+            problems.push(format!("{table} has {restored} rows, live has {live}"));
+        }
+        report.tables.push(BackupTableCount {
+            table: table.to_string(),
+            restored,
+            live,
+        });
+    }
+    if !problems.is_empty() {
+        return Err(format!(
+            "Restored database is behind: {}",
+            problems.join("; ")
+        ));
+    }
+    Ok(())
+}
+
+/// Rows in the live `table`. Only the SQLite build serves from the replicated file;
+/// Postgres builds have nothing to compare the restore against.
+#[cfg(feature = "staging")]
+async fn live_count(db: &Database, table: &str) -> Result<Option<i64>, sqlx::Error> {
+    let count = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+        .fetch_one(&db.pool)
+        .await?;
+    Ok(Some(count))
+}
+
+#[cfg(not(feature = "staging"))]
+async fn live_count(_db: &Database, _table: &str) -> Result<Option<i64>, sqlx::Error> {
+    Ok(None)
+}
+
+async fn remove_restored(path: &Path) {
+    for suffix in ["", "-wal", "-shm"] {
+        let mut file = path.as_os_str().to_owned();
+        file.push(suffix);
+        tokio::fs::remove_file(PathBuf::from(file)).await.ok();
+    }
+}
+
+/// Verify the backup now, logging the outcome and alerting when it failed. `None`
+/// when a verification is already running.
+pub async fn run(state: &AppState) -> Option<BackupVerificationResponse> {
+    let report = state.backup_verifier.verify(&state.db).await?;
+    match &report.error {
+        None => tracing::info!(duration_ms = report.duration_ms, "Backup verified"),
+        Some(error) => {
+            tracing::error!(%error, "Backup verification failed");
+            state
+                .google_chat
+                .notify_backup_verification_failed(error)
+                .await;
+        }
+    }
+    Some(report)
+}
+
+/// Verify the backup every `interval`. The first run waits an interval too, so
+/// it doesn't compete with startup.
+pub fn spawn_backup_verifier(state: Arc<AppState>, interval: Duration) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            if run(&state).await.is_none() {
+                tracing::info!("Backup verification already running, skipping");
+            }
+        }
+    });
+}
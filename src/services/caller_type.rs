@@ -0,0 +1,141 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+
+use crate::models::responses::CacheStats;
+
+/// Entries kept before expired ones are swept out.
+const MAX_ENTRIES: usize = 50_000;
+
+/// Whether the authenticated caller is a regular user or a bot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CallerType {
+    User,
+    Bot,
+}
+
+struct Entry {
+    caller_type: CallerType,
+    expires_at: Instant,
+}
+
+/// TTL cache in front of the User Info Service canister lookup. Failed lookups
+/// resolve to `User` and are cached for the shorter `negative_ttl`, so a
+/// canister outage doesn't put a round trip on every request.
+pub struct CallerTypeCache {
+    entries: DashMap<String, Entry>,
+    ttl: Duration,
+    negative_ttl: Duration,
+    /// Skip the canister entirely (environments without IC access)
+    force_user: bool,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CallerTypeCache {
+    /// A zero `ttl` disables caching of successful lookups, a zero
+    /// `negative_ttl` that of failed ones.
+    pub fn new(ttl: Duration, negative_ttl: Duration, force_user: bool) -> Self {
+        Self {
+            entries: DashMap::new(),
+            ttl,
+            negative_ttl,
+            force_user,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub async fn resolve(&self, agent: &ic_agent::Agent, principal: &str) -> CallerType {
+        if self.force_user {
+            return CallerType::User;
+        }
+        if let Some(entry) = self.entries.get(principal)
+            && entry.expires_at > Instant::now()
+        {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return entry.caller_type;
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let (caller_type, ttl) = match lookup(agent, principal).await {
+            Some(caller_type) => (caller_type, self.ttl),
+            None => (CallerType::User, self.negative_ttl),
+        };
+        if !ttl.is_zero() {
+            if self.entries.len() >= MAX_ENTRIES {
+                let now = Instant::now();
+                self.entries.retain(|_, e| e.expires_at > now);
+            }
+            if self.entries.len() < MAX_ENTRIES {
+                self.entries.insert(
+                    principal.to_string(),
+                    Entry {
+                        caller_type,
+                        expires_at: Instant::now() + ttl,
+                    },
+                );
+            }
+        }
+        caller_type
+    }
+
+    /// Forget a principal, e.g. after its account type changed. Returns whether
+    /// anything was cached.
+    pub fn invalidate(&self, principal: &str) -> bool {
+        self.entries.remove(principal).is_some()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let lookups = hits + misses;
+        CacheStats {
+            entries: self.entries.len(),
+            hits,
+            misses,
+            hit_rate: if lookups == 0 {
+                0.0
+            } else {
+                hits as f64 / lookups as f64
+            },
+        }
+    }
+}
+
+/// Ask the User Info Service canister whether `principal_str` is a bot account.
+/// `None` when the answer couldn't be had (parsing, network, canister error).
+async fn lookup(agent: &ic_agent::Agent, principal_str: &str) -> Option<CallerType> {
+    let principal = match candid::Principal::from_text(principal_str) {
+        Ok(p) => p,
+        Err(e) => {
+            tracing::debug!(error = %e, principal = %principal_str, "Failed to parse principal, defaulting to User");
+            return None;
+        }
+    };
+
+    let canister_id = yral_canisters_client::ic::USER_INFO_SERVICE_ID;
+    let service = yral_canisters_client::user_info_service::UserInfoService(canister_id, agent);
+
+    match service.get_user_profile_details_v_7(principal).await {
+        Ok(yral_canisters_client::user_info_service::Result7::Ok(profile)) => {
+            match profile.account_type {
+                yral_canisters_client::user_info_service::UserAccountType::BotAccount {
+                    ..
+                } => Some(CallerType::Bot),
+                yral_canisters_client::user_info_service::UserAccountType::MainAccount {
+                    ..
+                } => Some(CallerType::User),
+            }
+        }
+        Ok(yral_canisters_client::user_info_service::Result7::Err(e)) => {
+            tracing::warn!(error = %e, "Canister returned error for caller type lookup, defaulting to User");
+            None
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "IC agent error during caller type lookup, defaulting to User");
+            None
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use std::sync::Arc;
+
+use crate::AppState;
+
+/// Periodically drop `change_log` entries older than `retention_days`. Clients
+/// whose sync cursor falls behind the retained range must resync from scratch.
+pub fn spawn_change_log_pruner(state: Arc<AppState>, interval_secs: u64, retention_days: i64) {
+    tokio::spawn(async move {
+        let interval = std::time::Duration::from_secs(interval_secs);
+        loop {
+            tokio::time::sleep(interval).await;
+            let cutoff = chrono::Utc::now().naive_utc() - chrono::Duration::days(retention_days);
+            match state.db.change_log_repo().prune_before(cutoff).await {
+                Ok(0) => {}
+                Ok(pruned) => tracing::info!(pruned, "Pruned change log"),
+                Err(e) => tracing::warn!(error = %e, "Failed to prune change log"),
+            }
+        }
+    });
+}
@@ -1,9 +1,14 @@
+use std::time::{Duration, Instant};
+
+use async_openai::types::chat::ResponseFormatJsonSchema;
+use dashmap::DashMap;
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 
 use crate::error::AppError;
 use crate::models::responses::GeneratedMetadataResponse;
-use crate::services::ai::AiClient;
-use crate::services::replicate::ReplicateClient;
+use crate::services::ai::{AiApi, GenerationOptions};
+use crate::services::replicate::ImageGen;
 
 const GENERATE_PROMPT: &str = r#"You are an expert AI Character Architect. Transform the user's concept into high-fidelity System Instructions.
 
@@ -172,55 +177,212 @@ struct ValidationResult {
     image_prompt: Option<String>,
 }
 
+impl ValidationResult {
+    /// Enforce the parts of the schema the provider may not: a valid character
+    /// must come back with everything creation needs.
+    fn check(&self) -> Result<(), String> {
+        let Some(is_valid) = self.is_valid else {
+            return Err("missing is_valid".into());
+        };
+        if !is_valid {
+            return Ok(());
+        }
+        let name = self.name.as_deref().unwrap_or_default();
+        if !(3..=12).contains(&name.len())
+            || !name
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+        {
+            return Err(format!("name {name:?} is not 3-12 lowercase alphanumerics"));
+        }
+        for (field, value) in [
+            ("display_name", &self.display_name),
+            ("description", &self.description),
+            ("initial_greeting", &self.initial_greeting),
+        ] {
+            if value.as_deref().is_none_or(|v| v.trim().is_empty()) {
+                return Err(format!("missing {field}"));
+            }
+        }
+        if self.suggested_messages.as_ref().is_none_or(Vec::is_empty) {
+            return Err("missing suggested_messages".into());
+        }
+        Ok(())
+    }
+}
+
+/// Structured-output schema for [`VALIDATE_PROMPT`], mirroring [`ValidationResult`].
+fn validation_schema() -> ResponseFormatJsonSchema {
+    ResponseFormatJsonSchema {
+        description: Some("Character validation verdict and generated metadata".into()),
+        name: "character_metadata".into(),
+        schema: Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "is_valid": {"type": "boolean"},
+                "reason": {"type": "string", "nullable": true},
+                "name": {"type": "string"},
+                "display_name": {"type": "string"},
+                "description": {"type": "string"},
+                "initial_greeting": {"type": "string"},
+                "suggested_messages": {"type": "array", "items": {"type": "string"}},
+                "personality_traits": {
+                    "type": "object",
+                    "additionalProperties": {"type": "string"}
+                },
+                "category": {"type": "string"},
+                "image_prompt": {"type": "string"}
+            },
+            "required": ["is_valid"]
+        })),
+        strict: None,
+    }
+}
+
+/// Attempts at getting well-formed metadata before giving up.
+const MAX_METADATA_ATTEMPTS: usize = 3;
+
 #[derive(Deserialize)]
 struct GreetingResult {
     initial_greeting: Option<String>,
     suggested_messages: Option<Vec<String>>,
 }
 
-pub struct CharacterGeneratorService;
+/// Entries kept per cache before expired ones are swept out.
+const MAX_CACHE_ENTRIES: usize = 1_000;
+
+/// TTL cache keyed by a SHA-256 of the model input, so retries of the same
+/// character-creation step don't go back to Gemini.
+struct ResponseCache<V> {
+    entries: DashMap<String, (V, Instant)>,
+    ttl: Duration,
+}
+
+impl<V: Clone> ResponseCache<V> {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            entries: DashMap::new(),
+            ttl,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<V> {
+        let entry = self.entries.get(key)?;
+        (entry.1.elapsed() < self.ttl).then(|| entry.0.clone())
+    }
+
+    fn insert(&self, key: String, value: V) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        if self.entries.len() >= MAX_CACHE_ENTRIES {
+            self.entries.retain(|_, (_, at)| at.elapsed() < self.ttl);
+        }
+        if self.entries.len() < MAX_CACHE_ENTRIES {
+            self.entries.insert(key, (value, Instant::now()));
+        }
+    }
+}
+
+fn cache_key(input: &str) -> String {
+    hex::encode(Sha256::digest(input.as_bytes()))
+}
+
+/// Character-creation calls to Gemini. Generated prompts and validated metadata
+/// are cached by input for `ttl`; a zero `ttl` disables caching.
+pub struct CharacterGeneratorService {
+    prompts: ResponseCache<String>,
+    metadata: ResponseCache<GeneratedMetadataResponse>,
+}
 
 impl CharacterGeneratorService {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            prompts: ResponseCache::new(ttl),
+            metadata: ResponseCache::new(ttl),
+        }
+    }
+
+    /// With `bypass_cache` the model is asked again and the cached entry replaced.
     pub async fn generate_system_instructions(
-        gemini: &AiClient,
+        &self,
+        gemini: &dyn AiApi,
         prompt: &str,
+        bypass_cache: bool,
     ) -> Result<String, AppError> {
+        let key = cache_key(prompt);
+        if !bypass_cache && let Some(cached) = self.prompts.get(&key) {
+            return Ok(cached);
+        }
+
         let (text, _) = gemini
             .generate_response(prompt, GENERATE_PROMPT, &[], None)
             .await?;
+        self.prompts.insert(key, text.clone());
         Ok(text)
     }
 
+    /// Cached like [`Self::generate_system_instructions`], including the avatar
+    /// URL, so a retry doesn't generate another image.
     pub async fn validate_and_generate_metadata(
-        gemini: &AiClient,
-        replicate: &ReplicateClient,
+        &self,
+        gemini: &dyn AiApi,
+        replicate: &dyn ImageGen,
         system_instructions: &str,
+        bypass_cache: bool,
     ) -> Result<GeneratedMetadataResponse, AppError> {
-        if contains_safety_refusal(system_instructions) {
-            return Ok(invalid_metadata("Content failed safety validation"));
+        let key = cache_key(system_instructions);
+        if !bypass_cache && let Some(cached) = self.metadata.get(&key) {
+            return Ok(cached);
         }
 
-        let (text, _) = gemini
-            .generate_response(system_instructions, VALIDATE_PROMPT, &[], None)
-            .await?;
+        let result = Self::generate_metadata(gemini, replicate, system_instructions).await?;
+        self.metadata.insert(key, result.clone());
+        Ok(result)
+    }
 
-        if contains_safety_refusal(&text) {
+    async fn generate_metadata(
+        gemini: &dyn AiApi,
+        replicate: &dyn ImageGen,
+        system_instructions: &str,
+    ) -> Result<GeneratedMetadataResponse, AppError> {
+        if contains_safety_refusal(system_instructions) {
             return Ok(invalid_metadata("Content failed safety validation"));
         }
 
-        let result: ValidationResult =
-            parse_json_from_response(&text).unwrap_or(ValidationResult {
-                is_valid: Some(false),
-                reason: Some("Failed to parse validation response".to_string()),
-                name: None,
-                display_name: None,
-                description: None,
-                initial_greeting: None,
-                suggested_messages: None,
-                personality_traits: None,
-                category: None,
-                image_prompt: None,
-            });
+        let options = GenerationOptions {
+            json_schema: Some(validation_schema()),
+            ..Default::default()
+        };
+        let mut parsed = None;
+        for attempt in 1..=MAX_METADATA_ATTEMPTS {
+            let (text, _) = gemini
+                .generate_response_with(system_instructions, VALIDATE_PROMPT, &[], None, &options)
+                .await?;
+
+            if contains_safety_refusal(&text) {
+                return Ok(invalid_metadata("Content failed safety validation"));
+            }
+
+            let result = serde_json::from_str::<ValidationResult>(text.trim())
+                .ok()
+                .or_else(|| parse_json_from_response(&text))
+                .ok_or_else(|| "not a JSON object".to_string())
+                .and_then(|r| r.check().map(|()| r));
+            match result {
+                Ok(result) => {
+                    parsed = Some(result);
+                    break;
+                }
+                Err(e) => tracing::warn!(attempt, error = %e, "Malformed metadata response"),
+            }
+        }
+        // Not a verdict on the character, so not reported (or cached) as one
+        let Some(result) = parsed else {
+            return Err(AppError::service_unavailable(
+                "AI returned malformed character metadata. Please retry.",
+            ));
+        };
 
         if !result.is_valid.unwrap_or(false) {
             return Ok(invalid_metadata(
@@ -261,7 +423,7 @@ impl CharacterGeneratorService {
     }
 
     pub async fn generate_initial_greeting(
-        gemini: &AiClient,
+        gemini: &dyn AiApi,
         display_name: &str,
         system_instructions: &str,
     ) -> Result<(String, Vec<String>), AppError> {
@@ -292,7 +454,7 @@ impl CharacterGeneratorService {
     }
 
     pub async fn generate_starter_video_prompt(
-        gemini: &AiClient,
+        gemini: &dyn AiApi,
         display_name: &str,
         system_instructions: &str,
     ) -> Result<String, AppError> {
@@ -308,7 +470,7 @@ impl CharacterGeneratorService {
     }
 
     pub async fn generate_subsequent_video_prompt(
-        gemini: &AiClient,
+        gemini: &dyn AiApi,
         display_name: &str,
         system_instructions: &str,
         scene_description: &str,
@@ -346,7 +508,7 @@ impl CharacterGeneratorService {
     }
 
     pub async fn generate_base_video_prompt(
-        gemini: &AiClient,
+        gemini: &dyn AiApi,
         display_name: &str,
         scene_description: &str,
         avatar_url: Option<&str>,
@@ -382,7 +544,7 @@ impl CharacterGeneratorService {
     }
 }
 
-fn parse_json_from_response<T: serde::de::DeserializeOwned>(text: &str) -> Option<T> {
+pub(crate) fn parse_json_from_response<T: serde::de::DeserializeOwned>(text: &str) -> Option<T> {
     let start = text.find('{')?;
     let end = text.rfind('}')?;
     if start >= end {
@@ -0,0 +1,119 @@
+use std::collections::HashSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use axum::http::{HeaderMap, header};
+use chrono::{NaiveDate, Timelike, Utc};
+
+use crate::AppState;
+use crate::models::entities::{AIInfluencer, DayPart};
+
+/// Who is asking and when, for choosing which suggested messages to show.
+pub struct StarterContext {
+    user_id: String,
+    language: Option<String>,
+    day_part: DayPart,
+    date: NaiveDate,
+    count: usize,
+}
+
+impl StarterContext {
+    /// The language comes from `Accept-Language`, the local time from an IANA
+    /// `X-Timezone` header, else the user's quiet hours timezone, else UTC.
+    pub async fn resolve(state: &AppState, headers: &HeaderMap, user_id: &str) -> Self {
+        let header_tz = headers
+            .get("X-Timezone")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|tz| tz.trim().parse::<chrono_tz::Tz>().ok());
+        let tz = match header_tz {
+            Some(tz) => tz,
+            None => match state.db.notification_prefs_repo().get(user_id).await {
+                Ok(prefs) => prefs
+                    .and_then(|p| p.timezone.parse().ok())
+                    .unwrap_or(chrono_tz::UTC),
+                Err(e) => {
+                    tracing::warn!(error = %e, user_id, "Failed to load timezone for suggested messages");
+                    chrono_tz::UTC
+                }
+            },
+        };
+        let local = Utc::now().with_timezone(&tz);
+        Self {
+            user_id: user_id.to_string(),
+            language: headers
+                .get(header::ACCEPT_LANGUAGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(preferred_language),
+            day_part: DayPart::from_hour(local.hour()),
+            date: local.date_naive(),
+            count: state.settings.load().suggested_message_count,
+        }
+    }
+}
+
+/// Primary subtag of a language tag, lowercased: "en-US" becomes "en".
+pub fn language_tag(raw: &str) -> Option<String> {
+    let primary = raw.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+    ((2..=3).contains(&primary.len()) && primary.chars().all(|c| c.is_ascii_alphabetic()))
+        .then_some(primary)
+}
+
+/// The first language in an `Accept-Language` header; clients list them in
+/// order of preference.
+fn preferred_language(header: &str) -> Option<String> {
+    header
+        .split(',')
+        .map(|entry| entry.split(';').next().unwrap_or(""))
+        .find_map(language_tag)
+}
+
+/// Suggested messages to show `ctx`'s user: the influencer's tagged starters
+/// that fit their language and time of day, then its untagged ones, each group
+/// shuffled. The order is stable for a user through one part of the day and
+/// rotates at the next, so refreshing the list doesn't reshuffle it. Influencers
+/// without tagged starters, and a count of 0, get the plain `suggested_messages`,
+/// all of them.
+pub fn pick(influencer: &AIInfluencer, ctx: &StarterContext) -> Vec<String> {
+    let starters = influencer.conversation_starters();
+    if ctx.count == 0 || starters.is_empty() {
+        return influencer.suggested_messages.clone();
+    }
+    let tagged = starters
+        .into_iter()
+        .filter(|s| s.day_parts.is_empty() || s.day_parts.contains(&ctx.day_part))
+        .filter(|s| {
+            s.languages.is_empty()
+                || ctx
+                    .language
+                    .as_ref()
+                    .is_some_and(|language| s.languages.contains(language))
+        })
+        .map(|s| {
+            let targeted = !s.day_parts.is_empty() || !s.languages.is_empty();
+            (targeted, s.text)
+        });
+    let untagged = influencer
+        .suggested_messages
+        .iter()
+        .map(|text| (false, text.clone()));
+
+    let mut hasher = DefaultHasher::new();
+    (&ctx.user_id, &influencer.id, ctx.date, ctx.day_part as u8).hash(&mut hasher);
+    let seed = hasher.finish();
+    let rank = |text: &str| {
+        let mut hasher = DefaultHasher::new();
+        (seed, text).hash(&mut hasher);
+        hasher.finish()
+    };
+
+    let mut seen = HashSet::new();
+    let mut candidates: Vec<(bool, String)> = tagged
+        .chain(untagged)
+        .filter(|(_, text)| !text.trim().is_empty() && seen.insert(text.trim().to_lowercase()))
+        .collect();
+    candidates.sort_by_cached_key(|(targeted, text)| (!targeted, rank(text)));
+    candidates
+        .into_iter()
+        .take(ctx.count)
+        .map(|(_, text)| text)
+        .collect()
+}
@@ -0,0 +1,55 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+
+use crate::error::AppError;
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Above this many tracked conversations, idle ones are dropped.
+const MAX_TRACKED_CONVERSATIONS: usize = 10_000;
+
+/// Per-conversation cap on AI replies over a sliding minute. The request rate
+/// limit is per caller; this catches one conversation spinning, e.g. a bot that
+/// is itself a user answering our replies, or a client retrying in a loop.
+#[derive(Default)]
+pub struct ConversationThrottle {
+    replies: DashMap<String, VecDeque<Instant>>,
+}
+
+impl ConversationThrottle {
+    /// Take one reply for `conversation_id`, or fail with how long until the
+    /// oldest one in the window expires. A `limit` of 0 disables the cap.
+    pub fn acquire(&self, conversation_id: &str, limit: u32) -> Result<(), AppError> {
+        if limit == 0 {
+            return Ok(());
+        }
+        let now = Instant::now();
+        if self.replies.len() >= MAX_TRACKED_CONVERSATIONS {
+            self.replies
+                .retain(|_, r| r.back().is_some_and(|at| now.duration_since(*at) < WINDOW));
+        }
+
+        let mut replies = self.replies.entry(conversation_id.to_string()).or_default();
+        while replies
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= WINDOW)
+        {
+            replies.pop_front();
+        }
+        if replies.len() >= limit as usize {
+            let oldest = replies.front().copied().unwrap_or(now);
+            let retry_after = WINDOW
+                .saturating_sub(now.duration_since(oldest))
+                .as_secs()
+                .max(1);
+            return Err(AppError::conversation_throttled(
+                format!("This conversation is limited to {limit} replies per minute"),
+                retry_after,
+            ));
+        }
+        replies.push_back(now);
+        Ok(())
+    }
+}
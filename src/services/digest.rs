@@ -0,0 +1,255 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::NaiveDateTime;
+use dashmap::DashMap;
+
+use crate::AppState;
+use crate::error::AppError;
+use crate::models::entities::UnreadActivity;
+use crate::services::notification_format::truncate_graphemes;
+
+/// Conversations considered per digest.
+const MAX_DIGEST_CONVERSATIONS: i64 = 10;
+/// Subscriptions processed per scheduler tick.
+const DIGEST_BATCH_SIZE: i64 = 200;
+/// Graphemes kept of the generated summary; the prompt asks for 150 characters.
+const BODY_MAX_GRAPHEMES: usize = 160;
+/// How long a generated summary is reused while the unread activity is unchanged.
+const SUMMARY_TTL: Duration = Duration::from_secs(6 * 3600);
+/// Above this many cached summaries, expired ones are dropped.
+const MAX_CACHED_SUMMARIES: usize = 10_000;
+
+struct CachedSummary {
+    activity_hash: u64,
+    body: String,
+    created_at: Instant,
+}
+
+/// Generated digest summaries per user, so refreshing the preview (or a digest
+/// that goes out right after one) doesn't pay for another AI call. A summary is
+/// only reused for the exact same unread activity.
+#[derive(Default)]
+pub struct DigestSummaries {
+    entries: DashMap<String, CachedSummary>,
+}
+
+impl DigestSummaries {
+    fn get(&self, user_id: &str, activity_hash: u64) -> Option<String> {
+        let entry = self.entries.get(user_id)?;
+        (entry.activity_hash == activity_hash && entry.created_at.elapsed() < SUMMARY_TTL)
+            .then(|| entry.body.clone())
+    }
+
+    fn insert(&self, user_id: &str, activity_hash: u64, body: String) {
+        if self.entries.len() >= MAX_CACHED_SUMMARIES {
+            self.entries
+                .retain(|_, entry| entry.created_at.elapsed() < SUMMARY_TTL);
+        }
+        self.entries.insert(
+            user_id.to_string(),
+            CachedSummary {
+                activity_hash,
+                body,
+                created_at: Instant::now(),
+            },
+        );
+    }
+}
+
+pub struct Digest {
+    pub title: String,
+    pub body: String,
+    pub activity: Vec<UnreadActivity>,
+}
+
+/// Summarize a user's unread replies since their last digest (`since`), or `None`
+/// when there is nothing new.
+pub async fn build_digest(
+    state: &AppState,
+    user_id: &str,
+    since: Option<NaiveDateTime>,
+) -> Result<Option<Digest>, AppError> {
+    let activity = state
+        .db
+        .digest_repo()
+        .unread_activity(user_id, since, MAX_DIGEST_CONVERSATIONS)
+        .await?;
+    if activity.is_empty() {
+        return Ok(None);
+    }
+
+    let title = match activity.len() {
+        1 => format!("{} replied", activity[0].influencer_name),
+        n => format!("{n} bots replied"),
+    };
+
+    let activity_hash = hash_activity(&activity);
+    let body = match state.digest_summaries.get(user_id, activity_hash) {
+        Some(body) => body,
+        None => match summarize(state, &activity).await {
+            Ok(summary) => {
+                state
+                    .digest_summaries
+                    .insert(user_id, activity_hash, summary.clone());
+                summary
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Digest summary failed, using plain listing");
+                fallback_body(&activity)
+            }
+        },
+    };
+
+    Ok(Some(Digest {
+        title,
+        body,
+        activity,
+    }))
+}
+
+fn hash_activity(activity: &[UnreadActivity]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for a in activity {
+        (&a.conversation_id, a.unread_count, &a.latest_content).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+async fn summarize(state: &AppState, activity: &[UnreadActivity]) -> Result<String, AppError> {
+    let listing = activity
+        .iter()
+        .map(|a| {
+            let preview: String = a
+                .latest_content
+                .as_deref()
+                .unwrap_or_default()
+                .chars()
+                .take(200)
+                .collect();
+            format!(
+                "- {} ({} unread): {preview}",
+                a.influencer_name, a.unread_count
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let prompt = format!(
+        "Write one friendly push notification sentence (max 150 characters) telling the user \
+         what they missed in these chats. Mention names, keep it teasing, no quotes or hashtags.\n\n{listing}"
+    );
+    let (text, _) = state
+        .gemini
+        .generate_response(&prompt, "You write short push notifications.", &[], None)
+        .await?;
+
+    // The model doesn't always respect the length it was asked for
+    let text = truncate_graphemes(text.trim().trim_matches('"'), BODY_MAX_GRAPHEMES);
+    if text.is_empty() {
+        return Err(AppError::service_unavailable("Empty response from AI"));
+    }
+    Ok(text)
+}
+
+fn fallback_body(activity: &[UnreadActivity]) -> String {
+    let names: Vec<&str> = activity
+        .iter()
+        .take(3)
+        .map(|a| a.influencer_name.as_str())
+        .collect();
+    let others = activity.len().saturating_sub(names.len());
+    if others > 0 {
+        format!(
+            "{} and {others} more are waiting for you. Here's what you missed.",
+            names.join(", ")
+        )
+    } else if names.len() == 1 {
+        format!("{} is waiting for you. Here's what you missed.", names[0])
+    } else {
+        format!(
+            "{} are waiting for you. Here's what you missed.",
+            names.join(", ")
+        )
+    }
+}
+
+/// Periodically send digests to every subscriber whose period has elapsed.
+pub fn spawn_digest_scheduler(state: Arc<AppState>, interval_secs: u64) {
+    tokio::spawn(async move {
+        let interval = Duration::from_secs(interval_secs);
+        loop {
+            tokio::time::sleep(interval).await;
+            let due = match state.db.digest_repo().list_due(DIGEST_BATCH_SIZE).await {
+                Ok(due) => due,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to load due digests (non-fatal)");
+                    continue;
+                }
+            };
+
+            let now = chrono::Utc::now();
+            let mut sent = 0;
+            for subscription in &due {
+                // Left due, so it goes out on the first tick after quiet hours
+                if let Ok(Some(prefs)) = state
+                    .db
+                    .notification_prefs_repo()
+                    .get(&subscription.user_id)
+                    .await
+                    && prefs.in_quiet_hours(now)
+                {
+                    continue;
+                }
+                match build_digest(&state, &subscription.user_id, subscription.last_sent_at).await {
+                    Ok(Some(digest)) => {
+                        let data = serde_json::json!({
+                            "type": "digest",
+                            "collapse_key": "digest",
+                            "conversation_ids": digest
+                                .activity
+                                .iter()
+                                .map(|a| a.conversation_id.as_str())
+                                .collect::<Vec<_>>(),
+                        });
+                        if state
+                            .push_notifications
+                            .send_push_notification(
+                                &subscription.user_id,
+                                &digest.title,
+                                &digest.body,
+                                Some(&data),
+                            )
+                            .await
+                        {
+                            sent += 1;
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::warn!(
+                            error = %e,
+                            user_id = %subscription.user_id,
+                            "Digest build failed"
+                        );
+                        continue;
+                    }
+                }
+                // A quiet period still counts, so the next digest waits a full period
+                if let Err(e) = state
+                    .db
+                    .digest_repo()
+                    .mark_sent(&subscription.user_id)
+                    .await
+                {
+                    tracing::warn!(error = %e, "Failed to mark digest sent");
+                }
+            }
+
+            if !due.is_empty() {
+                tracing::info!(due = due.len(), sent, "Digest run completed");
+            }
+        }
+    });
+}
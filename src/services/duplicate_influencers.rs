@@ -0,0 +1,182 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+
+use dashmap::DashMap;
+
+use crate::AppState;
+use crate::error::{AppError, DuplicateCandidate};
+use crate::models::entities::AIInfluencer;
+use crate::services::ai::EmbeddingTask;
+use crate::services::knowledge::cosine_similarity;
+use crate::services::moderation;
+
+/// Most recent of the caller's influencers compared against a new one.
+const MAX_COMPARED: i64 = 50;
+/// Embeddings kept before the cache is emptied and rebuilt on demand.
+const MAX_CACHED_EMBEDDINGS: usize = 10_000;
+
+struct CachedEmbedding {
+    /// Hash of the stored instructions the embedding was made from
+    version: u64,
+    embedding: Arc<[f32]>,
+}
+
+/// Embeddings of existing influencers' prompts, so a create only embeds the
+/// new prompt and those changed since they were last compared.
+#[derive(Default)]
+pub struct PromptEmbeddings {
+    entries: DashMap<String, CachedEmbedding>,
+}
+
+fn prompt_version(influencer: &AIInfluencer) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    influencer.system_instructions.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl PromptEmbeddings {
+    fn get(&self, influencer: &AIInfluencer) -> Option<Arc<[f32]>> {
+        let entry = self.entries.get(&influencer.id)?;
+        (entry.version == prompt_version(influencer)).then(|| entry.embedding.clone())
+    }
+
+    fn insert(&self, influencer: &AIInfluencer, embedding: Arc<[f32]>) {
+        if self.entries.len() >= MAX_CACHED_EMBEDDINGS {
+            self.entries.clear();
+        }
+        self.entries.insert(
+            influencer.id.clone(),
+            CachedEmbedding {
+                version: prompt_version(influencer),
+                embedding,
+            },
+        );
+    }
+}
+
+/// Lowercase letters and digits only, so `Chef_Bot` and `chefbot` compare equal.
+fn normalize(name: &str) -> Vec<char> {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 1.0 for names that normalize to the same string, falling with the edit
+/// distance relative to the longer name.
+fn name_similarity(a: &str, b: &str) -> f32 {
+    let (a, b) = (normalize(a), normalize(b));
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 0.0;
+    }
+    // Levenshtein distance over one row
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    1.0 - row[b.len()] as f32 / longest as f32
+}
+
+/// Cosine similarity of `system_instructions` to each existing prompt, in
+/// order. `None` when embedding fails; the check then goes by names alone.
+async fn prompt_similarities(
+    state: &AppState,
+    system_instructions: &str,
+    existing: &[AIInfluencer],
+) -> Option<Vec<f32>> {
+    let mut embeddings: Vec<Option<Arc<[f32]>>> = existing
+        .iter()
+        .map(|i| state.prompt_embeddings.get(i))
+        .collect();
+    let missing: Vec<usize> = (0..existing.len())
+        .filter(|&index| embeddings[index].is_none())
+        .collect();
+
+    let mut texts = vec![system_instructions.trim().to_string()];
+    texts.extend(
+        missing
+            .iter()
+            .map(|&index| moderation::strip_guardrails(&existing[index].system_instructions)),
+    );
+    let new = match state.gemini.embed(&texts, EmbeddingTask::Document).await {
+        Ok(fresh) if fresh.len() == texts.len() => {
+            let mut fresh = fresh.into_iter();
+            let new = fresh.next()?;
+            for (index, embedding) in missing.into_iter().zip(fresh) {
+                let embedding: Arc<[f32]> = embedding.into();
+                state
+                    .prompt_embeddings
+                    .insert(&existing[index], embedding.clone());
+                embeddings[index] = Some(embedding);
+            }
+            new
+        }
+        Ok(_) => return None,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to embed prompts for the duplicate check");
+            return None;
+        }
+    };
+    embeddings
+        .iter()
+        .map(|e| e.as_deref().map(|e| cosine_similarity(&new, e)))
+        .collect()
+}
+
+/// The caller's influencers in this tenant that a new one with these details
+/// closely resembles, by name or by system prompt, most similar first.
+pub async fn find_duplicates(
+    state: &AppState,
+    owner: &str,
+    tenant: &str,
+    name: &str,
+    display_name: &str,
+    system_instructions: &str,
+) -> Result<Vec<DuplicateCandidate>, AppError> {
+    let settings = state.settings.load();
+    if !settings.duplicate_check_enabled {
+        return Ok(Vec::new());
+    }
+    let existing = state
+        .db
+        .inf_repo()
+        .list_by_owner(owner, tenant, MAX_COMPARED)
+        .await?;
+    if existing.is_empty() {
+        return Ok(Vec::new());
+    }
+    let prompt_scores = prompt_similarities(state, system_instructions, &existing).await;
+
+    let mut duplicates: Vec<DuplicateCandidate> = existing
+        .into_iter()
+        .enumerate()
+        .map(|(index, influencer)| DuplicateCandidate {
+            name_similarity: name_similarity(name, &influencer.name)
+                .max(name_similarity(display_name, &influencer.display_name)),
+            prompt_similarity: prompt_scores.as_ref().map(|scores| scores[index]),
+            influencer_id: influencer.id,
+            name: influencer.name,
+            display_name: influencer.display_name,
+        })
+        .filter(|c| {
+            c.name_similarity >= settings.duplicate_name_similarity
+                || c.prompt_similarity
+                    .is_some_and(|s| s >= settings.duplicate_prompt_similarity)
+        })
+        .collect();
+    let strongest =
+        |c: &DuplicateCandidate| c.name_similarity.max(c.prompt_similarity.unwrap_or(0.0));
+    duplicates.sort_by(|a, b| strongest(b).total_cmp(&strongest(a)));
+    Ok(duplicates)
+}
@@ -0,0 +1,217 @@
+use crate::error::AppError;
+
+const SENDGRID_SEND_URL: &str = "https://api.sendgrid.com/v3/mail/send";
+
+/// Email channel: resolves senders to principals via the Yral Metadata Server
+/// and sends replies through SendGrid.
+#[derive(Clone)]
+pub struct EmailService {
+    http: reqwest::Client,
+    metadata_url: String,
+    metadata_auth_token: Option<String>,
+    sendgrid_api_key: Option<String>,
+    domain: String,
+}
+
+pub struct OutgoingEmail<'a> {
+    pub to: &'a str,
+    pub from_local: &'a str,
+    pub from_name: &'a str,
+    pub subject: &'a str,
+    pub text: &'a str,
+    pub in_reply_to: Option<&'a str>,
+}
+
+impl EmailService {
+    pub fn new(
+        http: reqwest::Client,
+        metadata_url: &str,
+        metadata_auth_token: Option<String>,
+        sendgrid_api_key: Option<String>,
+        domain: &str,
+    ) -> Self {
+        Self {
+            http,
+            metadata_url: metadata_url.trim_end_matches('/').to_string(),
+            metadata_auth_token,
+            sendgrid_api_key: sendgrid_api_key.filter(|k| !k.is_empty()),
+            domain: domain.to_string(),
+        }
+    }
+
+    /// Look up the principal linked to a verified email address.
+    pub async fn resolve_principal(&self, email: &str) -> Result<Option<String>, AppError> {
+        let url = format!("{}/principal-by-email", self.metadata_url);
+        let mut req = self
+            .http
+            .post(&url)
+            .json(&serde_json::json!({ "email": email }))
+            .timeout(std::time::Duration::from_secs(10));
+        if let Some(token) = &self.metadata_auth_token {
+            req = req.header("Authorization", format!("Bearer {token}"));
+        }
+
+        let resp = req.send().await.map_err(|e| {
+            AppError::service_unavailable(format!("Metadata server unreachable: {e}"))
+        })?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            return Err(AppError::service_unavailable(format!(
+                "Metadata server returned {}",
+                resp.status()
+            )));
+        }
+
+        let json: serde_json::Value = resp.json().await.map_err(|e| {
+            AppError::service_unavailable(format!("Invalid metadata response: {e}"))
+        })?;
+        Ok(json
+            .get("Ok")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.is_empty())
+            .map(str::to_string))
+    }
+
+    /// Send a plain-text email from `{from_local}@{domain}`. Returns false when
+    /// sending is not configured or the provider rejects the message.
+    pub async fn send(&self, email: &OutgoingEmail<'_>) -> bool {
+        let Some(api_key) = &self.sendgrid_api_key else {
+            return false;
+        };
+
+        let mut payload = serde_json::json!({
+            "personalizations": [{ "to": [{ "email": email.to }] }],
+            "from": {
+                "email": format!("{}@{}", email.from_local, self.domain),
+                "name": email.from_name,
+            },
+            "subject": email.subject,
+            "content": [{ "type": "text/plain", "value": email.text }],
+        });
+        if let Some(message_id) = email.in_reply_to {
+            payload["headers"] = serde_json::json!({
+                "In-Reply-To": message_id,
+                "References": message_id,
+            });
+        }
+
+        match self
+            .http
+            .post(SENDGRID_SEND_URL)
+            .bearer_auth(api_key)
+            .json(&payload)
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await
+        {
+            Ok(resp) if resp.status().is_success() => true,
+            Ok(resp) => {
+                tracing::error!(status = %resp.status(), "Email reply rejected by provider");
+                false
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Email reply error");
+                false
+            }
+        }
+    }
+}
+
+/// Extract the bare address from a header value like `"Jane <jane@example.com>"`.
+pub fn parse_address(value: &str) -> Option<String> {
+    let value = value.trim();
+    let addr = match (value.rfind('<'), value.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &value[start + 1..end],
+        _ => value,
+    };
+    let addr = addr.trim().to_lowercase();
+    let (local, domain) = addr.split_once('@')?;
+    (!local.is_empty() && domain.contains('.')).then_some(addr)
+}
+
+/// Drop the quoted thread that mail clients append below a reply.
+pub fn strip_quoted_reply(text: &str) -> String {
+    let mut kept = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("-----Original Message-----")
+            || (trimmed.starts_with("On ") && trimmed.ends_with("wrote:"))
+        {
+            break;
+        }
+        if trimmed.starts_with('>') {
+            continue;
+        }
+        kept.push(line);
+    }
+    kept.join("\n").trim().to_string()
+}
+
+/// Compare a provided webhook secret without leaking through timing how much of
+/// it matched.
+pub fn secret_matches(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Whether the mail provider authenticated `sender` (a parsed address): SPF
+/// passed, or a DKIM signature of the sender's domain (or a parent domain)
+/// passed. `dkim` is the provider's result list, e.g. `{@example.com : pass}`.
+pub fn sender_authenticated(sender: &str, spf: Option<&str>, dkim: Option<&str>) -> bool {
+    if spf.is_some_and(|spf| spf.trim().eq_ignore_ascii_case("pass")) {
+        return true;
+    }
+    let Some((_, domain)) = sender.rsplit_once('@') else {
+        return false;
+    };
+    dkim.unwrap_or_default().split(',').any(|result| {
+        let Some((signer, verdict)) = result.trim().trim_matches(['{', '}']).split_once(':') else {
+            return false;
+        };
+        let signer = signer.trim().trim_start_matches('@').to_ascii_lowercase();
+        verdict.trim().eq_ignore_ascii_case("pass")
+            && !signer.is_empty()
+            && (domain == signer || domain.ends_with(&format!(".{signer}")))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrets_must_match_exactly() {
+        assert!(secret_matches("s3cret", "s3cret"));
+        assert!(!secret_matches("s3cres", "s3cret"));
+        assert!(!secret_matches("s3cret-longer", "s3cret"));
+        assert!(!secret_matches("", "s3cret"));
+    }
+
+    #[test]
+    fn sender_needs_spf_or_aligned_dkim() {
+        let sender = "jane@mail.example.com";
+        assert!(sender_authenticated(sender, Some("pass"), None));
+        assert!(sender_authenticated(
+            sender,
+            Some("softfail"),
+            Some("{@other.com : fail}, {@example.com : pass}")
+        ));
+        assert!(!sender_authenticated(
+            sender,
+            Some("fail"),
+            Some("{@attacker.com : pass}")
+        ));
+        assert!(!sender_authenticated(
+            sender,
+            None,
+            Some("{@example.com : fail}")
+        ));
+        assert!(!sender_authenticated(sender, None, None));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::services::sentry_alerts::SentryAlert;
+
 /// Google Chat webhook notification service.
 #[derive(Clone)]
 pub struct GoogleChatService {
@@ -11,16 +13,18 @@ impl GoogleChatService {
     }
 
     pub async fn send_message(&self, text: &str) {
+        self.post(&serde_json::json!({ "text": text })).await;
+    }
+
+    async fn post(&self, payload: &serde_json::Value) {
         let Some(url) = &self.webhook_url else {
             return;
         };
 
-        let payload = serde_json::json!({ "text": text });
-
         match self
             .http
             .post(url)
-            .json(&payload)
+            .json(payload)
             .timeout(std::time::Duration::from_secs(10))
             .send()
             .await
@@ -35,6 +39,43 @@ impl GoogleChatService {
         }
     }
 
+    /// Relay a Sentry alert as a card with a button through to the issue.
+    pub async fn notify_sentry_alert(&self, alert: &SentryAlert) {
+        let mut widgets = Vec::new();
+        for (label, value) in [
+            ("Project", &alert.project),
+            ("Environment", &alert.environment),
+            ("Level", &alert.level),
+            ("Culprit", &alert.culprit),
+            ("Alert rule", &alert.rule),
+        ] {
+            if let Some(value) = value {
+                widgets.push(serde_json::json!({
+                    "decoratedText": { "topLabel": label, "text": value }
+                }));
+            }
+        }
+        if let Some(url) = &alert.web_url {
+            widgets.push(serde_json::json!({
+                "buttonList": { "buttons": [{
+                    "text": "Open in Sentry",
+                    "onClick": { "openLink": { "url": url } }
+                }]}
+            }));
+        }
+
+        let payload = serde_json::json!({
+            "cardsV2": [{
+                "cardId": format!("sentry-{}", alert.issue_id),
+                "card": {
+                    "header": { "title": "🚨 Sentry alert", "subtitle": alert.title },
+                    "sections": [{ "widgets": widgets }]
+                }
+            }]
+        });
+        self.post(&payload).await;
+    }
+
     pub async fn notify_influencer_banned(&self, influencer_id: &str, influencer_name: &str) {
         self.send_message(&format!(
             "🚫 AI Influencer banned\nID: {influencer_id}\nName: {influencer_name}"
@@ -56,6 +97,13 @@ impl GoogleChatService {
         .await;
     }
 
+    pub async fn notify_backup_verification_failed(&self, error: &str) {
+        self.send_message(&format!(
+            "❌ Database backup verification failed\nError: {error}"
+        ))
+        .await;
+    }
+
     pub async fn notify_influencer_unban_failed(&self, influencer_id: &str, error: &str) {
         self.send_message(&format!(
             "❌ Failed to unban AI Influencer\nID: {influencer_id}\nError: {error}"
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::error::AppError;
+use crate::models::entities::Message;
+use crate::services::ai::GenerationOptions;
+
+const CAPTION_SYSTEM: &str = "You write alt text for images shared in a chat app. \
+Screen readers read it aloud to people who can't see the image.";
+
+const CAPTION_PROMPT: &str = "Describe the image in one plain sentence of at most 20 words. \
+Say what is shown and any text that is clearly readable. Don't start with \"Image of\" \
+and don't guess who a person is. If there are several images, cover them all briefly.";
+
+/// Captions longer than this are cut at a word boundary.
+const MAX_ALT_TEXT_CHARS: usize = 250;
+
+/// Write alt text for `message`'s images in the background and store it under
+/// `metadata.alt_text`. Messages without images are skipped; failures are only logged.
+pub fn spawn_caption(state: &Arc<AppState>, message: &Message) {
+    if !message.has_images() || !state.settings.load().image_captions_enabled {
+        return;
+    }
+    let state = state.clone();
+    let message_id = message.id.clone();
+    let media_urls = message.media_urls.clone();
+    tokio::spawn(async move {
+        let alt_text = match caption(&state, &media_urls).await {
+            Ok(text) => text,
+            Err(e) => {
+                tracing::warn!(error = %e, message_id, "Failed to caption image message");
+                return;
+            }
+        };
+        if let Err(e) = state
+            .db
+            .msg_repo()
+            .set_metadata_key(&message_id, "alt_text", &alt_text.into())
+            .await
+        {
+            tracing::warn!(error = %e, message_id, "Failed to store image caption");
+        }
+    });
+}
+
+async fn caption(state: &AppState, media_urls: &[String]) -> Result<String, AppError> {
+    let images = state
+        .storage
+        .generate_presigned_urls_batch(media_urls)
+        .await;
+    let images: Vec<String> = media_urls
+        .iter()
+        .map(|key| images.get(key).cloned().unwrap_or_else(|| key.clone()))
+        .collect();
+    let options = GenerationOptions {
+        max_tokens: Some(100),
+        temperature: Some(0.2),
+        ..Default::default()
+    };
+    let (text, _) = state
+        .gemini
+        .generate_response_with(CAPTION_PROMPT, CAPTION_SYSTEM, &[], Some(&images), &options)
+        .await?;
+    let text = text.trim().trim_matches('"').trim();
+    if text.is_empty() {
+        return Err(AppError::service_unavailable(
+            "Vision model returned no caption",
+        ));
+    }
+    Ok(truncate(text))
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_ALT_TEXT_CHARS {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(MAX_ALT_TEXT_CHARS).collect();
+    let cut = cut.rsplit_once(' ').map_or(cut.as_str(), |(head, _)| head);
+    format!("{}…", cut.trim_end_matches([',', ';', ':', ' ']))
+}
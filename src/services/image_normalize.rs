@@ -0,0 +1,55 @@
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+
+/// A re-encoded photo and the extension it was written as.
+pub struct Normalized {
+    pub bytes: Vec<u8>,
+    pub ext: &'static str,
+}
+
+/// Re-encode an uploaded photo upright and without EXIF/XMP metadata (phone
+/// photos carry GPS coordinates), lossy at `quality` where the format allows.
+/// Returns `Ok(None)` for formats left as-is: GIFs are passed through so
+/// animations survive.
+///
+/// The WebP encoder available is lossless only, which can come out several
+/// times larger than the upload. Opaque WebP photos are written as JPEG instead;
+/// ones with transparency stay lossless WebP.
+pub fn normalize(
+    bytes: &[u8],
+    ext: &str,
+    quality: u8,
+) -> Result<Option<Normalized>, image::ImageError> {
+    let format = match ext {
+        ".jpg" | ".jpeg" => ImageFormat::Jpeg,
+        ".png" => ImageFormat::Png,
+        ".webp" => ImageFormat::WebP,
+        _ => return Ok(None),
+    };
+
+    let mut decoder = ImageReader::with_format(Cursor::new(bytes), format).into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut img = DynamicImage::from_decoder(decoder)?;
+    img.apply_orientation(orientation);
+
+    // Encoders never write the source metadata back, so re-encoding strips it
+    let mut out = Vec::new();
+    let ext = match format {
+        ImageFormat::WebP if img.color().has_alpha() => {
+            DynamicImage::ImageRgba8(img.to_rgba8())
+                .write_to(&mut Cursor::new(&mut out), format)?;
+            ".webp"
+        }
+        ImageFormat::Jpeg | ImageFormat::WebP => {
+            JpegEncoder::new_with_quality(&mut out, quality).encode_image(&img.to_rgb8())?;
+            ".jpg"
+        }
+        _ => {
+            img.write_to(&mut Cursor::new(&mut out), format)?;
+            ".png"
+        }
+    };
+    Ok(Some(Normalized { bytes: out, ext }))
+}
@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use axum::http::{HeaderMap, HeaderValue};
+use chrono::Utc;
+
+use crate::db::Database;
+use crate::error::AppError;
+
+/// Where a user stands against the daily image quota.
+pub struct QuotaStatus {
+    /// 0 when there is no daily cap
+    pub limit: u32,
+    pub remaining: u32,
+    /// Seconds until the daily count resets (UTC midnight)
+    pub reset_secs: u64,
+}
+
+impl QuotaStatus {
+    pub fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if self.limit > 0 {
+            headers.insert("X-ImageQuota-Limit", HeaderValue::from(self.limit));
+            headers.insert("X-ImageQuota-Remaining", HeaderValue::from(self.remaining));
+            headers.insert("X-ImageQuota-Reset", HeaderValue::from(self.reset_secs));
+        }
+        headers
+    }
+}
+
+/// Per-user cap on image generation: a daily quota (UTC days) plus a cooldown
+/// between generations, on top of the global request rate limit. Usage is kept
+/// in the database so the cap holds across instances and restarts.
+pub struct ImageQuota {
+    daily_limit: u32,
+    cooldown: Duration,
+}
+
+impl ImageQuota {
+    /// 0 disables either check.
+    pub fn new(daily_limit: u32, cooldown: Duration) -> Self {
+        Self {
+            daily_limit,
+            cooldown,
+        }
+    }
+
+    /// Take one generation for `user_id`, or fail with how long to wait.
+    pub async fn acquire(&self, db: &Database, user_id: &str) -> Result<QuotaStatus, AppError> {
+        let now = Utc::now().naive_utc();
+        let today = now.date();
+        let reset_secs = today
+            .succ_opt()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map_or(0, |midnight| (midnight - now).num_seconds().max(1) as u64);
+        let cooldown = chrono::Duration::from_std(self.cooldown).unwrap_or_default();
+
+        let repo = db.image_quota_repo();
+        if let Some(count) = repo
+            .try_acquire(user_id, today, now, now - cooldown, self.daily_limit)
+            .await?
+        {
+            return Ok(QuotaStatus {
+                limit: self.daily_limit,
+                remaining: self.daily_limit.saturating_sub(count),
+                reset_secs,
+            });
+        }
+
+        // Refused: work out which check it was from the stored usage
+        let usage = repo.get(user_id).await?;
+        let used = usage
+            .as_ref()
+            .filter(|u| u.day == today)
+            .map_or(0, |u| u.count);
+        let status = QuotaStatus {
+            limit: self.daily_limit,
+            remaining: self.daily_limit.saturating_sub(used),
+            reset_secs,
+        };
+        if self.daily_limit > 0 && status.remaining == 0 {
+            return Err(AppError::image_quota_exceeded(
+                format!(
+                    "Daily image limit of {} reached. Try again tomorrow.",
+                    self.daily_limit
+                ),
+                reset_secs,
+                status.headers(),
+            ));
+        }
+        let wait = usage.map_or(0, |u| {
+            (u.last_at + cooldown - now).num_seconds().max(1) as u64
+        });
+        Err(AppError::image_quota_exceeded(
+            format!("Please wait {wait} seconds before generating another image."),
+            wait.max(1),
+            status.headers(),
+        ))
+    }
+
+    /// Give back a generation that failed upstream. The cooldown still applies.
+    pub async fn release(&self, db: &Database, user_id: &str) {
+        let today = Utc::now().date_naive();
+        if let Err(e) = db.image_quota_repo().release(user_id, today).await {
+            tracing::warn!(error = %e, "Failed to release image quota (non-fatal)");
+        }
+    }
+}
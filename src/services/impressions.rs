@@ -0,0 +1,120 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+
+use crate::AppState;
+
+/// Influencers with unflushed impressions; ids past this are dropped until the
+/// next flush so junk ids can't grow the buffer.
+const MAX_PENDING_INFLUENCERS: usize = 10_000;
+
+/// A user viewing the same profile again within this counts once.
+const VIEW_DEDUPE_WINDOW: Duration = Duration::from_secs(3600);
+/// Impressions one user can add per dedupe window, across all profiles.
+const MAX_IMPRESSIONS_PER_USER: u32 = 200;
+/// Above this many remembered views or users, expired ones are dropped.
+const MAX_TRACKED: usize = 100_000;
+
+/// Counts profile impressions in memory so each one doesn't cost a write;
+/// [`spawn_impression_flusher`] adds them to `view_count` periodically. Each
+/// user counts once per profile per window and only so many times in all, so a
+/// script replaying the endpoint can't inflate the ranking signal.
+#[derive(Default)]
+pub struct ImpressionBuffer {
+    pending: DashMap<String, i64>,
+    seen: DashMap<(String, String), Instant>,
+    per_user: DashMap<String, (Instant, u32)>,
+}
+
+impl ImpressionBuffer {
+    /// Count `user_id` viewing `influencer_id`; false when it didn't count.
+    pub fn record(&self, user_id: &str, influencer_id: &str) -> bool {
+        let now = Instant::now();
+        let view = (user_id.to_string(), influencer_id.to_string());
+        if self
+            .seen
+            .get(&view)
+            .is_some_and(|at| now.duration_since(*at) < VIEW_DEDUPE_WINDOW)
+        {
+            return false;
+        }
+        if self.seen.len() >= MAX_TRACKED {
+            self.seen
+                .retain(|_, at| now.duration_since(*at) < VIEW_DEDUPE_WINDOW);
+        }
+        if self.per_user.len() >= MAX_TRACKED {
+            self.per_user
+                .retain(|_, (start, _)| now.duration_since(*start) < VIEW_DEDUPE_WINDOW);
+        }
+        {
+            let mut quota = self.per_user.entry(user_id.to_string()).or_insert((now, 0));
+            if now.duration_since(quota.0) >= VIEW_DEDUPE_WINDOW {
+                *quota = (now, 0);
+            }
+            if quota.1 >= MAX_IMPRESSIONS_PER_USER {
+                return false;
+            }
+            quota.1 += 1;
+        }
+        self.seen.insert(view, now);
+
+        if let Some(mut count) = self.pending.get_mut(influencer_id) {
+            *count += 1;
+            return true;
+        }
+        if self.pending.len() < MAX_PENDING_INFLUENCERS {
+            *self.pending.entry(influencer_id.to_string()).or_default() += 1;
+        }
+        true
+    }
+
+    /// Remove and return everything counted so far.
+    fn drain(&self) -> Vec<(String, i64)> {
+        let ids: Vec<String> = self.pending.iter().map(|e| e.key().clone()).collect();
+        ids.into_iter()
+            .filter_map(|id| self.pending.remove(&id))
+            .collect()
+    }
+}
+
+/// Periodically write buffered impressions. A failed flush is logged and its
+/// counts are lost; view counts are a ranking signal, not billing.
+pub fn spawn_impression_flusher(state: Arc<AppState>, interval_secs: u64) {
+    tokio::spawn(async move {
+        let interval = std::time::Duration::from_secs(interval_secs);
+        loop {
+            tokio::time::sleep(interval).await;
+            let counts = state.impressions.drain();
+            if counts.is_empty() {
+                continue;
+            }
+            if let Err(e) = state.db.inf_repo().add_views(&counts).await {
+                tracing::warn!(error = %e, influencers = counts.len(), "Failed to flush influencer impressions (non-fatal)");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeat_views_count_once() {
+        let buffer = ImpressionBuffer::default();
+        assert!(buffer.record("user-1", "bot-1"));
+        assert!(!buffer.record("user-1", "bot-1"));
+        assert!(buffer.record("user-2", "bot-1"));
+        assert_eq!(buffer.drain(), [("bot-1".to_string(), 2)]);
+    }
+
+    #[test]
+    fn each_user_adds_a_bounded_number() {
+        let buffer = ImpressionBuffer::default();
+        let counted = (0..MAX_IMPRESSIONS_PER_USER + 50)
+            .filter(|i| buffer.record("script", &format!("bot-{i}")))
+            .count();
+        assert_eq!(counted, MAX_IMPRESSIONS_PER_USER as usize);
+    }
+}
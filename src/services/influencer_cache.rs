@@ -0,0 +1,91 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+
+use crate::db::repositories::InfluencerRepository;
+use crate::models::entities::AIInfluencer;
+use crate::models::responses::CacheStats;
+
+/// Entries kept before expired ones are swept out.
+const MAX_ENTRIES: usize = 10_000;
+
+struct Entry {
+    influencer: AIInfluencer,
+    loaded_at: Instant,
+}
+
+/// Read-through TTL cache in front of `InfluencerRepository::get_by_id`.
+/// Writers must call `invalidate` after changing an influencer.
+pub struct InfluencerCache {
+    entries: DashMap<String, Entry>,
+    ttl: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl InfluencerCache {
+    /// A zero `ttl` disables caching.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: DashMap::new(),
+            ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub async fn get_by_id(
+        &self,
+        repo: &InfluencerRepository,
+        influencer_id: &str,
+    ) -> Result<Option<AIInfluencer>, sqlx::Error> {
+        if let Some(entry) = self.entries.get(influencer_id)
+            && entry.loaded_at.elapsed() < self.ttl
+        {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(Some(entry.influencer.clone()));
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        // Misses aren't cached so a newly created influencer is visible immediately
+        let influencer = repo.get_by_id(influencer_id).await?;
+        if let Some(ref influencer) = influencer
+            && !self.ttl.is_zero()
+        {
+            if self.entries.len() >= MAX_ENTRIES {
+                self.entries.retain(|_, e| e.loaded_at.elapsed() < self.ttl);
+            }
+            if self.entries.len() < MAX_ENTRIES {
+                self.entries.insert(
+                    influencer_id.to_string(),
+                    Entry {
+                        influencer: influencer.clone(),
+                        loaded_at: Instant::now(),
+                    },
+                );
+            }
+        }
+        Ok(influencer)
+    }
+
+    pub fn invalidate(&self, influencer_id: &str) {
+        self.entries.remove(influencer_id);
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let lookups = hits + misses;
+        CacheStats {
+            entries: self.entries.len(),
+            hits,
+            misses,
+            hit_rate: if lookups == 0 {
+                0.0
+            } else {
+                hits as f64 / lookups as f64
+            },
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::error::AppError;
+use crate::models::entities::{AIInfluencer, GenerationStatus, InfluencerGeneration};
+use crate::services::avatar_moderation::{self, AvatarModerator};
+use crate::services::character_generator::CharacterGeneratorService;
+use crate::services::moderation;
+
+pub const STEP_AVATAR_CHECK: &str = "avatar_check";
+pub const STEP_AVATAR: &str = "avatar";
+pub const STEP_GREETING: &str = "greeting";
+pub const STEP_STARTER_VIDEO_PROMPT: &str = "starter_video_prompt";
+pub const STEP_STARTER_VIDEO: &str = "starter_video";
+
+/// Steps run in this order: the video renders from the avatar and the prompt.
+const STEP_ORDER: [&str; 5] = [
+    STEP_AVATAR_CHECK,
+    STEP_AVATAR,
+    STEP_GREETING,
+    STEP_STARTER_VIDEO_PROMPT,
+    STEP_STARTER_VIDEO,
+];
+
+/// Initial `metadata.generation` for a new influencer: every step pending.
+pub fn pending(steps: &[&str]) -> InfluencerGeneration {
+    InfluencerGeneration {
+        status: GenerationStatus::Pending,
+        steps: steps
+            .iter()
+            .map(|s| (s.to_string(), GenerationStatus::Pending))
+            .collect(),
+        starter_video_prompt: None,
+    }
+}
+
+/// Run the pending generation steps of a freshly created influencer in the
+/// background, recording progress in `metadata.generation` after each step.
+/// Steps are best-effort: a failed step is marked failed and the rest still run.
+pub fn spawn_enrichment(state: Arc<AppState>, influencer: AIInfluencer) {
+    tokio::spawn(async move {
+        let Some(mut generation) = influencer.generation() else {
+            return;
+        };
+        let repo = state.db.inf_repo();
+        let instructions = moderation::strip_guardrails(&influencer.system_instructions);
+        let settings = state.tenants.settings(&influencer.tenant);
+        let mut avatars = AvatarModerator::new(&state, &influencer);
+
+        generation.status = GenerationStatus::Running;
+        save_progress(&state, &influencer.id, &generation).await;
+
+        let mut saved = true;
+        let mut current_avatar = influencer.avatar_url.clone();
+        let mut video_prompt = influencer.starter_video_prompt();
+        let steps: Vec<String> = STEP_ORDER
+            .iter()
+            .filter(|s| generation.steps.contains_key(**s))
+            .map(|s| s.to_string())
+            .collect();
+        for step in steps {
+            set_step(&mut generation, &step, GenerationStatus::Running);
+            save_progress(&state, &influencer.id, &generation).await;
+
+            let mut avatar_url = None;
+            let mut greeting = None;
+            let mut suggested_messages = None;
+
+            let ok = match step.as_str() {
+                STEP_AVATAR_CHECK => match influencer.pending_avatar_url() {
+                    // Held back at creation so it never shows before it's checked
+                    Some(pending) => {
+                        let url = avatars
+                            .supplied(pending, settings.avatar_moderation_max_attempts)
+                            .await;
+                        if let Err(e) = repo
+                            .set_metadata_key(
+                                &influencer.id,
+                                "pending_avatar_url",
+                                &serde_json::Value::Null,
+                            )
+                            .await
+                        {
+                            tracing::warn!(error = %e, influencer_id = %influencer.id, "Failed to clear pending avatar (non-fatal)");
+                        }
+                        match url {
+                            Some(url) => {
+                                current_avatar = Some(url.clone());
+                                avatar_url = Some(url);
+                                true
+                            }
+                            None => false,
+                        }
+                    }
+                    None => true,
+                },
+                STEP_AVATAR => {
+                    let url = if settings.avatar_moderation_enabled {
+                        avatars
+                            .generate(settings.avatar_moderation_max_attempts)
+                            .await
+                    } else {
+                        generate_avatar(&state, &influencer).await
+                    };
+                    match url {
+                        Some(url) => {
+                            current_avatar = Some(url.clone());
+                            avatar_url = Some(url);
+                            true
+                        }
+                        None => false,
+                    }
+                }
+                STEP_GREETING => {
+                    match CharacterGeneratorService::generate_initial_greeting(
+                        state.gemini.as_ref(),
+                        &influencer.display_name,
+                        &instructions,
+                    )
+                    .await
+                    {
+                        Ok((gen_greeting, gen_suggestions)) => {
+                            if influencer.initial_greeting.is_none() {
+                                greeting = Some(gen_greeting);
+                            }
+                            if influencer.suggested_messages.is_empty() {
+                                suggested_messages = Some(gen_suggestions);
+                            }
+                            true
+                        }
+                        Err(e) => {
+                            tracing::error!(error = %e, influencer_id = %influencer.id, "Failed to generate greeting");
+                            if influencer.initial_greeting.is_none() {
+                                greeting = Some(format!(
+                                    "Hey! I'm {}! How can I help you today?",
+                                    influencer.display_name
+                                ));
+                            }
+                            false
+                        }
+                    }
+                }
+                STEP_STARTER_VIDEO_PROMPT => {
+                    match CharacterGeneratorService::generate_starter_video_prompt(
+                        state.gemini.as_ref(),
+                        &influencer.display_name,
+                        &instructions,
+                    )
+                    .await
+                    {
+                        Ok(prompt) => {
+                            video_prompt = Some(prompt.clone());
+                            match repo
+                                .set_metadata_key(
+                                    &influencer.id,
+                                    "starter_video_prompt",
+                                    &serde_json::json!(prompt),
+                                )
+                                .await
+                            {
+                                Ok(()) => true,
+                                Err(e) => {
+                                    tracing::error!(error = %e, influencer_id = %influencer.id, "Failed to save starter video prompt");
+                                    saved = false;
+                                    false
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            tracing::error!(error = %e, influencer_id = %influencer.id, "Failed to generate starter video prompt");
+                            false
+                        }
+                    }
+                }
+                STEP_STARTER_VIDEO => match video_prompt.as_deref() {
+                    Some(prompt) => {
+                        render_starter_video(
+                            &state,
+                            &influencer.id,
+                            prompt,
+                            current_avatar.as_deref(),
+                        )
+                        .await
+                    }
+                    None => false,
+                },
+                _ => false,
+            };
+
+            // Results are saved as each step finishes so clients see them while polling
+            if let Err(e) = repo
+                .apply_enrichment(
+                    &influencer.id,
+                    avatar_url.as_deref(),
+                    greeting.as_deref(),
+                    suggested_messages.as_deref(),
+                )
+                .await
+            {
+                tracing::error!(error = %e, influencer_id = %influencer.id, step, "Failed to save enrichment");
+                saved = false;
+            }
+
+            let status = if ok {
+                GenerationStatus::Completed
+            } else {
+                GenerationStatus::Failed
+            };
+            set_step(&mut generation, &step, status);
+        }
+
+        generation.status = if saved {
+            GenerationStatus::Completed
+        } else {
+            GenerationStatus::Failed
+        };
+        save_progress(&state, &influencer.id, &generation).await;
+    });
+}
+
+/// Generate an avatar without a safety check.
+async fn generate_avatar(state: &AppState, influencer: &AIInfluencer) -> Option<String> {
+    let prompt = avatar_moderation::avatar_prompt(influencer, 1);
+    match state.replicate.generate_image(&prompt, "1:1").await {
+        Ok(url) => url,
+        Err(e) => {
+            tracing::error!(error = %e, influencer_id = %influencer.id, "Avatar generation failed");
+            None
+        }
+    }
+}
+
+/// Render the video on Replicate, copy it into our bucket and record its key.
+async fn render_starter_video(
+    state: &AppState,
+    influencer_id: &str,
+    prompt: &str,
+    avatar_url: Option<&str>,
+) -> bool {
+    let image = match avatar_url {
+        Some(url) => Some(state.storage.generate_presigned_url(url).await),
+        None => None,
+    };
+    let result = async {
+        let url = state
+            .replicate
+            .generate_video(prompt, image.as_deref())
+            .await?
+            .ok_or_else(|| AppError::service_unavailable("Replicate returned no video"))?;
+        let key = state.storage.import_object(&url, influencer_id).await?;
+        state
+            .db
+            .inf_repo()
+            .set_metadata_key(influencer_id, "starter_video_key", &serde_json::json!(key))
+            .await?;
+        Ok::<_, AppError>(())
+    }
+    .await;
+    if let Err(e) = result {
+        tracing::error!(error = %e, influencer_id, "Starter video generation failed");
+        return false;
+    }
+    true
+}
+
+fn set_step(generation: &mut InfluencerGeneration, step: &str, status: GenerationStatus) {
+    generation.steps.insert(step.to_string(), status);
+}
+
+async fn save_progress(state: &AppState, influencer_id: &str, generation: &InfluencerGeneration) {
+    let value = serde_json::to_value(generation).unwrap_or_default();
+    if let Err(e) = state
+        .db
+        .inf_repo()
+        .set_metadata_key(influencer_id, "generation", &value)
+        .await
+    {
+        tracing::warn!(error = %e, influencer_id, "Failed to record generation progress (non-fatal)");
+    }
+    state.influencer_cache.invalidate(influencer_id);
+}
@@ -0,0 +1,290 @@
+use std::fmt::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::db::repositories::{KnowledgeLimits, KnowledgeRepository};
+use crate::error::AppError;
+use crate::models::entities::{KnowledgeChunk, KnowledgeDocument};
+use crate::services::ai::EmbeddingTask;
+
+/// How long an influencer's chunks stay cached. Uploads and deletes on this
+/// instance invalidate at once; other instances pick them up within this.
+const INDEX_TTL: Duration = Duration::from_secs(300);
+
+/// Memory the cached chunks may take, text and embeddings together. Past it,
+/// expired entries are swept out, then the least recently loaded.
+const MAX_CACHED_BYTES: usize = 256 * 1024 * 1024;
+
+struct Entry {
+    chunks: Arc<Vec<KnowledgeChunk>>,
+    bytes: usize,
+    loaded_at: Instant,
+}
+
+/// Read-through cache of each influencer's knowledge chunks and their embeddings,
+/// so similarity search doesn't reload them for every message. Influencers without
+/// documents are cached too, which lets their messages skip the query embedding.
+#[derive(Default)]
+pub struct KnowledgeIndex {
+    entries: DashMap<String, Entry>,
+    bytes: AtomicUsize,
+}
+
+impl KnowledgeIndex {
+    pub async fn chunks(
+        &self,
+        repo: &KnowledgeRepository,
+        influencer_id: &str,
+    ) -> Result<Arc<Vec<KnowledgeChunk>>, sqlx::Error> {
+        if let Some(entry) = self.entries.get(influencer_id)
+            && entry.loaded_at.elapsed() < INDEX_TTL
+        {
+            return Ok(entry.chunks.clone());
+        }
+        let chunks = Arc::new(repo.list_chunks(influencer_id).await?);
+        let bytes = chunks
+            .iter()
+            .map(|c| c.content.len() + c.embedding.len() * size_of::<f32>())
+            .sum();
+        if bytes > MAX_CACHED_BYTES {
+            return Ok(chunks);
+        }
+        if self.bytes.load(Ordering::Relaxed) + bytes > MAX_CACHED_BYTES {
+            self.evict(MAX_CACHED_BYTES - bytes);
+        }
+        self.insert(
+            influencer_id,
+            Entry {
+                chunks: chunks.clone(),
+                bytes,
+                loaded_at: Instant::now(),
+            },
+        );
+        Ok(chunks)
+    }
+
+    pub fn invalidate(&self, influencer_id: &str) {
+        if let Some((_, entry)) = self.entries.remove(influencer_id) {
+            self.bytes.fetch_sub(entry.bytes, Ordering::Relaxed);
+        }
+    }
+
+    fn insert(&self, influencer_id: &str, entry: Entry) {
+        self.bytes.fetch_add(entry.bytes, Ordering::Relaxed);
+        if let Some(old) = self.entries.insert(influencer_id.to_string(), entry) {
+            self.bytes.fetch_sub(old.bytes, Ordering::Relaxed);
+        }
+    }
+
+    /// Drop expired entries, then the oldest, until at most `target` bytes are cached.
+    fn evict(&self, target: usize) {
+        let mut by_age: Vec<(Instant, String)> = self
+            .entries
+            .iter()
+            .map(|e| (e.loaded_at, e.key().clone()))
+            .collect();
+        by_age.sort_unstable();
+        for (loaded_at, influencer_id) in by_age {
+            if loaded_at.elapsed() < INDEX_TTL && self.bytes.load(Ordering::Relaxed) <= target {
+                break;
+            }
+            self.invalidate(&influencer_id);
+        }
+    }
+}
+
+/// Split `text` into chunks of about `max_chars`, breaking between paragraphs
+/// where possible and otherwise between words.
+pub fn chunk_text(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(100);
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut push = |current: &mut String| {
+        let chunk = current.trim();
+        if !chunk.is_empty() {
+            chunks.push(chunk.to_string());
+        }
+        current.clear();
+    };
+
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        if current.chars().count() + paragraph.chars().count() + 2 > max_chars {
+            push(&mut current);
+        }
+        if paragraph.chars().count() <= max_chars {
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(paragraph);
+            continue;
+        }
+        // A paragraph longer than a chunk is cut between words
+        for word in paragraph.split_whitespace() {
+            if current.chars().count() + word.chars().count() + 1 > max_chars {
+                push(&mut current);
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+    }
+    push(&mut current);
+    chunks
+}
+
+/// Chunk and embed a document's text and add it to the influencer's knowledge base.
+pub async fn ingest(
+    state: &AppState,
+    mut document: KnowledgeDocument,
+    text: &str,
+) -> Result<KnowledgeDocument, AppError> {
+    let settings = state.settings.load();
+    let limits = KnowledgeLimits {
+        max_documents: settings.knowledge_max_documents,
+        max_chunks: settings.knowledge_max_chunks,
+    };
+    let chunks = chunk_text(text, settings.knowledge_chunk_chars);
+    if chunks.is_empty() {
+        return Err(AppError::field_error("file", "Document contains no text"));
+    }
+    // Checked before embedding so an oversized document costs nothing
+    if chunks.len() > limits.max_chunks {
+        return Err(AppError::field_error(
+            "file",
+            format!(
+                "Document is too long: {} passages, the knowledge base holds {}",
+                chunks.len(),
+                limits.max_chunks
+            ),
+        ));
+    }
+    let embeddings = state.gemini.embed(&chunks, EmbeddingTask::Document).await?;
+
+    document.chunk_count = chunks.len() as i32;
+    let rows: Vec<(String, Vec<f32>)> = chunks.into_iter().zip(embeddings).collect();
+    if !state
+        .db
+        .knowledge_repo()
+        .create(&document, &rows, limits)
+        .await?
+    {
+        return Err(AppError::field_error(
+            "file",
+            format!(
+                "Knowledge base is full ({} documents or {} passages). Delete one first",
+                limits.max_documents, limits.max_chunks
+            ),
+        ));
+    }
+    state.knowledge_index.invalidate(&document.influencer_id);
+    Ok(document)
+}
+
+/// New document record for an upload; `chunk_count` is filled in by [`ingest`].
+pub fn new_document(
+    influencer_id: &str,
+    title: &str,
+    content_type: &str,
+    size_bytes: usize,
+    uploaded_by: &str,
+) -> KnowledgeDocument {
+    KnowledgeDocument {
+        id: Uuid::new_v4().to_string(),
+        influencer_id: influencer_id.to_string(),
+        title: title.to_string(),
+        content_type: content_type.to_string(),
+        size_bytes: size_bytes as i64,
+        chunk_count: 0,
+        uploaded_by: uploaded_by.to_string(),
+        created_at: chrono::Utc::now().naive_utc(),
+    }
+}
+
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// The influencer's passages most similar to `query`, best first. Retrieval is
+/// best effort: failures are logged and leave the turn without passages.
+pub async fn relevant_passages(state: &AppState, influencer_id: &str, query: &str) -> Vec<String> {
+    let settings = state.settings.load();
+    if !settings.knowledge_base_enabled || settings.knowledge_top_k == 0 || query.trim().is_empty()
+    {
+        return Vec::new();
+    }
+    let chunks = match state
+        .knowledge_index
+        .chunks(&state.db.knowledge_repo(), influencer_id)
+        .await
+    {
+        Ok(chunks) if !chunks.is_empty() => chunks,
+        Ok(_) => return Vec::new(),
+        Err(e) => {
+            tracing::warn!(error = %e, influencer_id, "Failed to load knowledge chunks");
+            return Vec::new();
+        }
+    };
+    let query_embedding = match state
+        .gemini
+        .embed(&[query.to_string()], EmbeddingTask::Query)
+        .await
+    {
+        Ok(mut embeddings) if !embeddings.is_empty() => embeddings.swap_remove(0),
+        Ok(_) => return Vec::new(),
+        Err(e) => {
+            tracing::warn!(error = %e, influencer_id, "Failed to embed knowledge query");
+            return Vec::new();
+        }
+    };
+
+    let mut scored: Vec<(f32, &KnowledgeChunk)> = chunks
+        .iter()
+        .map(|chunk| (cosine_similarity(&query_embedding, &chunk.embedding), chunk))
+        .filter(|(score, _)| *score >= settings.knowledge_min_score)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored
+        .into_iter()
+        .take(settings.knowledge_top_k)
+        .map(|(_, chunk)| chunk.content.clone())
+        .collect()
+}
+
+/// Append the influencer's passages relevant to `query` to the system prompt.
+pub async fn augment_prompt(
+    state: &AppState,
+    influencer_id: &str,
+    query: &str,
+    system_instructions: &mut String,
+) {
+    let passages = relevant_passages(state, influencer_id, query).await;
+    if passages.is_empty() {
+        return;
+    }
+    system_instructions.push_str(
+        "\n\n**KNOWLEDGE:**\nExcerpts from your reference documents that may help with the \
+         user's latest message. Use them for facts; if they don't cover the question, say \
+         what you know without making things up.\n",
+    );
+    for passage in &passages {
+        let _ = write!(system_instructions, "\n---\n{passage}\n");
+    }
+}
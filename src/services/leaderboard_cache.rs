@@ -0,0 +1,65 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::NaiveDateTime;
+use dashmap::DashMap;
+
+use crate::db::repositories::InfluencerRepository;
+use crate::models::entities::{LeaderboardEntry, LeaderboardMetric, LeaderboardWindow};
+
+/// Influencers kept per leaderboard; requests take a prefix.
+const LEADERBOARD_SIZE: i64 = 100;
+
+struct Entry {
+    ranking: Arc<Vec<LeaderboardEntry>>,
+    generated_at: NaiveDateTime,
+    loaded_at: Instant,
+}
+
+/// Computed leaderboards per tenant, window and metric, recomputed once `ttl` has passed.
+/// The aggregation scans every user message in the window, so it must not run per request.
+pub struct LeaderboardCache {
+    entries: DashMap<(String, LeaderboardWindow, LeaderboardMetric), Entry>,
+    ttl: Duration,
+}
+
+impl LeaderboardCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: DashMap::new(),
+            ttl,
+        }
+    }
+
+    /// The ranking and when it was computed.
+    pub async fn get(
+        &self,
+        repo: &InfluencerRepository,
+        tenant: &str,
+        window: LeaderboardWindow,
+        metric: LeaderboardMetric,
+    ) -> Result<(Arc<Vec<LeaderboardEntry>>, NaiveDateTime), sqlx::Error> {
+        let key = (tenant.to_string(), window, metric);
+        if let Some(entry) = self.entries.get(&key)
+            && entry.loaded_at.elapsed() < self.ttl
+        {
+            return Ok((entry.ranking.clone(), entry.generated_at));
+        }
+
+        let now = chrono::Utc::now().naive_utc();
+        let since = now - chrono::Duration::days(window.days());
+        let ranking = Arc::new(
+            repo.leaderboard(tenant, since, metric, LEADERBOARD_SIZE)
+                .await?,
+        );
+        self.entries.insert(
+            key,
+            Entry {
+                ranking: ranking.clone(),
+                generated_at: now,
+                loaded_at: Instant::now(),
+            },
+        );
+        Ok((ranking, now))
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+use sqlx::Row;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow};
+
+use crate::error::AppError;
+use crate::models::entities::{
+    AIInfluencer, Conversation, InfluencerStatus, Message, MessageRole, MessageType,
+};
+use crate::services::tenants::DEFAULT_TENANT;
+
+/// Columns read from each legacy table, with the SQLite type they are cast to.
+const INFLUENCER_COLUMNS: &[(&str, &str)] = &[
+    ("id", "TEXT"),
+    ("name", "TEXT"),
+    ("display_name", "TEXT"),
+    ("avatar_url", "TEXT"),
+    ("description", "TEXT"),
+    ("category", "TEXT"),
+    ("system_instructions", "TEXT"),
+    ("personality_traits", "TEXT"),
+    ("initial_greeting", "TEXT"),
+    ("suggested_messages", "TEXT"),
+    ("is_active", "TEXT"),
+    ("is_nsfw", "INTEGER"),
+    ("parent_principal_id", "TEXT"),
+    ("source", "TEXT"),
+    ("created_at", "TEXT"),
+    ("updated_at", "TEXT"),
+    ("metadata", "TEXT"),
+];
+
+const CONVERSATION_COLUMNS: &[(&str, &str)] = &[
+    ("id", "TEXT"),
+    ("user_id", "TEXT"),
+    ("influencer_id", "TEXT"),
+    ("created_at", "TEXT"),
+    ("updated_at", "TEXT"),
+    ("metadata", "TEXT"),
+];
+
+const MESSAGE_COLUMNS: &[(&str, &str)] = &[
+    ("id", "TEXT"),
+    ("conversation_id", "TEXT"),
+    ("role", "TEXT"),
+    ("content", "TEXT"),
+    ("message_type", "TEXT"),
+    ("media_urls", "TEXT"),
+    ("audio_url", "TEXT"),
+    ("audio_duration_seconds", "INTEGER"),
+    ("token_count", "INTEGER"),
+    ("client_message_id", "TEXT"),
+    ("created_at", "TEXT"),
+    ("metadata", "TEXT"),
+    ("status", "TEXT"),
+    ("is_read", "INTEGER"),
+];
+
+/// Read-only view of a SQLite database from the Python backend. Columns that the
+/// dump predates are read as NULL, so older dumps load with defaults.
+pub struct LegacyDump {
+    pool: SqlitePool,
+    influencer_cols: String,
+    conversation_cols: String,
+    message_cols: String,
+}
+
+impl LegacyDump {
+    pub async fn open(path: &Path) -> Result<Self, AppError> {
+        let options = SqliteConnectOptions::new().filename(path).read_only(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .map_err(|e| AppError::bad_request(format!("Not a readable SQLite file: {e}")))?;
+
+        let influencer_cols = select_list(&pool, "ai_influencers", INFLUENCER_COLUMNS).await?;
+        let conversation_cols = select_list(&pool, "conversations", CONVERSATION_COLUMNS).await?;
+        let message_cols = select_list(&pool, "messages", MESSAGE_COLUMNS).await?;
+
+        Ok(Self {
+            pool,
+            influencer_cols,
+            conversation_cols,
+            message_cols,
+        })
+    }
+
+    pub async fn close(self) {
+        self.pool.close().await;
+    }
+
+    pub async fn influencers(&self) -> Result<Vec<AIInfluencer>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM ai_influencers ORDER BY created_at ASC",
+            self.influencer_cols
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(influencer_from_row).collect()
+    }
+
+    pub async fn conversations(&self) -> Result<Vec<Conversation>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM conversations ORDER BY created_at ASC",
+            self.conversation_cols
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(conversation_from_row).collect()
+    }
+
+    /// Messages of one conversation, oldest first. Rows that can't be mapped onto the
+    /// current schema come back as `Err` with the reason.
+    pub async fn messages(
+        &self,
+        conversation_id: &str,
+    ) -> Result<Vec<Result<Message, String>>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM messages WHERE conversation_id = ? ORDER BY created_at ASC",
+            self.message_cols
+        ))
+        .bind(conversation_id)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(message_from_row).collect()
+    }
+}
+
+/// Build a select list for `table`, casting present columns and substituting NULL for
+/// missing ones.
+async fn select_list(
+    pool: &SqlitePool,
+    table: &str,
+    columns: &[(&str, &str)],
+) -> Result<String, AppError> {
+    let present: HashSet<String> =
+        sqlx::query_scalar(&format!("SELECT name FROM pragma_table_info('{table}')"))
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::bad_request(format!("Failed to read legacy schema: {e}")))?
+            .into_iter()
+            .collect();
+    if !present.contains("id") {
+        return Err(AppError::bad_request(format!(
+            "Not a legacy dump: table '{table}' is missing"
+        )));
+    }
+
+    Ok(columns
+        .iter()
+        .map(|(name, ty)| {
+            if present.contains(*name) {
+                format!("CAST({name} AS {ty}) AS {name}")
+            } else {
+                format!("NULL AS {name}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", "))
+}
+
+/// The Python backend wrote both SQLite's `datetime('now')` format and ISO 8601.
+fn parse_legacy_dt(value: Option<String>) -> Option<NaiveDateTime> {
+    let value = value?;
+    let value = value.trim();
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(value, fmt).ok())
+        .or_else(|| {
+            chrono::DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|dt| dt.naive_utc())
+        })
+}
+
+fn parse_object(value: Option<String>) -> serde_json::Value {
+    value
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .filter(serde_json::Value::is_object)
+        .unwrap_or(serde_json::Value::Object(Default::default()))
+}
+
+/// JSON arrays are the norm; a bare string is taken as a single entry.
+fn parse_string_list(value: Option<String>) -> Vec<String> {
+    match value {
+        Some(v) if !v.trim().is_empty() => {
+            serde_json::from_str(&v).unwrap_or_else(|_| vec![v.trim().to_string()])
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn parse_status(value: Option<String>) -> InfluencerStatus {
+    match value.as_deref().map(str::trim) {
+        Some("0") | Some("false") => InfluencerStatus::Discontinued,
+        Some(v) => v.parse().unwrap_or(InfluencerStatus::Active),
+        None => InfluencerStatus::Active,
+    }
+}
+
+fn influencer_from_row(row: &SqliteRow) -> Result<AIInfluencer, sqlx::Error> {
+    let name: String = row
+        .try_get::<Option<String>, _>("name")?
+        .unwrap_or_default();
+    let created_at = parse_legacy_dt(row.try_get("created_at")?)
+        .unwrap_or_else(|| chrono::Utc::now().naive_utc());
+    Ok(AIInfluencer {
+        id: row.try_get::<Option<String>, _>("id")?.unwrap_or_default(),
+        display_name: row
+            .try_get::<Option<String>, _>("display_name")?
+            .unwrap_or_else(|| name.clone()),
+        name,
+        avatar_url: row.try_get("avatar_url")?,
+        description: row.try_get("description")?,
+        category: row.try_get("category")?,
+        system_instructions: row
+            .try_get::<Option<String>, _>("system_instructions")?
+            .unwrap_or_default(),
+        personality_traits: parse_object(row.try_get("personality_traits")?),
+        initial_greeting: row.try_get("initial_greeting")?,
+        suggested_messages: parse_string_list(row.try_get("suggested_messages")?),
+        is_active: parse_status(row.try_get("is_active")?),
+        is_nsfw: row.try_get::<Option<i64>, _>("is_nsfw")?.unwrap_or(0) != 0,
+        is_verified: false,
+        is_official: false,
+        parent_principal_id: row.try_get("parent_principal_id")?,
+        source: row.try_get("source")?,
+        tenant: DEFAULT_TENANT.to_string(),
+        created_at,
+        updated_at: parse_legacy_dt(row.try_get("updated_at")?).unwrap_or(created_at),
+        metadata: parse_object(row.try_get("metadata")?),
+        conversation_count: None,
+        message_count: None,
+        view_count: 0,
+    })
+}
+
+fn conversation_from_row(row: &SqliteRow) -> Result<Conversation, sqlx::Error> {
+    let created_at = parse_legacy_dt(row.try_get("created_at")?)
+        .unwrap_or_else(|| chrono::Utc::now().naive_utc());
+    Ok(Conversation {
+        id: row.try_get::<Option<String>, _>("id")?.unwrap_or_default(),
+        user_id: row
+            .try_get::<Option<String>, _>("user_id")?
+            .unwrap_or_default(),
+        influencer_id: row
+            .try_get::<Option<String>, _>("influencer_id")?
+            .unwrap_or_default(),
+        created_at,
+        updated_at: parse_legacy_dt(row.try_get("updated_at")?).unwrap_or(created_at),
+        metadata: parse_object(row.try_get("metadata")?),
+        influencer: None,
+        message_count: None,
+        unread_count: 0,
+        last_message: None,
+        recent_messages: None,
+    })
+}
+
+fn message_from_row(row: &SqliteRow) -> Result<Result<Message, String>, sqlx::Error> {
+    let id: String = row.try_get::<Option<String>, _>("id")?.unwrap_or_default();
+    let role: Option<String> = row.try_get("role")?;
+    let Some(role) = role.as_deref().and_then(|r| r.parse::<MessageRole>().ok()) else {
+        return Ok(Err(format!(
+            "Message {id}: unsupported role {}",
+            role.as_deref().unwrap_or("NULL")
+        )));
+    };
+    let Some(created_at) = parse_legacy_dt(row.try_get("created_at")?) else {
+        return Ok(Err(format!("Message {id}: missing or invalid created_at")));
+    };
+
+    Ok(Ok(Message {
+        id,
+        conversation_id: row
+            .try_get::<Option<String>, _>("conversation_id")?
+            .unwrap_or_default(),
+        role,
+        content: row.try_get("content")?,
+        message_type: row
+            .try_get::<Option<String>, _>("message_type")?
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(MessageType::Text),
+        media_urls: parse_string_list(row.try_get("media_urls")?),
+        audio_url: row.try_get("audio_url")?,
+        audio_duration_seconds: row
+            .try_get::<Option<i64>, _>("audio_duration_seconds")?
+            .map(|v| v as i32),
+        token_count: row
+            .try_get::<Option<i64>, _>("token_count")?
+            .map(|v| v as i32),
+        client_message_id: row.try_get("client_message_id")?,
+        created_at,
+        metadata: parse_object(row.try_get("metadata")?),
+        status: row
+            .try_get::<Option<String>, _>("status")?
+            .unwrap_or("delivered".to_string()),
+        is_read: row.try_get::<Option<i64>, _>("is_read")?.unwrap_or(0) != 0,
+    }))
+}
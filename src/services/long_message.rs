@@ -0,0 +1,104 @@
+use futures::future::join_all;
+
+use crate::error::AppError;
+use crate::services::ai::AiApi;
+
+const CONDENSE_SYSTEM_PROMPT: &str = "You condense parts of long chat messages. \
+     Keep names, numbers, questions and requests. Reply with the condensed text only.";
+
+/// Words each part is condensed to.
+const PART_SUMMARY_WORDS: usize = 120;
+
+/// Shrink a user message longer than `threshold` characters before it reaches the
+/// model: it is split into `chunk_chars` parts that are summarized concurrently.
+/// Returns `None` when the message is short enough to send as is. If summarizing
+/// fails, the start of the message is kept instead.
+pub async fn condense(
+    ai: &dyn AiApi,
+    text: &str,
+    threshold: usize,
+    chunk_chars: usize,
+) -> Option<String> {
+    let length = text.chars().count();
+    if threshold == 0 || length <= threshold {
+        return None;
+    }
+
+    let chunks = split_chunks(text, chunk_chars.max(500));
+    let total = chunks.len();
+    let summaries = join_all(
+        chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| summarize_part(ai, chunk, i + 1, total)),
+    )
+    .await;
+
+    let condensed = summaries
+        .into_iter()
+        .enumerate()
+        .map(|(i, summary)| summary.map(|s| format!("({}/{total}) {s}", i + 1)))
+        .collect::<Result<Vec<_>, _>>();
+    match condensed {
+        Ok(parts) => Some(format!(
+            "[The user sent a long message ({length} characters). Condensed, part by part:]\n{}",
+            parts.join("\n")
+        )),
+        Err(e) => {
+            tracing::warn!(error = %e, length, "Condensing long message failed, truncating");
+            let head: String = text.chars().take(threshold).collect();
+            Some(format!(
+                "{head}\n[Message truncated: {length} characters in total]"
+            ))
+        }
+    }
+}
+
+async fn summarize_part(
+    ai: &dyn AiApi,
+    chunk: &str,
+    part: usize,
+    total: usize,
+) -> Result<String, AppError> {
+    let prompt = format!(
+        "Condense part {part} of {total} of a message a user sent in a chat to at most \
+         {PART_SUMMARY_WORDS} words, in the language it is written in.\n\n{chunk}"
+    );
+    let (text, _) = ai
+        .generate_response(&prompt, CONDENSE_SYSTEM_PROMPT, &[], None)
+        .await?;
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::service_unavailable("Empty response from AI"));
+    }
+    Ok(text.to_string())
+}
+
+/// Split into parts of at most `max_chars` characters, preferring to break after
+/// a newline, then after a space, so words stay whole.
+fn split_chunks(text: &str, max_chars: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let Some((limit, _)) = rest.char_indices().nth(max_chars) else {
+            chunks.push(rest);
+            break;
+        };
+        let window = &rest[..limit];
+        let cut = [window.rfind('\n'), window.rfind(' ')]
+            .into_iter()
+            .flatten()
+            .find(|&i| i > limit / 2)
+            .map(|i| i + 1)
+            .unwrap_or(limit);
+        chunks.push(&rest[..cut]);
+        rest = &rest[cut..];
+    }
+    chunks
+}
+
+/// Cut a stored message down to `max_chars` for use as conversation history.
+pub fn clip_for_history(content: &str, max_chars: usize) -> Option<String> {
+    let (limit, _) = content.char_indices().nth(max_chars)?;
+    Some(format!("{}…", &content[..limit]))
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::NaiveDateTime;
+
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::models::responses::{MemorySizeBucket, MemoryStats};
+use crate::services::ai::AiApi;
+use crate::services::pii::PiiPolicy;
+
+/// Conversation metadata key holding when each memory was last written or mentioned.
+pub const REFS_KEY: &str = "memory_refs";
+
+/// Upper bounds of the memory-count buckets reported in [`MemoryStats`].
+const SIZE_BUCKETS: [usize; 5] = [5, 10, 20, 30, 50];
+
+/// Values shorter than this are too generic ("no", "yes") to count as mentioned.
+const MIN_MENTION_CHARS: usize = 3;
+
+pub type MemoryRefs = HashMap<String, NaiveDateTime>;
+
+/// Size caps applied to a conversation's memories after every extraction.
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryLimits {
+    pub max_count: usize,
+    pub max_value_chars: usize,
+    /// Ask the model to merge redundant memories above this count; 0 never asks
+    pub consolidate_at: usize,
+}
+
+impl MemoryLimits {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            max_count: settings.memory_max_count.max(1),
+            max_value_chars: settings.memory_max_value_chars.max(1),
+            consolidate_at: settings.memory_consolidate_at,
+        }
+    }
+}
+
+/// Counters and the size distribution of saved memory sets, for `/status`.
+#[derive(Default)]
+pub struct MemoryMetrics {
+    updates: AtomicU64,
+    pruned: AtomicU64,
+    consolidations: AtomicU64,
+    consolidation_failures: AtomicU64,
+    total_chars: AtomicU64,
+    buckets: [AtomicU64; SIZE_BUCKETS.len() + 1],
+}
+
+impl MemoryMetrics {
+    pub fn record_save(&self, memories: &HashMap<String, String>) {
+        self.updates.fetch_add(1, Ordering::Relaxed);
+        let chars: usize = memories
+            .iter()
+            .map(|(k, v)| k.chars().count() + v.chars().count())
+            .sum();
+        self.total_chars.fetch_add(chars as u64, Ordering::Relaxed);
+        let bucket = SIZE_BUCKETS
+            .iter()
+            .position(|&max| memories.len() <= max)
+            .unwrap_or(SIZE_BUCKETS.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> MemoryStats {
+        let updates = self.updates.load(Ordering::Relaxed);
+        let mut lower = 0;
+        let size_distribution = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(i, count)| {
+                let range = match SIZE_BUCKETS.get(i) {
+                    Some(max) => format!("{lower}-{max}"),
+                    None => format!("{lower}+"),
+                };
+                lower = SIZE_BUCKETS.get(i).map_or(lower, |max| max + 1);
+                MemorySizeBucket {
+                    range,
+                    updates: count.load(Ordering::Relaxed),
+                }
+            })
+            .collect();
+        MemoryStats {
+            updates,
+            pruned: self.pruned.load(Ordering::Relaxed),
+            consolidations: self.consolidations.load(Ordering::Relaxed),
+            consolidation_failures: self.consolidation_failures.load(Ordering::Relaxed),
+            mean_chars: if updates == 0 {
+                0.0
+            } else {
+                self.total_chars.load(Ordering::Relaxed) as f64 / updates as f64
+            },
+            size_distribution,
+        }
+    }
+}
+
+/// Read the reference timestamps stored next to the memories.
+pub fn load_refs(metadata: &serde_json::Value) -> MemoryRefs {
+    metadata
+        .get(REFS_KEY)
+        .and_then(|r| serde_json::from_value(r.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Stamp memories that were added, changed or mentioned in this turn, cap value
+/// length, consolidate and prune. Returns the memories and refs to save.
+#[allow(clippy::too_many_arguments)]
+pub async fn maintain(
+    ai: &dyn AiApi,
+    pii: &PiiPolicy,
+    metrics: &MemoryMetrics,
+    limits: MemoryLimits,
+    previous: &HashMap<String, String>,
+    mut memories: HashMap<String, String>,
+    mut refs: MemoryRefs,
+    turn_text: &str,
+) -> (HashMap<String, String>, MemoryRefs) {
+    let now = chrono::Utc::now().naive_utc();
+    let turn_text = turn_text.to_lowercase();
+    for (key, value) in &mut memories {
+        if value.chars().count() > limits.max_value_chars {
+            *value = value.chars().take(limits.max_value_chars).collect();
+        }
+        if previous.get(key) != Some(value) || is_mentioned(key, value, &turn_text) {
+            refs.insert(key.clone(), now);
+        }
+    }
+
+    // Only when this turn added memories, so a set with nothing to merge isn't re-sent every turn
+    let grew = memories.keys().any(|k| !previous.contains_key(k));
+    if limits.consolidate_at > 0 && memories.len() > limits.consolidate_at && grew {
+        match consolidate(ai, pii, &memories).await {
+            Ok(None) => {}
+            Ok(Some(merged)) => {
+                metrics.consolidations.fetch_add(1, Ordering::Relaxed);
+                // Merged entries are as recent as the newest memory they replace
+                let newest = refs.values().max().copied().unwrap_or(now);
+                for key in merged.keys() {
+                    if !memories.contains_key(key) {
+                        refs.insert(key.clone(), newest);
+                    }
+                }
+                memories = merged;
+            }
+            Err(e) => {
+                metrics
+                    .consolidation_failures
+                    .fetch_add(1, Ordering::Relaxed);
+                tracing::warn!(error = %e, "Memory consolidation failed, pruning only");
+            }
+        }
+    }
+
+    if memories.len() > limits.max_count {
+        let mut by_age: Vec<(String, Option<NaiveDateTime>)> = memories
+            .keys()
+            .map(|k| (k.clone(), refs.get(k).copied()))
+            .collect();
+        by_age.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        let excess = memories.len() - limits.max_count;
+        for (key, _) in by_age.into_iter().take(excess) {
+            memories.remove(&key);
+        }
+        metrics.pruned.fetch_add(excess as u64, Ordering::Relaxed);
+    }
+
+    refs.retain(|k, _| memories.contains_key(k));
+    (memories, refs)
+}
+
+fn is_mentioned(key: &str, value: &str, turn_text: &str) -> bool {
+    let value = value.to_lowercase();
+    let key = key.replace('_', " ").to_lowercase();
+    [value, key]
+        .iter()
+        .any(|needle| needle.chars().count() >= MIN_MENTION_CHARS && turn_text.contains(needle))
+}
+
+/// Ask the model to merge duplicate or overlapping memories; `None` when it found
+/// nothing to merge. An empty result is rejected so nothing is lost. Memories the
+/// client would redact are left out of the request and kept as they are, and a
+/// reply holding a redaction placeholder is rejected, so a placeholder never
+/// replaces what was stored.
+async fn consolidate(
+    ai: &dyn AiApi,
+    pii: &PiiPolicy,
+    memories: &HashMap<String, String>,
+) -> Result<Option<HashMap<String, String>>, AppError> {
+    let (sent, held): (HashMap<String, String>, HashMap<String, String>) = memories
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .partition(|(k, v)| pii.redact(k) == k.as_str() && pii.redact(v) == v.as_str());
+    if sent.len() < 2 {
+        return Ok(None);
+    }
+
+    let listing = serde_json::to_string_pretty(&sent).unwrap_or_default();
+    let prompt = format!(
+        "These are facts remembered about a user. Merge entries that are redundant, \
+         overlapping or outdated by another entry into one, keeping the most specific value. \
+         Keep unrelated entries unchanged. Use lowercase keys with underscores.\n\
+         Return ONLY a JSON object of key-value pairs.\n\n{listing}"
+    );
+    let (text, _) = ai
+        .generate_response(&prompt, "You tidy up stored user memories.", &[], None)
+        .await?;
+
+    let json = match (text.find('{'), text.rfind('}')) {
+        (Some(s), Some(e)) if s < e => &text[s..=e],
+        _ => {
+            return Err(AppError::service_unavailable(
+                "No JSON in consolidation reply",
+            ));
+        }
+    };
+    let mut merged: HashMap<String, String> = serde_json::from_str(json)
+        .map_err(|e| AppError::service_unavailable(format!("Invalid consolidation JSON: {e}")))?;
+    if merged.is_empty() {
+        return Err(AppError::service_unavailable(
+            "Consolidation returned no memories",
+        ));
+    }
+    if merged.values().any(|v| v.contains("[redacted ")) {
+        return Err(AppError::service_unavailable(
+            "Consolidation returned redacted text",
+        ));
+    }
+    if merged.len() >= sent.len() {
+        return Ok(None);
+    }
+    merged.extend(held);
+    Ok(Some(merged))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::FakeAi;
+
+    fn memories(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn consolidation_keeps_memories_with_personal_data_out_of_the_request() {
+        let ai = FakeAi::replying(Ok(r#"{"name": "Asha Rao"}"#));
+        let stored = memories(&[
+            ("name", "Asha"),
+            ("full_name", "Asha Rao"),
+            ("email", "asha@example.com"),
+            ("phone", "+91 98765 43210"),
+        ]);
+
+        let merged = consolidate(&ai, &PiiPolicy::all(), &stored)
+            .await
+            .unwrap()
+            .expect("names merged");
+
+        assert_eq!(
+            merged,
+            memories(&[
+                ("name", "Asha Rao"),
+                ("email", "asha@example.com"),
+                ("phone", "+91 98765 43210"),
+            ])
+        );
+        let prompt = &ai.prompts.lock().unwrap()[0];
+        assert!(!prompt.contains("asha@example.com"));
+        assert!(!prompt.contains("98765"));
+    }
+
+    #[tokio::test]
+    async fn consolidation_reply_with_placeholders_is_rejected() {
+        let ai = FakeAi::replying(Ok(r#"{"contact": "[redacted email]"}"#));
+        let stored = memories(&[("contact", "reach me here"), ("contact_note", "by mail")]);
+
+        assert!(consolidate(&ai, &PiiPolicy::all(), &stored).await.is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::NaiveDateTime;
+
+use crate::AppState;
+use crate::error::AppError;
+use crate::models::entities::EmbeddedMessage;
+use crate::services::ai::EmbeddingTask;
+use crate::services::knowledge::cosine_similarity;
+
+/// Message text past this is cut before embedding; the model's input limit is
+/// about 2k tokens and the opening of a long message carries its topic.
+const MAX_EMBED_CHARS: usize = 2_000;
+
+/// Once the embedder has caught up, later passes only look at messages created
+/// this long before the last complete pass, instead of the whole table. Covers
+/// messages whose text is filled in a while after they are created.
+const RESCAN_WINDOW: chrono::Duration = chrono::Duration::hours(1);
+
+/// Periodically embed messages that don't have an embedding yet, newest first,
+/// so history becomes searchable shortly after it's written. Checks the
+/// setting each pass, so a reload can turn it on or off.
+pub fn spawn_message_embedder(state: Arc<AppState>, interval_secs: u64) {
+    tokio::spawn(async move {
+        let interval = std::time::Duration::from_secs(interval_secs);
+        let mut since = None;
+        loop {
+            tokio::time::sleep(interval).await;
+            if !state.settings.load().message_embeddings_enabled {
+                continue;
+            }
+            let started = chrono::Utc::now().naive_utc();
+            match embed_pending(&state, since).await {
+                Ok((embedded, caught_up)) => {
+                    if embedded > 0 {
+                        tracing::debug!(embedded, "Embedded messages");
+                    }
+                    if caught_up {
+                        since = Some(started - RESCAN_WINDOW);
+                    }
+                }
+                Err(e) => tracing::warn!(error = %e, "Failed to embed messages"),
+            }
+        }
+    });
+}
+
+/// Embed one batch of pending messages. Returns how many were embedded and
+/// whether nothing older is left.
+async fn embed_pending(
+    state: &AppState,
+    since: Option<NaiveDateTime>,
+) -> Result<(usize, bool), AppError> {
+    let repo = state.db.message_embedding_repo();
+    let batch_size = state.settings.load().message_embedding_batch_size.max(1);
+    let pending = repo.pending(since, batch_size).await?;
+    let caught_up = (pending.len() as i64) < batch_size;
+    if pending.is_empty() {
+        return Ok((0, caught_up));
+    }
+    let texts: Vec<String> = pending
+        .iter()
+        .map(|m| m.content.trim().chars().take(MAX_EMBED_CHARS).collect())
+        .collect();
+    let embeddings = match state.gemini.embed(&texts, EmbeddingTask::Document).await {
+        Ok(embeddings) => embeddings.into_iter().map(Some).collect(),
+        Err(e) => embed_one_by_one(state, &texts, e).await?,
+    };
+
+    let mut rows: Vec<(String, String, Vec<f32>)> = Vec::with_capacity(pending.len());
+    let mut failed = Vec::new();
+    for (m, embedding) in pending.into_iter().zip(embeddings) {
+        match embedding {
+            Some(embedding) => rows.push((m.id, m.conversation_id, embedding)),
+            None => failed.push(m.id),
+        }
+    }
+    repo.store(&rows).await?;
+    if !failed.is_empty() {
+        tracing::warn!(
+            count = failed.len(),
+            "Skipping messages that failed to embed"
+        );
+        repo.record_failures(&failed, "embedding failed").await?;
+    }
+    Ok((rows.len(), caught_up))
+}
+
+/// After a batch fails, embed its texts separately so one bad message can't hold
+/// up the rest. `None` for each that still fails; when they all do, the provider
+/// is taken to be down and `batch_error` is returned so nothing is skipped.
+async fn embed_one_by_one(
+    state: &AppState,
+    texts: &[String],
+    batch_error: AppError,
+) -> Result<Vec<Option<Vec<f32>>>, AppError> {
+    let mut embeddings = Vec::with_capacity(texts.len());
+    for text in texts {
+        let embedding = state
+            .gemini
+            .embed(std::slice::from_ref(text), EmbeddingTask::Document)
+            .await
+            .ok()
+            .and_then(|mut e| e.pop());
+        embeddings.push(embedding);
+    }
+    if embeddings.iter().all(Option::is_none) {
+        return Err(batch_error);
+    }
+    Ok(embeddings)
+}
+
+/// A message matching a search, with its similarity to the query.
+pub struct MessageMatch {
+    pub message: EmbeddedMessage,
+    pub score: f32,
+}
+
+/// A conversation with messages matching a search. `score` is its best match.
+pub struct ConversationMatch {
+    pub conversation_id: String,
+    pub influencer_id: String,
+    pub score: f32,
+    pub matches: usize,
+}
+
+/// The user's messages most similar to `query`, best first, and the
+/// conversations they belong to, ranked by their best message.
+pub async fn search(
+    state: &AppState,
+    user_id: &str,
+    tenant: &str,
+    query: &str,
+    limit: usize,
+) -> Result<(Vec<MessageMatch>, Vec<ConversationMatch>), AppError> {
+    let settings = state.settings.load();
+    let candidates = state
+        .db
+        .message_embedding_repo()
+        .list_for_user(
+            user_id,
+            tenant,
+            settings.semantic_search_max_candidates.max(1),
+        )
+        .await?;
+    if candidates.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    let query_embedding = state
+        .gemini
+        .embed(&[query.to_string()], EmbeddingTask::Query)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| AppError::service_unavailable("Embedding service returned no vector"))?;
+
+    let mut scored: Vec<MessageMatch> = candidates
+        .into_iter()
+        .map(|message| MessageMatch {
+            score: cosine_similarity(&query_embedding, &message.embedding),
+            message,
+        })
+        .filter(|m| m.score >= settings.semantic_search_min_score)
+        .collect();
+    scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    let mut by_conversation: HashMap<&str, ConversationMatch> = HashMap::new();
+    for m in &scored {
+        let entry = by_conversation
+            .entry(&m.message.conversation_id)
+            .or_insert_with(|| ConversationMatch {
+                conversation_id: m.message.conversation_id.clone(),
+                influencer_id: m.message.influencer_id.clone(),
+                score: m.score,
+                matches: 0,
+            });
+        entry.matches += 1;
+    }
+    let mut conversations: Vec<ConversationMatch> = by_conversation.into_values().collect();
+    conversations.sort_by(|a, b| b.score.total_cmp(&a.score));
+    conversations.truncate(limit);
+
+    scored.truncate(limit);
+    Ok((scored, conversations))
+}
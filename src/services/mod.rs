@@ -1,8 +1,51 @@
+pub mod abuse_guard;
 pub mod ai;
+pub mod ai_samples;
+pub mod audio_duration;
+pub mod audit;
+pub mod avatar_moderation;
+pub mod backup_verify;
+pub mod caller_type;
+pub mod change_log;
 pub mod character_generator;
+pub mod conversation_starters;
+pub mod conversation_throttle;
+pub mod digest;
+pub mod duplicate_influencers;
+pub mod email;
 pub mod google_chat;
+pub mod image_caption;
+pub mod image_normalize;
+pub mod image_quota;
+pub mod impressions;
+pub mod influencer_cache;
+pub mod influencer_enrichment;
+pub mod knowledge;
+pub mod leaderboard_cache;
+pub mod legacy_import;
+pub mod long_message;
+pub mod memory;
+pub mod message_embeddings;
 pub mod moderation;
 pub mod notification;
+pub mod notification_format;
+pub mod output_sanitizer;
+pub mod pii;
+pub mod presence;
+pub mod profile_cache;
+pub mod prompt_cache;
+pub mod prompt_guard;
+pub mod push_batch;
+pub mod quiet_hours;
 pub mod replicate;
+pub mod revocation;
+pub mod sentry_alerts;
 pub mod storage;
+pub mod suggestion_rotation;
+pub mod telegram;
+pub mod tenants;
+pub mod tools;
+pub mod turn_debounce;
+pub mod upload_scan;
+pub mod webhooks;
 pub mod websocket;
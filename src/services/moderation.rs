@@ -1,3 +1,7 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
 pub const STYLE_PROMPT: &str = "\
 IMPORTANT: Avoid apologies or self-corrections in your responses.";
 
@@ -11,6 +15,23 @@ Key Rules:
 - Maintain consistency with your persona at all times
 - Ensure all content is safe for all ages";
 
+pub const SAFE_MODE_PROMPT: &str = "\
+Safe Mode (children may be reading this conversation):
+- Keep every reply suitable for young children
+- No romance, flirting, innuendo, profanity, violence, drugs, alcohol or scary content
+- If the user steers toward any of these, kindly change the subject";
+
+/// Sent instead of a reply that fails the safe mode output screen.
+pub const SAFE_MODE_REPLY: &str = "Let's talk about something else! What else is on your mind?";
+
+/// Words a safe mode reply must never contain.
+static UNSAFE_OUTPUT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(sex|sexy|sexual|nudes?|naked|porn\w*|fuck\w*|shit\w*|bitch\w*|dick|cock|pussy|orgasm\w*|horny|boobs?|kill yourself)\b",
+    )
+    .unwrap()
+});
+
 /// Append style + moderation prompts to system instructions.
 pub fn with_guardrails(instructions: &str) -> String {
     format!("{instructions}\n{STYLE_PROMPT}\n{MODERATION_PROMPT}")
@@ -24,3 +45,8 @@ pub fn strip_guardrails(instructions: &str) -> String {
         .trim()
         .to_string()
 }
+
+/// Whether assistant text must be withheld from a safe mode conversation.
+pub fn is_unsafe_output(text: &str) -> bool {
+    UNSAFE_OUTPUT_REGEX.is_match(text)
+}
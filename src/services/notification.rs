@@ -1,3 +1,5 @@
+use async_trait::async_trait;
+
 /// Push notification service via Yral Metadata Server.
 #[derive(Clone)]
 pub struct PushNotificationService {
@@ -17,8 +19,24 @@ impl PushNotificationService {
             configured,
         }
     }
+}
+
+/// Push delivery to a user's devices. [`PushNotificationService`] implements it.
+#[async_trait]
+pub trait PushApi: Send + Sync {
+    /// Returns whether the push was accepted; failures are logged, not raised.
+    async fn send_push_notification(
+        &self,
+        user_id: &str,
+        title: &str,
+        body: &str,
+        data: Option<&serde_json::Value>,
+    ) -> bool;
+}
 
-    pub async fn send_push_notification(
+#[async_trait]
+impl PushApi for PushNotificationService {
+    async fn send_push_notification(
         &self,
         user_id: &str,
         title: &str,
@@ -47,6 +65,16 @@ impl PushNotificationService {
             }
         }
 
+        // A collapse key in the data only reaches the app; the platforms replace an
+        // earlier notification when it is set on the message itself
+        if let Some(key) = data
+            .and_then(|d| d.get("collapse_key"))
+            .and_then(|k| k.as_str())
+        {
+            payload["android"] = serde_json::json!({ "collapse_key": key });
+            payload["apns"] = serde_json::json!({ "headers": { "apns-collapse-id": key } });
+        }
+
         let mut req = self
             .http
             .post(&url)
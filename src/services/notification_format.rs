@@ -0,0 +1,204 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::models::entities::MessageType;
+
+/// Graphemes of message text shown in a push notification body.
+pub const PREVIEW_MAX_GRAPHEMES: usize = 100;
+
+const ELLIPSIS: &str = "…";
+const PHOTO_ICON: &str = "📷";
+const VOICE_ICON: &str = "🎤";
+
+/// A cut this close to the limit may move back to the last space so words stay whole.
+const WORD_BREAK_WINDOW: usize = 20;
+
+/// Notification texts that don't come from the message itself.
+struct Templates {
+    photo: &'static str,
+    voice: &'static str,
+    new_message: &'static str,
+    /// `{n}` is the message count, `{name}` the sender
+    new_messages: &'static str,
+}
+
+const ENGLISH: Templates = Templates {
+    photo: "Photo",
+    voice: "Voice message",
+    new_message: "New message",
+    new_messages: "{n} new messages from {name}",
+};
+
+/// Languages matched by ISO code, English name or native name, as users type any of them
+/// into `preferred_language`.
+const LOCALIZED: &[(&[&str], Templates)] = &[
+    (
+        &["hi", "hindi", "हिन्दी", "हिंदी", "hinglish"],
+        Templates {
+            photo: "फ़ोटो",
+            voice: "वॉइस मैसेज",
+            new_message: "नया मैसेज",
+            new_messages: "{name} से {n} नए मैसेज",
+        },
+    ),
+    (
+        &["bn", "bengali", "bangla", "বাংলা"],
+        Templates {
+            photo: "ছবি",
+            voice: "ভয়েস মেসেজ",
+            new_message: "নতুন মেসেজ",
+            new_messages: "{name} থেকে {n}টি নতুন মেসেজ",
+        },
+    ),
+    (
+        &["mr", "marathi", "मराठी"],
+        Templates {
+            photo: "फोटो",
+            voice: "व्हॉइस मेसेज",
+            new_message: "नवीन मेसेज",
+            new_messages: "{name} कडून {n} नवीन मेसेज",
+        },
+    ),
+    (
+        &["ta", "tamil", "தமிழ்"],
+        Templates {
+            photo: "புகைப்படம்",
+            voice: "குரல் செய்தி",
+            new_message: "புதிய செய்தி",
+            new_messages: "{name} இடமிருந்து {n} புதிய செய்திகள்",
+        },
+    ),
+    (
+        &["te", "telugu", "తెలుగు"],
+        Templates {
+            photo: "ఫోటో",
+            voice: "వాయిస్ మెసేజ్",
+            new_message: "కొత్త సందేశం",
+            new_messages: "{name} నుండి {n} కొత్త సందేశాలు",
+        },
+    ),
+];
+
+fn templates(language: Option<&str>) -> &'static Templates {
+    let Some(language) = language.map(|l| l.trim().to_lowercase()) else {
+        return &ENGLISH;
+    };
+    // "hi-IN" and "Hindi (India)" match like "hi" and "hindi"
+    let base = language
+        .split(['-', '_', ' ', '('])
+        .next()
+        .unwrap_or_default();
+    LOCALIZED
+        .iter()
+        .find(|(names, _)| names.contains(&base) || names.contains(&language.as_str()))
+        .map_or(&ENGLISH, |(_, t)| t)
+}
+
+/// Body of a new-message push: the message text on one line, cut to
+/// [`PREVIEW_MAX_GRAPHEMES`], with a localized label for photos and voice notes.
+pub fn message_preview(
+    content: &str,
+    message_type: &MessageType,
+    language: Option<&str>,
+) -> String {
+    let t = templates(language);
+    let text = collapse_whitespace(content);
+    let (icon, label) = match message_type {
+        MessageType::Image | MessageType::Multimodal => (PHOTO_ICON, t.photo),
+        MessageType::Audio => (VOICE_ICON, t.voice),
+        MessageType::Text => ("", t.new_message),
+    };
+    match (text.is_empty(), icon.is_empty()) {
+        (true, true) => label.to_string(),
+        (true, false) => format!("{icon} {label}"),
+        (false, true) => truncate_graphemes(&text, PREVIEW_MAX_GRAPHEMES),
+        (false, false) => format!(
+            "{icon} {}",
+            truncate_graphemes(&text, PREVIEW_MAX_GRAPHEMES - 2)
+        ),
+    }
+}
+
+/// Body of a push standing in for `count` messages from `sender`.
+pub fn batched_preview(count: usize, sender: &str, language: Option<&str>) -> String {
+    templates(language)
+        .new_messages
+        .replace("{n}", &count.to_string())
+        .replace("{name}", sender)
+}
+
+/// Cut `text` to at most `max` grapheme clusters, ellipsis included, so conjuncts
+/// (क्ष), combining marks and emoji sequences (👨‍👩‍👧, flags, skin tones) are never split.
+pub fn truncate_graphemes(text: &str, max: usize) -> String {
+    if text.graphemes(true).nth(max).is_none() {
+        return text.to_string();
+    }
+    // Room for the ellipsis
+    let end = text
+        .grapheme_indices(true)
+        .nth(max.saturating_sub(1))
+        .map_or(text.len(), |(i, _)| i);
+    let head = &text[..end];
+    let cut = head
+        .rfind(char::is_whitespace)
+        .filter(|&i| head[i..].graphemes(true).count() <= WORD_BREAK_WINDOW)
+        .unwrap_or(end);
+    format!("{}{ELLIPSIS}", head[..cut].trim_end())
+}
+
+/// Newlines and runs of spaces render badly in a one-line notification.
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_text_is_kept() {
+        assert_eq!(truncate_graphemes("नमस्ते 🙏", 10), "नमस्ते 🙏");
+    }
+
+    #[test]
+    fn devanagari_is_cut_between_grapheme_clusters() {
+        // Each "क्षि" is one grapheme of four chars; a byte or char cut would split it
+        let text = "क्षि".repeat(30);
+        let cut = truncate_graphemes(&text, 10);
+        assert_eq!(cut, format!("{}…", "क्षि".repeat(9)));
+        assert_eq!(cut.graphemes(true).count(), 10);
+    }
+
+    #[test]
+    fn emoji_sequences_stay_whole() {
+        let family = "👨‍👩‍👧";
+        let flag = "🇮🇳";
+        let text = format!("{family}{flag}👍🏽").repeat(10);
+        let cut = truncate_graphemes(&text, 5);
+        assert_eq!(cut, format!("{family}{flag}👍🏽{family}…"));
+    }
+
+    #[test]
+    fn cut_moves_back_to_a_word_break() {
+        let text = "मैं आज बाज़ार जा रहा हूँ और शाम तक लौटूँगा";
+        let cut = truncate_graphemes(text, 20);
+        let head = cut.strip_suffix(ELLIPSIS).unwrap();
+        assert!(text[head.len()..].starts_with(' '), "{cut}");
+        assert!(cut.graphemes(true).count() <= 20);
+    }
+
+    #[test]
+    fn preview_is_localized_and_fits() {
+        let long = "😀".repeat(PREVIEW_MAX_GRAPHEMES * 2);
+        let preview = message_preview(&long, &MessageType::Image, Some("hi-IN"));
+        assert!(preview.starts_with("📷 😀"));
+        assert_eq!(preview.graphemes(true).count(), PREVIEW_MAX_GRAPHEMES);
+        assert_eq!(
+            message_preview("", &MessageType::Audio, Some("Hindi")),
+            "🎤 वॉइस मैसेज"
+        );
+        assert_eq!(
+            batched_preview(3, "Asha", Some("bn")),
+            "Asha থেকে 3টি নতুন মেসেজ"
+        );
+    }
+}
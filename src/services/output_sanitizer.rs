@@ -0,0 +1,233 @@
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+
+use crate::config::Settings;
+
+/// How assistant text is cleaned up before it is stored and shown to clients.
+#[derive(Debug, Clone)]
+pub struct OutputPolicy {
+    pub enabled: bool,
+    /// Longer replies are cut at this many characters
+    pub max_chars: usize,
+    /// When set, http(s) links become `{redirect}{percent-encoded url}`
+    pub link_redirect_url: Option<String>,
+}
+
+impl OutputPolicy {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            enabled: settings.output_sanitize_enabled,
+            max_chars: settings.assistant_max_chars,
+            link_redirect_url: settings.outbound_link_redirect_url.clone(),
+        }
+    }
+}
+
+/// Elements whose content is dropped along with the tags.
+static ACTIVE_HTML_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?is)<script\b.*?</script\s*>|<style\b.*?</style\s*>|<iframe\b.*?</iframe\s*>|<object\b.*?</object\s*>|<!--.*?-->",
+    )
+    .unwrap()
+});
+
+/// Any other raw HTML tag; markdown covers the formatting clients support.
+static HTML_TAG_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)</?[a-z][a-z0-9-]*(\s[^<>]*)?/?>").unwrap());
+
+static IMAGE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"!\[([^\]]*)\]\(\s*<?([^)\s>]*)>?(\s+"[^"]*")?\s*\)"#).unwrap());
+
+static LINK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\[([^\]]*)\]\(\s*<?([^)\s>]*)>?(\s+"[^"]*")?\s*\)"#).unwrap());
+
+static AUTOLINK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<([a-zA-Z][a-zA-Z0-9+.-]*:[^<>\s]*)>").unwrap());
+
+static LINK_DEFINITION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^ {0,3}\[[^\]]+\]:[ \t]*<?([^\s>]*)>?([ \t].*)?$").unwrap());
+
+#[derive(Debug, Clone)]
+pub struct SanitizedOutput {
+    /// Text to store and send.
+    pub text: String,
+    /// What was changed, empty when the text passed through untouched.
+    pub changes: Vec<&'static str>,
+}
+
+impl SanitizedOutput {
+    pub fn is_modified(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    /// Metadata recorded on the stored assistant message, keeping the original
+    /// text for audit.
+    pub fn to_metadata(&self, raw: &str) -> serde_json::Value {
+        serde_json::json!({
+            "sanitized": {
+                "changes": self.changes,
+                "raw_content": raw,
+            }
+        })
+    }
+}
+
+/// Clean assistant markdown: drop raw HTML and images, neutralize links with
+/// schemes other than http(s)/mailto, optionally route links through a redirect,
+/// and cap the length. Fenced code blocks are left as written since clients
+/// render them verbatim.
+pub fn sanitize(text: &str, policy: &OutputPolicy) -> SanitizedOutput {
+    if !policy.enabled {
+        return SanitizedOutput {
+            text: text.to_string(),
+            changes: vec![],
+        };
+    }
+
+    let mut changes = Vec::new();
+    let mut out = String::with_capacity(text.len());
+    let mut prose = String::new();
+    let mut fence: Option<&str> = None;
+    for line in text.split_inclusive('\n') {
+        let marker = fence_marker(line);
+        match (fence, marker) {
+            (None, Some(m)) => {
+                out.push_str(&sanitize_prose(&prose, policy, &mut changes));
+                prose.clear();
+                fence = Some(m);
+                out.push_str(line);
+            }
+            (Some(open), Some(m)) if m == open => {
+                fence = None;
+                out.push_str(line);
+            }
+            (Some(_), _) => out.push_str(line),
+            (None, None) => prose.push_str(line),
+        }
+    }
+    out.push_str(&sanitize_prose(&prose, policy, &mut changes));
+
+    if out.chars().count() > policy.max_chars {
+        let cut: String = out
+            .chars()
+            .take(policy.max_chars.saturating_sub(1))
+            .collect();
+        out = format!("{}…", cut.trim_end());
+        changes.push("truncated");
+    }
+
+    changes.sort_unstable();
+    changes.dedup();
+    SanitizedOutput { text: out, changes }
+}
+
+fn fence_marker(line: &str) -> Option<&'static str> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    if trimmed.starts_with("```") {
+        Some("```")
+    } else if trimmed.starts_with("~~~") {
+        Some("~~~")
+    } else {
+        None
+    }
+}
+
+fn sanitize_prose(text: &str, policy: &OutputPolicy, changes: &mut Vec<&'static str>) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+
+    let mut text = text.to_string();
+    for re in [&*ACTIVE_HTML_REGEX, &*HTML_TAG_REGEX] {
+        if re.is_match(&text) {
+            text = re.replace_all(&text, "").into_owned();
+            changes.push("html");
+        }
+    }
+    if IMAGE_REGEX.is_match(&text) {
+        text = IMAGE_REGEX.replace_all(&text, "$1").into_owned();
+        changes.push("image");
+    }
+
+    text = LINK_REGEX
+        .replace_all(&text, |caps: &Captures| {
+            let label = &caps[1];
+            match rewrite_link(&caps[2], policy) {
+                Some(url) => {
+                    if url != caps[2] {
+                        changes.push("link_rewritten");
+                    }
+                    format!("[{label}]({url})")
+                }
+                None => {
+                    changes.push("unsafe_link");
+                    label.to_string()
+                }
+            }
+        })
+        .into_owned();
+    text = AUTOLINK_REGEX
+        .replace_all(&text, |caps: &Captures| {
+            match rewrite_link(&caps[1], policy) {
+                Some(url) if url == caps[1] => caps[0].to_string(),
+                Some(url) => {
+                    changes.push("link_rewritten");
+                    format!("[{}]({url})", &caps[1])
+                }
+                None => {
+                    changes.push("unsafe_link");
+                    String::new()
+                }
+            }
+        })
+        .into_owned();
+    text = LINK_DEFINITION_REGEX
+        .replace_all(&text, |caps: &Captures| {
+            if rewrite_link(&caps[1], policy).is_some() {
+                caps[0].to_string()
+            } else {
+                changes.push("unsafe_link");
+                String::new()
+            }
+        })
+        .into_owned();
+
+    text
+}
+
+/// The URL to link to, or `None` when the link must be dropped.
+fn rewrite_link(url: &str, policy: &OutputPolicy) -> Option<String> {
+    let scheme = url
+        .split_once(':')
+        .map(|(s, _)| s.to_ascii_lowercase())
+        .filter(|s| {
+            s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+.-".contains(c))
+        });
+    match scheme.as_deref() {
+        Some("mailto") => Some(url.to_string()),
+        Some("http" | "https") => Some(match &policy.link_redirect_url {
+            Some(redirect) if !url.starts_with(redirect.as_str()) => {
+                format!("{redirect}{}", percent_encode(url))
+            }
+            _ => url.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+pub(crate) fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() * 3);
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || b"-_.~".contains(&b) {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
@@ -0,0 +1,136 @@
+use std::borrow::Cow;
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+use strum::{AsRefStr, Display, EnumString};
+
+use crate::config::Settings;
+
+/// Personal data the scrubber recognizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display, EnumString, AsRefStr)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum PiiKind {
+    Email,
+    Phone,
+    Address,
+}
+
+static EMAIL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b[a-z0-9._%+-]+@[a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,}\b").unwrap()
+});
+
+/// Digit runs with common separators; the digit count decides whether it's a phone number.
+static PHONE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\+?\(?\d[\d \t().-]{7,}\d").unwrap());
+
+/// A calendar date; digit runs holding one are left alone.
+static DATE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(?:\d{4}[-./]\d{1,2}[-./]\d{1,2}|\d{1,2}[-./]\d{1,2}[-./]\d{4})\b").unwrap()
+});
+
+/// A house number followed by a street word, or a labelled PIN/ZIP code.
+static ADDRESS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b\d{1,5}[a-z]?(?:[/-]\d{1,4})?,?\s+(?:[\p{L}0-9.'-]+\s+){0,4}(?:street|st|road|rd|avenue|ave|lane|ln|boulevard|blvd|drive|dr|marg|nagar|colony|sector|block)\b\.?|\b(?:pin\s*(?:code)?|zip\s*(?:code)?|postal\s+code)\s*[:#-]?\s*\d{5,6}\b",
+    )
+    .unwrap()
+});
+
+/// Digits in an international number, country code included.
+const PHONE_DIGITS: std::ops::RangeInclusive<usize> = 10..=15;
+
+impl PiiKind {
+    fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self {
+            Self::Email => EMAIL_REGEX.replace_all(text, "[redacted email]"),
+            Self::Phone => PHONE_REGEX.replace_all(text, |caps: &Captures| {
+                let digits = caps[0].chars().filter(char::is_ascii_digit).count();
+                if PHONE_DIGITS.contains(&digits) && !DATE_REGEX.is_match(&caps[0]) {
+                    "[redacted phone]".to_string()
+                } else {
+                    caps[0].to_string()
+                }
+            }),
+            Self::Address => ADDRESS_REGEX.replace_all(text, "[redacted address]"),
+        }
+    }
+}
+
+/// What is scrubbed from user text and memories before they go to an AI provider.
+/// Stored messages and memories keep the original; only the outgoing request is
+/// redacted. Matching is pattern based, so it catches the common formats rather
+/// than every way a user can write an address.
+#[derive(Debug, Clone, Default)]
+pub struct PiiPolicy {
+    /// Empty disables redaction
+    kinds: Vec<PiiKind>,
+}
+
+impl PiiPolicy {
+    pub fn from_settings(settings: &Settings) -> Self {
+        if !settings.pii_redaction_enabled {
+            return Self::default();
+        }
+        let mut kinds: Vec<PiiKind> = settings
+            .pii_redaction_kinds_list()
+            .iter()
+            .filter_map(|k| match k.parse() {
+                Ok(kind) => Some(kind),
+                Err(_) => {
+                    tracing::warn!(kind = %k, "Unknown PII kind in PII_REDACTION_KINDS, ignoring");
+                    None
+                }
+            })
+            .collect();
+        // Emails first, so their digits are never taken for a phone number
+        kinds.sort();
+        kinds.dedup();
+        Self { kinds }
+    }
+
+    /// Every kind, regardless of settings; for data kept for debugging.
+    pub fn all() -> Self {
+        Self {
+            kinds: vec![PiiKind::Email, PiiKind::Phone, PiiKind::Address],
+        }
+    }
+
+    /// `text` with every enabled kind replaced by a `[redacted …]` placeholder.
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for kind in &self.kinds {
+            if let Cow::Owned(redacted) = kind.redact(&text) {
+                text = Cow::Owned(redacted);
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_emails_and_phone_numbers() {
+        let policy = PiiPolicy::all();
+        assert_eq!(
+            policy.redact("mail asha@example.com or call +91 98765 43210"),
+            "mail [redacted email] or call [redacted phone]"
+        );
+        assert_eq!(policy.redact("(555) 123-4567"), "[redacted phone]");
+    }
+
+    #[test]
+    fn leaves_dates_and_short_numbers_alone() {
+        let policy = PiiPolicy::all();
+        for text in [
+            "see you on 2024-01-15 10",
+            "born 15.01.1998 12",
+            "it was 15-01-2024 2024",
+            "my pin is 1234",
+        ] {
+            assert_eq!(policy.redact(text), text);
+        }
+    }
+}
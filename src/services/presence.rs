@@ -0,0 +1,50 @@
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+
+use crate::db::Database;
+
+/// Throttles `last_seen_at` writes so a long-lived WebSocket touches the row at
+/// most once per interval instead of on every heartbeat.
+pub struct PresenceTracker {
+    last_written: DashMap<String, Instant>,
+    interval: Duration,
+}
+
+impl PresenceTracker {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            last_written: DashMap::new(),
+            interval,
+        }
+    }
+
+    /// How often a connected socket should call [`record`](Self::record).
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Persist that `user_id` is around now. Skipped when the last write is
+    /// newer than the interval, unless `force` (used on disconnect so the
+    /// stored time is when the user actually left).
+    pub async fn record(&self, db: &Database, user_id: &str, force: bool) {
+        if !force
+            && self
+                .last_written
+                .get(user_id)
+                .is_some_and(|at| at.elapsed() < self.interval)
+        {
+            return;
+        }
+        self.last_written
+            .insert(user_id.to_string(), Instant::now());
+        if let Err(e) = db.presence_repo().touch(user_id).await {
+            tracing::warn!(user_id = %user_id, error = %e, "Failed to record presence");
+        }
+    }
+
+    /// Forget a user that has no connections left.
+    pub fn forget(&self, user_id: &str) {
+        self.last_written.remove(user_id);
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+
+use crate::models::responses::ProfileCacheStats;
+
+/// Entries kept before expired ones are swept out.
+const MAX_ENTRIES: usize = 50_000;
+
+/// What the bot-facing conversation list shows about a user.
+#[derive(Debug, Clone, Default)]
+pub struct UserProfile {
+    pub username: Option<String>,
+    pub profile_picture_url: Option<String>,
+}
+
+struct Entry {
+    profile: UserProfile,
+    loaded_at: Instant,
+}
+
+/// Shared cache of user profiles from the metadata server and the User Info
+/// Service canister. Entries younger than `ttl` are served as-is; entries up to
+/// `ttl + stale_ttl` old are served while a background refresh replaces them.
+/// Lookups that fail on either source are returned but never cached.
+pub struct ProfileCache {
+    entries: DashMap<String, Entry>,
+    /// Principals with a background refresh in flight
+    refreshing: DashMap<String, ()>,
+    ttl: Duration,
+    stale_ttl: Duration,
+    hits: AtomicU64,
+    stale_hits: AtomicU64,
+    misses: AtomicU64,
+    fetch_failures: AtomicU64,
+}
+
+impl ProfileCache {
+    /// A zero `ttl` disables caching; a zero `stale_ttl` disables serving stale entries.
+    pub fn new(ttl: Duration, stale_ttl: Duration) -> Self {
+        Self {
+            entries: DashMap::new(),
+            refreshing: DashMap::new(),
+            ttl,
+            stale_ttl,
+            hits: AtomicU64::new(0),
+            stale_hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            fetch_failures: AtomicU64::new(0),
+        }
+    }
+
+    /// Profiles for `user_ids`. Missing or expired ones are fetched inline,
+    /// stale ones are returned immediately and refreshed in the background.
+    pub async fn get_many(
+        self: &Arc<Self>,
+        agent: &ic_agent::Agent,
+        http_client: &reqwest::Client,
+        metadata_url: &str,
+        user_ids: &[String],
+    ) -> HashMap<String, UserProfile> {
+        let mut profiles = HashMap::with_capacity(user_ids.len());
+        let mut missing = Vec::new();
+        let mut stale = Vec::new();
+
+        for uid in user_ids {
+            let age = match self.entries.get(uid) {
+                Some(entry) => {
+                    let age = entry.loaded_at.elapsed();
+                    if age < self.ttl + self.stale_ttl {
+                        profiles.insert(uid.clone(), entry.profile.clone());
+                    }
+                    Some(age)
+                }
+                None => None,
+            };
+            match age {
+                Some(age) if age < self.ttl => {
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                }
+                Some(age) if age < self.ttl + self.stale_ttl => {
+                    self.stale_hits.fetch_add(1, Ordering::Relaxed);
+                    if self.refreshing.insert(uid.clone(), ()).is_none() {
+                        stale.push(uid.clone());
+                    }
+                }
+                _ => {
+                    self.misses.fetch_add(1, Ordering::Relaxed);
+                    missing.push(uid.clone());
+                }
+            }
+        }
+
+        if !stale.is_empty() {
+            let cache = Arc::clone(self);
+            let agent = agent.clone();
+            let http_client = http_client.clone();
+            let metadata_url = metadata_url.to_string();
+            tokio::spawn(async move {
+                cache
+                    .fetch_and_store(&agent, &http_client, &metadata_url, &stale)
+                    .await;
+                for uid in &stale {
+                    cache.refreshing.remove(uid);
+                }
+            });
+        }
+
+        if !missing.is_empty() {
+            let fetched = self
+                .fetch_and_store(agent, http_client, metadata_url, &missing)
+                .await;
+            profiles.extend(fetched);
+        }
+
+        profiles
+    }
+
+    pub fn stats(&self) -> ProfileCacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let stale_hits = self.stale_hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let lookups = hits + stale_hits + misses;
+        ProfileCacheStats {
+            entries: self.entries.len(),
+            hits,
+            stale_hits,
+            misses,
+            fetch_failures: self.fetch_failures.load(Ordering::Relaxed),
+            hit_rate: if lookups == 0 {
+                0.0
+            } else {
+                (hits + stale_hits) as f64 / lookups as f64
+            },
+        }
+    }
+
+    async fn fetch_and_store(
+        &self,
+        agent: &ic_agent::Agent,
+        http_client: &reqwest::Client,
+        metadata_url: &str,
+        user_ids: &[String],
+    ) -> HashMap<String, UserProfile> {
+        // Fetch usernames from metadata server and profile pics from canister in parallel
+        let (usernames, pics) = tokio::join!(
+            fetch_usernames_from_metadata(http_client, metadata_url, user_ids),
+            fetch_profile_pics_from_canister(agent, user_ids),
+        );
+        let complete = usernames.is_some() && pics.is_some();
+        if !complete {
+            self.fetch_failures.fetch_add(1, Ordering::Relaxed);
+        }
+        let mut usernames = usernames.unwrap_or_default();
+        let mut pics = pics.unwrap_or_default();
+
+        let profiles: HashMap<String, UserProfile> = user_ids
+            .iter()
+            .map(|uid| {
+                (
+                    uid.clone(),
+                    UserProfile {
+                        username: usernames.remove(uid),
+                        profile_picture_url: pics.remove(uid),
+                    },
+                )
+            })
+            .collect();
+
+        // A partial answer would pin a missing username or picture for a full TTL
+        if complete && !self.ttl.is_zero() {
+            if self.entries.len() + profiles.len() > MAX_ENTRIES {
+                let max_age = self.ttl + self.stale_ttl;
+                self.entries.retain(|_, e| e.loaded_at.elapsed() < max_age);
+            }
+            let now = Instant::now();
+            for (uid, profile) in &profiles {
+                if self.entries.len() >= MAX_ENTRIES {
+                    break;
+                }
+                self.entries.insert(
+                    uid.clone(),
+                    Entry {
+                        profile: profile.clone(),
+                        loaded_at: now,
+                    },
+                );
+            }
+        }
+
+        profiles
+    }
+}
+
+/// Fetch usernames from the yral metadata server via POST /metadata-bulk.
+/// `None` when the server couldn't be reached or answered with an error.
+async fn fetch_usernames_from_metadata(
+    http_client: &reqwest::Client,
+    metadata_url: &str,
+    user_ids: &[String],
+) -> Option<HashMap<String, String>> {
+    let url = format!("{}/metadata-bulk", metadata_url.trim_end_matches('/'));
+
+    let body = serde_json::json!({ "users": user_ids });
+
+    match http_client.post(&url).json(&body).send().await {
+        Ok(resp) => {
+            if !resp.status().is_success() {
+                tracing::warn!(status = %resp.status(), "Metadata server returned error for bulk fetch");
+                return None;
+            }
+            match resp.json::<serde_json::Value>().await {
+                Ok(json) => {
+                    let mut usernames = HashMap::new();
+                    if let Some(ok_data) = json.get("Ok").and_then(|v| v.as_object()) {
+                        for (principal, meta) in ok_data {
+                            if let Some(name) = meta.get("user_name").and_then(|v| v.as_str())
+                                && !name.trim().is_empty()
+                            {
+                                usernames.insert(principal.clone(), name.to_string());
+                            }
+                        }
+                    }
+                    Some(usernames)
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to parse metadata bulk response");
+                    None
+                }
+            }
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to fetch usernames from metadata server");
+            None
+        }
+    }
+}
+
+/// Fetch profile pictures from the User Info Service canister.
+/// `None` on a canister or agent error.
+async fn fetch_profile_pics_from_canister(
+    agent: &ic_agent::Agent,
+    user_ids: &[String],
+) -> Option<HashMap<String, String>> {
+    let principals: Vec<candid::Principal> = user_ids
+        .iter()
+        .filter_map(|id| candid::Principal::from_text(id).ok())
+        .collect();
+
+    if principals.is_empty() {
+        return Some(HashMap::new());
+    }
+
+    let canister_id = yral_canisters_client::ic::USER_INFO_SERVICE_ID;
+    let service = yral_canisters_client::user_info_service::UserInfoService(canister_id, agent);
+
+    match service.get_users_profile_details(principals).await {
+        Ok(yral_canisters_client::user_info_service::Result9::Ok(details)) => {
+            let mut pics = HashMap::new();
+            for detail in details {
+                if let Some(pic) = detail.profile_picture {
+                    pics.insert(detail.principal_id.to_text(), pic.url);
+                }
+            }
+            Some(pics)
+        }
+        Ok(yral_canisters_client::user_info_service::Result9::Err(e)) => {
+            tracing::warn!(error = %e, "Canister error fetching user profiles");
+            None
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "IC agent error fetching user profiles");
+            None
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use dashmap::DashMap;
+
+use crate::models::entities::AIInfluencer;
+use crate::models::responses::CacheStats;
+use crate::services::moderation;
+
+/// Entries kept before the cache is emptied and rebuilt on demand.
+const MAX_ENTRIES: usize = 10_000;
+
+struct Entry {
+    /// Hash of the stored instructions the prompt was built from
+    version: u64,
+    prompt: Arc<str>,
+}
+
+/// Guardrail-wrapped system prompts per influencer, rebuilt only when the stored
+/// instructions change. Wrapping normalizes them: whatever guardrails the stored
+/// text carries are replaced by exactly one current set, so every turn sends the
+/// same guardrails even for prompts saved before they changed.
+#[derive(Default)]
+pub struct PromptCache {
+    entries: DashMap<String, Entry>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl PromptCache {
+    pub fn guarded(&self, influencer: &AIInfluencer) -> Arc<str> {
+        let mut hasher = DefaultHasher::new();
+        influencer.system_instructions.hash(&mut hasher);
+        let version = hasher.finish();
+
+        if let Some(entry) = self.entries.get(&influencer.id)
+            && entry.version == version
+        {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return entry.prompt.clone();
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let prompt: Arc<str> = moderation::with_guardrails(&moderation::strip_guardrails(
+            &influencer.system_instructions,
+        ))
+        .into();
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.clear();
+        }
+        self.entries.insert(
+            influencer.id.clone(),
+            Entry {
+                version,
+                prompt: prompt.clone(),
+            },
+        );
+        prompt
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let lookups = hits + misses;
+        CacheStats {
+            entries: self.entries.len(),
+            hits,
+            misses,
+            hit_rate: if lookups == 0 {
+                0.0
+            } else {
+                hits as f64 / lookups as f64
+            },
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+
+use crate::services::notification::PushApi;
+use crate::services::notification_format;
+
+/// How long a delivered message id is remembered, so a retried send doesn't notify twice.
+const DEDUPE_TTL: Duration = Duration::from_secs(600);
+/// Above this many remembered ids, expired ones are dropped.
+const MAX_TRACKED_IDS: usize = 10_000;
+
+/// One new-message push for a recipient.
+pub struct PushItem {
+    pub recipient: String,
+    pub conversation_id: String,
+    pub message_id: String,
+    /// Sender's display name, used as the title
+    pub title: String,
+    pub body: String,
+    pub data: serde_json::Value,
+    pub language: Option<String>,
+}
+
+struct Pending {
+    item: PushItem,
+    count: usize,
+}
+
+/// Collapses rapid-fire messages in one conversation into a single push. The first
+/// message opens a window; whatever arrives before it closes goes out as one
+/// notification ("3 new messages from X") that replaces earlier ones on the device
+/// through the conversation's collapse key.
+pub struct PushBatcher {
+    push: Arc<dyn PushApi>,
+    window: Duration,
+    pending: DashMap<(String, String), Pending>,
+    delivered: DashMap<(String, String), Instant>,
+}
+
+impl PushBatcher {
+    /// A zero `window` sends every message on its own, still deduplicated.
+    pub fn new(push: Arc<dyn PushApi>, window: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            push,
+            window,
+            pending: DashMap::new(),
+            delivered: DashMap::new(),
+        })
+    }
+
+    pub fn enqueue(self: &Arc<Self>, mut item: PushItem) {
+        if !self.first_delivery(&item.recipient, &item.message_id) {
+            tracing::debug!(
+                user_id = %item.recipient,
+                message_id = %item.message_id,
+                "Skipping duplicate push"
+            );
+            return;
+        }
+        if let Some(data) = item.data.as_object_mut() {
+            data.insert(
+                "collapse_key".into(),
+                collapse_key(&item.conversation_id).into(),
+            );
+        }
+
+        if self.window.is_zero() {
+            let push = self.push.clone();
+            tokio::spawn(async move {
+                push.send_push_notification(
+                    &item.recipient,
+                    &item.title,
+                    &item.body,
+                    Some(&item.data),
+                )
+                .await;
+            });
+            return;
+        }
+
+        let key = (item.recipient.clone(), item.conversation_id.clone());
+        match self.pending.entry(key.clone()) {
+            // The latest message's text and deep link win
+            Entry::Occupied(mut pending) => {
+                let pending = pending.get_mut();
+                pending.count += 1;
+                pending.item = item;
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(Pending { item, count: 1 });
+                let batcher = self.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(batcher.window).await;
+                    batcher.flush(&key).await;
+                });
+            }
+        }
+    }
+
+    async fn flush(&self, key: &(String, String)) {
+        let Some((_, Pending { item, count })) = self.pending.remove(key) else {
+            return;
+        };
+        let body = if count > 1 {
+            notification_format::batched_preview(count, &item.title, item.language.as_deref())
+        } else {
+            item.body
+        };
+        let mut data = item.data;
+        if let Some(obj) = data.as_object_mut() {
+            obj.insert("message_count".into(), count.into());
+        }
+        self.push
+            .send_push_notification(&item.recipient, &item.title, &body, Some(&data))
+            .await;
+    }
+
+    /// Records the message as notified; false when it already was recently.
+    fn first_delivery(&self, recipient: &str, message_id: &str) -> bool {
+        let key = (recipient.to_string(), message_id.to_string());
+        if self
+            .delivered
+            .get(&key)
+            .is_some_and(|at| at.elapsed() < DEDUPE_TTL)
+        {
+            return false;
+        }
+        if self.delivered.len() >= MAX_TRACKED_IDS {
+            self.delivered.retain(|_, at| at.elapsed() < DEDUPE_TTL);
+        }
+        self.delivered.insert(key, Instant::now());
+        true
+    }
+}
+
+/// Devices keep only the newest notification per collapse key. Sent as the
+/// Android collapse key and `apns-collapse-id`, which allows at most 64 bytes.
+fn collapse_key(conversation_id: &str) -> String {
+    format!("conversation:{conversation_id}")
+}
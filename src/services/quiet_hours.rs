@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::db::Database;
+use crate::services::digest::build_digest;
+
+/// Users flushed per tick.
+const FLUSH_BATCH_SIZE: i64 = 200;
+
+/// Whether a push to `user_id` should be held back for their quiet hours. Held
+/// pushes are recorded so [`spawn_quiet_hours_flusher`] can follow up once the
+/// window ends. Lookup failures let the push through.
+pub async fn should_defer(db: &Database, user_id: &str) -> bool {
+    let repo = db.notification_prefs_repo();
+    let prefs = match repo.get(user_id).await {
+        Ok(Some(prefs)) => prefs,
+        Ok(None) => return false,
+        Err(e) => {
+            tracing::warn!(error = %e, user_id, "Failed to load notification preferences (non-fatal)");
+            return false;
+        }
+    };
+    if !prefs.in_quiet_hours(chrono::Utc::now()) {
+        return false;
+    }
+    if let Err(e) = repo.mark_deferred(user_id).await {
+        tracing::warn!(error = %e, user_id, "Failed to record deferred push (non-fatal)");
+    }
+    true
+}
+
+/// Periodically find users whose quiet hours have ended with pushes held back,
+/// and send each a single digest of what they missed (if they asked for one).
+pub fn spawn_quiet_hours_flusher(state: Arc<AppState>, interval_secs: u64) {
+    tokio::spawn(async move {
+        let interval = std::time::Duration::from_secs(interval_secs);
+        loop {
+            tokio::time::sleep(interval).await;
+            let repo = state.db.notification_prefs_repo();
+            let deferred = match repo.list_deferred(FLUSH_BATCH_SIZE).await {
+                Ok(deferred) => deferred,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to load deferred pushes (non-fatal)");
+                    continue;
+                }
+            };
+
+            let now = chrono::Utc::now();
+            let mut sent = 0;
+            for prefs in deferred.iter().filter(|p| !p.in_quiet_hours(now)) {
+                if prefs.quiet_hours_digest {
+                    // Everything still unread, not just what arrived during quiet hours
+                    match build_digest(&state, &prefs.user_id, None).await {
+                        Ok(Some(digest)) => {
+                            let data = serde_json::json!({
+                                "type": "quiet_hours_digest",
+                                "collapse_key": "digest",
+                                "conversation_ids": digest
+                                    .activity
+                                    .iter()
+                                    .map(|a| a.conversation_id.as_str())
+                                    .collect::<Vec<_>>(),
+                            });
+                            if state
+                                .push_notifications
+                                .send_push_notification(
+                                    &prefs.user_id,
+                                    &digest.title,
+                                    &digest.body,
+                                    Some(&data),
+                                )
+                                .await
+                            {
+                                sent += 1;
+                            }
+                        }
+                        // Everything was read in the meantime
+                        Ok(None) => {}
+                        Err(e) => {
+                            tracing::warn!(
+                                error = %e,
+                                user_id = %prefs.user_id,
+                                "Quiet hours digest build failed"
+                            );
+                            continue;
+                        }
+                    }
+                }
+                if let Err(e) = repo.clear_deferred(&prefs.user_id).await {
+                    tracing::warn!(error = %e, "Failed to clear deferred pushes");
+                }
+            }
+
+            if sent > 0 {
+                tracing::info!(sent, "Quiet hours digests sent");
+            }
+        }
+    });
+}
@@ -1,15 +1,152 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::error::AppError;
+use crate::models::entities::PredictionKind;
+use crate::models::responses::{InFlightPrediction, PredictionBudget};
 
 #[derive(Clone)]
 pub struct ReplicateClient {
     http: reqwest::Client,
     api_token: String,
     model: String,
+    video_model: String,
     configured: bool,
+    /// Predictions being waited on, by a local key; Replicate's id arrives later
+    in_flight: Arc<DashMap<String, Running>>,
+    budget: Arc<DailyBudget>,
+}
+
+struct Running {
+    id: Option<String>,
+    model: String,
+    kind: PredictionKind,
+    started_at: NaiveDateTime,
+}
+
+/// Predictions started per UTC day and kind, across all users, so a runaway
+/// caller can't run up the bill. Counts reset at midnight and on restart.
+struct DailyBudget {
+    image_limit: u32,
+    video_limit: u32,
+    usage: Mutex<BudgetUsage>,
+}
+
+struct BudgetUsage {
+    day: NaiveDate,
+    images: u32,
+    videos: u32,
+}
+
+impl DailyBudget {
+    fn new(image_limit: u32, video_limit: u32) -> Self {
+        Self {
+            image_limit,
+            video_limit,
+            usage: Mutex::new(BudgetUsage {
+                day: Utc::now().date_naive(),
+                images: 0,
+                videos: 0,
+            }),
+        }
+    }
+
+    fn limit(&self, kind: PredictionKind) -> u32 {
+        match kind {
+            PredictionKind::Image => self.image_limit,
+            PredictionKind::Video => self.video_limit,
+        }
+    }
+
+    /// Count one prediction of `kind`, or refuse once today's limit is spent.
+    fn reserve(&self, kind: PredictionKind) -> Result<(), AppError> {
+        let limit = self.limit(kind);
+        let mut usage = self.usage.lock().unwrap();
+        usage.roll_over();
+        let used = usage.count_mut(kind);
+        if limit > 0 && *used >= limit {
+            tracing::warn!(%kind, limit, "Replicate daily budget reached");
+            return Err(AppError::service_unavailable(format!(
+                "Daily {kind} generation budget reached, try again tomorrow"
+            )));
+        }
+        *used += 1;
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Vec<PredictionBudget> {
+        let mut usage = self.usage.lock().unwrap();
+        usage.roll_over();
+        [PredictionKind::Image, PredictionKind::Video]
+            .into_iter()
+            .map(|kind| PredictionBudget {
+                kind,
+                used: *usage.count_mut(kind),
+                limit: self.limit(kind),
+            })
+            .collect()
+    }
+}
+
+impl BudgetUsage {
+    fn roll_over(&mut self) {
+        let today = Utc::now().date_naive();
+        if self.day != today {
+            *self = Self {
+                day: today,
+                images: 0,
+                videos: 0,
+            };
+        }
+    }
+
+    fn count_mut(&mut self, kind: PredictionKind) -> &mut u32 {
+        match kind {
+            PredictionKind::Image => &mut self.images,
+            PredictionKind::Video => &mut self.videos,
+        }
+    }
+}
+
+/// Tracks a prediction while it is waited on. Dropped before it settles, because
+/// the caller disconnected or polling timed out, it cancels the prediction so
+/// Replicate stops billing for output nobody will read.
+struct RunningGuard<'a> {
+    client: &'a ReplicateClient,
+    key: String,
+    settled: bool,
+}
+
+impl RunningGuard<'_> {
+    fn set_id(&self, id: &str) {
+        if let Some(mut running) = self.client.in_flight.get_mut(&self.key) {
+            running.id = Some(id.to_string());
+        }
+    }
 }
 
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        let Some((_, running)) = self.client.in_flight.remove(&self.key) else {
+            return;
+        };
+        // Without an id the create request never returned; there is nothing to cancel
+        if let (false, Some(id)) = (self.settled, running.id) {
+            let client = self.client.clone();
+            tokio::spawn(async move { client.cancel(&id).await });
+        }
+    }
+}
+
+/// Polls are 2s apart: about a minute for images, five for videos.
+const IMAGE_POLL_ATTEMPTS: u32 = 30;
+const VIDEO_POLL_ATTEMPTS: u32 = 150;
+
 #[derive(Serialize)]
 struct PredictionRequest {
     input: serde_json::Value,
@@ -29,69 +166,53 @@ struct PredictionUrls {
 }
 
 impl ReplicateClient {
-    pub fn new(http: reqwest::Client, api_token: &str, model: &str) -> Self {
+    pub fn new(http: reqwest::Client, api_token: &str, model: &str, video_model: &str) -> Self {
         Self {
             http,
             configured: !api_token.is_empty(),
             api_token: api_token.to_string(),
             model: model.to_string(),
+            video_model: video_model.to_string(),
+            in_flight: Arc::new(DashMap::new()),
+            budget: Arc::new(DailyBudget::new(0, 0)),
         }
     }
 
-    pub fn is_configured(&self) -> bool {
-        self.configured
-    }
-
-    pub async fn generate_image(
-        &self,
-        prompt: &str,
-        aspect_ratio: &str,
-    ) -> Result<Option<String>, AppError> {
-        self.run_prediction(
-            &self.model,
-            serde_json::json!({
-                "prompt": prompt,
-                "go_fast": true,
-                "megapixels": "1",
-                "aspect_ratio": aspect_ratio,
-                "output_format": "jpg",
-                "output_quality": 80
-            }),
-        )
-        .await
-    }
-
-    pub async fn generate_image_via_image(
-        &self,
-        prompt: &str,
-        input_image: &str,
-        aspect_ratio: &str,
-    ) -> Result<Option<String>, AppError> {
-        self.run_prediction(
-            "black-forest-labs/flux-kontext-dev",
-            serde_json::json!({
-                "prompt": prompt,
-                "go_fast": true,
-                "guidance": 2.5,
-                "megapixels": "1",
-                "num_inference_steps": 30,
-                "aspect_ratio": aspect_ratio,
-                "output_format": "jpg",
-                "output_quality": 80,
-                "input_image": input_image
-            }),
-        )
-        .await
+    /// Cap the predictions started per UTC day; 0 leaves a kind unlimited.
+    pub fn with_daily_limits(self, image_limit: u32, video_limit: u32) -> Self {
+        Self {
+            budget: Arc::new(DailyBudget::new(image_limit, video_limit)),
+            ..self
+        }
     }
 
     async fn run_prediction(
         &self,
         model: &str,
+        kind: PredictionKind,
         input: serde_json::Value,
+        poll_attempts: u32,
     ) -> Result<Option<String>, AppError> {
         if !self.configured {
             return Ok(None);
         }
+        self.budget.reserve(kind)?;
+
+        let key = Uuid::new_v4().to_string();
+        self.in_flight.insert(
+            key.clone(),
+            Running {
+                id: None,
+                model: model.to_string(),
+                kind,
+                started_at: Utc::now().naive_utc(),
+            },
+        );
+        let mut guard = RunningGuard {
+            client: self,
+            key,
+            settled: false,
+        };
 
         let url = format!("https://api.replicate.com/v1/models/{model}/predictions");
 
@@ -118,6 +239,7 @@ impl ReplicateClient {
         let prediction: PredictionResponse = resp.json().await.map_err(|e| {
             AppError::service_unavailable(format!("Failed to parse Replicate response: {e}"))
         })?;
+        guard.set_id(&prediction.id);
 
         // If "Prefer: wait" didn't resolve, poll
         if prediction.status == "starting" || prediction.status == "processing" {
@@ -125,14 +247,22 @@ impl ReplicateClient {
                 Some(PredictionUrls { get: Some(url) }) => url.clone(),
                 _ => format!("https://api.replicate.com/v1/predictions/{}", prediction.id),
             };
-            return self.poll_prediction(&poll_url).await;
+            return self
+                .poll_prediction(&poll_url, poll_attempts, &mut guard)
+                .await;
         }
 
+        guard.settled = true;
         Ok(extract_output_url(&prediction.output))
     }
 
-    async fn poll_prediction(&self, url: &str) -> Result<Option<String>, AppError> {
-        for _ in 0..30 {
+    async fn poll_prediction(
+        &self,
+        url: &str,
+        attempts: u32,
+        guard: &mut RunningGuard<'_>,
+    ) -> Result<Option<String>, AppError> {
+        for _ in 0..attempts {
             tokio::time::sleep(std::time::Duration::from_secs(2)).await;
 
             let resp = self
@@ -149,15 +279,172 @@ impl ReplicateClient {
             })?;
 
             match prediction.status.as_str() {
-                "succeeded" => return Ok(extract_output_url(&prediction.output)),
+                "succeeded" => {
+                    guard.settled = true;
+                    return Ok(extract_output_url(&prediction.output));
+                }
                 "failed" | "canceled" => {
-                    return Err(AppError::service_unavailable("Image generation failed"));
+                    guard.settled = true;
+                    return Err(AppError::service_unavailable("Replicate prediction failed"));
                 }
                 _ => continue,
             }
         }
 
-        Err(AppError::service_unavailable("Image generation timed out"))
+        // Left unsettled, so the guard cancels it
+        Err(AppError::service_unavailable(
+            "Replicate prediction timed out",
+        ))
+    }
+
+    /// Ask Replicate to stop a prediction; failures are only logged.
+    async fn cancel(&self, id: &str) {
+        let url = format!("https://api.replicate.com/v1/predictions/{id}/cancel");
+        match self
+            .http
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_token))
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await
+        {
+            Ok(resp) if resp.status().is_success() => {
+                tracing::info!(prediction_id = %id, "Canceled Replicate prediction");
+            }
+            Ok(resp) => {
+                tracing::warn!(prediction_id = %id, status = %resp.status(), "Failed to cancel Replicate prediction");
+            }
+            Err(e) => {
+                tracing::warn!(prediction_id = %id, error = %e, "Failed to cancel Replicate prediction");
+            }
+        }
+    }
+}
+
+/// Image and video generation. [`ReplicateClient`] implements it.
+#[async_trait]
+pub trait ImageGen: Send + Sync {
+    fn is_configured(&self) -> bool;
+
+    async fn generate_image(
+        &self,
+        prompt: &str,
+        aspect_ratio: &str,
+    ) -> Result<Option<String>, AppError>;
+
+    async fn generate_image_via_image(
+        &self,
+        prompt: &str,
+        input_image: &str,
+        aspect_ratio: &str,
+    ) -> Result<Option<String>, AppError>;
+
+    /// Render a short portrait video, starting from `image` when given.
+    async fn generate_video(
+        &self,
+        prompt: &str,
+        image: Option<&str>,
+    ) -> Result<Option<String>, AppError>;
+
+    /// Predictions being waited on, oldest first.
+    fn in_flight(&self) -> Vec<InFlightPrediction>;
+
+    /// Predictions started today per kind, against the daily limits.
+    fn budget(&self) -> Vec<PredictionBudget>;
+}
+
+#[async_trait]
+impl ImageGen for ReplicateClient {
+    fn is_configured(&self) -> bool {
+        self.configured
+    }
+
+    async fn generate_image(
+        &self,
+        prompt: &str,
+        aspect_ratio: &str,
+    ) -> Result<Option<String>, AppError> {
+        self.run_prediction(
+            &self.model,
+            PredictionKind::Image,
+            serde_json::json!({
+                "prompt": prompt,
+                "go_fast": true,
+                "megapixels": "1",
+                "aspect_ratio": aspect_ratio,
+                "output_format": "jpg",
+                "output_quality": 80
+            }),
+            IMAGE_POLL_ATTEMPTS,
+        )
+        .await
+    }
+
+    async fn generate_image_via_image(
+        &self,
+        prompt: &str,
+        input_image: &str,
+        aspect_ratio: &str,
+    ) -> Result<Option<String>, AppError> {
+        self.run_prediction(
+            "black-forest-labs/flux-kontext-dev",
+            PredictionKind::Image,
+            serde_json::json!({
+                "prompt": prompt,
+                "go_fast": true,
+                "guidance": 2.5,
+                "megapixels": "1",
+                "num_inference_steps": 30,
+                "aspect_ratio": aspect_ratio,
+                "output_format": "jpg",
+                "output_quality": 80,
+                "input_image": input_image
+            }),
+            IMAGE_POLL_ATTEMPTS,
+        )
+        .await
+    }
+
+    async fn generate_video(
+        &self,
+        prompt: &str,
+        image: Option<&str>,
+    ) -> Result<Option<String>, AppError> {
+        let mut input = serde_json::json!({
+            "prompt": prompt,
+            "aspect_ratio": "9:16",
+        });
+        if let Some(image) = image {
+            input["image"] = image.into();
+        }
+        self.run_prediction(
+            &self.video_model,
+            PredictionKind::Video,
+            input,
+            VIDEO_POLL_ATTEMPTS,
+        )
+        .await
+    }
+
+    fn in_flight(&self) -> Vec<InFlightPrediction> {
+        let now = Utc::now().naive_utc();
+        let mut predictions: Vec<InFlightPrediction> = self
+            .in_flight
+            .iter()
+            .map(|running| InFlightPrediction {
+                id: running.id.clone(),
+                model: running.model.clone(),
+                kind: running.kind,
+                started_at: running.started_at,
+                elapsed_seconds: (now - running.started_at).num_seconds().max(0) as u64,
+            })
+            .collect();
+        predictions.sort_by_key(|p| p.started_at);
+        predictions
+    }
+
+    fn budget(&self) -> Vec<PredictionBudget> {
+        self.budget.snapshot()
     }
 }
 
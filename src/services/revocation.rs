@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{NaiveDateTime, Utc};
+use dashmap::DashMap;
+
+use crate::AppState;
+use crate::db::Database;
+
+/// In-memory copy of `revoked_tokens`, so the auth extractor can check a jti
+/// without a database round trip. Revocations made on another instance show
+/// up after the next [`spawn_revocation_sync`] refresh.
+#[derive(Default)]
+pub struct RevocationList {
+    /// jti → when the token stops being accepted (its exp plus the JWT leeway)
+    /// and the entry can be forgotten
+    revoked: DashMap<String, NaiveDateTime>,
+}
+
+impl RevocationList {
+    pub fn is_revoked(&self, jti: &str) -> bool {
+        self.revoked
+            .get(jti)
+            .is_some_and(|expires_at| *expires_at > Utc::now().naive_utc())
+    }
+
+    pub fn insert(&self, jti: &str, expires_at: NaiveDateTime) {
+        self.revoked.insert(jti.to_string(), expires_at);
+    }
+
+    /// Replace the list with what the database holds.
+    pub async fn refresh(&self, db: &Database) -> Result<(), sqlx::Error> {
+        let active: HashMap<String, NaiveDateTime> = db
+            .revoked_token_repo()
+            .list_active()
+            .await?
+            .into_iter()
+            .collect();
+        self.revoked.retain(|jti, _| active.contains_key(jti));
+        for (jti, expires_at) in active {
+            self.revoked.insert(jti, expires_at);
+        }
+        Ok(())
+    }
+}
+
+/// Periodically reload revocations and drop rows for tokens that have expired.
+pub fn spawn_revocation_sync(state: Arc<AppState>, interval_secs: u64) {
+    tokio::spawn(async move {
+        let interval = std::time::Duration::from_secs(interval_secs);
+        loop {
+            tokio::time::sleep(interval).await;
+            if let Err(e) = state.revoked_tokens.refresh(&state.db).await {
+                tracing::warn!(error = %e, "Failed to refresh token revocation list");
+                continue;
+            }
+            match state.db.revoked_token_repo().purge_expired().await {
+                Ok(0) => {}
+                Ok(purged) => tracing::info!(purged, "Purged expired token revocations"),
+                Err(e) => tracing::warn!(error = %e, "Failed to purge expired token revocations"),
+            }
+        }
+    });
+}
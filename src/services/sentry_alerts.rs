@@ -0,0 +1,118 @@
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+
+/// Alerts remembered before expired ones are swept out.
+const MAX_TRACKED_ALERTS: usize = 10_000;
+
+/// An issue or alert-rule notification from a Sentry internal integration.
+#[derive(Debug, Clone)]
+pub struct SentryAlert {
+    /// Issue the alert is about; repeats are deduplicated on it
+    pub issue_id: String,
+    pub title: String,
+    pub culprit: Option<String>,
+    pub level: Option<String>,
+    pub project: Option<String>,
+    pub environment: Option<String>,
+    /// Alert rule that fired, for `event_alert` resources
+    pub rule: Option<String>,
+    pub web_url: Option<String>,
+}
+
+/// `Sentry-Hook-Signature` check: hex HMAC-SHA256 of the raw body keyed by the
+/// integration's client secret.
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Ok(expected) = hex::decode(signature.trim()) else {
+        return false;
+    };
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Pull the alert out of a webhook payload. `resource` is the
+/// `Sentry-Hook-Resource` header; only new issues and fired alert rules are
+/// relayed, anything else returns `None`.
+pub fn parse(resource: &str, payload: &Value) -> Option<SentryAlert> {
+    let str_at = |v: &Value, key: &str| v.get(key).and_then(Value::as_str).map(str::to_string);
+    let data = payload.get("data")?;
+    match resource {
+        "issue" if payload.get("action").and_then(Value::as_str) == Some("created") => {
+            let issue = data.get("issue")?;
+            Some(SentryAlert {
+                issue_id: id_at(issue, "id")?,
+                title: str_at(issue, "title")?,
+                culprit: str_at(issue, "culprit"),
+                level: str_at(issue, "level"),
+                project: issue.get("project").and_then(|p| str_at(p, "slug")),
+                environment: None,
+                rule: None,
+                web_url: str_at(issue, "web_url").or_else(|| str_at(issue, "permalink")),
+            })
+        }
+        "event_alert" => {
+            let event = data.get("event")?;
+            Some(SentryAlert {
+                issue_id: id_at(event, "issue_id")?,
+                title: str_at(event, "title")?,
+                culprit: str_at(event, "culprit"),
+                level: str_at(event, "level"),
+                project: str_at(event, "project").or_else(|| id_at(event, "project")),
+                environment: str_at(event, "environment"),
+                rule: str_at(data, "triggered_rule"),
+                web_url: str_at(event, "web_url"),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Sentry sends ids as strings or numbers depending on the resource.
+fn id_at(v: &Value, key: &str) -> Option<String> {
+    match v.get(key)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Drops alerts for an issue that was already relayed within the window, so a
+/// noisy issue doesn't flood the chat space.
+pub struct AlertDeduper {
+    seen: DashMap<String, Instant>,
+    window: Duration,
+}
+
+impl AlertDeduper {
+    /// A zero `window` relays every alert.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            seen: DashMap::new(),
+            window,
+        }
+    }
+
+    /// Whether an alert for `key` should go out now. Counts as sent if so.
+    pub fn should_relay(&self, key: &str) -> bool {
+        if self.window.is_zero() {
+            return true;
+        }
+        if self
+            .seen
+            .get(key)
+            .is_some_and(|at| at.elapsed() < self.window)
+        {
+            return false;
+        }
+        if self.seen.len() >= MAX_TRACKED_ALERTS {
+            self.seen.retain(|_, at| at.elapsed() < self.window);
+        }
+        self.seen.insert(key.to_string(), Instant::now());
+        true
+    }
+}
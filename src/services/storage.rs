@@ -1,26 +1,75 @@
 use std::collections::HashMap;
 use std::time::Duration;
 
+use async_trait::async_trait;
 use aws_sdk_s3::Client;
 use aws_sdk_s3::config::{Credentials, Region};
+use aws_sdk_s3::error::ProvideErrorMetadata;
 use aws_sdk_s3::presigning::PresigningConfig;
 use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::{Delete, ObjectIdentifier};
+use futures::StreamExt;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use strum::{Display, EnumString};
 
 use crate::config::Settings;
 use crate::error::AppError;
 
+/// How media keys are turned into URLs in API responses.
+#[derive(Debug, Clone, Copy, PartialEq, Display, EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum MediaUrlMode {
+    /// S3 presigned GETs, valid for `S3_URL_EXPIRES_SECONDS`.
+    Presigned,
+    /// Long-lived URLs on the CDN, signed with [`sign_media_url`] and verified at the edge.
+    Signed,
+    /// `/api/v1/media/{key}` paths served by the authenticated media proxy.
+    Proxy,
+}
+
+/// Path prefix of the media proxy route.
+pub const MEDIA_PROXY_PATH: &str = "/api/v1/media/";
+
+/// An object fetched for streaming, possibly a byte range of it.
+pub struct StoredObject {
+    pub body: ByteStream,
+    pub content_type: Option<String>,
+    pub content_length: Option<i64>,
+    /// Set when a range was served, e.g. `bytes 0-1023/4096`
+    pub content_range: Option<String>,
+}
+
 pub struct StorageService {
     client: Client,
     bucket: String,
     http_client: reqwest::Client,
     public_url_base: String,
     url_expires_seconds: u32,
+    url_mode: MediaUrlMode,
+    cdn_base_url: String,
+    signing_secret: String,
+    signed_url_ttl_seconds: u64,
     max_image_size_bytes: u64,
     max_audio_size_bytes: u64,
 }
 
 const IMAGE_EXTENSIONS: &[&str] = &[".jpg", ".jpeg", ".png", ".gif", ".webp"];
 const AUDIO_EXTENSIONS: &[&str] = &[".mp3", ".m4a", ".wav", ".ogg"];
+/// Presigned URLs generated at once by `generate_presigned_urls_batch`.
+const PRESIGN_CONCURRENCY: usize = 16;
+/// Signed URL expiry is rounded up to this boundary so repeated responses
+/// return identical URLs and clients can cache the media.
+const SIGNED_URL_EXPIRY_STEP_SECS: u64 = 3600;
+
+/// Signature for a CDN media URL: hex HMAC-SHA256 of `"{key}:{expires}"` keyed
+/// by `MEDIA_URL_SIGNING_SECRET`. The edge recomputes it and checks `expires`.
+pub fn sign_media_url(secret: &str, key: &str, expires: u64) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{key}:{expires}").as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
 
 impl StorageService {
     pub fn new(settings: &Settings, http_client: reqwest::Client) -> Result<Self, anyhow::Error> {
@@ -42,18 +91,87 @@ impl StorageService {
 
         let client = Client::from_conf(config);
 
+        let signing_secret = settings
+            .media_url_signing_secret
+            .clone()
+            .unwrap_or_default();
+        if settings.media_url_mode == MediaUrlMode::Signed && signing_secret.is_empty() {
+            anyhow::bail!("MEDIA_URL_MODE=signed requires MEDIA_URL_SIGNING_SECRET");
+        }
+
         Ok(Self {
             client,
             bucket: settings.aws_s3_bucket.clone(),
             http_client,
             public_url_base: settings.s3_public_url_base.clone(),
             url_expires_seconds: settings.s3_url_expires_seconds,
+            url_mode: settings.media_url_mode,
+            cdn_base_url: settings
+                .media_cdn_base_url
+                .clone()
+                .unwrap_or_else(|| settings.s3_public_url_base.clone()),
+            signing_secret,
+            signed_url_ttl_seconds: settings.media_signed_url_ttl_seconds,
             max_image_size_bytes: settings.max_image_size_bytes(),
             max_audio_size_bytes: settings.max_audio_size_bytes(),
         })
     }
 
-    pub async fn upload(
+    fn signed_url(&self, key: &str) -> String {
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+        let expires = (now + self.signed_url_ttl_seconds).div_ceil(SIGNED_URL_EXPIRY_STEP_SECS)
+            * SIGNED_URL_EXPIRY_STEP_SECS;
+        format!(
+            "{}/{key}?expires={expires}&signature={}",
+            self.cdn_base_url.trim_end_matches('/'),
+            sign_media_url(&self.signing_secret, key, expires)
+        )
+    }
+}
+
+/// Object storage for media. [`StorageService`] implements it on S3.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn upload(
+        &self,
+        user_id: &str,
+        file_bytes: Vec<u8>,
+        file_extension: &str,
+        content_type: &str,
+    ) -> Result<(String, u64), AppError>;
+
+    async fn generate_presigned_url(&self, key: &str) -> String;
+
+    async fn generate_presigned_urls_batch(&self, keys: &[String]) -> HashMap<String, String>;
+
+    /// Copy an object to a world-readable key under `prefix` and return its public URL.
+    /// Used for shared snapshots, which must outlive presigned URL expiry.
+    async fn copy_to_public(&self, key: &str, prefix: &str) -> Result<String, AppError>;
+
+    /// Delete every object under `prefix`, e.g. a revoked share's public copies.
+    /// Returns how many were deleted.
+    async fn delete_prefix(&self, prefix: &str) -> Result<usize, AppError>;
+
+    /// Bring a media reference from the legacy backend into the `{user_id}/{file}` layout
+    /// used by uploads and return the new key. Objects in this bucket are copied, other
+    /// URLs are downloaded and re-uploaded, and keys already in the layout are kept.
+    async fn import_object(&self, url_or_key: &str, user_id: &str) -> Result<String, AppError>;
+
+    /// Fetch an object for streaming. `range` is a raw HTTP `Range` header value.
+    async fn get_object(&self, key: &str, range: Option<&str>) -> Result<StoredObject, AppError>;
+
+    fn extract_key_from_url(&self, url_or_key: &str) -> String;
+
+    fn validate_image(&self, filename: &str, size: u64) -> Result<(), AppError>;
+
+    fn validate_audio(&self, filename: &str, size: u64) -> Result<(), AppError>;
+
+    async fn download_file(&self, url: &str) -> Result<(Vec<u8>, String), AppError>;
+}
+
+#[async_trait]
+impl Storage for StorageService {
+    async fn upload(
         &self,
         user_id: &str,
         file_bytes: Vec<u8>,
@@ -78,10 +196,15 @@ impl StorageService {
         Ok((key, size))
     }
 
-    pub async fn generate_presigned_url(&self, key: &str) -> String {
+    async fn generate_presigned_url(&self, key: &str) -> String {
         if key.starts_with("http://") || key.starts_with("https://") {
             return key.to_string();
         }
+        match self.url_mode {
+            MediaUrlMode::Signed => return self.signed_url(key),
+            MediaUrlMode::Proxy => return format!("{MEDIA_PROXY_PATH}{key}"),
+            MediaUrlMode::Presigned => {}
+        }
 
         let expires =
             PresigningConfig::expires_in(Duration::from_secs(self.url_expires_seconds as u64))
@@ -103,16 +226,151 @@ impl StorageService {
         }
     }
 
-    pub async fn generate_presigned_urls_batch(&self, keys: &[String]) -> HashMap<String, String> {
-        let mut map = HashMap::with_capacity(keys.len());
-        for key in keys {
-            let url = self.generate_presigned_url(key).await;
-            map.insert(key.clone(), url);
+    async fn generate_presigned_urls_batch(&self, keys: &[String]) -> HashMap<String, String> {
+        let mut unique = keys.to_vec();
+        unique.sort_unstable();
+        unique.dedup();
+
+        futures::stream::iter(unique)
+            .map(|key: String| async move {
+                let url = self.generate_presigned_url(&key).await;
+                (key, url)
+            })
+            .buffer_unordered(PRESIGN_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    async fn copy_to_public(&self, key: &str, prefix: &str) -> Result<String, AppError> {
+        let key = self.extract_key_from_url(key);
+        if key.starts_with("http://") || key.starts_with("https://") {
+            return Ok(key);
+        }
+        let filename = key.rsplit('/').next().unwrap_or(&key);
+        let public_key = format!("{prefix}/{filename}");
+
+        self.client
+            .copy_object()
+            .bucket(&self.bucket)
+            .copy_source(format!("{}/{key}", self.bucket))
+            .key(&public_key)
+            .acl(aws_sdk_s3::types::ObjectCannedAcl::PublicRead)
+            .send()
+            .await
+            .map_err(|e| AppError::service_unavailable(format!("S3 copy failed: {e}")))?;
+
+        Ok(format!(
+            "{}/{public_key}",
+            self.public_url_base.trim_end_matches('/')
+        ))
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<usize, AppError> {
+        let mut deleted = 0;
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(prefix)
+            .into_paginator()
+            .send();
+        while let Some(page) = pages.next().await {
+            let page =
+                page.map_err(|e| AppError::service_unavailable(format!("S3 list failed: {e}")))?;
+            let objects = page
+                .contents()
+                .iter()
+                .filter_map(|o| o.key())
+                .map(|key| ObjectIdentifier::builder().key(key).build())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| AppError::service_unavailable(format!("S3 delete failed: {e}")))?;
+            if objects.is_empty() {
+                continue;
+            }
+            let count = objects.len();
+            let delete = Delete::builder()
+                .set_objects(Some(objects))
+                .quiet(true)
+                .build()
+                .map_err(|e| AppError::service_unavailable(format!("S3 delete failed: {e}")))?;
+            let output = self
+                .client
+                .delete_objects()
+                .bucket(&self.bucket)
+                .delete(delete)
+                .send()
+                .await
+                .map_err(|e| AppError::service_unavailable(format!("S3 delete failed: {e}")))?;
+            if !output.errors().is_empty() {
+                return Err(AppError::service_unavailable(format!(
+                    "S3 delete failed for {} objects",
+                    output.errors().len()
+                )));
+            }
+            deleted += count;
+        }
+        Ok(deleted)
+    }
+
+    async fn import_object(&self, url_or_key: &str, user_id: &str) -> Result<String, AppError> {
+        let key = self.extract_key_from_url(url_or_key);
+        if key.starts_with("http://") || key.starts_with("https://") {
+            let (bytes, content_type) = self.download_file(&key).await?;
+            let path = key.split(['?', '#']).next().unwrap_or(&key);
+            let ext = file_extension(path.rsplit('/').next().unwrap_or(path));
+            let (new_key, _) = self.upload(user_id, bytes, &ext, &content_type).await?;
+            return Ok(new_key);
         }
-        map
+        if key.starts_with(&format!("{user_id}/")) {
+            return Ok(key);
+        }
+
+        let filename = key.rsplit('/').next().unwrap_or(&key);
+        let new_key = format!("{user_id}/{filename}");
+        self.client
+            .copy_object()
+            .bucket(&self.bucket)
+            .copy_source(format!("{}/{key}", self.bucket))
+            .key(&new_key)
+            .send()
+            .await
+            .map_err(|e| AppError::service_unavailable(format!("S3 copy failed: {e}")))?;
+
+        Ok(new_key)
     }
 
-    pub fn extract_key_from_url(&self, url_or_key: &str) -> String {
+    async fn get_object(&self, key: &str, range: Option<&str>) -> Result<StoredObject, AppError> {
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .set_range(range.map(String::from))
+            .send()
+            .await
+            .map_err(|e| {
+                let e = e.into_service_error();
+                if e.is_no_such_key() {
+                    AppError::not_found("File not found")
+                } else if e.code() == Some("InvalidRange") {
+                    AppError::range_not_satisfiable("Requested range is not satisfiable")
+                } else {
+                    AppError::service_unavailable(format!("S3 download failed: {e}"))
+                }
+            })?;
+
+        Ok(StoredObject {
+            content_type: output.content_type().map(String::from),
+            content_length: output.content_length(),
+            content_range: output.content_range().map(String::from),
+            body: output.body,
+        })
+    }
+
+    fn extract_key_from_url(&self, url_or_key: &str) -> String {
+        if let Some(key) = url_or_key.strip_prefix(MEDIA_PROXY_PATH) {
+            return key.to_string();
+        }
         if !url_or_key.starts_with("http://") && !url_or_key.starts_with("https://") {
             return url_or_key.to_string();
         }
@@ -121,10 +379,17 @@ impl StorageService {
                 .trim_start_matches('/')
                 .to_string();
         }
+        // Signed CDN URLs handed out in earlier responses
+        if self.url_mode == MediaUrlMode::Signed
+            && let Some(rest) = url_or_key.strip_prefix(self.cdn_base_url.trim_end_matches('/'))
+        {
+            let path = rest.split('?').next().unwrap_or_default();
+            return path.trim_start_matches('/').to_string();
+        }
         url_or_key.to_string()
     }
 
-    pub fn validate_image(&self, filename: &str, size: u64) -> Result<(), AppError> {
+    fn validate_image(&self, filename: &str, size: u64) -> Result<(), AppError> {
         let ext = file_extension(filename).to_lowercase();
         if !IMAGE_EXTENSIONS.contains(&ext.as_str()) {
             return Err(AppError::bad_request(format!(
@@ -141,7 +406,7 @@ impl StorageService {
         Ok(())
     }
 
-    pub fn validate_audio(&self, filename: &str, size: u64) -> Result<(), AppError> {
+    fn validate_audio(&self, filename: &str, size: u64) -> Result<(), AppError> {
         let ext = file_extension(filename).to_lowercase();
         if !AUDIO_EXTENSIONS.contains(&ext.as_str()) {
             return Err(AppError::bad_request(format!(
@@ -158,7 +423,7 @@ impl StorageService {
         Ok(())
     }
 
-    pub async fn download_file(&self, url: &str) -> Result<(Vec<u8>, String), AppError> {
+    async fn download_file(&self, url: &str) -> Result<(Vec<u8>, String), AppError> {
         let resp = self
             .http_client
             .get(url)
@@ -205,3 +470,49 @@ pub fn mime_from_extension(ext: &str) -> &'static str {
         _ => "application/octet-stream",
     }
 }
+
+/// Batch presigning against one `generate_presigned_url` call at a time, for a
+/// large history's worth of keys. Ignored by default; run with
+/// `cargo test --release presign_benchmark -- --ignored --nocapture`.
+#[cfg(test)]
+mod bench {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::test_support;
+
+    const KEYS: usize = 500;
+    const RUNS: u32 = 10;
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "benchmark"]
+    async fn presign_benchmark() {
+        let settings = test_support::settings(&[("MEDIA_URL_MODE", "presigned")]);
+        let storage = StorageService::new(&settings, reqwest::Client::new()).unwrap();
+        let keys: Vec<String> = (0..KEYS)
+            .map(|i| format!("user-{}/{i}.jpg", i % 7))
+            .collect();
+
+        let started = Instant::now();
+        for _ in 0..RUNS {
+            let mut urls = HashMap::new();
+            for key in &keys {
+                urls.insert(key.clone(), storage.generate_presigned_url(key).await);
+            }
+            std::hint::black_box(urls);
+        }
+        let sequential = started.elapsed() / RUNS;
+
+        let started = Instant::now();
+        for _ in 0..RUNS {
+            let urls = storage.generate_presigned_urls_batch(&keys).await;
+            assert_eq!(urls.len(), KEYS);
+            std::hint::black_box(urls);
+        }
+        let batched = started.elapsed() / RUNS;
+
+        println!(
+            "{KEYS} keys: sequential {sequential:?}, batch of {PRESIGN_CONCURRENCY} at a time {batched:?}"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::error::AppError;
+use crate::services::character_generator::CharacterGeneratorService;
+use crate::services::moderation;
+
+/// Influencers considered per run.
+const ROTATION_BATCH_SIZE: i64 = 50;
+
+/// Periodically replace suggested messages nobody tapped. An influencer is only
+/// considered once it has started `min_conversations` conversations in the last
+/// `window_days` days, so every suggestion was seen a fair number of times, and
+/// at most once per window.
+pub fn spawn_suggestion_rotation(
+    state: Arc<AppState>,
+    interval_secs: u64,
+    window_days: i32,
+    min_conversations: i64,
+) {
+    tokio::spawn(async move {
+        let interval = std::time::Duration::from_secs(interval_secs);
+        loop {
+            tokio::time::sleep(interval).await;
+            let candidates = match state
+                .db
+                .suggestion_repo()
+                .rotation_candidates(window_days, min_conversations, ROTATION_BATCH_SIZE)
+                .await
+            {
+                Ok(candidates) => candidates,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to load suggestion rotation candidates (non-fatal)");
+                    continue;
+                }
+            };
+
+            let mut rotated = 0;
+            for influencer_id in &candidates {
+                match rotate(&state, influencer_id, window_days).await {
+                    Ok(true) => rotated += 1,
+                    Ok(false) => {}
+                    Err(e) => {
+                        tracing::warn!(error = %e, influencer_id, "Suggestion rotation failed (non-fatal)");
+                    }
+                }
+            }
+            if rotated > 0 {
+                tracing::info!(rotated, "Rotated unused suggested messages");
+            }
+        }
+    });
+}
+
+/// Swap the influencer's untapped suggestions for freshly generated ones.
+/// Returns whether anything changed.
+async fn rotate(state: &AppState, influencer_id: &str, window_days: i32) -> Result<bool, AppError> {
+    let repo = state.db.inf_repo();
+    let Some(influencer) = repo.get_by_id(influencer_id).await? else {
+        return Ok(false);
+    };
+    let now = chrono::Utc::now().naive_utc();
+    if influencer
+        .suggestions_rotated_at()
+        .is_some_and(|at| now - at < chrono::Duration::days(window_days.into()))
+    {
+        return Ok(false);
+    }
+
+    let tapped: HashSet<String> = state
+        .db
+        .suggestion_repo()
+        .tap_counts(influencer_id, window_days)
+        .await?
+        .into_iter()
+        .map(|(text, _)| text)
+        .collect();
+    let unused: Vec<usize> = influencer
+        .suggested_messages
+        .iter()
+        .enumerate()
+        .filter(|(_, text)| !tapped.contains(text.trim()))
+        .map(|(i, _)| i)
+        .collect();
+    if unused.is_empty() {
+        return Ok(false);
+    }
+
+    let instructions = moderation::strip_guardrails(&influencer.system_instructions);
+    let (_, generated) = CharacterGeneratorService::generate_initial_greeting(
+        state.gemini.as_ref(),
+        &influencer.display_name,
+        &instructions,
+    )
+    .await?;
+    let mut fresh = generated
+        .into_iter()
+        .map(|text| text.trim().to_string())
+        .filter(|text| {
+            !text.is_empty()
+                && !influencer
+                    .suggested_messages
+                    .iter()
+                    .any(|m| m.trim() == text)
+        });
+
+    let mut suggestions = influencer.suggested_messages.clone();
+    let mut replaced = false;
+    for i in unused {
+        let Some(text) = fresh.next() else { break };
+        suggestions[i] = text;
+        replaced = true;
+    }
+    if !replaced {
+        return Ok(false);
+    }
+
+    repo.apply_enrichment(influencer_id, None, None, Some(&suggestions))
+        .await?;
+    let rotated_at = serde_json::json!(now.format("%Y-%m-%d %H:%M:%S").to_string());
+    repo.set_metadata_key(influencer_id, "suggestions_rotated_at", &rotated_at)
+        .await?;
+    state.influencer_cache.invalidate(influencer_id);
+    Ok(true)
+}
@@ -0,0 +1,153 @@
+use serde::Deserialize;
+
+use crate::error::AppError;
+
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+/// Telegram rejects messages longer than this.
+const MAX_MESSAGE_CHARS: usize = 4096;
+
+/// Telegram Bot API client for influencers bridged to Telegram.
+#[derive(Clone)]
+pub struct TelegramService {
+    http: reqwest::Client,
+    webhook_base_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    ok: bool,
+    result: Option<T>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BotUser {
+    pub username: Option<String>,
+}
+
+/// The subset of a Telegram `Update` the bridge handles.
+#[derive(Debug, Deserialize)]
+pub struct Update {
+    pub update_id: i64,
+    pub message: Option<IncomingMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IncomingMessage {
+    pub chat: Chat,
+    pub from: Option<Sender>,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Chat {
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Sender {
+    pub id: i64,
+    #[serde(default)]
+    pub is_bot: bool,
+}
+
+impl TelegramService {
+    pub fn new(http: reqwest::Client, webhook_base_url: Option<String>) -> Self {
+        Self {
+            http,
+            webhook_base_url: webhook_base_url.map(|u| u.trim_end_matches('/').to_string()),
+        }
+    }
+
+    /// Public URL Telegram posts updates for `influencer_id` to.
+    pub fn webhook_url(&self, influencer_id: &str) -> Option<String> {
+        self.webhook_base_url
+            .as_ref()
+            .map(|base| format!("{base}/api/v1/telegram/webhook/{influencer_id}"))
+    }
+
+    async fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        token: &str,
+        method: &str,
+        params: &serde_json::Value,
+    ) -> Result<T, AppError> {
+        let url = format!("{TELEGRAM_API_URL}/bot{token}/{method}");
+        let resp: ApiResponse<T> = self
+            .http
+            .post(&url)
+            .json(params)
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await
+            // Strip the URL from errors: it embeds the bot token
+            .map_err(|e| {
+                AppError::service_unavailable(format!("Telegram unreachable: {}", e.without_url()))
+            })?
+            .json()
+            .await
+            .map_err(|e| {
+                AppError::service_unavailable(format!(
+                    "Invalid Telegram response: {}",
+                    e.without_url()
+                ))
+            })?;
+
+        match resp {
+            ApiResponse {
+                ok: true,
+                result: Some(result),
+                ..
+            } => Ok(result),
+            ApiResponse { description, .. } => Err(AppError::bad_request(format!(
+                "Telegram {method} failed: {}",
+                description.unwrap_or_else(|| "unknown error".into())
+            ))),
+        }
+    }
+
+    /// Validate a token and return the bot's username.
+    pub async fn get_me(&self, token: &str) -> Result<String, AppError> {
+        let me: BotUser = self.call(token, "getMe", &serde_json::json!({})).await?;
+        me.username
+            .ok_or_else(|| AppError::bad_request("Telegram bot has no username"))
+    }
+
+    pub async fn set_webhook(&self, token: &str, url: &str, secret: &str) -> Result<(), AppError> {
+        let _: bool = self
+            .call(
+                token,
+                "setWebhook",
+                &serde_json::json!({
+                    "url": url,
+                    "secret_token": secret,
+                    "allowed_updates": ["message"],
+                }),
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_webhook(&self, token: &str) -> Result<(), AppError> {
+        let _: bool = self
+            .call(token, "deleteWebhook", &serde_json::json!({}))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn send_message(&self, token: &str, chat_id: i64, text: &str) {
+        let text: String = text.chars().take(MAX_MESSAGE_CHARS).collect();
+        let result: Result<serde_json::Value, _> = self
+            .call(
+                token,
+                "sendMessage",
+                &serde_json::json!({ "chat_id": chat_id, "text": text }),
+            )
+            .await;
+        if let Err(e) = result {
+            tracing::error!(error = %e, chat_id, "Telegram sendMessage failed");
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use arc_swap::ArcSwap;
+
+use crate::config::{self, Settings, SharedSettings};
+use crate::models::responses::TenantStats;
+
+/// Tenant of influencers created before tenants existed, and of legacy imports.
+pub const DEFAULT_TENANT: &str = "yral";
+
+struct TenantEntry {
+    settings: SharedSettings,
+    requests: AtomicU64,
+    rate_limited: AtomicU64,
+}
+
+/// The apps this deployment serves (`TENANTS`), each with its own settings and
+/// request counters. The default tenant's settings are `AppState::settings`
+/// itself; every other tenant layers its `{TENANT}_` overrides on top of them.
+pub struct TenantRegistry {
+    default: Arc<str>,
+    issuers: HashMap<String, Arc<str>>,
+    tenants: HashMap<Arc<str>, TenantEntry>,
+}
+
+impl TenantRegistry {
+    pub fn new(settings: &SharedSettings) -> Self {
+        let base = settings.load();
+        let mut names: Vec<Arc<str>> = base.tenants_list().into_iter().map(Arc::from).collect();
+        if names.is_empty() {
+            names.push(DEFAULT_TENANT.into());
+        }
+        let default = names[0].clone();
+
+        let tenants = names
+            .iter()
+            .map(|name| {
+                let settings = if *name == default {
+                    settings.clone()
+                } else {
+                    Arc::new(ArcSwap::from_pointee(
+                        base.for_tenant(name, |key| env::var(key)),
+                    ))
+                };
+                let entry = TenantEntry {
+                    settings,
+                    requests: AtomicU64::new(0),
+                    rate_limited: AtomicU64::new(0),
+                };
+                (name.clone(), entry)
+            })
+            .collect();
+
+        let issuers = base
+            .tenant_issuers_list()
+            .into_iter()
+            .filter_map(|(issuer, tenant)| match names.iter().find(|n| ***n == tenant) {
+                Some(name) => Some((issuer, name.clone())),
+                None => {
+                    tracing::warn!(%issuer, %tenant, "TENANT_ISSUERS names a tenant missing from TENANTS, ignoring");
+                    None
+                }
+            })
+            .collect();
+
+        Self {
+            default,
+            issuers,
+            tenants,
+        }
+    }
+
+    pub fn default_tenant(&self) -> &Arc<str> {
+        &self.default
+    }
+
+    /// Whether more than one tenant is served, so responses differ by tenant.
+    pub fn is_multi_tenant(&self) -> bool {
+        self.tenants.len() > 1
+    }
+
+    /// `name` if this deployment serves it.
+    pub fn get(&self, name: &str) -> Option<Arc<str>> {
+        self.tenants
+            .get_key_value(name)
+            .map(|(name, _)| name.clone())
+    }
+
+    /// Tenant of tokens from `issuer`; the default when the issuer isn't mapped.
+    pub fn for_issuer(&self, issuer: &str) -> Arc<str> {
+        self.issuers.get(issuer).unwrap_or(&self.default).clone()
+    }
+
+    /// Settings as `tenant` sees them; the default tenant's for an unknown one.
+    pub fn settings(&self, tenant: &str) -> Arc<Settings> {
+        self.tenants
+            .get(tenant)
+            .or_else(|| self.tenants.get(&self.default))
+            .map(|entry| entry.settings.load_full())
+            .expect("default tenant is registered")
+    }
+
+    /// Count a request for `tenant`, and whether the rate limiter turned it away.
+    pub fn record_request(&self, tenant: &str, rate_limited: bool) {
+        if let Some(entry) = self.tenants.get(tenant) {
+            entry.requests.fetch_add(1, Ordering::Relaxed);
+            if rate_limited {
+                entry.rate_limited.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Re-read `.env` and the environment into every tenant's settings. Returns the
+    /// changed settings, prefixed with the tenant for all but the default.
+    pub fn reload(&self) -> Vec<String> {
+        let var = config::dotenv_lookup();
+        let default = &self.tenants[&self.default];
+        let mut changed: Vec<String> = config::reload(&default.settings, &var)
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let base = default.settings.load();
+        for (name, entry) in &self.tenants {
+            if *name == self.default {
+                continue;
+            }
+            let next = base.for_tenant(name, &var);
+            let mut current = Settings::clone(&entry.settings.load());
+            let tenant_changed = current.apply_reloadable(&next);
+            if !tenant_changed.is_empty() {
+                changed.extend(tenant_changed.iter().map(|field| format!("{name}.{field}")));
+                entry.settings.store(Arc::new(next));
+            }
+        }
+        changed
+    }
+
+    pub fn stats(&self) -> Vec<TenantStats> {
+        let mut stats: Vec<TenantStats> = self
+            .tenants
+            .iter()
+            .map(|(name, entry)| TenantStats {
+                tenant: name.to_string(),
+                requests: entry.requests.load(Ordering::Relaxed),
+                rate_limited: entry.rate_limited.load(Ordering::Relaxed),
+            })
+            .collect();
+        stats.sort_by(|a, b| a.tenant.cmp(&b.tenant));
+        stats
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+
+/// User messages of one conversation sent in quick succession.
+struct Burst {
+    /// Ticket of the burst's first message; identifies the burst
+    first: u64,
+    /// Ticket of the latest message; only its request replies
+    latest: u64,
+    message_ids: Vec<String>,
+    last_at: Instant,
+}
+
+/// A message's place in its conversation's burst, from [`TurnDebouncer::join`].
+pub struct BurstTicket {
+    conversation_id: String,
+    burst: u64,
+    seq: u64,
+}
+
+/// Groups user messages sent within the debounce window of each other into one
+/// turn, so three quick messages get one reply instead of three. Each message's
+/// request waits out the window; a request whose message was followed by another
+/// one in the burst drops its reply and leaves the turn to the later request.
+#[derive(Default)]
+pub struct TurnDebouncer {
+    bursts: DashMap<String, Burst>,
+    next_seq: AtomicU64,
+}
+
+impl TurnDebouncer {
+    /// Add a just-stored message to its conversation's burst, or start a new burst
+    /// when the previous message is older than `window`.
+    pub fn join(&self, conversation_id: &str, message_id: &str, window: Duration) -> BurstTicket {
+        let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+        let now = Instant::now();
+        let mut entry = self
+            .bursts
+            .entry(conversation_id.to_string())
+            .or_insert_with(|| Burst {
+                first: seq,
+                latest: seq,
+                message_ids: Vec::new(),
+                last_at: now,
+            });
+        if now.duration_since(entry.last_at) > window {
+            entry.first = seq;
+            entry.message_ids.clear();
+        }
+        entry.latest = seq;
+        entry.last_at = now;
+        entry.message_ids.push(message_id.to_string());
+        BurstTicket {
+            conversation_id: conversation_id.to_string(),
+            burst: entry.first,
+            seq,
+        }
+    }
+
+    /// Whether a later message joined the ticket's burst, so its request replies instead.
+    pub fn is_superseded(&self, ticket: &BurstTicket) -> bool {
+        self.bursts
+            .get(&ticket.conversation_id)
+            .is_some_and(|b| b.first == ticket.burst && b.latest != ticket.seq)
+    }
+
+    /// IDs of the messages in the ticket's burst, oldest first.
+    pub fn message_ids(&self, ticket: &BurstTicket) -> Vec<String> {
+        self.bursts
+            .get(&ticket.conversation_id)
+            .filter(|b| b.first == ticket.burst)
+            .map(|b| b.message_ids.clone())
+            .unwrap_or_default()
+    }
+
+    /// The burst was answered; the next message starts a new one.
+    pub fn finish(&self, ticket: &BurstTicket) {
+        self.bursts
+            .remove_if(&ticket.conversation_id, |_, b| b.latest == ticket.seq);
+    }
+
+    /// Finish the ticket's burst when the returned guard is dropped, so a request
+    /// that bails out early doesn't leave its burst behind.
+    pub fn finish_on_drop<'a>(&'a self, ticket: &'a BurstTicket) -> FinishOnDrop<'a> {
+        FinishOnDrop {
+            debouncer: self,
+            ticket,
+        }
+    }
+}
+
+/// Calls [`TurnDebouncer::finish`] on drop; from [`TurnDebouncer::finish_on_drop`].
+pub struct FinishOnDrop<'a> {
+    debouncer: &'a TurnDebouncer,
+    ticket: &'a BurstTicket,
+}
+
+impl Drop for FinishOnDrop<'_> {
+    fn drop(&mut self) {
+        self.debouncer.finish(self.ticket);
+    }
+}
@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use strum::{AsRefStr, Display, EnumString};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::error::AppError;
+
+/// Which malware scanner checks media uploads before they are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Display, EnumString, AsRefStr)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum UploadScanMode {
+    /// Uploads are stored unscanned.
+    Off,
+    /// A ClamAV daemon, spoken to over its INSTREAM protocol.
+    Clamd,
+    /// An HTTP API that takes the raw bytes and answers `{"infected": bool, "signature": ...}`.
+    Http,
+}
+
+/// clamd rejects streams sent in larger chunks.
+const CLAMD_CHUNK_SIZE: usize = 64 * 1024;
+const SCAN_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub enum ScanResult {
+    Clean,
+    Infected(String),
+}
+
+#[derive(Deserialize)]
+struct HttpScanResponse {
+    infected: bool,
+    signature: Option<String>,
+}
+
+/// Malware scanning for uploads via a ClamAV sidecar or an external API.
+#[derive(Clone)]
+pub struct UploadScanner {
+    http: reqwest::Client,
+    mode: UploadScanMode,
+    clamd_address: String,
+    scan_url: Option<String>,
+    api_key: Option<String>,
+}
+
+impl UploadScanner {
+    pub fn new(
+        http: reqwest::Client,
+        mode: UploadScanMode,
+        clamd_address: &str,
+        scan_url: Option<String>,
+        api_key: Option<String>,
+    ) -> Self {
+        Self {
+            http,
+            mode,
+            clamd_address: clamd_address.to_string(),
+            scan_url,
+            api_key,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.mode != UploadScanMode::Off
+    }
+
+    /// Name recorded alongside each verdict.
+    pub fn scanner_name(&self) -> &str {
+        self.mode.as_ref()
+    }
+
+    pub async fn scan(&self, bytes: &[u8]) -> Result<ScanResult, AppError> {
+        let result = match self.mode {
+            UploadScanMode::Off => return Ok(ScanResult::Clean),
+            UploadScanMode::Clamd => tokio::time::timeout(SCAN_TIMEOUT, self.scan_clamd(bytes))
+                .await
+                .map_err(|_| anyhow::anyhow!("clamd scan timed out"))
+                .and_then(|r| r),
+            UploadScanMode::Http => self.scan_http(bytes).await,
+        };
+        result.map_err(|e| {
+            tracing::error!(error = %e, scanner = self.scanner_name(), "Upload scan failed");
+            AppError::service_unavailable("Upload scanning is unavailable. Please retry shortly.")
+        })
+    }
+
+    async fn scan_clamd(&self, bytes: &[u8]) -> anyhow::Result<ScanResult> {
+        let mut stream = TcpStream::connect(&self.clamd_address).await?;
+        stream.write_all(b"zINSTREAM\0").await?;
+        for chunk in bytes.chunks(CLAMD_CHUNK_SIZE) {
+            stream
+                .write_all(&(chunk.len() as u32).to_be_bytes())
+                .await?;
+            stream.write_all(chunk).await?;
+        }
+        stream.write_all(&0u32.to_be_bytes()).await?;
+
+        let mut reply = Vec::new();
+        stream.read_to_end(&mut reply).await?;
+        let reply = String::from_utf8_lossy(&reply);
+        let reply = reply.trim_end_matches(['\0', '\n']);
+
+        // "stream: OK" or "stream: <signature> FOUND"
+        match reply.strip_prefix("stream: ") {
+            Some("OK") => Ok(ScanResult::Clean),
+            Some(found) if found.ends_with(" FOUND") => Ok(ScanResult::Infected(
+                found.trim_end_matches(" FOUND").to_string(),
+            )),
+            _ => anyhow::bail!("unexpected clamd reply: {reply}"),
+        }
+    }
+
+    async fn scan_http(&self, bytes: &[u8]) -> anyhow::Result<ScanResult> {
+        let url = self
+            .scan_url
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("UPLOAD_SCAN_URL is not set"))?;
+        let mut req = self
+            .http
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .body(bytes.to_vec())
+            .timeout(SCAN_TIMEOUT);
+        if let Some(key) = &self.api_key {
+            req = req.header("Authorization", format!("Bearer {key}"));
+        }
+
+        let resp: HttpScanResponse = req.send().await?.error_for_status()?.json().await?;
+        Ok(if resp.infected {
+            ScanResult::Infected(resp.signature.unwrap_or_else(|| "unknown".to_string()))
+        } else {
+            ScanResult::Clean
+        })
+    }
+}
@@ -0,0 +1,278 @@
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use sha2::Sha256;
+
+use crate::AppState;
+use crate::models::entities::{Webhook, WebhookDeliveryStatus, WebhookEvent};
+
+const MAX_ATTEMPTS: i32 = 5;
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Client for owner-supplied webhook URLs. It only connects to public addresses,
+/// checked after DNS resolution so a hostname can't point it at internal
+/// services, and never follows redirects, which could lead there too.
+static WEBHOOK_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(|| {
+    reqwest::Client::builder()
+        .dns_resolver(Arc::new(PublicOnlyResolver))
+        .redirect(reqwest::redirect::Policy::none())
+        .no_proxy()
+        .build()
+        .expect("webhook HTTP client")
+});
+
+/// Resolves like the system resolver, but drops non-public addresses.
+struct PublicOnlyResolver;
+
+impl Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Whether `ip` is reachable on the public internet: not loopback, private,
+/// link-local (cloud metadata lives there), shared, unspecified or multicast.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || v4.is_documentation()
+                || a == 0
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                // Unique local fc00::/7 and link-local fe80::/10
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// Check a webhook URL points at a public host: https, and every address its
+/// host resolves to is public. Delivery re-checks at connect time, since DNS
+/// can change after registration.
+pub async fn check_target(url: &str) -> Result<(), String> {
+    let url = Url::parse(url).map_err(|_| "Webhook url is not a valid URL".to_string())?;
+    if url.scheme() != "https" {
+        return Err("Webhook url must use https".into());
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| "Webhook url has no host".to_string())?;
+    let port = url.port_or_known_default().unwrap_or(443);
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.trim_matches(['[', ']']), port))
+        .await
+        .map_err(|_| format!("Webhook host {host} could not be resolved"))?
+        .collect();
+    if addrs.is_empty() || !addrs.iter().all(|addr| is_public(addr.ip())) {
+        return Err("Webhook url must point to a public address".into());
+    }
+    Ok(())
+}
+
+/// `X-Yral-Signature` value: hex HMAC-SHA256 of `"{timestamp}.{body}"` keyed by the
+/// webhook secret, so receivers can verify origin and reject replays.
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Fan an event out to every webhook of `influencer_id` subscribed to it.
+/// Deliveries run in the background and are recorded in the delivery log.
+pub fn dispatch(
+    state: &Arc<AppState>,
+    influencer_id: &str,
+    event: WebhookEvent,
+    data: serde_json::Value,
+) {
+    let state = state.clone();
+    let influencer_id = influencer_id.to_string();
+
+    tokio::spawn(async move {
+        let webhooks = match state
+            .db
+            .webhook_repo()
+            .list_subscribed(&influencer_id, &event)
+            .await
+        {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to load webhooks");
+                return;
+            }
+        };
+
+        for webhook in webhooks {
+            let payload = serde_json::json!({
+                "event": event.as_ref(),
+                "influencer_id": influencer_id,
+                "created_at": chrono::Utc::now().to_rfc3339(),
+                "data": data,
+            });
+            let state = state.clone();
+            let event = event.clone();
+            tokio::spawn(async move { deliver(&state, &webhook, &event, payload).await });
+        }
+    });
+}
+
+/// POST one payload, retrying network errors, 429s and 5xx with exponential backoff.
+async fn deliver(
+    state: &AppState,
+    webhook: &Webhook,
+    event: &WebhookEvent,
+    payload: serde_json::Value,
+) {
+    let repo = state.db.webhook_repo();
+    let delivery_id = match repo.create_delivery(&webhook.id, event, &payload).await {
+        Ok(id) => id,
+        Err(e) => {
+            tracing::error!(error = %e, webhook_id = %webhook.id, "Failed to log webhook delivery");
+            return;
+        }
+    };
+    let body = payload.to_string();
+
+    // IP literals skip the resolver, so they are checked here
+    let literal = Url::parse(&webhook.url).ok().and_then(|url| {
+        url.host_str()?
+            .trim_matches(['[', ']'])
+            .parse::<IpAddr>()
+            .ok()
+    });
+    if literal.is_some_and(|ip| !is_public(ip)) {
+        tracing::warn!(webhook_id = %webhook.id, "Webhook url points to a non-public address");
+        if let Err(e) = repo
+            .update_delivery(
+                &delivery_id,
+                &WebhookDeliveryStatus::Failed,
+                0,
+                None,
+                Some("Webhook url must point to a public address"),
+            )
+            .await
+        {
+            tracing::error!(error = %e, "Failed to update webhook delivery");
+        }
+        return;
+    }
+
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let timestamp = chrono::Utc::now().timestamp();
+        let result = WEBHOOK_CLIENT
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .header("X-Yral-Event", event.as_ref())
+            .header("X-Yral-Delivery", &delivery_id)
+            .header("X-Yral-Timestamp", timestamp.to_string())
+            .header("X-Yral-Signature", sign(&webhook.secret, timestamp, &body))
+            .body(body.clone())
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .send()
+            .await;
+
+        let (status_code, error, retryable) = match result {
+            Ok(resp) if resp.status().is_success() => (Some(resp.status().as_u16()), None, false),
+            Ok(resp) => {
+                let status = resp.status();
+                let retryable = status.is_server_error() || status.as_u16() == 429;
+                (
+                    Some(status.as_u16()),
+                    Some(format!("HTTP {status}")),
+                    retryable,
+                )
+            }
+            Err(e) => (None, Some(e.to_string()), true),
+        };
+
+        let status = match (&error, retryable && attempt < MAX_ATTEMPTS) {
+            (None, _) => WebhookDeliveryStatus::Succeeded,
+            (Some(_), true) => WebhookDeliveryStatus::Pending,
+            (Some(_), false) => WebhookDeliveryStatus::Failed,
+        };
+        if let Err(e) = repo
+            .update_delivery(
+                &delivery_id,
+                &status,
+                attempt,
+                status_code.map(i32::from),
+                error.as_deref(),
+            )
+            .await
+        {
+            tracing::error!(error = %e, "Failed to update webhook delivery");
+        }
+
+        if status != WebhookDeliveryStatus::Pending {
+            if status == WebhookDeliveryStatus::Failed {
+                tracing::warn!(
+                    webhook_id = %webhook.id,
+                    delivery_id = %delivery_id,
+                    attempts = attempt,
+                    error = ?error,
+                    "Webhook delivery failed"
+                );
+            }
+            return;
+        }
+        tokio::time::sleep(Duration::from_secs(2u64.pow(attempt as u32))).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in ["8.8.8.8", "1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+    }
+}
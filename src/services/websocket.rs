@@ -1,40 +1,120 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
-use tokio::sync::mpsc;
+use tokio::sync::Notify;
+
+use crate::models::responses::{WsConnectionLag, WsStats};
 
 static CONN_COUNTER: AtomicU64 = AtomicU64::new(0);
 
-pub type WsSender = mpsc::UnboundedSender<String>;
+#[derive(Default)]
+struct QueueState {
+    events: VecDeque<String>,
+    /// When the queue last filled up; cleared once the client drains it
+    full_since: Option<Instant>,
+}
+
+/// Outgoing events of one connection. Bounded: when the client falls behind the
+/// oldest events are dropped so a slow socket can't hold memory or stall broadcasts.
+#[derive(Default)]
+struct ConnQueue {
+    state: Mutex<QueueState>,
+    notify: Notify,
+    closed: AtomicBool,
+    /// Closed by the manager because the client stayed behind too long
+    evicted: AtomicBool,
+    dropped: AtomicU64,
+}
+
+impl ConnQueue {
+    fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.notify.notify_one();
+    }
+}
+
+/// Receiving end of a connection's queue, drained by the socket task.
+pub struct WsReceiver {
+    queue: Arc<ConnQueue>,
+}
+
+impl WsReceiver {
+    /// Next event, or `None` once the connection was closed.
+    pub async fn recv(&mut self) -> Option<String> {
+        loop {
+            // An evicted client is cut off right away rather than fed its backlog
+            if self.was_evicted() {
+                return None;
+            }
+            {
+                let mut state = self.queue.state.lock().unwrap();
+                if let Some(event) = state.events.pop_front() {
+                    if state.events.is_empty() {
+                        state.full_since = None;
+                    }
+                    return Some(event);
+                }
+            }
+            if self.queue.closed.load(Ordering::Acquire) {
+                return None;
+            }
+            self.queue.notify.notified().await;
+        }
+    }
+
+    /// Whether the manager cut the connection for being too slow.
+    pub fn was_evicted(&self) -> bool {
+        self.queue.evicted.load(Ordering::Acquire)
+    }
+}
+
+impl Drop for WsReceiver {
+    fn drop(&mut self) {
+        self.queue.closed.store(true, Ordering::Release);
+    }
+}
 
 struct Connection {
     id: u64,
-    sender: WsSender,
+    queue: Arc<ConnQueue>,
 }
 
 pub struct WsManager {
     connections: DashMap<String, Vec<Connection>>,
+    queue_capacity: usize,
+    /// A connection whose queue stays full this long is disconnected
+    slow_client_timeout: Duration,
+    slow_disconnects: AtomicU64,
 }
 
 impl WsManager {
-    pub fn new() -> Self {
+    pub fn new(queue_capacity: usize, slow_client_timeout: Duration) -> Self {
         Self {
             connections: DashMap::new(),
+            queue_capacity: queue_capacity.max(1),
+            slow_client_timeout,
+            slow_disconnects: AtomicU64::new(0),
         }
     }
 
     /// Register a new WebSocket connection for a user.
     /// Returns (connection_id, receiver) — the receiver streams JSON messages to the WS client.
-    pub fn connect(&self, user_id: &str) -> (u64, mpsc::UnboundedReceiver<String>) {
+    pub fn connect(&self, user_id: &str) -> (u64, WsReceiver) {
         let id = CONN_COUNTER.fetch_add(1, Ordering::Relaxed);
-        let (tx, rx) = mpsc::unbounded_channel();
+        let queue = Arc::new(ConnQueue::default());
 
         self.connections
             .entry(user_id.to_string())
             .or_default()
-            .push(Connection { id, sender: tx });
+            .push(Connection {
+                id,
+                queue: queue.clone(),
+            });
 
-        (id, rx)
+        (id, WsReceiver { queue })
     }
 
     /// Remove a connection by user_id and connection id.
@@ -48,10 +128,16 @@ impl WsManager {
         }
     }
 
-    /// Send a JSON message to all connections for a user.
+    /// Whether the user has at least one open connection.
+    pub fn is_connected(&self, user_id: &str) -> bool {
+        self.connections.contains_key(user_id)
+    }
+
+    /// Queue a JSON message on all connections for a user. Never waits on the
+    /// client: a full queue loses its oldest event instead.
     fn send_to_user(&self, user_id: &str, message: &str) {
         if let Some(mut conns) = self.connections.get_mut(user_id) {
-            conns.retain(|c| c.sender.send(message.to_string()).is_ok());
+            conns.retain(|c| self.enqueue(user_id, c, message));
             if conns.is_empty() {
                 drop(conns);
                 self.connections.remove(user_id);
@@ -59,6 +145,66 @@ impl WsManager {
         }
     }
 
+    /// Returns whether the connection is still open.
+    fn enqueue(&self, user_id: &str, conn: &Connection, message: &str) -> bool {
+        let queue = &conn.queue;
+        if queue.closed.load(Ordering::Acquire) {
+            return false;
+        }
+
+        let mut state = queue.state.lock().unwrap();
+        if state.events.len() >= self.queue_capacity {
+            state.events.pop_front();
+            queue.dropped.fetch_add(1, Ordering::Relaxed);
+            let full_since = *state.full_since.get_or_insert_with(Instant::now);
+            if full_since.elapsed() >= self.slow_client_timeout {
+                drop(state);
+                tracing::warn!(
+                    user_id,
+                    conn_id = conn.id,
+                    dropped = queue.dropped.load(Ordering::Relaxed),
+                    "Disconnecting slow WebSocket client"
+                );
+                queue.evicted.store(true, Ordering::Release);
+                queue.close();
+                self.slow_disconnects.fetch_add(1, Ordering::Relaxed);
+                return false;
+            }
+        }
+        state.events.push_back(message.to_string());
+        drop(state);
+        queue.notify.notify_one();
+        true
+    }
+
+    pub fn stats(&self) -> WsStats {
+        let mut stats = WsStats {
+            connections: 0,
+            queued: 0,
+            dropped: 0,
+            slow_disconnects: self.slow_disconnects.load(Ordering::Relaxed),
+            lagging: Vec::new(),
+        };
+        for conns in self.connections.iter() {
+            for conn in conns.iter() {
+                let state = conn.queue.state.lock().unwrap();
+                let dropped = conn.queue.dropped.load(Ordering::Relaxed);
+                stats.connections += 1;
+                stats.queued += state.events.len();
+                stats.dropped += dropped;
+                if let Some(full_since) = state.full_since {
+                    stats.lagging.push(WsConnectionLag {
+                        conn_id: conn.id,
+                        queued: state.events.len(),
+                        dropped,
+                        lagging_seconds: full_since.elapsed().as_secs(),
+                    });
+                }
+            }
+        }
+        stats
+    }
+
     pub fn broadcast_new_message(
         &self,
         user_id: &str,
@@ -91,6 +237,43 @@ impl WsManager {
         self.send_to_user(user_id, &event.to_string());
     }
 
+    /// Tells the client a conversation has new activity so it can move it to
+    /// the top of the inbox.
+    pub fn broadcast_conversation_updated(
+        &self,
+        user_id: &str,
+        conversation_id: &str,
+        updated_at: &str,
+        last_message: &serde_json::Value,
+    ) {
+        let event = serde_json::json!({
+            "event": "conversation_updated",
+            "data": {
+                "conversation_id": conversation_id,
+                "updated_at": updated_at,
+                "last_message": last_message,
+            }
+        });
+        self.send_to_user(user_id, &event.to_string());
+    }
+
+    /// Tells the client replies in a conversation are paused, and for how long.
+    pub fn broadcast_conversation_throttled(
+        &self,
+        user_id: &str,
+        conversation_id: &str,
+        retry_after: u64,
+    ) {
+        let event = serde_json::json!({
+            "event": "conversation_throttled",
+            "data": {
+                "conversation_id": conversation_id,
+                "retry_after": retry_after,
+            }
+        });
+        self.send_to_user(user_id, &event.to_string());
+    }
+
     pub fn broadcast_typing_status(
         &self,
         user_id: &str,
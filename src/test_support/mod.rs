@@ -0,0 +1,142 @@
+//! Test doubles: a fake AI provider, and an in-process server with fakes for all
+//! the external clients (SQLite only, so it needs `--features staging`).
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::config::Settings;
+use crate::error::AppError;
+use crate::models::entities::Message;
+use crate::services::ai::{AiApi, EmbeddingTask, GenerationOptions};
+
+#[cfg(feature = "staging")]
+pub mod server;
+
+/// Settings for tests: an in-memory database, placeholder credentials and
+/// limits high enough not to get in the way, with `overrides` on top as
+/// environment variable name and value pairs.
+pub fn settings(overrides: &[(&str, &str)]) -> Settings {
+    let mut vars: HashMap<String, String> = [
+        ("DATABASE_PATH", ":memory:"),
+        ("JWT_SECRET_KEY", "test"),
+        ("GEMINI_API_KEY", "test"),
+        ("AWS_ACCESS_KEY_ID", "test"),
+        ("AWS_SECRET_ACCESS_KEY", "test"),
+        ("AWS_S3_BUCKET", "test"),
+        ("AWS_REGION", "us-east-1"),
+        ("S3_ENDPOINT_URL", "http://127.0.0.1:9"),
+        ("S3_PUBLIC_URL_BASE", "https://media.test"),
+        ("CALLER_TYPE_FORCE_USER", "true"),
+        ("RATE_LIMIT_PER_MINUTE", "10000"),
+        ("RATE_LIMIT_PER_HOUR", "100000"),
+        ("MESSAGE_DEBOUNCE_SECONDS", "0"),
+    ]
+    .into_iter()
+    .chain(overrides.iter().copied())
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+    vars.entry("ADMIN_KEY_TO_DELETE_INFLUENCER".into())
+        .or_insert_with(|| "test-admin-key".into());
+    Settings::from_lookup(|key| vars.get(key).cloned().ok_or(std::env::VarError::NotPresent))
+}
+
+/// Replies with a fixed text, or fails with the configured error. Records the
+/// user messages it was sent.
+pub struct FakeAi {
+    pub reply: Mutex<Result<String, String>>,
+    pub prompts: Mutex<Vec<String>>,
+}
+
+impl Default for FakeAi {
+    fn default() -> Self {
+        Self::replying(Ok("Hello from the fake model"))
+    }
+}
+
+impl FakeAi {
+    pub fn replying(reply: Result<&str, &str>) -> Self {
+        Self {
+            reply: Mutex::new(reply.map(str::to_string).map_err(str::to_string)),
+            prompts: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl AiApi for FakeAi {
+    fn is_configured(&self) -> bool {
+        true
+    }
+
+    fn provider(&self) -> &'static str {
+        "fake"
+    }
+
+    fn model(&self) -> String {
+        "fake-model".into()
+    }
+
+    fn max_tokens(&self) -> u32 {
+        1024
+    }
+
+    async fn generate_response_with(
+        &self,
+        user_message: &str,
+        _system_instructions: &str,
+        _conversation_history: &[Message],
+        _media_urls: Option<&[String]>,
+        _options: &GenerationOptions,
+    ) -> Result<(String, i32), AppError> {
+        self.prompts.lock().unwrap().push(user_message.to_string());
+        match &*self.reply.lock().unwrap() {
+            Ok(text) => Ok((text.clone(), 7)),
+            Err(e) => Err(AppError::service_unavailable(e.clone())),
+        }
+    }
+
+    async fn transcribe_audio(&self, _audio_url: &str) -> Result<String, AppError> {
+        Ok("transcribed audio".into())
+    }
+
+    async fn extract_text(&self, data: &[u8], _mime_type: &str) -> Result<String, AppError> {
+        Ok(String::from_utf8_lossy(data).into_owned())
+    }
+
+    async fn embed(
+        &self,
+        texts: &[String],
+        _task: EmbeddingTask,
+    ) -> Result<Vec<Vec<f32>>, AppError> {
+        // Letter counts: texts sharing words come out similar
+        Ok(texts
+            .iter()
+            .map(|text| {
+                let mut v = vec![0.0; 26];
+                for c in text
+                    .to_ascii_lowercase()
+                    .bytes()
+                    .filter(u8::is_ascii_lowercase)
+                {
+                    v[(c - b'a') as usize] += 1.0;
+                }
+                v
+            })
+            .collect())
+    }
+
+    async fn extract_memories(
+        &self,
+        _user_message: &str,
+        _assistant_response: &str,
+        existing_memories: &HashMap<String, String>,
+    ) -> Result<HashMap<String, String>, AppError> {
+        Ok(existing_memories.clone())
+    }
+
+    async fn translate(&self, text: &str, target_language: &str) -> Result<String, AppError> {
+        Ok(format!("[{target_language}] {text}"))
+    }
+}
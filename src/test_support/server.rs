@@ -0,0 +1,454 @@
+//! In-process server for tests: the full router on a local port, backed by an
+//! in-memory SQLite database and fakes for the AI, storage, image generation and
+//! push clients.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+use async_trait::async_trait;
+
+use super::FakeAi;
+use crate::config::SharedSettings;
+use crate::db::{self, Database};
+use crate::error::AppError;
+use crate::models::responses::{InFlightPrediction, PredictionBudget};
+use crate::services::notification::PushApi;
+use crate::services::replicate::ImageGen;
+use crate::services::storage::{Storage, StoredObject};
+use crate::{AppState, ExternalClients, build_router, build_state};
+
+/// Issuer on the tokens from [`TestServer::token`]; the first of the defaults.
+const TEST_ISSUER: &str = "https://auth.yral.com";
+
+/// A running server. It stops when the test's runtime shuts down.
+pub struct TestServer {
+    pub addr: SocketAddr,
+    pub state: Arc<AppState>,
+    pub client: reqwest::Client,
+    pub ai: Arc<FakeAi>,
+}
+
+impl TestServer {
+    pub async fn start() -> Self {
+        Self::start_with(&[]).await
+    }
+
+    /// Start with `overrides` on top of the [test settings](super::settings).
+    pub async fn start_with(overrides: &[(&str, &str)]) -> Self {
+        let settings = super::settings(overrides);
+
+        let database = Database::connect(&settings)
+            .await
+            .expect("Failed to open the test database");
+        db::run_migrations(&database.pool, db::migrations_dir())
+            .await
+            .expect("Failed to migrate the test database");
+
+        let ai = Arc::new(FakeAi::default());
+        let shared_settings: SharedSettings = Arc::new(ArcSwap::from_pointee(settings.clone()));
+        let state = build_state(
+            &settings,
+            shared_settings,
+            database,
+            reqwest::Client::new(),
+            ExternalClients {
+                storage: Arc::new(FakeStorage),
+                gemini: ai.clone(),
+                openrouter: ai.clone(),
+                replicate: Arc::new(FakeImageGen),
+                push_notifications: Arc::new(FakePush),
+            },
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind a local port");
+        let addr = listener.local_addr().expect("Listener has no address");
+        let app = build_router(state.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        Self {
+            addr,
+            state,
+            client: reqwest::Client::new(),
+            ai,
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{path}", self.addr)
+    }
+
+    /// A bearer token for `user_id`. Signatures aren't checked, so it only has to
+    /// have the right shape and claims.
+    pub fn token(user_id: &str) -> String {
+        let exp = chrono::Utc::now().timestamp() + 3600;
+        let claims = serde_json::json!({ "sub": user_id, "iss": TEST_ISSUER, "exp": exp });
+        Self::token_with(&claims)
+    }
+
+    pub fn token_with(claims: &serde_json::Value) -> String {
+        let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"RS256","typ":"JWT"}"#);
+        let payload = URL_SAFE_NO_PAD.encode(claims.to_string());
+        format!("{header}.{payload}.{}", URL_SAFE_NO_PAD.encode("unsigned"))
+    }
+
+    /// Store an active influencer owned by nobody.
+    pub async fn seed_influencer(&self, id: &str) {
+        sqlx::query(
+            "INSERT INTO ai_influencers (id, name, display_name, system_instructions)
+             VALUES (?, ?, ?, 'You are a friendly test bot.')",
+        )
+        .bind(id)
+        .bind(id)
+        .bind(format!("Bot {id}"))
+        .execute(&self.state.db.pool)
+        .await
+        .expect("Failed to seed influencer");
+    }
+
+    pub fn get(&self, path: &str, user_id: &str) -> reqwest::RequestBuilder {
+        self.client
+            .get(self.url(path))
+            .bearer_auth(Self::token(user_id))
+    }
+
+    pub fn post(&self, path: &str, user_id: &str) -> reqwest::RequestBuilder {
+        self.client
+            .post(self.url(path))
+            .bearer_auth(Self::token(user_id))
+    }
+}
+
+/// Keeps nothing; keys come back as their own URLs.
+pub struct FakeStorage;
+
+#[async_trait]
+impl Storage for FakeStorage {
+    async fn upload(
+        &self,
+        user_id: &str,
+        file_bytes: Vec<u8>,
+        file_extension: &str,
+        _content_type: &str,
+    ) -> Result<(String, u64), AppError> {
+        let key = format!("{user_id}/{}.{file_extension}", uuid::Uuid::new_v4());
+        Ok((key, file_bytes.len() as u64))
+    }
+
+    async fn generate_presigned_url(&self, key: &str) -> String {
+        format!("https://media.test/{key}")
+    }
+
+    async fn generate_presigned_urls_batch(&self, keys: &[String]) -> HashMap<String, String> {
+        keys.iter()
+            .map(|k| (k.clone(), format!("https://media.test/{k}")))
+            .collect()
+    }
+
+    async fn copy_to_public(&self, key: &str, prefix: &str) -> Result<String, AppError> {
+        Ok(format!("https://media.test/{prefix}/{key}"))
+    }
+
+    async fn delete_prefix(&self, _prefix: &str) -> Result<usize, AppError> {
+        Ok(0)
+    }
+
+    async fn import_object(&self, url_or_key: &str, user_id: &str) -> Result<String, AppError> {
+        Ok(format!(
+            "{user_id}/{}",
+            self.extract_key_from_url(url_or_key)
+        ))
+    }
+
+    async fn get_object(&self, _key: &str, _range: Option<&str>) -> Result<StoredObject, AppError> {
+        Err(AppError::not_found("Object not found"))
+    }
+
+    fn extract_key_from_url(&self, url_or_key: &str) -> String {
+        url_or_key
+            .trim_start_matches("https://media.test/")
+            .to_string()
+    }
+
+    fn validate_image(&self, _filename: &str, _size: u64) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    fn validate_audio(&self, _filename: &str, _size: u64) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn download_file(&self, _url: &str) -> Result<(Vec<u8>, String), AppError> {
+        Err(AppError::not_found("Nothing to download"))
+    }
+}
+
+/// Not configured, like a deployment without a Replicate token.
+pub struct FakeImageGen;
+
+#[async_trait]
+impl ImageGen for FakeImageGen {
+    fn is_configured(&self) -> bool {
+        false
+    }
+
+    async fn generate_image(
+        &self,
+        _prompt: &str,
+        _aspect_ratio: &str,
+    ) -> Result<Option<String>, AppError> {
+        Ok(None)
+    }
+
+    async fn generate_image_via_image(
+        &self,
+        _prompt: &str,
+        _input_image: &str,
+        _aspect_ratio: &str,
+    ) -> Result<Option<String>, AppError> {
+        Ok(None)
+    }
+
+    async fn generate_video(
+        &self,
+        _prompt: &str,
+        _image: Option<&str>,
+    ) -> Result<Option<String>, AppError> {
+        Ok(None)
+    }
+
+    fn in_flight(&self) -> Vec<InFlightPrediction> {
+        Vec::new()
+    }
+
+    fn budget(&self) -> Vec<PredictionBudget> {
+        Vec::new()
+    }
+}
+
+/// Accepts every push and delivers nothing.
+pub struct FakePush;
+
+#[async_trait]
+impl PushApi for FakePush {
+    async fn send_push_notification(
+        &self,
+        _user_id: &str,
+        _title: &str,
+        _body: &str,
+        _data: Option<&serde_json::Value>,
+    ) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn health_reports_the_database() {
+        let server = TestServer::start().await;
+        let resp = server
+            .client
+            .get(server.url("/health"))
+            .send()
+            .await
+            .unwrap();
+        assert!(resp.status().is_success());
+        let body: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(body["services"]["database"]["status"], "up");
+    }
+
+    #[tokio::test]
+    async fn unauthenticated_requests_are_refused() {
+        let server = TestServer::start().await;
+        let resp = server
+            .client
+            .get(server.url("/api/v1/chat/conversations"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 401);
+    }
+
+    #[tokio::test]
+    async fn message_gets_the_model_reply() {
+        let server = TestServer::start().await;
+        server.seed_influencer("bot-1").await;
+
+        let conv: serde_json::Value = server
+            .post("/api/v1/chat/conversations", "user-1")
+            .json(&serde_json::json!({ "influencer_id": "bot-1" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let conv_id = conv["id"].as_str().expect("conversation id");
+
+        let resp = server
+            .post(
+                &format!("/api/v1/chat/conversations/{conv_id}/messages"),
+                "user-1",
+            )
+            .json(&serde_json::json!({ "message_type": "text", "content": "hi there" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        let body: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(body["user_message"]["content"], "hi there");
+        assert_eq!(
+            body["assistant_message"]["content"],
+            "Hello from the fake model"
+        );
+        assert_eq!(*server.ai.prompts.lock().unwrap(), ["hi there"]);
+
+        let list: serde_json::Value = server
+            .get("/api/v1/chat/conversations", "user-1")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(list["conversations"][0]["id"], conv_id);
+    }
+
+    #[tokio::test]
+    async fn failed_generation_stores_a_retryable_reply() {
+        let server = TestServer::start().await;
+        server.seed_influencer("bot-1").await;
+        *server.ai.reply.lock().unwrap() = Err("provider down".into());
+
+        let conv: serde_json::Value = server
+            .post("/api/v1/chat/conversations", "user-1")
+            .json(&serde_json::json!({ "influencer_id": "bot-1" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let conv_id = conv["id"].as_str().unwrap();
+
+        let resp = server
+            .post(
+                &format!("/api/v1/chat/conversations/{conv_id}/messages"),
+                "user-1",
+            )
+            .json(&serde_json::json!({ "message_type": "text", "content": "hello?" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 503);
+        let body: serde_json::Value = resp.json().await.unwrap();
+        assert_eq!(body["assistant_message"]["status"], "failed");
+
+        let failed = server
+            .state
+            .db
+            .msg_repo()
+            .list_failed_replies(24, 5, 10)
+            .await
+            .unwrap();
+        assert_eq!(failed.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn share_of_one_message_publishes_only_that_message() {
+        let server = TestServer::start().await;
+        server.seed_influencer("bot-1").await;
+
+        let conv: serde_json::Value = server
+            .post("/api/v1/chat/conversations", "user-1")
+            .json(&serde_json::json!({ "influencer_id": "bot-1" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let conv_id = conv["id"].as_str().unwrap();
+        // The reply is written in the same second as the message
+        let turn: serde_json::Value = server
+            .post(
+                &format!("/api/v1/chat/conversations/{conv_id}/messages"),
+                "user-1",
+            )
+            .json(&serde_json::json!({ "message_type": "text", "content": "hi there" }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        for (message, content) in [
+            (&turn["user_message"], "hi there"),
+            (&turn["assistant_message"], "Hello from the fake model"),
+        ] {
+            let id = message["id"].as_str().unwrap();
+            let share: serde_json::Value = server
+                .post(
+                    &format!("/api/v1/chat/conversations/{conv_id}/share"),
+                    "user-1",
+                )
+                .json(&serde_json::json!({ "from_message_id": id, "to_message_id": id }))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            assert_eq!(share["message_count"], 1);
+
+            let snapshot: serde_json::Value = server
+                .client
+                .get(server.url(share["share_path"].as_str().unwrap()))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            assert_eq!(snapshot["messages"][0]["content"], content);
+        }
+    }
+
+    #[tokio::test]
+    async fn revoked_token_is_refused() {
+        let server = TestServer::start().await;
+        let exp = chrono::Utc::now().timestamp() + 3600;
+        let token = TestServer::token_with(&serde_json::json!({
+            "sub": "user-1", "iss": TEST_ISSUER, "exp": exp, "jti": "jti-1",
+        }));
+
+        let resp = server
+            .client
+            .post(server.url("/api/v1/admin/revoked-tokens"))
+            .header("X-Admin-Key", "test-admin-key")
+            .json(&serde_json::json!({ "jti": "jti-1", "exp": exp }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 201);
+
+        let resp = server
+            .client
+            .get(server.url("/api/v1/chat/conversations"))
+            .bearer_auth(token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 401);
+    }
+}